@@ -241,6 +241,21 @@ def upscale_image(
     }
 
 
+def apply_seed():
+    """Seed RNGs and prefer deterministic kernels when SUPERBOOK_SEED is set."""
+    seed = os.environ.get("SUPERBOOK_SEED")
+    if seed is None:
+        return
+    seed = int(seed)
+
+    import random
+    random.seed(seed)
+    np.random.seed(seed)
+    torch.manual_seed(seed)
+    torch.backends.cudnn.benchmark = False
+    torch.use_deterministic_algorithms(True, warn_only=True)
+
+
 def main():
     parser = argparse.ArgumentParser(description="RealESRGAN image upscaler")
     parser.add_argument("-i", "--input", required=True, help="Input image or directory")
@@ -253,6 +268,7 @@ def main():
     parser.add_argument("--json", action="store_true", help="Output as JSON")
 
     args = parser.parse_args()
+    apply_seed()
 
     input_path = Path(args.input)
     output_path = Path(args.output)
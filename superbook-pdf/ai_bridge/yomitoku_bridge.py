@@ -214,6 +214,22 @@ def format_output(result: Dict[str, Any], output_format: str) -> str:
         return json.dumps(result, ensure_ascii=False)
 
 
+def apply_seed():
+    """Seed RNGs and prefer deterministic kernels when SUPERBOOK_SEED is set."""
+    seed = os.environ.get("SUPERBOOK_SEED")
+    if seed is None:
+        return
+    seed = int(seed)
+
+    import random
+    random.seed(seed)
+    if torch is None:
+        return
+    torch.manual_seed(seed)
+    torch.backends.cudnn.benchmark = False
+    torch.use_deterministic_algorithms(True, warn_only=True)
+
+
 def main():
     parser = argparse.ArgumentParser(description="YomiToku Japanese AI-OCR")
     parser.add_argument("input", help="Input image or directory")
@@ -236,6 +252,7 @@ def main():
     )
 
     args = parser.parse_args()
+    apply_seed()
 
     input_path = Path(args.input)
     gpu_id = None if args.no_gpu else args.gpu
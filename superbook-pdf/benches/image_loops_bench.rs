@@ -0,0 +1,142 @@
+//! Benchmarks for the per-pixel image loops
+//!
+//! Each hot function is measured on synthetic book pages at two sizes
+//! (A4 at 150 and 300 DPI), independent of any pipeline configuration.
+//!
+//! Run with: cargo bench --bench image_loops_bench
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use image::{GrayImage, Luma, Rgb, RgbImage};
+use superbook_pdf::cleanup::deblur::Deblurrer;
+use superbook_pdf::cleanup::{BlurDetector, MarkerRemover};
+use superbook_pdf::margin::ContentAwareBoundaryDetector;
+use superbook_pdf::vertical_detect::compute_linear_score;
+use superbook_pdf::VerticalDetectOptions;
+
+/// Page sizes (A4 at 150 and 300 DPI)
+const PAGE_SIZES: [(&str, u32, u32); 2] = [("a4_150dpi", 1240, 1754), ("a4_300dpi", 2480, 3508)];
+
+/// Synthetic text page: margins, rows of glyph-sized blocks and a highlighter band
+fn synthetic_page(width: u32, height: u32) -> RgbImage {
+    let mut page = RgbImage::from_pixel(width, height, Rgb([250, 248, 242]));
+    let margin = width / 10;
+    let glyph = (width / 80).max(4);
+    let line_pitch = glyph * 2;
+
+    let mut y = margin;
+    let mut line = 0u32;
+    while y + glyph < height - margin {
+        let mut x = margin;
+        while x + glyph < width - margin {
+            // Vary glyph widths and leave word gaps so components differ
+            let glyph_width = glyph - (x / glyph + line) % 3;
+            if (x / glyph + line) % 7 != 0 {
+                for gy in y..y + glyph {
+                    for gx in x..x + glyph_width {
+                        page.put_pixel(gx, gy, Rgb([30, 30, 35]));
+                    }
+                }
+            }
+            x += glyph + glyph / 3;
+        }
+        // Highlighter band over every fifth line
+        if line % 5 == 0 {
+            for gy in y..y + glyph {
+                for gx in margin..width / 2 {
+                    let pixel = page.get_pixel_mut(gx, gy);
+                    pixel.0[2] = pixel.0[2].min(90);
+                }
+            }
+        }
+        y += line_pitch;
+        line += 1;
+    }
+    page
+}
+
+fn to_gray(page: &RgbImage) -> GrayImage {
+    image::DynamicImage::ImageRgb8(page.clone()).to_luma8()
+}
+
+/// Content pixels become white, as in content-aware margin detection
+fn to_binary(gray: &GrayImage) -> GrayImage {
+    GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+        Luma([if gray.get_pixel(x, y).0[0] < 128 { 255 } else { 0 }])
+    })
+}
+
+fn bench_laplacian_variance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("laplacian_variance");
+    group.sample_size(10);
+    for (name, width, height) in PAGE_SIZES {
+        let gray = to_gray(&synthetic_page(width, height));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &gray, |b, gray| {
+            b.iter(|| black_box(BlurDetector::laplacian_variance(black_box(gray))))
+        });
+    }
+    group.finish();
+}
+
+fn bench_compute_edge_map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_edge_map");
+    group.sample_size(10);
+    for (name, width, height) in PAGE_SIZES {
+        let page = synthetic_page(width, height);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &page, |b, page| {
+            b.iter(|| black_box(MarkerRemover::compute_edge_map(black_box(page))))
+        });
+    }
+    group.finish();
+}
+
+fn bench_find_connected_components(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_connected_components");
+    group.sample_size(10);
+    for (name, width, height) in PAGE_SIZES {
+        let binary = to_binary(&to_gray(&synthetic_page(width, height)));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &binary, |b, binary| {
+            b.iter(|| black_box(ContentAwareBoundaryDetector::find_connected_components(black_box(binary))))
+        });
+    }
+    group.finish();
+}
+
+fn bench_apply_unsharp_mask(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_unsharp_mask");
+    group.sample_size(10);
+    for (name, width, height) in PAGE_SIZES {
+        let page = synthetic_page(width, height);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &page, |b, page| {
+            b.iter_batched_ref(
+                || page.clone(),
+                |image| Deblurrer::apply_unsharp_mask(image, 1.5, 1.0),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_compute_linear_score(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_linear_score");
+    group.sample_size(10);
+    let options = VerticalDetectOptions::default();
+    for (name, width, height) in PAGE_SIZES {
+        let gray = to_gray(&synthetic_page(width, height));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &gray, |b, gray| {
+            b.iter(|| black_box(compute_linear_score(black_box(gray), &options)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_laplacian_variance,
+    bench_compute_edge_map,
+    bench_find_connected_components,
+    bench_apply_unsharp_mask,
+    bench_compute_linear_score,
+);
+
+criterion_main!(benches);
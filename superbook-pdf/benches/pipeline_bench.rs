@@ -398,6 +398,7 @@ fn bench_page_offset_structures(c: &mut Criterion) {
                 shift_y: -5,
                 page_number_position: None,
                 is_odd: true,
+                repaired: false,
             })
         })
     });
@@ -412,6 +413,7 @@ fn bench_page_offset_structures(c: &mut Criterion) {
                 odd_avg_y: None,
                 even_avg_y: None,
                 match_count: 0,
+                repaired_count: 0,
                 confidence: 0.0,
             })
         })
@@ -503,6 +505,43 @@ fn bench_cache_structures(c: &mut Criterion) {
     group.finish();
 }
 
+/// Benchmark per-page vs batched pdftoppm extraction of a 500-page book
+///
+/// Per-page extraction starts 500 pdftoppm processes; batched extraction
+/// starts `ceil(500 / batch_pages)`. Skipped when pdftoppm is not installed.
+fn bench_pdftoppm_extraction(c: &mut Criterion) {
+    use superbook_pdf::{LopdfExtractor, PdfWriterOptions, PopplerExtractor, PrintPdfWriter};
+
+    if !LopdfExtractor::pdftoppm_available() {
+        eprintln!("pdftoppm not found; skipping extraction benchmark");
+        return;
+    }
+
+    const PAGES: usize = 500;
+    let dir = tempfile::tempdir().unwrap();
+    let pdf = dir.path().join("book.pdf");
+    let images = vec![std::path::PathBuf::from("tests/fixtures/book_page_1.png"); PAGES];
+    PrintPdfWriter::create_from_images(&images, &pdf, &PdfWriterOptions::default()).unwrap();
+
+    let mut group = c.benchmark_group("pdftoppm_extraction");
+    group.sample_size(10);
+    for batch_pages in [1, superbook_pdf::image_extract::DEFAULT_BATCH_PAGES] {
+        let options = ExtractOptions::builder().dpi(72).batch_pages(batch_pages).build();
+        let processes = PopplerExtractor::batch_ranges(PAGES, batch_pages, options.parallel).len();
+        group.bench_with_input(
+            BenchmarkId::new(format!("{}_processes", processes), batch_pages),
+            &options,
+            |b, options| {
+                b.iter(|| {
+                    let out = tempfile::tempdir().unwrap();
+                    black_box(PopplerExtractor::extract_all(&pdf, out.path(), options).unwrap())
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_option_builders,
@@ -524,6 +563,8 @@ criterion_group!(
     bench_progress_structures,
     // Cache module benchmarks
     bench_cache_structures,
+    // Extraction process-count benchmark
+    bench_pdftoppm_extraction,
 );
 
 criterion_main!(benches);
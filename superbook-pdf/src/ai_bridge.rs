@@ -88,6 +88,10 @@ pub struct AiBridgeConfig {
     pub retry_config: RetryConfig,
     /// Log level
     pub log_level: LogLevel,
+    /// RNG seed passed to bridge scripts (deterministic mode)
+    pub seed: Option<u64>,
+    /// CPU threads granted to a bridge process (external tool budget)
+    pub thread_budget: Option<usize>,
 }
 
 impl Default for AiBridgeConfig {
@@ -98,6 +102,8 @@ impl Default for AiBridgeConfig {
             timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             retry_config: RetryConfig::default(),
             log_level: LogLevel::Info,
+            seed: None,
+            thread_budget: None,
         }
     }
 }
@@ -199,6 +205,20 @@ impl AiBridgeConfigBuilder {
         self
     }
 
+    /// Set RNG seed for bridge scripts
+    #[must_use]
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+
+    /// Set CPU threads granted to bridge processes
+    #[must_use]
+    pub fn thread_budget(mut self, threads: Option<usize>) -> Self {
+        self.config.thread_budget = threads;
+        self
+    }
+
     /// Build the configuration
     #[must_use]
     pub fn build(self) -> AiBridgeConfig {
@@ -377,6 +397,25 @@ impl SubprocessBridge {
         }
     }
 
+    /// Pass the configured seed to a bridge process
+    fn apply_seed(&self, cmd: &mut Command) {
+        if let Some(seed) = self.config.seed {
+            cmd.env("SUPERBOOK_SEED", seed.to_string());
+            cmd.env("PYTHONHASHSEED", seed.to_string());
+            // Required by PyTorch for deterministic cuBLAS kernels
+            cmd.env("CUBLAS_WORKSPACE_CONFIG", ":4096:8");
+        }
+    }
+
+    /// Cap the CPU threads of a bridge process to the external tool budget
+    ///
+    /// Bridge runs are sequential, so one process gets the whole budget.
+    fn apply_thread_budget(&self, cmd: &mut Command) {
+        if let Some(threads) = self.config.thread_budget {
+            crate::tools::limit_threads(cmd, threads);
+        }
+    }
+
     /// Check if a tool is available
     pub fn check_tool(&self, tool: AiTool) -> Result<bool> {
         let python = self.get_python_path();
@@ -398,7 +437,7 @@ impl SubprocessBridge {
 
     /// Check GPU status
     pub fn check_gpu(&self) -> Result<GpuStats> {
-        let output = Command::new("nvidia-smi")
+        let output = Command::new(crate::tools::resolve("nvidia-smi"))
             .args(["--query-gpu=memory.used", "--format=csv,noheader,nounits"])
             .output()
             .map_err(|_| AiBridgeError::GpuNotAvailable)?;
@@ -465,6 +504,8 @@ impl SubprocessBridge {
             for retry in 0..=self.config.retry_config.max_retries {
                 let mut cmd = Command::new(&python);
                 cmd.arg(&bridge_script);
+                self.apply_seed(&mut cmd);
+                self.apply_thread_budget(&mut cmd);
 
                 match tool {
                     AiTool::RealESRGAN => {
@@ -551,6 +592,8 @@ impl SubprocessBridge {
 
         let mut cmd = Command::new(&python);
         cmd.args(args);
+        self.apply_seed(&mut cmd);
+        self.apply_thread_budget(&mut cmd);
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
@@ -682,6 +725,20 @@ mod tests {
         assert_eq!(config.gpu_config.device_id, Some(1));
     }
 
+    #[test]
+    fn test_builder_seed() {
+        assert_eq!(AiBridgeConfig::default().seed, None);
+        let config = AiBridgeConfig::builder().seed(42).build();
+        assert_eq!(config.seed, Some(42));
+    }
+
+    #[test]
+    fn test_builder_thread_budget() {
+        assert_eq!(AiBridgeConfig::default().thread_budget, None);
+        let config = AiBridgeConfig::builder().thread_budget(Some(3)).build();
+        assert_eq!(config.thread_budget, Some(3));
+    }
+
     // Note: The following tests require actual Python environment and tools
     // They are marked with #[ignore] until environment is available
 
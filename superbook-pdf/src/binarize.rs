@@ -0,0 +1,233 @@
+//! Adaptive (local) thresholding
+//!
+//! A global threshold such as Otsu's picks one gray level for the whole
+//! page, so a camera scan lit from one side ends up with its shadowed half
+//! black and its bright half losing faint strokes. [`adaptive`] instead
+//! thresholds each pixel against the mean and standard deviation of its
+//! `window` x `window` neighborhood (computed with integral images, so the
+//! cost does not depend on the window size).
+//!
+//! - **Sauvola**: `T = m * (1 + k * (s / 128 - 1))`; flat regions stay
+//!   white, the usual choice for text pages
+//! - **Niblack**: `T = m + k * s` (`k` negative); keeps faint strokes but
+//!   turns noise in empty regions into specks
+//! - **Mean**: `T = m`
+//!
+//! Pixels below the threshold become black (0), the rest white (255).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::binarize::{adaptive, AdaptiveMethod};
+//!
+//! let gray = image::open("page.png").unwrap().to_luma8();
+//! let binary = adaptive(&gray, AdaptiveMethod::default());
+//! binary.save("page_binary.png").unwrap();
+//! ```
+
+use image::{GrayImage, Luma};
+use serde::{Deserialize, Serialize};
+
+/// Default neighborhood size in pixels
+pub const DEFAULT_WINDOW: u32 = 31;
+
+/// Default Sauvola sensitivity
+pub const DEFAULT_SAUVOLA_K: f32 = 0.34;
+
+/// Default Niblack sensitivity
+pub const DEFAULT_NIBLACK_K: f32 = -0.2;
+
+/// Dynamic range of the standard deviation in Sauvola's formula
+const SAUVOLA_R: f64 = 128.0;
+
+/// Local threshold formula
+///
+/// `window` is the side of the square neighborhood in pixels; even values
+/// are rounded up to the next odd size and the minimum is 3.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdaptiveMethod {
+    /// `m * (1 + k * (s / 128 - 1))`
+    Sauvola { window: u32, k: f32 },
+    /// `m + k * s`
+    Niblack { window: u32, k: f32 },
+    /// `m`
+    Mean { window: u32 },
+}
+
+impl Default for AdaptiveMethod {
+    fn default() -> Self {
+        AdaptiveMethod::Sauvola {
+            window: DEFAULT_WINDOW,
+            k: DEFAULT_SAUVOLA_K,
+        }
+    }
+}
+
+impl AdaptiveMethod {
+    /// Niblack with the default window and sensitivity
+    pub fn niblack() -> Self {
+        AdaptiveMethod::Niblack {
+            window: DEFAULT_WINDOW,
+            k: DEFAULT_NIBLACK_K,
+        }
+    }
+
+    /// Mean with the default window
+    pub fn mean() -> Self {
+        AdaptiveMethod::Mean { window: DEFAULT_WINDOW }
+    }
+
+    /// Neighborhood size actually used (odd, at least 3)
+    pub fn window(&self) -> u32 {
+        let window = match *self {
+            AdaptiveMethod::Sauvola { window, .. }
+            | AdaptiveMethod::Niblack { window, .. }
+            | AdaptiveMethod::Mean { window } => window,
+        };
+        window.max(3) | 1
+    }
+
+    /// Threshold for a neighborhood of mean `mean` and standard deviation `std_dev`
+    pub fn threshold(&self, mean: f64, std_dev: f64) -> f64 {
+        match *self {
+            AdaptiveMethod::Sauvola { k, .. } => mean * (1.0 + k as f64 * (std_dev / SAUVOLA_R - 1.0)),
+            AdaptiveMethod::Niblack { k, .. } => mean + k as f64 * std_dev,
+            AdaptiveMethod::Mean { .. } => mean,
+        }
+    }
+
+    /// Short name for logs
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdaptiveMethod::Sauvola { .. } => "sauvola",
+            AdaptiveMethod::Niblack { .. } => "niblack",
+            AdaptiveMethod::Mean { .. } => "mean",
+        }
+    }
+}
+
+/// Binarize `gray` with a local threshold (black = 0 below it, white = 255)
+///
+/// Neighborhoods are clipped at the image border.
+pub fn adaptive(gray: &GrayImage, method: AdaptiveMethod) -> GrayImage {
+    let (width, height) = gray.dimensions();
+    let (w, h) = (width as usize, height as usize);
+
+    // Integral images of values and squared values, with a zero row and column
+    let stride = w + 1;
+    let mut sum = vec![0u64; stride * (h + 1)];
+    let mut sum_sq = vec![0u64; stride * (h + 1)];
+    for y in 0..h {
+        let (mut row, mut row_sq) = (0u64, 0u64);
+        for x in 0..w {
+            let value = gray.get_pixel(x as u32, y as u32).0[0] as u64;
+            row += value;
+            row_sq += value * value;
+            sum[(y + 1) * stride + x + 1] = sum[y * stride + x + 1] + row;
+            sum_sq[(y + 1) * stride + x + 1] = sum_sq[y * stride + x + 1] + row_sq;
+        }
+    }
+    let area = |table: &[u64], x0: usize, y0: usize, x1: usize, y1: usize| {
+        table[y1 * stride + x1] + table[y0 * stride + x0] - table[y0 * stride + x1] - table[y1 * stride + x0]
+    };
+
+    let half = (method.window() / 2) as usize;
+    GrayImage::from_fn(width, height, |x, y| {
+        let (x, y) = (x as usize, y as usize);
+        let (x0, y0) = (x.saturating_sub(half), y.saturating_sub(half));
+        let (x1, y1) = ((x + half + 1).min(w), (y + half + 1).min(h));
+        let count = ((x1 - x0) * (y1 - y0)) as f64;
+        let mean = area(&sum, x0, y0, x1, y1) as f64 / count;
+        let variance = (area(&sum_sq, x0, y0, x1, y1) as f64 / count - mean * mean).max(0.0);
+        let value = gray.get_pixel(x as u32, y as u32).0[0] as f64;
+        if value < method.threshold(mean, variance.sqrt()) {
+            Luma([0])
+        } else {
+            Luma([255])
+        }
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Page lit from the left: paper fades from 250 to 90, with dark
+    /// "text" bars (40% of the local paper brightness) across the page
+    pub(crate) fn gradient_lit_page() -> (GrayImage, impl Fn(u32, u32) -> bool) {
+        let is_text = |x: u32, y: u32| {
+            (20..280).contains(&x) && (20..180).contains(&y) && x % 40 < 24 && (10..16).contains(&(y % 30))
+        };
+        let page = GrayImage::from_fn(300, 200, |x, y| {
+            let paper = 250.0 - 160.0 * x as f64 / 299.0;
+            let value = if is_text(x, y) { paper * 0.4 } else { paper };
+            Luma([value.round() as u8])
+        });
+        (page, is_text)
+    }
+
+    /// Share of text pixels black and of paper pixels white (away from the
+    /// left and right borders, where clipped neighborhoods are one-sided)
+    fn accuracy(binary: &GrayImage, is_text: &impl Fn(u32, u32) -> bool) -> (f64, f64) {
+        let (mut text, mut text_black, mut paper, mut paper_white) = (0, 0, 0, 0);
+        for (x, y, pixel) in binary.enumerate_pixels() {
+            if !(16..binary.width() - 16).contains(&x) {
+                continue;
+            }
+            if is_text(x, y) {
+                text += 1;
+                text_black += (pixel.0[0] == 0) as usize;
+            } else {
+                paper += 1;
+                paper_white += (pixel.0[0] == 255) as usize;
+            }
+        }
+        (text_black as f64 / text as f64, paper_white as f64 / paper as f64)
+    }
+
+    #[test]
+    fn test_window_is_odd() {
+        assert_eq!(AdaptiveMethod::default().window(), 31);
+        assert_eq!(AdaptiveMethod::Mean { window: 16 }.window(), 17);
+        assert_eq!(AdaptiveMethod::Mean { window: 0 }.window(), 3);
+        assert_eq!(AdaptiveMethod::niblack().name(), "niblack");
+    }
+
+    #[test]
+    fn test_uniform_page_stays_white() {
+        let page = GrayImage::from_pixel(40, 40, Luma([120]));
+        for method in [AdaptiveMethod::default(), AdaptiveMethod::niblack(), AdaptiveMethod::mean()] {
+            assert!(adaptive(&page, method).pixels().all(|p| p.0[0] == 255), "{}", method.name());
+        }
+    }
+
+    #[test]
+    fn test_gradient_lit_page() {
+        let (page, is_text) = gradient_lit_page();
+
+        // A global Otsu threshold blackens the shadowed side
+        let otsu = crate::ImageProcDeskewer::otsu_binarize(&page);
+        let (_, otsu_paper) = accuracy(&otsu, &is_text);
+        assert!(otsu_paper < 0.8, "otsu paper {}", otsu_paper);
+
+        // Mean thresholds the rounding noise of flat paper into specks
+        for (method, min_paper) in [
+            (AdaptiveMethod::default(), 0.99),
+            (AdaptiveMethod::niblack(), 0.99),
+            (AdaptiveMethod::mean(), 0.8),
+        ] {
+            let binary = adaptive(&page, method);
+            assert!(binary.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255));
+            let (text, paper) = accuracy(&binary, &is_text);
+            assert!(text > 0.99, "{} text {}", method.name(), text);
+            assert!(paper > min_paper, "{} paper {}", method.name(), paper);
+        }
+
+        // Sauvola keeps empty paper white on both sides
+        let binary = adaptive(&page, AdaptiveMethod::default());
+        assert_eq!(binary.get_pixel(5, 5).0[0], 255);
+        assert_eq!(binary.get_pixel(295, 195).0[0], 255);
+        assert_eq!(binary.get_pixel(262, 42).0[0], 0);
+    }
+}
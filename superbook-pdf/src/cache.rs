@@ -16,6 +16,18 @@ pub const CACHE_EXTENSION: &str = ".superbook-cache";
 /// Current cache version
 pub const CACHE_VERSION: u32 = 1;
 
+/// Hash a JSON string of processing options (`sha256:<hex>`)
+pub fn hash_options(options_json: &str) -> String {
+    hash_bytes(options_json.as_bytes())
+}
+
+/// Hash arbitrary content (`sha256:<hex>`)
+pub fn hash_bytes(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    format!("sha256:{:x}", hasher.finalize())
+}
+
 /// Digest that uniquely identifies a processing run
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CacheDigest {
@@ -46,14 +58,10 @@ impl CacheDigest {
             .as_secs();
         let size = metadata.len();
 
-        let mut hasher = Sha256::new();
-        hasher.update(options_json.as_bytes());
-        let hash = format!("sha256:{:x}", hasher.finalize());
-
         Ok(Self {
             source_modified: modified,
             source_size: size,
-            options_hash: hash,
+            options_hash: hash_options(options_json),
         })
     }
 
@@ -80,6 +88,36 @@ pub struct ProcessingResult {
     pub elapsed_seconds: f64,
     /// Output file size in bytes
     pub output_size: u64,
+    /// Elapsed time per stage (seconds)
+    #[serde(default, skip_serializing_if = "crate::progress::StageTimings::is_empty")]
+    pub stage_timings: crate::progress::StageTimings,
+    /// Per-page mean OCR confidence (0.0-1.0, None = no OCR result)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ocr_confidence: Vec<Option<f32>>,
+    /// Per-page SSIM/PSNR between extracted and final page (empty unless enabled)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub page_quality: Vec<crate::quality::PageQuality>,
+    /// Pages whose image reuses an identical earlier page in the PDF
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub deduplicated_pages: usize,
+    /// 256-entry tone curve LUT applied to every page (empty unless configured)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tone_curve_lut: Vec<u8>,
+    /// Pages (0-based) left out of the output after a tool timeout
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_pages: Vec<usize>,
+    /// Median final image pixels per inch of the physical page
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_dpi: Option<f64>,
+    /// Written with the quick (draft) preset
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub draft: bool,
+    /// Plain text OCR sidecar written next to the output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_sidecar: Option<PathBuf>,
+    /// Pages extracted below the configured DPI to fit the page size cap
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clamped_pages: Vec<crate::pipeline::ClampedPage>,
 }
 
 impl Default for ProcessingResult {
@@ -90,6 +128,16 @@ impl Default for ProcessingResult {
             is_vertical: false,
             elapsed_seconds: 0.0,
             output_size: 0,
+            stage_timings: Default::default(),
+            ocr_confidence: Vec::new(),
+            page_quality: Vec::new(),
+            deduplicated_pages: 0,
+            tone_curve_lut: Vec::new(),
+            failed_pages: Vec::new(),
+            effective_dpi: None,
+            draft: false,
+            text_sidecar: None,
+            clamped_pages: Vec::new(),
         }
     }
 }
@@ -109,8 +157,97 @@ impl ProcessingResult {
             is_vertical,
             elapsed_seconds,
             output_size,
+            stage_timings: Default::default(),
+            ocr_confidence: Vec::new(),
+            page_quality: Vec::new(),
+            deduplicated_pages: 0,
+            tone_curve_lut: Vec::new(),
+            failed_pages: Vec::new(),
+            effective_dpi: None,
+            draft: false,
+            text_sidecar: None,
+            clamped_pages: Vec::new(),
         }
     }
+
+    /// Builder pattern: set per-stage timings
+    pub fn with_stage_timings(mut self, timings: crate::progress::StageTimings) -> Self {
+        self.stage_timings = timings;
+        self
+    }
+
+    /// Builder pattern: set per-page OCR confidence
+    pub fn with_ocr_confidence(mut self, confidence: Vec<Option<f32>>) -> Self {
+        self.ocr_confidence = confidence;
+        self
+    }
+
+    /// Builder pattern: set per-page quality metrics
+    pub fn with_page_quality(mut self, quality: Vec<crate::quality::PageQuality>) -> Self {
+        self.page_quality = quality;
+        self
+    }
+
+    /// Builder pattern: set number of deduplicated pages
+    pub fn with_deduplicated_pages(mut self, count: usize) -> Self {
+        self.deduplicated_pages = count;
+        self
+    }
+
+    /// Builder pattern: set the applied tone curve LUT
+    pub fn with_tone_curve_lut(mut self, lut: Vec<u8>) -> Self {
+        self.tone_curve_lut = lut;
+        self
+    }
+
+    /// Builder pattern: set pages skipped after a tool timeout
+    pub fn with_failed_pages(mut self, pages: Vec<usize>) -> Self {
+        self.failed_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set the effective output DPI
+    pub fn with_effective_dpi(mut self, dpi: Option<f64>) -> Self {
+        self.effective_dpi = dpi;
+        self
+    }
+
+    /// Builder pattern: mark the output as a quick (draft) conversion
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = draft;
+        self
+    }
+
+    /// Builder pattern: set the written text sidecar
+    pub fn with_text_sidecar(mut self, path: Option<PathBuf>) -> Self {
+        self.text_sidecar = path;
+        self
+    }
+
+    /// Builder pattern: set pages extracted below the configured DPI
+    pub fn with_clamped_pages(mut self, pages: Vec<crate::pipeline::ClampedPage>) -> Self {
+        self.clamped_pages = pages;
+        self
+    }
+
+    /// 1-based pages whose OCR confidence is below `min_percent` (0-100)
+    pub fn low_confidence_pages(&self, min_percent: f32) -> Vec<usize> {
+        low_confidence_pages(&self.ocr_confidence, min_percent)
+    }
+}
+
+/// 1-based pages whose OCR confidence (0.0-1.0) is below `min_percent` (0-100)
+pub fn low_confidence_pages(confidence: &[Option<f32>], min_percent: f32) -> Vec<usize> {
+    confidence
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_some_and(|c| c * 100.0 < min_percent))
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
 }
 
 /// Processing cache entry
@@ -124,6 +261,12 @@ pub struct ProcessingCache {
     pub digest: CacheDigest,
     /// Processing result metadata
     pub result: ProcessingResult,
+    /// Source file the output was converted from (absolute path)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+    /// Pipeline options of the run (`PipelineConfig` JSON), replayed by `rerun`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<serde_json::Value>,
 }
 
 impl ProcessingCache {
@@ -139,9 +282,19 @@ impl ProcessingCache {
             processed_at,
             digest,
             result,
+            source: None,
+            options: None,
         }
     }
 
+    /// Builder pattern: record the source file and options of the run
+    pub fn with_source<P: AsRef<Path>>(mut self, source: P, options_json: &str) -> Self {
+        let source = source.as_ref();
+        self.source = Some(source.canonicalize().unwrap_or_else(|_| source.to_path_buf()));
+        self.options = serde_json::from_str(options_json).ok();
+        self
+    }
+
     /// Get the cache file path for an output file
     pub fn cache_path<P: AsRef<Path>>(output_path: P) -> PathBuf {
         let mut path = output_path.as_ref().as_os_str().to_owned();
@@ -174,6 +327,11 @@ impl ProcessingCache {
         fs::write(&cache_path, content)
     }
 
+    /// Pretty-printed JSON, as stored in the cache file (`cache-info --json`)
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
     /// Check if the cache is valid for a given digest
     ///
     /// # Arguments
@@ -195,6 +353,85 @@ impl ProcessingCache {
     }
 }
 
+/// Result of comparing an existing output's cache against the current run
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)] // checked once per input file
+pub enum CacheStatus {
+    /// Cache matches source and options; processing can be skipped
+    Valid(ProcessingCache),
+    /// Output file does not exist yet
+    OutputMissing,
+    /// No readable cache file exists next to the output
+    NoCache,
+    /// Cache was written by a different cache version
+    VersionMismatch,
+    /// Source file size or modification time changed
+    SourceModified,
+    /// Processing options hash changed
+    OptionsChanged,
+}
+
+impl CacheStatus {
+    /// Whether processing can be skipped
+    pub fn is_valid(&self) -> bool {
+        matches!(self, CacheStatus::Valid(_))
+    }
+
+    /// Human-readable reason for (re)processing
+    pub fn reason(&self) -> &'static str {
+        match self {
+            CacheStatus::Valid(_) => "cache valid",
+            CacheStatus::OutputMissing => "output missing",
+            CacheStatus::NoCache => "no cache found",
+            CacheStatus::VersionMismatch => "cache version changed",
+            CacheStatus::SourceModified => "source modified",
+            CacheStatus::OptionsChanged => "options hash changed",
+        }
+    }
+}
+
+/// Compare the cache of an existing output against the current source and options
+///
+/// Source changes take precedence over option changes when both differ.
+///
+/// # Arguments
+/// * `source_path` - Path to the source PDF
+/// * `output_path` - Path to the output PDF
+/// * `options_json` - JSON string of processing options
+pub fn check_cache_status<P1: AsRef<Path>, P2: AsRef<Path>>(
+    source_path: P1,
+    output_path: P2,
+    options_json: &str,
+) -> CacheStatus {
+    if !output_path.as_ref().exists() {
+        return CacheStatus::OutputMissing;
+    }
+
+    let cache = match ProcessingCache::load(&output_path) {
+        Ok(cache) => cache,
+        Err(_) => return CacheStatus::NoCache,
+    };
+
+    if cache.version != CACHE_VERSION {
+        return CacheStatus::VersionMismatch;
+    }
+
+    let digest = match CacheDigest::new(&source_path, options_json) {
+        Ok(digest) => digest,
+        Err(_) => return CacheStatus::SourceModified,
+    };
+
+    if cache.digest.source_modified != digest.source_modified
+        || cache.digest.source_size != digest.source_size
+    {
+        CacheStatus::SourceModified
+    } else if cache.digest.options_hash != digest.options_hash {
+        CacheStatus::OptionsChanged
+    } else {
+        CacheStatus::Valid(cache)
+    }
+}
+
 /// Check if processing should be skipped based on cache
 ///
 /// # Arguments
@@ -215,20 +452,9 @@ pub fn should_skip_processing<P1: AsRef<Path>, P2: AsRef<Path>>(
         return None;
     }
 
-    // Check if output exists
-    if !output_path.as_ref().exists() {
-        return None;
-    }
-
-    // Try to create digest
-    let digest = CacheDigest::new(&source_path, options_json).ok()?;
-
-    // Try to load and validate cache
-    let cache = ProcessingCache::load(&output_path).ok()?;
-    if cache.is_valid(&digest) {
-        Some(cache)
-    } else {
-        None
+    match check_cache_status(source_path, output_path, options_json) {
+        CacheStatus::Valid(cache) => Some(cache),
+        _ => None,
     }
 }
 
@@ -253,6 +479,17 @@ mod tests {
         assert!(digest.options_hash.starts_with("sha256:"));
     }
 
+    #[test]
+    fn test_hash_options_matches_digest() {
+        let mut temp = NamedTempFile::new().unwrap();
+        temp.write_all(b"test content").unwrap();
+
+        let digest = CacheDigest::new(temp.path(), r#"{"dpi": 300}"#).unwrap();
+        assert_eq!(digest.options_hash, hash_options(r#"{"dpi": 300}"#));
+        assert_ne!(hash_options(r#"{"dpi": 300}"#), hash_options(r#"{"dpi": 600}"#));
+        assert_eq!(hash_bytes(b"abc"), hash_bytes(b"abc"));
+    }
+
     #[test]
     fn test_cache_digest_with_values() {
         let digest = CacheDigest::with_values(1234567890, 999, "sha256:abc123");
@@ -332,6 +569,60 @@ mod tests {
         assert_eq!(result.output_size, 12345678);
     }
 
+    #[test]
+    fn test_processing_result_ocr_confidence() {
+        let result = ProcessingResult::default();
+        assert!(!serde_json::to_string(&result).unwrap().contains("ocr_confidence"));
+
+        let result = ProcessingResult::default().with_ocr_confidence(vec![Some(0.9), Some(0.4), None, Some(0.59)]);
+        assert_eq!(result.low_confidence_pages(60.0), vec![2, 4]);
+        assert!(result.low_confidence_pages(0.0).is_empty());
+
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: ProcessingResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.ocr_confidence, result.ocr_confidence);
+    }
+
+    #[test]
+    fn test_processing_result_deduplicated_pages() {
+        assert!(!serde_json::to_string(&ProcessingResult::default())
+            .unwrap()
+            .contains("deduplicated_pages"));
+
+        let result = ProcessingResult::default().with_deduplicated_pages(3);
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#""deduplicated_pages":3"#));
+        let parsed: ProcessingResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.deduplicated_pages, 3);
+    }
+
+    #[test]
+    fn test_processing_result_tone_curve_lut() {
+        assert!(!serde_json::to_string(&ProcessingResult::default()).unwrap().contains("tone_curve_lut"));
+
+        let lut: Vec<u8> = (0..=255).rev().collect();
+        let result = ProcessingResult::default().with_tone_curve_lut(lut.clone());
+        let parsed: ProcessingResult = serde_json::from_str(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(parsed.tone_curve_lut, lut);
+    }
+
+    #[test]
+    fn test_processing_result_stage_timings_serde() {
+        // Caches written before stage timings existed still load
+        let json = r#"{"page_count":1,"page_number_shift":null,"is_vertical":false,"elapsed_seconds":1.0,"output_size":10}"#;
+        let result: ProcessingResult = serde_json::from_str(json).unwrap();
+        assert!(result.stage_timings.is_empty());
+        assert!(!serde_json::to_string(&result).unwrap().contains("stage_timings"));
+
+        let mut timings = crate::progress::StageTimings::new();
+        timings.add("ocr", std::time::Duration::from_secs(3));
+        let result = ProcessingResult::default().with_stage_timings(timings.clone());
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains(r#""stage_timings":{"ocr":3.0}"#));
+        let parsed: ProcessingResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.stage_timings, timings);
+    }
+
     // ============ ProcessingCache Tests ============
 
     #[test]
@@ -345,6 +636,28 @@ mod tests {
         assert_eq!(cache.digest, digest);
     }
 
+    #[test]
+    fn test_processing_cache_with_source() {
+        // TC: CACHE-012
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source = temp_dir.path().join("book.pdf");
+        fs::write(&source, b"%PDF").unwrap();
+        let output_path = temp_dir.path().join("book_converted.pdf");
+
+        let digest = CacheDigest::with_values(1234567890, 999, "sha256:abc");
+        let cache = ProcessingCache::new(digest, ProcessingResult::default()).with_source(&source, r#"{"dpi":450}"#);
+        cache.save(&output_path).unwrap();
+
+        let loaded = ProcessingCache::load(&output_path).unwrap();
+        assert_eq!(loaded.source, Some(source.canonicalize().unwrap()));
+        assert_eq!(loaded.options, Some(serde_json::json!({"dpi": 450})));
+
+        // Caches written before the source was recorded still load
+        let old = r#"{"version":1,"processed_at":0,"digest":{"source_modified":1,"source_size":2,"options_hash":"sha256:x"},"result":{"page_count":1,"page_number_shift":null,"is_vertical":false,"elapsed_seconds":0.0,"output_size":0}}"#;
+        let parsed: ProcessingCache = serde_json::from_str(old).unwrap();
+        assert!(parsed.source.is_none() && parsed.options.is_none());
+    }
+
     #[test]
     fn test_processing_cache_path() {
         let path = ProcessingCache::cache_path("/output/file.pdf");
@@ -370,6 +683,13 @@ mod tests {
         assert_eq!(loaded.result.page_count, 50);
         assert_eq!(loaded.result.page_number_shift, Some(3));
         assert!(loaded.result.is_vertical);
+
+        // JSON output matches the stored file
+        let stored = fs::read_to_string(ProcessingCache::cache_path(&output_path)).unwrap();
+        assert_eq!(loaded.to_json(), stored);
+        let value: serde_json::Value = serde_json::from_str(&loaded.to_json()).unwrap();
+        assert_eq!(value["digest"]["options_hash"], "sha256:abc");
+        assert_eq!(value["result"]["page_count"], 50);
     }
 
     #[test]
@@ -558,4 +878,88 @@ mod tests {
         let result = should_skip_processing(&source_path, &output_path, r#"{"dpi": 600}"#, false);
         assert!(result.is_none());
     }
+
+    // ============ check_cache_status Tests ============
+
+    #[test]
+    fn test_cache_status_output_missing() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source_path = temp_dir.path().join("source.pdf");
+        let output_path = temp_dir.path().join("output.pdf");
+        fs::write(&source_path, "source").unwrap();
+
+        let status = check_cache_status(&source_path, &output_path, "{}");
+        assert!(matches!(status, CacheStatus::OutputMissing));
+        assert!(!status.is_valid());
+    }
+
+    #[test]
+    fn test_cache_status_no_cache() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source_path = temp_dir.path().join("source.pdf");
+        let output_path = temp_dir.path().join("output.pdf");
+        fs::write(&source_path, "source").unwrap();
+        fs::write(&output_path, "output").unwrap();
+
+        let status = check_cache_status(&source_path, &output_path, "{}");
+        assert!(matches!(status, CacheStatus::NoCache));
+    }
+
+    #[test]
+    fn test_cache_status_options_changed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source_path = temp_dir.path().join("source.pdf");
+        let output_path = temp_dir.path().join("output.pdf");
+        fs::write(&source_path, "source").unwrap();
+        fs::write(&output_path, "output").unwrap();
+
+        let digest = CacheDigest::new(&source_path, r#"{"dpi": 300}"#).unwrap();
+        ProcessingCache::new(digest, ProcessingResult::default())
+            .save(&output_path)
+            .unwrap();
+
+        let status = check_cache_status(&source_path, &output_path, r#"{"dpi": 600}"#);
+        assert!(matches!(status, CacheStatus::OptionsChanged));
+        assert_eq!(status.reason(), "options hash changed");
+    }
+
+    #[test]
+    fn test_cache_status_source_modified() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source_path = temp_dir.path().join("source.pdf");
+        let output_path = temp_dir.path().join("output.pdf");
+        fs::write(&source_path, "source").unwrap();
+        fs::write(&output_path, "output").unwrap();
+
+        let digest = CacheDigest::new(&source_path, "{}").unwrap();
+        ProcessingCache::new(digest, ProcessingResult::default())
+            .save(&output_path)
+            .unwrap();
+
+        // Change the source size
+        fs::write(&source_path, "modified source content").unwrap();
+
+        let status = check_cache_status(&source_path, &output_path, "{}");
+        assert!(matches!(status, CacheStatus::SourceModified));
+        assert_eq!(status.reason(), "source modified");
+    }
+
+    #[test]
+    fn test_cache_status_valid() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let source_path = temp_dir.path().join("source.pdf");
+        let output_path = temp_dir.path().join("output.pdf");
+        fs::write(&source_path, "source").unwrap();
+        fs::write(&output_path, "output").unwrap();
+
+        let digest = CacheDigest::new(&source_path, "{}").unwrap();
+        ProcessingCache::new(digest, ProcessingResult::new(3, None, false, 1.0, 10))
+            .save(&output_path)
+            .unwrap();
+
+        match check_cache_status(&source_path, &output_path, "{}") {
+            CacheStatus::Valid(cache) => assert_eq!(cache.result.page_count, 3),
+            other => panic!("Expected valid cache, got {:?}", other),
+        }
+    }
 }
@@ -39,6 +39,12 @@ const MIN_UNSHARP_AMOUNT: f32 = 0.1;
 /// Maximum unsharp mask amount
 const MAX_UNSHARP_AMOUNT: f32 = 5.0;
 
+/// Default adaptive amount for barely blurry pages (severity 0)
+const DEFAULT_ADAPTIVE_MIN_AMOUNT: f32 = 0.5;
+
+/// Default adaptive amount for the blurriest pages (severity 1)
+const DEFAULT_ADAPTIVE_MAX_AMOUNT: f32 = 2.5;
+
 // ============================================================
 // Types
 // ============================================================
@@ -94,6 +100,16 @@ pub struct DeblurOptions {
     /// Unsharp mask sharpening amount
     pub unsharp_amount: f32,
 
+    /// Scale the amount with the measured blur severity instead of using
+    /// `unsharp_amount` (light sharpening for mild blur, more for heavy blur)
+    pub adaptive_amount: bool,
+
+    /// Adaptive amount at blur severity 0
+    pub adaptive_min_amount: f32,
+
+    /// Adaptive amount at blur severity 1
+    pub adaptive_max_amount: f32,
+
     /// AI model configuration (for AI algorithms)
     pub ai_model: Option<AiDeblurModel>,
 }
@@ -106,6 +122,9 @@ impl Default for DeblurOptions {
             blur_threshold: DEFAULT_BLUR_THRESHOLD,
             unsharp_sigma: DEFAULT_UNSHARP_SIGMA,
             unsharp_amount: DEFAULT_UNSHARP_AMOUNT,
+            adaptive_amount: false,
+            adaptive_min_amount: DEFAULT_ADAPTIVE_MIN_AMOUNT,
+            adaptive_max_amount: DEFAULT_ADAPTIVE_MAX_AMOUNT,
             ai_model: None,
         }
     }
@@ -143,6 +162,19 @@ impl DeblurOptions {
             ..Default::default()
         }
     }
+
+    /// Unsharp amount for a page with the given blur metrics
+    ///
+    /// With `adaptive_amount`, interpolates linearly from
+    /// `adaptive_min_amount` (severity 0) to `adaptive_max_amount`
+    /// (severity 1); otherwise `unsharp_amount`.
+    pub fn amount_for(&self, metrics: &BlurMetrics) -> f32 {
+        if !self.adaptive_amount {
+            return self.unsharp_amount;
+        }
+        let severity = metrics.blur_severity.clamp(0.0, 1.0) as f32;
+        self.adaptive_min_amount + (self.adaptive_max_amount - self.adaptive_min_amount) * severity
+    }
 }
 
 /// Builder for DeblurOptions
@@ -187,6 +219,23 @@ impl DeblurOptionsBuilder {
         self
     }
 
+    /// Enable severity-driven unsharp amount
+    #[must_use]
+    pub fn adaptive_amount(mut self, adaptive: bool) -> Self {
+        self.options.adaptive_amount = adaptive;
+        self
+    }
+
+    /// Set the adaptive amount range (swapped if given in reverse)
+    #[must_use]
+    pub fn adaptive_amount_range(mut self, min: f32, max: f32) -> Self {
+        let min = min.clamp(MIN_UNSHARP_AMOUNT, MAX_UNSHARP_AMOUNT);
+        let max = max.clamp(MIN_UNSHARP_AMOUNT, MAX_UNSHARP_AMOUNT);
+        self.options.adaptive_min_amount = min.min(max);
+        self.options.adaptive_max_amount = min.max(max);
+        self
+    }
+
     /// Set AI model
     #[must_use]
     pub fn ai_model(mut self, model: AiDeblurModel) -> Self {
@@ -226,6 +275,9 @@ pub struct DeblurResult {
     /// Was the image processed?
     pub processed: bool,
 
+    /// Unsharp amount applied (`None` if not processed)
+    pub unsharp_amount: Option<f32>,
+
     /// Algorithm used
     pub algorithm: DeblurAlgorithm,
 
@@ -350,20 +402,22 @@ impl Deblurrer {
                 before_metrics,
                 after_metrics: None,
                 processed: false,
+                unsharp_amount: None,
                 algorithm: options.algorithm,
                 image_size: (width, height),
             });
         }
 
         // Apply deblur
+        let amount = options.amount_for(&before_metrics);
         match options.algorithm {
             DeblurAlgorithm::UnsharpMask => {
-                Self::apply_unsharp_mask(&mut rgb, options.unsharp_sigma, options.unsharp_amount);
+                Self::apply_unsharp_mask(&mut rgb, options.unsharp_sigma, amount);
             }
             DeblurAlgorithm::NafNet | DeblurAlgorithm::DeblurGanV2 => {
                 // AI deblur would be handled by Python bridge
                 // For now, fall back to unsharp mask
-                Self::apply_unsharp_mask(&mut rgb, options.unsharp_sigma, options.unsharp_amount);
+                Self::apply_unsharp_mask(&mut rgb, options.unsharp_sigma, amount);
             }
         }
 
@@ -379,6 +433,7 @@ impl Deblurrer {
             before_metrics,
             after_metrics: Some(after_metrics),
             processed: true,
+            unsharp_amount: Some(amount),
             algorithm: options.algorithm,
             image_size: (width, height),
         })
@@ -398,19 +453,21 @@ impl Deblurrer {
                 before_metrics,
                 after_metrics: None,
                 processed: false,
+                unsharp_amount: None,
                 algorithm: options.algorithm,
                 image_size: (width, height),
             });
         }
 
         // Apply deblur
+        let amount = options.amount_for(&before_metrics);
         match options.algorithm {
             DeblurAlgorithm::UnsharpMask => {
-                Self::apply_unsharp_mask(image, options.unsharp_sigma, options.unsharp_amount);
+                Self::apply_unsharp_mask(image, options.unsharp_sigma, amount);
             }
             DeblurAlgorithm::NafNet | DeblurAlgorithm::DeblurGanV2 => {
                 // AI deblur would be handled by Python bridge
-                Self::apply_unsharp_mask(image, options.unsharp_sigma, options.unsharp_amount);
+                Self::apply_unsharp_mask(image, options.unsharp_sigma, amount);
             }
         }
 
@@ -422,6 +479,7 @@ impl Deblurrer {
             before_metrics,
             after_metrics: Some(after_metrics),
             processed: true,
+            unsharp_amount: Some(amount),
             algorithm: options.algorithm,
             image_size: (width, height),
         })
@@ -561,6 +619,35 @@ mod tests {
         assert!(ai.ai_model.is_some());
     }
 
+    #[test]
+    fn test_adaptive_amount() {
+        let metrics = |blur_severity| BlurMetrics {
+            laplacian_variance: 0.0,
+            is_blurry: true,
+            blur_severity,
+        };
+
+        // Fixed amount unless adaptive
+        let fixed = DeblurOptions::default();
+        assert_eq!(fixed.amount_for(&metrics(0.9)), DEFAULT_UNSHARP_AMOUNT);
+
+        let opts = DeblurOptions::builder()
+            .adaptive_amount(true)
+            .adaptive_amount_range(3.0, 1.0)
+            .build();
+        assert_eq!((opts.adaptive_min_amount, opts.adaptive_max_amount), (1.0, 3.0));
+        assert_eq!(opts.amount_for(&metrics(0.0)), 1.0);
+        assert_eq!(opts.amount_for(&metrics(0.5)), 2.0);
+        assert_eq!(opts.amount_for(&metrics(1.0)), 3.0);
+
+        // The applied amount is reported per page
+        let mut flat = RgbImage::from_pixel(20, 20, Rgb([128, 128, 128]));
+        let result = Deblurrer::process_in_place(&mut flat, &opts).unwrap();
+        assert!(result.processed);
+        assert_eq!(result.unsharp_amount, Some(opts.amount_for(&result.before_metrics)));
+        assert_eq!(result.unsharp_amount, Some(3.0));
+    }
+
     #[test]
     fn test_laplacian_variance_sharp_image() {
         // Create a sharp image with edges
@@ -666,6 +753,7 @@ mod tests {
                 blur_severity: 0.2,
             }),
             processed: true,
+            unsharp_amount: Some(1.5),
             algorithm: DeblurAlgorithm::UnsharpMask,
             image_size: (100, 100),
         };
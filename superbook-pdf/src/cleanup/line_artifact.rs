@@ -0,0 +1,512 @@
+//! Scanner Line Artifact Removal module
+//!
+//! Detects and removes long thin dark streaks left by dust or hair on the
+//! scanner platen.
+//!
+//! # Algorithm
+//!
+//! 1. Count dark pixels in every column (vertical lines) and row (horizontal lines)
+//! 2. Keep runs of adjacent columns/rows that are dark over most of the
+//!    page dimension and at most `max_width` pixels wide
+//! 3. Reject runs with perpendicular structure (dark strokes leaving the line
+//!    for at least `perpendicular_length` pixels), which marks table rules
+//!    and frames rather than debris
+//! 4. Inpaint the remaining runs by interpolating the neighboring columns/rows
+
+use image::{GrayImage, Rgb, RgbImage};
+use std::path::Path;
+
+use super::types::{CleanupError, Result};
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Luminance below which a pixel counts as dark
+const DEFAULT_DARK_THRESHOLD: u8 = 128;
+
+/// Fraction of the page dimension a line must cover
+const DEFAULT_MIN_COVERAGE: f32 = 0.9;
+
+/// Maximum line width in pixels
+const DEFAULT_MAX_WIDTH: u32 = 6;
+
+/// Length of a perpendicular stroke that marks a line as structure (table rule)
+const DEFAULT_PERPENDICULAR_LENGTH: u32 = 64;
+
+// ============================================================
+// Types
+// ============================================================
+
+/// Direction of a line artifact
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineOrientation {
+    /// Runs top to bottom (a dark column band)
+    Vertical,
+    /// Runs left to right (a dark row band)
+    Horizontal,
+}
+
+/// Options for line artifact removal
+#[derive(Debug, Clone)]
+pub struct LineArtifactOptions {
+    /// Luminance below which a pixel counts as dark
+    pub dark_threshold: u8,
+
+    /// Fraction (0.0-1.0) of the page height/width a line must cover
+    pub min_coverage: f32,
+
+    /// Maximum line width in pixels; wider bands are left alone
+    pub max_width: u32,
+
+    /// Perpendicular stroke length that marks a line as structure
+    pub perpendicular_length: u32,
+
+    /// Detect vertical lines
+    pub vertical: bool,
+
+    /// Detect horizontal lines
+    pub horizontal: bool,
+}
+
+impl Default for LineArtifactOptions {
+    fn default() -> Self {
+        Self {
+            dark_threshold: DEFAULT_DARK_THRESHOLD,
+            min_coverage: DEFAULT_MIN_COVERAGE,
+            max_width: DEFAULT_MAX_WIDTH,
+            perpendicular_length: DEFAULT_PERPENDICULAR_LENGTH,
+            vertical: true,
+            horizontal: true,
+        }
+    }
+}
+
+impl LineArtifactOptions {
+    /// Create a builder
+    pub fn builder() -> LineArtifactOptionsBuilder {
+        LineArtifactOptionsBuilder::default()
+    }
+
+    /// Create options for vertical lines only (sheet-fed scanners)
+    pub fn vertical_only() -> Self {
+        Self {
+            horizontal: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// Builder for LineArtifactOptions
+#[derive(Debug, Default)]
+pub struct LineArtifactOptionsBuilder {
+    options: LineArtifactOptions,
+}
+
+impl LineArtifactOptionsBuilder {
+    /// Set the dark pixel threshold
+    #[must_use]
+    pub fn dark_threshold(mut self, threshold: u8) -> Self {
+        self.options.dark_threshold = threshold;
+        self
+    }
+
+    /// Set the required coverage of the page dimension
+    #[must_use]
+    pub fn min_coverage(mut self, coverage: f32) -> Self {
+        self.options.min_coverage = coverage.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the maximum line width
+    #[must_use]
+    pub fn max_width(mut self, width: u32) -> Self {
+        self.options.max_width = width.max(1);
+        self
+    }
+
+    /// Set the perpendicular stroke length treated as structure
+    #[must_use]
+    pub fn perpendicular_length(mut self, length: u32) -> Self {
+        self.options.perpendicular_length = length.max(1);
+        self
+    }
+
+    /// Set which orientations are detected
+    #[must_use]
+    pub fn orientations(mut self, vertical: bool, horizontal: bool) -> Self {
+        self.options.vertical = vertical;
+        self.options.horizontal = horizontal;
+        self
+    }
+
+    /// Build the options
+    #[must_use]
+    pub fn build(self) -> LineArtifactOptions {
+        self.options
+    }
+}
+
+/// A detected line artifact
+#[derive(Debug, Clone, PartialEq)]
+pub struct LineArtifact {
+    /// Line direction
+    pub orientation: LineOrientation,
+
+    /// First column (vertical) or row (horizontal) of the line
+    pub position: u32,
+
+    /// Width in pixels
+    pub width: u32,
+
+    /// Fraction of the page dimension covered by the darkest column/row
+    pub coverage: f32,
+}
+
+/// Line artifact detection result
+#[derive(Debug, Clone)]
+pub struct LineArtifactResult {
+    /// Isolated lines (removed by `remove`)
+    pub artifacts: Vec<LineArtifact>,
+
+    /// Thin full-length lines kept because they have perpendicular structure
+    pub structured_lines: usize,
+
+    /// Image dimensions
+    pub image_size: (u32, u32),
+}
+
+impl LineArtifactResult {
+    /// Check if any artifacts were detected
+    pub fn has_artifacts(&self) -> bool {
+        !self.artifacts.is_empty()
+    }
+}
+
+// ============================================================
+// Line Artifact Remover
+// ============================================================
+
+/// Scanner line artifact processor
+pub struct LineArtifactRemover;
+
+impl LineArtifactRemover {
+    /// Detect line artifacts in an image file
+    pub fn detect(image_path: &Path, options: &LineArtifactOptions) -> Result<LineArtifactResult> {
+        if !image_path.exists() {
+            return Err(CleanupError::ImageNotFound(image_path.to_path_buf()));
+        }
+
+        let img = image::open(image_path).map_err(|e| CleanupError::InvalidImage(e.to_string()))?;
+        Ok(Self::detect_from_gray(&img.to_luma8(), options))
+    }
+
+    /// Detect line artifacts in an RGB image
+    pub fn detect_from_image(image: &RgbImage, options: &LineArtifactOptions) -> Result<LineArtifactResult> {
+        let gray = image::DynamicImage::ImageRgb8(image.clone()).to_luma8();
+        Ok(Self::detect_from_gray(&gray, options))
+    }
+
+    /// Remove line artifacts from an image file
+    pub fn remove(
+        image_path: &Path,
+        output_path: &Path,
+        options: &LineArtifactOptions,
+    ) -> Result<LineArtifactResult> {
+        if !image_path.exists() {
+            return Err(CleanupError::ImageNotFound(image_path.to_path_buf()));
+        }
+
+        let img = image::open(image_path).map_err(|e| CleanupError::InvalidImage(e.to_string()))?;
+        let mut rgb = img.to_rgb8();
+
+        let result = Self::remove_in_place(&mut rgb, options)?;
+
+        rgb.save(output_path)
+            .map_err(|e| CleanupError::InvalidImage(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Remove line artifacts from an RGB image in place
+    pub fn remove_in_place(image: &mut RgbImage, options: &LineArtifactOptions) -> Result<LineArtifactResult> {
+        let result = Self::detect_from_image(image, options)?;
+        for artifact in &result.artifacts {
+            Self::inpaint(image, artifact);
+        }
+        Ok(result)
+    }
+
+    fn detect_from_gray(gray: &GrayImage, options: &LineArtifactOptions) -> LineArtifactResult {
+        let mut artifacts = Vec::new();
+        let mut structured_lines = 0;
+
+        let orientations = [
+            (LineOrientation::Vertical, options.vertical),
+            (LineOrientation::Horizontal, options.horizontal),
+        ];
+        for (orientation, enabled) in orientations {
+            if enabled {
+                let view = LineView { gray, orientation };
+                structured_lines += Self::detect_lines(&view, options, &mut artifacts);
+            }
+        }
+
+        LineArtifactResult {
+            artifacts,
+            structured_lines,
+            image_size: gray.dimensions(),
+        }
+    }
+
+    /// Collect isolated lines of one orientation, returning how many were structure
+    fn detect_lines(view: &LineView, options: &LineArtifactOptions, artifacts: &mut Vec<LineArtifact>) -> usize {
+        let (across, along) = view.dimensions();
+        if across == 0 || along == 0 {
+            return 0;
+        }
+
+        let coverage: Vec<f32> = (0..across)
+            .map(|a| (0..along).filter(|&l| view.is_dark(a, l, options.dark_threshold)).count() as f32 / along as f32)
+            .collect();
+
+        let mut structured = 0;
+        let mut start = 0u32;
+        while start < across {
+            if coverage[start as usize] < options.min_coverage {
+                start += 1;
+                continue;
+            }
+            let mut end = start;
+            while end + 1 < across && coverage[end as usize + 1] >= options.min_coverage {
+                end += 1;
+            }
+
+            let width = end - start + 1;
+            if width <= options.max_width {
+                if Self::has_perpendicular_structure(view, start, end, options) {
+                    structured += 1;
+                } else {
+                    artifacts.push(LineArtifact {
+                        orientation: view.orientation,
+                        position: start,
+                        width,
+                        coverage: coverage[start as usize..=end as usize].iter().cloned().fold(0.0, f32::max),
+                    });
+                }
+            }
+            start = end + 1;
+        }
+        structured
+    }
+
+    /// Whether a dark stroke leaves the line on either side for `perpendicular_length` pixels
+    fn has_perpendicular_structure(view: &LineView, start: u32, end: u32, options: &LineArtifactOptions) -> bool {
+        let (across, along) = view.dimensions();
+        let length = options.perpendicular_length;
+        let dark = |a: u32, l: u32| view.is_dark(a, l, options.dark_threshold);
+
+        (0..along).any(|l| {
+            let before = start >= length && (start - length..start).all(|a| dark(a, l));
+            let after = end + length < across && (end + 1..=end + length).all(|a| dark(a, l));
+            before || after
+        })
+    }
+
+    /// Replace a line by interpolating between its neighboring columns/rows
+    fn inpaint(image: &mut RgbImage, artifact: &LineArtifact) {
+        let (width, height) = image.dimensions();
+        let (across, along) = match artifact.orientation {
+            LineOrientation::Vertical => (width, height),
+            LineOrientation::Horizontal => (height, width),
+        };
+        let coords = |a: u32, l: u32| match artifact.orientation {
+            LineOrientation::Vertical => (a, l),
+            LineOrientation::Horizontal => (l, a),
+        };
+
+        let start = artifact.position;
+        let end = (start + artifact.width).min(across);
+        let before = start.checked_sub(1);
+        let after = (end < across).then_some(end);
+
+        for l in 0..along {
+            let pixel_at = |a: Option<u32>| {
+                a.map(|a| {
+                    let (x, y) = coords(a, l);
+                    *image.get_pixel(x, y)
+                })
+            };
+            let (left, right) = match (pixel_at(before), pixel_at(after)) {
+                (Some(left), Some(right)) => (left, right),
+                (Some(only), None) | (None, Some(only)) => (only, only),
+                (None, None) => return,
+            };
+
+            for a in start..end {
+                let t = (a - start + 1) as f32 / (artifact.width + 1) as f32;
+                let (x, y) = coords(a, l);
+                image.put_pixel(x, y, Self::lerp(&left, &right, t));
+            }
+        }
+    }
+
+    fn lerp(from: &Rgb<u8>, to: &Rgb<u8>, t: f32) -> Rgb<u8> {
+        let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Rgb([mix(from.0[0], to.0[0]), mix(from.0[1], to.0[1]), mix(from.0[2], to.0[2])])
+    }
+}
+
+/// Grayscale access in line coordinates: `across` indexes columns (vertical)
+/// or rows (horizontal), `along` runs the length of the line
+struct LineView<'a> {
+    gray: &'a GrayImage,
+    orientation: LineOrientation,
+}
+
+impl LineView<'_> {
+    fn dimensions(&self) -> (u32, u32) {
+        let (width, height) = self.gray.dimensions();
+        match self.orientation {
+            LineOrientation::Vertical => (width, height),
+            LineOrientation::Horizontal => (height, width),
+        }
+    }
+
+    fn is_dark(&self, across: u32, along: u32, threshold: u8) -> bool {
+        let (x, y) = match self.orientation {
+            LineOrientation::Vertical => (across, along),
+            LineOrientation::Horizontal => (along, across),
+        };
+        self.gray.get_pixel(x, y).0[0] < threshold
+    }
+}
+
+// ============================================================
+// Tests
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgb<u8> = Rgb([255, 255, 255]);
+    const INK: Rgb<u8> = Rgb([20, 20, 20]);
+
+    fn page_with_text() -> RgbImage {
+        let mut image = RgbImage::from_pixel(300, 400, WHITE);
+        // A few short text-like strokes
+        for line in 0..5 {
+            for x in 40..260 {
+                if x % 12 < 6 {
+                    for y in 0..8 {
+                        image.put_pixel(x, 50 + line * 60 + y, INK);
+                    }
+                }
+            }
+        }
+        image
+    }
+
+    fn draw_vertical(image: &mut RgbImage, x0: u32, width: u32) {
+        for x in x0..x0 + width {
+            for y in 0..image.height() {
+                image.put_pixel(x, y, Rgb([60, 60, 60]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_options_builder() {
+        let options = LineArtifactOptions::builder()
+            .dark_threshold(100)
+            .min_coverage(1.5)
+            .max_width(0)
+            .orientations(true, false)
+            .build();
+        assert_eq!(options.dark_threshold, 100);
+        assert_eq!(options.min_coverage, 1.0);
+        assert_eq!(options.max_width, 1);
+        assert!(options.vertical && !options.horizontal);
+        assert!(!LineArtifactOptions::vertical_only().horizontal);
+    }
+
+    #[test]
+    fn test_detect_and_remove_vertical_hair() {
+        let mut image = page_with_text();
+        draw_vertical(&mut image, 150, 2);
+
+        let result = LineArtifactRemover::remove_in_place(&mut image, &LineArtifactOptions::default()).unwrap();
+        assert_eq!(result.artifacts.len(), 1);
+        let artifact = &result.artifacts[0];
+        assert_eq!(artifact.orientation, LineOrientation::Vertical);
+        assert_eq!((artifact.position, artifact.width), (150, 2));
+        assert!(artifact.coverage > 0.99);
+
+        // Paper areas are white again, text rows are interpolated from their neighbors
+        assert_eq!(*image.get_pixel(150, 10), WHITE);
+        assert_eq!(*image.get_pixel(151, 390), WHITE);
+        assert!(!LineArtifactRemover::detect_from_image(&image, &LineArtifactOptions::default())
+            .unwrap()
+            .has_artifacts());
+    }
+
+    #[test]
+    fn test_detect_horizontal_line() {
+        let mut image = page_with_text();
+        for x in 0..300 {
+            image.put_pixel(x, 20, Rgb([70, 70, 70]));
+        }
+        let result = LineArtifactRemover::detect_from_image(&image, &LineArtifactOptions::default()).unwrap();
+        assert_eq!(result.artifacts.len(), 1);
+        assert_eq!(result.artifacts[0].orientation, LineOrientation::Horizontal);
+        assert_eq!(result.artifacts[0].position, 20);
+
+        let vertical = LineArtifactRemover::detect_from_image(&image, &LineArtifactOptions::vertical_only()).unwrap();
+        assert!(!vertical.has_artifacts());
+    }
+
+    #[test]
+    fn test_table_rule_is_kept() {
+        let mut image = page_with_text();
+        draw_vertical(&mut image, 150, 2);
+        // Horizontal rule crossing the vertical one: a table, not a hair
+        for x in 50..250 {
+            image.put_pixel(x, 200, INK);
+        }
+        let result = LineArtifactRemover::detect_from_image(&image, &LineArtifactOptions::default()).unwrap();
+        assert!(!result.has_artifacts());
+        assert_eq!(result.structured_lines, 1);
+    }
+
+    #[test]
+    fn test_wide_and_short_bands_are_kept() {
+        let mut image = page_with_text();
+        // Wide dark gutter
+        draw_vertical(&mut image, 100, 20);
+        // Short line (half the page)
+        for y in 0..200 {
+            image.put_pixel(250, y, INK);
+        }
+        let result = LineArtifactRemover::detect_from_image(&image, &LineArtifactOptions::default()).unwrap();
+        assert!(!result.has_artifacts());
+    }
+
+    #[test]
+    fn test_remove_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("page.png");
+        let output = dir.path().join("clean.png");
+        let mut image = page_with_text();
+        draw_vertical(&mut image, 10, 1);
+        image.save(&input).unwrap();
+
+        let result = LineArtifactRemover::remove(&input, &output, &LineArtifactOptions::default()).unwrap();
+        assert!(result.has_artifacts());
+        let cleaned = image::open(&output).unwrap().to_rgb8();
+        assert_eq!(*cleaned.get_pixel(10, 5), WHITE);
+
+        let missing = LineArtifactRemover::detect(Path::new("/nonexistent/page.png"), &LineArtifactOptions::default());
+        assert!(matches!(missing, Err(CleanupError::ImageNotFound(_))));
+    }
+}
@@ -12,13 +12,23 @@
 //!
 //! 1. Convert pixels to HSV color space
 //! 2. Match against predefined highlighter color ranges
-//! 3. Apply Sobel edge detection to preserve text edges
+//! 3. Apply Sobel (or Scharr) edge detection to preserve text edges
 //! 4. Fade matched pixels toward white
+//!
+//! Colors are named on the command line and in `superbook.toml`; besides the
+//! built-in presets, `[[highlighter]]` tables define [`CustomHighlighter`]s
+//! that [`MarkerRemovalOptions::from_names`] resolves by name.
+//!
+//! Detection and removal can be confined with [`MarkerRegion`]; the
+//! `Margins` region uses [`ContentAwareBoundaryDetector`] to leave the text
+//! block (and any printed colored headings in it) untouched.
 
 use image::{GrayImage, Luma, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
 use std::path::Path;
 
 use super::types::{CleanupError, Result};
+use crate::margin::{ContentAwareBoundaryDetector, ContentAwareOptions, ContentRect};
 
 // ============================================================
 // Constants - HSV Color Ranges for Highlighters
@@ -101,6 +111,31 @@ impl HighlighterColor {
         ]
     }
 
+    /// Built-in color by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<HighlighterColor> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "yellow" => Some(HighlighterColor::Yellow),
+            "pink" => Some(HighlighterColor::Pink),
+            "green" => Some(HighlighterColor::Green),
+            "blue" => Some(HighlighterColor::Blue),
+            "orange" => Some(HighlighterColor::Orange),
+            _ => None,
+        }
+    }
+
+    /// Resolve a color name against config-defined highlighters, then the built-ins
+    ///
+    /// A `[[highlighter]]` with a built-in name replaces that preset.
+    pub fn resolve(name: &str, custom: &[CustomHighlighter]) -> Result<HighlighterColor> {
+        let name = name.trim();
+        custom
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(CustomHighlighter::color)
+            .or_else(|| HighlighterColor::from_name(name))
+            .ok_or_else(|| CleanupError::UnknownHighlighter(name.to_string()))
+    }
+
     /// Get HSV range for this color
     fn hsv_range(&self) -> HsvRange {
         match self {
@@ -163,6 +198,50 @@ impl HighlighterColor {
     }
 }
 
+/// Named highlighter color defined in `superbook.toml`
+///
+/// ```toml
+/// [[highlighter]]
+/// name = "mybrand"
+/// hue_min = 160      # degrees (0-360, hue_min > hue_max wraps around red)
+/// hue_max = 180
+/// sat_min = 25       # percent
+/// sat_max = 90
+/// val_min = 60       # percent
+/// val_max = 100
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomHighlighter {
+    /// Name used in `--marker-colors` / `highlighter_colors`
+    pub name: String,
+    /// Minimum hue in degrees
+    pub hue_min: u16,
+    /// Maximum hue in degrees
+    pub hue_max: u16,
+    /// Minimum saturation in percent
+    pub sat_min: u8,
+    /// Maximum saturation in percent
+    pub sat_max: u8,
+    /// Minimum value (brightness) in percent
+    pub val_min: u8,
+    /// Maximum value (brightness) in percent
+    pub val_max: u8,
+}
+
+impl CustomHighlighter {
+    /// The color this definition matches
+    pub fn color(&self) -> HighlighterColor {
+        HighlighterColor::Custom {
+            hue_min: self.hue_min,
+            hue_max: self.hue_max,
+            sat_min: self.sat_min,
+            sat_max: self.sat_max,
+            val_min: self.val_min,
+            val_max: self.val_max,
+        }
+    }
+}
+
 /// HSV range for color matching
 #[derive(Debug, Clone, Copy)]
 struct HsvRange {
@@ -192,6 +271,130 @@ impl HsvRange {
     }
 }
 
+/// Gradient operator for the edge-preservation map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeOperator {
+    /// 3x3 Sobel (1-2-1 smoothing)
+    #[default]
+    Sobel,
+    /// 3x3 Scharr (3-10-3 smoothing): more rotationally accurate, keeps
+    /// diagonal parts of thin strokes; scaled to Sobel's range so
+    /// `edge_threshold` means the same for both
+    Scharr,
+}
+
+impl EdgeOperator {
+    /// (corner weight, center weight, divisor to Sobel scale)
+    fn weights(self) -> (i32, i32, f64) {
+        match self {
+            EdgeOperator::Sobel => (1, 2, 1.0),
+            EdgeOperator::Scharr => (3, 10, 4.0),
+        }
+    }
+}
+
+/// Image values the edge map is computed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeSource {
+    /// Luminance (BT.601)
+    #[default]
+    Luminance,
+    /// Strongest gradient of the R, G and B channels; keeps colored-ink
+    /// strokes whose luminance is close to the highlighter's
+    MaxChannel,
+}
+
+/// Page area where markers are detected and removed
+#[derive(Debug, Clone, Copy, Default)]
+pub enum MarkerRegion {
+    /// Whole page
+    #[default]
+    Page,
+    /// Only inside the given rectangle
+    Within(ContentRect),
+    /// Only outside the content block detected on each page (margin
+    /// annotations); nothing is removed when no content is found
+    Margins,
+}
+
+/// Page area for marker removal selectable from the CLI, config file and
+/// web options (`--marker-region`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MarkerArea {
+    /// Whole page
+    #[default]
+    Page,
+    /// Only outside the detected content block
+    Margins,
+}
+
+impl MarkerArea {
+    /// Whether markers are removed from the whole page (the default)
+    pub fn is_page(&self) -> bool {
+        *self == MarkerArea::Page
+    }
+}
+
+impl From<MarkerArea> for MarkerRegion {
+    fn from(area: MarkerArea) -> Self {
+        match area {
+            MarkerArea::Page => MarkerRegion::Page,
+            MarkerArea::Margins => MarkerRegion::Margins,
+        }
+    }
+}
+
+/// Region resolved for one image
+#[derive(Debug, Clone, Copy)]
+enum RegionFilter {
+    All,
+    Inside(ContentRect),
+    Outside(ContentRect),
+}
+
+impl RegionFilter {
+    fn resolve(image: &RgbImage, region: MarkerRegion) -> Self {
+        match region {
+            MarkerRegion::Page => RegionFilter::All,
+            MarkerRegion::Within(rect) => RegionFilter::Inside(rect),
+            MarkerRegion::Margins => {
+                let gray = image::DynamicImage::ImageRgb8(image.clone()).to_luma8();
+                let (width, height) = image.dimensions();
+                let content = ContentAwareBoundaryDetector::detect_from_image(&gray, &ContentAwareOptions::default())
+                    .map(|b| {
+                        let rect = b.safe_content_rect();
+                        ContentRect {
+                            x: rect.x,
+                            y: rect.y,
+                            width: rect.width,
+                            height: rect.height,
+                        }
+                    })
+                    .ok()
+                    .filter(|rect| rect.width > 0 && rect.height > 0)
+                    // No content found: treat the whole page as content
+                    .unwrap_or(ContentRect {
+                        x: 0,
+                        y: 0,
+                        width,
+                        height,
+                    });
+                RegionFilter::Outside(content)
+            }
+        }
+    }
+
+    fn contains(&self, x: u32, y: u32) -> bool {
+        let inside = |r: &ContentRect| x >= r.x && x < r.x + r.width && y >= r.y && y < r.y + r.height;
+        match self {
+            RegionFilter::All => true,
+            RegionFilter::Inside(rect) => inside(rect),
+            RegionFilter::Outside(rect) => !inside(rect),
+        }
+    }
+}
+
 /// Options for marker removal
 #[derive(Debug, Clone)]
 pub struct MarkerRemovalOptions {
@@ -206,6 +409,15 @@ pub struct MarkerRemovalOptions {
 
     /// Edge detection threshold
     pub edge_threshold: u8,
+
+    /// Gradient operator for edge preservation
+    pub edge_operator: EdgeOperator,
+
+    /// Values edges are computed on
+    pub edge_source: EdgeSource,
+
+    /// Area where markers are detected and removed
+    pub region: MarkerRegion,
 }
 
 impl Default for MarkerRemovalOptions {
@@ -215,6 +427,9 @@ impl Default for MarkerRemovalOptions {
             strength: 1.0,
             preserve_text_edges: true,
             edge_threshold: EDGE_THRESHOLD,
+            edge_operator: EdgeOperator::Sobel,
+            edge_source: EdgeSource::Luminance,
+            region: MarkerRegion::Page,
         }
     }
 }
@@ -225,6 +440,21 @@ impl MarkerRemovalOptions {
         MarkerRemovalOptionsBuilder::default()
     }
 
+    /// Create options removing the named colors
+    ///
+    /// Names resolve through [`HighlighterColor::resolve`]; no names keeps
+    /// the built-in defaults. Unknown names are an error.
+    pub fn from_names(names: &[String], custom: &[CustomHighlighter]) -> Result<Self> {
+        let mut builder = Self::builder();
+        if !names.is_empty() {
+            builder = builder.colors(Vec::new());
+            for name in names {
+                builder = builder.add_color(HighlighterColor::resolve(name, custom)?);
+            }
+        }
+        Ok(builder.build())
+    }
+
     /// Create options for yellow markers only
     pub fn yellow_only() -> Self {
         Self {
@@ -233,6 +463,14 @@ impl MarkerRemovalOptions {
         }
     }
 
+    /// Create options that only clean margin annotations
+    pub fn margins_only() -> Self {
+        Self {
+            region: MarkerRegion::Margins,
+            ..Default::default()
+        }
+    }
+
     /// Create options for all markers with partial removal
     pub fn partial(strength: f32) -> Self {
         Self {
@@ -286,6 +524,27 @@ impl MarkerRemovalOptionsBuilder {
         self
     }
 
+    /// Set the gradient operator for edge preservation
+    #[must_use]
+    pub fn edge_operator(mut self, operator: EdgeOperator) -> Self {
+        self.options.edge_operator = operator;
+        self
+    }
+
+    /// Set the values edges are computed on
+    #[must_use]
+    pub fn edge_source(mut self, source: EdgeSource) -> Self {
+        self.options.edge_source = source;
+        self
+    }
+
+    /// Set the region markers are removed from
+    #[must_use]
+    pub fn region(mut self, region: MarkerRegion) -> Self {
+        self.options.region = region;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> MarkerRemovalOptions {
@@ -362,9 +621,14 @@ impl MarkerRemover {
             .collect();
 
         let mut total_marker_pixels = 0u32;
+        let region = RegionFilter::resolve(image, options.region);
 
         for y in 0..height {
             for x in 0..width {
+                if !region.contains(x, y) {
+                    continue;
+                }
+
                 let pixel = image.get_pixel(x, y);
                 let (h, s, v) = Self::rgb_to_hsv(pixel.0[0], pixel.0[1], pixel.0[2]);
 
@@ -418,7 +682,7 @@ impl MarkerRemover {
 
         // Compute edge map if preserving edges
         let edge_map = if options.preserve_text_edges {
-            Some(Self::compute_edge_map(image))
+            Some(Self::compute_edge_map_with(image, options.edge_operator, options.edge_source))
         } else {
             None
         };
@@ -430,9 +694,14 @@ impl MarkerRemover {
             .collect();
 
         let mut total_marker_pixels = 0u32;
+        let region = RegionFilter::resolve(image, options.region);
 
         for y in 0..height {
             for x in 0..width {
+                if !region.contains(x, y) {
+                    continue;
+                }
+
                 // Skip if on edge
                 if let Some(ref edges) = edge_map {
                     if edges.get_pixel(x, y).0[0] > options.edge_threshold {
@@ -471,37 +740,48 @@ impl MarkerRemover {
     }
 
     /// Compute Sobel edge map
-    fn compute_edge_map(image: &RgbImage) -> GrayImage {
+    pub fn compute_edge_map(image: &RgbImage) -> GrayImage {
+        Self::compute_edge_map_with(image, EdgeOperator::Sobel, EdgeSource::Luminance)
+    }
+
+    /// Compute an edge map with the given operator and source values
+    ///
+    /// With [`EdgeSource::MaxChannel`] each pixel takes the strongest
+    /// gradient of the three channels.
+    pub fn compute_edge_map_with(image: &RgbImage, operator: EdgeOperator, source: EdgeSource) -> GrayImage {
         let (width, height) = image.dimensions();
         let mut edges = GrayImage::new(width, height);
+        if width < 3 || height < 3 {
+            return edges;
+        }
 
-        // Convert to grayscale first
-        let gray: Vec<u8> = image
-            .pixels()
-            .map(|p| Self::luminance(p.0[0], p.0[1], p.0[2]))
-            .collect();
+        let planes: Vec<Vec<u8>> = match source {
+            EdgeSource::Luminance => vec![image
+                .pixels()
+                .map(|p| Self::luminance(p.0[0], p.0[1], p.0[2]))
+                .collect()],
+            EdgeSource::MaxChannel => (0..3)
+                .map(|c| image.pixels().map(|p| p.0[c]).collect())
+                .collect(),
+        };
+        let (corner, center, scale) = operator.weights();
 
         for y in 1..height - 1 {
             for x in 1..width - 1 {
                 let idx = |x: u32, y: u32| -> usize { (y * width + x) as usize };
 
-                // Sobel kernels
-                let gx = gray[idx(x + 1, y - 1)] as i32
-                    + 2 * gray[idx(x + 1, y)] as i32
-                    + gray[idx(x + 1, y + 1)] as i32
-                    - gray[idx(x - 1, y - 1)] as i32
-                    - 2 * gray[idx(x - 1, y)] as i32
-                    - gray[idx(x - 1, y + 1)] as i32;
-
-                let gy = gray[idx(x - 1, y + 1)] as i32
-                    + 2 * gray[idx(x, y + 1)] as i32
-                    + gray[idx(x + 1, y + 1)] as i32
-                    - gray[idx(x - 1, y - 1)] as i32
-                    - 2 * gray[idx(x, y - 1)] as i32
-                    - gray[idx(x + 1, y - 1)] as i32;
-
-                let magnitude = ((gx * gx + gy * gy) as f64).sqrt() as u8;
-                edges.put_pixel(x, y, Luma([magnitude]));
+                let magnitude = planes
+                    .iter()
+                    .map(|plane| {
+                        let v = |x: u32, y: u32| plane[idx(x, y)] as i32;
+                        let gx = corner * (v(x + 1, y - 1) + v(x + 1, y + 1) - v(x - 1, y - 1) - v(x - 1, y + 1))
+                            + center * (v(x + 1, y) - v(x - 1, y));
+                        let gy = corner * (v(x - 1, y + 1) + v(x + 1, y + 1) - v(x - 1, y - 1) - v(x + 1, y - 1))
+                            + center * (v(x, y + 1) - v(x, y - 1));
+                        ((gx * gx + gy * gy) as f64).sqrt() / scale
+                    })
+                    .fold(0.0, f64::max);
+                edges.put_pixel(x, y, Luma([magnitude as u8]));
             }
         }
 
@@ -575,6 +855,73 @@ mod tests {
         assert_eq!(opts.strength, 0.8);
         assert!(!opts.preserve_text_edges);
         assert_eq!(opts.edge_threshold, 100);
+        assert_eq!(opts.edge_operator, EdgeOperator::Sobel);
+        assert_eq!(opts.edge_source, EdgeSource::Luminance);
+
+        let opts = MarkerRemovalOptions::builder()
+            .edge_operator(EdgeOperator::Scharr)
+            .edge_source(EdgeSource::MaxChannel)
+            .build();
+        assert_eq!(opts.edge_operator, EdgeOperator::Scharr);
+        assert_eq!(opts.edge_source, EdgeSource::MaxChannel);
+    }
+
+    const MARK: Rgb<u8> = Rgb([255, 255, 120]);
+
+    /// White page with a block of black glyphs, a highlighter band inside
+    /// it (a printed heading) and a highlighter scribble in the left margin
+    fn page_with_marks() -> RgbImage {
+        RgbImage::from_fn(200, 200, |x, y| {
+            if (60..140).contains(&x) && (64..70).contains(&y) {
+                MARK
+            } else if (60..140).contains(&x) && (72..140).contains(&y) && x % 12 < 8 && y % 14 < 10 {
+                // Anti-aliased ink rather than flat black, as in a scan
+                let ink = ((x % 12) * 10) as u8;
+                Rgb([ink, ink, ink])
+            } else if (10..25).contains(&x) && (90..110).contains(&y) {
+                MARK
+            } else {
+                Rgb([255, 255, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn test_marker_region_margins_keeps_content() {
+        let options = MarkerRemovalOptions::builder()
+            .colors(vec![HighlighterColor::Yellow])
+            .preserve_text_edges(false)
+            .region(MarkerRegion::Margins)
+            .build();
+
+        let mut img = page_with_marks();
+        let result = MarkerRemover::remove_in_place(&mut img, &options).unwrap();
+
+        // Only the 15x20 margin scribble is removed
+        assert_eq!(result.total_marker_pixels, 15 * 20);
+        assert_eq!(*img.get_pixel(15, 100), Rgb([255, 255, 255]));
+        assert_eq!(*img.get_pixel(100, 66), MARK);
+
+        let page = MarkerRemover::detect_from_image(&page_with_marks(), &MarkerRemovalOptions::yellow_only()).unwrap();
+        assert_eq!(page.total_marker_pixels, 15 * 20 + 80 * 6);
+    }
+
+    #[test]
+    fn test_marker_region_within_rect() {
+        let rect = ContentRect {
+            x: 0,
+            y: 0,
+            width: 50,
+            height: 200,
+        };
+        let options = MarkerRemovalOptions::builder()
+            .colors(vec![HighlighterColor::Yellow])
+            .region(MarkerRegion::Within(rect))
+            .build();
+
+        let result = MarkerRemover::detect_from_image(&page_with_marks(), &options).unwrap();
+        assert_eq!(result.total_marker_pixels, 15 * 20);
+        assert_eq!(result.total_pixels, 200 * 200);
     }
 
     #[test]
@@ -776,4 +1123,135 @@ mod tests {
         // Should not match yellow
         assert!(!pink_range.matches(60.0, 0.5, 0.9));
     }
+
+    fn brand_highlighter() -> CustomHighlighter {
+        CustomHighlighter {
+            name: "mybrand".to_string(),
+            hue_min: 160,
+            hue_max: 180,
+            sat_min: 25,
+            sat_max: 90,
+            val_min: 60,
+            val_max: 100,
+        }
+    }
+
+    #[test]
+    fn test_resolve_builtin_and_custom_names() {
+        let custom = vec![brand_highlighter()];
+        assert_eq!(HighlighterColor::resolve("Yellow", &custom).unwrap(), HighlighterColor::Yellow);
+        assert_eq!(HighlighterColor::resolve(" mybrand ", &custom).unwrap(), custom[0].color());
+        assert!(matches!(
+            HighlighterColor::resolve("teal", &custom),
+            Err(CleanupError::UnknownHighlighter(name)) if name == "teal"
+        ));
+
+        // A config entry with a built-in name replaces the preset
+        let tuned = CustomHighlighter {
+            name: "yellow".to_string(),
+            ..brand_highlighter()
+        };
+        assert_eq!(
+            HighlighterColor::resolve("yellow", std::slice::from_ref(&tuned)).unwrap(),
+            tuned.color()
+        );
+    }
+
+    #[test]
+    fn test_options_from_names() {
+        let custom = vec![brand_highlighter()];
+        let names = vec!["yellow".to_string(), "mybrand".to_string(), "yellow".to_string()];
+        let opts = MarkerRemovalOptions::from_names(&names, &custom).unwrap();
+        assert_eq!(opts.colors, vec![HighlighterColor::Yellow, custom[0].color()]);
+
+        let opts = MarkerRemovalOptions::from_names(&[], &custom).unwrap();
+        assert_eq!(opts.colors, HighlighterColor::all());
+
+        assert!(MarkerRemovalOptions::from_names(&["teal".to_string()], &custom).is_err());
+    }
+
+    #[test]
+    fn test_custom_highlighter_removes_marker() {
+        // Teal-ish stroke only the brand definition matches
+        let mut img = RgbImage::from_pixel(40, 20, Rgb([255, 255, 255]));
+        for y in 5..15 {
+            for x in 5..35 {
+                img.put_pixel(x, y, Rgb([120, 230, 210]));
+            }
+        }
+        let builtin = MarkerRemovalOptions::default();
+        assert!(!MarkerRemover::detect_from_image(&img, &builtin).unwrap().has_markers());
+
+        let opts = MarkerRemovalOptions::from_names(&["mybrand".to_string()], &[brand_highlighter()]).unwrap();
+        let result = MarkerRemover::remove_in_place(&mut img, &opts).unwrap();
+        assert!(result.has_markers());
+        assert_eq!(*img.get_pixel(20, 10), Rgb([255, 255, 255]));
+    }
+
+    const HIGHLIGHT: Rgb<u8> = Rgb([255, 240, 90]);
+
+    /// Yellow highlight band crossed by a thin vertical stroke at x = 20..22
+    fn stroke_under_highlight(stroke: Rgb<u8>) -> RgbImage {
+        RgbImage::from_fn(40, 30, |x, y| {
+            if !(5..25).contains(&y) {
+                Rgb([255, 255, 255])
+            } else if (20..22).contains(&x) {
+                stroke
+            } else {
+                HIGHLIGHT
+            }
+        })
+    }
+
+    #[test]
+    fn test_thin_dark_stroke_survives_highlight_removal() {
+        // Dark ink seen through the highlighter, itself in the yellow HSV range
+        let stroke = Rgb([190, 175, 70]);
+        for operator in [EdgeOperator::Sobel, EdgeOperator::Scharr] {
+            let mut image = stroke_under_highlight(stroke);
+            let options = MarkerRemovalOptions::builder().edge_operator(operator).build();
+            MarkerRemover::remove_in_place(&mut image, &options).unwrap();
+            assert_eq!(*image.get_pixel(20, 15), stroke, "{:?}", operator);
+            assert_eq!(*image.get_pixel(21, 15), stroke, "{:?}", operator);
+            // The highlight away from the stroke is still removed
+            assert_eq!(*image.get_pixel(8, 15), Rgb([255, 255, 255]));
+        }
+
+        // Without edge preservation the stroke is eaten
+        let mut image = stroke_under_highlight(stroke);
+        let options = MarkerRemovalOptions::builder().preserve_text_edges(false).build();
+        MarkerRemover::remove_in_place(&mut image, &options).unwrap();
+        assert_eq!(*image.get_pixel(20, 15), Rgb([255, 255, 255]));
+    }
+
+    #[test]
+    fn test_max_channel_edges_keep_colored_ink() {
+        // Orange-red ink with almost the highlighter's luminance
+        let stroke = Rgb([250, 228, 60]);
+        let highlight = Rgb([225, 240, 90]);
+        let image = RgbImage::from_fn(40, 30, |x, _| if (20..22).contains(&x) { stroke } else { highlight });
+
+        let luma = MarkerRemover::compute_edge_map_with(&image, EdgeOperator::Sobel, EdgeSource::Luminance);
+        let max = MarkerRemover::compute_edge_map_with(&image, EdgeOperator::Sobel, EdgeSource::MaxChannel);
+        assert!(luma.get_pixel(20, 15).0[0] < EDGE_THRESHOLD);
+        assert!(max.get_pixel(20, 15).0[0] > EDGE_THRESHOLD);
+
+        let mut removed = image.clone();
+        let options = MarkerRemovalOptions::builder().edge_source(EdgeSource::MaxChannel).build();
+        MarkerRemover::remove_in_place(&mut removed, &options).unwrap();
+        assert_eq!(*removed.get_pixel(20, 15), stroke);
+    }
+
+    #[test]
+    fn test_scharr_matches_sobel_scale_on_straight_edges() {
+        let image = RgbImage::from_fn(20, 20, |x, _| if x < 10 { Rgb([0, 0, 0]) } else { Rgb([40, 40, 40]) });
+        let sobel = MarkerRemover::compute_edge_map_with(&image, EdgeOperator::Sobel, EdgeSource::Luminance);
+        let scharr = MarkerRemover::compute_edge_map_with(&image, EdgeOperator::Scharr, EdgeSource::Luminance);
+        assert_eq!(sobel.get_pixel(10, 10), scharr.get_pixel(10, 10));
+        assert_eq!(sobel, MarkerRemover::compute_edge_map(&image));
+
+        // Too small for a 3x3 kernel: no edges, no panic
+        let tiny = RgbImage::new(2, 2);
+        assert_eq!(MarkerRemover::compute_edge_map(&tiny).dimensions(), (2, 2));
+    }
 }
@@ -6,6 +6,7 @@
 //!
 //! - **Marker Removal** ([`marker_removal`]) - Remove highlighter marks and annotations
 //! - **Deblur** ([`deblur`]) - Correct focus blur using unsharp mask or AI
+//! - **Line Artifacts** ([`line_artifact`]) - Remove dust/hair streaks from the scanner platen
 //!
 //! # Issue Coverage
 //!
@@ -13,6 +14,7 @@
 //! - Issue #35: ピントボケ補正
 
 pub mod deblur;
+pub mod line_artifact;
 pub mod marker_removal;
 mod types;
 
@@ -21,9 +23,14 @@ pub use deblur::{
     BlurDetector, DeblurAlgorithm, DeblurOptions, DeblurOptionsBuilder, DeblurResult,
 };
 
+pub use line_artifact::{
+    LineArtifact, LineArtifactOptions, LineArtifactOptionsBuilder, LineArtifactRemover,
+    LineArtifactResult, LineOrientation,
+};
+
 pub use marker_removal::{
-    HighlighterColor, MarkerDetectionResult, MarkerRemovalOptions, MarkerRemovalOptionsBuilder,
-    MarkerRemover,
+    CustomHighlighter, EdgeOperator, EdgeSource, HighlighterColor, MarkerArea, MarkerDetectionResult, MarkerRegion, MarkerRemovalOptions,
+    MarkerRemovalOptionsBuilder, MarkerRemover,
 };
 
 pub use types::CleanupError;
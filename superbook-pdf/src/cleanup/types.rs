@@ -15,6 +15,9 @@ pub enum CleanupError {
     #[error("Processing failed: {0}")]
     ProcessingFailed(String),
 
+    #[error("Unknown highlighter color: {0} (built-in: yellow, pink, green, blue, orange; or a [[highlighter]] name)")]
+    UnknownHighlighter(String),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
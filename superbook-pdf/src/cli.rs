@@ -6,6 +6,9 @@ use clap::{Args, Parser, Subcommand};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::path::PathBuf;
 
+/// Default `--marker-colors`
+pub const DEFAULT_MARKER_COLORS: &str = "yellow,pink,green,blue";
+
 /// Exit codes for the CLI
 ///
 /// These codes follow standard Unix conventions and provide
@@ -66,6 +69,148 @@ impl From<ExitCode> for std::process::ExitCode {
     }
 }
 
+/// CLI command error
+///
+/// Each variant maps to a distinct [`ExitCode`] so scripts can tell
+/// failure modes apart (e.g. "no input found" vs "processing failed").
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// Input path does not exist
+    #[error("Input path does not exist: {0}")]
+    InputNotFound(PathBuf),
+
+    /// Input path contains no processable files
+    #[error("No PDF files found in input path")]
+    NoInputFiles,
+
+    /// Input file is empty, has no PDF header or is truncated
+    #[error("Not a valid PDF: {}: {reason}", path.display())]
+    InvalidPdf {
+        /// The rejected file
+        path: PathBuf,
+        /// What the check found
+        reason: crate::InvalidPdf,
+    },
+
+    /// Invalid argument combination or value
+    #[error("Invalid arguments: {0}")]
+    InvalidArgs(String),
+
+    /// Output could not be written (permission denied, disk full, etc.)
+    #[error("Output error: {0}")]
+    Output(#[from] std::io::Error),
+
+    /// Pipeline failure for a single file
+    #[error(transparent)]
+    Pipeline(#[from] crate::pipeline::PipelineError),
+
+    /// One or more files in a batch failed; carries the first failure's exit code
+    #[error("{failed} file(s) failed to process")]
+    BatchFailed {
+        /// Number of failed files
+        failed: usize,
+        /// Exit code of the first failure
+        code: ExitCode,
+    },
+
+    /// Reprocess state error
+    #[error(transparent)]
+    Reprocess(#[from] crate::reprocess::ReprocessError),
+
+    /// Markdown conversion error
+    #[error(transparent)]
+    Markdown(#[from] crate::markdown::MarkdownError),
+
+    /// Option comparison error
+    #[error(transparent)]
+    Compare(#[from] crate::compare::CompareError),
+
+    /// Self test error
+    #[error(transparent)]
+    Selftest(#[from] crate::selftest::SelftestError),
+
+    /// PDF comparison error or differences found
+    #[error(transparent)]
+    Diff(#[from] crate::pdf_diff::DiffError),
+
+    /// External tool failure
+    #[error("External tool error: {0}")]
+    ExternalTool(String),
+
+    /// Any other error
+    #[error("{0}")]
+    Other(String),
+}
+
+impl CliError {
+    /// Get the exit code for this error
+    #[must_use]
+    pub fn exit_code(&self) -> ExitCode {
+        use crate::compare::CompareError;
+        use crate::pdf_diff::DiffError;
+        use crate::pipeline::PipelineError;
+        use crate::selftest::SelftestError;
+
+        match self {
+            CliError::InputNotFound(_) | CliError::NoInputFiles => ExitCode::InputNotFound,
+            CliError::InvalidArgs(_) | CliError::InvalidPdf { .. } => ExitCode::InvalidArgs,
+            CliError::Output(_) => ExitCode::OutputError,
+            CliError::Pipeline(e) => match e {
+                PipelineError::InputNotFound(_) => ExitCode::InputNotFound,
+                PipelineError::OutputNotWritable(_) | PipelineError::Output(_) | PipelineError::Io(_) => {
+                    ExitCode::OutputError
+                }
+                PipelineError::ExtractionFailed(_) => ExitCode::ExternalToolError,
+                PipelineError::ImageProcessingFailed(_)
+                | PipelineError::PdfGenerationFailed(_) => ExitCode::ProcessingError,
+                PipelineError::InvalidConfig(_)
+                | PipelineError::InvalidMetadata(..)
+                | PipelineError::PageManifest(_) => ExitCode::InvalidArgs,
+                PipelineError::OcrUnavailable { .. } | PipelineError::HookFailed(_) => {
+                    ExitCode::ExternalToolError
+                }
+            },
+            CliError::BatchFailed { code, .. } => *code,
+            CliError::Reprocess(_) | CliError::Markdown(_) => ExitCode::ProcessingError,
+            CliError::Compare(e) => match e {
+                CompareError::InvalidProfile(..) | CompareError::NoProfiles => {
+                    ExitCode::InvalidArgs
+                }
+                CompareError::IoError(_) => ExitCode::OutputError,
+                CompareError::ProfileFailed(..) | CompareError::ImageError(_) => {
+                    ExitCode::ProcessingError
+                }
+            },
+            CliError::Selftest(e) => match e {
+                SelftestError::IoError(_) => ExitCode::OutputError,
+                SelftestError::TestPdf(_) | SelftestError::Failed(_) => ExitCode::ProcessingError,
+            },
+            CliError::Diff(e) => match e {
+                DiffError::PdfNotFound(_) => ExitCode::InputNotFound,
+                DiffError::IoError(_) => ExitCode::OutputError,
+                DiffError::Render(..) | DiffError::ImageError(_) | DiffError::Quality(_) => {
+                    ExitCode::ProcessingError
+                }
+                DiffError::Differs { .. } => ExitCode::GeneralError,
+            },
+            CliError::ExternalTool(_) => ExitCode::ExternalToolError,
+            CliError::Other(_) => ExitCode::GeneralError,
+        }
+    }
+}
+
+impl From<String> for CliError {
+    fn from(msg: String) -> Self {
+        CliError::Other(msg)
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(msg: &str) -> Self {
+        CliError::Other(msg.to_string())
+    }
+}
+
 /// High-quality PDF converter for scanned books
 #[derive(Parser, Debug)]
 #[command(name = "superbook-pdf")]
@@ -87,6 +232,7 @@ pub struct Cli {
 
 /// Available commands
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)] // parsed once per process
 pub enum Commands {
     /// Convert PDF files with AI enhancement
     Convert(ConvertArgs),
@@ -96,8 +242,21 @@ pub enum Commands {
     Markdown(MarkdownArgs),
     /// Show system information
     Info,
+    /// List pipeline stages, whether they run by default and whether their tools are installed
+    #[command(visible_alias = "list-stages")]
+    Stages,
     /// Show cache information for a processed file
     CacheInfo(CacheInfoArgs),
+    /// Convert the source of a previous output again with modified options
+    Rerun(RerunArgs),
+    /// Render one page under several option profiles and compare them in a grid
+    CompareOptions(CompareOptionsArgs),
+    /// Build a PDF from already-processed page images (e.g. a --save-debug work directory)
+    Assemble(AssembleArgs),
+    /// Convert a generated test book and report which stages and tools work
+    Selftest(SelftestArgs),
+    /// Compare two PDFs page by page and report which pages changed
+    Diff(DiffArgs),
     /// Start web server for browser-based conversion
     #[cfg(feature = "web")]
     Serve(ServeArgs),
@@ -109,6 +268,208 @@ pub struct CacheInfoArgs {
     /// Path to the output PDF file (to show cache info)
     #[arg(value_name = "OUTPUT_PDF")]
     pub output_pdf: std::path::PathBuf,
+
+    /// Print the cache (version, processed_at, digest, result) as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for the selftest command
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+Examples:
+  # 既定の設定で合成テストPDFを変換し、ステージごとの結果を表示
+  superbook-pdf selftest
+
+  # 設定ファイルの内容で試し、作業ファイルを残す
+  superbook-pdf selftest -c superbook.toml --work-dir selftest/
+"#)]
+pub struct SelftestArgs {
+    /// Number of pages in the test book
+    #[arg(long, default_value_t = crate::selftest::DEFAULT_PAGES as u32, value_parser = clap::value_parser!(u32).range(1..=64))]
+    pub pages: u32,
+
+    /// Keep the test PDF and its output in this directory (default: a removed temporary directory)
+    #[arg(long, value_name = "DIR")]
+    pub work_dir: Option<PathBuf>,
+
+    /// Configuration file to test instead of the defaults (TOML format)
+    #[arg(short = 'c', long)]
+    pub config: Option<PathBuf>,
+
+    /// Verbose output (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+}
+
+/// Arguments for the diff command
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+Examples:
+  # オプション変更前後の出力を比較
+  superbook-pdf diff before/book_converted.pdf after/book_converted.pdf
+
+  # 差分の大きいページのヒートマップを保存し、結果を JSON で出力
+  superbook-pdf diff a.pdf b.pdf --threshold 1.0 --heatmap-dir diff/ --json
+"#)]
+pub struct DiffArgs {
+    /// First PDF (reference)
+    pub a: PathBuf,
+
+    /// Second PDF
+    pub b: PathBuf,
+
+    /// DPI both PDFs are rendered at
+    #[arg(long, default_value_t = crate::pdf_diff::DEFAULT_DIFF_DPI, value_parser = clap::value_parser!(u32).range(72..=600))]
+    pub dpi: u32,
+
+    /// Mean absolute difference (gray levels, 0-255) above which a page counts as changed
+    #[arg(long, default_value_t = crate::pdf_diff::DEFAULT_DIFF_THRESHOLD)]
+    pub threshold: f64,
+
+    /// Write a difference heatmap for each changed page into this directory
+    #[arg(long, value_name = "DIR")]
+    pub heatmap_dir: Option<PathBuf>,
+
+    /// Print the report as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for the rerun command
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+Examples:
+  # 前回と同じ入力・オプションに OCR を追加して再変換
+  superbook-pdf rerun output/book_converted.pdf --ocr
+
+  # オプションを変更して別ディレクトリへ出力
+  superbook-pdf rerun output/book_converted.pdf --set dpi=450 --set remove_markers=true -o output2/
+"#)]
+pub struct RerunArgs {
+    /// Output of a previous convert run (its cache file records the source and options)
+    #[arg(value_name = "OUTPUT_PDF")]
+    pub output_pdf: PathBuf,
+
+    /// Change an option as KEY=VALUE (PipelineConfig field name, repeatable)
+    #[arg(long = "set", value_name = "KEY=VALUE")]
+    pub set: Vec<String>,
+
+    /// Enable OCR (same as --set ocr=true)
+    #[arg(long)]
+    pub ocr: bool,
+
+    /// Output directory (default: directory of OUTPUT_PDF)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Configuration file for settings the cache does not record (tool paths, hooks, TOML format)
+    #[arg(short = 'c', long)]
+    pub config: Option<PathBuf>,
+
+    /// Verbose output (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Suppress the summary
+    #[arg(short, long)]
+    pub quiet: bool,
+}
+
+/// Arguments for the compare-options command
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+Examples:
+  # DPI 300 と 450 を比較
+  superbook-pdf compare-options input.pdf --page 5 --profile dpi300:dpi=300 --profile dpi450:dpi=450
+
+  # 超解像・色補正の有無を比較
+  superbook-pdf compare-options input.pdf -p 12 -o compare.png \
+    --profile base --profile no-upscale:upscale=false --profile no-color:color_correction=false
+"#)]
+pub struct CompareOptionsArgs {
+    /// Input PDF file
+    pub input: PathBuf,
+
+    /// Page to render (1-based)
+    #[arg(short = 'p', long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub page: u32,
+
+    /// Option profile as NAME[:KEY=VALUE,...] (repeatable)
+    #[arg(long = "profile", required = true)]
+    pub profiles: Vec<String>,
+
+    /// Output grid image (PNG)
+    #[arg(short = 'o', long, default_value = "compare.png")]
+    pub output: PathBuf,
+
+    /// Configuration file providing the base options (TOML format)
+    #[arg(short = 'c', long)]
+    pub config: Option<PathBuf>,
+
+    /// Height of each rendered page in the grid (pixels)
+    #[arg(long, default_value_t = crate::compare::DEFAULT_CELL_HEIGHT)]
+    pub cell_height: u32,
+
+    /// Number of grid columns (0 = auto)
+    #[arg(long, default_value_t = 0)]
+    pub columns: usize,
+
+    /// Keep per-profile intermediate images in this directory
+    #[arg(long)]
+    pub work_dir: Option<PathBuf>,
+
+    /// Verbose output (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+}
+
+/// Arguments for the assemble command
+#[derive(Args, Debug)]
+#[command(after_help = r#"
+Examples:
+  # --save-debug で残した作業ディレクトリから PDF だけを再生成
+  superbook-pdf assemble output/.work_book -o output/book.pdf --jpeg-quality 80
+
+  # 任意の画像ディレクトリ (ファイル名順) から生成
+  superbook-pdf assemble pages/ -o book.pdf --dpi 400 --mixed-color
+"#)]
+pub struct AssembleArgs {
+    /// Directory of page images (with manifest.json from --output-format images),
+    /// or a work directory kept with --save-debug
+    pub input: PathBuf,
+
+    /// Output PDF file
+    #[arg(short = 'o', long)]
+    pub output: PathBuf,
+
+    /// Directory of returned hOCR files named after the pages (page_0001.hocr; default: input directory)
+    #[arg(long, value_name = "DIR")]
+    pub ocr_dir: Option<PathBuf>,
+
+    /// Configuration file providing PDF options and metadata (TOML format)
+    #[arg(short = 'c', long)]
+    pub config: Option<PathBuf>,
+
+    /// Output DPI (1-4800, default: config file or 300)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=4800))]
+    pub dpi: Option<u32>,
+
+    /// JPEG quality for PDF image compression (1-100, default: config file or 90)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub jpeg_quality: Option<u8>,
+
+    /// Keep colored pages in color and write the rest as grayscale
+    #[arg(long)]
+    pub mixed_color: bool,
+
+    /// Produce byte-identical output for identical input
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Verbose output (-v, -vv, -vvv)
+    #[arg(short, long, action = clap::ArgAction::Count)]
+    pub verbose: u8,
 }
 
 /// Shadow removal mode
@@ -139,6 +500,334 @@ pub enum DeblurAlgorithmCli {
     DeblurganV2,
 }
 
+/// Auto contrast mode for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum AutoContrastCli {
+    /// No contrast enhancement
+    #[default]
+    Off,
+    /// Linear stretch between luminance percentiles (see --contrast-percentiles)
+    Percentile,
+    /// Adaptive histogram equalization (uneven lighting)
+    Clahe,
+}
+
+/// Page order (binding direction) for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum PageOrderCli {
+    /// Right-to-left when vertical text is detected
+    #[default]
+    Auto,
+    /// Left-to-right (western binding)
+    Ltr,
+    /// Right-to-left (Japanese binding)
+    Rtl,
+}
+
+impl From<PageOrderCli> for crate::Binding {
+    fn from(order: PageOrderCli) -> Self {
+        match order {
+            PageOrderCli::Auto => crate::Binding::Auto,
+            PageOrderCli::Ltr => crate::Binding::LeftToRight,
+            PageOrderCli::Rtl => crate::Binding::RightToLeft,
+        }
+    }
+}
+
+/// Resize filter for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ResizeFilterCli {
+    /// Fastest; hard pixel edges
+    Nearest,
+    /// Bilinear; fast and soft
+    Triangle,
+    /// Bicubic; sharp with little ringing (line art)
+    CatmullRom,
+    /// Smooth, no ringing (photos)
+    Gaussian,
+    /// Sharpest, slowest; may ring around hard edges
+    #[default]
+    Lanczos3,
+}
+
+impl From<ResizeFilterCli> for crate::Resampler {
+    fn from(filter: ResizeFilterCli) -> Self {
+        match filter {
+            ResizeFilterCli::Nearest => crate::Resampler::Nearest,
+            ResizeFilterCli::Triangle => crate::Resampler::Bilinear,
+            ResizeFilterCli::CatmullRom => crate::Resampler::Bicubic,
+            ResizeFilterCli::Gaussian => crate::Resampler::Gaussian,
+            ResizeFilterCli::Lanczos3 => crate::Resampler::Lanczos3,
+        }
+    }
+}
+
+/// Bilevel page encoding for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ImageEncodingCli {
+    /// Same as other pages (8-bit)
+    #[default]
+    Standard,
+    /// 1-bit Flate
+    Flate,
+    /// 1-bit JBIG2 via jbig2enc (falls back to Flate if unavailable)
+    Jbig2,
+}
+
+impl From<ImageEncodingCli> for crate::ImageEncoding {
+    fn from(encoding: ImageEncodingCli) -> Self {
+        match encoding {
+            ImageEncodingCli::Standard => crate::ImageEncoding::Standard,
+            ImageEncodingCli::Flate => crate::ImageEncoding::Flate,
+            ImageEncodingCli::Jbig2 => crate::ImageEncoding::Jbig2,
+        }
+    }
+}
+
+/// Tone curve interpolation for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ToneInterpolationCli {
+    /// Straight segments between points
+    Linear,
+    /// Smooth monotone cubic (no overshoot)
+    Monotone,
+}
+
+impl From<ToneInterpolationCli> for crate::ToneInterpolation {
+    fn from(interpolation: ToneInterpolationCli) -> Self {
+        match interpolation {
+            ToneInterpolationCli::Linear => crate::ToneInterpolation::Linear,
+            ToneInterpolationCli::Monotone => crate::ToneInterpolation::Monotone,
+        }
+    }
+}
+
+/// PDF/A conformance level for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum PdfaCli {
+    /// PDF/A-1b (raised to 2b when an OCR text layer is written)
+    #[value(name = "1b")]
+    A1b,
+    /// PDF/A-2b
+    #[value(name = "2b")]
+    A2b,
+}
+
+impl From<PdfaCli> for crate::PdfaLevel {
+    fn from(level: PdfaCli) -> Self {
+        match level {
+            PdfaCli::A1b => crate::PdfaLevel::A1b,
+            PdfaCli::A2b => crate::PdfaLevel::A2b,
+        }
+    }
+}
+
+/// Output container for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum BookFormatCli {
+    /// PDF
+    Pdf,
+    /// CBZ comic archive
+    Cbz,
+    /// Page images with a manifest, for external OCR (assemble with `assemble`)
+    Images,
+}
+
+impl From<BookFormatCli> for crate::BookFormat {
+    fn from(format: BookFormatCli) -> Self {
+        match format {
+            BookFormatCli::Pdf => crate::BookFormat::Pdf,
+            BookFormatCli::Cbz => crate::BookFormat::Cbz,
+            BookFormatCli::Images => crate::BookFormat::Images,
+        }
+    }
+}
+
+/// Running header/footer handling for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum RunningHeadModeCli {
+    /// Keep them
+    #[default]
+    Keep,
+    /// Leave them out of the OCR text layer and hOCR / ALTO export
+    Exclude,
+    /// Also erase them from the page images
+    Erase,
+}
+
+impl From<RunningHeadModeCli> for crate::markdown::RunningHeadMode {
+    fn from(mode: RunningHeadModeCli) -> Self {
+        match mode {
+            RunningHeadModeCli::Keep => crate::markdown::RunningHeadMode::Keep,
+            RunningHeadModeCli::Exclude => crate::markdown::RunningHeadMode::Exclude,
+            RunningHeadModeCli::Erase => crate::markdown::RunningHeadMode::Erase,
+        }
+    }
+}
+
+/// Page parity selection for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum PageParityCli {
+    /// Every page
+    #[default]
+    All,
+    /// Odd pages only
+    Odd,
+    /// Even pages only
+    Even,
+}
+
+impl From<PageParityCli> for crate::PageParity {
+    fn from(parity: PageParityCli) -> Self {
+        match parity {
+            PageParityCli::All => crate::PageParity::All,
+            PageParityCli::Odd => crate::PageParity::Odd,
+            PageParityCli::Even => crate::PageParity::Even,
+        }
+    }
+}
+
+/// Negative scan inversion for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum InvertCli {
+    /// Never invert
+    #[default]
+    Off,
+    /// Invert pages detected as negatives (dark, high-variance pages)
+    Auto,
+    /// Invert every page
+    Force,
+}
+
+impl From<InvertCli> for crate::Invert {
+    fn from(invert: InvertCli) -> Self {
+        match invert {
+            InvertCli::Off => crate::Invert::Off,
+            InvertCli::Auto => crate::Invert::Auto,
+            InvertCli::Force => crate::Invert::Force,
+        }
+    }
+}
+
+/// Marker removal area for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MarkerAreaCli {
+    /// Whole page
+    #[default]
+    Page,
+    /// Only outside the detected text block (margin annotations)
+    Margins,
+}
+
+impl From<MarkerAreaCli> for crate::cleanup::MarkerArea {
+    fn from(area: MarkerAreaCli) -> Self {
+        match area {
+            MarkerAreaCli::Page => crate::cleanup::MarkerArea::Page,
+            MarkerAreaCli::Margins => crate::cleanup::MarkerArea::Margins,
+        }
+    }
+}
+
+/// Failed page handling for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum PageErrorPolicyCli {
+    /// Fail the whole file
+    Abort,
+    /// Leave the page out of the output
+    #[default]
+    Skip,
+    /// Insert a "page N unavailable" page in its place
+    Placeholder,
+}
+
+impl From<PageErrorPolicyCli> for crate::PageErrorPolicy {
+    fn from(policy: PageErrorPolicyCli) -> Self {
+        match policy {
+            PageErrorPolicyCli::Abort => crate::PageErrorPolicy::Abort,
+            PageErrorPolicyCli::Skip => crate::PageErrorPolicy::Skip,
+            PageErrorPolicyCli::Placeholder => crate::PageErrorPolicy::Placeholder,
+        }
+    }
+}
+
+/// Low output DPI handling for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum LowDpiPolicyCli {
+    /// Write the output and warn
+    #[default]
+    Warn,
+    /// Fail the file before writing the output
+    Refuse,
+}
+
+impl From<LowDpiPolicyCli> for crate::LowDpiPolicy {
+    fn from(policy: LowDpiPolicyCli) -> Self {
+        match policy {
+            LowDpiPolicyCli::Warn => crate::LowDpiPolicy::Warn,
+            LowDpiPolicyCli::Refuse => crate::LowDpiPolicy::Refuse,
+        }
+    }
+}
+
+/// Color policy for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorPolicyCli {
+    /// Whole pages in color (or grayscale with --mixed-color)
+    #[default]
+    PerPage,
+    /// Grayscale pages with detected figures kept in color (requires --ocr)
+    FiguresOnly,
+}
+
+impl From<ColorPolicyCli> for crate::ColorPolicy {
+    fn from(policy: ColorPolicyCli) -> Self {
+        match policy {
+            ColorPolicyCli::PerPage => crate::ColorPolicy::PerPage,
+            ColorPolicyCli::FiguresOnly => crate::ColorPolicy::FiguresOnly,
+        }
+    }
+}
+
+/// Mixed page size handling for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum MixedSizeCli {
+    /// Size every page like the others
+    #[default]
+    Uniform,
+    /// Crop minority-orientation pages separately and fit them into the page turned by 90°
+    PerOrientation,
+    /// Leave minority-orientation pages uncropped at their relative size
+    Preserve,
+}
+
+impl From<MixedSizeCli> for crate::MixedSize {
+    fn from(mixed_size: MixedSizeCli) -> Self {
+        match mixed_size {
+            MixedSizeCli::Uniform => crate::MixedSize::Uniform,
+            MixedSizeCli::PerOrientation => crate::MixedSize::PerOrientation,
+            MixedSizeCli::Preserve => crate::MixedSize::Preserve,
+        }
+    }
+}
+
+/// OCR export format for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OcrFormatCli {
+    /// hOCR (XHTML)
+    Hocr,
+    /// ALTO v4 XML
+    Alto,
+}
+
+impl From<OcrFormatCli> for crate::markdown::OcrExportFormat {
+    fn from(format: OcrFormatCli) -> Self {
+        match format {
+            OcrFormatCli::Hocr => crate::markdown::OcrExportFormat::Hocr,
+            OcrFormatCli::Alto => crate::markdown::OcrExportFormat::Alto,
+        }
+    }
+}
+
 /// Text direction option for CLI
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
 pub enum TextDirectionCli {
@@ -151,6 +840,42 @@ pub enum TextDirectionCli {
     Vertical,
 }
 
+impl From<TextDirectionCli> for crate::markdown::TextDirectionOption {
+    fn from(direction: TextDirectionCli) -> Self {
+        match direction {
+            TextDirectionCli::Auto => crate::markdown::TextDirectionOption::Auto,
+            TextDirectionCli::Horizontal => crate::markdown::TextDirectionOption::Horizontal,
+            TextDirectionCli::Vertical => crate::markdown::TextDirectionOption::Vertical,
+        }
+    }
+}
+
+/// Message language for CLI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum LangCli {
+    /// English
+    En,
+    /// Japanese (日本語)
+    Ja,
+}
+
+impl From<LangCli> for crate::i18n::Lang {
+    fn from(lang: LangCli) -> Self {
+        match lang {
+            LangCli::En => crate::i18n::Lang::En,
+            LangCli::Ja => crate::i18n::Lang::Ja,
+        }
+    }
+}
+
+/// Format of `--print-config`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ConfigFormatCli {
+    #[default]
+    Toml,
+    Json,
+}
+
 /// Validation provider for CLI
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
 pub enum ValidationProviderCli {
@@ -219,7 +944,19 @@ pub struct MarkdownArgs {
     #[arg(long)]
     pub generate_metadata: bool,
 
-    /// Enable validation of output Markdown
+    /// Mark text blocks below this OCR confidence (percent) with a review comment
+    #[arg(long, value_name = "PERCENT")]
+    pub mark_low_confidence: Option<f32>,
+
+    /// Leave running headers/footers (repeated chapter titles, page numbers) out of the Markdown
+    #[arg(long)]
+    pub exclude_running_heads: bool,
+
+    /// Join words hyphenated at line ends ("associ-" / "ation"), keeping compounds such as "well-known"
+    #[arg(long)]
+    pub dehyphenate: bool,
+
+    /// Enable validation of output Markdown
     #[arg(long)]
     pub validate: bool,
 
@@ -333,6 +1070,41 @@ pub struct ServeArgs {
     /// Disable CORS
     #[arg(long)]
     pub no_cors: bool,
+
+    /// Days to keep finished jobs and their files (0 = keep forever)
+    #[arg(long, default_value = "30")]
+    pub retention_days: u32,
+
+    /// Pending jobs above which /readyz returns 503 so load balancers shed load
+    #[arg(long, default_value_t = crate::web::DEFAULT_MAX_QUEUE_DEPTH)]
+    pub max_queue_depth: usize,
+
+    /// Run GPU stages (upscale, OCR) of jobs one at a time, also with other superbook-pdf processes
+    #[arg(long)]
+    pub gpu_wait: bool,
+}
+
+/// How existing outputs are treated when converting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReprocessPolicy {
+    /// Re-process only when the source file or options changed (cache digest)
+    #[default]
+    IfChanged,
+    /// Skip whenever the output file exists
+    SkipExisting,
+    /// Always re-process
+    Force,
+}
+
+impl ReprocessPolicy {
+    /// Short name used in plans and logs
+    pub fn name(self) -> &'static str {
+        match self {
+            ReprocessPolicy::IfChanged => "if-changed",
+            ReprocessPolicy::SkipExisting => "skip-existing",
+            ReprocessPolicy::Force => "force",
+        }
+    }
 }
 
 /// Arguments for the convert command
@@ -355,10 +1127,10 @@ Examples:
   superbook-pdf convert input.pdf -o output/ -vvv
 "#)]
 pub struct ConvertArgs {
-    /// Input PDF file or directory
+    /// Input PDF file, CBZ/CBR comic archive, or directory
     pub input: PathBuf,
 
-    /// Output directory
+    /// Output directory, or s3://bucket/prefix when built with the `s3` feature
     #[arg(short = 'o', long = "output", default_value = "./output")]
     pub output: PathBuf,
 
@@ -366,10 +1138,59 @@ pub struct ConvertArgs {
     #[arg(short = 'c', long)]
     pub config: Option<PathBuf>,
 
+    /// Also process CBZ/CBR comic archives found in an input directory
+    #[arg(long)]
+    pub include_archives: bool,
+
     /// Enable Japanese OCR (YomiToku)
     #[arg(long)]
     pub ocr: bool,
 
+    /// Continue without OCR if YomiToku is missing
+    #[arg(long)]
+    pub ocr_optional: bool,
+
+    /// Reuse the text layer of PDFs that already have one instead of running OCR
+    #[arg(long)]
+    pub skip_existing_ocr: bool,
+
+    /// Flag pages whose mean OCR confidence is below this percentage
+    #[arg(long, default_value_t = crate::pipeline::DEFAULT_OCR_MIN_CONFIDENCE, value_name = "PERCENT")]
+    pub ocr_min_confidence: f32,
+
+    /// Page order / binding direction hint for PDF viewers
+    #[arg(long, value_enum, default_value = "auto")]
+    pub page_order: PageOrderCli,
+
+    /// Force vertical or horizontal text when auto-detection gets the book wrong
+    /// (page number search, binding, reading order)
+    #[arg(long, value_enum, default_value = "auto")]
+    pub text_direction: TextDirectionCli,
+
+    /// Color transparent page pixels are composited over (#RRGGBB or R,G,B)
+    #[arg(long, value_name = "COLOR", default_value = "#ffffff", value_parser = crate::util::parse_rgb_color)]
+    pub flatten_background: [u8; 3],
+
+    /// Interpolation filter for internal normalization and output resizing
+    #[arg(long, value_enum, default_value = "lanczos3")]
+    pub resize_filter: ResizeFilterCli,
+
+    /// Report SSIM/PSNR between each extracted page and its final image
+    #[arg(long)]
+    pub quality_metrics: bool,
+
+    /// Run this executable on the finished PDF (gets the path and a JSON manifest on stdin)
+    #[arg(long, value_name = "PATH")]
+    pub post_hook: Option<String>,
+
+    /// Run this executable on each final page image (gets the image path and page number)
+    #[arg(long, value_name = "PATH")]
+    pub page_hook: Option<String>,
+
+    /// Also write OCR layout as hOCR and/or ALTO XML (requires OCR, e.g. hocr,alto)
+    #[arg(long = "format", value_enum, value_delimiter = ',')]
+    pub ocr_formats: Vec<OcrFormatCli>,
+
     /// Enable AI upscaling (RealESRGAN)
     #[arg(short, long, default_value_t = true)]
     #[arg(action = clap::ArgAction::Set)]
@@ -402,14 +1223,127 @@ pub struct ConvertArgs {
     #[arg(long, default_value_t = 90, value_parser = clap::value_parser!(u8).range(1..=100))]
     pub jpeg_quality: u8,
 
+    /// Encoding of pure black-and-white pages (jbig2 = smallest, needs jbig2enc)
+    #[arg(long, value_enum, default_value = "standard")]
+    pub image_encoding: ImageEncodingCli,
+
+    /// Write archival PDF/A output (embedded sRGB profile and XMP metadata)
+    #[arg(long, value_enum, value_name = "LEVEL")]
+    pub pdfa: Option<PdfaCli>,
+
+    /// Check PDF/A output with veraPDF (or Ghostscript) and warn on problems
+    #[arg(long, requires = "pdfa")]
+    pub pdfa_validate: bool,
+
+    /// Output container (cbz = final page images with ComicInfo.xml, no text layer;
+    /// images = page image directory with manifest.json for external OCR)
+    #[arg(long, value_enum, default_value = "pdf")]
+    pub output_format: BookFormatCli,
+
+    /// Write PDF page labels from detected page numbers (roman front matter, then 1, 2, ...; needs tesseract)
+    #[arg(long, alias = "page-label")]
+    pub page_labels: bool,
+
+    /// Running headers/footers (repeated chapter titles, page numbers) found in the OCR text:
+    /// keep, exclude from the text layer, or also erase from the page images (needs OCR)
+    #[arg(long, value_enum, default_value = "keep")]
+    pub running_heads: RunningHeadModeCli,
+
+    /// Process only odd or even pages (for sides scanned in separate passes);
+    /// the output name gets an _odd / _even suffix
+    #[arg(long, value_enum, default_value = "all")]
+    pub parity: PageParityCli,
+
     /// Number of parallel threads
     #[arg(short = 't', long)]
     pub threads: Option<usize>,
 
+    /// Niceness of spawned CPU tools (pdftoppm, ImageMagick, tesseract, ...) on Unix
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true, value_parser = clap::value_parser!(i32).range(-20..=19))]
+    pub nice: i32,
+
+    /// Run GPU stages (upscale, OCR) one at a time across concurrent conversions
+    #[arg(long)]
+    pub gpu_wait: bool,
+
+    /// Kill an external tool call (pdftoppm, ImageMagick, tesseract, ...) after SECS and retry once; a page that times out again is handled per --on-page-error
+    #[arg(long, value_name = "SECS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub tool_timeout: Option<u64>,
+
+    /// Warn when a stage reports no progress for SECS (possible hang; nothing is stopped, 0 = off)
+    #[arg(long, value_name = "SECS", default_value_t = crate::DEFAULT_STALL_WARNING_SECS)]
+    pub stall_warning: u64,
+
+    /// Tesseract calls run at once during page number detection (default: one per thread)
+    #[arg(long, value_name = "N", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pub ocr_concurrency: Option<usize>,
+
+    /// What to do with a page that fails: abort the file, skip the page, or insert a placeholder page
+    #[arg(long, value_enum, default_value = "skip")]
+    pub on_page_error: PageErrorPolicyCli,
+
+    /// Share of --threads (0.0-1.0) reserved for external tools (ImageMagick, tesseract, ...); the rest sizes the internal thread pool
+    #[arg(long, value_name = "FRACTION", value_parser = crate::parallel::parse_thread_fraction)]
+    pub external_thread_fraction: Option<f32>,
+
     /// Chunk size for memory-controlled parallel processing (0 = process all at once)
     #[arg(long, default_value_t = 0)]
     pub chunk_size: usize,
 
+    /// Pages extracted ahead of processing (0 = extract all pages before processing)
+    #[arg(long, default_value_t = crate::pipeline::DEFAULT_EXTRACT_QUEUE_DEPTH)]
+    pub extract_queue_depth: usize,
+
+    /// Pages rendered per pdftoppm process (1 = one process per page)
+    #[arg(long, default_value_t = crate::image_extract::DEFAULT_BATCH_PAGES, value_name = "PAGES")]
+    pub extract_batch_pages: usize,
+
+    /// Reproducible output: fixed seeds, stable ordering, options recorded in PDF metadata
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Fill the PDF Subject with a summary of the OCR text (requires --ocr)
+    #[arg(long)]
+    pub metadata_from_ocr: bool,
+
+    /// Keep the source PDF's creator, producer and dates instead of stamping superbook-pdf
+    #[arg(long)]
+    pub preserve_metadata: bool,
+
+    /// Copy the source PDF's bookmarks into the output, pointing at the output pages
+    #[arg(long)]
+    pub preserve_outline: bool,
+
+    /// Also write the OCR text in reading order to <output>.txt (requires --ocr)
+    #[arg(long)]
+    pub text_sidecar: bool,
+
+    /// Join words hyphenated at line ends in the --text-sidecar text (horizontal text only)
+    #[arg(long)]
+    pub dehyphenate: bool,
+
+    /// OCR only these source pages, e.g. 1-12,15,40- (others get no text layer; requires --ocr)
+    #[arg(long, value_name = "PAGES", value_parser = crate::util::parse_page_ranges)]
+    pub ocr_pages: Option<crate::PageRanges>,
+
+    /// Skip OCR on pages that are mostly pictures (photo and illustration plates; requires --ocr)
+    #[arg(long)]
+    pub ocr_skip_pictures: bool,
+
+    /// Keep color pages in color and write text pages as grayscale
+    #[arg(long)]
+    pub mixed_color: bool,
+
+    /// Which parts keep their color: whole pages, or only detected figures
+    /// on grayscale pages (figures-only, requires --ocr)
+    #[arg(long, value_enum, default_value = "per-page")]
+    pub color_policy: ColorPolicyCli,
+
+    /// Sizing of pages in the minority orientation (e.g. landscape fold-out maps
+    /// in a portrait book): uniform, per-orientation, or preserve
+    #[arg(long, value_enum, default_value = "uniform")]
+    pub mixed_size: MixedSizeCli,
+
     /// Enable GPU processing
     #[arg(short, long, default_value_t = true)]
     #[arg(action = clap::ArgAction::Set)]
@@ -424,6 +1358,10 @@ pub struct ConvertArgs {
     #[arg(short, long, action = clap::ArgAction::Count)]
     pub verbose: u8,
 
+    /// Print a per-stage timing breakdown after each file
+    #[arg(long)]
+    pub verbose_timing: bool,
+
     /// Suppress progress output
     #[arg(short, long)]
     pub quiet: bool,
@@ -432,6 +1370,18 @@ pub struct ConvertArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// With --dry-run, sample a few pages and explain each stage decision
+    #[arg(long, requires = "dry_run")]
+    pub explain: bool,
+
+    /// Print the configuration resolved from the config file and options (PipelineConfig fields) and exit
+    #[arg(long, value_enum, value_name = "FORMAT", num_args = 0..=1, default_missing_value = "toml")]
+    pub print_config: Option<ConfigFormatCli>,
+
+    /// Message language (default: detected from LANG)
+    #[arg(long, value_enum)]
+    pub lang: Option<LangCli>,
+
     // === Phase 6: Advanced processing options ===
     /// Enable internal resolution normalization (4960x7016)
     #[arg(long)]
@@ -445,15 +1395,50 @@ pub struct ConvertArgs {
     #[arg(long)]
     pub offset_alignment: bool,
 
+    /// Shift odd and even pages so their content lines up (constant
+    /// front/back offset of duplex scans; needs no page numbers)
+    #[arg(long)]
+    pub register_sides: bool,
+
     /// Output height in pixels (default: 3508)
     #[arg(long, default_value_t = 3508)]
     pub output_height: u32,
 
+    /// Warn (or refuse, see --on-low-dpi) when the output has fewer pixels per inch of the physical page than DPI (0 = no check)
+    #[arg(long, value_name = "DPI", default_value_t = crate::pipeline::DEFAULT_MIN_OUTPUT_DPI, value_parser = clap::value_parser!(u32).range(0..=4800))]
+    pub min_output_dpi: u32,
+
+    /// What to do with a book below --min-output-dpi: warn, or refuse to write it
+    #[arg(long, value_enum, default_value = "warn")]
+    pub on_low_dpi: LowDpiPolicyCli,
+
+    /// Largest page image in megapixels the --dpi / upscaling / --output-height
+    /// combination may produce; larger requests are rejected up front (0 = no limit)
+    #[arg(long, value_name = "MP", default_value_t = crate::pipeline::DEFAULT_MAX_PAGE_MEGAPIXELS)]
+    pub max_page_megapixels: u32,
+
+    /// Longest edge in pixels of an extracted page; pages whose media box would
+    /// exceed it at --dpi are extracted at a lower DPI, with a warning (0 = no cap)
+    #[arg(long, value_name = "PX", default_value_t = crate::pipeline::DEFAULT_MAX_PAGE_DIMENSION)]
+    pub max_dimension: u32,
+
+    /// Fit pages to a W:H aspect ratio after group crop (e.g. 3:4); pads instead of clipping content
+    #[arg(long, value_name = "W:H", value_parser = crate::finalize::parse_aspect_ratio)]
+    pub target_aspect: Option<(u32, u32)>,
+
     /// Enable advanced processing for best quality output
     /// (includes: internal resolution normalization, color correction, offset alignment)
     #[arg(long)]
     pub advanced: bool,
 
+    /// Fast draft conversion: 150 DPI, no AI upscaling or OCR, 1% margin trim,
+    /// lower JPEG quality; the output is marked as a draft in the manifests
+    #[arg(
+        long,
+        conflicts_with_all = ["dpi", "output_height", "upscale", "ocr", "deblur", "advanced", "quality_metrics"]
+    )]
+    pub quick: bool,
+
     /// Skip files if output already exists
     #[arg(long)]
     pub skip_existing: bool,
@@ -462,6 +1447,47 @@ pub struct ConvertArgs {
     #[arg(long, short = 'f')]
     pub force: bool,
 
+    /// Re-process only when the source or options changed (default behavior)
+    #[arg(long, alias = "overwrite-on-change", conflicts_with_all = ["force", "skip_existing"])]
+    pub if_changed: bool,
+
+    /// Neither read nor write `.superbook-cache` sidecars (always process;
+    /// `--skip-existing` still skips outputs that already exist)
+    #[arg(long, conflicts_with = "if_changed")]
+    pub no_cache: bool,
+
+    /// Stop at the first file that fails and return its error
+    #[arg(long, conflicts_with = "keep_going")]
+    pub fail_fast: bool,
+
+    /// Continue after file errors and fail at the end (default behavior)
+    #[arg(long)]
+    pub keep_going: bool,
+
+    /// Write a JSON summary of the batch (per-file outcome and totals) to this path
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Append NDJSON events (file start/finish, stage timings, warnings,
+    /// errors) to this file as the run progresses, independent of -v/-q
+    #[arg(long, value_name = "PATH")]
+    pub event_log: Option<PathBuf>,
+
+    /// Reuse per-page stage results (trim, upscale, deskew, ...) of identical
+    /// pages across books and runs from this content-addressed cache directory
+    #[arg(long, value_name = "DIR")]
+    pub work_cache: Option<PathBuf>,
+
+    /// Size limit of --work-cache in MB; least recently used entries are evicted
+    #[arg(long, value_name = "MB", default_value_t = crate::work_cache::DEFAULT_WORK_CACHE_MAX_MB, requires = "work_cache")]
+    pub work_cache_max_mb: u64,
+
+    /// Continue an interrupted conversion: pages recorded in the progress file of
+    /// the book's work directory are not rendered or processed again (streamed
+    /// extraction; the file is checked against the source and options)
+    #[arg(long)]
+    pub resume: bool,
+
     // === Content-Aware Margin Options (Issue #32) ===
     /// Enable content-aware margin detection to prevent text clipping
     #[arg(long, default_value_t = true)]
@@ -491,10 +1517,23 @@ pub struct ConvertArgs {
     #[arg(long)]
     pub remove_markers: bool,
 
-    /// Colors to remove (comma-separated: yellow,pink,green,blue,orange)
-    #[arg(long, value_delimiter = ',', default_value = "yellow,pink,green,blue")]
+    /// Colors to remove (comma-separated: yellow,pink,green,blue,orange,
+    /// or names defined by `[[highlighter]]` tables in the config file)
+    #[arg(long, value_delimiter = ',', default_value = DEFAULT_MARKER_COLORS)]
     pub marker_colors: Vec<String>,
 
+    /// Where markers are removed: the whole page, or only the margins outside the text block
+    #[arg(long, value_enum, default_value = "page")]
+    pub marker_region: MarkerAreaCli,
+
+    /// Remove thin full-length streaks left by dust or hair on the scanner platen
+    #[arg(long)]
+    pub remove_line_artifacts: bool,
+
+    /// Invert negative scans (white text on black, e.g. microfilm) before the other stages
+    #[arg(long, value_enum, default_value = "off")]
+    pub invert: InvertCli,
+
     // === Deblur Options (Issue #35) ===
     /// Enable blur detection and correction
     #[arg(long)]
@@ -504,12 +1543,42 @@ pub struct ConvertArgs {
     #[arg(long, value_enum, default_value = "unsharp-mask")]
     pub deblur_algorithm: DeblurAlgorithmCli,
 
+    // === Auto Contrast Options ===
+    /// Contrast enhancement for faint or low-contrast scans
+    #[arg(long, value_enum, default_value = "off")]
+    pub auto_contrast: AutoContrastCli,
+
+    /// Low/high percentiles for --auto-contrast percentile (e.g. 1,99)
+    #[arg(long, value_delimiter = ',', default_values_t = [1.0, 99.0])]
+    pub contrast_percentiles: Vec<f32>,
+
+    /// Apply auto contrast to each R/G/B channel instead of luminance
+    #[arg(long)]
+    pub contrast_per_channel: bool,
+
+    /// Manual tone curve as IN:OUT control points, 0-255 (e.g. 0:0,128:80,255:255 darkens faded text)
+    #[arg(long, value_name = "POINTS", value_parser = crate::contrast::parse_tone_curve)]
+    pub tone_curve: Option<crate::ToneCurve>,
+
+    /// Interpolation between --tone-curve points
+    #[arg(long, value_enum, default_value = "monotone", requires = "tone_curve")]
+    pub tone_curve_interpolation: ToneInterpolationCli,
+
+    /// Apply --tone-curve to each R/G/B channel instead of luminance
+    #[arg(long, requires = "tone_curve")]
+    pub tone_curve_per_channel: bool,
+
+    /// Gray out colors whose saturation (chroma, 0.0-1.0) is below this value,
+    /// removing color fringes around black text while keeping saturated ink
+    #[arg(long, value_name = "SATURATION", alias = "strip-color-from-scans-of-black-text")]
+    pub desaturate_below: Option<f32>,
+
     // === Debug options ===
     /// Maximum pages to process (for debugging)
     #[arg(long)]
     pub max_pages: Option<usize>,
 
-    /// Save intermediate debug images
+    /// Save intermediate debug images (with --ocr, also reading order overlays)
     #[arg(long)]
     pub save_debug: bool,
 }
@@ -554,6 +1623,68 @@ impl ConvertArgs {
     pub fn effective_content_aware_margins(&self) -> bool {
         self.content_aware_margins && !self.no_content_aware_margins
     }
+
+    /// Get effective auto contrast mode
+    pub fn effective_auto_contrast(&self) -> crate::AutoContrast {
+        match self.auto_contrast {
+            AutoContrastCli::Off => crate::AutoContrast::Off,
+            AutoContrastCli::Percentile => {
+                let low = self.contrast_percentiles.first().copied().unwrap_or(1.0);
+                let high = self.contrast_percentiles.get(1).copied().unwrap_or(99.0);
+                crate::AutoContrast::percentile(low, high)
+            }
+            AutoContrastCli::Clahe => crate::AutoContrast::Clahe,
+        }
+    }
+
+    /// Get the manual tone curve (`None` without --tone-curve)
+    pub fn effective_tone_curve(&self) -> Option<crate::ToneCurve> {
+        self.tone_curve.clone().map(|curve| {
+            curve
+                .with_interpolation(self.tone_curve_interpolation.into())
+                .with_per_channel(self.tone_curve_per_channel)
+        })
+    }
+
+    /// Get requested OCR export formats, without duplicates
+    pub fn effective_ocr_formats(&self) -> Vec<crate::markdown::OcrExportFormat> {
+        let mut formats = Vec::new();
+        for format in self.ocr_formats.iter().copied().map(Into::into) {
+            if !formats.contains(&format) {
+                formats.push(format);
+            }
+        }
+        formats
+    }
+
+    /// Get the reprocess policy
+    ///
+    /// Precedence: `--force` > `--skip-existing` > `--no-cache` > `--if-changed`.
+    /// Without a cache there is nothing to compare against, so `--no-cache`
+    /// alone always re-processes.
+    pub fn reprocess_policy(&self) -> ReprocessPolicy {
+        if self.force {
+            ReprocessPolicy::Force
+        } else if self.skip_existing {
+            ReprocessPolicy::SkipExisting
+        } else if self.no_cache {
+            ReprocessPolicy::Force
+        } else {
+            ReprocessPolicy::IfChanged
+        }
+    }
+
+    /// Get effective message language (`--lang`, then `LANG`, then English)
+    pub fn effective_lang(&self) -> crate::i18n::Lang {
+        self.lang
+            .map(Into::into)
+            .unwrap_or_else(crate::i18n::Lang::from_env)
+    }
+
+    /// Whether cache sidecars should be written after processing
+    pub fn use_cache(&self) -> bool {
+        !self.no_cache
+    }
 }
 
 /// Create a styled progress bar for file processing
@@ -703,6 +1834,73 @@ mod tests {
         assert!(matches!(cli.command, Commands::Info));
     }
 
+    #[test]
+    fn test_stages_command() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "stages"]).unwrap();
+        assert!(matches!(cli.command, Commands::Stages));
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "list-stages"]).unwrap();
+        assert!(matches!(cli.command, Commands::Stages));
+    }
+
+    #[test]
+    fn test_selftest_command() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "selftest"]).unwrap();
+        let Commands::Selftest(args) = cli.command else {
+            panic!("Expected Selftest command");
+        };
+        assert_eq!(args.pages as usize, crate::selftest::DEFAULT_PAGES);
+        assert!(args.work_dir.is_none() && args.config.is_none());
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "selftest", "--pages", "2", "--work-dir", "st", "-vv"]).unwrap();
+        let Commands::Selftest(args) = cli.command else {
+            panic!("Expected Selftest command");
+        };
+        assert_eq!(args.pages, 2);
+        assert_eq!(args.work_dir, Some(PathBuf::from("st")));
+        assert_eq!(args.verbose, 2);
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "selftest", "--pages", "0"]).is_err());
+
+        let error = CliError::from(crate::selftest::SelftestError::Failed("output has 3 pages, expected 4".into()));
+        assert_eq!(error.exit_code(), ExitCode::ProcessingError);
+    }
+
+    #[test]
+    fn test_diff_command() {
+        use crate::pdf_diff::DiffError;
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "diff", "a.pdf", "b.pdf"]).unwrap();
+        let Commands::Diff(args) = cli.command else {
+            panic!("Expected Diff command");
+        };
+        assert_eq!((args.a, args.b), (PathBuf::from("a.pdf"), PathBuf::from("b.pdf")));
+        assert_eq!(args.dpi, crate::pdf_diff::DEFAULT_DIFF_DPI);
+        assert_eq!(args.threshold, crate::pdf_diff::DEFAULT_DIFF_THRESHOLD);
+        assert!(args.heatmap_dir.is_none() && !args.json);
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf", "diff", "a.pdf", "b.pdf", "--dpi", "150", "--threshold", "0.5", "--heatmap-dir", "diff",
+            "--json",
+        ])
+        .unwrap();
+        let Commands::Diff(args) = cli.command else {
+            panic!("Expected Diff command");
+        };
+        assert_eq!(args.dpi, 150);
+        assert_eq!(args.threshold, 0.5);
+        assert_eq!(args.heatmap_dir, Some(PathBuf::from("diff")));
+        assert!(args.json);
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "diff", "a.pdf"]).is_err());
+        assert!(Cli::try_parse_from(["superbook-pdf", "diff", "a.pdf", "b.pdf", "--dpi", "10"]).is_err());
+
+        let differs = CliError::from(DiffError::Differs { changed: 2, pages_a: 10, pages_b: 10 });
+        assert_eq!(differs.exit_code(), ExitCode::GeneralError);
+        let missing = CliError::from(DiffError::PdfNotFound(PathBuf::from("a.pdf")));
+        assert_eq!(missing.exit_code(), ExitCode::InputNotFound);
+    }
+
     // TC-CLI-007: Progress bar display
     #[test]
     fn test_progress_bar_display() {
@@ -1074,9 +2272,24 @@ mod tests {
 
         if let Commands::Convert(args) = cli.command {
             assert!(args.dry_run);
+            assert!(!args.explain);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_explain_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--dry-run", "--explain"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.dry_run && args.explain);
         } else {
             panic!("Expected Convert command");
         }
+
+        // --explain only makes sense for a dry run
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--explain"]).is_err());
     }
 
     // ============ Debug Implementation Tests ============
@@ -1807,6 +3020,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_extract_queue_depth() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.extract_queue_depth, crate::pipeline::DEFAULT_EXTRACT_QUEUE_DEPTH);
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--extract-queue-depth",
+            "0",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.extract_queue_depth, 0);
+        }
+    }
+
+    #[test]
+    fn test_extract_batch_pages() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.extract_batch_pages, crate::image_extract::DEFAULT_BATCH_PAGES);
+        }
+
+        let cli =
+            Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--extract-batch-pages", "1"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).extract_batch_pages, 1);
+        }
+    }
+
     #[test]
     fn test_chunk_size_explicit() {
         let cli =
@@ -1875,6 +3122,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_verbose_timing() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.verbose_timing);
+        }
+
+        let cli =
+            Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--verbose-timing"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.verbose_timing);
+        }
+    }
+
     #[test]
     fn test_verbose_boundary_maximum() {
         let cli = Cli::try_parse_from([
@@ -2196,6 +3457,17 @@ mod tests {
             Cli::try_parse_from(["superbook-pdf", "cache-info", "output.pdf"]).unwrap();
         if let Commands::CacheInfo(args) = cli.command {
             assert_eq!(args.output_pdf, PathBuf::from("output.pdf"));
+            assert!(!args.json);
+        } else {
+            panic!("Expected CacheInfo command");
+        }
+    }
+
+    #[test]
+    fn test_cache_info_json() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "cache-info", "output.pdf", "--json"]).unwrap();
+        if let Commands::CacheInfo(args) = cli.command {
+            assert!(args.json);
         } else {
             panic!("Expected CacheInfo command");
         }
@@ -2222,6 +3494,54 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // ============ Compare Options Command Tests ============
+
+    #[test]
+    fn test_compare_options_command() {
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "compare-options",
+            "input.pdf",
+            "--page",
+            "5",
+            "--profile",
+            "dpi300:dpi=300",
+            "--profile",
+            "dpi450:dpi=450",
+        ])
+        .unwrap();
+        if let Commands::CompareOptions(args) = cli.command {
+            assert_eq!(args.input, PathBuf::from("input.pdf"));
+            assert_eq!(args.page, 5);
+            assert_eq!(args.profiles, vec!["dpi300:dpi=300", "dpi450:dpi=450"]);
+            assert_eq!(args.output, PathBuf::from("compare.png"));
+            assert_eq!(args.cell_height, crate::compare::DEFAULT_CELL_HEIGHT);
+            assert_eq!(args.columns, 0);
+        } else {
+            panic!("Expected CompareOptions command");
+        }
+    }
+
+    #[test]
+    fn test_compare_options_requires_profile() {
+        let result = Cli::try_parse_from(["superbook-pdf", "compare-options", "input.pdf"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_compare_options_rejects_page_zero() {
+        let result = Cli::try_parse_from([
+            "superbook-pdf",
+            "compare-options",
+            "input.pdf",
+            "--page",
+            "0",
+            "--profile",
+            "base",
+        ]);
+        assert!(result.is_err());
+    }
+
     // ============ Reprocess Command Tests ============
 
     #[test]
@@ -2447,4 +3767,1325 @@ mod tests {
             panic!("Expected Reprocess command");
         }
     }
+
+    // Reprocess policy tests
+
+    #[test]
+    fn test_reprocess_policy_default_if_changed() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.if_changed);
+            assert_eq!(args.reprocess_policy(), ReprocessPolicy::IfChanged);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_reprocess_policy_explicit_flags() {
+        let cli =
+            Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--if-changed"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.if_changed);
+            assert_eq!(args.reprocess_policy(), ReprocessPolicy::IfChanged);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--force"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.reprocess_policy(), ReprocessPolicy::Force);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--skip-existing"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.reprocess_policy(), ReprocessPolicy::SkipExisting);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_if_changed_conflicts_with_force() {
+        let result = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--if-changed",
+            "--force",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_no_cache_policy() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--no-cache"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.use_cache());
+            assert_eq!(args.reprocess_policy(), ReprocessPolicy::Force);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--no-cache",
+            "--skip-existing",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.use_cache());
+            assert_eq!(args.reprocess_policy(), ReprocessPolicy::SkipExisting);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_no_cache_conflicts_with_if_changed() {
+        let result = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--no-cache",
+            "--if-changed",
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_lang_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--lang", "ja"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.lang, Some(LangCli::Ja));
+            assert_eq!(args.effective_lang(), crate::i18n::Lang::Ja);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let result = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--lang", "fr"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_auto_contrast_default_off() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.auto_contrast, AutoContrastCli::Off);
+            assert_eq!(args.effective_auto_contrast(), crate::AutoContrast::Off);
+            assert!(!args.contrast_per_channel);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_auto_contrast_percentile() {
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--auto-contrast",
+            "percentile",
+            "--contrast-percentiles",
+            "2,98",
+            "--contrast-per-channel",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(
+                args.effective_auto_contrast(),
+                crate::AutoContrast::Percentile(2.0, 98.0)
+            );
+            assert!(args.contrast_per_channel);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_tone_curve_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.effective_tone_curve(), None);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--tone-curve",
+            "0:0,128:80,255:255",
+            "--tone-curve-interpolation",
+            "linear",
+            "--tone-curve-per-channel",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let curve = crate::PipelineConfig::from_convert_args(&args).tone_curve.unwrap();
+            assert_eq!(curve.points, vec![(0, 0), (128, 80), (255, 255)]);
+            assert_eq!(curve.interpolation, crate::ToneInterpolation::Linear);
+            assert!(curve.per_channel);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--tone-curve", "128:0,64:255"]).is_err());
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--tone-curve-per-channel"]).is_err());
+    }
+
+    #[test]
+    fn test_auto_contrast_clahe() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--auto-contrast", "clahe"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.effective_auto_contrast(), crate::AutoContrast::Clahe);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.auto_contrast, crate::AutoContrast::Clahe);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let result = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--auto-contrast", "gamma"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_ocr_formats() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.effective_ocr_formats().is_empty());
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--ocr",
+            "--format",
+            "hocr,alto,hocr",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(
+                args.effective_ocr_formats(),
+                vec![
+                    crate::markdown::OcrExportFormat::Hocr,
+                    crate::markdown::OcrExportFormat::Alto
+                ]
+            );
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let result = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--format", "txt"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_text_sidecar_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr", "--text-sidecar"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.text_sidecar);
+            assert!(crate::PipelineConfig::from_convert_args(&args).write_text_sidecar);
+            assert!(!crate::PipelineConfig::from_convert_args(&args).dehyphenate);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr", "--text-sidecar", "--dehyphenate"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.dehyphenate);
+            assert!(config.to_json().contains("\"dehyphenate\":true"));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_ocr_pages_args() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.ocr_pages, None);
+            assert!(!config.ocr_skip_pictures);
+            assert!(!config.to_json().contains("ocr_pages"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--ocr",
+            "--ocr-pages",
+            "5-120,130-",
+            "--ocr-skip-pictures",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            let pages = config.ocr_pages.as_ref().unwrap();
+            assert!(!pages.contains(4) && pages.contains(5) && !pages.contains(125) && pages.contains(400));
+            assert!(config.ocr_skip_pictures);
+            assert!(config.to_json().contains("\"ocr_pages\":\"5-120,130-\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        for invalid in ["0", "9-3", "odd"] {
+            assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr-pages", invalid]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_deterministic_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--deterministic"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.deterministic);
+            assert!(crate::PipelineConfig::from_convert_args(&args).deterministic);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_ocr_optional_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr", "--ocr-optional"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.ocr_optional);
+            assert!(crate::PipelineConfig::from_convert_args(&args).ocr_optional);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_skip_existing_ocr_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--skip-existing-ocr"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.skip_existing_ocr);
+            assert!(crate::PipelineConfig::from_convert_args(&args).skip_existing_ocr);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_nice_and_gpu_wait_options() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--nice", "10", "--gpu-wait"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.nice, 10);
+            assert!(args.gpu_wait);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.nice, 10);
+            assert!(config.gpu_wait);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--nice", "-5"]).is_ok());
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--nice", "20"]).is_err());
+    }
+
+    #[test]
+    fn test_external_thread_fraction_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "-t", "8", "--external-thread-fraction", "0.5"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.external_thread_fraction, Some(0.5));
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.thread_budget(), Some(crate::ThreadBudget { internal: 4, external: 4 }));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--external-thread-fraction", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_min_output_dpi_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.min_output_dpi, crate::pipeline::DEFAULT_MIN_OUTPUT_DPI);
+            assert_eq!(args.on_low_dpi, LowDpiPolicyCli::Warn);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.on_low_dpi, crate::LowDpiPolicy::Warn);
+            assert!(!config.to_json().contains("min_output_dpi"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--min-output-dpi",
+            "200",
+            "--on-low-dpi",
+            "refuse",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.min_output_dpi, 200);
+            assert_eq!(config.on_low_dpi, crate::LowDpiPolicy::Refuse);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--min-output-dpi", "5000"]).is_err());
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--on-low-dpi", "ignore"]).is_err());
+    }
+
+    #[test]
+    fn test_quick_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--quick"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.quick);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.quick);
+            assert_eq!(config.dpi, crate::pipeline::QUICK_DPI);
+            assert!(!config.upscale);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        for conflicting in [&["--dpi", "600"][..], &["--ocr"], &["--deblur"], &["--advanced"]] {
+            let mut argv = vec!["superbook-pdf", "convert", "input.pdf", "--quick"];
+            argv.extend_from_slice(conflicting);
+            assert!(Cli::try_parse_from(argv).is_err(), "{:?}", conflicting);
+        }
+    }
+
+    #[test]
+    fn test_work_cache_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.work_cache, None);
+            assert_eq!(args.work_cache_max_mb, crate::work_cache::DEFAULT_WORK_CACHE_MAX_MB);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--work-cache",
+            "/var/cache/superbook",
+            "--work-cache-max-mb",
+            "512",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.work_cache, Some(PathBuf::from("/var/cache/superbook")));
+            assert_eq!(config.work_cache_max_mb, 512);
+            // Not output-affecting
+            assert!(!config.to_json().contains("work_cache"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        // The size limit needs a cache directory
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--work-cache-max-mb", "512"]).is_err());
+    }
+
+    #[test]
+    fn test_resume_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.resume);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--resume"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.resume);
+            // Not output-affecting
+            assert!(!config.to_json().contains("resume"));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_max_dimension_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.max_dimension, crate::pipeline::DEFAULT_MAX_PAGE_DIMENSION);
+            assert!(!crate::PipelineConfig::from_convert_args(&args).to_json().contains("max_page_dimension"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--max-dimension", "8000"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.max_page_dimension, 8000);
+            assert!(config.to_json().contains("max_page_dimension"));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_preserve_source_options() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(!config.preserve_source_metadata && !config.preserve_source_outline);
+            assert!(!config.to_json().contains("preserve_source"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--preserve-metadata",
+            "--preserve-outline",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.preserve_source_metadata);
+            assert!(config.preserve_source_outline);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_max_page_megapixels_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.max_page_megapixels, crate::pipeline::DEFAULT_MAX_PAGE_MEGAPIXELS);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--dpi",
+            "1200",
+            "--max-page-megapixels",
+            "0",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.max_page_megapixels, 0);
+            assert!(config.validate().is_ok());
+            assert!(!config.to_json().contains("max_page_megapixels"));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_on_page_error_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.on_page_error, PageErrorPolicyCli::Skip);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.on_page_error.is_skip());
+            assert!(!config.to_json().contains("on_page_error"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--on-page-error", "placeholder"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.on_page_error, crate::PageErrorPolicy::Placeholder);
+            assert!(config.to_json().contains("\"on_page_error\":\"placeholder\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--on-page-error", "retry"]).is_err());
+    }
+
+    #[test]
+    fn test_tool_timeout_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.tool_timeout, None);
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).tool_timeout_secs, None);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--tool-timeout", "120"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.tool_timeout_secs, Some(120));
+            // Environment, not output: kept out of the cache digest
+            assert!(!config.to_json().contains("tool_timeout"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--tool-timeout", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_stall_warning_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.stall_warning, crate::DEFAULT_STALL_WARNING_SECS);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.stall_warning_secs, Some(crate::DEFAULT_STALL_WARNING_SECS));
+            assert!(!config.to_json().contains("stall_warning"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--stall-warning", "0"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).stall_warning_secs, None);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_print_config_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        let Commands::Convert(args) = cli.command else {
+            panic!("Expected Convert command");
+        };
+        assert_eq!(args.print_config, None);
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--print-config"]).unwrap();
+        let Commands::Convert(args) = cli.command else {
+            panic!("Expected Convert command");
+        };
+        assert_eq!(args.print_config, Some(ConfigFormatCli::Toml));
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--print-config", "json"]).unwrap();
+        let Commands::Convert(args) = cli.command else {
+            panic!("Expected Convert command");
+        };
+        assert_eq!(args.print_config, Some(ConfigFormatCli::Json));
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--print-config", "yaml"]).is_err());
+    }
+
+    #[test]
+    fn test_ocr_concurrency_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.ocr_concurrency, None);
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).ocr_concurrency, None);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr-concurrency", "3", "-t", "8"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.ocr_concurrency, Some(3));
+            assert_eq!(config.threads, Some(8));
+            assert!(!config.to_json().contains("ocr_concurrency"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr-concurrency", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_remove_line_artifacts_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--remove-line-artifacts"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.remove_line_artifacts);
+            assert!(crate::PipelineConfig::from_convert_args(&args).remove_line_artifacts);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_desaturate_below_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--desaturate-below", "0.2"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.desaturate_below, Some(0.2));
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).desaturate_below, Some(0.2));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--strip-color-from-scans-of-black-text",
+            "1.5",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).desaturate_below, Some(1.0));
+        }
+    }
+
+    #[test]
+    fn test_marker_colors_option() {
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--remove-markers",
+            "--marker-colors",
+            "yellow,mybrand",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.marker_colors, vec!["yellow", "mybrand"]);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.remove_markers);
+            assert_eq!(config.marker_colors, vec!["yellow", "mybrand"]);
+            // Custom names need their [[highlighter]] definition
+            assert!(config.marker_removal_options().is_err());
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.marker_colors.join(","), DEFAULT_MARKER_COLORS);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(!config.remove_markers);
+            assert!(config.marker_colors.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_marker_region_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--remove-markers"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.marker_region, MarkerAreaCli::Page);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.marker_region.is_page());
+            assert!(!config.to_json().contains("marker_region"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "input.pdf",
+            "--remove-markers",
+            "--marker-region",
+            "margins",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.marker_region, crate::cleanup::MarkerArea::Margins);
+            assert!(config.to_json().contains("\"marker_region\":\"margins\""));
+            let options = config.marker_removal_options().unwrap().unwrap();
+            assert!(matches!(options.region, crate::cleanup::MarkerRegion::Margins));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--marker-region", "gutter"]).is_err());
+    }
+
+    #[test]
+    fn test_report_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--report", "out/report.json"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.report, Some(PathBuf::from("out/report.json")));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.report, None);
+        }
+    }
+
+    #[test]
+    fn test_event_log_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--event-log", "logs/run.ndjson", "-q"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.event_log, Some(PathBuf::from("logs/run.ndjson")));
+            // Not a processing option
+            assert!(!crate::PipelineConfig::from_convert_args(&args).to_json().contains("event_log"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.event_log, None);
+        }
+    }
+
+    #[test]
+    fn test_target_aspect_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--target-aspect", "3:4"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.target_aspect, Some((3, 4)));
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).target_aspect, Some((3, 4)));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.target_aspect, None);
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--target-aspect", "0:4"]).is_err());
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--target-aspect", "3x4"]).is_err());
+    }
+
+    #[test]
+    fn test_color_policy_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.color_policy, ColorPolicyCli::PerPage);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.color_policy.is_per_page());
+            assert!(!config.to_json().contains("color_policy"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--color-policy", "figures-only"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.color_policy, crate::ColorPolicy::FiguresOnly);
+            assert!(config.to_json().contains("\"color_policy\":\"figures-only\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--color-policy", "mono"]).is_err());
+    }
+
+    #[test]
+    fn test_mixed_size_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.mixed_size, MixedSizeCli::Uniform);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.mixed_size.is_uniform());
+            assert!(!config.to_json().contains("mixed_size"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        for (value, expected) in [
+            ("per-orientation", crate::MixedSize::PerOrientation),
+            ("preserve", crate::MixedSize::Preserve),
+        ] {
+            let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--mixed-size", value]).unwrap();
+            if let Commands::Convert(args) = cli.command {
+                let config = crate::PipelineConfig::from_convert_args(&args);
+                assert_eq!(config.mixed_size, expected);
+                assert!(config.to_json().contains(&format!("\"mixed_size\":\"{}\"", value)));
+            } else {
+                panic!("Expected Convert command");
+            }
+        }
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--mixed-size", "rotate"]).is_err());
+    }
+
+    #[test]
+    fn test_mixed_color_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--mixed-color"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.mixed_color);
+            assert!(crate::PipelineConfig::from_convert_args(&args).mixed_color);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_fail_fast_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.fail_fast);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--fail-fast"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.fail_fast);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let result =
+            Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--fail-fast", "--keep-going"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_page_order_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.page_order, PageOrderCli::Auto);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--page-order", "rtl"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).binding, crate::Binding::RightToLeft);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--page-order", "up"]).is_err());
+    }
+
+    #[test]
+    fn test_text_direction_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.text_direction, TextDirectionCli::Auto);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.text_direction.is_auto());
+            assert!(!config.to_json().contains("text_direction"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--text-direction", "vertical"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.text_direction.forced_vertical(), Some(true));
+            assert!(config.to_json().contains("\"text_direction\":\"vertical\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--text-direction", "diagonal"]).is_err());
+    }
+
+    #[test]
+    fn test_flatten_background_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.flatten_background, [255, 255, 255]);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.flatten_background, crate::util::DEFAULT_FLATTEN_BACKGROUND);
+            assert!(!config.to_json().contains("flatten_background"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--flatten-background", "#f5ebdc"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.flatten_background, [245, 235, 220]);
+            assert!(config.to_json().contains("\"flatten_background\":[245,235,220]"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--flatten-background", "white"]).is_err());
+    }
+
+    #[test]
+    fn test_resize_filter_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.resize_filter, ResizeFilterCli::Lanczos3);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli =
+            Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--resize-filter", "catmull-rom"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).resize_filter, crate::Resampler::Bicubic);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--resize-filter", "box"]).is_err());
+    }
+
+    #[test]
+    fn test_image_encoding_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.image_encoding, ImageEncodingCli::Standard);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli =
+            Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--image-encoding", "jbig2"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).image_encoding, crate::ImageEncoding::Jbig2);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--image-encoding", "g3"]).is_err());
+    }
+
+    #[test]
+    fn test_pdfa_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.pdfa, None);
+            assert!(!args.pdfa_validate);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--pdfa", "2b", "--pdfa-validate"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.pdfa, Some(crate::PdfaLevel::A2b));
+            assert!(config.pdfa_validate);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--pdfa", "3u"]).is_err());
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--pdfa-validate"]).is_err());
+    }
+
+    #[test]
+    fn test_archive_options() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "books/"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.include_archives);
+            assert_eq!(args.output_format, BookFormatCli::Pdf);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf",
+            "convert",
+            "books/",
+            "--include-archives",
+            "--output-format",
+            "cbz",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.include_archives);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.output_format, crate::BookFormat::Cbz);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "in.pdf", "--output-format", "images"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).output_format, crate::BookFormat::Images);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "in.cbz", "--output-format", "epub"]).is_err());
+    }
+
+    #[test]
+    fn test_page_labels_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!args.page_labels);
+            assert!(!crate::PipelineConfig::from_convert_args(&args).page_labels);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        for flag in ["--page-labels", "--page-label"] {
+            let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", flag]).unwrap();
+            if let Commands::Convert(args) = cli.command {
+                let config = crate::PipelineConfig::from_convert_args(&args);
+                assert!(config.page_labels);
+                assert!(!config.offset_alignment);
+            } else {
+                panic!("Expected Convert command");
+            }
+        }
+    }
+
+    #[test]
+    fn test_register_sides_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(!crate::PipelineConfig::from_convert_args(&args).register_sides);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--register-sides"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.register_sides);
+            assert!(!config.offset_alignment);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_running_heads_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.running_heads, RunningHeadModeCli::Keep);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.running_heads.is_keep());
+            assert!(!config.to_json().contains("running_heads"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr", "--running-heads", "erase"])
+            .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.running_heads, crate::markdown::RunningHeadMode::Erase);
+            assert!(config.to_json().contains("\"running_heads\":\"erase\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_parity_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.parity, PageParityCli::All);
+            assert!(crate::PipelineConfig::from_convert_args(&args).parity.is_all());
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--parity", "even"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.parity, crate::PageParity::Even);
+            assert!(config.to_json().contains("\"parity\":\"even\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        assert!(Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--parity", "left"]).is_err());
+    }
+
+    #[test]
+    fn test_invert_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.invert, InvertCli::Off);
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert!(config.invert.is_off());
+            assert!(!config.to_json().contains("invert"));
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--invert", "auto"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.invert, crate::Invert::Auto);
+            assert!(config.to_json().contains("\"invert\":\"auto\""));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_quality_metrics_flag() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--quality-metrics"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert!(args.quality_metrics);
+            assert!(crate::PipelineConfig::from_convert_args(&args).quality_metrics);
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_hook_options() {
+        let cli = Cli::try_parse_from([
+            "superbook-pdf", "convert", "input.pdf", "--post-hook", "./publish.sh", "--page-hook", "/opt/stamp",
+        ])
+        .unwrap();
+        if let Commands::Convert(args) = cli.command {
+            let config = crate::PipelineConfig::from_convert_args(&args);
+            assert_eq!(config.post_hook.as_deref(), Some("./publish.sh"));
+            assert_eq!(config.page_hook.as_deref(), Some("/opt/stamp"));
+        } else {
+            panic!("Expected Convert command");
+        }
+    }
+
+    #[test]
+    fn test_ocr_min_confidence_option() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.ocr_min_confidence, 60.0);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "convert", "input.pdf", "--ocr-min-confidence", "75"]).unwrap();
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(crate::PipelineConfig::from_convert_args(&args).ocr_min_confidence, 75.0);
+        } else {
+            panic!("Expected Convert command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "markdown", "input.pdf", "--mark-low-confidence", "50"]).unwrap();
+        if let Commands::Markdown(args) = cli.command {
+            assert_eq!(args.mark_low_confidence, Some(50.0));
+        } else {
+            panic!("Expected Markdown command");
+        }
+    }
+
+    #[test]
+    fn test_markdown_exclude_running_heads() {
+        let cli = Cli::try_parse_from(["superbook-pdf", "markdown", "input.pdf"]).unwrap();
+        if let Commands::Markdown(args) = cli.command {
+            assert!(!args.exclude_running_heads);
+        } else {
+            panic!("Expected Markdown command");
+        }
+
+        let cli = Cli::try_parse_from(["superbook-pdf", "markdown", "input.pdf", "--exclude-running-heads", "--dehyphenate"])
+            .unwrap();
+        if let Commands::Markdown(args) = cli.command {
+            assert!(args.exclude_running_heads);
+            assert!(args.dehyphenate);
+        } else {
+            panic!("Expected Markdown command");
+        }
+    }
+
+    #[test]
+    fn test_assemble_command() {
+        let cli = Cli::try_parse_from([
+            "superbook-pdf", "assemble", "output/.work_book", "-o", "book.pdf", "--jpeg-quality", "80",
+        ])
+        .unwrap();
+        if let Commands::Assemble(args) = cli.command {
+            assert_eq!(args.input, PathBuf::from("output/.work_book"));
+            assert_eq!(args.output, PathBuf::from("book.pdf"));
+            assert_eq!(args.jpeg_quality, Some(80));
+            assert_eq!(args.dpi, None);
+            assert!(!args.mixed_color);
+            assert_eq!(args.ocr_dir, None);
+        } else {
+            panic!("Expected Assemble command");
+        }
+
+        let cli = Cli::try_parse_from([
+            "superbook-pdf", "assemble", "book_converted", "-o", "book.pdf", "--ocr-dir", "returned",
+        ])
+        .unwrap();
+        if let Commands::Assemble(args) = cli.command {
+            assert_eq!(args.ocr_dir, Some(PathBuf::from("returned")));
+        } else {
+            panic!("Expected Assemble command");
+        }
+
+        // Output is required
+        assert!(Cli::try_parse_from(["superbook-pdf", "assemble", "pages"]).is_err());
+    }
+
+    // CliError tests
+
+    #[test]
+    fn test_cli_error_exit_codes() {
+        use crate::pipeline::PipelineError;
+
+        assert_eq!(
+            CliError::InputNotFound(PathBuf::from("/x.pdf")).exit_code(),
+            ExitCode::InputNotFound
+        );
+        assert_eq!(CliError::NoInputFiles.exit_code(), ExitCode::InputNotFound);
+        assert_eq!(
+            CliError::InvalidArgs("bad".into()).exit_code(),
+            ExitCode::InvalidArgs
+        );
+        assert_eq!(
+            CliError::from(std::io::Error::other("disk full")).exit_code(),
+            ExitCode::OutputError
+        );
+        assert_eq!(
+            CliError::from(PipelineError::ExtractionFailed("pdftoppm".into())).exit_code(),
+            ExitCode::ExternalToolError
+        );
+        assert_eq!(
+            CliError::from(PipelineError::ImageProcessingFailed("decode".into())).exit_code(),
+            ExitCode::ProcessingError
+        );
+        assert_eq!(
+            CliError::from(PipelineError::OcrUnavailable {
+                what: "OCR",
+                tool: "tesseract",
+                flag: "--ocr",
+            })
+            .exit_code(),
+            ExitCode::ExternalToolError
+        );
+        assert_eq!(CliError::from("oops").exit_code(), ExitCode::GeneralError);
+    }
+
+    #[test]
+    fn test_cli_error_batch_failed() {
+        let err = CliError::BatchFailed {
+            failed: 2,
+            code: ExitCode::ExternalToolError,
+        };
+        assert_eq!(err.exit_code(), ExitCode::ExternalToolError);
+        assert_eq!(err.to_string(), "2 file(s) failed to process");
+    }
+
+    #[test]
+    fn test_cli_error_display() {
+        let err = CliError::InputNotFound(PathBuf::from("/missing.pdf"));
+        assert!(err.to_string().contains("Input path does not exist"));
+        assert!(err.to_string().contains("/missing.pdf"));
+
+        let err = CliError::InvalidPdf {
+            path: PathBuf::from("/empty.pdf"),
+            reason: crate::InvalidPdf::Empty,
+        };
+        assert_eq!(err.to_string(), "Not a valid PDF: /empty.pdf: file is empty");
+        assert_eq!(err.exit_code(), ExitCode::InvalidArgs);
+    }
 }
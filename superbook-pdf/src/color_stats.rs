@@ -9,6 +9,8 @@
 //! - MAD-based outlier exclusion
 //! - Linear scale/offset color adjustment
 //! - Ghost suppression for see-through pages
+//! - Per-page color content detection for mixed color/grayscale books
+//! - Low-saturation desaturation of color-scan fringes around black text
 //!
 //! # Example
 //!
@@ -59,6 +61,31 @@ const DEFAULT_COLOR_DIST_THRESHOLD: u8 = 35;
 /// Default white clip range
 const DEFAULT_WHITE_CLIP_RANGE: u8 = 30;
 
+/// Number of bins in the page saturation histogram
+pub const SATURATION_BINS: usize = 20;
+
+/// Minimum HSV saturation for a pixel to count as visibly colored
+/// (yellowed paper and sepia ink stay below this)
+const COLOR_PAGE_MIN_SATURATION: f32 = 0.35;
+
+/// Minimum HSV value for saturation to be counted (unreliable near black)
+const COLOR_PAGE_MIN_VALUE: f32 = 0.2;
+
+/// Default fraction of colored pixels above which a page is kept in color
+pub const DEFAULT_COLOR_PAGE_RATIO: f64 = 0.01;
+
+/// Luminance range of the mid-tones that make up photos and illustrations
+/// (text pages are paper with thin ink strokes and few pixels in between)
+const PICTURE_MID_TONES: std::ops::RangeInclusive<u8> = 48..=200;
+
+/// Default fraction of mid-tone or colored pixels above which a page is
+/// treated as a picture (image plate) rather than text
+pub const DEFAULT_PICTURE_PAGE_RATIO: f64 = 0.3;
+
+/// Width of the band above the desaturation threshold that is only
+/// partially desaturated, as a fraction of the threshold (avoids speckle)
+const DESATURATE_FADE: f32 = 0.25;
+
 // ============================================================
 // Error Types
 // ============================================================
@@ -125,6 +152,26 @@ impl ColorStats {
     }
 }
 
+/// Output color mode of a page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageColorMode {
+    /// Keep RGB (color plates, illustrations)
+    #[default]
+    Color,
+    /// Convert to 8-bit grayscale (text pages)
+    Grayscale,
+}
+
+impl PageColorMode {
+    /// Short name for reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            PageColorMode::Color => "color",
+            PageColorMode::Grayscale => "grayscale",
+        }
+    }
+}
+
 /// Bleed-through (裏写り) suppression parameters using HSV color space
 ///
 /// This structure defines the HSV color ranges that identify bleed-through
@@ -698,6 +745,52 @@ impl ColorAnalyzer {
         }
     }
 
+    /// Replace low-saturation colors with their gray level
+    ///
+    /// Saturation is chroma (`(max - min) / 255`), which stays low for the
+    /// dark rainbow fringes JPEG scans leave around black text, unlike HSV
+    /// saturation. Pixels below `threshold` become gray, pixels slightly
+    /// above it are blended, and saturated ink is kept. Returns the number
+    /// of changed pixels.
+    pub fn desaturate_below(image: &mut RgbImage, threshold: f32) -> u64 {
+        if threshold <= 0.0 {
+            return 0;
+        }
+        let fade_end = threshold * (1.0 + DESATURATE_FADE);
+        let mut changed = 0u64;
+
+        for pixel in image.pixels_mut() {
+            let Rgb([r, g, b]) = *pixel;
+            let chroma = (r.max(g).max(b) - r.min(g).min(b)) as f32 / 255.0;
+            if chroma == 0.0 || chroma >= fade_end {
+                continue;
+            }
+
+            // 1.0 below the threshold, fading to 0.0 at the end of the band
+            let weight = ((fade_end - chroma) / (fade_end - threshold)).min(1.0);
+            let gray = 0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64;
+            let blend = |c: u8| Self::clamp8(c as f64 + (gray - c as f64) * weight as f64);
+            *pixel = Rgb([blend(r), blend(g), blend(b)]);
+            changed += 1;
+        }
+        changed
+    }
+
+    /// Desaturate a page image file (see [`ColorAnalyzer::desaturate_below`])
+    pub fn desaturate_file(input: &Path, output: &Path, threshold: f32) -> Result<u64> {
+        if !input.exists() {
+            return Err(ColorStatsError::ImageNotFound(input.to_path_buf()));
+        }
+        let mut image = image::open(input)
+            .map_err(|e| ColorStatsError::InvalidImage(e.to_string()))?
+            .to_rgb8();
+        let changed = Self::desaturate_below(&mut image, threshold);
+        image
+            .save(output)
+            .map_err(|e| ColorStatsError::IoError(std::io::Error::other(e)))?;
+        Ok(changed)
+    }
+
     /// Analyze multiple pages and return statistics with outliers filtered per group
     pub fn analyze_book_pages(
         image_paths: &[PathBuf],
@@ -733,10 +826,103 @@ impl ColorAnalyzer {
         Ok((odd, even))
     }
 
+    /// Sampled saturation histogram of a page (dark pixels excluded)
+    pub fn saturation_histogram(image: &RgbImage) -> [u32; SATURATION_BINS] {
+        let mut histogram = [0u32; SATURATION_BINS];
+        let (w, h) = image.dimensions();
+
+        for y in (0..h).step_by(SAMPLE_STEP as usize) {
+            for x in (0..w).step_by(SAMPLE_STEP as usize) {
+                let Rgb([r, g, b]) = *image.get_pixel(x, y);
+                let (_, s, v) = Self::rgb_to_hsv(r, g, b);
+                if v >= COLOR_PAGE_MIN_VALUE {
+                    let bin = ((s * SATURATION_BINS as f32) as usize).min(SATURATION_BINS - 1);
+                    histogram[bin] += 1;
+                }
+            }
+        }
+        histogram
+    }
+
+    /// Fraction of sampled pixels that are visibly colored
+    pub fn colored_ratio(image: &RgbImage) -> f64 {
+        let histogram = Self::saturation_histogram(image);
+        let total: u32 = histogram.iter().sum();
+        if total == 0 {
+            return 0.0;
+        }
+        let first_colored = (COLOR_PAGE_MIN_SATURATION * SATURATION_BINS as f32).ceil() as usize;
+        let colored: u32 = histogram[first_colored..].iter().sum();
+        colored as f64 / total as f64
+    }
+
+    /// Decide whether a page keeps color (`min_ratio` of colored pixels)
+    pub fn detect_page_color_mode(image: &RgbImage, min_ratio: f64) -> PageColorMode {
+        Self::color_mode_for_ratio(Self::colored_ratio(image), min_ratio)
+    }
+
+    /// Per-page color decisions with their colored-pixel ratios
+    ///
+    /// Pages that cannot be read stay in color so nothing is lost.
+    pub fn detect_book_color_modes(image_paths: &[PathBuf], min_ratio: f64) -> Vec<(PageColorMode, f64)> {
+        image_paths
+            .par_iter()
+            .map(|path| match image::open(path) {
+                Ok(img) => {
+                    let ratio = Self::colored_ratio(&img.to_rgb8());
+                    (Self::color_mode_for_ratio(ratio, min_ratio), ratio)
+                }
+                Err(_) => (PageColorMode::Color, 1.0),
+            })
+            .collect()
+    }
+
+    /// Fraction of sampled pixels that are mid-tones or visibly colored
+    pub fn picture_ratio(image: &RgbImage) -> f64 {
+        let (w, h) = image.dimensions();
+        let mut total = 0u32;
+        let mut picture = 0u32;
+        for y in (0..h).step_by(SAMPLE_STEP as usize) {
+            for x in (0..w).step_by(SAMPLE_STEP as usize) {
+                let Rgb([r, g, b]) = *image.get_pixel(x, y);
+                let (_, s, v) = Self::rgb_to_hsv(r, g, b);
+                total += 1;
+                if PICTURE_MID_TONES.contains(&Self::luminance(r, g, b))
+                    || (s >= COLOR_PAGE_MIN_SATURATION && v >= COLOR_PAGE_MIN_VALUE)
+                {
+                    picture += 1;
+                }
+            }
+        }
+        if total == 0 {
+            return 0.0;
+        }
+        picture as f64 / total as f64
+    }
+
+    /// Per-page decision whether a page is predominantly a picture
+    /// (`min_ratio` of mid-tone or colored pixels)
+    ///
+    /// Pages that cannot be read count as text so they are still OCRed.
+    pub fn detect_picture_pages(image_paths: &[PathBuf], min_ratio: f64) -> Vec<bool> {
+        image_paths
+            .par_iter()
+            .map(|path| image::open(path).is_ok_and(|img| Self::picture_ratio(&img.to_rgb8()) >= min_ratio))
+            .collect()
+    }
+
     // ============================================================
     // Private Helper Functions
     // ============================================================
 
+    fn color_mode_for_ratio(ratio: f64, min_ratio: f64) -> PageColorMode {
+        if ratio >= min_ratio {
+            PageColorMode::Color
+        } else {
+            PageColorMode::Grayscale
+        }
+    }
+
     fn luminance(r: u8, g: u8, b: u8) -> u8 {
         (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64).round() as u8
     }
@@ -1405,4 +1591,152 @@ mod tests {
         assert!(params.bleed_suppression.enabled);
         assert_eq!(params.bleed_suppression.hue_min, 20.0);
     }
+
+    #[test]
+    fn test_text_page_detected_as_grayscale() {
+        // Yellowed paper with dark gray text
+        let mut img = RgbImage::from_pixel(200, 200, Rgb([235, 225, 200]));
+        for y in 40..60 {
+            for x in 20..180 {
+                img.put_pixel(x, y, Rgb([30, 30, 30]));
+            }
+        }
+        assert!(ColorAnalyzer::colored_ratio(&img) < DEFAULT_COLOR_PAGE_RATIO);
+        assert_eq!(
+            ColorAnalyzer::detect_page_color_mode(&img, DEFAULT_COLOR_PAGE_RATIO),
+            PageColorMode::Grayscale
+        );
+    }
+
+    #[test]
+    fn test_color_plate_detected_as_color() {
+        let mut img = RgbImage::from_pixel(200, 200, Rgb([250, 250, 250]));
+        for y in 50..150 {
+            for x in 50..150 {
+                img.put_pixel(x, y, Rgb([200, 40, 60]));
+            }
+        }
+        let histogram = ColorAnalyzer::saturation_histogram(&img);
+        assert_eq!(histogram.iter().sum::<u32>(), 50 * 50);
+        assert!(ColorAnalyzer::colored_ratio(&img) > 0.2);
+        assert_eq!(
+            ColorAnalyzer::detect_page_color_mode(&img, DEFAULT_COLOR_PAGE_RATIO),
+            PageColorMode::Color
+        );
+    }
+
+    /// Black glyphs with a rainbow JPEG fringe, and a saturated red stamp
+    fn fringed_text_page() -> RgbImage {
+        let fringe = [Rgb([70, 40, 95]), Rgb([40, 85, 60]), Rgb([95, 60, 40]), Rgb([175, 200, 215])];
+        let mut img = RgbImage::from_pixel(120, 60, Rgb([250, 250, 250]));
+        for glyph in 0..4 {
+            let x0 = 10 + glyph * 20;
+            for y in 10..30 {
+                for x in x0..x0 + 12 {
+                    let edge = x == x0 || x == x0 + 11 || y == 10 || y == 29;
+                    let color = if edge { fringe[((x + y) % 4) as usize] } else { Rgb([15, 15, 18]) };
+                    img.put_pixel(x, y, color);
+                }
+            }
+        }
+        for y in 40..55 {
+            for x in 10..60 {
+                img.put_pixel(x, y, Rgb([210, 30, 40]));
+            }
+        }
+        img
+    }
+
+    #[test]
+    fn test_desaturate_below_removes_text_fringe() {
+        let mut img = fringed_text_page();
+        let changed = ColorAnalyzer::desaturate_below(&mut img, 0.25);
+        assert!(changed > 0);
+
+        // Fringe pixels around the glyphs become neutral gray
+        for glyph in 0..4u32 {
+            let x0 = 10 + glyph * 20;
+            for (x, y) in [(x0, 10), (x0 + 11, 20), (x0 + 5, 29)] {
+                let Rgb([r, g, b]) = *img.get_pixel(x, y);
+                assert!(r == g && g == b, "fringe at ({}, {}) still colored: {:?}", x, y, (r, g, b));
+            }
+        }
+        // Saturated ink and paper are untouched
+        assert_eq!(*img.get_pixel(30, 45), Rgb([210, 30, 40]));
+        assert_eq!(*img.get_pixel(100, 50), Rgb([250, 250, 250]));
+        // Colored content still makes the page a color page
+        assert_eq!(
+            ColorAnalyzer::detect_page_color_mode(&img, DEFAULT_COLOR_PAGE_RATIO),
+            PageColorMode::Color
+        );
+
+        // Threshold 0 disables it
+        let mut img = fringed_text_page();
+        assert_eq!(ColorAnalyzer::desaturate_below(&mut img, 0.0), 0);
+        assert_eq!(img, fringed_text_page());
+    }
+
+    #[test]
+    fn test_desaturate_fade_band() {
+        // Chroma 0.27 lies in the fade band above 0.25: partially desaturated
+        let mut img = RgbImage::from_pixel(1, 1, Rgb([150, 150, 219]));
+        ColorAnalyzer::desaturate_below(&mut img, 0.25);
+        let Rgb([r, _, b]) = *img.get_pixel(0, 0);
+        assert!(b > r && b < 219 && r > 150, "{:?}", img.get_pixel(0, 0));
+    }
+
+    #[test]
+    fn test_desaturate_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("page.png");
+        let output = dir.path().join("out.png");
+        fringed_text_page().save(&input).unwrap();
+
+        let changed = ColorAnalyzer::desaturate_file(&input, &output, 0.25).unwrap();
+        assert!(changed > 0);
+        assert!(output.exists());
+        assert!(matches!(
+            ColorAnalyzer::desaturate_file(&dir.path().join("missing.png"), &output, 0.25),
+            Err(ColorStatsError::ImageNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_picture_page_detection() {
+        // Gray photo plate with a white border
+        let photo = RgbImage::from_fn(200, 200, |x, y| {
+            if (20..180).contains(&x) && (20..180).contains(&y) {
+                let level = (40 + (x + y) % 160) as u8;
+                Rgb([level, level, level])
+            } else {
+                Rgb([250, 250, 250])
+            }
+        });
+        assert!(ColorAnalyzer::picture_ratio(&photo) >= DEFAULT_PICTURE_PAGE_RATIO);
+
+        // Text glyphs (with colored JPEG fringe and a stamp) stay a text page
+        let text = fringed_text_page();
+        assert!(ColorAnalyzer::picture_ratio(&text) < DEFAULT_PICTURE_PAGE_RATIO);
+        assert_eq!(ColorAnalyzer::picture_ratio(&RgbImage::new(0, 0)), 0.0);
+
+        let dir = tempfile::tempdir().unwrap();
+        let photo_path = dir.path().join("photo.png");
+        let text_path = dir.path().join("text.png");
+        photo.save(&photo_path).unwrap();
+        text.save(&text_path).unwrap();
+        let pages = [photo_path, text_path, PathBuf::from("/nonexistent/page.png")];
+        assert_eq!(
+            ColorAnalyzer::detect_picture_pages(&pages, DEFAULT_PICTURE_PAGE_RATIO),
+            vec![true, false, false]
+        );
+    }
+
+    #[test]
+    fn test_book_color_modes_unreadable_page_stays_color() {
+        let modes = ColorAnalyzer::detect_book_color_modes(
+            &[PathBuf::from("/nonexistent/page.png")],
+            DEFAULT_COLOR_PAGE_RATIO,
+        );
+        assert_eq!(modes[0].0, PageColorMode::Color);
+    }
 }
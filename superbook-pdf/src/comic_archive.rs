@@ -0,0 +1,595 @@
+//! Comic book archive (CBZ/CBR) input and CBZ output
+//!
+//! Manga and comic scans are commonly distributed as CBZ (ZIP) or CBR (RAR)
+//! archives of page images. The pages of an archive are its image entries
+//! in natural order ("page2" before "page10", directories included in the
+//! comparison); metadata files (`ComicInfo.xml`, macOS/Windows leftovers)
+//! are ignored and any other entry is rejected, as are entries whose names
+//! differ only in case.
+//!
+//! CBZ is read and written with the `zip` crate. CBR is listed and read
+//! with `unrar`, or `bsdtar` (libarchive) when unrar is not installed.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::ComicArchive;
+//!
+//! let archive = ComicArchive::open(std::path::Path::new("volume01.cbz")).unwrap();
+//! let pages = archive.extract_pages(std::path::Path::new("extracted"), None).unwrap();
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// RAR extractor (preferred for CBR)
+pub const UNRAR_TOOL: &str = "unrar";
+
+/// libarchive's tar, used for CBR when unrar is unavailable
+pub const BSDTAR_TOOL: &str = "bsdtar";
+
+/// Input file extensions recognized as comic archives
+pub const ARCHIVE_EXTENSIONS: [&str; 2] = ["cbz", "cbr"];
+
+/// Extensions of archive entries treated as pages
+const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "webp", "gif", "bmp", "tif", "tiff"];
+
+/// Entry file names (lowercase) that are skipped rather than rejected
+const IGNORED_FILE_NAMES: [&str; 4] = ["comicinfo.xml", ".ds_store", "thumbs.db", "desktop.ini"];
+
+/// Directory (lowercase, with slash) whose entries are skipped
+const IGNORED_DIRECTORY: &str = "__macosx/";
+
+/// Comic archive error types
+#[derive(Debug, Error)]
+pub enum ComicArchiveError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid archive {path}: {reason}")]
+    InvalidArchive { path: PathBuf, reason: String },
+
+    #[error("Archive entry is not an image: {0}")]
+    NonImageEntry(String),
+
+    #[error("Duplicate page entry: {0}")]
+    DuplicateEntry(String),
+
+    #[error("Archive contains no page images: {0}")]
+    NoPages(PathBuf),
+
+    #[error("Unsupported archive entry {entry}: {reason}")]
+    UnsupportedEntry { entry: String, reason: String },
+
+    #[error("No RAR extractor found (install {} or {})", UNRAR_TOOL, BSDTAR_TOOL)]
+    ExtractorNotFound,
+
+    #[error("Failed to decode page {entry}: {reason}")]
+    DecodeFailed { entry: String, reason: String },
+}
+
+pub type Result<T> = std::result::Result<T, ComicArchiveError>;
+
+/// Comic archive container
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    /// ZIP archive
+    Cbz,
+    /// RAR archive
+    Cbr,
+}
+
+impl ArchiveFormat {
+    /// Format by file extension (case-insensitive)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_string_lossy().to_lowercase().as_str() {
+            "cbz" => Some(ArchiveFormat::Cbz),
+            "cbr" => Some(ArchiveFormat::Cbr),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `path` has a comic archive extension
+pub fn is_comic_archive(path: &Path) -> bool {
+    ArchiveFormat::from_path(path).is_some()
+}
+
+/// Output container of a converted book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookFormat {
+    /// PDF (with OCR text layer and metadata)
+    #[default]
+    Pdf,
+    /// CBZ of the final page images (with `ComicInfo.xml`)
+    Cbz,
+    /// Directory of the final page images with a `manifest.json`
+    /// (for external OCR; see [`crate::page_manifest`])
+    Images,
+}
+
+impl BookFormat {
+    /// Whether this is the default PDF output
+    pub fn is_pdf(&self) -> bool {
+        *self == BookFormat::Pdf
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            BookFormat::Pdf => "PDF",
+            BookFormat::Cbz => "CBZ",
+            BookFormat::Images => "page images",
+        }
+    }
+
+    /// Output file extension (page image extension for [`BookFormat::Images`])
+    pub fn extension(self) -> &'static str {
+        match self {
+            BookFormat::Pdf => "pdf",
+            BookFormat::Cbz => "cbz",
+            BookFormat::Images => "png",
+        }
+    }
+
+    /// Whether the output is a directory rather than a file
+    pub fn is_directory(self) -> bool {
+        self == BookFormat::Images
+    }
+}
+
+/// An opened comic archive with its validated page order
+#[derive(Debug)]
+pub struct ComicArchive {
+    path: PathBuf,
+    format: ArchiveFormat,
+    pages: Vec<String>,
+    zip_entries: HashMap<String, usize>,
+    rar_tool: Option<&'static str>,
+    tools: crate::tools::ToolContext,
+}
+
+impl ComicArchive {
+    /// Open an archive and validate its entries
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::open_with_tools(path, crate::tools::ToolContext::default())
+    }
+
+    /// Open an archive, running the RAR extractor in `tools`
+    pub fn open_with_tools(path: &Path, tools: crate::tools::ToolContext) -> Result<Self> {
+        let format = ArchiveFormat::from_path(path).ok_or_else(|| ComicArchiveError::InvalidArchive {
+            path: path.to_path_buf(),
+            reason: "not a .cbz or .cbr file".to_string(),
+        })?;
+
+        let (names, zip_entries, rar_tool) = match format {
+            ArchiveFormat::Cbz => {
+                let entries = read_zip_directory(path)?;
+                let names = entries.iter().map(|(name, _)| name.clone()).collect();
+                (names, entries.into_iter().collect(), None)
+            }
+            ArchiveFormat::Cbr => {
+                let tool = [UNRAR_TOOL, BSDTAR_TOOL]
+                    .into_iter()
+                    .find(|tool| crate::tools::is_available(tool))
+                    .ok_or(ComicArchiveError::ExtractorNotFound)?;
+                (list_rar_entries(path, tool, &tools)?, HashMap::new(), Some(tool))
+            }
+        };
+
+        let pages = order_pages(&names)?;
+        if pages.is_empty() {
+            return Err(ComicArchiveError::NoPages(path.to_path_buf()));
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            format,
+            pages,
+            zip_entries,
+            rar_tool,
+            tools,
+        })
+    }
+
+    /// Archive container format
+    pub fn format(&self) -> ArchiveFormat {
+        self.format
+    }
+
+    /// Page entry names in page order
+    pub fn pages(&self) -> &[String] {
+        &self.pages
+    }
+
+    /// Number of pages
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Raw bytes of page `index` (0-based)
+    pub fn read_page(&self, index: usize) -> Result<Vec<u8>> {
+        let name = self.pages.get(index).ok_or_else(|| ComicArchiveError::InvalidArchive {
+            path: self.path.clone(),
+            reason: format!("page {} out of range ({} pages)", index + 1, self.pages.len()),
+        })?;
+        match self.rar_tool {
+            Some(tool) => read_rar_entry(&self.path, tool, name, &self.tools),
+            None => read_zip_entry(&self.path, name, self.zip_entries[name]),
+        }
+    }
+
+    /// Decode page `index` and write it as `page_NNNN.png` (1-based) in `out_dir`
+    pub fn extract_page(&self, index: usize, out_dir: &Path) -> Result<PathBuf> {
+        let data = self.read_page(index)?;
+        let image = image::load_from_memory(&data).map_err(|e| ComicArchiveError::DecodeFailed {
+            entry: self.pages[index].clone(),
+            reason: e.to_string(),
+        })?;
+        let path = out_dir.join(format!("page_{:04}.png", index + 1));
+        image.save(&path).map_err(|e| ComicArchiveError::DecodeFailed {
+            entry: self.pages[index].clone(),
+            reason: e.to_string(),
+        })?;
+        Ok(path)
+    }
+
+    /// Extract the first `limit` pages (all when `None`) as PNG files
+    pub fn extract_pages(&self, out_dir: &Path, limit: Option<usize>) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(out_dir)?;
+        let count = limit.map_or(self.pages.len(), |limit| limit.min(self.pages.len()));
+        (0..count).map(|index| self.extract_page(index, out_dir)).collect()
+    }
+}
+
+/// Validate entry names and return the page entries in natural order
+///
+/// Directories and known metadata files are skipped; any other non-image
+/// entry, or two entries that differ only in case, is an error.
+fn order_pages(names: &[String]) -> Result<Vec<String>> {
+    let directories: HashSet<String> = names
+        .iter()
+        .flat_map(|name| {
+            let parts: Vec<&str> = name.split('/').collect();
+            (1..parts.len()).map(move |n| parts[..n].join("/"))
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut pages = Vec::new();
+    for name in names {
+        let lower = name.to_lowercase();
+        let file_name = lower.rsplit('/').next().unwrap_or_default();
+        if name.ends_with('/')
+            || directories.contains(name.as_str())
+            || lower.starts_with(IGNORED_DIRECTORY)
+            || lower.contains(&format!("/{}", IGNORED_DIRECTORY))
+            || IGNORED_FILE_NAMES.contains(&file_name)
+        {
+            continue;
+        }
+        let is_image = Path::new(file_name)
+            .extension()
+            .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_string_lossy().as_ref()));
+        if !is_image {
+            return Err(ComicArchiveError::NonImageEntry(name.clone()));
+        }
+        if !seen.insert(lower) {
+            return Err(ComicArchiveError::DuplicateEntry(name.clone()));
+        }
+        pages.push(name.clone());
+    }
+
+    pages.sort_by(|a, b| natural_cmp(a, b));
+    Ok(pages)
+}
+
+/// Compare names with digit runs ordered numerically and text case-insensitively
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    fn chunks(s: &str) -> Vec<(bool, String)> {
+        let mut chunks: Vec<(bool, String)> = Vec::new();
+        for c in s.chars() {
+            let digit = c.is_ascii_digit();
+            match chunks.last_mut() {
+                Some((is_digit, chunk)) if *is_digit == digit => chunk.push(c),
+                _ => chunks.push((digit, c.to_string())),
+            }
+        }
+        chunks
+    }
+
+    let (a_chunks, b_chunks) = (chunks(a), chunks(b));
+    for ((a_digit, a_chunk), (b_digit, b_chunk)) in a_chunks.iter().zip(&b_chunks) {
+        let ordering = if *a_digit && *b_digit {
+            let (a_num, b_num) = (a_chunk.trim_start_matches('0'), b_chunk.trim_start_matches('0'));
+            a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num))
+        } else {
+            a_chunk.to_lowercase().cmp(&b_chunk.to_lowercase())
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    a_chunks.len().cmp(&b_chunks.len()).then_with(|| a.cmp(b))
+}
+
+fn invalid(path: &Path, reason: impl Into<String>) -> ComicArchiveError {
+    ComicArchiveError::InvalidArchive {
+        path: path.to_path_buf(),
+        reason: reason.into(),
+    }
+}
+
+fn zip_error(path: &Path, error: zip::result::ZipError) -> ComicArchiveError {
+    match error {
+        zip::result::ZipError::Io(e) => ComicArchiveError::IoError(e),
+        e => invalid(path, e.to_string()),
+    }
+}
+
+/// Entry names of a ZIP file with their indices in the archive
+fn read_zip_directory(path: &Path) -> Result<Vec<(String, usize)>> {
+    let archive = zip::ZipArchive::new(File::open(path)?).map_err(|e| zip_error(path, e))?;
+    Ok((0..archive.len())
+        .filter_map(|index| Some((archive.name_for_index(index)?.replace('\\', "/"), index)))
+        .collect())
+}
+
+/// Read and decompress one ZIP entry (the reader checks its CRC)
+///
+/// The declared size comes from the archive, so nothing is reserved up
+/// front: inflation stops one byte past it (a deflate bomb fails the size
+/// check instead of filling memory).
+fn read_zip_entry(path: &Path, name: &str, index: usize) -> Result<Vec<u8>> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?).map_err(|e| zip_error(path, e))?;
+    let entry = archive.by_index(index).map_err(|e| match e {
+        zip::result::ZipError::UnsupportedArchive(reason) => ComicArchiveError::UnsupportedEntry {
+            entry: name.to_string(),
+            reason: reason.to_string(),
+        },
+        e => zip_error(path, e),
+    })?;
+    let size = entry.size();
+    let mut data = Vec::new();
+    entry
+        .take(size + 1)
+        .read_to_end(&mut data)
+        .map_err(|e| invalid(path, format!("failed to read {}: {}", name, e)))?;
+    if data.len() as u64 != size {
+        return Err(invalid(path, format!("size mismatch in {}", name)));
+    }
+    Ok(data)
+}
+
+/// List RAR entry names with `tool`
+fn list_rar_entries(path: &Path, tool: &'static str, tools: &crate::tools::ToolContext) -> Result<Vec<String>> {
+    let mut command = tools.command(tool);
+    match tool {
+        UNRAR_TOOL => command.args(["lb", "--"]).arg(path),
+        _ => command.arg("-tf").arg(path),
+    };
+    let output = tools.output(&mut command)?;
+    if !output.status.success() {
+        return Err(invalid(path, String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.replace('\\', "/"))
+        .collect())
+}
+
+/// Read one RAR entry to memory with `tool`
+fn read_rar_entry(path: &Path, tool: &'static str, name: &str, tools: &crate::tools::ToolContext) -> Result<Vec<u8>> {
+    let mut command = tools.command(tool);
+    match tool {
+        UNRAR_TOOL => command.args(["p", "-inul", "--"]).arg(path).arg(name),
+        _ => command.arg("-xOf").arg(path).arg("--").arg(name),
+    };
+    let output = tools.output(&mut command)?;
+    if !output.status.success() || output.stdout.is_empty() {
+        return Err(invalid(path, format!("failed to read {}", name)));
+    }
+    Ok(output.stdout)
+}
+
+/// `ComicInfo.xml` describing a converted book
+pub fn comic_info_xml(metadata: &crate::PdfMetadata, page_count: usize, right_to_left: bool) -> String {
+    let escape = |text: &str| text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+    let mut fields = Vec::new();
+    if let Some(title) = &metadata.title {
+        fields.push(format!("  <Title>{}</Title>", escape(title)));
+    }
+    if let Some(author) = &metadata.author {
+        fields.push(format!("  <Writer>{}</Writer>", escape(author)));
+    }
+    if let Some(subject) = &metadata.subject {
+        fields.push(format!("  <Summary>{}</Summary>", escape(subject)));
+    }
+    fields.push(format!("  <PageCount>{}</PageCount>", page_count));
+    if right_to_left {
+        fields.push("  <Manga>YesAndRightToLeft</Manga>".to_string());
+    }
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<ComicInfo xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xmlns:xsd=\"http://www.w3.org/2001/XMLSchema\">\n{}\n</ComicInfo>\n",
+        fields.join("\n")
+    )
+}
+
+/// Write `pages` (in order, named `0001.<ext>`...) and an optional
+/// `ComicInfo.xml` into an uncompressed CBZ; returns the file size
+///
+/// Entries are stored rather than deflated: page images are already
+/// compressed. Timestamps are fixed so identical pages give identical files.
+pub fn write_cbz(pages: &[PathBuf], output: &Path, comic_info: Option<&str>) -> Result<u64> {
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .last_modified_time(zip::DateTime::default());
+    let mut writer = zip::ZipWriter::new(BufWriter::new(File::create(output)?));
+    for (index, page) in pages.iter().enumerate() {
+        let extension = page.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let data = std::fs::read(page)?;
+        writer
+            .start_file(
+                format!("{:04}.{}", index + 1, extension),
+                options.large_file(data.len() as u64 >= u32::MAX as u64),
+            )
+            .map_err(|e| zip_error(output, e))?;
+        writer.write_all(&data)?;
+    }
+    if let Some(xml) = comic_info {
+        writer.start_file("ComicInfo.xml", options).map_err(|e| zip_error(output, e))?;
+        writer.write_all(xml.as_bytes())?;
+    }
+    writer.finish().map_err(|e| zip_error(output, e))?.flush()?;
+
+    Ok(std::fs::metadata(output)?.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn page_image(dir: &Path, name: &str, shade: u8) -> PathBuf {
+        let path = dir.join(name);
+        image::GrayImage::from_pixel(8, 12, image::Luma([shade])).save(&path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_archive_format_from_path() {
+        assert_eq!(ArchiveFormat::from_path(Path::new("a.cbz")), Some(ArchiveFormat::Cbz));
+        assert_eq!(ArchiveFormat::from_path(Path::new("a.CBR")), Some(ArchiveFormat::Cbr));
+        assert!(!is_comic_archive(Path::new("a.pdf")));
+        assert_eq!(BookFormat::default().extension(), "pdf");
+        assert_eq!(serde_json::to_string(&BookFormat::Cbz).unwrap(), "\"cbz\"");
+    }
+
+    #[test]
+    fn test_natural_page_order() {
+        let pages = order_pages(&names(&["p10.jpg", "p2.jpg", "P1.png", "ch2/", "ch2/p1.jpg", "ch10/p1.jpg"])).unwrap();
+        assert_eq!(pages, names(&["ch2/p1.jpg", "ch10/p1.jpg", "P1.png", "p2.jpg", "p10.jpg"]));
+        assert!(natural_cmp("007", "7").is_ne());
+    }
+
+    #[test]
+    fn test_entry_validation() {
+        // Metadata and directories are skipped
+        let pages = order_pages(&names(&["ComicInfo.xml", "__MACOSX/._1.jpg", "vol", "vol/1.jpg", "Thumbs.db"]))
+            .unwrap();
+        assert_eq!(pages, names(&["vol/1.jpg"]));
+
+        assert!(matches!(
+            order_pages(&names(&["1.jpg", "notes.txt"])),
+            Err(ComicArchiveError::NonImageEntry(name)) if name == "notes.txt"
+        ));
+        assert!(matches!(
+            order_pages(&names(&["a/1.jpg", "A/1.JPG"])),
+            Err(ComicArchiveError::DuplicateEntry(_))
+        ));
+    }
+
+    #[test]
+    fn test_cbz_round_trip() {
+        let dir = tempdir().unwrap();
+        let pages = vec![page_image(dir.path(), "x.png", 10), page_image(dir.path(), "y.png", 200)];
+        let cbz = dir.path().join("book.cbz");
+        let info = comic_info_xml(
+            &crate::PdfMetadata {
+                title: Some("A & B".to_string()),
+                ..Default::default()
+            },
+            2,
+            true,
+        );
+        let size = write_cbz(&pages, &cbz, Some(&info)).unwrap();
+        assert_eq!(size, std::fs::metadata(&cbz).unwrap().len());
+        let again = dir.path().join("again.cbz");
+        write_cbz(&pages, &again, Some(&info)).unwrap();
+        assert_eq!(std::fs::read(&again).unwrap(), std::fs::read(&cbz).unwrap());
+
+        let archive = ComicArchive::open(&cbz).unwrap();
+        assert_eq!(archive.format(), ArchiveFormat::Cbz);
+        assert_eq!(archive.pages(), names(&["0001.png", "0002.png"]).as_slice());
+        assert_eq!(archive.read_page(1).unwrap(), std::fs::read(&pages[1]).unwrap());
+
+        let extracted = archive.extract_pages(&dir.path().join("out"), Some(1)).unwrap();
+        assert_eq!(extracted.len(), 1);
+        assert_eq!(image::open(&extracted[0]).unwrap().to_luma8().get_pixel(0, 0).0, [10]);
+
+        let (name, index) = read_zip_directory(&cbz).unwrap().remove(2);
+        let xml = String::from_utf8(read_zip_entry(&cbz, &name, index).unwrap()).unwrap();
+        assert!(xml.contains("<Title>A &amp; B</Title>"));
+        assert!(xml.contains("<Manga>YesAndRightToLeft</Manga>"));
+    }
+
+    #[test]
+    fn test_deflated_entries_and_rejections() {
+        fn zip_with(entries: &[(&str, &[u8])]) -> Vec<u8> {
+            let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+            let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+            for (name, data) in entries {
+                writer.start_file(*name, options).unwrap();
+                writer.write_all(data).unwrap();
+            }
+            writer.finish().unwrap().into_inner()
+        }
+
+        let dir = tempdir().unwrap();
+        let png = std::fs::read(page_image(dir.path(), "p.png", 128)).unwrap();
+
+        let good = dir.path().join("good.cbz");
+        std::fs::write(&good, zip_with(&[("2.png", &png), ("10.png", &png), ("ComicInfo.xml", b"<x/>")])).unwrap();
+        let archive = ComicArchive::open(&good).unwrap();
+        assert_eq!(archive.pages(), names(&["2.png", "10.png"]).as_slice());
+        assert_eq!(archive.read_page(0).unwrap(), png);
+
+        let mixed = dir.path().join("mixed.cbz");
+        std::fs::write(&mixed, zip_with(&[("1.png", &png), ("readme.txt", b"hi")])).unwrap();
+        assert!(matches!(ComicArchive::open(&mixed), Err(ComicArchiveError::NonImageEntry(_))));
+
+        let empty = dir.path().join("empty.cbz");
+        std::fs::write(&empty, zip_with(&[("ComicInfo.xml", b"<x/>")])).unwrap();
+        assert!(matches!(ComicArchive::open(&empty), Err(ComicArchiveError::NoPages(_))));
+
+        let garbage = dir.path().join("garbage.cbz");
+        std::fs::write(&garbage, b"not a zip").unwrap();
+        assert!(matches!(ComicArchive::open(&garbage), Err(ComicArchiveError::InvalidArchive { .. })));
+
+        // Sizes and checksums in the central directory are not trusted
+        let zeros = vec![0u8; 1 << 20];
+        let bytes = zip_with(&[("1.png", &zeros)]);
+        let central = bytes.windows(4).rposition(|w| w == [0x50, 0x4b, 0x01, 0x02]).unwrap();
+        let patched = |field: usize, value: u32| {
+            let mut bytes = bytes.clone();
+            bytes[central + field..central + field + 4].copy_from_slice(&value.to_le_bytes());
+            bytes
+        };
+
+        // A 1 MiB deflate bomb declared as 16 bytes stops after 17 bytes
+        let bomb = dir.path().join("bomb.cbz");
+        std::fs::write(&bomb, patched(24, 16)).unwrap();
+        let (name, index) = read_zip_directory(&bomb).unwrap().remove(0);
+        assert!(matches!(
+            read_zip_entry(&bomb, &name, index),
+            Err(ComicArchiveError::InvalidArchive { reason, .. }) if reason.contains("size mismatch")
+        ));
+
+        // Corrupt CRC
+        let corrupt = dir.path().join("corrupt.cbz");
+        std::fs::write(&corrupt, patched(16, 0xDEAD_BEEF)).unwrap();
+        let (name, index) = read_zip_directory(&corrupt).unwrap().remove(0);
+        assert!(matches!(
+            read_zip_entry(&corrupt, &name, index),
+            Err(ComicArchiveError::InvalidArchive { reason, .. }) if reason.contains("failed to read")
+        ));
+    }
+}
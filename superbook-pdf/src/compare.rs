@@ -0,0 +1,531 @@
+//! Option comparison module
+//!
+//! Renders a single page under several named option profiles and composes
+//! the results into one labeled grid image, so settings such as DPI,
+//! upscaling or marker removal can be compared side by side.
+//!
+//! # Profile syntax
+//!
+//! A profile is `NAME:KEY=VALUE[,KEY=VALUE...]` where each key is a
+//! [`PipelineConfig`] field. Values are parsed as JSON when possible and
+//! fall back to plain strings, so `dpi=450`, `upscale=false` and
+//! `auto_contrast=clahe` all work. A bare `NAME` uses the base settings.
+//!
+//! # Example
+//!
+//! ```rust
+//! use superbook_pdf::compare::CompareProfile;
+//! use superbook_pdf::PipelineConfig;
+//!
+//! let base = PipelineConfig::default();
+//! let profile = CompareProfile::parse("hi-res:dpi=450,upscale=false", &base).unwrap();
+//! assert_eq!(profile.name, "hi-res");
+//! assert_eq!(profile.config.dpi, 450);
+//! assert!(!profile.config.upscale);
+//! ```
+
+use image::{imageops::FilterType, Rgb, RgbImage};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::pipeline::{PdfPipeline, PipelineConfig, PipelineError, ProgressCallback};
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Default height of each grid cell (pixels, excluding the label bar)
+pub const DEFAULT_CELL_HEIGHT: u32 = 1200;
+
+/// Space between grid cells and around the border
+const CELL_GAP: u32 = 16;
+
+/// Scale factor applied to the 5x7 label font
+const LABEL_SCALE: u32 = 4;
+
+/// Padding above and below label text
+const LABEL_PADDING: u32 = 8;
+
+/// Glyph dimensions of the built-in label font
+const GLYPH_WIDTH: u32 = 5;
+const GLYPH_HEIGHT: u32 = 7;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const LABEL_BACKGROUND: Rgb<u8> = Rgb([230, 230, 230]);
+const LABEL_FOREGROUND: Rgb<u8> = Rgb([0, 0, 0]);
+
+// ============================================================
+// Error Types
+// ============================================================
+
+/// Option comparison error
+#[derive(Debug, Error)]
+pub enum CompareError {
+    #[error("Invalid profile '{0}': {1}")]
+    InvalidProfile(String, String),
+
+    #[error("No profiles given")]
+    NoProfiles,
+
+    #[error("Profile '{0}' failed: {1}")]
+    ProfileFailed(String, PipelineError),
+
+    #[error("Image error: {0}")]
+    ImageError(String),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, CompareError>;
+
+// ============================================================
+// Profiles
+// ============================================================
+
+/// A named set of pipeline options to compare
+#[derive(Debug, Clone)]
+pub struct CompareProfile {
+    /// Label shown above the rendered page
+    pub name: String,
+    /// Pipeline configuration used for this profile
+    pub config: PipelineConfig,
+}
+
+impl CompareProfile {
+    /// Create a profile from an explicit configuration
+    pub fn new(name: impl Into<String>, config: PipelineConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+        }
+    }
+
+    /// Parse `NAME:KEY=VALUE[,KEY=VALUE...]` on top of `base`
+    pub fn parse(spec: &str, base: &PipelineConfig) -> Result<Self> {
+        let invalid = |msg: String| CompareError::InvalidProfile(spec.to_string(), msg);
+
+        let (name, overrides) = match spec.split_once(':') {
+            Some((name, overrides)) => (name.trim(), overrides),
+            None => (spec.trim(), ""),
+        };
+        if name.is_empty() {
+            return Err(invalid("missing profile name".to_string()));
+        }
+
+        let fields = parse_overrides(overrides).map_err(invalid)?;
+        let config = apply_overrides(base, &fields).map_err(invalid)?;
+        Ok(Self::new(name, config))
+    }
+}
+
+/// Parse `KEY=VALUE[,KEY=VALUE...]` into option fields
+///
+/// Values are parsed as JSON when possible and fall back to plain strings.
+pub fn parse_overrides(overrides: &str) -> std::result::Result<serde_json::Map<String, serde_json::Value>, String> {
+    let mut fields = serde_json::Map::new();
+    for pair in split_overrides(overrides) {
+        let (key, raw) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", pair))?;
+        let raw = raw.trim();
+        let parsed = serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()));
+        fields.insert(key.trim().to_string(), parsed);
+    }
+    Ok(fields)
+}
+
+/// Replace [`PipelineConfig`] fields of `base` with `fields`
+///
+/// Names that are not serialized config fields are rejected.
+pub fn apply_overrides(
+    base: &PipelineConfig,
+    fields: &serde_json::Map<String, serde_json::Value>,
+) -> std::result::Result<PipelineConfig, String> {
+    let mut value = serde_json::to_value(base).map_err(|e| e.to_string())?;
+    let config_fields = value
+        .as_object_mut()
+        .ok_or_else(|| "base configuration is not an object".to_string())?;
+    for (key, field) in fields {
+        if !config_fields.contains_key(key) {
+            return Err(format!("unknown option '{}'", key));
+        }
+        config_fields.insert(key.clone(), field.clone());
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+/// Split `a=1,b=[1,2]` on top-level commas only
+fn split_overrides(overrides: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    for (i, c) in overrides.char_indices() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&overrides[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&overrides[start..]);
+    parts.into_iter().map(str::trim).filter(|p| !p.is_empty()).collect()
+}
+
+// ============================================================
+// Comparison
+// ============================================================
+
+/// Comparison output
+#[derive(Debug, Clone)]
+pub struct CompareResult {
+    /// Path of the composed grid image
+    pub output_path: PathBuf,
+    /// Rendered page for each profile, in profile order
+    pub pages: Vec<(String, PathBuf)>,
+}
+
+/// Renders one page under multiple profiles and composes a grid
+pub struct OptionComparer {
+    cell_height: u32,
+    columns: usize,
+}
+
+impl Default for OptionComparer {
+    fn default() -> Self {
+        Self {
+            cell_height: DEFAULT_CELL_HEIGHT,
+            columns: 0,
+        }
+    }
+}
+
+impl OptionComparer {
+    /// Create a comparer with default layout
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder pattern: set the cell height (pixels)
+    pub fn with_cell_height(mut self, height: u32) -> Self {
+        self.cell_height = height.max(1);
+        self
+    }
+
+    /// Builder pattern: set the number of grid columns (0 = auto)
+    pub fn with_columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Process `page_index` (0-based) of `input` under each profile and
+    /// write the labeled grid to `output_path`
+    pub fn run<P: ProgressCallback>(
+        &self,
+        input: &Path,
+        page_index: usize,
+        profiles: &[CompareProfile],
+        work_dir: &Path,
+        output_path: &Path,
+        progress: &P,
+    ) -> Result<CompareResult> {
+        if profiles.is_empty() {
+            return Err(CompareError::NoProfiles);
+        }
+
+        let mut pages = Vec::with_capacity(profiles.len());
+        for (i, profile) in profiles.iter().enumerate() {
+            progress.on_step_start(&format!(
+                "Profile {}/{}: {}",
+                i + 1,
+                profiles.len(),
+                profile.name
+            ));
+            let profile_dir = work_dir.join(format!("profile_{:02}", i));
+            let page = PdfPipeline::new(profile.config.clone())
+                .process_page(input, page_index, &profile_dir, progress)
+                .map_err(|e| CompareError::ProfileFailed(profile.name.clone(), e))?;
+            progress.on_step_complete(&profile.name, &page.display().to_string());
+            pages.push((profile.name.clone(), page));
+        }
+
+        let tiles = pages
+            .iter()
+            .map(|(name, path)| {
+                image::open(path)
+                    .map(|img| (name.clone(), img.to_rgb8()))
+                    .map_err(|e| CompareError::ImageError(e.to_string()))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if let Some(parent) = output_path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        self.compose(&tiles)
+            .save(output_path)
+            .map_err(|e| CompareError::ImageError(e.to_string()))?;
+
+        Ok(CompareResult {
+            output_path: output_path.to_path_buf(),
+            pages,
+        })
+    }
+
+    /// Compose labeled tiles into a grid
+    ///
+    /// Each tile is scaled to the cell height and centered in a cell as wide
+    /// as the widest scaled tile, with its label in a bar above it.
+    pub fn compose(&self, tiles: &[(String, RgbImage)]) -> RgbImage {
+        let scaled: Vec<(&str, RgbImage)> = tiles
+            .iter()
+            .map(|(name, img)| {
+                let width = (img.width() as u64 * self.cell_height as u64 / img.height().max(1) as u64)
+                    .max(1) as u32;
+                let resized = image::imageops::resize(img, width, self.cell_height, FilterType::Triangle);
+                (name.as_str(), resized)
+            })
+            .collect();
+
+        let columns = self.column_count(scaled.len());
+        let rows = scaled.len().div_ceil(columns).max(1);
+        let label_height = GLYPH_HEIGHT * LABEL_SCALE + LABEL_PADDING * 2;
+        let cell_width = scaled.iter().map(|(_, img)| img.width()).max().unwrap_or(1);
+        let cell_height = self.cell_height + label_height;
+
+        let width = columns as u32 * (cell_width + CELL_GAP) + CELL_GAP;
+        let height = rows as u32 * (cell_height + CELL_GAP) + CELL_GAP;
+        let mut canvas = RgbImage::from_pixel(width, height, BACKGROUND);
+
+        for (i, (name, tile)) in scaled.iter().enumerate() {
+            let x = CELL_GAP + (i % columns) as u32 * (cell_width + CELL_GAP);
+            let y = CELL_GAP + (i / columns) as u32 * (cell_height + CELL_GAP);
+
+            fill_rect(&mut canvas, x, y, cell_width, label_height, LABEL_BACKGROUND);
+            draw_label(&mut canvas, name, x + LABEL_PADDING, y + LABEL_PADDING, cell_width - LABEL_PADDING);
+
+            let offset = (cell_width - tile.width()) / 2;
+            image::imageops::replace(&mut canvas, tile, (x + offset) as i64, (y + label_height) as i64);
+        }
+
+        canvas
+    }
+
+    fn column_count(&self, tiles: usize) -> usize {
+        if self.columns > 0 {
+            return self.columns.min(tiles.max(1));
+        }
+        // Near-square grid: 2 -> 2x1, 3 -> 2x2, 4 -> 2x2, 5 -> 3x2
+        let mut columns = 1;
+        while columns * columns < tiles {
+            columns += 1;
+        }
+        columns
+    }
+}
+
+// ============================================================
+// Label rendering
+// ============================================================
+
+fn fill_rect(canvas: &mut RgbImage, x: u32, y: u32, width: u32, height: u32, color: Rgb<u8>) {
+    for py in y..(y + height).min(canvas.height()) {
+        for px in x..(x + width).min(canvas.width()) {
+            canvas.put_pixel(px, py, color);
+        }
+    }
+}
+
+/// Draw `text` with the built-in font, clipped to `max_width`
+fn draw_label(canvas: &mut RgbImage, text: &str, x: u32, y: u32, max_width: u32) {
+    let advance = (GLYPH_WIDTH + 1) * LABEL_SCALE;
+    for (i, c) in text.chars().enumerate() {
+        let gx = x + i as u32 * advance;
+        if gx + GLYPH_WIDTH * LABEL_SCALE > x + max_width {
+            break;
+        }
+        let glyph = glyph(c);
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                    fill_rect(
+                        canvas,
+                        gx + col * LABEL_SCALE,
+                        y + row as u32 * LABEL_SCALE,
+                        LABEL_SCALE,
+                        LABEL_SCALE,
+                        LABEL_FOREGROUND,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// 5x7 bitmap glyph (one byte per row, low 5 bits); lowercase maps to uppercase
+pub(crate) fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ' ' => [0x00; 7],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profile_overrides() {
+        let base = PipelineConfig::default();
+        let profile = CompareProfile::parse("dpi450:dpi=450, upscale=false", &base).unwrap();
+
+        assert_eq!(profile.name, "dpi450");
+        assert_eq!(profile.config.dpi, 450);
+        assert!(!profile.config.upscale);
+        assert_eq!(profile.config.deskew, base.deskew);
+    }
+
+    #[test]
+    fn test_parse_profile_name_only() {
+        let base = PipelineConfig::default().with_dpi(200);
+        let profile = CompareProfile::parse("baseline", &base).unwrap();
+
+        assert_eq!(profile.name, "baseline");
+        assert_eq!(profile.config.dpi, 200);
+    }
+
+    #[test]
+    fn test_parse_profile_string_and_nested_values() {
+        let base = PipelineConfig::default();
+        let profile =
+            CompareProfile::parse("fmt:ocr_formats=[\"hocr\",\"alto\"],dpi=150", &base).unwrap();
+
+        assert_eq!(profile.config.ocr_formats.len(), 2);
+        assert_eq!(profile.config.dpi, 150);
+    }
+
+    #[test]
+    fn test_parse_profile_errors() {
+        let base = PipelineConfig::default();
+
+        assert!(matches!(
+            CompareProfile::parse(":dpi=300", &base),
+            Err(CompareError::InvalidProfile(..))
+        ));
+        assert!(matches!(
+            CompareProfile::parse("a:no_such_option=1", &base),
+            Err(CompareError::InvalidProfile(..))
+        ));
+        assert!(matches!(
+            CompareProfile::parse("a:dpi", &base),
+            Err(CompareError::InvalidProfile(..))
+        ));
+        assert!(matches!(
+            CompareProfile::parse("a:dpi=high", &base),
+            Err(CompareError::InvalidProfile(..))
+        ));
+    }
+
+    #[test]
+    fn test_compose_grid_layout() {
+        let tiles = vec![
+            ("A".to_string(), RgbImage::from_pixel(100, 200, Rgb([10, 10, 10]))),
+            ("B".to_string(), RgbImage::from_pixel(50, 100, Rgb([10, 10, 10]))),
+            ("C".to_string(), RgbImage::from_pixel(100, 200, Rgb([10, 10, 10]))),
+        ];
+        let grid = OptionComparer::new().with_cell_height(100).compose(&tiles);
+
+        let label_height = GLYPH_HEIGHT * LABEL_SCALE + LABEL_PADDING * 2;
+        // 3 tiles -> 2 columns x 2 rows, each cell 50px wide
+        assert_eq!(grid.width(), 2 * (50 + CELL_GAP) + CELL_GAP);
+        assert_eq!(grid.height(), 2 * (100 + label_height + CELL_GAP) + CELL_GAP);
+        // Label bar and tile content
+        assert_eq!(*grid.get_pixel(CELL_GAP + 45, CELL_GAP + 1), LABEL_BACKGROUND);
+        assert_eq!(*grid.get_pixel(CELL_GAP + 25, CELL_GAP + label_height + 50), Rgb([10, 10, 10]));
+    }
+
+    #[test]
+    fn test_compose_explicit_columns() {
+        let tiles: Vec<_> = (0..4)
+            .map(|i| (format!("P{}", i), RgbImage::from_pixel(10, 10, BACKGROUND)))
+            .collect();
+        let grid = OptionComparer::new().with_cell_height(10).with_columns(4).compose(&tiles);
+
+        assert_eq!(grid.width(), 4 * (10 + CELL_GAP) + CELL_GAP);
+    }
+
+    #[test]
+    fn test_draw_label_marks_pixels() {
+        let mut canvas = RgbImage::from_pixel(100, 40, BACKGROUND);
+        draw_label(&mut canvas, "I", 0, 0, 100);
+
+        // Center column of 'I' is set on every row
+        assert_eq!(*canvas.get_pixel(2 * LABEL_SCALE, 3 * LABEL_SCALE), LABEL_FOREGROUND);
+        assert_eq!(*canvas.get_pixel(0, 3 * LABEL_SCALE), BACKGROUND);
+    }
+
+    #[test]
+    fn test_run_requires_profiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = OptionComparer::new().run(
+            Path::new("input.pdf"),
+            0,
+            &[],
+            dir.path(),
+            &dir.path().join("out.png"),
+            &crate::SilentProgress,
+        );
+        assert!(matches!(result, Err(CompareError::NoProfiles)));
+    }
+}
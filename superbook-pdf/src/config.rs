@@ -20,6 +20,10 @@
 //! [advanced]
 //! internal_resolution = true
 //! color_correction = true
+//!
+//! [metadata]
+//! author = "Author Name"
+//! keywords = "novel, history"
 //! ```
 
 use serde::{Deserialize, Serialize};
@@ -58,6 +62,42 @@ pub struct GeneralConfig {
     /// Verbosity level (0-2)
     #[serde(default)]
     pub verbose: Option<u8>,
+
+    /// Niceness of spawned CPU tools (-20 to 19)
+    #[serde(default)]
+    pub nice: Option<i32>,
+
+    /// Run GPU stages one at a time across concurrent conversions
+    #[serde(default)]
+    pub gpu_wait: Option<bool>,
+
+    /// Timeout of one external tool call in seconds (retried once)
+    #[serde(default)]
+    pub tool_timeout_secs: Option<u64>,
+
+    /// Seconds without progress before warning of a possible hang (0 = off)
+    #[serde(default)]
+    pub stall_warning_secs: Option<u64>,
+
+    /// Tesseract calls run at once (default: one per thread)
+    #[serde(default)]
+    pub ocr_concurrency: Option<usize>,
+
+    /// Share of the threads (0.0-1.0) reserved for external tools
+    #[serde(default)]
+    pub external_thread_fraction: Option<f32>,
+
+    /// Handling of a failed page ("abort", "skip" or "placeholder")
+    #[serde(default)]
+    pub on_page_error: Option<crate::PageErrorPolicy>,
+
+    /// Content-addressed cache directory of per-page stage results
+    #[serde(default)]
+    pub work_cache: Option<PathBuf>,
+
+    /// Size limit of the work cache in MB
+    #[serde(default)]
+    pub work_cache_max_mb: Option<u64>,
 }
 
 /// Processing configuration options
@@ -79,6 +119,22 @@ pub struct ProcessingConfig {
     #[serde(default)]
     pub gpu: Option<bool>,
 
+    /// Resize filter (nearest, bilinear/triangle, bicubic/catmull_rom, gaussian, lanczos3)
+    #[serde(default)]
+    pub resize_filter: Option<crate::Resampler>,
+
+    /// Negative scan inversion (off, auto, force)
+    #[serde(default)]
+    pub invert: Option<crate::Invert>,
+
+    /// Text direction override when auto-detection is wrong (auto, vertical, horizontal)
+    #[serde(default)]
+    pub text_direction: Option<crate::markdown::TextDirectionOption>,
+
+    /// Color transparent page pixels are composited over ([R, G, B])
+    #[serde(default)]
+    pub flatten_background: Option<[u8; 3]>,
+
     // Issue #32: Content-aware margins
     /// Enable content-aware margin detection
     #[serde(default)]
@@ -109,6 +165,10 @@ pub struct CleanupConfig {
     #[serde(default)]
     pub highlighter_colors: Option<Vec<String>>,
 
+    /// Page area markers are removed from ("page" or "margins")
+    #[serde(default)]
+    pub marker_region: Option<crate::cleanup::MarkerArea>,
+
     /// Enable deblur processing
     #[serde(default)]
     pub deblur: Option<bool>,
@@ -116,6 +176,14 @@ pub struct CleanupConfig {
     /// Deblur algorithm (unsharp_mask, nafnet, deblurgan_v2)
     #[serde(default)]
     pub deblur_algorithm: Option<String>,
+
+    /// Remove scanner dust/hair lines
+    #[serde(default)]
+    pub line_artifacts: Option<bool>,
+
+    /// Gray out colors below this saturation (0.0-1.0)
+    #[serde(default)]
+    pub desaturate_below: Option<f32>,
 }
 
 /// Markdown conversion configuration (Issue #36)
@@ -173,9 +241,49 @@ pub struct AdvancedConfig {
     #[serde(default)]
     pub offset_alignment: Option<bool>,
 
+    /// Shift odd and even pages so their content lines up
+    #[serde(default)]
+    pub register_sides: Option<bool>,
+
     /// Output height in pixels
     #[serde(default)]
     pub output_height: Option<u32>,
+
+    /// Effective output DPI below which a book is flagged (0 = no check, default 150)
+    #[serde(default)]
+    pub min_output_dpi: Option<u32>,
+
+    /// Handling of a book below `min_output_dpi` ("warn" or "refuse")
+    #[serde(default)]
+    pub on_low_dpi: Option<crate::LowDpiPolicy>,
+
+    /// Largest page image in megapixels the DPI / output height may produce (0 = no limit, default 256)
+    #[serde(default)]
+    pub max_page_megapixels: Option<u32>,
+
+    /// Longest edge in pixels of an extracted page; larger pages are extracted at a lower DPI (0 = no cap, default 16384)
+    #[serde(default)]
+    pub max_page_dimension: Option<u32>,
+
+    /// Tukey fence multiplier for group crop outlier removal (default 1.5)
+    #[serde(default)]
+    pub tukey_k: Option<f64>,
+
+    /// Minimum inlier ratio before group crop falls back to all pages (default 0.5)
+    #[serde(default)]
+    pub min_inlier_ratio: Option<f64>,
+
+    /// Minimum inlier count before group crop falls back to all pages (default 3)
+    #[serde(default)]
+    pub min_inlier_count: Option<usize>,
+
+    /// Group crop edge aggregation ("median", "extremes" or `{ percentile = 90.0 }`)
+    #[serde(default)]
+    pub crop_aggregation: Option<crate::CropAggregation>,
+
+    /// Aspect ratio pages are fitted to after group crop ("W:H", e.g. "3:4")
+    #[serde(default)]
+    pub target_aspect: Option<String>,
 }
 
 /// OCR configuration
@@ -188,6 +296,30 @@ pub struct OcrConfig {
     /// OCR language
     #[serde(default)]
     pub language: Option<String>,
+
+    /// Confidence (percent) below which pages are flagged for review
+    #[serde(default)]
+    pub min_confidence: Option<f32>,
+
+    /// Reuse an existing text layer instead of running OCR
+    #[serde(default)]
+    pub skip_existing: Option<bool>,
+
+    /// Write the OCR text to `<output>.txt`
+    #[serde(default)]
+    pub text_sidecar: Option<bool>,
+
+    /// Join words hyphenated at line ends in the text sidecar
+    #[serde(default)]
+    pub dehyphenate: Option<bool>,
+
+    /// Source pages to OCR (e.g. "1-12,15,40-")
+    #[serde(default)]
+    pub pages: Option<crate::PageRanges>,
+
+    /// Skip OCR on pages that are predominantly pictures
+    #[serde(default)]
+    pub skip_pictures: Option<bool>,
 }
 
 /// Output configuration
@@ -200,6 +332,46 @@ pub struct OutputConfig {
     /// Skip existing files
     #[serde(default)]
     pub skip_existing: Option<bool>,
+
+    /// Encoding of bilevel pages ("standard", "flate", "jbig2")
+    #[serde(default)]
+    pub image_encoding: Option<crate::ImageEncoding>,
+
+    /// PDF/A conformance level ("1b", "2b")
+    #[serde(default)]
+    pub pdfa: Option<crate::PdfaLevel>,
+
+    /// Validate PDF/A output with veraPDF (or Ghostscript)
+    #[serde(default)]
+    pub pdfa_validate: Option<bool>,
+
+    /// Output container ("pdf", "cbz")
+    #[serde(default)]
+    pub format: Option<crate::BookFormat>,
+
+    /// Write PDF page labels from detected page numbers
+    #[serde(default)]
+    pub page_labels: Option<bool>,
+
+    /// Which parts keep their color ("per-page", "figures-only")
+    #[serde(default)]
+    pub color_policy: Option<crate::ColorPolicy>,
+
+    /// Sizing of minority-orientation pages ("uniform", "per-orientation", "preserve")
+    #[serde(default)]
+    pub mixed_size: Option<crate::MixedSize>,
+}
+
+/// External hook configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HooksConfig {
+    /// Executable run on the finished PDF
+    #[serde(default)]
+    pub post: Option<String>,
+
+    /// Executable run on each final page image
+    #[serde(default)]
+    pub page: Option<String>,
 }
 
 /// Main configuration structure
@@ -225,13 +397,34 @@ pub struct Config {
     #[serde(default)]
     pub output: OutputConfig,
 
+    /// External hooks
+    #[serde(default)]
+    pub hooks: HooksConfig,
+
+    /// External tool locations (`pdftoppm = "C:/poppler/bin/pdftoppm.exe"`)
+    #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+    pub tools: std::collections::HashMap<String, PathBuf>,
+
     /// Cleanup settings (Issue #34-35)
     #[serde(default)]
     pub cleanup: CleanupConfig,
 
+    /// Named highlighter colors (`[[highlighter]]` tables) usable in
+    /// `highlighter_colors` and `--marker-colors`
+    #[serde(default, rename = "highlighter", skip_serializing_if = "Vec::is_empty")]
+    pub highlighters: Vec<crate::cleanup::CustomHighlighter>,
+
+    /// Manual tone curve (`[tone_curve]` table with `points = [[in, out], ...]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tone_curve: Option<crate::ToneCurve>,
+
     /// Markdown conversion settings (Issue #36)
     #[serde(default)]
     pub markdown: MarkdownConfig,
+
+    /// Output PDF metadata (title, author, subject, keywords, creator)
+    #[serde(default, skip_serializing_if = "crate::PdfMetadata::is_empty")]
+    pub metadata: crate::PdfMetadata,
 }
 
 impl Config {
@@ -298,6 +491,33 @@ impl Config {
         if let Some(threads) = self.general.threads {
             config.threads = Some(threads);
         }
+        if let Some(niceness) = self.general.nice {
+            config = config.with_nice(niceness);
+        }
+        if let Some(gpu_wait) = self.general.gpu_wait {
+            config = config.with_gpu_wait(gpu_wait);
+        }
+        if let Some(secs) = self.general.tool_timeout_secs {
+            config = config.with_tool_timeout_secs(Some(secs));
+        }
+        if let Some(secs) = self.general.stall_warning_secs {
+            config = config.with_stall_warning_secs(Some(secs));
+        }
+        if let Some(limit) = self.general.ocr_concurrency {
+            config = config.with_ocr_concurrency(Some(limit));
+        }
+        if let Some(fraction) = self.general.external_thread_fraction {
+            config = config.with_external_thread_fraction(fraction);
+        }
+        if let Some(policy) = self.general.on_page_error {
+            config = config.with_on_page_error(policy);
+        }
+        if let Some(dir) = &self.general.work_cache {
+            config = config.with_work_cache(Some(dir.clone()));
+        }
+        if let Some(max_mb) = self.general.work_cache_max_mb {
+            config = config.with_work_cache_max_mb(max_mb);
+        }
 
         // Apply processing settings
         if let Some(deskew) = self.processing.deskew {
@@ -312,6 +532,18 @@ impl Config {
         if let Some(gpu) = self.processing.gpu {
             config = config.with_gpu(gpu);
         }
+        if let Some(invert) = self.processing.invert {
+            config = config.with_invert(invert);
+        }
+        if let Some(direction) = self.processing.text_direction {
+            config = config.with_text_direction(direction);
+        }
+        if let Some(background) = self.processing.flatten_background {
+            config = config.with_flatten_background(background);
+        }
+        if let Some(filter) = self.processing.resize_filter {
+            config = config.with_resize_filter(filter);
+        }
 
         // Apply advanced settings
         if let Some(internal) = self.advanced.internal_resolution {
@@ -323,19 +555,128 @@ impl Config {
         if let Some(offset) = self.advanced.offset_alignment {
             config.offset_alignment = offset;
         }
+        if let Some(enabled) = self.advanced.register_sides {
+            config = config.with_register_sides(enabled);
+        }
         if let Some(height) = self.advanced.output_height {
             config.output_height = height;
         }
+        if let Some(dpi) = self.advanced.min_output_dpi {
+            config = config.with_min_output_dpi(dpi);
+        }
+        if let Some(policy) = self.advanced.on_low_dpi {
+            config = config.with_on_low_dpi(policy);
+        }
+        if let Some(megapixels) = self.advanced.max_page_megapixels {
+            config = config.with_max_page_megapixels(megapixels);
+        }
+        if let Some(pixels) = self.advanced.max_page_dimension {
+            config = config.with_max_page_dimension(pixels);
+        }
+        if let Some(k) = self.advanced.tukey_k {
+            config.group_crop = config.group_crop.with_tukey_k(k);
+        }
+        if let Some(ratio) = self.advanced.min_inlier_ratio {
+            config.group_crop = config.group_crop.with_min_inlier_ratio(ratio);
+        }
+        if let Some(count) = self.advanced.min_inlier_count {
+            config.group_crop = config.group_crop.with_min_inlier_count(count);
+        }
+        if let Some(aggregation) = self.advanced.crop_aggregation {
+            config.group_crop = config.group_crop.with_aggregation(aggregation);
+        }
+        if let Some(aspect) = &self.advanced.target_aspect {
+            config = config.with_target_aspect(crate::finalize::parse_aspect_ratio(aspect).ok());
+        }
 
         // Apply OCR settings
         if let Some(ocr) = self.ocr.enabled {
             config = config.with_ocr(ocr);
         }
+        if let Some(percent) = self.ocr.min_confidence {
+            config = config.with_ocr_min_confidence(percent);
+        }
+        if let Some(skip) = self.ocr.skip_existing {
+            config = config.with_skip_existing_ocr(skip);
+        }
+        if let Some(enabled) = self.ocr.text_sidecar {
+            config = config.with_text_sidecar(enabled);
+        }
+        if let Some(enabled) = self.ocr.dehyphenate {
+            config = config.with_dehyphenate(enabled);
+        }
+        if let Some(pages) = &self.ocr.pages {
+            config = config.with_ocr_pages(Some(pages.clone()));
+        }
+        if let Some(enabled) = self.ocr.skip_pictures {
+            config = config.with_ocr_skip_pictures(enabled);
+        }
+
+        // Apply cleanup settings
+        if let Some(enabled) = self.cleanup.line_artifacts {
+            config = config.with_remove_line_artifacts(enabled);
+        }
+        if let Some(enabled) = self.cleanup.marker_removal {
+            config = config.with_remove_markers(enabled);
+        }
+        if let Some(colors) = &self.cleanup.highlighter_colors {
+            config = config.with_marker_colors(colors.clone());
+        }
+        if let Some(area) = self.cleanup.marker_region {
+            config = config.with_marker_region(area);
+        }
+        // Definitions only matter (and only enter the cache digest) when used
+        if config.remove_markers {
+            config = config.with_highlighters(self.highlighters.clone());
+        }
+        if let Some(threshold) = self.cleanup.desaturate_below {
+            config = config.with_desaturate_below(Some(threshold));
+        }
+        if let Some(curve) = &self.tone_curve {
+            config = config.with_tone_curve(Some(curve.clone()));
+        }
 
         // Apply output settings
         if let Some(quality) = self.output.jpeg_quality {
             config.jpeg_quality = quality;
         }
+        if let Some(encoding) = self.output.image_encoding {
+            config = config.with_image_encoding(encoding);
+        }
+        if let Some(level) = self.output.pdfa {
+            config = config.with_pdfa(Some(level));
+        }
+        if let Some(validate) = self.output.pdfa_validate {
+            config = config.with_pdfa_validate(validate);
+        }
+        if let Some(format) = self.output.format {
+            config = config.with_output_format(format);
+        }
+        if let Some(enabled) = self.output.page_labels {
+            config = config.with_page_labels(enabled);
+        }
+        if let Some(policy) = self.output.color_policy {
+            config = config.with_color_policy(policy);
+        }
+        if let Some(mixed_size) = self.output.mixed_size {
+            config = config.with_mixed_size(mixed_size);
+        }
+
+        // Apply tool locations
+        for (name, path) in &self.tools {
+            config = config.with_tool_path(name.clone(), path.clone());
+        }
+
+        // Apply hooks
+        if let Some(hook) = &self.hooks.post {
+            config = config.with_post_hook(Some(hook.clone()));
+        }
+        if let Some(hook) = &self.hooks.page {
+            config = config.with_page_hook(Some(hook.clone()));
+        }
+
+        // Apply metadata
+        config = config.with_metadata(self.metadata.clone());
 
         config
     }
@@ -366,6 +707,27 @@ impl Config {
         if let Some(threads) = cli.threads {
             config.threads = Some(threads);
         }
+        if let Some(niceness) = cli.nice {
+            config = config.with_nice(niceness);
+        }
+        if let Some(gpu_wait) = cli.gpu_wait {
+            config = config.with_gpu_wait(gpu_wait);
+        }
+        if let Some(secs) = cli.tool_timeout_secs {
+            config = config.with_tool_timeout_secs(Some(secs));
+        }
+        if let Some(secs) = cli.stall_warning_secs {
+            config = config.with_stall_warning_secs(Some(secs));
+        }
+        if let Some(limit) = cli.ocr_concurrency {
+            config = config.with_ocr_concurrency(Some(limit));
+        }
+        if let Some(fraction) = cli.external_thread_fraction {
+            config = config.with_external_thread_fraction(fraction);
+        }
+        if let Some(policy) = cli.on_page_error {
+            config = config.with_on_page_error(policy);
+        }
         if let Some(internal) = cli.internal_resolution {
             config.internal_resolution = internal;
         }
@@ -375,9 +737,33 @@ impl Config {
         if let Some(offset) = cli.offset_alignment {
             config.offset_alignment = offset;
         }
+        if let Some(enabled) = cli.register_sides {
+            config = config.with_register_sides(enabled);
+        }
         if let Some(height) = cli.output_height {
             config.output_height = height;
         }
+        if let Some(dpi) = cli.min_output_dpi {
+            config = config.with_min_output_dpi(dpi);
+        }
+        if let Some(policy) = cli.on_low_dpi {
+            config = config.with_on_low_dpi(policy);
+        }
+        if let Some(megapixels) = cli.max_page_megapixels {
+            config = config.with_max_page_megapixels(megapixels);
+        }
+        if let Some(pixels) = cli.max_page_dimension {
+            config = config.with_max_page_dimension(pixels);
+        }
+        if let Some(dir) = &cli.work_cache {
+            config = config.with_work_cache(Some(dir.clone()));
+        }
+        if let Some(max_mb) = cli.work_cache_max_mb {
+            config = config.with_work_cache_max_mb(max_mb);
+        }
+        if let Some(resume) = cli.resume {
+            config = config.with_resume(resume);
+        }
         if let Some(quality) = cli.jpeg_quality {
             config.jpeg_quality = quality;
         }
@@ -387,6 +773,135 @@ impl Config {
         if let Some(save_debug) = cli.save_debug {
             config.save_debug = save_debug;
         }
+        if let Some(mode) = cli.auto_contrast {
+            config.auto_contrast = mode;
+        }
+        if let Some(per_channel) = cli.auto_contrast_per_channel {
+            config.auto_contrast_per_channel = per_channel;
+        }
+        if let Some(curve) = &cli.tone_curve {
+            config = config.with_tone_curve(Some(curve.clone()));
+        }
+        if let Some(formats) = &cli.ocr_formats {
+            config = config.with_ocr_formats(formats.clone());
+        }
+        if let Some(deterministic) = cli.deterministic {
+            config = config.with_deterministic(deterministic);
+        }
+        if let Some(depth) = cli.extract_queue_depth {
+            config = config.with_extract_queue_depth(depth);
+        }
+        if let Some(pages) = cli.extract_batch_pages {
+            config = config.with_extract_batch_pages(pages);
+        }
+        if let Some(from_ocr) = cli.metadata_from_ocr {
+            config.metadata_from_ocr = from_ocr;
+        }
+        if let Some(enabled) = cli.preserve_source_metadata {
+            config = config.with_preserve_source_metadata(enabled);
+        }
+        if let Some(enabled) = cli.preserve_source_outline {
+            config = config.with_preserve_source_outline(enabled);
+        }
+        if let Some(enabled) = cli.text_sidecar {
+            config = config.with_text_sidecar(enabled);
+        }
+        if let Some(enabled) = cli.dehyphenate {
+            config = config.with_dehyphenate(enabled);
+        }
+        if let Some(pages) = &cli.ocr_pages {
+            config = config.with_ocr_pages(Some(pages.clone()));
+        }
+        if let Some(enabled) = cli.ocr_skip_pictures {
+            config = config.with_ocr_skip_pictures(enabled);
+        }
+        if let Some(optional) = cli.ocr_optional {
+            config = config.with_ocr_optional(optional);
+        }
+        if let Some(skip) = cli.skip_existing_ocr {
+            config = config.with_skip_existing_ocr(skip);
+        }
+        if let Some(enabled) = cli.remove_line_artifacts {
+            config = config.with_remove_line_artifacts(enabled);
+        }
+        if let Some(invert) = cli.invert {
+            config = config.with_invert(invert);
+        }
+        if let Some(threshold) = cli.desaturate_below {
+            config = config.with_desaturate_below(Some(threshold));
+        }
+        if let Some(aspect) = cli.target_aspect {
+            config = config.with_target_aspect(Some(aspect));
+        }
+        if let Some(mixed) = cli.mixed_color {
+            config = config.with_mixed_color(mixed);
+        }
+        if let Some(policy) = cli.color_policy {
+            config = config.with_color_policy(policy);
+        }
+        if let Some(mixed_size) = cli.mixed_size {
+            config = config.with_mixed_size(mixed_size);
+        }
+        if let Some(percent) = cli.ocr_min_confidence {
+            config = config.with_ocr_min_confidence(percent);
+        }
+        if let Some(binding) = cli.binding {
+            config = config.with_binding(binding);
+        }
+        if let Some(direction) = cli.text_direction {
+            config = config.with_text_direction(direction);
+        }
+        if let Some(background) = cli.flatten_background {
+            config = config.with_flatten_background(background);
+        }
+        if let Some(filter) = cli.resize_filter {
+            config = config.with_resize_filter(filter);
+        }
+        if let Some(encoding) = cli.image_encoding {
+            config = config.with_image_encoding(encoding);
+        }
+        if let Some(level) = cli.pdfa {
+            config = config.with_pdfa(Some(level));
+        }
+        if let Some(validate) = cli.pdfa_validate {
+            config = config.with_pdfa_validate(validate);
+        }
+        if let Some(format) = cli.output_format {
+            config = config.with_output_format(format);
+        }
+        if let Some(enabled) = cli.page_labels {
+            config = config.with_page_labels(enabled);
+        }
+        if let Some(parity) = cli.parity {
+            config = config.with_parity(parity);
+        }
+        if let Some(mode) = cli.running_heads {
+            config = config.with_running_heads(mode);
+        }
+        if let Some(enabled) = cli.quality_metrics {
+            config = config.with_quality_metrics(enabled);
+        }
+        if let Some(hook) = &cli.post_hook {
+            config = config.with_post_hook(Some(hook.clone()));
+        }
+        if let Some(hook) = &cli.page_hook {
+            config = config.with_page_hook(Some(hook.clone()));
+        }
+        if let Some(enabled) = cli.remove_markers {
+            config = config.with_remove_markers(enabled);
+        }
+        if let Some(colors) = &cli.marker_colors {
+            config = config.with_marker_colors(colors.clone());
+        }
+        if let Some(area) = cli.marker_region {
+            config = config.with_marker_region(area);
+        }
+        let highlighters = if config.remove_markers { self.highlighters.clone() } else { vec![] };
+        config = config.with_highlighters(highlighters);
+        // The quick preset wins over config file values
+        if let Some(quick) = cli.quick {
+            config = config.with_quick(quick);
+        }
 
         config
     }
@@ -406,6 +921,7 @@ impl Config {
 /// CLI override values for merging with config file
 #[derive(Debug, Clone, Default)]
 pub struct CliOverrides {
+    pub quick: Option<bool>,
     pub dpi: Option<u32>,
     pub deskew: Option<bool>,
     pub margin_trim: Option<f64>,
@@ -413,13 +929,69 @@ pub struct CliOverrides {
     pub gpu: Option<bool>,
     pub ocr: Option<bool>,
     pub threads: Option<usize>,
+    pub nice: Option<i32>,
+    pub gpu_wait: Option<bool>,
+    pub tool_timeout_secs: Option<u64>,
+    pub stall_warning_secs: Option<u64>,
+    pub ocr_concurrency: Option<usize>,
+    pub external_thread_fraction: Option<f32>,
+    pub on_page_error: Option<crate::PageErrorPolicy>,
     pub internal_resolution: Option<bool>,
     pub color_correction: Option<bool>,
     pub offset_alignment: Option<bool>,
+    pub register_sides: Option<bool>,
     pub output_height: Option<u32>,
+    pub min_output_dpi: Option<u32>,
+    pub on_low_dpi: Option<crate::LowDpiPolicy>,
+    pub max_page_megapixels: Option<u32>,
+    pub max_page_dimension: Option<u32>,
+    pub work_cache: Option<PathBuf>,
+    pub work_cache_max_mb: Option<u64>,
+    pub resume: Option<bool>,
     pub jpeg_quality: Option<u8>,
     pub max_pages: Option<usize>,
     pub save_debug: Option<bool>,
+    pub auto_contrast: Option<crate::AutoContrast>,
+    pub auto_contrast_per_channel: Option<bool>,
+    pub tone_curve: Option<crate::ToneCurve>,
+    pub ocr_formats: Option<Vec<crate::markdown::OcrExportFormat>>,
+    pub deterministic: Option<bool>,
+    pub extract_queue_depth: Option<usize>,
+    pub extract_batch_pages: Option<usize>,
+    pub metadata_from_ocr: Option<bool>,
+    pub preserve_source_metadata: Option<bool>,
+    pub preserve_source_outline: Option<bool>,
+    pub text_sidecar: Option<bool>,
+    pub dehyphenate: Option<bool>,
+    pub ocr_pages: Option<crate::PageRanges>,
+    pub ocr_skip_pictures: Option<bool>,
+    pub ocr_optional: Option<bool>,
+    pub skip_existing_ocr: Option<bool>,
+    pub remove_line_artifacts: Option<bool>,
+    pub invert: Option<crate::Invert>,
+    pub remove_markers: Option<bool>,
+    pub marker_colors: Option<Vec<String>>,
+    pub marker_region: Option<crate::cleanup::MarkerArea>,
+    pub desaturate_below: Option<f32>,
+    pub target_aspect: Option<(u32, u32)>,
+    pub mixed_color: Option<bool>,
+    pub color_policy: Option<crate::ColorPolicy>,
+    pub mixed_size: Option<crate::MixedSize>,
+    pub ocr_min_confidence: Option<f32>,
+    pub post_hook: Option<String>,
+    pub page_hook: Option<String>,
+    pub quality_metrics: Option<bool>,
+    pub binding: Option<crate::Binding>,
+    pub text_direction: Option<crate::markdown::TextDirectionOption>,
+    pub flatten_background: Option<[u8; 3]>,
+    pub resize_filter: Option<crate::Resampler>,
+    pub image_encoding: Option<crate::ImageEncoding>,
+    pub pdfa: Option<crate::PdfaLevel>,
+    pub pdfa_validate: Option<bool>,
+    pub output_format: Option<crate::BookFormat>,
+    pub page_labels: Option<bool>,
+    pub parity: Option<crate::PageParity>,
+    pub running_heads: Option<crate::markdown::RunningHeadMode>,
 }
 
 impl CliOverrides {
@@ -463,6 +1035,18 @@ impl CliOverrides {
         self.ocr = Some(ocr);
         self
     }
+
+    /// Set auto contrast override
+    pub fn with_auto_contrast(mut self, mode: crate::AutoContrast) -> Self {
+        self.auto_contrast = Some(mode);
+        self
+    }
+
+    /// Set OCR export formats override
+    pub fn with_ocr_formats(mut self, formats: Vec<crate::markdown::OcrExportFormat>) -> Self {
+        self.ocr_formats = Some(formats);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -502,6 +1086,549 @@ deskew = true
         assert_eq!(config.processing.deskew, Some(true));
     }
 
+    #[test]
+    fn test_config_pdfa() {
+        let config = Config::from_toml("[output]\npdfa = \"1b\"\npdfa_validate = true\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.pdfa, Some(crate::PdfaLevel::A1b));
+        assert!(pipeline.pdfa_validate);
+
+        let cli = CliOverrides {
+            pdfa: Some(crate::PdfaLevel::A2b),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).pdfa, Some(crate::PdfaLevel::A2b));
+        assert!(Config::from_toml("[output]\npdfa = \"3u\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_tone_curve() {
+        let config =
+            Config::from_toml("[tone_curve]\npoints = [[0, 0], [128, 80], [255, 255]]\nper_channel = true\n").unwrap();
+        let curve = config.to_pipeline_config().tone_curve.unwrap();
+        assert_eq!(curve.points, vec![(0, 0), (128, 80), (255, 255)]);
+        assert_eq!(curve.interpolation, crate::ToneInterpolation::Monotone);
+        assert!(curve.per_channel);
+
+        let cli = CliOverrides {
+            tone_curve: Some(crate::ToneCurve::new(vec![(0, 30), (255, 255)]).unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).tone_curve.unwrap().points, vec![(0, 30), (255, 255)]);
+        assert!(Config::from_toml("[tone_curve]\npoints = [[128, 0], [64, 255]]\n").is_err());
+        assert!(Config::from_toml("[tone_curve]\npoints = [[0, 0], [300, 255]]\n").is_err());
+    }
+
+    #[test]
+    fn test_config_output_format() {
+        let config = Config::from_toml("[output]\nformat = \"cbz\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().output_format, crate::BookFormat::Cbz);
+
+        let cli = CliOverrides {
+            output_format: Some(crate::BookFormat::Pdf),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).output_format, crate::BookFormat::Pdf);
+        assert!(Config::from_toml("[output]\nformat = \"epub\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_page_labels() {
+        let config = Config::from_toml("[output]\npage_labels = true\n").unwrap();
+        assert!(config.to_pipeline_config().page_labels);
+        assert!(!Config::default().to_pipeline_config().page_labels);
+
+        let cli = CliOverrides {
+            page_labels: Some(false),
+            ..Default::default()
+        };
+        assert!(!config.merge_with_cli(&cli).page_labels);
+    }
+
+    #[test]
+    fn test_config_parity_override() {
+        let config = Config::default();
+        assert!(config.to_pipeline_config().parity.is_all());
+
+        let cli = CliOverrides {
+            parity: Some(crate::PageParity::Odd),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).parity, crate::PageParity::Odd);
+    }
+
+    #[test]
+    fn test_config_running_heads_override() {
+        let config = Config::default();
+        assert!(config.to_pipeline_config().running_heads.is_keep());
+
+        let cli = CliOverrides {
+            running_heads: Some(crate::markdown::RunningHeadMode::Exclude),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.merge_with_cli(&cli).running_heads,
+            crate::markdown::RunningHeadMode::Exclude
+        );
+    }
+
+    #[test]
+    fn test_config_resize_filter() {
+        let config = Config::from_toml("[processing]\nresize_filter = \"catmull_rom\"\n").unwrap();
+        assert_eq!(config.processing.resize_filter, Some(crate::Resampler::Bicubic));
+        assert_eq!(config.to_pipeline_config().resize_filter, crate::Resampler::Bicubic);
+
+        let cli = CliOverrides {
+            resize_filter: Some(crate::Resampler::Gaussian),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).resize_filter, crate::Resampler::Gaussian);
+    }
+
+    #[test]
+    fn test_config_image_encoding() {
+        let config = Config::from_toml("[output]\nimage_encoding = \"flate\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().image_encoding, crate::ImageEncoding::Flate);
+
+        let cli = CliOverrides {
+            image_encoding: Some(crate::ImageEncoding::Jbig2),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).image_encoding, crate::ImageEncoding::Jbig2);
+        assert!(Config::from_toml("[output]\nimage_encoding = \"ccitt\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_mixed_size() {
+        let config = Config::from_toml("[output]\nmixed_size = \"per-orientation\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().mixed_size, crate::MixedSize::PerOrientation);
+        assert!(Config::default().to_pipeline_config().mixed_size.is_uniform());
+        let cli = CliOverrides {
+            mixed_size: Some(crate::MixedSize::Preserve),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).mixed_size, crate::MixedSize::Preserve);
+        assert!(Config::from_toml("[output]\nmixed_size = \"rotate\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_color_policy() {
+        let config = Config::from_toml("[output]\ncolor_policy = \"figures-only\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().color_policy, crate::ColorPolicy::FiguresOnly);
+        assert!(Config::default().to_pipeline_config().color_policy.is_per_page());
+
+        let cli = CliOverrides {
+            color_policy: Some(crate::ColorPolicy::PerPage),
+            ..Default::default()
+        };
+        assert!(config.merge_with_cli(&cli).color_policy.is_per_page());
+        assert!(Config::from_toml("[output]\ncolor_policy = \"figures\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_nice_and_gpu_wait() {
+        let config = Config::from_toml("[general]\nnice = 10\ngpu_wait = true\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.nice, 10);
+        assert!(pipeline.gpu_wait);
+
+        let cli = CliOverrides {
+            nice: Some(5),
+            gpu_wait: Some(false),
+            ..Default::default()
+        };
+        let merged = config.merge_with_cli(&cli);
+        assert_eq!(merged.nice, 5);
+        assert!(!merged.gpu_wait);
+    }
+
+    #[test]
+    fn test_config_on_page_error() {
+        let config = Config::from_toml("[general]\non_page_error = \"placeholder\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().on_page_error, crate::PageErrorPolicy::Placeholder);
+        assert!(Config::default().to_pipeline_config().on_page_error.is_skip());
+
+        let cli = CliOverrides {
+            on_page_error: Some(crate::PageErrorPolicy::Abort),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).on_page_error, crate::PageErrorPolicy::Abort);
+        assert!(Config::from_toml("[general]\non_page_error = \"retry\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_quick_overrides_file() {
+        let config = Config::from_toml("[general]\ndpi = 600\n\n[ocr]\nenabled = true\n").unwrap();
+        assert!(config.merge_with_cli(&CliOverrides::default()).ocr);
+
+        let cli = CliOverrides {
+            quick: Some(true),
+            ..Default::default()
+        };
+        let merged = config.merge_with_cli(&cli);
+        assert!(merged.quick);
+        assert_eq!(merged.dpi, crate::pipeline::QUICK_DPI);
+        assert!(!merged.ocr);
+        assert!(merged.validate().is_ok());
+    }
+
+    #[test]
+    fn test_config_max_page_megapixels() {
+        // A 20000px output height at 1200 DPI is rejected with the config file's budget
+        let config = Config::from_toml("[advanced]\nmax_page_megapixels = 100\n").unwrap();
+        assert_eq!(config.to_pipeline_config().max_page_megapixels, 100);
+        let cli = CliOverrides {
+            dpi: Some(1200),
+            output_height: Some(20000),
+            ..Default::default()
+        };
+        let err = config.merge_with_cli(&cli).validate().unwrap_err().to_string();
+        assert!(err.contains("over the 100 MP budget"), "{}", err);
+
+        // The CLI budget overrides the file's
+        let cli = CliOverrides {
+            max_page_megapixels: Some(0),
+            ..cli
+        };
+        assert!(config.merge_with_cli(&cli).validate().is_ok());
+        assert_eq!(
+            Config::default().to_pipeline_config().max_page_megapixels,
+            crate::pipeline::DEFAULT_MAX_PAGE_MEGAPIXELS
+        );
+    }
+
+    #[test]
+    fn test_config_max_page_dimension() {
+        let config = Config::from_toml("[advanced]\nmax_page_dimension = 8000\n").unwrap();
+        assert_eq!(config.to_pipeline_config().max_page_dimension, 8000);
+        let cli = CliOverrides {
+            max_page_dimension: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).max_page_dimension, 0);
+        assert_eq!(
+            Config::default().to_pipeline_config().max_page_dimension,
+            crate::pipeline::DEFAULT_MAX_PAGE_DIMENSION
+        );
+    }
+
+    #[test]
+    fn test_config_work_cache() {
+        let config = Config::from_toml("[general]\nwork_cache = \"/var/cache/superbook\"\nwork_cache_max_mb = 1024\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.work_cache, Some(PathBuf::from("/var/cache/superbook")));
+        assert_eq!(pipeline.work_cache_max_mb, 1024);
+
+        let cli = CliOverrides {
+            work_cache: Some(PathBuf::from("/tmp/work-cache")),
+            ..Default::default()
+        };
+        let merged = config.merge_with_cli(&cli);
+        assert_eq!(merged.work_cache, Some(PathBuf::from("/tmp/work-cache")));
+        assert_eq!(merged.work_cache_max_mb, 1024);
+        assert_eq!(Config::default().to_pipeline_config().work_cache, None);
+    }
+
+    #[test]
+    fn test_cli_override_resume() {
+        // Per run only: there is no config file key
+        assert!(!Config::default().to_pipeline_config().resume);
+        let cli = CliOverrides {
+            resume: Some(true),
+            ..Default::default()
+        };
+        assert!(Config::default().merge_with_cli(&cli).resume);
+    }
+
+    #[test]
+    fn test_config_min_output_dpi() {
+        let config = Config::from_toml("[advanced]\nmin_output_dpi = 200\non_low_dpi = \"refuse\"\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.min_output_dpi, 200);
+        assert_eq!(pipeline.on_low_dpi, crate::LowDpiPolicy::Refuse);
+        let defaults = Config::default().to_pipeline_config();
+        assert_eq!(defaults.min_output_dpi, crate::pipeline::DEFAULT_MIN_OUTPUT_DPI);
+        assert_eq!(defaults.on_low_dpi, crate::LowDpiPolicy::Warn);
+
+        let cli = CliOverrides {
+            min_output_dpi: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).min_output_dpi, 0);
+        assert!(Config::from_toml("[advanced]\non_low_dpi = \"ignore\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_tool_timeout() {
+        let config = Config::from_toml("[general]\ntool_timeout_secs = 300\n").unwrap();
+        assert_eq!(config.to_pipeline_config().tool_timeout_secs, Some(300));
+        assert_eq!(Config::default().to_pipeline_config().tool_timeout_secs, None);
+
+        let cli = CliOverrides {
+            tool_timeout_secs: Some(60),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).tool_timeout_secs, Some(60));
+    }
+
+    #[test]
+    fn test_config_stall_warning() {
+        assert_eq!(
+            Config::default().to_pipeline_config().stall_warning_secs,
+            Some(crate::DEFAULT_STALL_WARNING_SECS)
+        );
+        let config = Config::from_toml("[general]\nstall_warning_secs = 1800\n").unwrap();
+        assert_eq!(config.to_pipeline_config().stall_warning_secs, Some(1800));
+        let off = Config::from_toml("[general]\nstall_warning_secs = 0\n").unwrap();
+        assert_eq!(off.to_pipeline_config().stall_warning_secs, None);
+
+        let cli = CliOverrides {
+            stall_warning_secs: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).stall_warning_secs, None);
+    }
+
+    #[test]
+    fn test_config_ocr_concurrency() {
+        let config = Config::from_toml("[general]\nocr_concurrency = 4\n").unwrap();
+        assert_eq!(config.to_pipeline_config().ocr_concurrency, Some(4));
+        assert_eq!(Config::default().to_pipeline_config().ocr_concurrency, None);
+
+        let cli = CliOverrides {
+            ocr_concurrency: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).ocr_concurrency, Some(2));
+    }
+
+    #[test]
+    fn test_config_external_thread_fraction() {
+        let config = Config::from_toml("[general]\nthreads = 16\nexternal_thread_fraction = 0.25\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.external_thread_fraction, 0.25);
+        assert_eq!(pipeline.thread_budget(), Some(crate::ThreadBudget { internal: 12, external: 4 }));
+        assert!(Config::default().to_pipeline_config().thread_budget().is_none());
+
+        let cli = CliOverrides {
+            external_thread_fraction: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).external_thread_fraction, 0.5);
+    }
+
+    #[test]
+    fn test_config_line_artifacts() {
+        let config = Config::from_toml("[cleanup]\nline_artifacts = true\n").unwrap();
+        assert!(config.to_pipeline_config().remove_line_artifacts);
+
+        let cli = CliOverrides {
+            remove_line_artifacts: Some(false),
+            ..Default::default()
+        };
+        assert!(!config.merge_with_cli(&cli).remove_line_artifacts);
+    }
+
+    #[test]
+    fn test_config_invert() {
+        let config = Config::from_toml("[processing]\ninvert = \"auto\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().invert, crate::Invert::Auto);
+        assert!(Config::default().to_pipeline_config().invert.is_off());
+
+        let cli = CliOverrides {
+            invert: Some(crate::Invert::Force),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).invert, crate::Invert::Force);
+        assert!(Config::from_toml("[processing]\ninvert = \"sometimes\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_text_direction() {
+        use crate::markdown::TextDirectionOption;
+
+        let config = Config::from_toml("[processing]\ntext_direction = \"vertical\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().text_direction, TextDirectionOption::Vertical);
+        assert!(Config::default().to_pipeline_config().text_direction.is_auto());
+
+        let cli = CliOverrides {
+            text_direction: Some(TextDirectionOption::Horizontal),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).text_direction, TextDirectionOption::Horizontal);
+        assert!(Config::from_toml("[processing]\ntext_direction = \"sideways\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_flatten_background() {
+        let config = Config::from_toml("[processing]\nflatten_background = [245, 235, 220]\n").unwrap();
+        assert_eq!(config.to_pipeline_config().flatten_background, [245, 235, 220]);
+        assert_eq!(Config::default().to_pipeline_config().flatten_background, [255, 255, 255]);
+
+        let cli = CliOverrides {
+            flatten_background: Some([0, 0, 0]),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).flatten_background, [0, 0, 0]);
+        assert!(Config::from_toml("[processing]\nflatten_background = [256, 0, 0]\n").is_err());
+    }
+
+    #[test]
+    fn test_config_custom_highlighters() {
+        let toml = r#"
+[cleanup]
+marker_removal = true
+highlighter_colors = ["yellow", "mybrand"]
+
+[[highlighter]]
+name = "mybrand"
+hue_min = 160
+hue_max = 180
+sat_min = 25
+sat_max = 90
+val_min = 60
+val_max = 100
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.highlighters.len(), 1);
+        assert_eq!(config.highlighters[0].name, "mybrand");
+
+        let pipeline = config.to_pipeline_config();
+        assert!(pipeline.remove_markers);
+        assert_eq!(pipeline.marker_colors, vec!["yellow", "mybrand"]);
+        let options = pipeline.marker_removal_options().unwrap().unwrap();
+        assert_eq!(
+            options.colors,
+            vec![crate::cleanup::HighlighterColor::Yellow, config.highlighters[0].color()]
+        );
+
+        // CLI names resolve against the same definitions
+        let cli = CliOverrides {
+            marker_colors: Some(vec!["mybrand".to_string()]),
+            ..Default::default()
+        };
+        let merged = config.merge_with_cli(&cli);
+        assert_eq!(merged.marker_removal_options().unwrap().unwrap().colors.len(), 1);
+
+        // Unused definitions stay out of the pipeline config
+        let toml = toml.replace("marker_removal = true", "marker_removal = false");
+        let config = Config::from_toml(&toml).unwrap();
+        assert!(config.to_pipeline_config().highlighters.is_empty());
+        let cli = CliOverrides {
+            remove_markers: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).highlighters.len(), 1);
+    }
+
+    #[test]
+    fn test_config_marker_region() {
+        let config = Config::from_toml("[cleanup]\nmarker_removal = true\nmarker_region = \"margins\"\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.marker_region, crate::cleanup::MarkerArea::Margins);
+        let options = pipeline.marker_removal_options().unwrap().unwrap();
+        assert!(matches!(options.region, crate::cleanup::MarkerRegion::Margins));
+
+        let cli = CliOverrides {
+            marker_region: Some(crate::cleanup::MarkerArea::Page),
+            ..Default::default()
+        };
+        assert!(config.merge_with_cli(&cli).marker_region.is_page());
+        assert!(Config::from_toml("[cleanup]\nmarker_region = \"gutter\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_desaturate_below() {
+        let config = Config::from_toml("[cleanup]\ndesaturate_below = 0.2\n").unwrap();
+        assert_eq!(config.to_pipeline_config().desaturate_below, Some(0.2));
+
+        let cli = CliOverrides {
+            desaturate_below: Some(0.3),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).desaturate_below, Some(0.3));
+
+        // Zero turns it off
+        let config = Config::from_toml("[cleanup]\ndesaturate_below = 0.0\n").unwrap();
+        assert_eq!(config.to_pipeline_config().desaturate_below, None);
+    }
+
+    #[test]
+    fn test_config_target_aspect() {
+        let config = Config::from_toml("[advanced]\ntarget_aspect = \"3:4\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().target_aspect, Some((3, 4)));
+
+        let cli = CliOverrides {
+            target_aspect: Some((2, 3)),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&cli).target_aspect, Some((2, 3)));
+
+        // Invalid ratios are ignored
+        let config = Config::from_toml("[advanced]\ntarget_aspect = \"wide\"\n").unwrap();
+        assert_eq!(config.to_pipeline_config().target_aspect, None);
+    }
+
+    #[test]
+    fn test_config_skip_existing_ocr() {
+        let config = Config::from_toml("[ocr]\nenabled = true\nskip_existing = true\n").unwrap();
+        assert!(config.to_pipeline_config().skip_existing_ocr);
+
+        let cli = CliOverrides {
+            skip_existing_ocr: Some(false),
+            ..Default::default()
+        };
+        assert!(!config.merge_with_cli(&cli).skip_existing_ocr);
+    }
+
+    #[test]
+    fn test_config_text_sidecar() {
+        assert!(!Config::default().to_pipeline_config().write_text_sidecar);
+        let config = Config::from_toml("[ocr]\nenabled = true\ntext_sidecar = true\n").unwrap();
+        assert!(config.to_pipeline_config().write_text_sidecar);
+
+        let cli = CliOverrides {
+            text_sidecar: Some(false),
+            ..Default::default()
+        };
+        assert!(!config.merge_with_cli(&cli).write_text_sidecar);
+    }
+
+    #[test]
+    fn test_config_dehyphenate() {
+        assert!(!Config::default().to_pipeline_config().dehyphenate);
+        let config = Config::from_toml("[ocr]\ntext_sidecar = true\ndehyphenate = true\n").unwrap();
+        assert!(config.to_pipeline_config().dehyphenate);
+
+        let cli = CliOverrides {
+            dehyphenate: Some(false),
+            ..Default::default()
+        };
+        assert!(!config.merge_with_cli(&cli).dehyphenate);
+    }
+
+    #[test]
+    fn test_config_ocr_pages() {
+        let defaults = Config::default().to_pipeline_config();
+        assert_eq!(defaults.ocr_pages, None);
+        assert!(!defaults.ocr_skip_pictures);
+
+        let config = Config::from_toml("[ocr]\npages = \"3-40\"\nskip_pictures = true\n").unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.ocr_pages.map(|pages| pages.to_string()).as_deref(), Some("3-40"));
+        assert!(pipeline.ocr_skip_pictures);
+        assert!(Config::from_toml("[ocr]\npages = \"40-3\"\n").is_err());
+
+        let cli = CliOverrides {
+            ocr_pages: Some(crate::parse_page_ranges("1-").unwrap()),
+            ocr_skip_pictures: Some(false),
+            ..Default::default()
+        };
+        let merged = config.merge_with_cli(&cli);
+        assert_eq!(merged.ocr_pages.map(|pages| pages.to_string()).as_deref(), Some("1-"));
+        assert!(!merged.ocr_skip_pictures);
+    }
+
     // CFG-003: Config::load_from_path (non-existent file)
     #[test]
     fn test_config_load_from_path_not_found() {
@@ -560,6 +1687,7 @@ deskew = true
                 color_correction: Some(true),
                 offset_alignment: Some(true),
                 output_height: Some(4000),
+                ..Default::default()
             },
             ocr: OcrConfig {
                 enabled: Some(true),
@@ -611,13 +1739,22 @@ output_height = 3508
 [ocr]
 enabled = true
 language = "ja"
+min_confidence = 70.0
 
 [output]
 jpeg_quality = 90
 skip_existing = true
+
+[hooks]
+post = "/usr/local/bin/publish-book"
 "#;
 
         let config = Config::from_toml(toml).unwrap();
+        assert_eq!(config.ocr.min_confidence, Some(70.0));
+        assert_eq!(config.to_pipeline_config().ocr_min_confidence, 70.0);
+        assert_eq!(config.hooks.post.as_deref(), Some("/usr/local/bin/publish-book"));
+        assert_eq!(config.to_pipeline_config().post_hook.as_deref(), Some("/usr/local/bin/publish-book"));
+        assert!(config.to_pipeline_config().page_hook.is_none());
         assert_eq!(config.general.dpi, Some(300));
         assert_eq!(config.general.threads, Some(4));
         assert_eq!(config.general.verbose, Some(2));
@@ -629,6 +1766,62 @@ skip_existing = true
         assert_eq!(config.output.skip_existing, Some(true));
     }
 
+    #[test]
+    fn test_config_tool_paths() {
+        let toml = r#"
+[tools]
+pdftoppm = "/opt/poppler/bin/pdftoppm"
+"#;
+        let config = Config::from_toml(toml).unwrap();
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(
+            pipeline.tool_paths.get("pdftoppm"),
+            Some(&PathBuf::from("/opt/poppler/bin/pdftoppm"))
+        );
+        // Tool locations do not affect the cache digest
+        assert!(!pipeline.to_json().contains("pdftoppm"));
+        assert!(Config::default().to_toml().unwrap().find("[tools]").is_none());
+    }
+
+    #[test]
+    fn test_config_group_crop_params() {
+        let toml = r#"
+[advanced]
+tukey_k = 3.0
+min_inlier_count = 2
+"#;
+        let config = Config::from_toml(toml).unwrap().to_pipeline_config();
+        assert_eq!(config.group_crop.tukey_k, 3.0);
+        assert_eq!(config.group_crop.min_inlier_count, 2);
+        assert_eq!(config.group_crop.min_inlier_ratio, 0.5);
+        assert_eq!(config.group_crop.aggregation, crate::CropAggregation::Median);
+
+        let toml = r#"
+[advanced]
+crop_aggregation = { percentile = 90.0 }
+"#;
+        let config = Config::from_toml(toml).unwrap().to_pipeline_config();
+        assert_eq!(config.group_crop.aggregation, crate::CropAggregation::Percentile(90.0));
+        let config = Config::from_toml("[advanced]\ncrop_aggregation = \"extremes\"").unwrap();
+        assert_eq!(config.advanced.crop_aggregation, Some(crate::CropAggregation::Extremes));
+
+        // Defaults stay out of the serialized config (cache digest unchanged)
+        assert!(!PipelineConfig::default().to_json().contains("group_crop"));
+    }
+
+    #[test]
+    fn test_config_register_sides() {
+        let config = Config::from_toml("[advanced]\nregister_sides = true\n").unwrap();
+        assert!(config.to_pipeline_config().register_sides);
+        assert!(!PipelineConfig::default().to_json().contains("register_sides"));
+
+        let cli = CliOverrides {
+            register_sides: Some(false),
+            ..Default::default()
+        };
+        assert!(!config.merge_with_cli(&cli).register_sides);
+    }
+
     // CFG-008: TOML parse (partial config)
     #[test]
     fn test_config_toml_parse_partial() {
@@ -689,6 +1882,91 @@ dpi = 600
         assert_eq!(overrides.ocr, Some(true));
     }
 
+    #[test]
+    fn test_config_merge_auto_contrast() {
+        let config = Config::default();
+        assert_eq!(config.to_pipeline_config().auto_contrast, crate::AutoContrast::Off);
+
+        let overrides = CliOverrides::new().with_auto_contrast(crate::AutoContrast::Clahe);
+        let merged = config.merge_with_cli(&overrides);
+        assert_eq!(merged.auto_contrast, crate::AutoContrast::Clahe);
+        assert!(!merged.auto_contrast_per_channel);
+    }
+
+    #[test]
+    fn test_config_merge_ocr_formats() {
+        let config = Config::default();
+        assert!(config.to_pipeline_config().ocr_formats.is_empty());
+
+        let overrides = CliOverrides::new()
+            .with_ocr(true)
+            .with_ocr_formats(vec![crate::markdown::OcrExportFormat::Alto]);
+        let merged = config.merge_with_cli(&overrides);
+        assert!(merged.ocr);
+        assert_eq!(merged.ocr_formats, vec![crate::markdown::OcrExportFormat::Alto]);
+    }
+
+    #[test]
+    fn test_config_metadata_section() {
+        let config = Config::from_toml(
+            r#"
+[metadata]
+author = "Natsume Soseki"
+keywords = "novel, cat"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.metadata.author.as_deref(), Some("Natsume Soseki"));
+
+        let pipeline = config.to_pipeline_config();
+        assert_eq!(pipeline.metadata.keyword_list(), vec!["novel", "cat"]);
+        assert!(pipeline.metadata.title.is_none());
+
+        // Empty metadata is omitted when serializing
+        assert!(!Config::default().to_toml().unwrap().contains("[metadata]"));
+    }
+
+    #[test]
+    fn test_config_merge_deterministic() {
+        let config = Config::default();
+        assert!(!config.to_pipeline_config().deterministic);
+
+        let overrides = CliOverrides {
+            deterministic: Some(true),
+            ..Default::default()
+        };
+        assert!(config.merge_with_cli(&overrides).deterministic);
+    }
+
+    #[test]
+    fn test_config_merge_preserve_source() {
+        let config = Config::default();
+        let overrides = CliOverrides {
+            preserve_source_metadata: Some(true),
+            preserve_source_outline: Some(true),
+            ..Default::default()
+        };
+        let merged = config.merge_with_cli(&overrides);
+        assert!(merged.preserve_source_metadata);
+        assert!(merged.preserve_source_outline);
+        assert!(!config.to_pipeline_config().preserve_source_outline);
+    }
+
+    #[test]
+    fn test_config_merge_extract_queue_depth() {
+        let config = Config::default();
+        assert_eq!(
+            config.to_pipeline_config().extract_queue_depth,
+            crate::pipeline::DEFAULT_EXTRACT_QUEUE_DEPTH
+        );
+
+        let overrides = CliOverrides {
+            extract_queue_depth: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(config.merge_with_cli(&overrides).extract_queue_depth, 0);
+    }
+
     #[test]
     fn test_config_error_display() {
         let err = ConfigError::NotFound(PathBuf::from("/test/path"));
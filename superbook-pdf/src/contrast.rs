@@ -0,0 +1,1124 @@
+//! Auto Contrast module
+//!
+//! Provides contrast enhancement for faint or unevenly lit scans.
+//!
+//! # Features
+//!
+//! - Percentile-based contrast stretch (auto levels), luminance or per-channel
+//! - CLAHE (Contrast Limited Adaptive Histogram Equalization) for uneven lighting
+//! - Sampled-page before/after previews
+//! - Manual tone curve (levels) from control points, as a 256-entry LUT
+//! - Detection and inversion of negative (white-on-black) scans
+//!
+//! # Example
+//!
+//! ```rust
+//! use image::RgbImage;
+//! use superbook_pdf::contrast::{AutoContrast, AutoContrastOptions, ContrastEnhancer};
+//!
+//! let mut image = RgbImage::from_pixel(64, 64, image::Rgb([120, 120, 120]));
+//! let options = AutoContrastOptions::new(AutoContrast::percentile(1.0, 99.0));
+//! let result = ContrastEnhancer::apply(&mut image, &options);
+//! assert!(!result.applied); // flat image: nothing to stretch
+//! ```
+
+use image::{imageops::FilterType, GrayImage, Luma, RgbImage};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Default low percentile for contrast stretch
+pub const DEFAULT_LOW_PERCENTILE: f32 = 1.0;
+
+/// Default high percentile for contrast stretch
+pub const DEFAULT_HIGH_PERCENTILE: f32 = 99.0;
+
+/// Number of CLAHE tiles along each axis
+const CLAHE_TILES: u32 = 8;
+
+/// CLAHE clip limit (multiple of the uniform histogram height)
+const CLAHE_CLIP_LIMIT: f32 = 2.0;
+
+/// Sample step for histogram building (skip pixels for performance)
+const SAMPLE_STEP: u32 = 2;
+
+/// Minimum level spread required to stretch (avoid amplifying noise on blank pages)
+const MIN_LEVEL_SPREAD: u8 = 8;
+
+/// Height of preview images
+const PREVIEW_HEIGHT: u32 = 800;
+
+/// Highest mean luminance of a negative scan
+const NEGATIVE_MAX_MEAN: f32 = 90.0;
+
+/// Lowest luminance standard deviation of a negative scan (a dark blank
+/// page or a black border is flat)
+const NEGATIVE_MIN_STD_DEV: f32 = 24.0;
+
+// ============================================================
+// Error Types
+// ============================================================
+
+/// Auto contrast error types
+#[derive(Debug, Error)]
+pub enum ContrastError {
+    #[error("Image not found: {0}")]
+    ImageNotFound(PathBuf),
+
+    #[error("Invalid image: {0}")]
+    InvalidImage(String),
+
+    #[error("Invalid tone curve: {0}")]
+    InvalidToneCurve(String),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, ContrastError>;
+
+// ============================================================
+// Data Structures
+// ============================================================
+
+/// Auto contrast mode
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoContrast {
+    /// No contrast enhancement
+    #[default]
+    Off,
+    /// Linear stretch between the given low/high percentiles (0-100)
+    Percentile(f32, f32),
+    /// Contrast Limited Adaptive Histogram Equalization
+    Clahe,
+}
+
+impl AutoContrast {
+    /// Percentile stretch with sanitized bounds (clamped to 0-100, low < high)
+    pub fn percentile(low: f32, high: f32) -> Self {
+        let low = low.clamp(0.0, 100.0);
+        let high = high.clamp(0.0, 100.0);
+        if low < high {
+            AutoContrast::Percentile(low, high)
+        } else {
+            AutoContrast::Percentile(DEFAULT_LOW_PERCENTILE, DEFAULT_HIGH_PERCENTILE)
+        }
+    }
+
+    /// Whether contrast enhancement is enabled
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, AutoContrast::Off)
+    }
+
+    /// Human-readable description
+    pub fn describe(&self) -> String {
+        match self {
+            AutoContrast::Off => "off".to_string(),
+            AutoContrast::Percentile(low, high) => format!("percentile ({}-{}%)", low, high),
+            AutoContrast::Clahe => "clahe".to_string(),
+        }
+    }
+}
+
+/// Auto contrast options
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct AutoContrastOptions {
+    /// Contrast mode
+    pub mode: AutoContrast,
+    /// Process R/G/B independently instead of luminance (also neutralizes color casts)
+    pub per_channel: bool,
+}
+
+impl AutoContrastOptions {
+    /// Create options for the given mode
+    pub fn new(mode: AutoContrast) -> Self {
+        Self {
+            mode,
+            per_channel: false,
+        }
+    }
+
+    /// Set per-channel processing
+    pub fn with_per_channel(mut self, per_channel: bool) -> Self {
+        self.per_channel = per_channel;
+        self
+    }
+}
+
+/// Interpolation between tone curve control points
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToneInterpolation {
+    /// Straight segments between points
+    Linear,
+    /// Monotone cubic (Fritsch-Carlson): smooth, without overshoot
+    #[default]
+    Monotone,
+}
+
+/// Manual tone curve from (input, output) control points (0-255)
+///
+/// Inputs must be strictly increasing; levels outside the first/last point
+/// map to that point's output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "ToneCurveSpec")]
+pub struct ToneCurve {
+    /// Control points (input, output)
+    pub points: Vec<(u8, u8)>,
+    /// Interpolation between points
+    #[serde(default)]
+    pub interpolation: ToneInterpolation,
+    /// Apply the curve to each R/G/B channel instead of luminance
+    #[serde(default)]
+    pub per_channel: bool,
+}
+
+/// Unvalidated tone curve as written in config/JSON
+#[derive(Deserialize)]
+struct ToneCurveSpec {
+    points: Vec<(u8, u8)>,
+    #[serde(default)]
+    interpolation: ToneInterpolation,
+    #[serde(default)]
+    per_channel: bool,
+}
+
+impl TryFrom<ToneCurveSpec> for ToneCurve {
+    type Error = ContrastError;
+
+    fn try_from(spec: ToneCurveSpec) -> Result<Self> {
+        Ok(ToneCurve::new(spec.points)?
+            .with_interpolation(spec.interpolation)
+            .with_per_channel(spec.per_channel))
+    }
+}
+
+impl ToneCurve {
+    /// Create a monotone-cubic luminance curve, validating the control points
+    pub fn new(points: Vec<(u8, u8)>) -> Result<Self> {
+        if points.len() < 2 {
+            return Err(ContrastError::InvalidToneCurve(format!(
+                "need at least 2 control points, got {}",
+                points.len()
+            )));
+        }
+        if let Some(pair) = points.windows(2).find(|pair| pair[1].0 <= pair[0].0) {
+            return Err(ContrastError::InvalidToneCurve(format!(
+                "inputs must be strictly increasing ({} then {})",
+                pair[0].0, pair[1].0
+            )));
+        }
+        Ok(Self {
+            points,
+            interpolation: ToneInterpolation::default(),
+            per_channel: false,
+        })
+    }
+
+    /// Set interpolation
+    pub fn with_interpolation(mut self, interpolation: ToneInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Set per-channel processing
+    pub fn with_per_channel(mut self, per_channel: bool) -> Self {
+        self.per_channel = per_channel;
+        self
+    }
+
+    /// Whether the curve maps every level to itself
+    pub fn is_identity(&self) -> bool {
+        self.lut().iter().enumerate().all(|(i, &v)| i == v as usize)
+    }
+
+    /// Human-readable description
+    pub fn describe(&self) -> String {
+        let points: Vec<String> = self.points.iter().map(|(i, o)| format!("{}:{}", i, o)).collect();
+        let interpolation = match self.interpolation {
+            ToneInterpolation::Linear => "linear",
+            ToneInterpolation::Monotone => "monotone",
+        };
+        let target = if self.per_channel { "per channel" } else { "luminance" };
+        format!("{} {}, {}", interpolation, points.join(","), target)
+    }
+
+    /// 256-entry lookup table
+    pub fn lut(&self) -> [u8; 256] {
+        let xs: Vec<f32> = self.points.iter().map(|p| p.0 as f32).collect();
+        let ys: Vec<f32> = self.points.iter().map(|p| p.1 as f32).collect();
+        let slopes: Vec<f32> = (0..xs.len() - 1).map(|k| (ys[k + 1] - ys[k]) / (xs[k + 1] - xs[k])).collect();
+        let tangents = match self.interpolation {
+            ToneInterpolation::Linear => Vec::new(),
+            ToneInterpolation::Monotone => monotone_tangents(&slopes),
+        };
+
+        let mut lut = [0u8; 256];
+        for (level, value) in lut.iter_mut().enumerate() {
+            let x = level as f32;
+            let y = if x <= xs[0] {
+                ys[0]
+            } else if x >= xs[xs.len() - 1] {
+                ys[ys.len() - 1]
+            } else {
+                let k = xs.windows(2).position(|w| x < w[1]).unwrap_or(xs.len() - 2);
+                let h = xs[k + 1] - xs[k];
+                let t = (x - xs[k]) / h;
+                match self.interpolation {
+                    ToneInterpolation::Linear => ys[k] + (ys[k + 1] - ys[k]) * t,
+                    ToneInterpolation::Monotone => {
+                        let (t2, t3) = (t * t, t * t * t);
+                        (2.0 * t3 - 3.0 * t2 + 1.0) * ys[k]
+                            + (t3 - 2.0 * t2 + t) * h * tangents[k]
+                            + (-2.0 * t3 + 3.0 * t2) * ys[k + 1]
+                            + (t3 - t2) * h * tangents[k + 1]
+                    }
+                }
+            };
+            *value = y.round().clamp(0.0, 255.0) as u8;
+        }
+        lut
+    }
+
+    /// Apply the curve in place
+    ///
+    /// In luminance mode each pixel is shifted by its luminance change, so
+    /// hue is kept; per channel the LUT maps R, G and B directly.
+    pub fn apply(&self, image: &mut RgbImage) {
+        let lut = self.lut();
+        for pixel in image.pixels_mut() {
+            if self.per_channel {
+                for c in 0..3 {
+                    pixel.0[c] = lut[pixel.0[c] as usize];
+                }
+            } else {
+                let luma = luminance(pixel.0);
+                let delta = lut[luma as usize] as i16 - luma as i16;
+                for c in 0..3 {
+                    pixel.0[c] = (pixel.0[c] as i16 + delta).clamp(0, 255) as u8;
+                }
+            }
+        }
+    }
+
+    /// Apply the curve to an image file
+    pub fn process(&self, input_path: &Path, output_path: &Path) -> Result<()> {
+        if !input_path.exists() {
+            return Err(ContrastError::ImageNotFound(input_path.to_path_buf()));
+        }
+        let mut rgb = image::open(input_path)
+            .map_err(|e| ContrastError::InvalidImage(e.to_string()))?
+            .to_rgb8();
+        self.apply(&mut rgb);
+        rgb.save(output_path)
+            .map_err(|e| ContrastError::InvalidImage(e.to_string()))
+    }
+}
+
+/// Parse a tone curve written as `IN:OUT,IN:OUT,...` (e.g. `0:0,64:40,255:255`)
+pub fn parse_tone_curve(value: &str) -> std::result::Result<ToneCurve, String> {
+    let points = value
+        .split(',')
+        .map(|point| {
+            let (input, output) = point
+                .split_once(':')
+                .ok_or_else(|| format!("expected IN:OUT, got '{}'", point.trim()))?;
+            let level = |s: &str| {
+                s.trim()
+                    .parse::<u8>()
+                    .map_err(|_| format!("level must be 0-255, got '{}'", s.trim()))
+            };
+            Ok((level(input)?, level(output)?))
+        })
+        .collect::<std::result::Result<Vec<_>, String>>()?;
+    ToneCurve::new(points).map_err(|e| e.to_string())
+}
+
+/// Fritsch-Carlson tangents for monotone cubic Hermite interpolation
+fn monotone_tangents(slopes: &[f32]) -> Vec<f32> {
+    let n = slopes.len() + 1;
+    let mut tangents = vec![0.0; n];
+    tangents[0] = slopes[0];
+    tangents[n - 1] = slopes[n - 2];
+    for k in 1..n - 1 {
+        tangents[k] = if slopes[k - 1] * slopes[k] <= 0.0 {
+            0.0
+        } else {
+            (slopes[k - 1] + slopes[k]) / 2.0
+        };
+    }
+    for k in 0..n - 1 {
+        if slopes[k] == 0.0 {
+            tangents[k] = 0.0;
+            tangents[k + 1] = 0.0;
+            continue;
+        }
+        let (a, b) = (tangents[k] / slopes[k], tangents[k + 1] / slopes[k]);
+        let norm = a * a + b * b;
+        if norm > 9.0 {
+            let tau = 3.0 / norm.sqrt();
+            tangents[k] = tau * a * slopes[k];
+            tangents[k + 1] = tau * b * slopes[k];
+        }
+    }
+    tangents
+}
+
+/// Result of contrast enhancement on a single image
+#[derive(Debug, Clone, Default)]
+pub struct ContrastResult {
+    /// Whether the image was modified
+    pub applied: bool,
+    /// Stretch levels (low, high) per channel R/G/B (percentile mode only)
+    pub levels: Option<[(u8, u8); 3]>,
+}
+
+/// Negative (white-on-black) scan handling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Invert {
+    /// Never invert
+    #[default]
+    Off,
+    /// Invert pages detected as negatives
+    Auto,
+    /// Invert every page
+    Force,
+}
+
+impl Invert {
+    /// Whether inversion is disabled
+    pub fn is_off(&self) -> bool {
+        *self == Invert::Off
+    }
+}
+
+/// Luminance statistics behind the negative scan decision
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeDetection {
+    /// Mean luminance (0-255)
+    pub mean: f32,
+    /// Luminance standard deviation
+    pub std_dev: f32,
+    /// Whether the page looks like a negative
+    pub negative: bool,
+}
+
+/// Detection and inversion of negative scans (e.g. microfilm)
+///
+/// Binarization, margin detection and OCR assume dark content on a light
+/// background, so negatives are inverted before any other page stage.
+pub struct NegativeScan;
+
+impl NegativeScan {
+    /// Detect a negative from its luminance histogram
+    ///
+    /// A negative has a very low mean with high variance from the light
+    /// content; flat dark pages (blank film, black covers) are left alone.
+    pub fn detect(image: &RgbImage) -> NegativeDetection {
+        let hist = ContrastEnhancer::luma_histogram(image);
+        let total: u64 = hist.iter().sum();
+        if total == 0 {
+            return NegativeDetection {
+                mean: 0.0,
+                std_dev: 0.0,
+                negative: false,
+            };
+        }
+        let mean = hist.iter().enumerate().map(|(v, &n)| v as f64 * n as f64).sum::<f64>() / total as f64;
+        let variance = hist
+            .iter()
+            .enumerate()
+            .map(|(v, &n)| (v as f64 - mean).powi(2) * n as f64)
+            .sum::<f64>()
+            / total as f64;
+        let (mean, std_dev) = (mean as f32, variance.sqrt() as f32);
+        NegativeDetection {
+            mean,
+            std_dev,
+            negative: mean <= NEGATIVE_MAX_MEAN && std_dev >= NEGATIVE_MIN_STD_DEV,
+        }
+    }
+
+    /// Invert every channel in place
+    pub fn invert(image: &mut RgbImage) {
+        image::imageops::invert(image);
+    }
+
+    /// Invert `image` per `mode`, returning whether it was inverted
+    pub fn apply(image: &mut RgbImage, mode: Invert) -> bool {
+        let invert = match mode {
+            Invert::Off => false,
+            Invert::Auto => Self::detect(image).negative,
+            Invert::Force => true,
+        };
+        if invert {
+            Self::invert(image);
+        }
+        invert
+    }
+
+    /// Invert an image file per `mode`
+    ///
+    /// The output is only written when the page was inverted; the return
+    /// value tells whether it was.
+    pub fn process(input_path: &Path, output_path: &Path, mode: Invert) -> Result<bool> {
+        if !input_path.exists() {
+            return Err(ContrastError::ImageNotFound(input_path.to_path_buf()));
+        }
+        let mut rgb = image::open(input_path)
+            .map_err(|e| ContrastError::InvalidImage(e.to_string()))?
+            .to_rgb8();
+        if !Self::apply(&mut rgb, mode) {
+            return Ok(false);
+        }
+        rgb.save(output_path)
+            .map_err(|e| ContrastError::InvalidImage(e.to_string()))?;
+        Ok(true)
+    }
+}
+
+// ============================================================
+// Contrast Enhancer
+// ============================================================
+
+/// Contrast enhancement processor
+pub struct ContrastEnhancer;
+
+impl ContrastEnhancer {
+    /// Apply contrast enhancement to an image file
+    pub fn process(
+        input_path: &Path,
+        output_path: &Path,
+        options: &AutoContrastOptions,
+    ) -> Result<ContrastResult> {
+        if !input_path.exists() {
+            return Err(ContrastError::ImageNotFound(input_path.to_path_buf()));
+        }
+
+        let img = image::open(input_path).map_err(|e| ContrastError::InvalidImage(e.to_string()))?;
+        let mut rgb = img.to_rgb8();
+        let result = Self::apply(&mut rgb, options);
+        rgb.save(output_path)
+            .map_err(|e| ContrastError::InvalidImage(e.to_string()))?;
+
+        Ok(result)
+    }
+
+    /// Apply contrast enhancement in place
+    pub fn apply(image: &mut RgbImage, options: &AutoContrastOptions) -> ContrastResult {
+        match options.mode {
+            AutoContrast::Off => ContrastResult::default(),
+            AutoContrast::Percentile(low, high) => {
+                Self::apply_percentile(image, low, high, options.per_channel)
+            }
+            AutoContrast::Clahe => Self::apply_clahe(image, options.per_channel),
+        }
+    }
+
+    /// Linear stretch between low/high percentiles
+    fn apply_percentile(image: &mut RgbImage, low: f32, high: f32, per_channel: bool) -> ContrastResult {
+        let levels: [(u8, u8); 3] = if per_channel {
+            let hists = Self::channel_histograms(image);
+            [
+                Self::percentile_levels(&hists[0], low, high),
+                Self::percentile_levels(&hists[1], low, high),
+                Self::percentile_levels(&hists[2], low, high),
+            ]
+        } else {
+            let levels = Self::percentile_levels(&Self::luma_histogram(image), low, high);
+            [levels; 3]
+        };
+
+        if levels
+            .iter()
+            .all(|&(lo, hi)| hi.saturating_sub(lo) < MIN_LEVEL_SPREAD || (lo == 0 && hi == 255))
+        {
+            return ContrastResult {
+                applied: false,
+                levels: Some(levels),
+            };
+        }
+
+        let luts = levels.map(|(lo, hi)| Self::stretch_lut(lo, hi));
+        for pixel in image.pixels_mut() {
+            for c in 0..3 {
+                pixel.0[c] = luts[c][pixel.0[c] as usize];
+            }
+        }
+
+        ContrastResult {
+            applied: true,
+            levels: Some(levels),
+        }
+    }
+
+    /// CLAHE on luminance (shifting all channels equally) or on each channel
+    fn apply_clahe(image: &mut RgbImage, per_channel: bool) -> ContrastResult {
+        let (w, h) = image.dimensions();
+        if w == 0 || h == 0 {
+            return ContrastResult::default();
+        }
+
+        if per_channel {
+            for c in 0..3 {
+                let channel = GrayImage::from_fn(w, h, |x, y| Luma([image.get_pixel(x, y).0[c]]));
+                let equalized = Self::clahe(&channel, CLAHE_TILES, CLAHE_CLIP_LIMIT);
+                for (x, y, pixel) in image.enumerate_pixels_mut() {
+                    pixel.0[c] = equalized.get_pixel(x, y).0[0];
+                }
+            }
+        } else {
+            let luma = GrayImage::from_fn(w, h, |x, y| Luma([luminance(image.get_pixel(x, y).0)]));
+            let equalized = Self::clahe(&luma, CLAHE_TILES, CLAHE_CLIP_LIMIT);
+            for (x, y, pixel) in image.enumerate_pixels_mut() {
+                let delta = equalized.get_pixel(x, y).0[0] as i16 - luma.get_pixel(x, y).0[0] as i16;
+                for c in 0..3 {
+                    pixel.0[c] = (pixel.0[c] as i16 + delta).clamp(0, 255) as u8;
+                }
+            }
+        }
+
+        ContrastResult {
+            applied: true,
+            levels: None,
+        }
+    }
+
+    /// Find the levels at the given low/high percentiles of a histogram
+    pub fn percentile_levels(hist: &[u64; 256], low: f32, high: f32) -> (u8, u8) {
+        let total: u64 = hist.iter().sum();
+        if total == 0 {
+            return (0, 255);
+        }
+
+        let find = |percent: f32| -> u8 {
+            let target = (total as f64 * percent as f64 / 100.0).ceil().max(1.0) as u64;
+            let mut cumulative = 0u64;
+            for (level, &count) in hist.iter().enumerate() {
+                cumulative += count;
+                if cumulative >= target {
+                    return level as u8;
+                }
+            }
+            255
+        };
+
+        (find(low), find(high))
+    }
+
+    /// Build a lookup table mapping [low, high] linearly to [0, 255]
+    pub fn stretch_lut(low: u8, high: u8) -> [u8; 256] {
+        let mut lut = [0u8; 256];
+        if high <= low {
+            for (i, v) in lut.iter_mut().enumerate() {
+                *v = i as u8;
+            }
+            return lut;
+        }
+
+        let range = (high - low) as f32;
+        for (i, v) in lut.iter_mut().enumerate() {
+            let stretched = (i as f32 - low as f32) * 255.0 / range;
+            *v = stretched.round().clamp(0.0, 255.0) as u8;
+        }
+        lut
+    }
+
+    /// Contrast Limited Adaptive Histogram Equalization on a grayscale image
+    ///
+    /// The image is split into `tiles`×`tiles` regions; each region's histogram
+    /// is clipped at `clip_limit` times the uniform height before equalization,
+    /// and the per-tile mappings are bilinearly interpolated.
+    pub fn clahe(gray: &GrayImage, tiles: u32, clip_limit: f32) -> GrayImage {
+        let (w, h) = gray.dimensions();
+        if w == 0 || h == 0 {
+            return gray.clone();
+        }
+
+        let tiles_x = tiles.clamp(1, w);
+        let tiles_y = tiles.clamp(1, h);
+        let tile_w = w.div_ceil(tiles_x);
+        let tile_h = h.div_ceil(tiles_y);
+
+        // Per-tile mapping functions
+        let luts: Vec<[u8; 256]> = (0..tiles_y * tiles_x)
+            .into_par_iter()
+            .map(|i| {
+                let (tx, ty) = (i % tiles_x, i / tiles_x);
+                let x0 = tx * tile_w;
+                let y0 = ty * tile_h;
+                let x1 = (x0 + tile_w).min(w);
+                let y1 = (y0 + tile_h).min(h);
+
+                let mut hist = [0u64; 256];
+                for y in y0..y1 {
+                    for x in x0..x1 {
+                        hist[gray.get_pixel(x, y).0[0] as usize] += 1;
+                    }
+                }
+                let area = ((x1.saturating_sub(x0)) * (y1.saturating_sub(y0))) as u64;
+                clipped_equalization_lut(&mut hist, area, clip_limit)
+            })
+            .collect();
+
+        let mut output = GrayImage::new(w, h);
+        for (x, y, pixel) in output.enumerate_pixels_mut() {
+            let value = gray.get_pixel(x, y).0[0] as usize;
+
+            let fx = (x as f32 + 0.5) / tile_w as f32 - 0.5;
+            let fy = (y as f32 + 0.5) / tile_h as f32 - 0.5;
+            let tx0 = fx.floor().clamp(0.0, (tiles_x - 1) as f32) as u32;
+            let ty0 = fy.floor().clamp(0.0, (tiles_y - 1) as f32) as u32;
+            let tx1 = (tx0 + 1).min(tiles_x - 1);
+            let ty1 = (ty0 + 1).min(tiles_y - 1);
+            let ax = (fx - tx0 as f32).clamp(0.0, 1.0);
+            let ay = (fy - ty0 as f32).clamp(0.0, 1.0);
+
+            let lut = |tx: u32, ty: u32| luts[(ty * tiles_x + tx) as usize][value] as f32;
+            let top = lut(tx0, ty0) * (1.0 - ax) + lut(tx1, ty0) * ax;
+            let bottom = lut(tx0, ty1) * (1.0 - ax) + lut(tx1, ty1) * ax;
+            let v = top * (1.0 - ay) + bottom * ay;
+
+            *pixel = Luma([v.round().clamp(0.0, 255.0) as u8]);
+        }
+
+        output
+    }
+
+    /// Generate before/after previews for a sample of pages
+    ///
+    /// Picks up to `samples` evenly spaced pages and writes side-by-side
+    /// `preview_page_NNNN.png` images (original left, enhanced right).
+    pub fn preview(
+        images: &[PathBuf],
+        output_dir: &Path,
+        options: &AutoContrastOptions,
+        samples: usize,
+    ) -> Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(output_dir)?;
+
+        sample_indices(images.len(), samples)
+            .into_iter()
+            .map(|index| {
+                let img = image::open(&images[index])
+                    .map_err(|e| ContrastError::InvalidImage(e.to_string()))?;
+                let before = img
+                    .resize(u32::MAX, PREVIEW_HEIGHT.min(img.height()), FilterType::Triangle)
+                    .to_rgb8();
+                let mut after = before.clone();
+                Self::apply(&mut after, options);
+
+                let (w, h) = before.dimensions();
+                let mut combined = RgbImage::from_pixel(w * 2, h, image::Rgb([255, 255, 255]));
+                image::imageops::replace(&mut combined, &before, 0, 0);
+                image::imageops::replace(&mut combined, &after, w as i64, 0);
+
+                let path = output_dir.join(format!("preview_page_{:04}.png", index));
+                combined
+                    .save(&path)
+                    .map_err(|e| ContrastError::InvalidImage(e.to_string()))?;
+                Ok(path)
+            })
+            .collect()
+    }
+
+    fn luma_histogram(image: &RgbImage) -> [u64; 256] {
+        let mut hist = [0u64; 256];
+        for y in (0..image.height()).step_by(SAMPLE_STEP as usize) {
+            for x in (0..image.width()).step_by(SAMPLE_STEP as usize) {
+                hist[luminance(image.get_pixel(x, y).0) as usize] += 1;
+            }
+        }
+        hist
+    }
+
+    fn channel_histograms(image: &RgbImage) -> [[u64; 256]; 3] {
+        let mut hists = [[0u64; 256]; 3];
+        for y in (0..image.height()).step_by(SAMPLE_STEP as usize) {
+            for x in (0..image.width()).step_by(SAMPLE_STEP as usize) {
+                let pixel = image.get_pixel(x, y).0;
+                for c in 0..3 {
+                    hists[c][pixel[c] as usize] += 1;
+                }
+            }
+        }
+        hists
+    }
+}
+
+/// ITU-R BT.601 luminance
+fn luminance(rgb: [u8; 3]) -> u8 {
+    (0.299 * rgb[0] as f32 + 0.587 * rgb[1] as f32 + 0.114 * rgb[2] as f32).round() as u8
+}
+
+/// Clip a histogram, redistribute the excess and return the equalization LUT
+///
+/// Empty tiles (the grid overhangs a small image) map every level to itself so
+/// blending with them leaves neighbouring pixels untouched.
+fn clipped_equalization_lut(hist: &mut [u64; 256], area: u64, clip_limit: f32) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    if area == 0 {
+        for (i, v) in lut.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+        return lut;
+    }
+
+    let limit = ((clip_limit * area as f32 / 256.0) as u64).max(1);
+    let mut excess = 0u64;
+    for count in hist.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+    let bonus = excess / 256;
+    let remainder = (excess % 256) as usize;
+    for (i, count) in hist.iter_mut().enumerate() {
+        *count += bonus + u64::from(i < remainder);
+    }
+
+    let mut cumulative = 0u64;
+    for (i, &count) in hist.iter().enumerate() {
+        cumulative += count;
+        lut[i] = ((cumulative as f64 * 255.0) / area as f64).round().min(255.0) as u8;
+    }
+    lut
+}
+
+/// Evenly spaced sample indices (first and last page included)
+fn sample_indices(len: usize, samples: usize) -> Vec<usize> {
+    let samples = samples.min(len);
+    match samples {
+        0 => Vec::new(),
+        1 => vec![len / 2],
+        _ => {
+            let mut indices: Vec<usize> = (0..samples)
+                .map(|i| i * (len - 1) / (samples - 1))
+                .collect();
+            indices.dedup();
+            indices
+        }
+    }
+}
+
+// ============================================================
+// Tests
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgb;
+    use tempfile::tempdir;
+
+    /// Low-contrast gradient image (values 100..=150)
+    fn faded_image() -> RgbImage {
+        RgbImage::from_fn(100, 50, |x, _| {
+            let v = 100 + (x / 2) as u8;
+            Rgb([v, v, v])
+        })
+    }
+
+    #[test]
+    fn test_auto_contrast_default_off() {
+        assert_eq!(AutoContrast::default(), AutoContrast::Off);
+        assert!(!AutoContrast::Off.is_enabled());
+        assert!(AutoContrast::Clahe.is_enabled());
+    }
+
+    #[test]
+    fn test_auto_contrast_percentile_sanitized() {
+        assert_eq!(AutoContrast::percentile(2.0, 98.0), AutoContrast::Percentile(2.0, 98.0));
+        assert_eq!(AutoContrast::percentile(-5.0, 150.0), AutoContrast::Percentile(0.0, 100.0));
+        assert_eq!(
+            AutoContrast::percentile(99.0, 1.0),
+            AutoContrast::Percentile(DEFAULT_LOW_PERCENTILE, DEFAULT_HIGH_PERCENTILE)
+        );
+    }
+
+    #[test]
+    fn test_auto_contrast_describe() {
+        assert_eq!(AutoContrast::Off.describe(), "off");
+        assert_eq!(AutoContrast::Percentile(1.0, 99.0).describe(), "percentile (1-99%)");
+        assert_eq!(AutoContrast::Clahe.describe(), "clahe");
+    }
+
+    #[test]
+    fn test_auto_contrast_serde_roundtrip() {
+        for mode in [AutoContrast::Off, AutoContrast::Percentile(1.0, 99.0), AutoContrast::Clahe] {
+            let json = serde_json::to_string(&mode).unwrap();
+            let restored: AutoContrast = serde_json::from_str(&json).unwrap();
+            assert_eq!(mode, restored);
+        }
+    }
+
+    #[test]
+    fn test_percentile_levels() {
+        let mut hist = [0u64; 256];
+        hist[10] = 1;
+        hist[100] = 98;
+        hist[200] = 1;
+
+        assert_eq!(ContrastEnhancer::percentile_levels(&hist, 1.0, 99.0), (10, 100));
+        assert_eq!(ContrastEnhancer::percentile_levels(&hist, 0.0, 100.0), (10, 200));
+        assert_eq!(ContrastEnhancer::percentile_levels(&[0u64; 256], 1.0, 99.0), (0, 255));
+    }
+
+    #[test]
+    fn test_stretch_lut() {
+        let lut = ContrastEnhancer::stretch_lut(50, 200);
+        assert_eq!(lut[0], 0);
+        assert_eq!(lut[50], 0);
+        assert_eq!(lut[200], 255);
+        assert_eq!(lut[255], 255);
+        assert!(lut[100] > 50 && lut[100] < 128);
+
+        let identity = ContrastEnhancer::stretch_lut(100, 100);
+        assert_eq!(identity[42], 42);
+    }
+
+    #[test]
+    fn test_apply_off_is_noop() {
+        let mut image = faded_image();
+        let original = image.clone();
+        let result = ContrastEnhancer::apply(&mut image, &AutoContrastOptions::default());
+        assert!(!result.applied);
+        assert_eq!(image, original);
+    }
+
+    #[test]
+    fn test_apply_percentile_stretches_range() {
+        let mut image = faded_image();
+        let options = AutoContrastOptions::new(AutoContrast::Percentile(1.0, 99.0));
+        let result = ContrastEnhancer::apply(&mut image, &options);
+
+        assert!(result.applied);
+        let values: Vec<u8> = image.pixels().map(|p| p.0[0]).collect();
+        assert!(*values.iter().min().unwrap() <= 5);
+        assert!(*values.iter().max().unwrap() >= 250);
+    }
+
+    #[test]
+    fn test_apply_percentile_blank_page_untouched() {
+        let mut image = RgbImage::from_pixel(40, 40, Rgb([250, 250, 250]));
+        let original = image.clone();
+        let options = AutoContrastOptions::new(AutoContrast::Percentile(1.0, 99.0));
+        let result = ContrastEnhancer::apply(&mut image, &options);
+
+        assert!(!result.applied);
+        assert_eq!(image, original);
+    }
+
+    #[test]
+    fn test_apply_percentile_per_channel_neutralizes_cast() {
+        // Yellowish paper with dark ink
+        let mut image = RgbImage::from_fn(40, 40, |x, _| {
+            if x < 20 {
+                Rgb([60, 60, 40])
+            } else {
+                Rgb([230, 220, 160])
+            }
+        });
+        let options = AutoContrastOptions::new(AutoContrast::Percentile(1.0, 99.0)).with_per_channel(true);
+        let result = ContrastEnhancer::apply(&mut image, &options);
+
+        assert!(result.applied);
+        let levels = result.levels.unwrap();
+        assert_eq!(levels[2], (40, 160));
+        assert_eq!(image.get_pixel(30, 0).0, [255, 255, 255]);
+    }
+
+    #[test]
+    fn test_clahe_increases_local_contrast() {
+        let gray = GrayImage::from_fn(64, 64, |x, _| Luma([120 + (x % 8) as u8]));
+        let equalized = ContrastEnhancer::clahe(&gray, 4, 2.0);
+
+        let spread = |img: &GrayImage| {
+            let values: Vec<u8> = img.pixels().map(|p| p.0[0]).collect();
+            values.iter().max().unwrap() - values.iter().min().unwrap()
+        };
+        assert_eq!(equalized.dimensions(), gray.dimensions());
+        assert!(spread(&equalized) > spread(&gray));
+    }
+
+    #[test]
+    fn test_clahe_image_smaller_than_tile_grid() {
+        // 9px split into 4 tiles of 3px leaves the last tile empty
+        let gray = GrayImage::from_pixel(9, 9, Luma([200]));
+        let equalized = ContrastEnhancer::clahe(&gray, 4, 2.0);
+
+        assert_eq!(equalized.dimensions(), gray.dimensions());
+        assert!(equalized.pixels().all(|p| p.0[0] >= 200));
+    }
+
+    #[test]
+    fn test_apply_clahe_rgb() {
+        let mut image = faded_image();
+        let options = AutoContrastOptions::new(AutoContrast::Clahe);
+        let result = ContrastEnhancer::apply(&mut image, &options);
+
+        assert!(result.applied);
+        assert!(result.levels.is_none());
+        let p = image.get_pixel(10, 10).0;
+        assert_eq!(p[0], p[1]);
+        assert_eq!(p[1], p[2]);
+    }
+
+    #[test]
+    fn test_sample_indices() {
+        assert!(sample_indices(0, 3).is_empty());
+        assert_eq!(sample_indices(1, 3), vec![0]);
+        assert_eq!(sample_indices(10, 1), vec![5]);
+        assert_eq!(sample_indices(10, 3), vec![0, 4, 9]);
+        assert_eq!(sample_indices(2, 5), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_process_and_preview() {
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("page.png");
+        faded_image().save(&input).unwrap();
+
+        let output = dir.path().join("out.png");
+        let options = AutoContrastOptions::new(AutoContrast::Percentile(1.0, 99.0));
+        let result = ContrastEnhancer::process(&input, &output, &options).unwrap();
+        assert!(result.applied);
+        assert!(output.exists());
+
+        let previews =
+            ContrastEnhancer::preview(std::slice::from_ref(&input), &dir.path().join("preview"), &options, 3).unwrap();
+        assert_eq!(previews.len(), 1);
+        let preview = image::open(&previews[0]).unwrap();
+        assert_eq!(preview.width(), 200);
+    }
+
+    #[test]
+    fn test_tone_curve_validation() {
+        assert!(ToneCurve::new(vec![(0, 0)]).is_err());
+        assert!(ToneCurve::new(vec![(0, 0), (128, 100), (128, 200)]).is_err());
+        assert!(ToneCurve::new(vec![(200, 0), (100, 255)]).is_err());
+        assert!(ToneCurve::new(vec![(0, 0), (255, 255)]).unwrap().is_identity());
+
+        assert_eq!(parse_tone_curve("0:0, 64:40,255:255").unwrap().points, vec![(0, 0), (64, 40), (255, 255)]);
+        assert!(parse_tone_curve("0:0,256:255").is_err());
+        assert!(parse_tone_curve("0-0,255:255").is_err());
+        assert!(parse_tone_curve("255:255,0:0").is_err());
+
+        let curve: ToneCurve = serde_json::from_str(r#"{"points":[[0,10],[255,245]],"interpolation":"linear"}"#).unwrap();
+        assert_eq!(curve.interpolation, ToneInterpolation::Linear);
+        assert!(!curve.per_channel);
+        assert!(serde_json::from_str::<ToneCurve>(r#"{"points":[[10,0],[5,255]]}"#).is_err());
+    }
+
+    #[test]
+    fn test_tone_curve_lut() {
+        let linear = ToneCurve::new(vec![(32, 0), (96, 128), (224, 255)])
+            .unwrap()
+            .with_interpolation(ToneInterpolation::Linear)
+            .lut();
+        assert_eq!(linear[0], 0);
+        assert_eq!(linear[64], 64);
+        assert_eq!(linear[96], 128);
+        assert_eq!(linear[240], 255);
+
+        // Monotone cubic passes through the points without overshoot
+        let monotone = ToneCurve::new(vec![(0, 0), (64, 20), (128, 200), (255, 255)]).unwrap().lut();
+        assert_eq!((monotone[64], monotone[128], monotone[255]), (20, 200, 255));
+        assert!(monotone.windows(2).all(|w| w[0] <= w[1]));
+        assert!(monotone[100] > 20 && monotone[100] < 200);
+    }
+
+    #[test]
+    fn test_tone_curve_apply() {
+        // Darken faded text (gray 150 → 60), keep the white paper
+        let curve = ToneCurve::new(vec![(0, 0), (150, 60), (255, 255)]).unwrap();
+        let mut image = RgbImage::from_fn(2, 1, |x, _| if x == 0 { Rgb([150, 150, 150]) } else { Rgb([255, 255, 255]) });
+        curve.apply(&mut image);
+        assert_eq!(image.get_pixel(0, 0).0, [60, 60, 60]);
+        assert_eq!(image.get_pixel(1, 0).0, [255, 255, 255]);
+
+        // Luminance mode keeps the channel differences; per channel maps each
+        let mut tinted = RgbImage::from_pixel(1, 1, Rgb([160, 150, 140]));
+        let mut per_channel = tinted.clone();
+        curve.apply(&mut tinted);
+        let p = tinted.get_pixel(0, 0).0;
+        assert_eq!((p[0] as i16 - p[2] as i16), 20);
+        curve.clone().with_per_channel(true).apply(&mut per_channel);
+        let lut = curve.lut();
+        assert_eq!(per_channel.get_pixel(0, 0).0, [lut[160], lut[150], lut[140]]);
+    }
+
+    #[test]
+    fn test_process_missing_input() {
+        let dir = tempdir().unwrap();
+        let result = ContrastEnhancer::process(
+            Path::new("/nonexistent/page.png"),
+            &dir.path().join("out.png"),
+            &AutoContrastOptions::default(),
+        );
+        assert!(matches!(result, Err(ContrastError::ImageNotFound(_))));
+    }
+
+    /// White text lines on black, like a microfilm negative
+    fn negative_page() -> RgbImage {
+        RgbImage::from_fn(120, 160, |x, y| {
+            let text = (20..100).contains(&x) && y % 12 < 4 && (16..144).contains(&y);
+            if text {
+                Rgb([235, 235, 235])
+            } else {
+                Rgb([15, 15, 15])
+            }
+        })
+    }
+
+    #[test]
+    fn test_negative_scan_detection() {
+        let detection = NegativeScan::detect(&negative_page());
+        assert!(detection.negative);
+        assert!(detection.mean < 90.0);
+
+        // A normal page, a flat dark page and an empty image stay as they are
+        let mut normal = negative_page();
+        NegativeScan::invert(&mut normal);
+        assert!(!NegativeScan::detect(&normal).negative);
+        assert!(!NegativeScan::detect(&RgbImage::from_pixel(50, 50, Rgb([10, 10, 10]))).negative);
+        assert!(!NegativeScan::detect(&RgbImage::new(0, 0)).negative);
+    }
+
+    #[test]
+    fn test_negative_scan_apply() {
+        let mut page = negative_page();
+        assert!(NegativeScan::apply(&mut page, Invert::Auto));
+        assert_eq!(page.get_pixel(0, 0).0, [240, 240, 240]);
+        assert!(!NegativeScan::apply(&mut page, Invert::Auto));
+        assert!(!NegativeScan::apply(&mut page, Invert::Off));
+        assert!(NegativeScan::apply(&mut page, Invert::Force));
+        assert_eq!(page.get_pixel(0, 0).0, [15, 15, 15]);
+
+        let dir = tempdir().unwrap();
+        let input = dir.path().join("in.png");
+        let output = dir.path().join("out.png");
+        negative_page().save(&input).unwrap();
+        assert!(NegativeScan::process(&input, &output, Invert::Auto).unwrap());
+        assert_eq!(image::open(&output).unwrap().to_rgb8().get_pixel(0, 0).0, [240, 240, 240]);
+
+        let skipped = dir.path().join("skipped.png");
+        assert!(!NegativeScan::process(&output, &skipped, Invert::Auto).unwrap());
+        assert!(!skipped.exists());
+    }
+
+    #[test]
+    fn test_invert_serde() {
+        assert_eq!(serde_json::to_string(&Invert::Auto).unwrap(), "\"auto\"");
+        assert_eq!(serde_json::from_str::<Invert>("\"force\"").unwrap(), Invert::Force);
+        assert!(Invert::default().is_off());
+    }
+}
@@ -0,0 +1,456 @@
+//! Stage decision explanations for `--dry-run --explain`
+//!
+//! Extracts a few evenly spaced sample pages at low resolution and runs only
+//! the detection half of each stage (skew angle, shadow gradient, vertical
+//! writing probability, color ratio, tool availability) to justify why a
+//! full run would enable, skip or configure each stage. No image is modified
+//! and nothing is written outside a temporary directory.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::explain::{explain, DEFAULT_SAMPLE_PAGES};
+//! use superbook_pdf::{PdfPipeline, PipelineConfig};
+//! use std::path::Path;
+//!
+//! let pipeline = PdfPipeline::new(PipelineConfig::default());
+//! let report = explain(&pipeline, Path::new("book.pdf"), DEFAULT_SAMPLE_PAGES).unwrap();
+//! for decision in &report.decisions {
+//!     println!("{decision}");
+//! }
+//! ```
+
+use crate::pipeline::{PdfPipeline, PipelineError};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Number of pages sampled by default
+pub const DEFAULT_SAMPLE_PAGES: usize = 3;
+
+/// Maximum extraction DPI for samples (detections are resolution independent)
+pub const SAMPLE_DPI: u32 = 150;
+
+/// Why a stage would run (or not) for one input
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageDecision {
+    /// Stage key (matches stage timing keys where one exists)
+    pub stage: &'static str,
+    /// Whether the stage would run
+    pub enabled: bool,
+    /// Human-readable justification
+    pub reason: String,
+}
+
+impl StageDecision {
+    fn new(stage: &'static str, enabled: bool, reason: impl Into<String>) -> Self {
+        Self {
+            stage,
+            enabled,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl fmt::Display for StageDecision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = if self.enabled { "enabled" } else { "skipped" };
+        write!(f, "{}: {}; {}", self.stage, state, self.reason)
+    }
+}
+
+/// Stage decisions for one input
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExplainReport {
+    /// Total pages in the input (after `max_pages`)
+    pub page_count: usize,
+    /// 1-based pages that were sampled
+    pub sampled_pages: Vec<usize>,
+    /// Decisions in pipeline order
+    pub decisions: Vec<StageDecision>,
+}
+
+/// Evenly spaced 0-based sample indices, avoiding the cover when possible
+pub fn sample_indices(page_count: usize, samples: usize) -> Vec<usize> {
+    if samples == 0 || page_count == 0 {
+        return Vec::new();
+    }
+    if page_count <= samples {
+        return (0..page_count).collect();
+    }
+    (1..=samples).map(|i| i * page_count / (samples + 1)).collect()
+}
+
+/// Explain each stage decision for `input` using `samples` pages
+pub fn explain(pipeline: &PdfPipeline, input: &Path, samples: usize) -> Result<ExplainReport, PipelineError> {
+    let config = pipeline.config();
+    if !input.exists() {
+        return Err(PipelineError::InputNotFound(input.to_path_buf()));
+    }
+
+    let archive = if crate::comic_archive::is_comic_archive(input) {
+        Some(crate::ComicArchive::open_with_tools(input, pipeline.tools().clone()).map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?)
+    } else {
+        None
+    };
+    let total_pages = match &archive {
+        Some(archive) => archive.page_count(),
+        None => {
+            crate::LopdfReader::new(input)
+                .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?
+                .info
+                .page_count
+        }
+    };
+    let page_count = config.max_pages.map_or(total_pages, |max| total_pages.min(max));
+
+    let temp_dir = tempfile::tempdir()?;
+    let extract_options = crate::ExtractOptions::builder()
+        .dpi(config.dpi.min(SAMPLE_DPI))
+        .tools(pipeline.tools().clone())
+        .build();
+    let mut sampled_pages = Vec::new();
+    let mut images: Vec<PathBuf> = Vec::new();
+    for index in sample_indices(page_count, samples) {
+        let path = match &archive {
+            Some(archive) => archive.extract_page(index, temp_dir.path()).map_err(|e| e.to_string()),
+            None => crate::LopdfExtractor::extract_page_auto(input, index, temp_dir.path(), &extract_options)
+                .map(|page| page.path)
+                .map_err(|e| e.to_string()),
+        }
+        .map_err(PipelineError::ExtractionFailed)?;
+        sampled_pages.push(index + 1);
+        images.push(path);
+    }
+
+    let ocr_tools = pipeline.probe_ocr_tools();
+    let mut decisions = vec![
+        explain_invert(config.invert, &images, &sampled_pages),
+        explain_line_artifacts(config.remove_line_artifacts, &images, &sampled_pages),
+        explain_margin_trim(config.margin_trim),
+        explain_upscale(config.upscale, config.dpi),
+        explain_deskew(config.deskew, &images, &sampled_pages),
+        explain_shadow(&images, &sampled_pages),
+        explain_offset_alignment(config.offset_alignment, ocr_tools.tesseract),
+        explain_mixed_color(config.mixed_color, &images),
+    ];
+    let vertical = explain_vertical(config.text_direction, &images);
+    decisions.push(explain_binding(config.binding, vertical.as_ref().map(|(is_vertical, _)| *is_vertical)));
+    decisions.insert(
+        decisions.len() - 1,
+        vertical.map_or_else(
+            || StageDecision::new("vertical", true, "no sampled page could be analyzed; horizontal assumed"),
+            |(_, decision)| decision,
+        ),
+    );
+    let source_text = config
+        .skip_existing_ocr
+        .then(|| crate::TextLayer::read_with_tools(input, pipeline.tools()).ok())
+        .flatten();
+    decisions.push(match source_text.filter(crate::TextLayer::is_sufficient) {
+        Some(layer) => explain_text_layer(&layer),
+        None => explain_ocr(config.ocr, ocr_tools.yomitoku, config.ocr_optional),
+    });
+
+    Ok(ExplainReport {
+        page_count,
+        sampled_pages,
+        decisions,
+    })
+}
+
+fn explain_invert(mode: crate::Invert, images: &[PathBuf], pages: &[usize]) -> StageDecision {
+    match mode {
+        crate::Invert::Off => return StageDecision::new("invert", false, "disabled by configuration"),
+        crate::Invert::Force => return StageDecision::new("invert", true, "every page inverted (force)"),
+        crate::Invert::Auto => {}
+    }
+    let negatives: Vec<String> = images
+        .iter()
+        .zip(pages)
+        .filter_map(|(image, page)| {
+            let detection = crate::NegativeScan::detect(&image::open(image).ok()?.to_rgb8());
+            detection
+                .negative
+                .then(|| format!("page {}: mean {:.0}, deviation {:.0}", page, detection.mean, detection.std_dev))
+        })
+        .collect();
+
+    let reason = if negatives.is_empty() {
+        "no negative scans among sampled pages".to_string()
+    } else {
+        format!("negatives detected ({})", negatives.join("; "))
+    };
+    StageDecision::new("invert", true, reason)
+}
+
+fn explain_line_artifacts(enabled: bool, images: &[PathBuf], pages: &[usize]) -> StageDecision {
+    if !enabled {
+        return StageDecision::new("line_artifacts", false, "disabled by configuration");
+    }
+    let options = crate::cleanup::LineArtifactOptions::default();
+    let found: Vec<String> = images
+        .iter()
+        .zip(pages)
+        .filter_map(|(image, page)| {
+            let result = crate::cleanup::LineArtifactRemover::detect(image, &options).ok()?;
+            result
+                .has_artifacts()
+                .then(|| format!("page {}: {} lines", page, result.artifacts.len()))
+        })
+        .collect();
+
+    let reason = if found.is_empty() {
+        "no isolated full-length lines on sampled pages".to_string()
+    } else {
+        format!("lines detected ({})", found.join("; "))
+    };
+    StageDecision::new("line_artifacts", true, reason)
+}
+
+fn explain_margin_trim(margin_trim: f64) -> StageDecision {
+    if margin_trim > 0.0 {
+        StageDecision::new("trim", true, format!("{}% trimmed from each edge", margin_trim))
+    } else {
+        StageDecision::new("trim", false, "margin trim is 0%")
+    }
+}
+
+fn explain_upscale(upscale: bool, dpi: u32) -> StageDecision {
+    if upscale {
+        StageDecision::new("upscale", true, format!("RealESRGAN 2x on pages extracted at {} DPI", dpi))
+    } else {
+        StageDecision::new("upscale", false, "disabled by configuration")
+    }
+}
+
+fn explain_deskew(enabled: bool, images: &[PathBuf], pages: &[usize]) -> StageDecision {
+    if !enabled {
+        return StageDecision::new("deskew", false, "disabled by configuration");
+    }
+    let options = crate::DeskewOptions::builder()
+        .algorithm(crate::DeskewAlgorithm::PageEdge)
+        .build();
+    let largest = images
+        .iter()
+        .zip(pages)
+        .filter_map(|(image, page)| {
+            crate::ImageProcDeskewer::detect_skew(image, &options)
+                .ok()
+                .map(|detection| (detection.angle, *page))
+        })
+        .max_by(|a, b| a.0.abs().total_cmp(&b.0.abs()));
+
+    let reason = match largest {
+        None => "skew detection failed on sampled pages; pages would be left unrotated".to_string(),
+        Some((angle, page)) if angle.abs() >= options.threshold_angle => format!(
+            "sampled skew {:.1}° on page {} > {:.1}° threshold; pages will be rotated",
+            angle.abs(),
+            page,
+            options.threshold_angle
+        ),
+        Some((angle, _)) => format!(
+            "sampled skew at most {:.2}° < {:.1}° threshold; little or no rotation expected",
+            angle.abs(),
+            options.threshold_angle
+        ),
+    };
+    StageDecision::new("deskew", true, reason)
+}
+
+fn explain_shadow(images: &[PathBuf], pages: &[usize]) -> StageDecision {
+    let options = crate::margin::ShadowRemovalOptions::default();
+    let found: Vec<String> = images
+        .iter()
+        .zip(pages)
+        .filter_map(|(image, page)| {
+            let result = crate::margin::ShadowDetector::detect(image, &options).ok()?;
+            let edges: Vec<String> = result
+                .shadows
+                .iter()
+                .map(|shadow| format!("{:?} edge {}px", shadow.edge, shadow.width).to_lowercase())
+                .collect();
+            (!edges.is_empty()).then(|| format!("page {}: {}", page, edges.join(", ")))
+        })
+        .collect();
+
+    let reason = if found.is_empty() {
+        "no edge shadow gradient detected on sampled pages".to_string()
+    } else {
+        format!("gradient detected ({}); shadow removal is not part of convert", found.join("; "))
+    };
+    StageDecision::new("shadow", false, reason)
+}
+
+fn explain_offset_alignment(enabled: bool, tesseract: bool) -> StageDecision {
+    match (enabled, tesseract) {
+        (false, _) => StageDecision::new("page_numbers", false, "offset alignment disabled"),
+        (true, true) => StageDecision::new("page_numbers", true, "tesseract found; group crop and page offsets will run"),
+        (true, false) => StageDecision::new(
+            "page_numbers",
+            false,
+            "tesseract not found; skipped with a warning (group crop still runs)",
+        ),
+    }
+}
+
+fn explain_mixed_color(enabled: bool, images: &[PathBuf]) -> StageDecision {
+    if !enabled {
+        return StageDecision::new("color_detect", false, "mixed color output disabled");
+    }
+    let threshold = crate::color_stats::DEFAULT_COLOR_PAGE_RATIO;
+    let decisions = crate::ColorAnalyzer::detect_book_color_modes(images, threshold);
+    let color = decisions
+        .iter()
+        .filter(|(mode, _)| *mode == crate::PageColorMode::Color)
+        .count();
+    StageDecision::new(
+        "color_detect",
+        true,
+        format!(
+            "sampled pages: {} color, {} grayscale (color if > {:.1}% of pixels are colored)",
+            color,
+            decisions.len() - color,
+            threshold * 100.0
+        ),
+    )
+}
+
+/// Vertical writing verdict and its explanation (None if nothing could be analyzed)
+fn explain_vertical(
+    direction: crate::markdown::TextDirectionOption,
+    images: &[PathBuf],
+) -> Option<(bool, StageDecision)> {
+    let grays: Vec<_> = images
+        .iter()
+        .filter_map(|path| image::open(path).ok().map(|img| img.to_luma8()))
+        .collect();
+    let options = crate::VerticalDetectOptions::default();
+    let detected = if grays.is_empty() {
+        None
+    } else {
+        crate::detect_book_vertical_writing(&grays, &options).ok()
+    };
+
+    if let Some(is_vertical) = direction.forced_vertical() {
+        let mut reason = format!("--text-direction {}", direction.name());
+        if let Some(result) = detected.filter(|result| result.is_vertical != is_vertical) {
+            reason.push_str(&format!(
+                " (detector disagrees: probability {:.2} → {})",
+                result.vertical_probability,
+                if result.is_vertical { "vertical" } else { "horizontal" }
+            ));
+        }
+        return Some((is_vertical, StageDecision::new("vertical", true, reason)));
+    }
+
+    let result = detected?;
+    let (comparison, verdict) = if result.is_vertical {
+        ("≥", "vertical")
+    } else {
+        ("<", "horizontal")
+    };
+    let reason = format!(
+        "probability {:.2} {} {:.2} threshold → {}",
+        result.vertical_probability, comparison, options.vertical_threshold, verdict
+    );
+    Some((result.is_vertical, StageDecision::new("vertical", true, reason)))
+}
+
+fn explain_binding(binding: crate::Binding, is_vertical: Option<bool>) -> StageDecision {
+    let right_to_left = binding.is_right_to_left(is_vertical.unwrap_or(false));
+    let direction = if right_to_left { "right-to-left" } else { "left-to-right" };
+    let reason = match binding {
+        crate::Binding::Auto => format!("auto from text direction → {}", direction),
+        _ => format!("--page-order {} → {}", binding.name(), direction),
+    };
+    StageDecision::new("binding", right_to_left, reason)
+}
+
+fn explain_ocr(enabled: bool, yomitoku: bool, ocr_optional: bool) -> StageDecision {
+    match (enabled, yomitoku) {
+        (false, _) => StageDecision::new("ocr", false, "OCR disabled"),
+        (true, true) => StageDecision::new("ocr", true, "yomitoku found"),
+        (true, false) if ocr_optional => {
+            StageDecision::new("ocr", false, "yomitoku not found; skipped because of --ocr-optional")
+        }
+        (true, false) => StageDecision::new("ocr", true, "yomitoku not found; conversion would fail"),
+    }
+}
+
+fn explain_text_layer(layer: &crate::TextLayer) -> StageDecision {
+    StageDecision::new(
+        "ocr",
+        false,
+        format!(
+            "source has a text layer ({}/{} pages with text); reused instead of OCR",
+            layer.pages_with_text(),
+            layer.pages.len()
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::TextDirectionOption;
+    use crate::PipelineConfig;
+
+    #[test]
+    fn test_sample_indices() {
+        assert_eq!(sample_indices(10, 3), vec![2, 5, 7]);
+        assert_eq!(sample_indices(2, 3), vec![0, 1]);
+        assert!(sample_indices(0, 3).is_empty());
+        assert!(sample_indices(10, 0).is_empty());
+    }
+
+    #[test]
+    fn test_config_only_decisions() {
+        assert!(!explain_margin_trim(0.0).enabled);
+        assert_eq!(explain_margin_trim(0.5).to_string(), "trim: enabled; 0.5% trimmed from each edge");
+        assert!(!explain_deskew(false, &[], &[]).enabled);
+        assert!(explain_ocr(true, false, false).reason.contains("would fail"));
+        assert!(!explain_ocr(true, false, true).enabled);
+
+        let binding = explain_binding(crate::Binding::Auto, Some(true));
+        assert!(binding.enabled);
+        assert!(binding.reason.ends_with("right-to-left"));
+        assert!(!explain_binding(crate::Binding::LeftToRight, Some(true)).enabled);
+    }
+
+    #[test]
+    fn test_sampled_decisions() {
+        let images = vec![PathBuf::from("tests/fixtures/book_page_1.png")];
+        let deskew = explain_deskew(true, &images, &[1]);
+        assert!(deskew.enabled);
+        assert!(deskew.reason.contains("threshold"), "{}", deskew.reason);
+
+        let (detected, vertical) = explain_vertical(TextDirectionOption::Auto, &images).unwrap();
+        assert!(vertical.reason.starts_with("probability "), "{}", vertical.reason);
+        assert!(explain_vertical(TextDirectionOption::Auto, &[]).is_none());
+
+        // A forced direction wins and notes a disagreeing detector
+        let forced = if detected { TextDirectionOption::Horizontal } else { TextDirectionOption::Vertical };
+        let (is_vertical, vertical) = explain_vertical(forced, &images).unwrap();
+        assert_eq!(is_vertical, !detected);
+        assert!(vertical.reason.contains("detector disagrees"), "{}", vertical.reason);
+        assert_eq!(explain_vertical(TextDirectionOption::Vertical, &[]).map(|(v, _)| v), Some(true));
+
+        assert!(!explain_shadow(&images, &[1]).enabled);
+
+        assert!(!explain_invert(crate::Invert::Off, &images, &[1]).enabled);
+        let invert = explain_invert(crate::Invert::Auto, &images, &[1]);
+        assert_eq!(invert.reason, "no negative scans among sampled pages");
+
+        assert!(!explain_line_artifacts(false, &images, &[1]).enabled);
+        let lines = explain_line_artifacts(true, &images, &[1]);
+        assert!(lines.enabled);
+        assert!(lines.reason.contains("sampled pages") || lines.reason.starts_with("lines detected"));
+    }
+
+    #[test]
+    fn test_explain_missing_input() {
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        let result = explain(&pipeline, Path::new("/nonexistent/book.pdf"), DEFAULT_SAMPLE_PAGES);
+        assert!(matches!(result, Err(PipelineError::InputNotFound(_))));
+    }
+}
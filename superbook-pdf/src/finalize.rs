@@ -9,6 +9,7 @@
 //! - Page offset shift application
 //! - Edge feathering for seamless blending
 //! - Final output to target height (3508)
+//! - "Page N unavailable" placeholders for pages that failed
 //!
 //! # Example
 //!
@@ -33,7 +34,7 @@ use image::{GenericImageView, Rgb, RgbImage};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-use crate::normalize::{CornerColors, ImageNormalizer};
+use crate::normalize::{CornerColors, ImageNormalizer, Resampler};
 
 // ============================================================
 // Constants
@@ -48,6 +49,15 @@ const DEFAULT_CORNER_PATCH_PERCENT: u32 = 3;
 /// Default feather pixels for edge blending
 const DEFAULT_FEATHER_PIXELS: u32 = 4;
 
+/// Pixels darker than this are content when fitting an aspect ratio
+const ASPECT_CONTENT_THRESHOLD: u8 = 240;
+
+/// Background of placeholder pages
+const PLACEHOLDER_BACKGROUND: Rgb<u8> = Rgb([235, 235, 235]);
+
+/// Frame and text color of placeholder pages
+const PLACEHOLDER_INK: Rgb<u8> = Rgb([96, 96, 96]);
+
 // ============================================================
 // Error Types
 // ============================================================
@@ -67,6 +77,9 @@ pub enum FinalizeError {
     #[error("Invalid crop region")]
     InvalidCropRegion,
 
+    #[error("Invalid aspect ratio: {0}:{1}")]
+    InvalidAspectRatio(u32, u32),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -133,6 +146,8 @@ pub struct FinalizeOptions {
     pub feather_pixels: u32,
     /// Corner patch percentage for paper color sampling
     pub corner_patch_percent: u32,
+    /// Filter used to scale pages to the target height
+    pub resampler: Resampler,
 }
 
 impl Default for FinalizeOptions {
@@ -143,6 +158,7 @@ impl Default for FinalizeOptions {
             margin_percent: 0,
             feather_pixels: DEFAULT_FEATHER_PIXELS,
             corner_patch_percent: DEFAULT_CORNER_PATCH_PERCENT,
+            resampler: Resampler::Lanczos3,
         }
     }
 }
@@ -196,6 +212,13 @@ impl FinalizeOptionsBuilder {
         self
     }
 
+    /// Set resampling filter
+    #[must_use]
+    pub fn resampler(mut self, resampler: Resampler) -> Self {
+        self.options.resampler = resampler;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> FinalizeOptions {
@@ -220,6 +243,99 @@ pub struct FinalizeResult {
     pub shift_applied: (i32, i32),
 }
 
+/// How a page was changed to reach a target aspect ratio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AspectAdjustment {
+    /// Page already had the target ratio
+    #[default]
+    Unchanged,
+    /// Blank border removed (pixels removed horizontally and vertically)
+    Cropped { width: u32, height: u32 },
+    /// Paper-colored border added (pixels added horizontally and vertically)
+    Padded { width: u32, height: u32 },
+}
+
+impl AspectAdjustment {
+    /// Whether blank border was removed
+    pub fn is_cropped(&self) -> bool {
+        matches!(self, Self::Cropped { .. })
+    }
+
+    /// Whether border was added
+    pub fn is_padded(&self) -> bool {
+        matches!(self, Self::Padded { .. })
+    }
+}
+
+impl std::fmt::Display for AspectAdjustment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unchanged => write!(f, "unchanged"),
+            Self::Cropped { width, height } => write!(f, "cropped {}x{}", width, height),
+            Self::Padded { width, height } => write!(f, "padded {}x{}", width, height),
+        }
+    }
+}
+
+/// Aspect ratio fitting result
+#[derive(Debug, Clone)]
+pub struct AspectFitResult {
+    /// Size before fitting
+    pub original_size: (u32, u32),
+    /// Size after fitting
+    pub final_size: (u32, u32),
+    /// Adjustment applied
+    pub adjustment: AspectAdjustment,
+    /// Top-left of the kept window in the original (crop), or of the
+    /// original on the padded canvas
+    pub offset: (u32, u32),
+}
+
+/// Orientation of a page image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PageOrientation {
+    /// Taller than wide (or square)
+    #[default]
+    Portrait,
+    /// Wider than tall
+    Landscape,
+}
+
+impl PageOrientation {
+    /// Orientation of a `(width, height)` page
+    pub fn of(size: (u32, u32)) -> Self {
+        if size.0 > size.1 {
+            PageOrientation::Landscape
+        } else {
+            PageOrientation::Portrait
+        }
+    }
+
+    /// Orientation of most of `sizes` (portrait on a tie)
+    pub fn majority(sizes: &[(u32, u32)]) -> Self {
+        let landscape = sizes.iter().filter(|&&size| Self::of(size) == PageOrientation::Landscape).count();
+        if landscape * 2 > sizes.len() {
+            PageOrientation::Landscape
+        } else {
+            PageOrientation::Portrait
+        }
+    }
+
+    /// Short name for reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            PageOrientation::Portrait => "portrait",
+            PageOrientation::Landscape => "landscape",
+        }
+    }
+}
+
+impl std::fmt::Display for PageOrientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
 // ============================================================
 // Page Finalizer
 // ============================================================
@@ -268,6 +384,7 @@ impl PageFinalizer {
             shift_y,
             &corners,
             options.feather_pixels,
+            options.resampler,
         );
 
         // Save result
@@ -306,6 +423,132 @@ impl PageFinalizer {
         Ok(results)
     }
 
+    /// Fit a page image file to the aspect ratio `width:height`
+    pub fn fit_aspect(input_path: &Path, output_path: &Path, aspect: (u32, u32)) -> Result<AspectFitResult> {
+        if !input_path.exists() {
+            return Err(FinalizeError::ImageNotFound(input_path.to_path_buf()));
+        }
+
+        let img = image::open(input_path).map_err(|e| FinalizeError::InvalidImage(e.to_string()))?;
+        let rgb_img = img.to_rgb8();
+        let original_size = rgb_img.dimensions();
+
+        let (fitted, adjustment, offset) = Self::fit_aspect_placed(&rgb_img, aspect)?;
+        let final_size = fitted.dimensions();
+        fitted
+            .save(output_path)
+            .map_err(|e| FinalizeError::SaveError(e.to_string()))?;
+
+        Ok(AspectFitResult {
+            original_size,
+            final_size,
+            adjustment,
+            offset,
+        })
+    }
+
+    /// Fit an image to the aspect ratio `width:height`
+    ///
+    /// Blank border along the long axis is cropped when the content still
+    /// fits; otherwise the short axis is padded with the paper color, so
+    /// content is never clipped.
+    pub fn fit_aspect_image(image: &RgbImage, aspect: (u32, u32)) -> Result<(RgbImage, AspectAdjustment)> {
+        Self::fit_aspect_placed(image, aspect).map(|(fitted, adjustment, _)| (fitted, adjustment))
+    }
+
+    /// [`Self::fit_aspect_image`], also returning the offset of the kept
+    /// window in the image (crop) or of the image on the canvas (padding)
+    fn fit_aspect_placed(image: &RgbImage, aspect: (u32, u32)) -> Result<(RgbImage, AspectAdjustment, (u32, u32))> {
+        let (aspect_w, aspect_h) = aspect;
+        if aspect_w == 0 || aspect_h == 0 {
+            return Err(FinalizeError::InvalidAspectRatio(aspect_w, aspect_h));
+        }
+
+        let (w, h) = image.dimensions();
+        // Size along one axis that matches the ratio for the other axis
+        let width_for_height = ((h as u64 * aspect_w as u64 + aspect_h as u64 / 2) / aspect_h as u64) as u32;
+        let height_for_width = ((w as u64 * aspect_h as u64 + aspect_w as u64 / 2) / aspect_w as u64) as u32;
+
+        if w == width_for_height || h == height_for_width || w == 0 || h == 0 {
+            return Ok((image.clone(), AspectAdjustment::Unchanged, (0, 0)));
+        }
+
+        let content = Self::content_bounds(image);
+        if w > width_for_height {
+            // Too wide: crop blank columns, or pad top and bottom
+            let new_w = width_for_height.max(1);
+            match content {
+                Some((left, _, right, _)) if right - left < new_w => {
+                    let x = Self::centered_offset(left, right, new_w, w);
+                    let cropped = image::imageops::crop_imm(image, x, 0, new_w, h).to_image();
+                    Ok((cropped, AspectAdjustment::Cropped { width: w - new_w, height: 0 }, (x, 0)))
+                }
+                None => {
+                    let x = (w - new_w) / 2;
+                    let cropped = image::imageops::crop_imm(image, x, 0, new_w, h).to_image();
+                    Ok((cropped, AspectAdjustment::Cropped { width: w - new_w, height: 0 }, (x, 0)))
+                }
+                Some(_) => {
+                    let padded = Self::pad_to(image, w, height_for_width);
+                    Ok((padded, AspectAdjustment::Padded { width: 0, height: height_for_width - h }, (0, (height_for_width - h) / 2)))
+                }
+            }
+        } else {
+            // Too tall: crop blank rows, or pad left and right
+            let new_h = height_for_width.max(1);
+            match content {
+                Some((_, top, _, bottom)) if bottom - top < new_h => {
+                    let y = Self::centered_offset(top, bottom, new_h, h);
+                    let cropped = image::imageops::crop_imm(image, 0, y, w, new_h).to_image();
+                    Ok((cropped, AspectAdjustment::Cropped { width: 0, height: h - new_h }, (0, y)))
+                }
+                None => {
+                    let y = (h - new_h) / 2;
+                    let cropped = image::imageops::crop_imm(image, 0, y, w, new_h).to_image();
+                    Ok((cropped, AspectAdjustment::Cropped { width: 0, height: h - new_h }, (0, y)))
+                }
+                Some(_) => {
+                    let padded = Self::pad_to(image, width_for_height, h);
+                    Ok((padded, AspectAdjustment::Padded { width: width_for_height - w, height: 0 }, ((width_for_height - w) / 2, 0)))
+                }
+            }
+        }
+    }
+
+    /// Bounding box (left, top, right, bottom; inclusive) of content pixels
+    fn content_bounds(image: &RgbImage) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (x, y, pixel) in image.enumerate_pixels() {
+            let [r, g, b] = pixel.0;
+            let luma = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+            if luma < ASPECT_CONTENT_THRESHOLD as u32 {
+                bounds = Some(match bounds {
+                    Some((l, t, r, b)) => (l.min(x), t.min(y), r.max(x), b.max(y)),
+                    None => (x, y, x, y),
+                });
+            }
+        }
+        bounds
+    }
+
+    /// Start of a `window` centered on `[first, last]` that stays inside `0..total`
+    fn centered_offset(first: u32, last: u32, window: u32, total: u32) -> u32 {
+        let center = (first + last) / 2;
+        let min_start = (last + 1).saturating_sub(window);
+        let max_start = first.min(total - window);
+        center.saturating_sub(window / 2).clamp(min_start, max_start)
+    }
+
+    /// Center the image on a paper-colored canvas of the given size
+    fn pad_to(image: &RgbImage, width: u32, height: u32) -> RgbImage {
+        let corners = ImageNormalizer::sample_corner_colors(image, DEFAULT_CORNER_PATCH_PERCENT);
+        let mut canvas = Self::create_gradient_canvas(width, height, &corners);
+        let x = (width - image.width()) / 2;
+        let y = (height - image.height()) / 2;
+        image::imageops::replace(&mut canvas, image, x as i64, y as i64);
+        canvas
+    }
+
     /// Calculate output dimensions based on crop region and options
     fn calculate_output_dimensions(
         crop_w: u32,
@@ -335,6 +578,7 @@ impl PageFinalizer {
         shift_y: i32,
         corners: &CornerColors,
         feather: u32,
+        resampler: Resampler,
     ) -> RgbImage {
         // Calculate scaled shift
         let scaled_shift_x = (shift_x as f64 * scale).round() as i32;
@@ -352,12 +596,7 @@ impl PageFinalizer {
         let scaled_h = (src.height() as f64 * scale).round() as u32;
 
         // Resize source image
-        let scaled_img = image::imageops::resize(
-            src,
-            scaled_w,
-            scaled_h,
-            image::imageops::FilterType::Lanczos3,
-        );
+        let scaled_img = image::imageops::resize(src, scaled_w, scaled_h, resampler.filter_type());
 
         // Draw scaled image with offset
         for y in 0..scaled_h {
@@ -467,6 +706,85 @@ impl PageFinalizer {
     }
 }
 
+impl PageFinalizer {
+    /// Placeholder image for a page that could not be processed
+    ///
+    /// A gray page with a frame and "PAGE `page_number` UNAVAILABLE" in a
+    /// bitmap font scaled to the page width, so readers see the gap and the
+    /// page count stays intact.
+    pub fn placeholder(width: u32, height: u32, page_number: usize) -> RgbImage {
+        let (width, height) = (width.max(1), height.max(1));
+        let mut image = RgbImage::from_pixel(width, height, PLACEHOLDER_BACKGROUND);
+
+        let frame = (width.min(height) / 100).max(1);
+        let inset = (width.min(height) / 20).max(frame);
+        for y in 0..height {
+            for x in 0..width {
+                let distance = x.min(y).min(width - 1 - x).min(height - 1 - y);
+                if distance >= inset && distance < inset + frame {
+                    image.put_pixel(x, y, PLACEHOLDER_INK);
+                }
+            }
+        }
+
+        let lines = [format!("PAGE {}", page_number), "UNAVAILABLE".to_string()];
+        let longest = lines.iter().map(|line| line.len()).max().unwrap_or(1) as u32;
+        // 6 columns per character (5 + spacing); text spans about 60% of the width
+        let dot = (width * 3 / 5 / (longest * 6)).max(1);
+        let line_height = dot * 10;
+        let mut top = (height / 2).saturating_sub(line_height * lines.len() as u32 / 2);
+        for line in &lines {
+            let left = (width / 2).saturating_sub(line.len() as u32 * 6 * dot / 2);
+            Self::draw_text(&mut image, line, left, top, dot);
+            top += line_height;
+        }
+        image
+    }
+
+    /// Write a [`Self::placeholder`] image to `output_path`
+    pub fn write_placeholder(output_path: &Path, width: u32, height: u32, page_number: usize) -> Result<()> {
+        Self::placeholder(width, height, page_number)
+            .save(output_path)
+            .map_err(|e| FinalizeError::SaveError(e.to_string()))
+    }
+
+    /// Draw `text` with the built-in 5x7 font, one glyph dot as a `dot`-sized square
+    fn draw_text(image: &mut RgbImage, text: &str, left: u32, top: u32, dot: u32) {
+        for (index, c) in text.chars().enumerate() {
+            let rows = crate::compare::glyph(c);
+            let x0 = left + index as u32 * 6 * dot;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..5u32 {
+                    if bits & (0x10 >> col) == 0 {
+                        continue;
+                    }
+                    for dy in 0..dot {
+                        for dx in 0..dot {
+                            let (x, y) = (x0 + col * dot + dx, top + row as u32 * dot + dy);
+                            if x < image.width() && y < image.height() {
+                                image.put_pixel(x, y, PLACEHOLDER_INK);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse an aspect ratio written as `W:H` (e.g. `3:4`)
+pub fn parse_aspect_ratio(value: &str) -> std::result::Result<(u32, u32), String> {
+    let (w, h) = value
+        .split_once(':')
+        .ok_or_else(|| format!("expected W:H, got '{}'", value))?;
+    let w: u32 = w.trim().parse().map_err(|_| format!("invalid width in '{}'", value))?;
+    let h: u32 = h.trim().parse().map_err(|_| format!("invalid height in '{}'", value))?;
+    if w == 0 || h == 0 {
+        return Err(format!("aspect ratio must be positive, got '{}'", value));
+    }
+    Ok((w, h))
+}
+
 /// Add margin to crop region and clip to image bounds
 pub fn add_margin_and_clip(
     region: &CropRegion,
@@ -519,12 +837,15 @@ mod tests {
             .target_height(3508)
             .margin_percent(5)
             .feather_pixels(8)
+            .resampler(Resampler::Gaussian)
             .build();
 
         assert_eq!(opts.target_width, Some(2480));
         assert_eq!(opts.target_height, 3508);
         assert_eq!(opts.margin_percent, 5);
         assert_eq!(opts.feather_pixels, 8);
+        assert_eq!(opts.resampler, Resampler::Gaussian);
+        assert_eq!(FinalizeOptions::default().resampler, Resampler::Lanczos3);
     }
 
     #[test]
@@ -790,4 +1111,128 @@ mod tests {
             assert!(output.exists(), "Output {:?} should exist", output);
         }
     }
+
+    /// White page with a dark block covering `x0..x1` × `y0..y1`
+    fn page_with_block(width: u32, height: u32, block: (u32, u32, u32, u32)) -> RgbImage {
+        let (x0, y0, x1, y1) = block;
+        RgbImage::from_fn(width, height, |x, y| {
+            if (x0..x1).contains(&x) && (y0..y1).contains(&y) {
+                Rgb([20, 20, 20])
+            } else {
+                Rgb([250, 250, 250])
+            }
+        })
+    }
+
+    // TC-FINAL-006: アスペクト比調整 - 余白のみ切り詰め
+    #[test]
+    fn test_tc_final_006_fit_aspect_crops_blank_border() {
+        // 400x300 page with narrow content: 3:4 needs width 225
+        let page = page_with_block(400, 300, (150, 50, 250, 250));
+        let (fitted, adjustment) = PageFinalizer::fit_aspect_image(&page, (3, 4)).unwrap();
+
+        assert_eq!(fitted.dimensions(), (225, 300));
+        assert_eq!(adjustment, AspectAdjustment::Cropped { width: 175, height: 0 });
+        let (_, _, (x, y)) = PageFinalizer::fit_aspect_placed(&page, (3, 4)).unwrap();
+        assert_eq!(y, 0);
+        assert!(fitted.get_pixel(150 - x, 50).0[0] < 128 && fitted.get_pixel(149 - x, 50).0[0] > 240);
+        // All content pixels survive
+        let dark = fitted.pixels().filter(|p| p.0[0] < 128).count();
+        assert_eq!(dark, 100 * 200);
+    }
+
+    // TC-FINAL-007: アスペクト比調整 - 内容が切れる場合はパディング
+    #[test]
+    fn test_tc_final_007_fit_aspect_pads_instead_of_clipping() {
+        // Content spans almost the full width, so cropping would clip it
+        let page = page_with_block(400, 300, (10, 50, 390, 250));
+        let (fitted, adjustment) = PageFinalizer::fit_aspect_image(&page, (3, 4)).unwrap();
+
+        assert_eq!(fitted.dimensions(), (400, 533));
+        assert_eq!(adjustment, AspectAdjustment::Padded { width: 0, height: 233 });
+        assert!(adjustment.is_padded());
+        let (_, _, offset) = PageFinalizer::fit_aspect_placed(&page, (3, 4)).unwrap();
+        assert_eq!(offset, (0, 116));
+        let dark = fitted.pixels().filter(|p| p.0[0] < 128).count();
+        assert_eq!(dark, 380 * 200);
+        // Padding uses the paper color
+        assert!(fitted.get_pixel(200, 0).0[0] > 240);
+    }
+
+    #[test]
+    fn test_fit_aspect_tall_page_and_unchanged() {
+        // Too tall for 3:4 with content filling the height: pad the width
+        let page = page_with_block(200, 400, (20, 5, 180, 395));
+        let (fitted, adjustment) = PageFinalizer::fit_aspect_image(&page, (3, 4)).unwrap();
+        assert_eq!(fitted.dimensions(), (300, 400));
+        assert_eq!(adjustment, AspectAdjustment::Padded { width: 100, height: 0 });
+
+        // Already 3:4
+        let page = page_with_block(300, 400, (20, 20, 280, 380));
+        let (fitted, adjustment) = PageFinalizer::fit_aspect_image(&page, (3, 4)).unwrap();
+        assert_eq!(fitted.dimensions(), (300, 400));
+        assert_eq!(adjustment, AspectAdjustment::Unchanged);
+        assert_eq!(adjustment.to_string(), "unchanged");
+
+        assert!(matches!(
+            PageFinalizer::fit_aspect_image(&page, (0, 4)),
+            Err(FinalizeError::InvalidAspectRatio(0, 4))
+        ));
+    }
+
+    #[test]
+    fn test_parse_aspect_ratio() {
+        assert_eq!(parse_aspect_ratio("3:4"), Ok((3, 4)));
+        assert_eq!(parse_aspect_ratio(" 16 : 9 "), Ok((16, 9)));
+        assert!(parse_aspect_ratio("3x4").is_err());
+        assert!(parse_aspect_ratio("0:4").is_err());
+        assert!(parse_aspect_ratio("a:4").is_err());
+    }
+
+    #[test]
+    fn test_fit_aspect_file() {
+        let temp_dir = tempdir().unwrap();
+        let input = temp_dir.path().join("page.png");
+        let output = temp_dir.path().join("fitted.png");
+        page_with_block(400, 300, (150, 50, 250, 250)).save(&input).unwrap();
+
+        let result = PageFinalizer::fit_aspect(&input, &output, (3, 4)).unwrap();
+        assert_eq!(result.original_size, (400, 300));
+        assert_eq!(result.final_size, (225, 300));
+        assert!(result.adjustment.is_cropped());
+        assert_eq!(image::open(&output).unwrap().dimensions(), (225, 300));
+    }
+
+    #[test]
+    fn test_page_orientation() {
+        assert_eq!(PageOrientation::of((2480, 3508)), PageOrientation::Portrait);
+        assert_eq!(PageOrientation::of((3508, 2480)), PageOrientation::Landscape);
+        assert_eq!(PageOrientation::of((100, 100)), PageOrientation::Portrait);
+
+        let book = [(2480, 3508), (3508, 2480), (2480, 3508)];
+        assert_eq!(PageOrientation::majority(&book), PageOrientation::Portrait);
+        assert_eq!(PageOrientation::majority(&book[..2]), PageOrientation::Portrait);
+        assert_eq!(PageOrientation::majority(&book[1..2]), PageOrientation::Landscape);
+        assert_eq!(PageOrientation::majority(&[]), PageOrientation::Portrait);
+    }
+
+    #[test]
+    fn test_placeholder() {
+        let image = PageFinalizer::placeholder(600, 800, 12);
+        assert_eq!(image.dimensions(), (600, 800));
+        assert_eq!(*image.get_pixel(0, 0), PLACEHOLDER_BACKGROUND);
+        // Frame inset by 5% of the short side
+        assert_eq!(*image.get_pixel(300, 30), PLACEHOLDER_INK);
+
+        // Text is centered: ink in the middle band, none near the frame
+        let ink_rows: Vec<u32> = (40..760)
+            .filter(|&y| (40..560).any(|x| *image.get_pixel(x, y) == PLACEHOLDER_INK))
+            .collect();
+        assert!(!ink_rows.is_empty());
+        assert!(ink_rows.iter().all(|&y| (300..500).contains(&y)), "{:?}", ink_rows.first());
+
+        // Different page numbers render differently; tiny pages do not panic
+        assert_ne!(image, PageFinalizer::placeholder(600, 800, 13));
+        assert_eq!(PageFinalizer::placeholder(0, 3, 1).dimensions(), (1, 3));
+    }
 }
@@ -0,0 +1,274 @@
+//! External processing hooks
+//!
+//! Runs user-supplied executables at two points of a conversion:
+//!
+//! | Hook | When | Arguments | stdin |
+//! |------|------|-----------|-------|
+//! | page | after each page image is finalized | `<page image> <page number>` | - |
+//! | post | after the PDF is written | `<output pdf>` | result manifest (JSON) |
+//!
+//! Hooks are executed directly (no shell) inside a fresh temporary working
+//! directory that is removed afterwards. stdout/stderr are captured so the
+//! caller can forward them to the log, and a non-zero exit status fails the
+//! conversion of that file.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::hooks::HookRunner;
+//! use std::path::Path;
+//!
+//! let hook = HookRunner::new("./upload.sh");
+//! let output = hook.run_post(Path::new("book.pdf"), "{}").unwrap();
+//! for line in output.lines() {
+//!     println!("[post-hook] {line}");
+//! }
+//! ```
+
+use std::ffi::OsStr;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use thiserror::Error;
+
+/// Environment variable naming the hook kind (`page` or `post`)
+pub const HOOK_KIND_ENV: &str = "SUPERBOOK_HOOK";
+
+/// Hook error types
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("Hook not found: {0}")]
+    NotFound(String),
+
+    #[error("Hook {command} exited with {status}{}", stderr_suffix(.stderr))]
+    Failed {
+        command: String,
+        status: String,
+        stderr: String,
+    },
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+fn stderr_suffix(stderr: &str) -> String {
+    let last = stderr.lines().rev().find(|l| !l.trim().is_empty());
+    last.map(|l| format!(": {}", l.trim())).unwrap_or_default()
+}
+
+pub type Result<T> = std::result::Result<T, HookError>;
+
+/// Captured output of a successful hook run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HookOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl HookOutput {
+    /// Non-empty output lines, stdout first
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.stdout
+            .lines()
+            .chain(self.stderr.lines())
+            .filter(|l| !l.trim().is_empty())
+    }
+}
+
+/// Runs one configured hook executable
+#[derive(Debug, Clone)]
+pub struct HookRunner {
+    command: String,
+    sandbox_parent: Option<PathBuf>,
+}
+
+impl HookRunner {
+    /// Create a runner for an executable path or a command on `PATH`
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            sandbox_parent: None,
+        }
+    }
+
+    /// Create sandbox directories under `dir` instead of the system temp dir
+    #[must_use]
+    pub fn with_sandbox_parent(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.sandbox_parent = Some(dir.into());
+        self
+    }
+
+    /// Configured command
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Resolve the executable to an absolute path
+    ///
+    /// Anything containing a path separator is taken relative to the current
+    /// directory (hooks run in a sandbox cwd, so it must be absolute);
+    /// bare names are looked up on `PATH`.
+    pub fn resolve(&self) -> Result<PathBuf> {
+        let path = Path::new(&self.command);
+        if path.components().count() > 1 {
+            let abs = absolute(path)?;
+            if abs.is_file() {
+                Ok(abs)
+            } else {
+                Err(HookError::NotFound(self.command.clone()))
+            }
+        } else {
+            which::which(&self.command).map_err(|_| HookError::NotFound(self.command.clone()))
+        }
+    }
+
+    /// Run the page hook for one final page image
+    pub fn run_page(&self, image: &Path, page_number: usize) -> Result<HookOutput> {
+        let image = absolute(image)?;
+        let page = page_number.to_string();
+        self.run("page", &[image.as_os_str(), OsStr::new(&page)], None)
+    }
+
+    /// Run the post hook for the generated PDF, passing the manifest on stdin
+    pub fn run_post(&self, output: &Path, manifest_json: &str) -> Result<HookOutput> {
+        let output = absolute(output)?;
+        self.run("post", &[output.as_os_str()], Some(manifest_json))
+    }
+
+    /// Run the hook in a fresh sandbox directory and capture its output
+    pub fn run(&self, kind: &str, args: &[&OsStr], stdin: Option<&str>) -> Result<HookOutput> {
+        let program = self.resolve()?;
+
+        let mut builder = tempfile::Builder::new();
+        builder.prefix("superbook-hook-");
+        let sandbox = match &self.sandbox_parent {
+            Some(parent) => {
+                std::fs::create_dir_all(parent)?;
+                builder.tempdir_in(parent)?
+            }
+            None => builder.tempdir()?,
+        };
+
+        let mut child = Command::new(&program)
+            .args(args)
+            .current_dir(sandbox.path())
+            .env(HOOK_KIND_ENV, kind)
+            .stdin(if stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            // A hook that ignores stdin may exit before reading it
+            if let Err(e) = pipe.write_all(input.as_bytes()) {
+                if e.kind() != std::io::ErrorKind::BrokenPipe {
+                    return Err(e.into());
+                }
+            }
+        }
+
+        let output = child.wait_with_output()?;
+        let captured = HookOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        };
+
+        if output.status.success() {
+            Ok(captured)
+        } else {
+            Err(HookError::Failed {
+                command: self.command.clone(),
+                status: output.status.to_string(),
+                stderr: captured.stderr,
+            })
+        }
+    }
+}
+
+fn absolute(path: &Path) -> Result<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(std::env::current_dir()?.join(path))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::tempdir;
+
+    fn script(dir: &Path, name: &str, body: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_page_hook_receives_args() {
+        let dir = tempdir().unwrap();
+        let hook = script(dir.path(), "page.sh", "echo \"$SUPERBOOK_HOOK $2 $(basename \"$1\")\"");
+        let runner = HookRunner::new(hook.to_string_lossy());
+
+        let out = runner.run_page(&dir.path().join("page_0001.png"), 1).unwrap();
+        assert_eq!(out.stdout.trim(), "page 1 page_0001.png");
+    }
+
+    #[test]
+    fn test_post_hook_reads_manifest_from_stdin() {
+        let dir = tempdir().unwrap();
+        let hook = script(dir.path(), "post.sh", "cat; echo \" $1\" >&2");
+        let runner = HookRunner::new(hook.to_string_lossy());
+        let output = dir.path().join("out.pdf");
+
+        let out = runner.run_post(&output, "{\"pages\":3}").unwrap();
+        assert_eq!(out.stdout, "{\"pages\":3}");
+        assert_eq!(out.stderr.trim(), output.to_string_lossy());
+        assert_eq!(out.lines().count(), 2);
+    }
+
+    #[test]
+    fn test_hook_runs_in_sandbox_cwd() {
+        let dir = tempdir().unwrap();
+        let sandboxes = dir.path().join("sandboxes");
+        let hook = script(dir.path(), "cwd.sh", "touch scratch; pwd");
+        let runner = HookRunner::new(hook.to_string_lossy()).with_sandbox_parent(&sandboxes);
+
+        let out = runner.run_page(Path::new("x.png"), 1).unwrap();
+        let cwd = PathBuf::from(out.stdout.trim());
+        assert!(cwd.starts_with(std::fs::canonicalize(&sandboxes).unwrap()));
+        // Sandbox and anything the hook left there are removed
+        assert!(!cwd.exists());
+    }
+
+    #[test]
+    fn test_hook_failure_reports_stderr() {
+        let dir = tempdir().unwrap();
+        let hook = script(dir.path(), "fail.sh", "echo 'upload refused' >&2; exit 3");
+        let runner = HookRunner::new(hook.to_string_lossy());
+
+        let err = runner.run_post(Path::new("out.pdf"), "{}").unwrap_err();
+        assert!(matches!(err, HookError::Failed { .. }));
+        let msg = err.to_string();
+        assert!(msg.contains("upload refused"), "{msg}");
+        assert!(msg.contains('3'), "{msg}");
+    }
+
+    #[test]
+    fn test_hook_not_found() {
+        let runner = HookRunner::new("./definitely-missing-hook.sh");
+        assert!(matches!(runner.resolve(), Err(HookError::NotFound(_))));
+
+        let runner = HookRunner::new("definitely-missing-hook-command");
+        assert!(matches!(
+            runner.run_page(Path::new("a.png"), 1),
+            Err(HookError::NotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,453 @@
+//! Localization of user-facing CLI messages
+//!
+//! A lightweight message table keyed by [`Msg`]. English is the fallback
+//! for any message without a translation.
+//!
+//! # Example
+//!
+//! ```rust
+//! use superbook_pdf::i18n::{Lang, Msg};
+//!
+//! assert_eq!(Msg::Succeeded.text(Lang::En), "Succeeded");
+//! assert_eq!(Msg::Succeeded.text(Lang::Ja), "成功");
+//! ```
+
+/// Output language for CLI messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    /// English
+    #[default]
+    En,
+    /// Japanese
+    Ja,
+}
+
+impl Lang {
+    /// Parse a language code or locale string (`ja`, `ja_JP.UTF-8`, `en_US`, ...)
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_ascii_lowercase();
+        if code.starts_with("ja") {
+            Some(Lang::Ja)
+        } else if code.starts_with("en") || code == "c" || code == "posix" {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+
+    /// Detect language from the `LANG` environment variable (English if unset or unknown)
+    pub fn from_env() -> Self {
+        std::env::var("LANG")
+            .ok()
+            .and_then(|v| Self::from_code(&v))
+            .unwrap_or_default()
+    }
+}
+
+/// Message keys for localized CLI output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // Summary
+    SummaryTitle,
+    TotalFiles,
+    Succeeded,
+    Skipped,
+    Errors,
+    InvalidPdfs,
+    TotalTime,
+    StageTiming,
+    SkippedStages,
+    LowOcrConfidence,
+    LowSsimPages,
+    DetectedFeatures,
+    VerticalHorizontal,
+    MarkedPages,
+    PageNumberConfidence,
+    MeanTimePerBookPage,
+    Books,
+    // Progress
+    File,
+    Stage,
+    CompletedIn,
+    Progress,
+    ColorPages,
+    DeduplicatedPages,
+    BilevelPages,
+    TextLayerReused,
+    // Dry-run plan
+    DryRunTitle,
+    Input,
+    Output,
+    FilesToProcess,
+    PipelineConfiguration,
+    ImageExtraction,
+    DeskewCorrection,
+    MarginTrim,
+    AiUpscaling,
+    Ocr,
+    InternalResolution,
+    ColorCorrection,
+    AutoContrast,
+    ToneCurve,
+    OffsetAlignment,
+    RegisterSides,
+    MixedColor,
+    ColorPolicy,
+    QuickMode,
+    QualityMetrics,
+    PdfGeneration,
+    OutputHeight,
+    PageOrder,
+    TextDirection,
+    ResizeFilter,
+    BilevelEncoding,
+    SkipExistingOcr,
+    LineArtifactRemoval,
+    TargetAspect,
+    Desaturate,
+    ReportWritten,
+    MarkerRemoval,
+    AspectAdjusted,
+    PdfaOutput,
+    PdfaValidate,
+    OutputFormat,
+    PageLabels,
+    OddPagesOnly,
+    EvenPagesOnly,
+    RunningHeadsExcluded,
+    RunningHeadsErased,
+    TimedOutPages,
+    PlaceholderPages,
+    OnPageError,
+    NegativeInversion,
+    TransparencyBackground,
+    InvertedPages,
+    EffectiveDpi,
+    WorkCache,
+    ResumeRun,
+    ResumedPages,
+    MixedSizePages,
+    TextSidecar,
+    OcrPages,
+    OcrSkipPictures,
+    OcrSkippedPages,
+    SourceImageDpi,
+    MinOutputDpi,
+    StageDecisions,
+    SampledPages,
+    ProcessingOptions,
+    Threads,
+    ThreadBudget,
+    ChunkSize,
+    ChunkUnlimited,
+    StreamingExtraction,
+    ExtractBatch,
+    Pages,
+    Gpu,
+    ReprocessPolicy,
+    SkipExisting,
+    ForceReprocess,
+    Cache,
+    Deterministic,
+    Verbose,
+    DebugOptions,
+    MaxPages,
+    Unlimited,
+    SaveDebugImages,
+    Files,
+    // Common values
+    Enabled,
+    Disabled,
+    Yes,
+    No,
+}
+
+impl Msg {
+    /// Get the message text in the given language (falls back to English)
+    pub fn text(self, lang: Lang) -> &'static str {
+        match lang {
+            Lang::En => self.en(),
+            Lang::Ja => self.ja().unwrap_or_else(|| self.en()),
+        }
+    }
+
+    /// Format an on/off value
+    pub fn on_off(enabled: bool, lang: Lang) -> &'static str {
+        if enabled {
+            Msg::Enabled.text(lang)
+        } else {
+            Msg::Disabled.text(lang)
+        }
+    }
+
+    /// Format a yes/no value
+    pub fn yes_no(value: bool, lang: Lang) -> &'static str {
+        if value {
+            Msg::Yes.text(lang)
+        } else {
+            Msg::No.text(lang)
+        }
+    }
+
+    fn en(self) -> &'static str {
+        match self {
+            Msg::SummaryTitle => "Processing Summary",
+            Msg::TotalFiles => "Total files",
+            Msg::Succeeded => "Succeeded",
+            Msg::Skipped => "Skipped",
+            Msg::Errors => "Errors",
+            Msg::InvalidPdfs => "Invalid PDFs",
+            Msg::TotalTime => "Total time",
+            Msg::StageTiming => "Stage timing",
+            Msg::SkippedStages => "Skipped stages",
+            Msg::LowOcrConfidence => "Pages below OCR confidence",
+            Msg::LowSsimPages => "Drastically changed pages (low SSIM)",
+            Msg::DetectedFeatures => "Detected features",
+            Msg::VerticalHorizontal => "Vertical / horizontal",
+            Msg::MarkedPages => "Marker-cleaned pages",
+            Msg::PageNumberConfidence => "Page number match",
+            Msg::MeanTimePerBookPage => "Mean time book / page",
+            Msg::Books => "books",
+            Msg::File => "File",
+            Msg::Stage => "Stage",
+            Msg::CompletedIn => "Completed in",
+            Msg::Progress => "Progress",
+            Msg::ColorPages => "Color pages",
+            Msg::DeduplicatedPages => "Deduplicated pages",
+            Msg::BilevelPages => "Bilevel pages",
+            Msg::TextLayerReused => "Existing text layer reused (OCR skipped)",
+            Msg::DryRunTitle => "=== Dry Run - Execution Plan ===",
+            Msg::Input => "Input",
+            Msg::Output => "Output",
+            Msg::FilesToProcess => "Files to process",
+            Msg::PipelineConfiguration => "Pipeline Configuration",
+            Msg::ImageExtraction => "Image Extraction",
+            Msg::DeskewCorrection => "Deskew Correction",
+            Msg::MarginTrim => "Margin Trim",
+            Msg::AiUpscaling => "AI Upscaling",
+            Msg::Ocr => "OCR",
+            Msg::InternalResolution => "Internal Resolution Normalization",
+            Msg::ColorCorrection => "Global Color Correction",
+            Msg::AutoContrast => "Auto Contrast",
+            Msg::ToneCurve => "Tone Curve",
+            Msg::OffsetAlignment => "Page Number Offset Alignment",
+            Msg::RegisterSides => "Odd/Even Side Registration",
+            Msg::MixedColor => "Mixed Color/Grayscale Output",
+            Msg::ColorPolicy => "Color Policy",
+            Msg::QuickMode => "Quick mode: draft output (lower DPI, no upscaling or OCR)",
+            Msg::QualityMetrics => "Quality Metrics (SSIM/PSNR)",
+            Msg::PdfGeneration => "PDF Generation",
+            Msg::OutputHeight => "output height",
+            Msg::PageOrder => "Page order",
+            Msg::TextDirection => "Text direction (forced)",
+            Msg::ResizeFilter => "resize filter",
+            Msg::BilevelEncoding => "Bilevel page encoding",
+            Msg::SkipExistingOcr => "PDFs with a text layer reuse it instead of OCR",
+            Msg::LineArtifactRemoval => "Scanner Line Removal",
+            Msg::TargetAspect => "Target Aspect Ratio",
+            Msg::Desaturate => "Desaturate Color Noise (below saturation)",
+            Msg::ReportWritten => "Batch report written",
+            Msg::MarkerRemoval => "Highlighter Removal",
+            Msg::AspectAdjusted => "Aspect ratio adjusted (padded/cropped)",
+            Msg::PdfaOutput => "PDF/A conformance",
+            Msg::PdfaValidate => "validate after writing",
+            Msg::OutputFormat => "Output format",
+            Msg::PageLabels => "Page labels",
+            Msg::OddPagesOnly => "Odd pages only",
+            Msg::EvenPagesOnly => "Even pages only",
+            Msg::RunningHeadsExcluded => "Running headers/footers excluded from text",
+            Msg::RunningHeadsErased => "Running headers/footers erased",
+            Msg::TimedOutPages => "Pages skipped (tool timeout)",
+            Msg::PlaceholderPages => "Placeholder pages (tool timeout)",
+            Msg::OnPageError => "Failed pages",
+            Msg::NegativeInversion => "Negative scan inversion",
+            Msg::TransparencyBackground => "Transparency background",
+            Msg::InvertedPages => "Inverted pages (negative scans)",
+            Msg::EffectiveDpi => "Effective output DPI",
+            Msg::WorkCache => "Work cache",
+            Msg::ResumeRun => "Resume: pages finished by an interrupted run are reused",
+            Msg::ResumedPages => "Pages resumed from the interrupted run",
+            Msg::MixedSizePages => "Mixed page sizes",
+            Msg::TextSidecar => "Text sidecar",
+            Msg::OcrPages => "OCR pages",
+            Msg::OcrSkipPictures => "OCR skips picture pages",
+            Msg::OcrSkippedPages => "Pages without OCR",
+            Msg::SourceImageDpi => "source images",
+            Msg::MinOutputDpi => "Minimum output DPI",
+            Msg::StageDecisions => "Stage Decisions",
+            Msg::SampledPages => "sampled pages",
+            Msg::ProcessingOptions => "Processing Options",
+            Msg::Threads => "Threads",
+            Msg::ThreadBudget => "Thread budget (rayon / external tools)",
+            Msg::ChunkSize => "Chunk size",
+            Msg::ChunkUnlimited => "unlimited (all pages at once)",
+            Msg::StreamingExtraction => "Streaming extraction",
+            Msg::ExtractBatch => "Pages per pdftoppm call",
+            Msg::Pages => "pages",
+            Msg::Gpu => "GPU",
+            Msg::ReprocessPolicy => "Reprocess policy",
+            Msg::SkipExisting => "Skip existing",
+            Msg::ForceReprocess => "Force re-process",
+            Msg::Cache => "Cache",
+            Msg::Deterministic => "Deterministic",
+            Msg::Verbose => "Verbose",
+            Msg::DebugOptions => "Debug Options",
+            Msg::MaxPages => "Max pages",
+            Msg::Unlimited => "unlimited",
+            Msg::SaveDebugImages => "Save debug images",
+            Msg::Files => "Files",
+            Msg::Enabled => "ENABLED",
+            Msg::Disabled => "DISABLED",
+            Msg::Yes => "YES",
+            Msg::No => "NO",
+        }
+    }
+
+    fn ja(self) -> Option<&'static str> {
+        Some(match self {
+            Msg::SummaryTitle => "処理結果",
+            Msg::TotalFiles => "総ファイル数",
+            Msg::Succeeded => "成功",
+            Msg::Skipped => "スキップ",
+            Msg::Errors => "エラー",
+            Msg::InvalidPdfs => "無効なPDF",
+            Msg::TotalTime => "合計時間",
+            Msg::StageTiming => "ステージ別処理時間",
+            Msg::SkippedStages => "スキップしたステージ",
+            Msg::LowOcrConfidence => "OCR信頼度の低いページ",
+            Msg::LowSsimPages => "処理で大きく変化したページ (SSIM低)",
+            Msg::DetectedFeatures => "検出結果",
+            Msg::VerticalHorizontal => "縦書き / 横書き",
+            Msg::MarkedPages => "マーカー除去したページ",
+            Msg::PageNumberConfidence => "ページ番号一致度",
+            Msg::MeanTimePerBookPage => "平均時間 冊 / ページ",
+            Msg::Books => "冊",
+            Msg::File => "ファイル",
+            Msg::Stage => "ステージ",
+            Msg::CompletedIn => "完了 所要時間",
+            Msg::Progress => "進捗",
+            Msg::ColorPages => "カラーページ",
+            Msg::DeduplicatedPages => "重複ページ (画像共有)",
+            Msg::BilevelPages => "白黒2値ページ",
+            Msg::TextLayerReused => "既存のテキストレイヤーを再利用 (OCR省略)",
+            Msg::DryRunTitle => "=== ドライラン - 実行計画 ===",
+            Msg::Input => "入力",
+            Msg::Output => "出力",
+            Msg::FilesToProcess => "処理対象ファイル数",
+            Msg::PipelineConfiguration => "パイプライン設定",
+            Msg::ImageExtraction => "画像抽出",
+            Msg::DeskewCorrection => "傾き補正",
+            Msg::MarginTrim => "マージントリム",
+            Msg::AiUpscaling => "AI超解像",
+            Msg::InternalResolution => "内部解像度正規化",
+            Msg::ColorCorrection => "グローバル色補正",
+            Msg::AutoContrast => "自動コントラスト",
+            Msg::ToneCurve => "トーンカーブ",
+            Msg::OffsetAlignment => "ページ番号オフセット補正",
+            Msg::RegisterSides => "奇数/偶数ページ位置合わせ",
+            Msg::MixedColor => "カラー/グレースケール混在出力",
+            Msg::ColorPolicy => "カラーポリシー",
+            Msg::QuickMode => "クイックモード: 下書き出力 (低DPI、超解像・OCRなし)",
+            Msg::QualityMetrics => "画質指標 (SSIM/PSNR)",
+            Msg::PdfGeneration => "PDF生成",
+            Msg::OutputHeight => "出力高さ",
+            Msg::PageOrder => "綴じ方向",
+            Msg::TextDirection => "組方向 (固定)",
+            Msg::ResizeFilter => "リサイズフィルタ",
+            Msg::BilevelEncoding => "白黒2値ページの圧縮",
+            Msg::SkipExistingOcr => "テキストレイヤーを持つPDFはOCRせず再利用",
+            Msg::LineArtifactRemoval => "スキャナーの線ノイズ除去",
+            Msg::TargetAspect => "アスペクト比調整",
+            Msg::Desaturate => "低彩度の色ノイズ除去 (彩度しきい値)",
+            Msg::ReportWritten => "バッチレポートを出力しました",
+            Msg::MarkerRemoval => "蛍光ペン除去",
+            Msg::AspectAdjusted => "アスペクト比を調整したページ (パディング/切り詰め)",
+            Msg::PdfaOutput => "PDF/A 準拠",
+            Msg::PdfaValidate => "書き出し後に検証",
+            Msg::OutputFormat => "出力形式",
+            Msg::PageLabels => "ページラベル",
+            Msg::OddPagesOnly => "奇数ページのみ",
+            Msg::EvenPagesOnly => "偶数ページのみ",
+            Msg::RunningHeadsExcluded => "柱 (ヘッダー/フッター) をテキストから除外",
+            Msg::RunningHeadsErased => "柱 (ヘッダー/フッター) を消去",
+            Msg::TimedOutPages => "スキップしたページ (ツールのタイムアウト)",
+            Msg::PlaceholderPages => "代替ページを挿入したページ (ツールのタイムアウト)",
+            Msg::OnPageError => "失敗したページ",
+            Msg::NegativeInversion => "ネガ反転",
+            Msg::TransparencyBackground => "透過部分の背景色",
+            Msg::InvertedPages => "反転したページ (ネガ)",
+            Msg::EffectiveDpi => "実効出力 DPI",
+            Msg::WorkCache => "作業キャッシュ",
+            Msg::ResumeRun => "再開: 中断した実行で処理済みのページを再利用",
+            Msg::ResumedPages => "中断した実行から再開したページ",
+            Msg::MixedSizePages => "サイズ混在ページ",
+            Msg::TextSidecar => "テキストファイル",
+            Msg::OcrPages => "OCR対象ページ",
+            Msg::OcrSkipPictures => "図版ページはOCRしない",
+            Msg::OcrSkippedPages => "OCRしなかったページ",
+            Msg::SourceImageDpi => "元画像",
+            Msg::MinOutputDpi => "最低出力 DPI",
+            Msg::StageDecisions => "ステージ判定",
+            Msg::SampledPages => "サンプルページ",
+            Msg::ProcessingOptions => "処理オプション",
+            Msg::Threads => "スレッド数",
+            Msg::ThreadBudget => "スレッド配分 (rayon / 外部ツール)",
+            Msg::ChunkSize => "チャンクサイズ",
+            Msg::ChunkUnlimited => "無制限 (全ページ一括)",
+            Msg::StreamingExtraction => "ストリーミング抽出",
+            Msg::ExtractBatch => "pdftoppm 1回あたりのページ数",
+            Msg::Pages => "ページ",
+            Msg::ReprocessPolicy => "再処理ポリシー",
+            Msg::SkipExisting => "既存ファイルをスキップ",
+            Msg::ForceReprocess => "強制再処理",
+            Msg::Cache => "キャッシュ",
+            Msg::Deterministic => "決定論的出力",
+            Msg::Verbose => "詳細度",
+            Msg::DebugOptions => "デバッグオプション",
+            Msg::MaxPages => "最大ページ数",
+            Msg::Unlimited => "無制限",
+            Msg::SaveDebugImages => "デバッグ画像保存",
+            Msg::Files => "ファイル一覧",
+            Msg::Enabled => "有効",
+            Msg::Disabled => "無効",
+            Msg::Yes => "はい",
+            Msg::No => "いいえ",
+            // Same in both languages
+            Msg::Ocr | Msg::Gpu => return None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lang_from_code() {
+        assert_eq!(Lang::from_code("ja"), Some(Lang::Ja));
+        assert_eq!(Lang::from_code("ja_JP.UTF-8"), Some(Lang::Ja));
+        assert_eq!(Lang::from_code("en_US.UTF-8"), Some(Lang::En));
+        assert_eq!(Lang::from_code("C"), Some(Lang::En));
+        assert_eq!(Lang::from_code("fr_FR"), None);
+    }
+
+    #[test]
+    fn test_msg_text() {
+        assert_eq!(Msg::SummaryTitle.text(Lang::En), "Processing Summary");
+        assert_eq!(Msg::SummaryTitle.text(Lang::Ja), "処理結果");
+    }
+
+    #[test]
+    fn test_msg_english_fallback() {
+        assert_eq!(Msg::Ocr.text(Lang::Ja), "OCR");
+        assert_eq!(Msg::Gpu.text(Lang::Ja), Msg::Gpu.text(Lang::En));
+    }
+
+    #[test]
+    fn test_msg_on_off_yes_no() {
+        assert_eq!(Msg::on_off(true, Lang::En), "ENABLED");
+        assert_eq!(Msg::on_off(false, Lang::Ja), "無効");
+        assert_eq!(Msg::yes_no(true, Lang::Ja), "はい");
+        assert_eq!(Msg::yes_no(false, Lang::En), "NO");
+    }
+}
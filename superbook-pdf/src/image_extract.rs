@@ -5,9 +5,10 @@
 //! # Features
 //!
 //! - Extract pages as PNG, JPEG, or TIFF
-//! - Configurable DPI (72-1200)
+//! - Configurable DPI (72-1200), lowered per page where needed
 //! - Color space conversion (RGB, Grayscale, CMYK)
 //! - Parallel extraction with progress callbacks
+//! - Batched pdftoppm rendering (one process per page range, not per page)
 //! - Transparent background handling
 //!
 //! # Example
@@ -33,8 +34,14 @@
 //! // );
 //! ```
 
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::JoinHandle;
 use thiserror::Error;
 
 // ============================================================
@@ -56,6 +63,9 @@ const MIN_DPI: u32 = 72;
 /// Maximum allowed DPI
 const MAX_DPI: u32 = 1200;
 
+/// Default number of pages rendered per pdftoppm invocation
+pub const DEFAULT_BATCH_PAGES: usize = 32;
+
 /// Default white background color
 const WHITE_BACKGROUND: [u8; 3] = [255, 255, 255];
 
@@ -74,16 +84,41 @@ pub enum ExtractError {
     #[error("External tool error: {0}")]
     ExternalToolError(String),
 
+    #[error("Page {page} timed out: {reason}")]
+    TimedOut { page: usize, reason: String },
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, ExtractError>;
 
+impl ExtractError {
+    /// Error for page `page` from a failed tool call (timeouts become [`ExtractError::TimedOut`])
+    fn from_tool(page: usize, error: std::io::Error) -> Self {
+        if error.kind() == std::io::ErrorKind::TimedOut {
+            ExtractError::TimedOut {
+                page,
+                reason: error.to_string(),
+            }
+        } else {
+            ExtractError::IoError(error)
+        }
+    }
+
+    /// Whether the error is a page whose tool call timed out
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, ExtractError::TimedOut { .. })
+    }
+}
+
 /// Image extraction options
 pub struct ExtractOptions {
     /// Output DPI
     pub dpi: u32,
+    /// Per-page DPI overrides (0-based page index), e.g. pages whose size
+    /// at `dpi` would exceed a pixel cap
+    pub page_dpi: HashMap<usize, u32>,
     /// Output format
     pub format: ImageFormat,
     /// Color space
@@ -92,6 +127,10 @@ pub struct ExtractOptions {
     pub background: Option<[u8; 3]>,
     /// Number of parallel workers
     pub parallel: usize,
+    /// Pages rendered per pdftoppm process (1 = one process per page)
+    pub batch_pages: usize,
+    /// Context the renderer processes run in (niceness, limits)
+    pub tools: crate::tools::ToolContext,
     /// Progress callback
     #[allow(clippy::type_complexity)]
     pub progress_callback: Option<Box<dyn Fn(usize, usize) + Send + Sync>>,
@@ -101,10 +140,13 @@ impl std::fmt::Debug for ExtractOptions {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ExtractOptions")
             .field("dpi", &self.dpi)
+            .field("page_dpi", &self.page_dpi)
             .field("format", &self.format)
             .field("colorspace", &self.colorspace)
             .field("background", &self.background)
             .field("parallel", &self.parallel)
+            .field("batch_pages", &self.batch_pages)
+            .field("tools", &self.tools)
             .field(
                 "progress_callback",
                 &self.progress_callback.as_ref().map(|_| "<callback>"),
@@ -117,10 +159,13 @@ impl Default for ExtractOptions {
     fn default() -> Self {
         Self {
             dpi: DEFAULT_DPI,
+            page_dpi: HashMap::new(),
             format: ImageFormat::Png,
             colorspace: ColorSpace::Rgb,
             background: Some(WHITE_BACKGROUND),
             parallel: num_cpus::get(),
+            batch_pages: DEFAULT_BATCH_PAGES,
+            tools: crate::tools::ToolContext::default(),
             progress_callback: None,
         }
     }
@@ -157,6 +202,11 @@ impl ExtractOptions {
             ..Default::default()
         }
     }
+
+    /// DPI to render 0-based `page` at
+    pub fn dpi_for(&self, page: usize) -> u32 {
+        self.page_dpi.get(&page).copied().unwrap_or(self.dpi)
+    }
 }
 
 /// Builder for ExtractOptions
@@ -173,6 +223,14 @@ impl ExtractOptionsBuilder {
         self
     }
 
+    /// Override the DPI of 0-based `page` (not clamped, so a size cap can
+    /// go below MIN_DPI)
+    #[must_use]
+    pub fn page_dpi(mut self, page: usize, dpi: u32) -> Self {
+        self.options.page_dpi.insert(page, dpi.max(1));
+        self
+    }
+
     /// Set output format
     #[must_use]
     pub fn format(mut self, format: ImageFormat) -> Self {
@@ -208,6 +266,20 @@ impl ExtractOptionsBuilder {
         self
     }
 
+    /// Set pages rendered per pdftoppm process (minimum 1)
+    #[must_use]
+    pub fn batch_pages(mut self, pages: usize) -> Self {
+        self.options.batch_pages = pages.max(1);
+        self
+    }
+
+    /// Set the context renderer processes run in
+    #[must_use]
+    pub fn tools(mut self, tools: crate::tools::ToolContext) -> Self {
+        self.options.tools = tools;
+        self
+    }
+
     /// Set progress callback
     #[must_use]
     pub fn progress_callback(mut self, callback: Box<dyn Fn(usize, usize) + Send + Sync>) -> Self {
@@ -306,7 +378,7 @@ impl MagickExtractor {
 
         // 1. Input settings (before input file)
         args.push("-density".to_string());
-        args.push(options.dpi.to_string());
+        args.push(options.dpi_for(page_index).to_string());
 
         // 2. Input file with page index
         args.push(format!("{}[{}]", pdf_path.display(), page_index));
@@ -378,10 +450,10 @@ impl MagickExtractor {
         // (especially macOS ImageMagick which requires -alpha after input file)
         let args = Self::build_magick_args(pdf_path, page_index, output_path, options);
 
-        let mut cmd = Command::new("magick");
+        let mut cmd = options.tools.command("magick");
         cmd.args(&args);
 
-        let output = cmd.output()?;
+        let output = options.tools.output(&mut cmd).map_err(|e| ExtractError::from_tool(page_index, e))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -404,6 +476,9 @@ impl MagickExtractor {
     }
 
     /// Extract all pages from PDF
+    ///
+    /// Pages whose `magick` call timed out (see [`crate::tools::ToolContext::with_timeout`])
+    /// are left out of the result.
     pub fn extract_all(
         pdf_path: &Path,
         output_dir: &Path,
@@ -426,7 +501,7 @@ impl MagickExtractor {
         let _ = std::fs::remove_file(test_file);
 
         // Get page count using pdfinfo or similar
-        let page_count = Self::get_page_count(pdf_path)?;
+        let page_count = Self::get_page_count(pdf_path, &options.tools)?;
 
         // Extract pages (optionally in parallel)
         let extension = options.format.extension();
@@ -436,8 +511,11 @@ impl MagickExtractor {
         for i in 0..page_count {
             let output_path = output_dir.join(format!("page_{:05}.{}", i, extension));
 
-            let result = Self::extract_page(pdf_path, i, &output_path, options)?;
-            results.push(result);
+            match Self::extract_page(pdf_path, i, &output_path, options) {
+                Ok(result) => results.push(result),
+                Err(e) if e.is_timeout() => {}
+                Err(e) => return Err(e),
+            }
 
             // Call progress callback if provided
             if let Some(ref callback) = options.progress_callback {
@@ -449,9 +527,9 @@ impl MagickExtractor {
     }
 
     /// Get the number of pages in a PDF
-    fn get_page_count(pdf_path: &Path) -> Result<usize> {
+    fn get_page_count(pdf_path: &Path, tools: &crate::tools::ToolContext) -> Result<usize> {
         // Try using pdfinfo first
-        if let Ok(output) = Command::new("pdfinfo").arg(pdf_path).output() {
+        if let Ok(output) = tools.output(tools.command("pdfinfo").arg(pdf_path)) {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 for line in stdout.lines() {
@@ -467,12 +545,14 @@ impl MagickExtractor {
         }
 
         // Fallback: use ImageMagick identify
-        let output = Command::new("magick")
-            .args(["identify", "-format", "%n\n"])
-            .arg(pdf_path)
-            .output()?;
+        let output = tools.output(
+            tools
+                .command("magick")
+                .args(["identify", "-format", "%n\n"])
+                .arg(pdf_path),
+        );
 
-        if output.status.success() {
+        if let Some(output) = output.ok().filter(|o| o.status.success()) {
             let stdout = String::from_utf8_lossy(&output.stdout);
             if let Some(line) = stdout.lines().next() {
                 if let Ok(count) = line.trim().parse() {
@@ -692,12 +772,20 @@ impl LopdfExtractor {
 
     /// Check if ImageMagick is available
     pub fn magick_available() -> bool {
-        which::which("magick").is_ok() || which::which("convert").is_ok()
+        crate::tools::is_available("magick") || which::which("convert").is_ok()
     }
 
     /// Check if pdftoppm (poppler-utils) is available
     pub fn pdftoppm_available() -> bool {
-        which::which("pdftoppm").is_ok()
+        crate::tools::is_available("pdftoppm")
+    }
+
+    /// Check if single pages can be rendered (ImageMagick or pdftoppm)
+    ///
+    /// The pure Rust fallback works per document, so streaming extraction
+    /// needs one of the external renderers.
+    pub fn supports_page_extraction() -> bool {
+        Self::magick_available() || Self::pdftoppm_available()
     }
 
     /// Extract using best available method
@@ -706,6 +794,11 @@ impl LopdfExtractor {
         output_dir: &Path,
         options: &ExtractOptions,
     ) -> Result<Vec<ExtractedPage>> {
+        // Batched pdftoppm avoids one process start per page on large books
+        if options.batch_pages > 1 && Self::pdftoppm_available() {
+            return PopplerExtractor::extract_all(pdf_path, output_dir, options);
+        }
+
         // Try ImageMagick first if available (better quality for complex PDFs)
         if Self::magick_available() {
             return MagickExtractor::extract_all(pdf_path, output_dir, options);
@@ -719,42 +812,170 @@ impl LopdfExtractor {
         // Fall back to pure Rust extraction
         Self::extract_all(pdf_path, output_dir, options)
     }
-}
-
-/// Poppler-based extractor using pdftoppm
-pub struct PopplerExtractor;
 
-impl PopplerExtractor {
-    /// Extract a single page from PDF using pdftoppm
-    pub fn extract_page(
+    /// Extract a single page (0-based) using best available method
+    pub fn extract_page_auto(
         pdf_path: &Path,
         page_index: usize,
-        output_path: &Path,
+        output_dir: &Path,
         options: &ExtractOptions,
     ) -> Result<ExtractedPage> {
         if !pdf_path.exists() {
             return Err(ExtractError::PdfNotFound(pdf_path.to_path_buf()));
         }
+        std::fs::create_dir_all(output_dir)?;
 
-        // Create output directory if needed
-        if let Some(parent) = output_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
+        let output_path = output_dir.join(format!("page_{:05}.{}", page_index, options.format.extension()));
+        if Self::magick_available() {
+            return MagickExtractor::extract_page(pdf_path, page_index, &output_path, options);
+        }
+        if Self::pdftoppm_available() {
+            return PopplerExtractor::extract_page(pdf_path, page_index, &output_path, options);
+        }
+
+        // Pure Rust extraction works per document; keep only the requested page
+        Self::extract_all(pdf_path, output_dir, options)?
+            .into_iter()
+            .find(|p| p.page_index == page_index)
+            .ok_or_else(|| ExtractError::ExtractionFailed {
+                page: page_index,
+                reason: "Page not found in PDF".to_string(),
+            })
+    }
+}
+
+/// Streaming page extractor
+///
+/// Renders pages one at a time on a background thread and hands them to the
+/// consumer over a bounded channel, so processing can start on the first page
+/// while later pages are still being extracted. At most `depth` finished
+/// pages wait in the channel; the producer blocks until the consumer catches up.
+pub struct PageStream {
+    receiver: Option<Receiver<Result<ExtractedPage>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl PageStream {
+    /// Start extracting pages `0..page_count` of `pdf_path` into `output_dir`
+    ///
+    /// With pdftoppm available and `options.batch_pages > 1`, pages are
+    /// rendered in ranges of up to `depth` pages per process. A page whose
+    /// tool call timed out is reported as [`ExtractError::TimedOut`] and
+    /// extraction goes on; the stream ends after the last page or after the
+    /// first other error.
+    pub fn spawn(
+        pdf_path: &Path,
+        page_count: usize,
+        output_dir: &Path,
+        options: ExtractOptions,
+        depth: usize,
+    ) -> Self {
+        Self::spawn_pages(pdf_path, (0..page_count).collect(), output_dir, options, depth)
+    }
+
+    /// Start extracting `pages` (0-based, ascending) of `pdf_path` into
+    /// `output_dir`, e.g. the pages a resumed run still needs
+    ///
+    /// Consecutive pages are rendered together as in [`Self::spawn`].
+    pub fn spawn_pages(
+        pdf_path: &Path,
+        pages: Vec<usize>,
+        output_dir: &Path,
+        options: ExtractOptions,
+        depth: usize,
+    ) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(depth.max(1));
+        let pdf_path = pdf_path.to_path_buf();
+        let output_dir = output_dir.to_path_buf();
+
+        let handle = std::thread::spawn(move || {
+            let batch = if options.batch_pages > 1 && LopdfExtractor::pdftoppm_available() {
+                options.batch_pages.min(depth.max(1))
+            } else {
+                1
+            };
+            let page_count = pages.len();
+            let mut sent = 0;
+            'pages: while sent < page_count {
+                // Run of consecutive pages, at most `batch` long
+                let start = pages[sent];
+                let run = pages[sent..]
+                    .iter()
+                    .enumerate()
+                    .take(batch)
+                    .take_while(|&(offset, &page)| page == start + offset)
+                    .count();
+                let end = start + run;
+                let results = if batch > 1 {
+                    match PopplerExtractor::extract_range(&pdf_path, start..end, &output_dir, &options) {
+                        Ok(pages) => {
+                            // Pages left out of the range timed out
+                            let mut pages = pages.into_iter().peekable();
+                            (start..end)
+                                .map(|index| {
+                                    pages.next_if(|page| page.page_index == index).ok_or_else(|| {
+                                        ExtractError::TimedOut {
+                                            page: index,
+                                            reason: "pdftoppm timed out".to_string(),
+                                        }
+                                    })
+                                })
+                                .collect::<Vec<_>>()
+                        }
+                        Err(e) => vec![Err(e)],
+                    }
+                } else {
+                    vec![LopdfExtractor::extract_page_auto(&pdf_path, start, &output_dir, &options)]
+                };
+                for result in results {
+                    let failed = result.as_ref().is_err_and(|e| !e.is_timeout());
+                    sent += 1;
+                    if let Some(callback) = &options.progress_callback {
+                        callback(sent, page_count);
+                    }
+                    // Receiver dropped: the consumer gave up, stop extracting
+                    if sender.send(result).is_err() || failed {
+                        break 'pages;
+                    }
+                }
             }
+        });
+
+        Self {
+            receiver: Some(receiver),
+            handle: Some(handle),
         }
+    }
+}
 
-        // pdftoppm uses 1-based page numbers
-        let page_num = page_index + 1;
+impl Iterator for PageStream {
+    type Item = Result<ExtractedPage>;
 
-        // Get output path without extension (pdftoppm adds its own)
-        let output_stem = output_path.with_extension("");
-        let output_stem_str = output_stem.to_string_lossy();
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.as_ref()?.recv().ok()
+    }
+}
 
-        let mut cmd = Command::new("pdftoppm");
-        cmd.arg("-r").arg(options.dpi.to_string()); // Resolution
-        cmd.arg("-f").arg(page_num.to_string()); // First page
-        cmd.arg("-l").arg(page_num.to_string()); // Last page
-        cmd.arg("-singlefile"); // Single file output (no suffix)
+impl Drop for PageStream {
+    fn drop(&mut self) {
+        // Disconnect first so a blocked producer wakes up and exits
+        self.receiver.take();
+        if let Some(handle) = self.handle.take() {
+            handle.join().ok();
+        }
+    }
+}
+
+/// Poppler-based extractor using pdftoppm
+pub struct PopplerExtractor;
+
+impl PopplerExtractor {
+    /// pdftoppm invocation for 1-based pages `first..=last` at `dpi` (input and output prefix not yet added)
+    fn command(first: usize, last: usize, dpi: u32, options: &ExtractOptions) -> Command {
+        let mut cmd = options.tools.command("pdftoppm");
+        cmd.arg("-r").arg(dpi.to_string()); // Resolution
+        cmd.arg("-f").arg(first.to_string()); // First page
+        cmd.arg("-l").arg(last.to_string()); // Last page
 
         // Set output format (pdftoppm doesn't support BMP, fallback to PNG)
         match options.format {
@@ -780,12 +1001,99 @@ impl PopplerExtractor {
                 // RGB is default for pdftoppm
             }
         }
+        cmd
+    }
+
+    /// Extension pdftoppm gives its output
+    /// (BMP outputs as PNG since pdftoppm doesn't support BMP)
+    fn rendered_extension(format: ImageFormat) -> &'static str {
+        match format {
+            ImageFormat::Png | ImageFormat::Bmp => "png",
+            ImageFormat::Jpeg { .. } => "jpg",
+            ImageFormat::Tiff => "tif",
+        }
+    }
+
+    /// 1-based page number of a range output file (`<prefix>-<number>.<ext>`)
+    pub fn rendered_page_number(path: &Path) -> Option<usize> {
+        let stem = path.file_stem()?.to_str()?;
+        stem.rsplit_once('-')?.1.parse().ok()
+    }
+
+    /// Split `0..page_count` into ranges of at most `batch_pages` pages
+    ///
+    /// Ranges are shrunk so that every one of `workers` processes gets work.
+    pub fn batch_ranges(page_count: usize, batch_pages: usize, workers: usize) -> Vec<Range<usize>> {
+        let size = batch_pages
+            .min(page_count.div_ceil(workers.max(1)))
+            .max(1);
+        (0..page_count)
+            .step_by(size)
+            .map(|start| start..(start + size).min(page_count))
+            .collect()
+    }
+
+    /// Split `pages` into the runs one range call can render: pages with a
+    /// DPI override are left out (they are rendered one at a time)
+    pub fn uniform_runs(pages: Range<usize>, options: &ExtractOptions) -> Vec<Range<usize>> {
+        let mut runs: Vec<Range<usize>> = Vec::new();
+        for page in pages.filter(|page| !options.page_dpi.contains_key(page)) {
+            match runs.last_mut() {
+                Some(run) if run.end == page => run.end += 1,
+                _ => runs.push(page..page + 1),
+            }
+        }
+        runs
+    }
+
+    /// Describe a rendered page file
+    fn extracted_page(page_index: usize, path: &Path, options: &ExtractOptions) -> Result<ExtractedPage> {
+        let (width, height) = image::image_dimensions(path).map_err(|e| ExtractError::ExtractionFailed {
+            page: page_index,
+            reason: e.to_string(),
+        })?;
+        Ok(ExtractedPage {
+            page_index,
+            path: path.to_path_buf(),
+            width,
+            height,
+            format: options.format,
+        })
+    }
+
+    /// Extract a single page from PDF using pdftoppm
+    pub fn extract_page(
+        pdf_path: &Path,
+        page_index: usize,
+        output_path: &Path,
+        options: &ExtractOptions,
+    ) -> Result<ExtractedPage> {
+        if !pdf_path.exists() {
+            return Err(ExtractError::PdfNotFound(pdf_path.to_path_buf()));
+        }
+
+        // Create output directory if needed
+        if let Some(parent) = output_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        // pdftoppm uses 1-based page numbers
+        let page_num = page_index + 1;
+
+        // Get output path without extension (pdftoppm adds its own)
+        let output_stem = output_path.with_extension("");
+        let output_stem_str = output_stem.to_string_lossy();
+
+        let mut cmd = Self::command(page_num, page_num, options.dpi_for(page_index), options);
+        cmd.arg("-singlefile"); // Single file output (no suffix)
 
         // Input PDF and output prefix
         cmd.arg(pdf_path);
         cmd.arg(&*output_stem_str);
 
-        let output = cmd.output()?;
+        let output = options.tools.output(&mut cmd).map_err(|e| ExtractError::from_tool(page_index, e))?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -796,34 +1104,94 @@ impl PopplerExtractor {
         }
 
         // pdftoppm creates file with extension based on format
-        // (BMP outputs as PNG since pdftoppm doesn't support BMP)
-        let actual_output = match options.format {
-            ImageFormat::Png | ImageFormat::Bmp => output_stem.with_extension("png"),
-            ImageFormat::Jpeg { .. } => output_stem.with_extension("jpg"),
-            ImageFormat::Tiff => output_stem.with_extension("tif"),
-        };
+        let actual_output = output_stem.with_extension(Self::rendered_extension(options.format));
 
         // Rename to expected output path if different
         if actual_output != output_path {
             std::fs::rename(&actual_output, output_path)?;
         }
 
-        // Get image dimensions
-        let img = image::open(output_path).map_err(|e| ExtractError::ExtractionFailed {
-            page: page_index,
-            reason: e.to_string(),
-        })?;
+        Self::extracted_page(page_index, output_path, options)
+    }
 
-        Ok(ExtractedPage {
-            page_index,
-            path: output_path.to_path_buf(),
-            width: img.width(),
-            height: img.height(),
-            format: options.format,
-        })
+    /// Extract 0-based `pages` with a single pdftoppm process
+    ///
+    /// Output is named `page_NNNNN.<ext>` in `output_dir` as with
+    /// [`Self::extract_page`]. Pages the range call did not produce (a
+    /// damaged page makes pdftoppm stop early, a hung one is killed at the
+    /// tool timeout) are retried one at a time; pages that time out again
+    /// are left out of the result. Pages with a DPI override are always
+    /// rendered one at a time.
+    pub fn extract_range(
+        pdf_path: &Path,
+        pages: Range<usize>,
+        output_dir: &Path,
+        options: &ExtractOptions,
+    ) -> Result<Vec<ExtractedPage>> {
+        if !pdf_path.exists() {
+            return Err(ExtractError::PdfNotFound(pdf_path.to_path_buf()));
+        }
+        if pages.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Render into a private directory: pdftoppm pads the page number
+        // to the document's page count, so names are parsed, not predicted
+        let scratch = output_dir.join(format!(".range_{:05}", pages.start));
+        std::fs::create_dir_all(&scratch)?;
+        let mut rendered: HashMap<usize, PathBuf> = HashMap::new();
+        for run in Self::uniform_runs(pages.clone(), options) {
+            let mut cmd = Self::command(run.start + 1, run.end, options.dpi, options);
+            cmd.arg(pdf_path);
+            cmd.arg(scratch.join("page"));
+            // A non-zero exit is handled by the per-page retry below
+            let timed_out = match options.tools.output(&mut cmd) {
+                Ok(_) => false,
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => true,
+                Err(e) => return Err(e.into()),
+            };
+
+            let mut run_pages: HashMap<usize, PathBuf> = std::fs::read_dir(&scratch)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter_map(|path| Self::rendered_page_number(&path).map(|page| (page, path)))
+                .filter(|(page, _)| run.contains(&(page - 1)))
+                .collect();
+            if timed_out {
+                // The page being written when pdftoppm was killed may be incomplete
+                if let Some(last) = run_pages.keys().max().copied() {
+                    run_pages.remove(&last);
+                }
+            }
+            rendered.extend(run_pages);
+        }
+
+        let extension = options.format.extension();
+        let mut results = Vec::with_capacity(pages.len());
+        for page_index in pages {
+            let output_path = output_dir.join(format!("page_{:05}.{}", page_index, extension));
+            let batched = rendered
+                .remove(&(page_index + 1))
+                .and_then(|path| std::fs::rename(path, &output_path).ok())
+                .and_then(|_| Self::extracted_page(page_index, &output_path, options).ok());
+            let page = match batched {
+                Some(page) => page,
+                None => match Self::extract_page(pdf_path, page_index, &output_path, options) {
+                    Ok(page) => page,
+                    Err(e) if e.is_timeout() => continue,
+                    Err(e) => return Err(e),
+                },
+            };
+            results.push(page);
+        }
+        std::fs::remove_dir_all(&scratch).ok();
+
+        Ok(results)
     }
 
     /// Extract all pages from PDF using pdftoppm
+    ///
+    /// Pages are rendered in ranges of `options.batch_pages` with up to
+    /// `options.parallel` pdftoppm processes running at once.
     pub fn extract_all(
         pdf_path: &Path,
         output_dir: &Path,
@@ -839,32 +1207,47 @@ impl PopplerExtractor {
         }
 
         // Get page count using pdfinfo
-        let page_count = Self::get_page_count(pdf_path)?;
-
-        // Extract pages
-        let extension = options.format.extension();
-        let mut results = Vec::with_capacity(page_count);
-
-        for i in 0..page_count {
-            let output_path = output_dir.join(format!("page_{:05}.{}", i, extension));
+        let page_count = Self::get_page_count(pdf_path, &options.tools)?;
+        let ranges = Self::batch_ranges(page_count, options.batch_pages, options.parallel);
+        if ranges.is_empty() {
+            return Ok(Vec::new());
+        }
 
-            let result = Self::extract_page(pdf_path, i, &output_path, options)?;
-            results.push(result);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.parallel.clamp(1, ranges.len()))
+            .build()
+            .map_err(|e| ExtractError::ExternalToolError(e.to_string()))?;
+        let finished = AtomicUsize::new(0);
+        let batches: Vec<Result<Vec<ExtractedPage>>> = pool.install(|| {
+            ranges
+                .into_par_iter()
+                .map(|range| {
+                    let pages = Self::extract_range(pdf_path, range, output_dir, options)?;
+                    let done = finished.fetch_add(pages.len(), Ordering::Relaxed) + pages.len();
+                    // Call progress callback if provided
+                    if let Some(ref callback) = options.progress_callback {
+                        callback(done, page_count);
+                    }
+                    Ok(pages)
+                })
+                .collect()
+        });
 
-            // Call progress callback if provided
-            if let Some(ref callback) = options.progress_callback {
-                callback(i + 1, page_count);
-            }
+        let mut results = Vec::with_capacity(page_count);
+        for batch in batches {
+            results.extend(batch?);
         }
-
         Ok(results)
     }
 
     /// Get page count using pdfinfo
-    fn get_page_count(pdf_path: &Path) -> Result<usize> {
-        let output = Command::new("pdfinfo").arg(pdf_path).output()?;
+    fn get_page_count(pdf_path: &Path, tools: &crate::tools::ToolContext) -> Result<usize> {
+        let output = match tools.output(tools.command("pdfinfo").arg(pdf_path)) {
+            Err(e) if e.kind() != std::io::ErrorKind::TimedOut => return Err(e.into()),
+            output => output.ok(),
+        };
 
-        if output.status.success() {
+        if let Some(output) = output.filter(|o| o.status.success()) {
             let stdout = String::from_utf8_lossy(&output.stdout);
             for line in stdout.lines() {
                 if line.starts_with("Pages:") {
@@ -962,6 +1345,73 @@ mod tests {
         assert_eq!(options.parallel, 1);
     }
 
+    #[test]
+    fn test_builder_batch_pages() {
+        assert_eq!(ExtractOptions::default().batch_pages, DEFAULT_BATCH_PAGES);
+        assert_eq!(ExtractOptions::builder().batch_pages(0).build().batch_pages, 1);
+        assert_eq!(ExtractOptions::builder().batch_pages(8).build().batch_pages, 8);
+    }
+
+    #[test]
+    fn test_batch_ranges() {
+        // 500 pages, 4 workers: 16 pdftoppm processes instead of 500
+        let ranges = PopplerExtractor::batch_ranges(500, 32, 4);
+        assert_eq!(ranges.len(), 16);
+        assert_eq!(ranges[0], 0..32);
+        assert_eq!(ranges[15], 480..500);
+
+        // Small books are split so every worker gets a range
+        assert_eq!(PopplerExtractor::batch_ranges(10, 32, 4), vec![0..3, 3..6, 6..9, 9..10]);
+        assert_eq!(PopplerExtractor::batch_ranges(3, 1, 8), vec![0..1, 1..2, 2..3]);
+        assert!(PopplerExtractor::batch_ranges(0, 32, 4).is_empty());
+    }
+
+    #[test]
+    fn test_page_dpi_override() {
+        let options = ExtractOptions::builder().dpi(300).page_dpi(2, 40).page_dpi(5, 0).build();
+        assert_eq!(options.dpi_for(0), 300);
+        assert_eq!(options.dpi_for(2), 40);
+        assert_eq!(options.dpi_for(5), 1);
+
+        // Overridden pages are rendered on their own, at their DPI
+        assert_eq!(PopplerExtractor::uniform_runs(0..8, &options), vec![0..2, 3..5, 6..8]);
+        assert!(PopplerExtractor::uniform_runs(2..3, &options).is_empty());
+        assert_eq!(
+            PopplerExtractor::uniform_runs(0..4, &ExtractOptions::default()),
+            vec![0..4]
+        );
+        let args = MagickExtractor::build_magick_args(Path::new("in.pdf"), 2, Path::new("out.png"), &options);
+        assert_eq!(args[..2], ["-density".to_string(), "40".to_string()]);
+    }
+
+    #[test]
+    fn test_rendered_page_number() {
+        assert_eq!(PopplerExtractor::rendered_page_number(Path::new("/tmp/x/page-007.png")), Some(7));
+        assert_eq!(PopplerExtractor::rendered_page_number(Path::new("page-123.tif")), Some(123));
+        assert_eq!(PopplerExtractor::rendered_page_number(Path::new("page.png")), None);
+        assert_eq!(PopplerExtractor::rendered_page_number(Path::new("page-abc.png")), None);
+    }
+
+    #[test]
+    #[ignore = "requires external tool"]
+    fn test_extract_range_matches_single_pages() {
+        let pdf = Path::new("tests/fixtures/10pages.pdf");
+        let options = ExtractOptions::builder().dpi(72).build();
+        let batched_dir = tempdir().unwrap();
+        let single_dir = tempdir().unwrap();
+
+        let pages = PopplerExtractor::extract_range(pdf, 2..7, batched_dir.path(), &options).unwrap();
+        assert_eq!(pages.iter().map(|p| p.page_index).collect::<Vec<_>>(), vec![2, 3, 4, 5, 6]);
+        for page in &pages {
+            let single_path = single_dir.path().join(page.path.file_name().unwrap());
+            let single = PopplerExtractor::extract_page(pdf, page.page_index, &single_path, &options).unwrap();
+            assert_eq!(page.path.file_name(), single.path.file_name());
+            assert_eq!((page.width, page.height), (single.width, single.height));
+        }
+        // Scratch directory is cleaned up
+        assert_eq!(std::fs::read_dir(batched_dir.path()).unwrap().count(), 5);
+    }
+
     #[test]
     fn test_builder_no_background() {
         let options = ExtractOptions::builder().no_background().build();
@@ -2301,4 +2751,58 @@ mod tests {
             args
         );
     }
+
+    #[test]
+    fn test_page_stream_stops_after_error() {
+        let temp_dir = tempdir().unwrap();
+        let stream = PageStream::spawn(
+            Path::new("/nonexistent/input.pdf"),
+            5,
+            temp_dir.path(),
+            ExtractOptions::default(),
+            2,
+        );
+
+        let results: Vec<_> = stream.collect();
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0], Err(ExtractError::PdfNotFound(_))));
+    }
+
+    #[test]
+    fn test_page_stream_empty_document() {
+        let temp_dir = tempdir().unwrap();
+        let mut stream = PageStream::spawn(
+            Path::new("/nonexistent/input.pdf"),
+            0,
+            temp_dir.path(),
+            ExtractOptions::default(),
+            2,
+        );
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_page_stream_selected_pages() {
+        let temp_dir = tempdir().unwrap();
+        let pdf = temp_dir.path().join("book.pdf");
+        crate::selftest::write_synthetic_pdf(&pdf, 4).unwrap();
+
+        let stream = PageStream::spawn_pages(&pdf, vec![1, 3], &temp_dir.path().join("out"), ExtractOptions::default(), 2);
+        let pages: Vec<usize> = stream.map(|page| page.unwrap().page_index).collect();
+        assert_eq!(pages, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_page_stream_drop_while_pending() {
+        // Dropping an unread stream must not hang on the blocked producer
+        let temp_dir = tempdir().unwrap();
+        let stream = PageStream::spawn(
+            Path::new("/nonexistent/input.pdf"),
+            3,
+            temp_dir.path(),
+            ExtractOptions::default(),
+            1,
+        );
+        drop(stream);
+    }
 }
@@ -118,25 +118,44 @@
 //! AGPL-3.0
 
 pub mod ai_bridge;
+pub mod binarize;
 pub mod cache;
 pub mod cli;
 pub mod config;
 pub mod color_stats;
+pub mod comic_archive;
+pub mod compare;
+pub mod contrast;
 pub mod deskew;
+pub mod explain;
 pub mod finalize;
+pub mod hooks;
+pub mod i18n;
 pub mod image_extract;
 pub mod margin;
 pub mod normalize;
+pub mod output_sink;
+pub mod page_manifest;
 pub mod page_number;
 pub mod parallel;
+pub mod pdf_diff;
 pub mod pdf_reader;
 pub mod pipeline;
 pub mod progress;
+pub mod quality;
 pub mod pdf_writer;
+pub mod pdfa;
 pub mod realesrgan;
 pub mod reprocess;
+pub mod resume;
+pub mod selftest;
+pub mod stages;
+pub mod text_layer;
+pub mod tools;
 pub mod util;
 pub mod vertical_detect;
+pub mod watchdog;
+pub mod work_cache;
 #[cfg(feature = "web")]
 pub mod web;
 pub mod yomitoku;
@@ -151,15 +170,17 @@ pub mod markdown;
 pub use ai_bridge::{
     AiBridgeConfig, AiBridgeConfigBuilder, AiBridgeError, AiTool, SubprocessBridge,
 };
+pub use binarize::AdaptiveMethod;
 pub use cli::{
-    create_page_progress_bar, create_progress_bar, create_spinner, CacheInfoArgs, Cli, Commands,
-    ConvertArgs, DeblurAlgorithmCli, ExitCode, MarkdownArgs, ReprocessArgs, ShadowRemovalMode,
-    TextDirectionCli, ValidationProviderCli,
+    create_page_progress_bar, create_progress_bar, create_spinner, CacheInfoArgs, Cli, CliError, DiffArgs,
+    AssembleArgs, Commands, CompareOptionsArgs, ConfigFormatCli, ConvertArgs, AutoContrastCli, PageOrderCli, ResizeFilterCli, ImageEncodingCli, PdfaCli, BookFormatCli, PageParityCli, InvertCli, MarkerAreaCli, PageErrorPolicyCli, LowDpiPolicyCli, ColorPolicyCli, MixedSizeCli, RunningHeadModeCli, ToneInterpolationCli, DeblurAlgorithmCli, ExitCode, LangCli, MarkdownArgs, ReprocessArgs, RerunArgs,
+    ReprocessPolicy, SelftestArgs, ShadowRemovalMode, TextDirectionCli, ValidationProviderCli,
 };
 #[cfg(feature = "web")]
 pub use cli::ServeArgs;
+pub use comic_archive::{ArchiveFormat, BookFormat, ComicArchive, ComicArchiveError};
 pub use config::{
-    AdvancedConfig, CliOverrides, CleanupConfig, Config, ConfigError, GeneralConfig,
+    AdvancedConfig, CliOverrides, CleanupConfig, Config, ConfigError, GeneralConfig, HooksConfig,
     MarkdownConfig, MarkdownValidationConfig, OcrConfig, OutputConfig, ProcessingConfig,
 };
 pub use deskew::{
@@ -168,29 +189,41 @@ pub use deskew::{
 };
 pub use image_extract::{
     ColorSpace, ExtractError, ExtractOptions, ExtractOptionsBuilder, ExtractedPage, ImageFormat,
-    LopdfExtractor, MagickExtractor,
+    LopdfExtractor, MagickExtractor, PageStream, PopplerExtractor,
 };
 pub use margin::{
-    ContentDetectionMode, ContentRect, GroupCropAnalyzer, GroupCropRegion, ImageMarginDetector,
-    MarginDetection, MarginError, MarginOptions, MarginOptionsBuilder, Margins, PageBoundingBox,
-    TrimResult, UnifiedCropRegions, UnifiedMargins,
+    ContentDetectionMode, ContentRect, CropAggregation, GroupCropAnalyzer, GroupCropParams, GroupCropRegion, ImageMarginDetector,
+    MarginDetection, MarginError, MarginOptions, MarginOptionsBuilder, Margins, PageBoundingBox, PageImageSource, PageParity,
+    Polarity, TrimResult, UnifiedCropRegions, UnifiedMargins,
+};
+pub use page_manifest::{
+    HocrPage, HocrWord, ManifestPage, PageGeometry, PageManifest, PageManifestError, PageTransform,
 };
 pub use page_number::{
     calc_group_reference_position, calc_overlap_center, find_page_number_with_fallback,
     find_page_numbers_batch, BookOffsetAnalysis, DetectedPageNumber, FallbackMatchStats,
-    MatchStage, OffsetCorrection, PageNumberAnalysis, PageNumberCandidate, PageNumberError,
-    PageNumberMatch, PageNumberOptions, PageNumberOptionsBuilder, PageNumberPosition,
+    MatchStage, OffsetCorrection, PageLabelRange, PageLabelStyle, PageLabeler,
+    PageNumberAnalysis, PageNumberCandidate, PageNumberError, PageNumberMatch, PageNumberOptions, PageNumberOptionsBuilder, PageNumberPosition,
     PageNumberRect, PageOffsetAnalyzer, PageOffsetResult, Point, Rectangle, TesseractPageDetector,
 };
-pub use pdf_reader::{LopdfReader, PdfDocument, PdfMetadata, PdfPage, PdfReaderError};
-pub use pdf_writer::{PdfWriterError, PdfWriterOptions, PdfWriterOptionsBuilder, PrintPdfWriter};
+pub use pdf_reader::{
+    validate_pdf_bytes, validate_pdf_file, InvalidPdf, LopdfReader, OutlineItem, PdfDocument, PdfMetadata, PdfPage,
+    PdfReaderError,
+};
+pub use pdf_writer::{
+    BilevelStats, Binding, ImageEncoding, PdfWriteStats, PdfWriterError, PdfWriterOptions,
+    PdfWriterOptionsBuilder, PrintPdfWriter,
+};
+pub use pdfa::{PdfaLevel, PdfaValidation};
 pub use realesrgan::{RealEsrgan, RealEsrganError, RealEsrganOptions, RealEsrganOptionsBuilder};
 pub use reprocess::{
     PageStatus, ReprocessError, ReprocessOptions, ReprocessResult, ReprocessState,
 };
+pub use text_layer::{PageText, TextLayer, TextLayerError, TextWord};
 pub use util::{
-    clamp, ensure_dir_writable, ensure_file_exists, format_duration, format_file_size, load_image,
-    mm_to_pixels, mm_to_points, percentage, pixels_to_mm, points_to_mm,
+    clamp, ensure_dir_writable, ensure_file_exists, flatten_alpha, format_duration, format_file_size, image_has_alpha,
+    load_image, load_image_flattened, mm_to_pixels, parse_page_ranges, parse_rgb_color, mm_to_points, percentage,
+    pixels_to_mm, points_to_mm, PageRanges,
 };
 pub use yomitoku::{
     BatchOcrResult, OcrResult, TextBlock, TextDirection, YomiToku, YomiTokuError, YomiTokuOptions,
@@ -198,10 +231,23 @@ pub use yomitoku::{
 };
 
 // Phase 1-6: Advanced processing modules
-pub use color_stats::{ColorAnalyzer, ColorStats, ColorStatsError, GlobalColorParam};
+pub use compare::{apply_overrides, parse_overrides, CompareError, CompareProfile, CompareResult, OptionComparer};
+pub use pdf_diff::{DiffError, DiffReport, PageDiff, PdfDiff};
+pub use quality::{PageQuality, QualityError, QualitySummary};
+pub use selftest::{SelfTest, SelftestError, SelftestReport};
+pub use color_stats::{ColorAnalyzer, ColorStats, ColorStatsError, GlobalColorParam, PageColorMode};
+pub use contrast::{
+    AutoContrast, AutoContrastOptions, ContrastEnhancer, ContrastError, ContrastResult, Invert,
+    NegativeDetection, NegativeScan, ToneCurve, ToneInterpolation,
+};
 pub use finalize::{
-    FinalizeError, FinalizeOptions, FinalizeOptionsBuilder, FinalizeResult, PageFinalizer,
+    AspectAdjustment, AspectFitResult, FinalizeError, FinalizeOptions, FinalizeOptionsBuilder,
+    FinalizeResult, PageFinalizer, PageOrientation,
 };
+pub use hooks::{HookError, HookOutput, HookRunner};
+pub use output_sink::{LocalSink, OutputSink, OutputSinkError};
+#[cfg(feature = "s3")]
+pub use output_sink::S3CompatibleSink;
 pub use normalize::{
     ImageNormalizer, NormalizeError, NormalizeOptions, NormalizeOptionsBuilder, NormalizeResult,
     PaddingMode, PaperColor, Resampler,
@@ -210,33 +256,40 @@ pub use vertical_detect::{
     detect_book_vertical_writing, detect_vertical_probability, BookVerticalResult,
     VerticalDetectError, VerticalDetectOptions, VerticalDetectResult,
 };
+pub use watchdog::{StallWatchdog, DEFAULT_STALL_WARNING_SECS};
+pub use work_cache::{WorkCache, WorkCacheEntry, WorkCacheError, WorkCacheStats};
+pub use resume::{PageProgress, PageStage, ProgressFile, ResumeError};
 pub use parallel::{
     parallel_map, parallel_process, ParallelError, ParallelOptions, ParallelProcessor,
-    ParallelResult,
+    ParallelResult, ThreadBudget,
+};
+pub use progress::{
+    build_progress_bar, BatchFeatures, BatchReport, BatchTotals, EventLog, EventRecord, FileFeatures, FileReport, FileStatus, OutputMode,
+    ProcessingStage, ProgressTracker, RunEvent, StageTimings,
 };
-pub use progress::{build_progress_bar, OutputMode, ProcessingStage, ProgressTracker};
 pub use cache::{
-    should_skip_processing, CacheDigest, ProcessingCache, ProcessingResult, CACHE_EXTENSION,
-    CACHE_VERSION,
+    check_cache_status, hash_bytes, hash_options, should_skip_processing, CacheDigest,
+    CacheStatus, ProcessingCache, ProcessingResult, CACHE_EXTENSION, CACHE_VERSION,
 };
 pub use pipeline::{
-    calculate_optimal_chunk_size, process_in_chunks, PdfPipeline, PipelineConfig, PipelineError,
-    PipelineResult, ProcessingContext, ProgressCallback, SilentProgress,
+    calculate_optimal_chunk_size, is_generated_output, paths_overlap, process_in_chunks, ClampedPage, ColorPolicy, LowDpiPolicy, MixedSize, MixedSizeDecision, OcrTools, PageErrorPolicy, PdfPipeline, PipelineConfig, PipelineConfigBuilder,
+    PipelineContext, PipelineError, PipelineResult, ProcessedImages, ProcessingContext,
+    ProgressCallback, SilentProgress, SkippedStage, SourceResolution,
 };
 
 // Web server (optional feature)
 #[cfg(feature = "web")]
 pub use web::{
-    generate_preview_base64, preview_stage, ApiKey, AuthConfig, AuthError, AuthManager,
-    AuthResult, AuthStatusResponse, BatchJob, BatchProgress, BatchQueue, BatchStatistics,
-    BatchStatus, ConvertOptions as WebConvertOptions, CorsConfig, HistoryQuery, HistoryResponse,
-    Job, JobQueue, JobStatistics, JobStatus, JobStore, JsonJobStore, MetricsCollector,
-    PersistenceConfig, Priority, Progress as WebProgress, RateLimitConfig, RateLimitError,
-    RateLimitResult, RateLimiter, RateLimitStatus, RecoveryManager, RecoveryResult, RetryResponse,
-    Scope, ServerConfig, ServerInfo, ShutdownConfig, ShutdownCoordinator, ShutdownResult,
-    ShutdownSignal, StatsResponse, StorageBackend, StoreError, SystemMetrics, WebServer,
-    WsBroadcaster, WsMessage, extract_api_key, graceful_shutdown, wait_for_shutdown_signal,
-    PREVIEW_WIDTH,
+    generate_preview_base64, generate_preview_jpeg, preview_stage, ApiKey, AuthConfig, AuthError, AuthManager, AuthResult,
+    AuthStatusResponse, BatchJob, BatchProgress, BatchQueue, BatchStatistics, BatchStatus,
+    CleanupStats, ConvertOptions as WebConvertOptions, CorsConfig, HistoryQuery, HistoryResponse,
+    InMemoryJobStore, Job, JobQueue, JobStatistics, JobStatus, JobStore, JsonJobStore,
+    MetricsCollector, PersistenceConfig, Priority, Progress as WebProgress, RateLimitConfig,
+    RateLimitError, RateLimitResult, RateLimiter, RateLimitStatus, RecoveryManager, RecoveryResult,
+    RetentionTask, RetryResponse, Scope, ServerConfig, ServerInfo, ShutdownConfig,
+    ShutdownCoordinator, ShutdownResult, ShutdownSignal, StatsResponse, StorageBackend, StoreError,
+    SystemMetrics, WebServer, WsBroadcaster, WsMessage, extract_api_key, graceful_shutdown,
+    wait_for_shutdown_signal, PAGE_PREVIEW_WIDTH, PREVIEW_WIDTH,
 };
 
 /// Exit codes for CLI (deprecated: prefer using `ExitCode` enum)
@@ -4,22 +4,31 @@
 
 use clap::Parser;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use superbook_pdf::{
     exit_codes,
+    CliError, ExitCode,
     // Cache module
-    CacheDigest, ProcessingCache, should_skip_processing,
+    CacheDigest, CacheStatus, ProcessingCache, check_cache_status,
     // CLI
-    CacheInfoArgs, Cli, Commands, ConvertArgs, MarkdownArgs, ReprocessArgs,
+    AssembleArgs, CacheInfoArgs, Cli, Commands, CompareOptionsArgs, ConfigFormatCli, ConvertArgs, DiffArgs, MarkdownArgs, ReprocessArgs, ReprocessPolicy, RerunArgs, SelftestArgs,
     // Config
     CliOverrides, Config,
     // Pipeline
-    PdfPipeline, ProgressCallback,
+    PdfPipeline, PipelineContext, ProgressCallback,
+    // Option comparison
+    apply_overrides, parse_overrides, CompareProfile, OptionComparer,
     // Progress tracking
-    ProgressTracker,
+    BatchReport, EventLog, FileFeatures, FileReport, ProgressTracker, RunEvent,
     // Reprocess
     PageStatus, ReprocessOptions, ReprocessState,
+    // Self test
+    SelfTest, SelftestError,
+    // PDF comparison
+    PdfDiff,
+    // Localization
+    i18n::{Lang, Msg},
 };
 
 #[cfg(feature = "web")]
@@ -35,7 +44,13 @@ fn main() {
         Commands::Reprocess(args) => run_reprocess(&args),
         Commands::Markdown(args) => run_markdown(&args),
         Commands::Info => run_info(),
+        Commands::Stages => run_stages(),
         Commands::CacheInfo(args) => run_cache_info(&args),
+        Commands::Rerun(args) => run_rerun(&args),
+        Commands::CompareOptions(args) => run_compare_options(&args),
+        Commands::Assemble(args) => run_assemble(&args),
+        Commands::Selftest(args) => run_selftest(&args),
+        Commands::Diff(args) => run_diff(&args),
         #[cfg(feature = "web")]
         Commands::Serve(args) => run_serve(&args),
     };
@@ -44,7 +59,7 @@ fn main() {
         Ok(()) => exit_codes::SUCCESS,
         Err(e) => {
             eprintln!("Error: {}", e);
-            exit_codes::GENERAL_ERROR
+            e.exit_code().code()
         }
     });
 }
@@ -54,11 +69,59 @@ fn main() {
 /// Verbose progress callback for CLI output
 struct VerboseProgress {
     verbose_level: u32,
+    lang: Lang,
+    /// File currently being processed (multi-file runs label progress with it)
+    file: std::sync::Mutex<Option<PipelineContext>>,
+    /// NDJSON event log (`--event-log`), written regardless of verbosity
+    event_log: Option<EventLog>,
+    /// Set once a write to the event log has failed (warned only once)
+    event_log_failed: std::sync::atomic::AtomicBool,
 }
 
 impl VerboseProgress {
     fn new(verbose_level: u32) -> Self {
-        Self { verbose_level }
+        Self {
+            verbose_level,
+            lang: Lang::default(),
+            file: std::sync::Mutex::new(None),
+            event_log: None,
+            event_log_failed: std::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Input of the file currently being processed
+    fn current_input(&self) -> Option<PathBuf> {
+        self.file.lock().ok().and_then(|file| file.as_ref().map(|context| context.input.clone()))
+    }
+
+    /// Append an event to the event log, if any
+    fn log_event(&self, event: RunEvent) {
+        let Some(log) = &self.event_log else { return };
+        if let Err(e) = log.log(event) {
+            if !self.event_log_failed.swap(true, std::sync::atomic::Ordering::Relaxed) {
+                eprintln!("Warning: Failed to write event log: {}", e);
+            }
+        }
+    }
+
+    /// `[i/n] ` prefix for progress lines when several files are processed
+    fn file_label(&self) -> String {
+        match self.file.lock().ok().and_then(|file| file.clone()) {
+            Some(context) if context.file_count > 1 => format!("{} ", context.position()),
+            _ => String::new(),
+        }
+    }
+
+    /// Set the message language
+    fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+
+    /// Write events to `event_log`
+    fn with_event_log(mut self, event_log: Option<EventLog>) -> Self {
+        self.event_log = event_log;
+        self
     }
 
     /// Check if step messages should be shown (level >= 1)
@@ -81,15 +144,30 @@ impl VerboseProgress {
 }
 
 impl ProgressCallback for VerboseProgress {
+    fn on_file_start(&self, context: &PipelineContext) {
+        if let Ok(mut file) = self.file.lock() {
+            *file = Some(context.clone());
+        }
+        self.log_event(RunEvent::FileStart {
+            input: context.input.clone(),
+            index: context.file_index + 1,
+            total: context.file_count,
+        });
+    }
+
     fn on_step_start(&self, step: &str) {
         if self.verbose_level > 0 {
             println!("  {}", step);
         }
+        self.log_event(RunEvent::StepStart {
+            input: self.current_input(),
+            step: step.to_string(),
+        });
     }
 
     fn on_step_progress(&self, current: usize, total: usize) {
         if self.verbose_level > 0 {
-            print!("\r    Progress: {}/{}", current, total);
+            print!("\r    {}{}: {}/{}", self.file_label(), Msg::Progress.text(self.lang), current, total);
             std::io::stdout().flush().ok();
         }
     }
@@ -98,6 +176,11 @@ impl ProgressCallback for VerboseProgress {
         if self.verbose_level > 0 {
             println!("    {}: {}", step, message);
         }
+        self.log_event(RunEvent::StepComplete {
+            input: self.current_input(),
+            step: step.to_string(),
+            message: message.to_string(),
+        });
     }
 
     fn on_debug(&self, message: &str) {
@@ -105,24 +188,68 @@ impl ProgressCallback for VerboseProgress {
             println!("    [DEBUG] {}", message);
         }
     }
+
+    fn on_warning(&self, message: &str) {
+        eprintln!("    {}Warning: {}", self.file_label(), message);
+        self.log_event(RunEvent::Warning {
+            input: self.current_input(),
+            message: message.to_string(),
+        });
+    }
 }
 
 // ============ Convert Command ============
 
-fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
+fn run_convert(args: &ConvertArgs) -> Result<(), CliError> {
     let start_time = Instant::now();
 
     // Validate input path
     if !args.input.exists() {
-        eprintln!("Error: Input path does not exist: {}", args.input.display());
-        std::process::exit(exit_codes::INPUT_NOT_FOUND);
+        return Err(CliError::InputNotFound(args.input.clone()));
     }
 
-    // Collect PDF files to process
-    let pdf_files = collect_pdf_files(&args.input)?;
+    // Input overlapping the output: leave out files written by earlier runs
+    // instead of feeding them back in
+    let input_dir = if args.input.is_dir() {
+        args.input.as_path()
+    } else {
+        args.input.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."))
+    };
+    let skip_outputs = superbook_pdf::paths_overlap(input_dir, &args.output);
+    if skip_outputs && args.input.is_file() && superbook_pdf::is_generated_output(&args.input) {
+        return Err(CliError::InvalidArgs(format!(
+            "{} is an output of a previous run in {}; convert the original instead",
+            args.input.display(),
+            args.output.display()
+        )));
+    }
+
+    // Collect PDF files (and comic archives) to process
+    let inputs = collect_pdf_files(&args.input, args.include_archives, skip_outputs)?;
+    let pdf_files = inputs.files;
+    if inputs.skipped_outputs > 0 && !args.quiet {
+        eprintln!(
+            "Warning: Skipping {} file(s) written by a previous run (input and output directories overlap)",
+            inputs.skipped_outputs
+        );
+    }
+    for (path, reason) in &inputs.invalid {
+        eprintln!("Error: Not a valid PDF: {}: {}", path.display(), reason);
+    }
+    if let Some((path, reason)) = inputs.invalid.first().filter(|_| args.fail_fast) {
+        return Err(CliError::InvalidPdf {
+            path: path.clone(),
+            reason: *reason,
+        });
+    }
     if pdf_files.is_empty() {
-        eprintln!("Error: No PDF files found in input path");
-        std::process::exit(exit_codes::INPUT_NOT_FOUND);
+        if !inputs.invalid.is_empty() {
+            return Err(CliError::BatchFailed {
+                failed: inputs.invalid.len(),
+                code: ExitCode::InvalidArgs,
+            });
+        }
+        return Err(CliError::NoInputFiles);
     }
 
     // Load config file if specified, otherwise use default
@@ -146,18 +273,55 @@ fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
     let pipeline_config = file_config.merge_with_cli(&cli_overrides);
     let pipeline = PdfPipeline::new(pipeline_config);
 
+    // Out-of-range config file values and unknown highlighter names fail
+    // before any file is processed
+    pipeline.config().validate()?;
+
+    if let Some(format) = args.print_config {
+        match format {
+            ConfigFormatCli::Toml => print!("{}", pipeline.config().to_resolved_toml()?),
+            ConfigFormatCli::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(&pipeline.config().to_resolved_json()).unwrap_or_default()
+            ),
+        }
+        return Ok(());
+    }
+
     if args.dry_run {
         print_execution_plan(args, &pdf_files, pipeline.config());
+        if args.explain {
+            print_stage_decisions(args, &pdf_files, &pipeline);
+        }
         return Ok(());
     }
 
-    // Create output directory
-    std::fs::create_dir_all(&args.output)?;
-
     let verbose = args.verbose > 0;
+    let lang = args.effective_lang();
+
+    // Create progress callback (with the event log, opened before anything can fail)
+    let event_log = args.event_log.as_deref().map(EventLog::open).transpose()?;
+    let progress = VerboseProgress::new(args.verbose.into())
+        .with_lang(lang)
+        .with_event_log(event_log);
+    progress.log_event(RunEvent::RunStart {
+        files: pdf_files.len(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    });
 
-    // Create progress callback
-    let progress = VerboseProgress::new(args.verbose.into());
+    // Fail fast (or degrade with --ocr-optional) when OCR tools are missing
+    let pipeline = pipeline.check_ocr_dependencies().inspect_err(|e| {
+        progress.log_event(RunEvent::Error { input: None, message: e.to_string() });
+    })?;
+
+    // Remote output (s3://...): each book is written to a staging directory
+    // and uploaded once it is finished
+    let sink = superbook_pdf::output_sink::from_output(&args.output).map_err(|e| CliError::InvalidArgs(e.to_string()))?;
+    let staging = if sink.is_remote() { Some(tempfile::tempdir()?) } else { None };
+    let output_dir = staging.as_ref().map_or(args.output.as_path(), |dir| dir.path());
+
+    // Create output directory
+    std::fs::create_dir_all(output_dir)?;
 
     // Pre-compute options JSON for caching
     let options_json = pipeline.config().to_json();
@@ -166,14 +330,24 @@ fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
     let mut ok_count = 0usize;
     let mut skip_count = 0usize;
     let mut error_count = 0usize;
+    let mut first_error_code: Option<ExitCode> = None;
+    let mut low_confidence_count = 0usize;
+    let mut low_ssim_count = 0usize;
+    let ocr_min_confidence = pipeline.config().ocr_min_confidence;
+    let mut report = BatchReport::new(pdf_files.len() + inputs.invalid.len());
+    for (path, reason) in &inputs.invalid {
+        let output_pdf = pipeline.get_output_path(path, output_dir);
+        record_file(&mut report, &progress, FileReport::invalid(path, &output_pdf, *reason));
+        first_error_code.get_or_insert(ExitCode::InvalidArgs);
+    }
 
     // Process each PDF file
     for (idx, pdf_path) in pdf_files.iter().enumerate() {
-        let output_pdf = pipeline.get_output_path(pdf_path, &args.output);
+        let output_pdf = pipeline.get_output_path(pdf_path, output_dir);
 
         // Check cache for smart skipping
-        if args.skip_existing && !args.force {
-            if output_pdf.exists() {
+        let reprocess_reason = match args.reprocess_policy() {
+            ReprocessPolicy::SkipExisting if output_pdf.exists() => {
                 if verbose {
                     println!(
                         "[{}/{}] Skipping (exists): {}",
@@ -183,23 +357,33 @@ fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
                     );
                 }
                 skip_count += 1;
+                record_file(&mut report, &progress, FileReport::skip(pdf_path, &output_pdf, 0));
                 continue;
             }
-        } else if !args.force {
-            if let Some(cache) = should_skip_processing(pdf_path, &output_pdf, &options_json, false) {
-                if verbose {
-                    println!(
-                        "[{}/{}] Skipping (cached, {} pages): {}",
-                        idx + 1,
-                        pdf_files.len(),
-                        cache.result.page_count,
-                        pdf_path.display()
-                    );
+            ReprocessPolicy::SkipExisting => None,
+            ReprocessPolicy::Force if !args.use_cache() => Some("cache disabled"),
+            ReprocessPolicy::Force => Some("forced"),
+            ReprocessPolicy::IfChanged => {
+                match check_cache_status(pdf_path, &output_pdf, &options_json) {
+                    CacheStatus::Valid(cache) => {
+                        if verbose {
+                            println!(
+                                "[{}/{}] Skipping (cached, {} pages): {}",
+                                idx + 1,
+                                pdf_files.len(),
+                                cache.result.page_count,
+                                pdf_path.display()
+                            );
+                        }
+                        skip_count += 1;
+                        record_file(&mut report, &progress, FileReport::skip(pdf_path, &output_pdf, cache.result.page_count));
+                        continue;
+                    }
+                    CacheStatus::OutputMissing => None,
+                    status => Some(status.reason()),
                 }
-                skip_count += 1;
-                continue;
             }
-        }
+        };
 
         if verbose {
             println!(
@@ -208,18 +392,49 @@ fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
                 pdf_files.len(),
                 pdf_path.display()
             );
+            if let Some(reason) = reprocess_reason {
+                println!("    reprocessing: {}", reason);
+            }
         }
 
         // Process using pipeline
-        match pipeline.process_with_progress(pdf_path, &args.output, &progress) {
+        let context = PipelineContext::new(pdf_path, idx, pdf_files.len());
+        if staging.is_some() {
+            // Drop what a failed book left behind
+            std::fs::remove_dir_all(output_dir).ok();
+            std::fs::create_dir_all(output_dir)?;
+        }
+        let processed = pipeline.process_file(&context, output_dir, &progress).and_then(|mut result| {
+            if sink.is_remote() {
+                let stored = superbook_pdf::output_sink::publish_dir(sink.as_ref(), output_dir)?;
+                progress.on_step_complete("Upload", &stored.join(", "));
+                if let Some(name) = result.output_path.file_name() {
+                    let location = format!("{}/{}", sink.location().trim_end_matches('/'), name.to_string_lossy());
+                    result.output_path = PathBuf::from(location);
+                }
+            }
+            Ok(result)
+        });
+        match processed {
             Ok(result) => {
                 ok_count += 1;
-
-                // Save cache after successful processing
-                if let Ok(digest) = CacheDigest::new(pdf_path, &options_json) {
-                    let cache_result = result.to_cache_result();
-                    let cache = ProcessingCache::new(digest, cache_result);
-                    let _ = cache.save(&output_pdf);
+                record_file(&mut report, &progress, FileReport::ok(
+                    pdf_path,
+                    &result.output_path,
+                    result.page_count,
+                    result.elapsed_seconds,
+                    result.output_size,
+                ).with_features(FileFeatures::of(&result)));
+
+                // Save cache after successful processing (pages skipped
+                // after a tool timeout are retried on the next run; remote
+                // outputs cannot be checked, so they are not cached)
+                if args.use_cache() && result.failed_pages.is_empty() && !sink.is_remote() {
+                    if let Ok(digest) = CacheDigest::new(pdf_path, &options_json) {
+                        let cache_result = result.to_cache_result();
+                        let cache = ProcessingCache::new(digest, cache_result).with_source(pdf_path, &options_json);
+                        let _ = cache.save(&output_pdf);
+                    }
                 }
 
                 if verbose {
@@ -230,24 +445,203 @@ fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
                         result.output_size
                     );
                 }
+                if !result.failed_pages.is_empty() {
+                    let pages: Vec<String> = result.failed_pages.iter().map(|p| (p + 1).to_string()).collect();
+                    let msg = if pipeline.config().on_page_error == superbook_pdf::PageErrorPolicy::Placeholder {
+                        Msg::PlaceholderPages
+                    } else {
+                        Msg::TimedOutPages
+                    };
+                    println!("    {}: {}", msg.text(lang), pages.join(", "));
+                }
+                if !result.inverted_pages.is_empty() && !args.quiet {
+                    let pages: Vec<usize> = result.inverted_pages.iter().map(|p| p + 1).collect();
+                    println!("    {}: {}", Msg::InvertedPages.text(lang), format_page_list(&pages));
+                }
+                if !result.ocr_skipped_pages.is_empty() && verbose {
+                    let pages: Vec<usize> = result.ocr_skipped_pages.iter().map(|p| p + 1).collect();
+                    println!("    {}: {}", Msg::OcrSkippedPages.text(lang), format_page_list(&pages));
+                }
+                if result.draft && !args.quiet {
+                    println!("    {}", Msg::QuickMode.text(lang));
+                }
+                if let Some(path) = result.text_sidecar.as_ref().filter(|_| !args.quiet) {
+                    println!("    {}: {}", Msg::TextSidecar.text(lang), path.display());
+                }
+                if !result.mixed_size_pages.is_empty() && !args.quiet {
+                    let pages: Vec<usize> = result.mixed_size_pages.iter().map(|d| d.page + 1).collect();
+                    println!(
+                        "    {} ({}): {}",
+                        Msg::MixedSizePages.text(lang),
+                        pipeline.config().mixed_size.name(),
+                        format_page_list(&pages)
+                    );
+                }
+                if let Some(dpi) = result.effective_dpi.filter(|_| verbose) {
+                    println!("    {}: {:.0}", Msg::EffectiveDpi.text(lang), dpi);
+                }
+                if result.resumed_pages > 0 && !args.quiet {
+                    println!("    {}: {}", Msg::ResumedPages.text(lang), result.resumed_pages);
+                }
+                if verbose && result.work_cache.lookups() > 0 {
+                    println!(
+                        "    {}: {}/{} ({:.0}%), {} evicted",
+                        Msg::WorkCache.text(lang),
+                        result.work_cache.hits,
+                        result.work_cache.lookups(),
+                        result.work_cache.hit_rate() * 100.0,
+                        result.work_cache.evicted
+                    );
+                }
+                if verbose && result.deduplicated_pages > 0 {
+                    println!(
+                        "    {}: {}/{}",
+                        Msg::DeduplicatedPages.text(lang),
+                        result.deduplicated_pages,
+                        result.page_count
+                    );
+                }
+                if verbose && !result.aspect_adjustments.is_empty() {
+                    let padded = result.aspect_adjustments.iter().filter(|a| a.is_padded()).count();
+                    let cropped = result.aspect_adjustments.iter().filter(|a| a.is_cropped()).count();
+                    println!("    {}: {}/{}", Msg::AspectAdjusted.text(lang), padded, cropped);
+                }
+                if verbose && result.text_layer_reused {
+                    println!("    {}", Msg::TextLayerReused.text(lang));
+                }
+                if verbose && result.bilevel.pages > 0 {
+                    println!(
+                        "    {}: {} (JBIG2 {}), {} -> {} ({:.1}x)",
+                        Msg::BilevelPages.text(lang),
+                        result.bilevel.pages,
+                        result.bilevel.jbig2_pages,
+                        superbook_pdf::format_file_size(result.bilevel.raw_bytes),
+                        superbook_pdf::format_file_size(result.bilevel.encoded_bytes),
+                        result.bilevel.compression_ratio()
+                    );
+                }
+                if verbose && !result.page_color_modes.is_empty() {
+                    let pages: Vec<String> = result.color_pages().iter().map(|p| p.to_string()).collect();
+                    println!(
+                        "    {}: {}/{} [{}]",
+                        Msg::ColorPages.text(lang),
+                        pages.len(),
+                        result.page_count,
+                        pages.join(", ")
+                    );
+                }
+                let low_pages = result.low_confidence_pages(ocr_min_confidence);
+                low_confidence_count += low_pages.len();
+                if !low_pages.is_empty() && !args.quiet {
+                    println!(
+                        "    {} (<{:.0}%): {}",
+                        Msg::LowOcrConfidence.text(lang),
+                        ocr_min_confidence,
+                        format_page_list(&low_pages)
+                    );
+                }
+                if let Some(quality) = result.quality_summary() {
+                    low_ssim_count += quality.low_ssim_pages.len();
+                    if verbose {
+                        println!(
+                            "    {}: SSIM {:.3} (min {:.3} p{}), PSNR {:.1} dB",
+                            Msg::QualityMetrics.text(lang),
+                            quality.mean_ssim,
+                            quality.min_ssim,
+                            quality.min_ssim_page,
+                            quality.mean_psnr
+                        );
+                    }
+                    if !quality.low_ssim_pages.is_empty() && !args.quiet {
+                        println!(
+                            "    {} (<{:.2}): {}",
+                            Msg::LowSsimPages.text(lang),
+                            superbook_pdf::quality::DEFAULT_MIN_SSIM,
+                            format_page_list(&quality.low_ssim_pages)
+                        );
+                    }
+                }
+                progress.log_event(RunEvent::StageTimings {
+                    input: pdf_path.clone(),
+                    timings: result.stage_timings.clone(),
+                });
+                if args.verbose_timing {
+                    result.stage_timings.print_breakdown(lang);
+                }
             }
             Err(e) => {
                 eprintln!("Error processing {}: {}", pdf_path.display(), e);
+                progress.log_event(RunEvent::Error {
+                    input: Some(pdf_path.clone()),
+                    message: e.to_string(),
+                });
+                record_file(&mut report, &progress, FileReport::error(pdf_path, &output_pdf, e.to_string()));
+                if args.fail_fast {
+                    report.set_elapsed(start_time.elapsed());
+                    progress.log_event(RunEvent::RunFinish(report.totals.clone()));
+                    if let Some(path) = &args.report {
+                        if let Err(save_err) = report.save(path) {
+                            eprintln!("Failed to write report {}: {}", path.display(), save_err);
+                        }
+                    }
+                    return Err(CliError::from(e));
+                }
                 error_count += 1;
+                first_error_code.get_or_insert(CliError::from(e).exit_code());
             }
         }
     }
 
     let elapsed = start_time.elapsed();
+    report.set_elapsed(elapsed);
+    progress.log_event(RunEvent::RunFinish(report.totals.clone()));
+
+    if let Some(path) = &args.report {
+        report.save(path)?;
+        if verbose {
+            println!("{}: {}", Msg::ReportWritten.text(lang), path.display());
+        }
+    }
 
     // Print summary
     if !args.quiet {
-        ProgressTracker::print_summary(pdf_files.len(), ok_count, skip_count, error_count);
-        println!("Total time: {:.2}s", elapsed.as_secs_f64());
+        ProgressTracker::print_summary_lang(
+            lang,
+            pdf_files.len() + inputs.invalid.len(),
+            ok_count,
+            skip_count,
+            error_count,
+            inputs.invalid.len(),
+        );
+        println!("{}: {:.2}s", Msg::TotalTime.text(lang), elapsed.as_secs_f64());
+        if low_confidence_count > 0 {
+            println!(
+                "{} (<{:.0}%): {}",
+                Msg::LowOcrConfidence.text(lang),
+                ocr_min_confidence,
+                low_confidence_count
+            );
+        }
+        if low_ssim_count > 0 {
+            println!(
+                "{} (<{:.2}): {}",
+                Msg::LowSsimPages.text(lang),
+                superbook_pdf::quality::DEFAULT_MIN_SSIM,
+                low_ssim_count
+            );
+        }
+        if !pipeline.skipped_stages().is_empty() {
+            let stages: Vec<String> = pipeline.skipped_stages().iter().map(|s| s.to_string()).collect();
+            println!("{}: {}", Msg::SkippedStages.text(lang), stages.join(", "));
+        }
+        report.features.print_lang(lang);
     }
 
-    if error_count > 0 {
-        return Err(format!("{} file(s) failed to process", error_count).into());
+    if error_count + inputs.invalid.len() > 0 {
+        return Err(CliError::BatchFailed {
+            failed: error_count + inputs.invalid.len(),
+            code: first_error_code.unwrap_or(ExitCode::ProcessingError),
+        });
     }
 
     Ok(())
@@ -255,6 +649,12 @@ fn run_convert(args: &ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
 
 // ============ Helper Functions ============
 
+/// Record a file's outcome in the batch report and the event log
+fn record_file(report: &mut BatchReport, progress: &VerboseProgress, file: FileReport) {
+    progress.log_event(RunEvent::FileFinish(file.clone()));
+    report.push(file);
+}
+
 /// Create CLI overrides from ConvertArgs
 ///
 /// Only override config file values when CLI explicitly sets a non-default value.
@@ -268,6 +668,10 @@ fn create_cli_overrides(args: &ConvertArgs) -> CliOverrides {
     const DEFAULT_OUTPUT_HEIGHT: u32 = 3508;
     const DEFAULT_JPEG_QUALITY: u8 = 90;
 
+    if args.quick {
+        overrides.quick = Some(true);
+    }
+
     // Basic options - only set if they differ from defaults
     if args.dpi != DEFAULT_DPI {
         overrides.dpi = Some(args.dpi);
@@ -311,17 +715,185 @@ fn create_cli_overrides(args: &ConvertArgs) -> CliOverrides {
     if args.offset_alignment || args.advanced {
         overrides.offset_alignment = Some(true);
     }
+    if args.register_sides {
+        overrides.register_sides = Some(true);
+    }
 
     // Output height: only set if changed from default
     if args.output_height != DEFAULT_OUTPUT_HEIGHT {
         overrides.output_height = Some(args.output_height);
     }
+    if args.min_output_dpi != superbook_pdf::pipeline::DEFAULT_MIN_OUTPUT_DPI {
+        overrides.min_output_dpi = Some(args.min_output_dpi);
+    }
+    if args.on_low_dpi != superbook_pdf::LowDpiPolicyCli::Warn {
+        overrides.on_low_dpi = Some(args.on_low_dpi.into());
+    }
+    if args.max_page_megapixels != superbook_pdf::pipeline::DEFAULT_MAX_PAGE_MEGAPIXELS {
+        overrides.max_page_megapixels = Some(args.max_page_megapixels);
+    }
+    if args.max_dimension != superbook_pdf::pipeline::DEFAULT_MAX_PAGE_DIMENSION {
+        overrides.max_page_dimension = Some(args.max_dimension);
+    }
+    overrides.work_cache = args.work_cache.clone();
+    if args.work_cache_max_mb != superbook_pdf::work_cache::DEFAULT_WORK_CACHE_MAX_MB {
+        overrides.work_cache_max_mb = Some(args.work_cache_max_mb);
+    }
+    if args.resume {
+        overrides.resume = Some(true);
+    }
 
     // JPEG quality: only set if changed from default
     if args.jpeg_quality != DEFAULT_JPEG_QUALITY {
         overrides.jpeg_quality = Some(args.jpeg_quality);
     }
 
+    // Auto contrast: only set if enabled
+    let auto_contrast = args.effective_auto_contrast();
+    if auto_contrast.is_enabled() {
+        overrides.auto_contrast = Some(auto_contrast);
+    }
+    if args.contrast_per_channel {
+        overrides.auto_contrast_per_channel = Some(true);
+    }
+    overrides.tone_curve = args.effective_tone_curve();
+
+    // OCR exports: only set if requested
+    let ocr_formats = args.effective_ocr_formats();
+    if !ocr_formats.is_empty() {
+        overrides.ocr_formats = Some(ocr_formats);
+    }
+
+    // Deterministic output: only set if enabled
+    if args.deterministic {
+        overrides.deterministic = Some(true);
+    }
+    if args.metadata_from_ocr {
+        overrides.metadata_from_ocr = Some(true);
+    }
+    if args.preserve_metadata {
+        overrides.preserve_source_metadata = Some(true);
+    }
+    if args.preserve_outline {
+        overrides.preserve_source_outline = Some(true);
+    }
+    if args.text_sidecar {
+        overrides.text_sidecar = Some(true);
+    }
+    if args.dehyphenate {
+        overrides.dehyphenate = Some(true);
+    }
+    if args.ocr_pages.is_some() {
+        overrides.ocr_pages = args.ocr_pages.clone();
+    }
+    if args.ocr_skip_pictures {
+        overrides.ocr_skip_pictures = Some(true);
+    }
+    if args.ocr_optional {
+        overrides.ocr_optional = Some(true);
+    }
+    if args.nice != 0 {
+        overrides.nice = Some(args.nice);
+    }
+    if args.gpu_wait {
+        overrides.gpu_wait = Some(true);
+    }
+    if args.tool_timeout.is_some() {
+        overrides.tool_timeout_secs = args.tool_timeout;
+    }
+    if args.stall_warning != superbook_pdf::DEFAULT_STALL_WARNING_SECS {
+        overrides.stall_warning_secs = Some(args.stall_warning);
+    }
+    if args.ocr_concurrency.is_some() {
+        overrides.ocr_concurrency = args.ocr_concurrency;
+    }
+    if args.external_thread_fraction.is_some() {
+        overrides.external_thread_fraction = args.external_thread_fraction;
+    }
+    if args.on_page_error != superbook_pdf::PageErrorPolicyCli::Skip {
+        overrides.on_page_error = Some(args.on_page_error.into());
+    }
+    if args.skip_existing_ocr {
+        overrides.skip_existing_ocr = Some(true);
+    }
+    if args.remove_line_artifacts {
+        overrides.remove_line_artifacts = Some(true);
+    }
+    if args.invert != superbook_pdf::InvertCli::Off {
+        overrides.invert = Some(args.invert.into());
+    }
+    if args.remove_markers {
+        overrides.remove_markers = Some(true);
+    }
+    if args.marker_colors.join(",") != superbook_pdf::cli::DEFAULT_MARKER_COLORS {
+        overrides.marker_colors = Some(args.marker_colors.clone());
+    }
+    if args.marker_region != superbook_pdf::MarkerAreaCli::Page {
+        overrides.marker_region = Some(args.marker_region.into());
+    }
+    if args.desaturate_below.is_some() {
+        overrides.desaturate_below = args.desaturate_below;
+    }
+    if args.target_aspect.is_some() {
+        overrides.target_aspect = args.target_aspect;
+    }
+    if args.mixed_color {
+        overrides.mixed_color = Some(true);
+    }
+    if args.color_policy != superbook_pdf::ColorPolicyCli::PerPage {
+        overrides.color_policy = Some(args.color_policy.into());
+    }
+    if args.mixed_size != superbook_pdf::MixedSizeCli::Uniform {
+        overrides.mixed_size = Some(args.mixed_size.into());
+    }
+    if args.ocr_min_confidence != superbook_pdf::pipeline::DEFAULT_OCR_MIN_CONFIDENCE {
+        overrides.ocr_min_confidence = Some(args.ocr_min_confidence);
+    }
+    if args.quality_metrics {
+        overrides.quality_metrics = Some(true);
+    }
+    if args.page_order != superbook_pdf::PageOrderCli::Auto {
+        overrides.binding = Some(args.page_order.into());
+    }
+    if args.text_direction != superbook_pdf::TextDirectionCli::Auto {
+        overrides.text_direction = Some(args.text_direction.into());
+    }
+    if args.flatten_background != superbook_pdf::util::DEFAULT_FLATTEN_BACKGROUND {
+        overrides.flatten_background = Some(args.flatten_background);
+    }
+    if args.resize_filter != superbook_pdf::ResizeFilterCli::Lanczos3 {
+        overrides.resize_filter = Some(args.resize_filter.into());
+    }
+    if args.image_encoding != superbook_pdf::ImageEncodingCli::Standard {
+        overrides.image_encoding = Some(args.image_encoding.into());
+    }
+    overrides.pdfa = args.pdfa.map(Into::into);
+    if args.pdfa_validate {
+        overrides.pdfa_validate = Some(true);
+    }
+    if args.output_format != superbook_pdf::BookFormatCli::Pdf {
+        overrides.output_format = Some(args.output_format.into());
+    }
+    if args.page_labels {
+        overrides.page_labels = Some(true);
+    }
+    if args.running_heads != superbook_pdf::RunningHeadModeCli::Keep {
+        overrides.running_heads = Some(args.running_heads.into());
+    }
+    if args.parity != superbook_pdf::PageParityCli::All {
+        overrides.parity = Some(args.parity.into());
+    }
+    overrides.post_hook = args.post_hook.clone();
+    overrides.page_hook = args.page_hook.clone();
+
+    // Streaming extraction: only set if changed from default
+    if args.extract_queue_depth != superbook_pdf::pipeline::DEFAULT_EXTRACT_QUEUE_DEPTH {
+        overrides.extract_queue_depth = Some(args.extract_queue_depth);
+    }
+    if args.extract_batch_pages != superbook_pdf::image_extract::DEFAULT_BATCH_PAGES {
+        overrides.extract_batch_pages = Some(args.extract_batch_pages);
+    }
+
     // Debug options
     overrides.max_pages = args.max_pages;
     if args.save_debug {
@@ -331,94 +903,327 @@ fn create_cli_overrides(args: &ConvertArgs) -> CliOverrides {
     overrides
 }
 
+/// Inputs found by [`collect_pdf_files`]
+#[derive(Default)]
+struct CollectedInputs {
+    /// PDFs (and comic archives) to process, sorted
+    files: Vec<PathBuf>,
+    /// Outputs of earlier runs left out
+    skipped_outputs: usize,
+    /// PDFs that are empty, have no header or are truncated
+    invalid: Vec<(PathBuf, superbook_pdf::InvalidPdf)>,
+}
+
 /// Collect PDF files from input path (file or directory)
-fn collect_pdf_files(input: &PathBuf) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
-    let mut pdf_files = Vec::new();
+///
+/// An input file may also be a CBZ/CBR comic archive; archives in a
+/// directory are only collected with `include_archives`. With
+/// `skip_outputs`, outputs of earlier runs in the directory are left out
+/// and counted. PDFs failing [`superbook_pdf::validate_pdf_file`] are
+/// collected separately; a single input file that fails is an error.
+fn collect_pdf_files(input: &PathBuf, include_archives: bool, skip_outputs: bool) -> Result<CollectedInputs, CliError> {
+    let mut inputs = CollectedInputs::default();
 
     if input.is_file() {
         if input.extension().is_some_and(|ext| ext == "pdf") {
-            pdf_files.push(input.clone());
+            // A single bad file fails the run before any pipeline setup
+            if let Some(reason) = invalid_pdf(input) {
+                return Err(CliError::InvalidPdf {
+                    path: input.clone(),
+                    reason,
+                });
+            }
+            inputs.files.push(input.clone());
+        } else if superbook_pdf::comic_archive::is_comic_archive(input) {
+            inputs.files.push(input.clone());
         }
     } else if input.is_dir() {
         for entry in std::fs::read_dir(input)? {
             let entry = entry?;
             let path = entry.path();
-            if path.is_file() && path.extension().is_some_and(|ext| ext == "pdf") {
-                pdf_files.push(path);
+            let is_pdf = path.extension().is_some_and(|ext| ext == "pdf");
+            let is_archive = include_archives && superbook_pdf::comic_archive::is_comic_archive(&path);
+            if !path.is_file() || !(is_pdf || is_archive) {
+                continue;
+            }
+            if skip_outputs && superbook_pdf::is_generated_output(&path) {
+                inputs.skipped_outputs += 1;
+            } else if let Some(reason) = is_pdf.then(|| invalid_pdf(&path)).flatten() {
+                inputs.invalid.push((path, reason));
+            } else {
+                inputs.files.push(path);
             }
         }
-        pdf_files.sort();
+        inputs.files.sort();
+        inputs.invalid.sort_by(|a, b| a.0.cmp(&b.0));
     }
 
-    Ok(pdf_files)
+    Ok(inputs)
+}
+
+/// Why `path` fails the PDF header/trailer check (`None` for a valid PDF;
+/// read errors are left to the pipeline)
+fn invalid_pdf(path: &Path) -> Option<superbook_pdf::InvalidPdf> {
+    match superbook_pdf::validate_pdf_file(path) {
+        Err(superbook_pdf::PdfReaderError::InvalidPdf(reason)) => Some(reason),
+        _ => None,
+    }
+}
+
+/// Comma-separated 1-based page list ("none" when empty)
+fn format_page_list(pages: &[usize]) -> String {
+    if pages.is_empty() {
+        return "none".to_string();
+    }
+    pages.iter().map(|p| p.to_string()).collect::<Vec<_>>().join(", ")
 }
 
 /// Print execution plan for dry-run mode
 fn print_execution_plan(args: &ConvertArgs, pdf_files: &[PathBuf], config: &superbook_pdf::PipelineConfig) {
-    println!("=== Dry Run - Execution Plan ===");
+    let lang = args.effective_lang();
+    let t = |msg: Msg| msg.text(lang);
+
+    println!("{}", t(Msg::DryRunTitle));
     println!();
-    println!("Input: {}", args.input.display());
-    println!("Output: {}", args.output.display());
-    println!("Files to process: {}", pdf_files.len());
+    println!("{}: {}", t(Msg::Input), args.input.display());
+    println!("{}: {}", t(Msg::Output), args.output.display());
+    println!("{}: {}", t(Msg::FilesToProcess), pdf_files.len());
     println!();
-    println!("Pipeline Configuration:");
-    println!("  1. Image Extraction (DPI: {})", config.dpi);
-    if config.deskew {
-        println!("  2. Deskew Correction: ENABLED");
-    } else {
-        println!("  2. Deskew Correction: DISABLED");
+    println!("{}:", t(Msg::PipelineConfiguration));
+    if config.quick {
+        println!("  {}", t(Msg::QuickMode));
+    }
+    if let Some(dir) = &config.work_cache {
+        println!("  {}: {} (max {} MB)", t(Msg::WorkCache), dir.display(), config.work_cache_max_mb);
+    }
+    if config.resume {
+        println!("  {}", t(Msg::ResumeRun));
+    }
+    println!("  1. {} (DPI: {})", t(Msg::ImageExtraction), config.dpi);
+    if config.flatten_background != superbook_pdf::util::DEFAULT_FLATTEN_BACKGROUND {
+        let [r, g, b] = config.flatten_background;
+        println!("     {}: #{:02x}{:02x}{:02x}", t(Msg::TransparencyBackground), r, g, b);
+    }
+    match config.invert {
+        superbook_pdf::Invert::Off => {}
+        superbook_pdf::Invert::Auto => println!("     {}: auto", t(Msg::NegativeInversion)),
+        superbook_pdf::Invert::Force => println!("     {}: force", t(Msg::NegativeInversion)),
+    }
+    if config.remove_line_artifacts {
+        println!("     {}: {}", t(Msg::LineArtifactRemoval), t(Msg::Enabled));
+    }
+    if config.remove_markers {
+        let colors = if config.marker_colors.is_empty() {
+            "yellow, pink, green, blue, orange".to_string()
+        } else {
+            config.marker_colors.join(", ")
+        };
+        if config.marker_region.is_page() {
+            println!("     {}: {}", t(Msg::MarkerRemoval), colors);
+        } else {
+            println!("     {}: {} (margins)", t(Msg::MarkerRemoval), colors);
+        }
     }
-    println!("  3. Margin Trim: {}%", config.margin_trim);
+    println!("  2. {}: {}", t(Msg::DeskewCorrection), Msg::on_off(config.deskew, lang));
+    println!("  3. {}: {}%", t(Msg::MarginTrim), config.margin_trim);
     if config.upscale {
-        println!("  4. AI Upscaling (RealESRGAN 2x): ENABLED");
+        println!("  4. {} (RealESRGAN 2x): {}", t(Msg::AiUpscaling), t(Msg::Enabled));
     } else {
-        println!("  4. AI Upscaling: DISABLED");
+        println!("  4. {}: {}", t(Msg::AiUpscaling), t(Msg::Disabled));
     }
     if config.ocr {
-        println!("  5. OCR (YomiToku): ENABLED");
+        let mut exports: Vec<_> = config.ocr_formats.iter().map(|f| f.extension()).collect();
+        if config.write_text_sidecar {
+            exports.push("txt");
+        }
+        if exports.is_empty() {
+            println!("  5. {} (YomiToku): {}", t(Msg::Ocr), t(Msg::Enabled));
+        } else {
+            println!("  5. {} (YomiToku): {} [{}]", t(Msg::Ocr), t(Msg::Enabled), exports.join(", "));
+        }
     } else {
-        println!("  5. OCR: DISABLED");
+        println!("  5. {}: {}", t(Msg::Ocr), t(Msg::Disabled));
+    }
+    if config.skip_existing_ocr {
+        println!("     {}", t(Msg::SkipExistingOcr));
+    }
+    if config.ocr {
+        if let Some(pages) = &config.ocr_pages {
+            println!("     {}: {}", t(Msg::OcrPages), pages);
+        }
+        if config.ocr_skip_pictures {
+            println!("     {}", t(Msg::OcrSkipPictures));
+        }
     }
     if config.internal_resolution {
-        println!("  6. Internal Resolution Normalization (4960x7016): ENABLED");
+        println!("  6. {} (4960x7016): {}", t(Msg::InternalResolution), t(Msg::Enabled));
     }
     if config.color_correction {
-        println!("  7. Global Color Correction: ENABLED");
+        println!("  7. {}: {}", t(Msg::ColorCorrection), t(Msg::Enabled));
+    }
+    if config.auto_contrast.is_enabled() {
+        println!("  7. {}: {}", t(Msg::AutoContrast), config.auto_contrast.describe());
+    }
+    if let Some(curve) = &config.tone_curve {
+        println!("  7. {}: {}", t(Msg::ToneCurve), curve.describe());
+    }
+    if let Some(threshold) = config.desaturate_below {
+        println!("  7. {}: {:.2}", t(Msg::Desaturate), threshold);
+    }
+    if config.register_sides {
+        println!("  8. {}: {}", t(Msg::RegisterSides), t(Msg::Enabled));
     }
     if config.offset_alignment {
-        println!("  8. Page Number Offset Alignment: ENABLED");
+        println!("  8. {}: {}", t(Msg::OffsetAlignment), t(Msg::Enabled));
+    }
+    if let Some((w, h)) = config.target_aspect {
+        println!("  8. {}: {}:{}", t(Msg::TargetAspect), w, h);
+    }
+    if config.quality_metrics {
+        println!("  8. {}: {}", t(Msg::QualityMetrics), t(Msg::Enabled));
+    }
+    if config.mixed_color {
+        println!("  9. {}: {}", t(Msg::MixedColor), t(Msg::Enabled));
+    }
+    if !config.color_policy.is_per_page() {
+        println!("  9. {}: {}", t(Msg::ColorPolicy), config.color_policy.name());
+    }
+    if !config.mixed_size.is_uniform() {
+        println!("  9. {}: {}", t(Msg::MixedSizePages), config.mixed_size.name());
+    }
+    println!(
+        "  9. {} ({}: {}, {}: {}, {}: {})",
+        t(Msg::PdfGeneration),
+        t(Msg::OutputHeight),
+        config.output_height,
+        t(Msg::ResizeFilter),
+        config.resize_filter.name(),
+        t(Msg::PageOrder),
+        config.binding.name()
+    );
+    if !config.text_direction.is_auto() {
+        println!("     {}: {}", t(Msg::TextDirection), config.text_direction.name());
+    }
+    if !config.image_encoding.is_standard() {
+        println!("     {}: {}", t(Msg::BilevelEncoding), config.image_encoding.name());
+    }
+    if let Some(level) = config.pdfa {
+        let validate = if config.pdfa_validate { format!(" ({})", t(Msg::PdfaValidate)) } else { String::new() };
+        println!("     {}: {}{}", t(Msg::PdfaOutput), level, validate);
+    }
+    if !config.output_format.is_pdf() {
+        println!("     {}: {}", t(Msg::OutputFormat), config.output_format.name());
+    }
+    if config.min_output_dpi > 0 {
+        let action = match config.on_low_dpi {
+            superbook_pdf::LowDpiPolicy::Warn => "warn",
+            superbook_pdf::LowDpiPolicy::Refuse => "refuse",
+        };
+        println!("     {}: {} ({})", t(Msg::MinOutputDpi), config.min_output_dpi, action);
+    }
+    if config.page_labels {
+        println!("     {}: {}", t(Msg::PageLabels), t(Msg::Enabled));
+    }
+    match config.running_heads {
+        superbook_pdf::markdown::RunningHeadMode::Keep => {}
+        superbook_pdf::markdown::RunningHeadMode::Exclude => println!("     {}", t(Msg::RunningHeadsExcluded)),
+        superbook_pdf::markdown::RunningHeadMode::Erase => println!("     {}", t(Msg::RunningHeadsErased)),
+    }
+    match config.parity {
+        superbook_pdf::PageParity::All => {}
+        superbook_pdf::PageParity::Odd => println!("     {}", t(Msg::OddPagesOnly)),
+        superbook_pdf::PageParity::Even => println!("     {}", t(Msg::EvenPagesOnly)),
     }
-    println!("  9. PDF Generation (output height: {})", config.output_height);
     println!();
-    println!("Processing Options:");
-    println!("  Threads: {}", config.threads.unwrap_or_else(num_cpus::get));
+    println!("{}:", t(Msg::ProcessingOptions));
+    println!("  {}: {}", t(Msg::Threads), config.threads.unwrap_or_else(num_cpus::get));
+    if let Some(budget) = config.thread_budget() {
+        println!("  {}: {} / {}", t(Msg::ThreadBudget), budget.internal, budget.external);
+    }
+    match config.on_page_error {
+        superbook_pdf::PageErrorPolicy::Skip => {}
+        superbook_pdf::PageErrorPolicy::Abort => println!("  {}: abort", t(Msg::OnPageError)),
+        superbook_pdf::PageErrorPolicy::Placeholder => println!("  {}: placeholder", t(Msg::OnPageError)),
+    }
     if args.chunk_size > 0 {
-        println!("  Chunk size: {} pages", args.chunk_size);
+        println!("  {}: {} {}", t(Msg::ChunkSize), args.chunk_size, t(Msg::Pages));
+    } else {
+        println!("  {}: {}", t(Msg::ChunkSize), t(Msg::ChunkUnlimited));
+    }
+    if config.extract_queue_depth > 0 {
+        println!("  {}: {} {}", t(Msg::StreamingExtraction), config.extract_queue_depth, t(Msg::Pages));
     } else {
-        println!("  Chunk size: unlimited (all pages at once)");
+        println!("  {}: {}", t(Msg::StreamingExtraction), t(Msg::Disabled));
     }
-    println!("  GPU: {}", if config.gpu { "YES" } else { "NO" });
-    println!("  Skip existing: {}", if args.skip_existing { "YES" } else { "NO" });
-    println!("  Force re-process: {}", if args.force { "YES" } else { "NO" });
-    println!("  Verbose: {}", args.verbose);
+    println!("  {}: {}", t(Msg::ExtractBatch), config.extract_batch_pages);
+    println!("  {}: {}", t(Msg::Gpu), Msg::yes_no(config.gpu, lang));
+    println!("  {}: {}", t(Msg::ReprocessPolicy), args.reprocess_policy().name());
+    println!("  {}: {}", t(Msg::SkipExisting), Msg::yes_no(args.skip_existing, lang));
+    println!("  {}: {}", t(Msg::ForceReprocess), Msg::yes_no(args.force, lang));
+    println!("  {}: {}", t(Msg::Cache), Msg::on_off(args.use_cache(), lang));
+    println!("  {}: {}", t(Msg::Deterministic), Msg::on_off(config.deterministic, lang));
+    println!("  {}: {}", t(Msg::Verbose), args.verbose);
     println!();
-    println!("Debug Options:");
+    println!("{}:", t(Msg::DebugOptions));
     if let Some(max) = config.max_pages {
-        println!("  Max pages: {}", max);
+        println!("  {}: {}", t(Msg::MaxPages), max);
     } else {
-        println!("  Max pages: unlimited");
+        println!("  {}: {}", t(Msg::MaxPages), t(Msg::Unlimited));
     }
-    println!("  Save debug images: {}", if config.save_debug { "YES" } else { "NO" });
+    println!("  {}: {}", t(Msg::SaveDebugImages), Msg::yes_no(config.save_debug, lang));
     println!();
-    println!("Files:");
+    println!("{}:", t(Msg::Files));
     for (i, file) in pdf_files.iter().enumerate() {
-        println!("  {}. {}", i + 1, file.display());
+        match superbook_pdf::SourceResolution::probe(file, config.dpi) {
+            Some(resolution) => {
+                println!(
+                    "  {}. {} ({}: ~{:.0} DPI)",
+                    i + 1,
+                    file.display(),
+                    t(Msg::SourceImageDpi),
+                    resolution.source_dpi
+                );
+                if resolution.is_low() {
+                    println!("     Warning: {}", resolution.warning());
+                }
+            }
+            None => println!("  {}. {}", i + 1, file.display()),
+        }
+    }
+}
+
+fn print_stage_decisions(args: &ConvertArgs, pdf_files: &[PathBuf], pipeline: &PdfPipeline) {
+    let lang = args.effective_lang();
+    for file in pdf_files {
+        println!();
+        match superbook_pdf::explain::explain(pipeline, file, superbook_pdf::explain::DEFAULT_SAMPLE_PAGES) {
+            Ok(report) => {
+                let pages: Vec<String> = report.sampled_pages.iter().map(|p| p.to_string()).collect();
+                println!(
+                    "{}: {} ({}: {} / {})",
+                    Msg::StageDecisions.text(lang),
+                    file.display(),
+                    Msg::SampledPages.text(lang),
+                    pages.join(", "),
+                    report.page_count
+                );
+                for decision in &report.decisions {
+                    println!("  {}", decision);
+                }
+            }
+            Err(e) => eprintln!("{}: {}: {}", Msg::StageDecisions.text(lang), file.display(), e),
+        }
     }
 }
 
 // ============ Info Command ============
 
-fn run_info() -> Result<(), Box<dyn std::error::Error>> {
+fn run_info() -> Result<(), CliError> {
+    // Honour [tools] locations from the default config file
+    if let Ok(config) = Config::load() {
+        superbook_pdf::tools::register_overrides(&config.tools);
+    }
+
     println!("superbook-pdf v{}", env!("CARGO_PKG_VERSION"));
     println!();
 
@@ -428,15 +1233,9 @@ fn run_info() -> Result<(), Box<dyn std::error::Error>> {
     println!("  Arch: {}", std::env::consts::ARCH);
     println!("  CPUs: {}", num_cpus::get());
 
-    // Memory info (Linux)
-    if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
-        if let Some(line) = meminfo.lines().find(|l| l.starts_with("MemTotal:")) {
-            if let Some(kb) = line.split_whitespace().nth(1) {
-                if let Ok(kb_val) = kb.parse::<u64>() {
-                    println!("  Memory: {:.1} GB", kb_val as f64 / 1_048_576.0);
-                }
-            }
-        }
+    match superbook_pdf::pipeline::total_memory_mb() {
+        Some(mb) => println!("  Memory: {:.1} GB", mb as f64 / 1024.0),
+        None => println!("  Memory: unknown (not detected on {})", std::env::consts::OS),
     }
 
     // External Tools
@@ -458,7 +1257,7 @@ fn run_info() -> Result<(), Box<dyn std::error::Error>> {
     // GPU Status
     println!();
     println!("GPU Status:");
-    if let Ok(output) = std::process::Command::new("nvidia-smi")
+    if let Ok(output) = std::process::Command::new(superbook_pdf::tools::resolve("nvidia-smi"))
         .arg("--query-gpu=name,memory.total,driver_version")
         .arg("--format=csv,noheader")
         .output()
@@ -487,15 +1286,20 @@ fn run_info() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn check_tool(cmd: &str, name: &str) {
-    match which::which(cmd) {
-        Ok(path) => println!("  {}: {} (found)", name, path.display()),
-        Err(_) => println!("  {}: Not found", name),
+    match superbook_pdf::tools::find(cmd) {
+        Some(path) => println!("  {}: {} (found)", name, path.display()),
+        None => println!(
+            "  {}: Not found (set {} or [tools] {})",
+            name,
+            superbook_pdf::tools::env_var_name(cmd),
+            cmd
+        ),
     }
 }
 
 fn check_tool_with_version(cmd: &str, name: &str, version_args: &[&str]) {
-    match which::which(cmd) {
-        Ok(path) => {
+    match superbook_pdf::tools::find(cmd) {
+        Some(path) => {
             // Try to get version
             if let Ok(output) = std::process::Command::new(&path).args(version_args).output() {
                 let version_str = String::from_utf8_lossy(&output.stdout);
@@ -509,17 +1313,18 @@ fn check_tool_with_version(cmd: &str, name: &str, version_args: &[&str]) {
                 println!("  {}: {} (found)", name, path.display());
             }
         }
-        Err(_) => println!("  {}: Not found", name),
+        None => println!(
+            "  {}: Not found (set {} or [tools] {})",
+            name,
+            superbook_pdf::tools::env_var_name(cmd),
+            cmd
+        ),
     }
 }
 
 fn check_python() {
     // Check for Python
-    let python_cmd = if which::which("python3").is_ok() {
-        "python3"
-    } else if which::which("python").is_ok() {
-        "python"
-    } else {
+    let Some(python_cmd) = superbook_pdf::stages::python_command() else {
         println!("  Python: Not found");
         return;
     };
@@ -532,40 +1337,236 @@ fn check_python() {
         println!("  Python: {}", version.trim());
     }
 
-    // Check for RealESRGAN
-    if let Ok(output) = std::process::Command::new(python_cmd)
-        .args(["-c", "import realesrgan; print('available')"])
-        .output()
-    {
-        if output.status.success() {
-            println!("  RealESRGAN: Available");
+    // Check for RealESRGAN and YomiToku
+    for (module, name) in [("realesrgan", "RealESRGAN"), ("yomitoku", "YomiToku")] {
+        if superbook_pdf::stages::python_module_available(module) {
+            println!("  {}: Available", name);
         } else {
-            println!("  RealESRGAN: Not installed");
+            println!("  {}: Not installed", name);
         }
     }
+}
 
-    // Check for YomiToku
-    if let Ok(output) = std::process::Command::new(python_cmd)
-        .args(["-c", "import yomitoku; print('available')"])
-        .output()
-    {
-        if output.status.success() {
-            println!("  YomiToku: Available");
+// ============ Stages Command ============
+
+fn run_stages() -> Result<(), CliError> {
+    // Honour [tools] locations from the default config file
+    if let Ok(config) = Config::load() {
+        superbook_pdf::tools::register_overrides(&config.tools);
+    }
+
+    let statuses = superbook_pdf::stages::stage_statuses(&superbook_pdf::PipelineConfig::default());
+    println!("Pipeline stages (in order; DEFAULT = run by `convert` without options):");
+    println!();
+    println!("  {:<15} {:<8} {:<30} {:<36} DESCRIPTION", "STAGE", "DEFAULT", "STATUS", "OPTION");
+    for status in &statuses {
+        let stage = status.stage;
+        let state = if stage.requires.is_empty() {
+            "built in".to_string()
+        } else if let Some(found) = status.available.first() {
+            format!("ok ({})", found)
         } else {
-            println!("  YomiToku: Not installed");
+            let requires: Vec<String> = stage.requires.iter().map(ToString::to_string).collect();
+            format!("missing {}", requires.join(" | "))
+        };
+        println!(
+            "  {:<15} {:<8} {:<30} {:<36} {}",
+            stage.key,
+            if status.enabled { "on" } else { "off" },
+            state,
+            stage.option,
+            stage.description
+        );
+    }
+
+    let missing: Vec<_> = statuses.iter().filter(|status| !status.is_ready()).collect();
+    if !missing.is_empty() {
+        println!();
+        println!("To make the missing stages available:");
+        for status in missing {
+            let hints: Vec<String> = status.stage.requires.iter().map(|r| r.hint()).collect();
+            let fallback = status
+                .stage
+                .fallback
+                .map(|fallback| format!(" (without it: {})", fallback))
+                .unwrap_or_default();
+            println!("  {}: {}{}", status.stage.key, hints.join(", or "), fallback);
+        }
+    }
+
+    Ok(())
+}
+
+// ============ Selftest Command ============
+
+fn run_selftest(args: &SelftestArgs) -> Result<(), CliError> {
+    let config = match &args.config {
+        Some(config_path) => Config::load_from_path(config_path).map_err(|e| CliError::InvalidArgs(e.to_string()))?,
+        None => Config::load().unwrap_or_default(),
+    };
+    superbook_pdf::tools::register_overrides(&config.tools);
+    let pipeline_config = if args.config.is_some() {
+        config.to_pipeline_config().with_ocr_optional(true)
+    } else {
+        superbook_pdf::PipelineConfig::default().with_ocr_optional(true)
+    };
+    pipeline_config.validate()?;
+
+    // Work files go to a temporary directory unless they should be kept
+    let temp_dir;
+    let work_dir = match &args.work_dir {
+        Some(dir) => {
+            std::fs::create_dir_all(dir)?;
+            dir.clone()
+        }
+        None => {
+            temp_dir = tempfile::tempdir()?;
+            temp_dir.path().to_path_buf()
+        }
+    };
+
+    println!("superbook-pdf v{} self test ({} pages)", env!("CARGO_PKG_VERSION"), args.pages);
+    let progress = VerboseProgress::new(args.verbose.into());
+    let report = SelfTest::new()
+        .with_config(pipeline_config)
+        .with_pages(args.pages as usize)
+        .run(&work_dir, &progress)?;
+
+    println!();
+    println!("  {:<15} {:>8}  RESULT", "STAGE", "TIME");
+    for stage in &report.stages {
+        let time = stage.seconds.map(|seconds| format!("{:.2}s", seconds)).unwrap_or_default();
+        println!("  {:<15} {:>8}  {}", stage.key, time, stage.outcome);
+    }
+
+    println!();
+    let tools: Vec<String> = report.tools_exercised().iter().map(ToString::to_string).collect();
+    if tools.is_empty() {
+        println!("Tools exercised: none (built-in stages only)");
+    } else {
+        println!("Tools exercised: {}", tools.join(", "));
+    }
+    let hints: Vec<_> = report.stages.iter().filter_map(|stage| stage.hint.as_ref().map(|hint| (stage.key, hint))).collect();
+    if !hints.is_empty() {
+        println!("To run the skipped or degraded stages:");
+        for (key, hint) in hints {
+            println!("  {}: {}", key, hint);
+        }
+    }
+    if let Some(pages) = report.output_pages {
+        println!("Output: {} of {} pages", pages, report.expected_pages);
+    }
+    if args.work_dir.is_some() {
+        println!("Work files: {}", work_dir.display());
+    }
+
+    match report.failure {
+        None => {
+            println!("Self test passed ({:.2}s)", report.elapsed_seconds);
+            Ok(())
+        }
+        Some(failure) => Err(SelftestError::Failed(failure).into()),
+    }
+}
+
+// ============ Diff Command ============
+
+fn run_diff(args: &DiffArgs) -> Result<(), CliError> {
+    for path in [&args.a, &args.b] {
+        if !path.exists() {
+            return Err(CliError::InputNotFound(path.clone()));
+        }
+    }
+    if !args.threshold.is_finite() || args.threshold < 0.0 {
+        return Err(CliError::InvalidArgs(format!("--threshold must be 0 or more, got {}", args.threshold)));
+    }
+
+    let mut diff = PdfDiff::new().with_dpi(args.dpi).with_threshold(args.threshold);
+    if let Some(dir) = &args.heatmap_dir {
+        diff = diff.with_heatmap_dir(dir);
+    }
+    let temp_dir = tempfile::tempdir()?;
+    let report = diff.run(&args.a, &args.b, temp_dir.path())?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| CliError::Other(e.to_string()))?);
+    } else {
+        println!(
+            "Comparing {} ({} pages) with {} ({} pages) at {} DPI",
+            args.a.display(),
+            report.pages_a,
+            args.b.display(),
+            report.pages_b,
+            report.dpi
+        );
+        println!();
+        println!("  {:>5} {:>8} {:>7}  RESULT", "PAGE", "MAD", "SSIM");
+        for page in &report.pages {
+            let mut result = String::new();
+            if page.changed {
+                result.push_str("changed");
+            }
+            if page.size_a != page.size_b {
+                result.push_str(&format!(
+                    " (size {}x{} vs {}x{})",
+                    page.size_a.0, page.size_a.1, page.size_b.0, page.size_b.1
+                ));
+            }
+            if let Some(heatmap) = &page.heatmap {
+                result.push_str(&format!(" heatmap: {}", heatmap.display()));
+            }
+            let line = format!("  {:>5} {:>8.2} {:>7.3}  {}", page.page, page.mean_abs_diff, page.ssim, result.trim_start());
+            println!("{}", line.trim_end());
+        }
+        println!();
+        if report.page_count_mismatch() {
+            let (longer, count) = if report.pages_a > report.pages_b {
+                (&args.a, report.pages_a)
+            } else {
+                (&args.b, report.pages_b)
+            };
+            println!(
+                "Page count differs: {} vs {} (pages {}-{} only in {})",
+                report.pages_a,
+                report.pages_b,
+                report.pages.len() + 1,
+                count,
+                longer.display()
+            );
         }
+        println!(
+            "{} of {} compared pages changed (mean absolute difference above {})",
+            report.changed_pages().len(),
+            report.pages.len(),
+            report.threshold
+        );
+    }
+
+    match report.differences() {
+        None => Ok(()),
+        Some(differences) => Err(differences.into()),
     }
 }
 
 // ============ Cache Info Command ============
 
-fn run_cache_info(args: &CacheInfoArgs) -> Result<(), Box<dyn std::error::Error>> {
+fn run_cache_info(args: &CacheInfoArgs) -> Result<(), CliError> {
     use chrono::{DateTime, Local, TimeZone};
 
     let output_path = &args.output_pdf;
 
     if !output_path.exists() {
-        return Err(format!("Output file not found: {}", output_path.display()).into());
+        return Err(CliError::InputNotFound(output_path.clone()));
+    }
+
+    if args.json {
+        let cache_path = ProcessingCache::cache_path(output_path);
+        let cache = ProcessingCache::load(output_path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => CliError::InputNotFound(cache_path.clone()),
+            _ => CliError::Other(format!("Invalid cache file {}: {}", cache_path.display(), e)),
+        })?;
+        println!("{}", cache.to_json());
+        return Ok(());
     }
 
     match ProcessingCache::load(output_path) {
@@ -597,11 +1598,44 @@ fn run_cache_info(args: &CacheInfoArgs) -> Result<(), Box<dyn std::error::Error>
             );
             println!("  Vertical:    {}", if cache.result.is_vertical { "yes" } else { "no" });
             println!("  Elapsed:     {:.2}s", cache.result.elapsed_seconds);
+            if !cache.result.stage_timings.is_empty() {
+                cache.result.stage_timings.print_breakdown(Lang::default());
+            }
             println!(
                 "  Output size: {} bytes ({:.2} MB)",
                 cache.result.output_size,
                 cache.result.output_size as f64 / 1_048_576.0
             );
+            if cache.result.deduplicated_pages > 0 {
+                println!("  Deduplicated pages: {}", cache.result.deduplicated_pages);
+            }
+            if !cache.result.tone_curve_lut.is_empty() {
+                println!("  Tone curve LUT: {} entries", cache.result.tone_curve_lut.len());
+            }
+            if !cache.result.ocr_confidence.is_empty() {
+                let low_pages = cache
+                    .result
+                    .low_confidence_pages(superbook_pdf::pipeline::DEFAULT_OCR_MIN_CONFIDENCE);
+                println!(
+                    "  Low OCR confidence (<{:.0}%): {}",
+                    superbook_pdf::pipeline::DEFAULT_OCR_MIN_CONFIDENCE,
+                    format_page_list(&low_pages)
+                );
+            }
+            if let Some(quality) = superbook_pdf::quality::QualitySummary::from_pages(
+                &cache.result.page_quality,
+                superbook_pdf::quality::DEFAULT_MIN_SSIM,
+            ) {
+                println!(
+                    "  Quality:     SSIM {:.3} (min {:.3} p{}), PSNR {:.1} dB",
+                    quality.mean_ssim, quality.min_ssim, quality.min_ssim_page, quality.mean_psnr
+                );
+                println!(
+                    "  Low SSIM (<{:.2}): {}",
+                    superbook_pdf::quality::DEFAULT_MIN_SSIM,
+                    format_page_list(&quality.low_ssim_pages)
+                );
+            }
         }
         Err(e) => {
             println!("No cache found for: {}", output_path.display());
@@ -614,9 +1648,82 @@ fn run_cache_info(args: &CacheInfoArgs) -> Result<(), Box<dyn std::error::Error>
     Ok(())
 }
 
+// ============ Rerun Command ============
+
+fn run_rerun(args: &RerunArgs) -> Result<(), CliError> {
+    let output_path = &args.output_pdf;
+    let cache_path = ProcessingCache::cache_path(output_path);
+    let cache = ProcessingCache::load(output_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => CliError::InputNotFound(cache_path.clone()),
+        _ => CliError::Other(format!("Invalid cache file {}: {}", cache_path.display(), e)),
+    })?;
+    let (Some(source), Some(serde_json::Value::Object(options))) = (&cache.source, &cache.options) else {
+        return Err(CliError::InvalidArgs(format!(
+            "{} does not record the source and options; convert the file again",
+            cache_path.display()
+        )));
+    };
+    if !source.exists() {
+        return Err(CliError::InputNotFound(source.clone()));
+    }
+
+    // Settings the cache does not record (tool paths, hooks, limits) come
+    // from the config file; the recorded options and --set go on top
+    let base = match &args.config {
+        Some(config_path) => Config::load_from_path(config_path)
+            .map_err(|e| CliError::InvalidArgs(e.to_string()))?,
+        None => Config::load().unwrap_or_default(),
+    }
+    .to_pipeline_config();
+    let mut config = apply_overrides(&base, options)
+        .map_err(|e| CliError::Other(format!("Invalid options in {}: {}", cache_path.display(), e)))?;
+    if args.ocr {
+        config = config.with_ocr(true);
+    }
+    for spec in &args.set {
+        let fields = parse_overrides(spec).map_err(CliError::InvalidArgs)?;
+        config = apply_overrides(&config, &fields)
+            .map_err(|e| CliError::InvalidArgs(format!("--set {}: {}", spec, e)))?;
+    }
+
+    let pipeline = PdfPipeline::new(config);
+    pipeline.config().validate()?;
+    let pipeline = pipeline.check_ocr_dependencies()?;
+
+    let output_dir = match &args.output {
+        Some(dir) => dir.clone(),
+        None => output_path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new(".")).to_path_buf(),
+    };
+    std::fs::create_dir_all(&output_dir)?;
+
+    let progress = VerboseProgress::new(args.verbose.into());
+    let context = PipelineContext::new(source, 0, 1);
+    let result = pipeline.process_file(&context, &output_dir, &progress)?;
+
+    if result.failed_pages.is_empty() {
+        let options_json = pipeline.config().to_json();
+        if let Ok(digest) = CacheDigest::new(source, &options_json) {
+            let cache = ProcessingCache::new(digest, result.to_cache_result()).with_source(source, &options_json);
+            let _ = cache.save(pipeline.get_output_path(source, &output_dir));
+        }
+    }
+
+    if !args.quiet {
+        println!(
+            "Re-converted {}: {} pages, {:.2}s -> {}",
+            source.display(),
+            result.page_count,
+            result.elapsed_seconds,
+            result.output_path.display()
+        );
+    }
+
+    Ok(())
+}
+
 // ============ Reprocess Command ============
 
-fn run_reprocess(args: &ReprocessArgs) -> Result<(), Box<dyn std::error::Error>> {
+fn run_reprocess(args: &ReprocessArgs) -> Result<(), CliError> {
     let start_time = Instant::now();
     let verbose = args.verbose > 0;
 
@@ -636,7 +1743,7 @@ fn run_reprocess(args: &ReprocessArgs) -> Result<(), Box<dyn std::error::Error>>
         ReprocessState::load(&state_path)?
     } else {
         if args.is_state_file() {
-            return Err(format!("State file not found: {}", state_path.display()).into());
+            return Err(CliError::InputNotFound(state_path));
         }
         // No existing state - need to run initial processing first
         return Err("No processing state found. Please run 'convert' command first to create initial state.".into());
@@ -786,7 +1893,7 @@ fn print_reprocess_status(state: &ReprocessState) {
 
 // ============ Markdown Command ============
 
-fn run_markdown(args: &MarkdownArgs) -> Result<(), Box<dyn std::error::Error>> {
+fn run_markdown(args: &MarkdownArgs) -> Result<(), CliError> {
     use superbook_pdf::markdown::{
         MarkdownConverter, MarkdownOptions, TextDirectionOption,
     };
@@ -795,7 +1902,7 @@ fn run_markdown(args: &MarkdownArgs) -> Result<(), Box<dyn std::error::Error>> {
 
     // Validate input
     if !args.input.exists() {
-        return Err(format!("Input file not found: {}", args.input.display()).into());
+        return Err(CliError::InputNotFound(args.input.clone()));
     }
 
     if !args.quiet {
@@ -807,11 +1914,7 @@ fn run_markdown(args: &MarkdownArgs) -> Result<(), Box<dyn std::error::Error>> {
     }
 
     // Build options
-    let text_direction = match args.text_direction {
-        superbook_pdf::TextDirectionCli::Auto => TextDirectionOption::Auto,
-        superbook_pdf::TextDirectionCli::Horizontal => TextDirectionOption::Horizontal,
-        superbook_pdf::TextDirectionCli::Vertical => TextDirectionOption::Vertical,
-    };
+    let text_direction: TextDirectionOption = args.text_direction.into();
 
     let api_provider = if args.validate {
         Some(match args.api_provider {
@@ -831,6 +1934,9 @@ fn run_markdown(args: &MarkdownArgs) -> Result<(), Box<dyn std::error::Error>> {
         .generate_metadata(args.generate_metadata)
         .validate(args.validate)
         .api_provider_opt(api_provider)
+        .mark_low_confidence(args.mark_low_confidence)
+        .exclude_running_heads(args.exclude_running_heads)
+        .dehyphenate(args.dehyphenate)
         .build();
 
     // Create converter and run
@@ -854,6 +1960,10 @@ fn run_markdown(args: &MarkdownArgs) -> Result<(), Box<dyn std::error::Error>> {
             println!("Metadata:         {}", meta_path.display());
         }
 
+        if !result.running_heads.is_empty() {
+            println!("Running heads:    {}", result.running_heads.len());
+        }
+
         if let Some(validation) = &result.validation {
             println!();
             println!("Validation:");
@@ -871,14 +1981,115 @@ fn run_markdown(args: &MarkdownArgs) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+// ============ Compare Options Command ============
+
+fn run_compare_options(args: &CompareOptionsArgs) -> Result<(), CliError> {
+    if !args.input.exists() {
+        return Err(CliError::InputNotFound(args.input.clone()));
+    }
+
+    let base = match &args.config {
+        Some(config_path) => Config::load_from_path(config_path)
+            .map_err(|e| CliError::InvalidArgs(e.to_string()))?,
+        None => Config::load().unwrap_or_default(),
+    }
+    .to_pipeline_config();
+
+    let profiles = args
+        .profiles
+        .iter()
+        .map(|spec| CompareProfile::parse(spec, &base))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Intermediate images go to a temp dir unless the user wants to keep them
+    let temp_dir;
+    let work_dir = match &args.work_dir {
+        Some(dir) => dir.clone(),
+        None => {
+            temp_dir = tempfile::tempdir()?;
+            temp_dir.path().to_path_buf()
+        }
+    };
+
+    println!("Comparing {} profiles on page {} of {}", profiles.len(), args.page, args.input.display());
+    let progress = VerboseProgress::new(u32::from(args.verbose));
+    let result = OptionComparer::new()
+        .with_cell_height(args.cell_height)
+        .with_columns(args.columns)
+        .run(
+            &args.input,
+            args.page as usize - 1,
+            &profiles,
+            &work_dir,
+            &args.output,
+            &progress,
+        )?;
+
+    if args.work_dir.is_some() {
+        for (name, path) in &result.pages {
+            println!("  {}: {}", name, path.display());
+        }
+    }
+    println!("Comparison grid: {}", result.output_path.display());
+
+    Ok(())
+}
+
+// ============ Assemble Command ============
+
+fn run_assemble(args: &AssembleArgs) -> Result<(), CliError> {
+    if !args.input.is_dir() {
+        return Err(CliError::InputNotFound(args.input.clone()));
+    }
+
+    let file_config = match &args.config {
+        Some(config_path) => Config::load_from_path(config_path)
+            .map_err(|e| CliError::InvalidArgs(e.to_string()))?,
+        None => Config::load().unwrap_or_default(),
+    };
+    let mut config = file_config.to_pipeline_config();
+    if let Some(dpi) = args.dpi {
+        config = config.with_dpi(dpi);
+    }
+    if let Some(quality) = args.jpeg_quality {
+        config.jpeg_quality = quality;
+    }
+    if args.mixed_color {
+        config = config.with_mixed_color(true);
+    }
+    if args.deterministic {
+        config = config.with_deterministic(true);
+    }
+
+    let progress = VerboseProgress::new(u32::from(args.verbose));
+    let result = PdfPipeline::new(config).assemble_with_progress(
+        &args.input,
+        args.ocr_dir.as_deref(),
+        &args.output,
+        &progress,
+    )?;
+
+    println!(
+        "Assembled {} pages into {} ({})",
+        result.page_count,
+        result.output_path.display(),
+        superbook_pdf::format_file_size(result.output_size)
+    );
+
+    Ok(())
+}
+
 // ============ Serve Command (Web Server) ============
 
 #[cfg(feature = "web")]
-fn run_serve(args: &ServeArgs) -> Result<(), Box<dyn std::error::Error>> {
+fn run_serve(args: &ServeArgs) -> Result<(), CliError> {
     let mut config = ServerConfig::default()
         .with_port(args.port)
         .with_bind(&args.bind)
-        .with_upload_limit(args.upload_limit * 1024 * 1024);
+        .with_upload_limit(args.upload_limit * 1024 * 1024)
+        .with_retention_days(args.retention_days)
+        .with_max_queue_depth(args.max_queue_depth)
+        .with_gpu_wait(args.gpu_wait);
 
     // Configure CORS
     if args.no_cors {
@@ -893,7 +2104,7 @@ fn run_serve(args: &ServeArgs) -> Result<(), Box<dyn std::error::Error>> {
     let rt = tokio::runtime::Runtime::new()?;
     rt.block_on(async {
         let server = WebServer::with_config(config);
-        server.run().await.map_err(|e| e.to_string())
+        server.run().await.map_err(|e| CliError::Other(e.to_string()))
     })?;
 
     Ok(())
@@ -952,4 +2163,18 @@ mod tests {
         assert!(handler_3.should_show_progress());
         assert!(handler_3.should_show_debug());
     }
+
+    #[test]
+    fn test_progress_labels_current_file() {
+        use superbook_pdf::{PipelineContext, ProgressCallback};
+
+        let handler = VerboseProgress::new(1);
+        assert_eq!(handler.file_label(), "");
+
+        handler.on_file_start(&PipelineContext::single("book.pdf"));
+        assert_eq!(handler.file_label(), "");
+
+        handler.on_file_start(&PipelineContext::new("vol2.pdf", 1, 3));
+        assert_eq!(handler.file_label(), "[2/3] ");
+    }
 }
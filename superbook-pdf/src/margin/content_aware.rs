@@ -11,16 +11,31 @@
 //!
 //! # Algorithm
 //!
-//! 1. Apply Otsu thresholding for optimal binarization
+//! 1. Apply Otsu thresholding for optimal binarization (content is the darker
+//!    side, or the lighter side on inverted pages), or an adaptive threshold
+//!    ([`crate::binarize`]) for unevenly lit pages when `adaptive` is set
 //! 2. Use connected component analysis to detect text regions
 //! 3. Filter noise using size and aspect ratio constraints
 //! 4. Calculate safe trim positions with configurable safety buffers
+//!
+//! # Safety buffer
+//!
+//! The buffer kept around detected content on each axis is the largest of
+//!
+//! - `safety_buffer_percent` of the image dimension,
+//! - `min_safety_buffer` pixels, and
+//! - `min_safety_buffer_mm` millimetres at the image `dpi`,
+//!
+//! so a physical margin survives at any resolution (ascenders/descenders
+//! are not clipped at 600+ DPI) while the pixel floor can stay small enough
+//! not to over-pad low-resolution scans.
 
 use image::{GrayImage, Luma};
 use std::collections::VecDeque;
 use std::path::Path;
 
 use super::types::{MarginError, Result};
+use super::Polarity;
 use crate::deskew::ImageProcDeskewer;
 
 // ============================================================
@@ -37,7 +52,16 @@ const DEFAULT_MAX_CHAR_SIZE: u32 = 500;
 const DEFAULT_SAFETY_BUFFER_PERCENT: f32 = 0.5;
 
 /// Minimum safety buffer in pixels
-const MIN_SAFETY_BUFFER_PIXELS: u32 = 10;
+const MIN_SAFETY_BUFFER_PIXELS: u32 = 4;
+
+/// Default physical safety buffer floor in millimetres (about 12 px at 300 DPI)
+const DEFAULT_MIN_SAFETY_BUFFER_MM: f32 = 1.0;
+
+/// Resolution assumed for the millimetre floor when none is set
+const DEFAULT_DPI: u32 = 300;
+
+/// Millimetres per inch
+const MM_PER_INCH: f32 = 25.4;
 
 /// Maximum aspect ratio for valid text components (height/width or width/height)
 const MAX_COMPONENT_ASPECT_RATIO: f32 = 20.0;
@@ -64,11 +88,23 @@ pub struct ContentAwareOptions {
     /// Minimum safety buffer in pixels
     pub min_safety_buffer: u32,
 
+    /// Minimum safety buffer in millimetres, resolved against `dpi`
+    pub min_safety_buffer_mm: f32,
+
+    /// Resolution of the analyzed image (for `min_safety_buffer_mm`)
+    pub dpi: u32,
+
     /// Enable aggressive trimming (may clip text)
     pub aggressive_trim: bool,
 
     /// Custom Otsu threshold (None = auto-detect)
     pub custom_threshold: Option<u8>,
+
+    /// Local threshold instead of the global one (shadowed or unevenly lit pages)
+    pub adaptive: Option<crate::binarize::AdaptiveMethod>,
+
+    /// Page polarity (Auto = decide per page)
+    pub polarity: Polarity,
 }
 
 impl Default for ContentAwareOptions {
@@ -78,8 +114,12 @@ impl Default for ContentAwareOptions {
             max_char_size: DEFAULT_MAX_CHAR_SIZE,
             safety_buffer_percent: DEFAULT_SAFETY_BUFFER_PERCENT,
             min_safety_buffer: MIN_SAFETY_BUFFER_PIXELS,
+            min_safety_buffer_mm: DEFAULT_MIN_SAFETY_BUFFER_MM,
+            dpi: DEFAULT_DPI,
             aggressive_trim: false,
             custom_threshold: None,
+            adaptive: None,
+            polarity: Polarity::Auto,
         }
     }
 }
@@ -95,6 +135,7 @@ impl ContentAwareOptions {
         Self {
             safety_buffer_percent: 0.1,
             min_safety_buffer: 2,
+            min_safety_buffer_mm: 0.25,
             aggressive_trim: true,
             ..Default::default()
         }
@@ -105,10 +146,19 @@ impl ContentAwareOptions {
         Self {
             safety_buffer_percent: 1.0,
             min_safety_buffer: 20,
+            min_safety_buffer_mm: 2.0,
             aggressive_trim: false,
             ..Default::default()
         }
     }
+
+    /// Safety buffer in pixels for an axis of `dimension` pixels:
+    /// `max(percent of dimension, min_safety_buffer, min_safety_buffer_mm at dpi)`
+    pub fn safety_buffer(&self, dimension: u32) -> u32 {
+        let percent = (dimension as f32 * self.safety_buffer_percent / 100.0) as u32;
+        let physical = (self.min_safety_buffer_mm * self.dpi as f32 / MM_PER_INCH).ceil() as u32;
+        percent.max(self.min_safety_buffer).max(physical)
+    }
 }
 
 /// Builder for ContentAwareOptions
@@ -146,6 +196,20 @@ impl ContentAwareOptionsBuilder {
         self
     }
 
+    /// Set minimum safety buffer in millimetres (negative values become 0)
+    #[must_use]
+    pub fn min_safety_buffer_mm(mut self, mm: f32) -> Self {
+        self.options.min_safety_buffer_mm = mm.max(0.0);
+        self
+    }
+
+    /// Set the image resolution used for the millimetre buffer (minimum 1)
+    #[must_use]
+    pub fn dpi(mut self, dpi: u32) -> Self {
+        self.options.dpi = dpi.max(1);
+        self
+    }
+
     /// Enable aggressive trimming
     #[must_use]
     pub fn aggressive_trim(mut self, aggressive: bool) -> Self {
@@ -160,6 +224,20 @@ impl ContentAwareOptionsBuilder {
         self
     }
 
+    /// Set the adaptive threshold method (None = global threshold)
+    #[must_use]
+    pub fn adaptive(mut self, method: Option<crate::binarize::AdaptiveMethod>) -> Self {
+        self.options.adaptive = method;
+        self
+    }
+
+    /// Set page polarity
+    #[must_use]
+    pub fn polarity(mut self, polarity: Polarity) -> Self {
+        self.options.polarity = polarity;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> ContentAwareOptions {
@@ -264,12 +342,12 @@ pub struct ContentRect {
 
 /// Connected component (bounding box)
 #[derive(Debug, Clone, Copy)]
-struct ConnectedComponent {
-    min_x: u32,
-    min_y: u32,
-    max_x: u32,
-    max_y: u32,
-    pixel_count: u32,
+pub struct ConnectedComponent {
+    pub min_x: u32,
+    pub min_y: u32,
+    pub max_x: u32,
+    pub max_y: u32,
+    pub pixel_count: u32,
 }
 
 impl ConnectedComponent {
@@ -342,7 +420,11 @@ impl ContentAwareBoundaryDetector {
             .custom_threshold
             .unwrap_or_else(|| ImageProcDeskewer::otsu_threshold(gray));
 
-        let binary = Self::binarize_for_content(gray, threshold);
+        let inverted = options.polarity.resolve(gray).is_inverted();
+        let binary = match options.adaptive {
+            Some(method) => Self::binarize_adaptive(gray, method, inverted),
+            None => Self::binarize_for_content(gray, threshold, inverted),
+        };
 
         // Step 2: Find connected components
         let components = Self::find_connected_components(&binary);
@@ -364,22 +446,39 @@ impl ContentAwareBoundaryDetector {
         Ok(boundaries)
     }
 
-    /// Binarize image for content detection (invert so text is white)
-    fn binarize_for_content(gray: &GrayImage, threshold: u8) -> GrayImage {
+    /// Binarize image for content detection (content becomes white)
+    ///
+    /// Normal pages: dark pixels are content. Inverted pages: light pixels are.
+    fn binarize_for_content(gray: &GrayImage, threshold: u8, inverted: bool) -> GrayImage {
         let (width, height) = gray.dimensions();
         let mut binary = GrayImage::new(width, height);
 
         for (x, y, pixel) in gray.enumerate_pixels() {
-            // Invert: dark pixels (text) become white (255)
-            let value = if pixel.0[0] < threshold { 255 } else { 0 };
+            let is_content = if inverted {
+                pixel.0[0] > threshold
+            } else {
+                pixel.0[0] < threshold
+            };
+            let value = if is_content { 255 } else { 0 };
             binary.put_pixel(x, y, Luma([value]));
         }
 
         binary
     }
 
-    /// Find connected components using flood-fill algorithm
-    fn find_connected_components(binary: &GrayImage) -> Vec<ConnectedComponent> {
+    /// Binarize image for content detection with a local threshold (content becomes white)
+    fn binarize_adaptive(gray: &GrayImage, method: crate::binarize::AdaptiveMethod, inverted: bool) -> GrayImage {
+        let mut source = gray.clone();
+        if inverted {
+            image::imageops::invert(&mut source);
+        }
+        let mut binary = crate::binarize::adaptive(&source, method);
+        image::imageops::invert(&mut binary);
+        binary
+    }
+
+    /// Find 8-connected components of non-zero pixels using flood fill
+    pub fn find_connected_components(binary: &GrayImage) -> Vec<ConnectedComponent> {
         let (width, height) = binary.dimensions();
         let mut visited = vec![false; (width * height) as usize];
         let mut components = Vec::new();
@@ -466,7 +565,7 @@ impl ContentAwareBoundaryDetector {
 
         // Aspect ratio constraint
         let aspect = component.aspect_ratio();
-        if aspect < MIN_COMPONENT_ASPECT_RATIO || aspect > MAX_COMPONENT_ASPECT_RATIO {
+        if !(MIN_COMPONENT_ASPECT_RATIO..=MAX_COMPONENT_ASPECT_RATIO).contains(&aspect) {
             return false;
         }
 
@@ -517,10 +616,8 @@ impl ContentAwareBoundaryDetector {
         }
 
         // Calculate safety buffers
-        let buffer_x = ((width as f32 * options.safety_buffer_percent / 100.0) as u32)
-            .max(options.min_safety_buffer);
-        let buffer_y = ((height as f32 * options.safety_buffer_percent / 100.0) as u32)
-            .max(options.min_safety_buffer);
+        let buffer_x = options.safety_buffer(width);
+        let buffer_y = options.safety_buffer(height);
 
         // Calculate safe positions (with buffer)
         let safe_top = min_y.saturating_sub(buffer_y);
@@ -674,6 +771,31 @@ mod tests {
         assert!(opts.aggressive_trim);
     }
 
+    #[test]
+    fn test_safety_buffer_three_way_max() {
+        let options = |dpi| {
+            ContentAwareOptions::builder()
+                .safety_buffer_percent(0.5)
+                .min_safety_buffer(4)
+                .min_safety_buffer_mm(1.0)
+                .dpi(dpi)
+                .build()
+        };
+
+        // 300 DPI A4 width: 1mm (12px) beats 0.5% of 2480 (12px) and 4px
+        assert_eq!(options(300).safety_buffer(2480), 12);
+        // 1200 DPI: the physical floor (48px) protects ascenders/descenders
+        assert_eq!(options(1200).safety_buffer(2000), 48);
+        // Large page: the percentage dominates
+        assert_eq!(options(300).safety_buffer(10000), 50);
+        // 72 DPI: the pixel floor applies, no longer a fixed 10px (3.5mm)
+        assert_eq!(options(72).safety_buffer(600), 4);
+
+        let no_mm = ContentAwareOptions::builder().min_safety_buffer_mm(-1.0).min_safety_buffer(7).build();
+        assert_eq!(no_mm.min_safety_buffer_mm, 0.0);
+        assert_eq!(no_mm.safety_buffer(100), 7);
+    }
+
     #[test]
     fn test_content_aware_options_presets() {
         let aggressive = ContentAwareOptions::aggressive();
@@ -713,7 +835,7 @@ mod tests {
         // Light pixel (background)
         gray.put_pixel(3, 3, Luma([200]));
 
-        let binary = ContentAwareBoundaryDetector::binarize_for_content(&gray, 128);
+        let binary = ContentAwareBoundaryDetector::binarize_for_content(&gray, 128, false);
 
         // Dark pixel should become white (content)
         assert_eq!(binary.get_pixel(5, 5).0[0], 255);
@@ -947,6 +1069,23 @@ mod tests {
         assert!(matches!(result, Err(MarginError::NoContentDetected)));
     }
 
+    #[test]
+    fn test_adaptive_threshold_on_gradient_lit_page() {
+        let (page, _) = crate::binarize::tests::gradient_lit_page();
+
+        // The global threshold takes the shadowed side for content
+        let global = ContentAwareBoundaryDetector::detect_from_image(&page, &ContentAwareOptions::default()).unwrap();
+        assert!(global.right.aggressive_position >= 290);
+
+        let options = ContentAwareOptions::builder()
+            .adaptive(Some(crate::binarize::AdaptiveMethod::default()))
+            .build();
+        let boundaries = ContentAwareBoundaryDetector::detect_from_image(&page, &options).unwrap();
+        assert_eq!(boundaries.right.aggressive_position, 263);
+        assert_eq!(boundaries.top.aggressive_position, 40);
+        assert_eq!(boundaries.bottom.aggressive_position, 165);
+    }
+
     #[test]
     fn test_average_confidence() {
         let boundaries = ContentBoundaries {
@@ -25,8 +25,10 @@ impl ImageMarginDetector {
         let gray = img.to_luma8();
         let (width, height) = img.dimensions();
 
-        let is_background =
-            |pixel: &image::Luma<u8>| -> bool { pixel.0[0] >= options.background_threshold };
+        let inverted = options.polarity.resolve(&gray).is_inverted();
+        let is_background = |pixel: &image::Luma<u8>| -> bool {
+            Self::is_background_value(pixel.0[0], options.background_threshold, inverted)
+        };
 
         // Detect margins based on mode
         let (top, bottom, left, right) = match options.detection_mode {
@@ -34,7 +36,9 @@ impl ImageMarginDetector {
                 Self::detect_background_margins(&gray, is_background, options)
             }
             ContentDetectionMode::EdgeDetection => Self::detect_edge_margins(&gray, options),
-            ContentDetectionMode::Histogram => Self::detect_histogram_margins(&gray, options),
+            ContentDetectionMode::Histogram => {
+                Self::detect_histogram_margins(&gray, options, inverted)
+            }
             ContentDetectionMode::Combined => {
                 // Average of background and edge detection
                 let (t1, b1, l1, r1) =
@@ -44,11 +48,12 @@ impl ImageMarginDetector {
             }
         };
 
+        let min_margin = options.effective_min_margin(width, height);
         let margins = Margins {
-            top: top.max(options.min_margin),
-            bottom: bottom.max(options.min_margin),
-            left: left.max(options.min_margin),
-            right: right.max(options.min_margin),
+            top: top.max(min_margin),
+            bottom: bottom.max(min_margin),
+            left: left.max(min_margin),
+            right: right.max(min_margin),
         };
 
         let content_width = width.saturating_sub(margins.total_horizontal());
@@ -73,6 +78,15 @@ impl ImageMarginDetector {
         })
     }
 
+    /// Background test; on inverted pages the threshold applies to the inverted value
+    fn is_background_value(value: u8, threshold: u8, inverted: bool) -> bool {
+        if inverted {
+            255 - value >= threshold
+        } else {
+            value >= threshold
+        }
+    }
+
     /// Background color based margin detection
     fn detect_background_margins<F>(
         gray: &GrayImage,
@@ -200,11 +214,15 @@ impl ImageMarginDetector {
     }
 
     /// Histogram based margin detection
-    fn detect_histogram_margins(gray: &GrayImage, options: &MarginOptions) -> (u32, u32, u32, u32) {
+    fn detect_histogram_margins(
+        gray: &GrayImage,
+        options: &MarginOptions,
+        inverted: bool,
+    ) -> (u32, u32, u32, u32) {
         // For now, delegate to background detection with adjusted threshold
-        let is_background = |pixel: &image::Luma<u8>| -> bool {
-            pixel.0[0] >= options.background_threshold.saturating_sub(10)
-        };
+        let threshold = options.background_threshold.saturating_sub(10);
+        let is_background =
+            |pixel: &image::Luma<u8>| -> bool { Self::is_background_value(pixel.0[0], threshold, inverted) };
         Self::detect_background_margins(gray, is_background, options)
     }
 
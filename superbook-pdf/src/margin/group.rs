@@ -4,8 +4,12 @@
 //! across multiple pages.
 
 use super::types::{ContentRect, MarginError, Result};
+use image::GrayImage;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // ============================================================
 // Constants
@@ -24,6 +28,173 @@ const MIN_INLIER_COUNT: usize = 3;
 // Data Structures
 // ============================================================
 
+/// A page image for bounding box detection: a file to decode, or an
+/// image the caller already has in memory (no second decode)
+pub trait PageImageSource: Sync {
+    /// Grayscale view of the page
+    fn luma(&self) -> Result<Cow<'_, GrayImage>>;
+}
+
+impl PageImageSource for Path {
+    fn luma(&self) -> Result<Cow<'_, GrayImage>> {
+        if !self.exists() {
+            return Err(MarginError::ImageNotFound(self.to_path_buf()));
+        }
+        let img = image::open(self).map_err(|e| MarginError::InvalidImage(e.to_string()))?;
+        Ok(Cow::Owned(img.into_luma8()))
+    }
+}
+
+impl PageImageSource for PathBuf {
+    fn luma(&self) -> Result<Cow<'_, GrayImage>> {
+        self.as_path().luma()
+    }
+}
+
+impl PageImageSource for image::DynamicImage {
+    fn luma(&self) -> Result<Cow<'_, GrayImage>> {
+        Ok(match self {
+            image::DynamicImage::ImageLuma8(gray) => Cow::Borrowed(gray),
+            img => Cow::Owned(img.to_luma8()),
+        })
+    }
+}
+
+impl PageImageSource for GrayImage {
+    fn luma(&self) -> Result<Cow<'_, GrayImage>> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+/// How inlier page edges are combined into the group crop region
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CropAggregation {
+    /// Median of each edge (uniform crop, may clip the widest pages)
+    #[default]
+    Median,
+    /// Minimum left/top and maximum right/bottom (union; never clips an inlier)
+    Extremes,
+    /// Edge coverage percentile (0-100): left/top take the `100 - p`th
+    /// percentile and right/bottom the `p`th, so 50 matches the median,
+    /// 100 the extremes and values below 50 crop more aggressively
+    Percentile(f64),
+}
+
+/// Which pages of the book a run processes
+///
+/// When the odd and even sides were scanned in separate passes, each side
+/// can be converted on its own with different settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageParity {
+    /// Every page
+    #[default]
+    All,
+    /// Odd pages only (1, 3, 5, ...)
+    Odd,
+    /// Even pages only (2, 4, 6, ...)
+    Even,
+}
+
+impl PageParity {
+    /// Check if every page is processed
+    pub fn is_all(&self) -> bool {
+        *self == Self::All
+    }
+
+    /// Check if a source page (1-indexed) is processed
+    pub fn includes(self, page_number: usize) -> bool {
+        match self {
+            Self::All => true,
+            Self::Odd => page_number % 2 == 1,
+            Self::Even => page_number % 2 == 0,
+        }
+    }
+
+    /// Check if the `index`-th processed page (0-indexed) is an odd page
+    pub fn is_odd_at(self, index: usize) -> bool {
+        match self {
+            Self::All => index % 2 == 0,
+            Self::Odd => true,
+            Self::Even => false,
+        }
+    }
+
+    /// Output name suffix (`None` for every page)
+    pub fn suffix(self) -> Option<&'static str> {
+        match self {
+            Self::All => None,
+            Self::Odd => Some("odd"),
+            Self::Even => Some("even"),
+        }
+    }
+}
+
+/// Outlier rejection parameters for group crop analysis
+///
+/// A larger `tukey_k` widens the fence so fewer pages are treated as
+/// outliers (varied layouts such as poetry); a smaller one tightens it for
+/// very uniform books. When fewer than `min_inlier_count` pages or less than
+/// `min_inlier_ratio` of the valid pages survive, all pages are used instead.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupCropParams {
+    /// Tukey fence multiplier (outlier if outside Q1 - k*IQR .. Q3 + k*IQR)
+    pub tukey_k: f64,
+    /// Minimum inlier ratio before falling back to all data
+    pub min_inlier_ratio: f64,
+    /// Minimum inlier count before falling back to all data
+    pub min_inlier_count: usize,
+    /// Edge aggregation over the inlier pages
+    pub aggregation: CropAggregation,
+}
+
+impl Default for GroupCropParams {
+    fn default() -> Self {
+        Self {
+            tukey_k: TUKEY_K,
+            min_inlier_ratio: MIN_INLIER_RATIO,
+            min_inlier_count: MIN_INLIER_COUNT,
+            aggregation: CropAggregation::Median,
+        }
+    }
+}
+
+impl GroupCropParams {
+    /// Builder pattern: set the Tukey fence multiplier (clamped to >= 0)
+    pub fn with_tukey_k(mut self, k: f64) -> Self {
+        self.tukey_k = k.max(0.0);
+        self
+    }
+
+    /// Builder pattern: set the minimum inlier ratio (clamped to 0.0-1.0)
+    pub fn with_min_inlier_ratio(mut self, ratio: f64) -> Self {
+        self.min_inlier_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Builder pattern: set the minimum inlier count
+    pub fn with_min_inlier_count(mut self, count: usize) -> Self {
+        self.min_inlier_count = count;
+        self
+    }
+
+    /// Builder pattern: set edge aggregation (percentiles clamped to 0-100)
+    pub fn with_aggregation(mut self, aggregation: CropAggregation) -> Self {
+        self.aggregation = match aggregation {
+            CropAggregation::Percentile(p) => CropAggregation::Percentile(p.clamp(0.0, 100.0)),
+            other => other,
+        };
+        self
+    }
+
+    /// Whether all parameters are at their defaults
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 /// Bounding box with page information
 #[derive(Debug, Clone)]
 pub struct PageBoundingBox {
@@ -127,10 +298,13 @@ impl GroupCropAnalyzer {
     /// Algorithm:
     /// 1. Collect bounding boxes from all pages
     /// 2. Calculate Q1, Q3, IQR for each edge (left, top, right, bottom)
-    /// 3. Apply Tukey fence (k=1.5) to identify outliers
+    /// 3. Apply Tukey fence (`params.tukey_k`, default 1.5) to identify outliers
     /// 4. Remove pages where ANY edge is an outlier
-    /// 5. Calculate median of inliers for final crop region
-    pub fn decide_group_crop_region(bounding_boxes: &[PageBoundingBox]) -> GroupCropRegion {
+    /// 5. Aggregate inlier edges (`params.aggregation`, default median) for the final crop region
+    pub fn decide_group_crop_region(
+        bounding_boxes: &[PageBoundingBox],
+        params: &GroupCropParams,
+    ) -> GroupCropRegion {
         // Validation
         if bounding_boxes.is_empty() {
             return GroupCropRegion::default();
@@ -164,33 +338,34 @@ impl GroupCropAnalyzer {
         let inliers: Vec<&PageBoundingBox> = valid
             .iter()
             .filter(|b| {
-                !Self::is_outlier(b.bounding_box.x, q1_l, q3_l, iqr_l)
-                    && !Self::is_outlier(b.bounding_box.y, q1_t, q3_t, iqr_t)
-                    && !Self::is_outlier(b.right(), q1_r, q3_r, iqr_r)
-                    && !Self::is_outlier(b.bottom(), q1_b, q3_b, iqr_b)
+                let k = params.tukey_k;
+                !Self::is_outlier(b.bounding_box.x, q1_l, q3_l, iqr_l, k)
+                    && !Self::is_outlier(b.bounding_box.y, q1_t, q3_t, iqr_t, k)
+                    && !Self::is_outlier(b.right(), q1_r, q3_r, iqr_r, k)
+                    && !Self::is_outlier(b.bottom(), q1_b, q3_b, iqr_b, k)
             })
             .copied()
             .collect();
 
         // If too few inliers, fall back to using all valid data
-        let use_inliers = if inliers.len() >= MIN_INLIER_COUNT
-            && inliers.len() as f64 >= valid.len() as f64 * MIN_INLIER_RATIO
+        let use_inliers = if inliers.len() >= params.min_inlier_count
+            && inliers.len() as f64 >= valid.len() as f64 * params.min_inlier_ratio
         {
             inliers
         } else {
             valid
         };
 
-        // Calculate median for final crop region
+        // Aggregate inlier edges for final crop region
         let lefts: Vec<u32> = use_inliers.iter().map(|b| b.bounding_box.x).collect();
         let tops: Vec<u32> = use_inliers.iter().map(|b| b.bounding_box.y).collect();
         let rights: Vec<u32> = use_inliers.iter().map(|b| b.right()).collect();
         let bottoms: Vec<u32> = use_inliers.iter().map(|b| b.bottom()).collect();
 
-        let left = Self::median_u32(&lefts);
-        let top = Self::median_u32(&tops);
-        let right = Self::median_u32(&rights);
-        let bottom = Self::median_u32(&bottoms);
+        let left = Self::aggregate_edge(&lefts, params.aggregation, false);
+        let top = Self::aggregate_edge(&tops, params.aggregation, false);
+        let right = Self::aggregate_edge(&rights, params.aggregation, true);
+        let bottom = Self::aggregate_edge(&bottoms, params.aggregation, true);
 
         // Calculate width and height
         let width = right.saturating_sub(left);
@@ -207,8 +382,26 @@ impl GroupCropAnalyzer {
     }
 
     /// Unify crop regions for odd and even page groups
-    pub fn unify_odd_even_regions(bounding_boxes: &[PageBoundingBox]) -> UnifiedCropRegions {
-        Self::unify_and_expand_regions(bounding_boxes, 0, 0, 0)
+    pub fn unify_odd_even_regions(
+        bounding_boxes: &[PageBoundingBox],
+        params: &GroupCropParams,
+    ) -> UnifiedCropRegions {
+        Self::unify_and_expand_regions(bounding_boxes, params, 0, 0, 0)
+    }
+
+    /// Horizontal offset of the odd pages' content relative to the even
+    /// pages' (pixels, positive when odd pages sit further right)
+    ///
+    /// Compares the centers of the odd and even group crop regions, so a
+    /// constant front/back shift of a duplex feeder shows up even when no
+    /// page numbers can be read. `None` unless both sides have content.
+    pub fn side_shift(bounding_boxes: &[PageBoundingBox], params: &GroupCropParams) -> Option<i32> {
+        let unified = Self::unify_odd_even_regions(bounding_boxes, params);
+        if !unified.odd_region.is_valid() || !unified.even_region.is_valid() {
+            return None;
+        }
+        let doubled_center = |region: &GroupCropRegion| 2 * region.left as i64 + region.width as i64;
+        Some(((doubled_center(&unified.odd_region) - doubled_center(&unified.even_region)) / 2) as i32)
     }
 
     /// Unify crop regions with Y coordinate unification, margin expansion, and size limits
@@ -221,6 +414,7 @@ impl GroupCropAnalyzer {
     /// 5. Clamp to image bounds
     pub fn unify_and_expand_regions(
         bounding_boxes: &[PageBoundingBox],
+        params: &GroupCropParams,
         margin_percent: u32,
         max_width: u32,
         max_height: u32,
@@ -238,8 +432,8 @@ impl GroupCropAnalyzer {
             .collect();
 
         // Calculate crop region for each group
-        let mut odd_region = Self::decide_group_crop_region(&odd_boxes);
-        let mut even_region = Self::decide_group_crop_region(&even_boxes);
+        let mut odd_region = Self::decide_group_crop_region(&odd_boxes, params);
+        let mut even_region = Self::decide_group_crop_region(&even_boxes, params);
 
         // Unify Y coordinates (min top, max bottom) for consistent vertical positioning
         if odd_region.is_valid() && even_region.is_valid() {
@@ -340,10 +534,10 @@ impl GroupCropAnalyzer {
         (q1, q3, iqr)
     }
 
-    /// Check if a value is an outlier using Tukey fence
-    fn is_outlier(value: u32, q1: f64, q3: f64, iqr: f64) -> bool {
+    /// Check if a value is an outlier using Tukey fence with multiplier `k`
+    fn is_outlier(value: u32, q1: f64, q3: f64, iqr: f64, k: f64) -> bool {
         let v = value as f64;
-        v < q1 - TUKEY_K * iqr || v > q3 + TUKEY_K * iqr
+        v < q1 - k * iqr || v > q3 + k * iqr
     }
 
     /// Calculate percentile with linear interpolation
@@ -368,6 +562,28 @@ impl GroupCropAnalyzer {
         }
     }
 
+    /// Combine one edge across pages; `far_edge` is true for right/bottom,
+    /// whose outer tail is the high end (percentiles round outward)
+    fn aggregate_edge(values: &[u32], aggregation: CropAggregation, far_edge: bool) -> u32 {
+        match aggregation {
+            CropAggregation::Median => Self::median_u32(values),
+            CropAggregation::Extremes => {
+                let extreme = if far_edge { values.iter().max() } else { values.iter().min() };
+                extreme.copied().unwrap_or(0)
+            }
+            CropAggregation::Percentile(p) => {
+                let mut sorted = values.to_vec();
+                sorted.sort_unstable();
+                let coverage = p.clamp(0.0, 100.0) / 100.0;
+                if far_edge {
+                    Self::percentile(&sorted, coverage).ceil() as u32
+                } else {
+                    Self::percentile(&sorted, 1.0 - coverage).floor() as u32
+                }
+            }
+        }
+    }
+
     /// Calculate median of u32 values
     fn median_u32(values: &[u32]) -> u32 {
         if values.is_empty() {
@@ -393,12 +609,12 @@ impl GroupCropAnalyzer {
         image_path: &Path,
         background_threshold: u8,
     ) -> Result<ContentRect> {
-        if !image_path.exists() {
-            return Err(MarginError::ImageNotFound(image_path.to_path_buf()));
-        }
+        let gray = image_path.luma()?;
+        Self::detect_bounding_box_in(&gray, background_threshold)
+    }
 
-        let img = image::open(image_path).map_err(|e| MarginError::InvalidImage(e.to_string()))?;
-        let gray = img.to_luma8();
+    /// Detect the content bounding box of a decoded grayscale page
+    pub fn detect_bounding_box_in(gray: &GrayImage, background_threshold: u8) -> Result<ContentRect> {
         let (width, height) = gray.dimensions();
 
         let mut min_x = width;
@@ -437,16 +653,50 @@ impl GroupCropAnalyzer {
         image_paths: &[PathBuf],
         background_threshold: u8,
     ) -> Vec<PageBoundingBox> {
-        image_paths
-            .par_iter()
-            .enumerate()
-            .filter_map(|(idx, path)| {
-                match Self::detect_text_bounding_box(path, background_threshold) {
-                    Ok(bbox) => Some(PageBoundingBox::new(idx + 1, bbox)),
-                    Err(_) => None,
-                }
-            })
-            .collect()
+        Self::detect_all_bounding_boxes_with(image_paths, background_threshold, 0, None::<&fn(usize, usize)>)
+    }
+
+    /// Detect bounding boxes for all pages on `threads` threads (0 = the
+    /// current rayon pool), calling `progress(done, total)` as pages finish
+    ///
+    /// Pages may be files or images already decoded ([`PageImageSource`]).
+    /// Pages without content or that fail to decode are left out; boxes
+    /// keep their 1-based page number and come back in page order.
+    pub fn detect_all_bounding_boxes_with<S, P>(
+        pages: &[S],
+        background_threshold: u8,
+        threads: usize,
+        progress: Option<&P>,
+    ) -> Vec<PageBoundingBox>
+    where
+        S: PageImageSource,
+        P: Fn(usize, usize) + Sync,
+    {
+        let total = pages.len();
+        let done = AtomicUsize::new(0);
+        let detect = || {
+            pages
+                .par_iter()
+                .enumerate()
+                .filter_map(|(idx, page)| {
+                    let bbox = page
+                        .luma()
+                        .and_then(|gray| Self::detect_bounding_box_in(&gray, background_threshold));
+                    if let Some(callback) = progress {
+                        callback(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                    }
+                    bbox.ok().map(|bbox| PageBoundingBox::new(idx + 1, bbox))
+                })
+                .collect()
+        };
+
+        let pool = (threads > 0)
+            .then(|| rayon::ThreadPoolBuilder::new().num_threads(threads).build().ok())
+            .flatten();
+        match pool {
+            Some(pool) => pool.install(detect),
+            None => detect(),
+        }
     }
 }
 
@@ -454,6 +704,29 @@ impl GroupCropAnalyzer {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_page_parity() {
+        assert!(PageParity::default().is_all());
+        assert_eq!(
+            (1..=6).filter(|&page| PageParity::Odd.includes(page)).collect::<Vec<_>>(),
+            vec![1, 3, 5]
+        );
+        assert_eq!(
+            (1..=6).filter(|&page| PageParity::Even.includes(page)).collect::<Vec<_>>(),
+            vec![2, 4, 6]
+        );
+        assert!((1..=6).all(|page| PageParity::All.includes(page)));
+
+        // A single-side run keeps its side for every processed page
+        assert!((0..4).all(|i| PageParity::Odd.is_odd_at(i)));
+        assert!((0..4).all(|i| !PageParity::Even.is_odd_at(i)));
+        assert!(PageParity::All.is_odd_at(0) && !PageParity::All.is_odd_at(1));
+
+        assert_eq!(PageParity::All.suffix(), None);
+        assert_eq!(PageParity::Even.suffix(), Some("even"));
+        assert_eq!(serde_json::to_string(&PageParity::Odd).unwrap(), "\"odd\"");
+    }
+
     #[test]
     fn test_page_bounding_box_creation() {
         let rect = ContentRect {
@@ -513,7 +786,7 @@ mod tests {
 
     #[test]
     fn test_decide_group_crop_empty() {
-        let result = GroupCropAnalyzer::decide_group_crop_region(&[]);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&[], &GroupCropParams::default());
         assert!(!result.is_valid());
         assert_eq!(result.inlier_count, 0);
     }
@@ -529,7 +802,7 @@ mod tests {
                 height: 1200,
             },
         )];
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
         assert!(result.is_valid());
         assert_eq!(result.left, 100);
         assert_eq!(result.top, 50);
@@ -568,7 +841,7 @@ mod tests {
                 },
             ),
         ];
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
         assert!(result.is_valid());
         assert_eq!(result.inlier_count, 3);
         // Median should be close to 100, 50
@@ -625,7 +898,7 @@ mod tests {
                 },
             ), // Outlier
         ];
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
         assert!(result.is_valid());
         // Outlier should be excluded
         assert!(result.inlier_count <= boxes.len());
@@ -671,7 +944,7 @@ mod tests {
                 },
             ),
         ];
-        let result = GroupCropAnalyzer::unify_odd_even_regions(&boxes);
+        let result = GroupCropAnalyzer::unify_odd_even_regions(&boxes, &GroupCropParams::default());
 
         // Odd pages (1, 3) should be grouped
         assert!(result.odd_region.is_valid());
@@ -682,6 +955,31 @@ mod tests {
         assert_eq!(result.even_region.total_count, 2);
     }
 
+    #[test]
+    fn test_side_shift() {
+        // Odd pages sit 40px right of the even pages, with one odd outlier
+        let mut boxes: Vec<PageBoundingBox> = (1..=8)
+            .map(|page| {
+                let x = if page % 2 == 1 { 140 } else { 100 };
+                PageBoundingBox::new(page, ContentRect { x, y: 100, width: 800, height: 1000 })
+            })
+            .collect();
+        boxes.push(PageBoundingBox::new(9, ContentRect { x: 400, y: 100, width: 300, height: 1000 }));
+        let params = GroupCropParams::default();
+        assert_eq!(GroupCropAnalyzer::side_shift(&boxes, &params), Some(40));
+
+        // Mirrored: even pages further right
+        for bbox in &mut boxes {
+            bbox.is_odd = !bbox.is_odd;
+        }
+        assert_eq!(GroupCropAnalyzer::side_shift(&boxes, &params), Some(-40));
+
+        // One side only (parity runs, single page)
+        let odd_only: Vec<PageBoundingBox> = boxes.iter().filter(|b| b.is_odd).cloned().collect();
+        assert_eq!(GroupCropAnalyzer::side_shift(&odd_only, &params), None);
+        assert_eq!(GroupCropAnalyzer::side_shift(&[], &params), None);
+    }
+
     #[test]
     fn test_group_crop_region_to_content_rect() {
         let region = GroupCropRegion {
@@ -714,7 +1012,7 @@ mod tests {
             PageBoundingBox::new(4, ContentRect { x: 100, y: 100, width: 800, height: 1000 }),
         ];
 
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
 
         // All pages have same margins, so result should be exact
         assert!(result.is_valid());
@@ -736,7 +1034,7 @@ mod tests {
             PageBoundingBox::new(4, ContentRect { x: 105, y: 105, width: 795, height: 995 }),
         ];
 
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
 
         // Result should use median values to unify
         assert!(result.is_valid());
@@ -754,7 +1052,7 @@ mod tests {
             PageBoundingBox::new(2, ContentRect { x: 0, y: 0, width: 1000, height: 1200 }),
         ];
 
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
 
         assert!(result.is_valid());
         assert_eq!(result.left, 0);
@@ -774,7 +1072,7 @@ mod tests {
             PageBoundingBox::new(5, ContentRect { x: 300, y: 300, width: 400, height: 600 }),
         ];
 
-        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
 
         // Outlier should be excluded, result should be based on normal pages
         assert!(result.is_valid());
@@ -784,6 +1082,87 @@ mod tests {
         assert!(result.left < 200); // Far from outlier's 300
     }
 
+    /// Four consistent pages plus one page whose left edge sits 60px further in
+    fn boxes_with_shifted_page() -> Vec<PageBoundingBox> {
+        vec![
+            PageBoundingBox::new(1, ContentRect { x: 100, y: 100, width: 800, height: 1000 }),
+            PageBoundingBox::new(3, ContentRect { x: 110, y: 100, width: 790, height: 1000 }),
+            PageBoundingBox::new(5, ContentRect { x: 90, y: 100, width: 810, height: 1000 }),
+            PageBoundingBox::new(7, ContentRect { x: 105, y: 100, width: 795, height: 1000 }),
+            PageBoundingBox::new(9, ContentRect { x: 160, y: 100, width: 740, height: 1000 }),
+        ]
+    }
+
+    #[test]
+    fn test_group_crop_params_tukey_k() {
+        let boxes = boxes_with_shifted_page();
+
+        // Default fence (k = 1.5) rejects the shifted page
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &GroupCropParams::default());
+        assert_eq!(result.inlier_count, 4);
+
+        // A looser fence keeps it
+        let loose = GroupCropParams::default().with_tukey_k(6.0);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &loose);
+        assert_eq!(result.inlier_count, 5);
+    }
+
+    #[test]
+    fn test_group_crop_params_fallback() {
+        let boxes = boxes_with_shifted_page();
+
+        // Requiring every page to be an inlier forces the all-pages fallback
+        let strict = GroupCropParams::default().with_min_inlier_ratio(1.0);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &strict);
+        assert_eq!(result.inlier_count, 5);
+
+        let strict = GroupCropParams::default().with_min_inlier_count(5);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&boxes, &strict);
+        assert_eq!(result.inlier_count, 5);
+
+        assert!(GroupCropParams::default().is_default());
+        assert!(!strict.is_default());
+        assert_eq!(GroupCropParams::default().with_tukey_k(-1.0).tukey_k, 0.0);
+    }
+
+    #[test]
+    fn test_crop_aggregation() {
+        let boxes = vec![
+            PageBoundingBox::new(1, ContentRect { x: 100, y: 90, width: 800, height: 1000 }),
+            PageBoundingBox::new(3, ContentRect { x: 96, y: 100, width: 812, height: 990 }),
+            PageBoundingBox::new(5, ContentRect { x: 104, y: 95, width: 790, height: 1010 }),
+        ];
+        let decide = |aggregation| {
+            let params = GroupCropParams::default().with_aggregation(aggregation);
+            GroupCropAnalyzer::decide_group_crop_region(&boxes, &params)
+        };
+        let edges = |r: &GroupCropRegion| (r.left, r.top, r.right(), r.bottom());
+
+        // Median: left 100, top 95, right 900, bottom 1090
+        let median = decide(CropAggregation::Median);
+        assert_eq!(edges(&median), (100, 95, 900, 1090));
+
+        // Extremes cover every page
+        let union = decide(CropAggregation::Extremes);
+        assert_eq!(edges(&union), (96, 90, 908, 1105));
+        for b in &boxes {
+            assert!(union.left <= b.bounding_box.x && union.right() >= b.right());
+            assert!(union.top <= b.bounding_box.y && union.bottom() >= b.bottom());
+        }
+
+        assert_eq!(edges(&decide(CropAggregation::Percentile(100.0))), edges(&union));
+        assert_eq!(edges(&decide(CropAggregation::Percentile(50.0))), edges(&median));
+
+        // Below 50 crops tighter than the median
+        let tight = decide(CropAggregation::Percentile(0.0));
+        assert_eq!((tight.left, tight.right()), (104, 894));
+
+        assert_eq!(
+            GroupCropParams::default().with_aggregation(CropAggregation::Percentile(150.0)).aggregation,
+            CropAggregation::Percentile(100.0)
+        );
+    }
+
     // TC-MARGIN-005: 奇偶ページ差 - 個別リージョン
     #[test]
     fn test_tc_margin_005_odd_even_separate_regions() {
@@ -795,7 +1174,7 @@ mod tests {
             PageBoundingBox::new(4, ContentRect { x: 98, y: 98, width: 782, height: 1002 }),  // Even
         ];
 
-        let result = GroupCropAnalyzer::unify_odd_even_regions(&boxes);
+        let result = GroupCropAnalyzer::unify_odd_even_regions(&boxes, &GroupCropParams::default());
 
         // Both regions should be valid
         assert!(result.odd_region.is_valid());
@@ -808,4 +1187,53 @@ mod tests {
         assert_eq!(result.odd_region.total_count, 2);
         assert_eq!(result.even_region.total_count, 2);
     }
+
+    #[test]
+    fn test_detect_all_bounding_boxes_with() {
+        // Dark block at (10, 20)-(29, 49) on white; page 2 is blank
+        let page = |blank: bool| {
+            GrayImage::from_fn(60, 80, |x, y| {
+                let ink = !blank && (10..30).contains(&x) && (20..50).contains(&y);
+                image::Luma([if ink { 0 } else { 255 }])
+            })
+        };
+        let decoded = vec![page(false), page(true), page(false)];
+
+        let calls = std::sync::Mutex::new(Vec::new());
+        let record = |done: usize, total: usize| calls.lock().unwrap().push((done, total));
+        let boxes = GroupCropAnalyzer::detect_all_bounding_boxes_with(&decoded, 240, 2, Some(&record));
+        let summary = |boxes: &[PageBoundingBox]| -> Vec<(usize, (u32, u32, u32, u32))> {
+            boxes
+                .iter()
+                .map(|b| {
+                    let r = b.bounding_box;
+                    (b.page_number, (r.x, r.y, r.width, r.height))
+                })
+                .collect()
+        };
+        let rect = (10, 20, 20, 30);
+        assert_eq!(summary(&boxes), vec![(1, rect), (3, rect)]);
+        let mut calls = calls.into_inner().unwrap();
+        calls.sort_unstable();
+        assert_eq!(calls, vec![(1, 3), (2, 3), (3, 3)]);
+
+        // Files give the same boxes; a missing file is left out
+        let dir = tempfile::tempdir().unwrap();
+        let mut paths: Vec<PathBuf> = decoded
+            .iter()
+            .enumerate()
+            .map(|(i, img)| {
+                let path = dir.path().join(format!("page_{}.png", i));
+                img.save(&path).unwrap();
+                path
+            })
+            .collect();
+        assert_eq!(summary(&GroupCropAnalyzer::detect_all_bounding_boxes(&paths, 240)), summary(&boxes));
+        paths.push(dir.path().join("missing.png"));
+        assert_eq!(GroupCropAnalyzer::detect_all_bounding_boxes(&paths, 240).len(), 2);
+
+        let color = image::DynamicImage::ImageRgb8(image::DynamicImage::ImageLuma8(decoded[0].clone()).to_rgb8());
+        let boxes = GroupCropAnalyzer::detect_all_bounding_boxes_with(&[color], 240, 0, None::<&fn(usize, usize)>);
+        assert_eq!(summary(&boxes), vec![(1, rect)]);
+    }
 }
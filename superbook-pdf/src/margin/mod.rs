@@ -9,6 +9,7 @@
 //! - Configurable trim percentages
 //! - Parallel processing support
 //! - Tukey fence outlier removal for group analysis
+//! - Page polarity detection for inverted (light-on-dark) pages
 //!
 //! # Example
 //!
@@ -29,6 +30,8 @@
 //! println!("Margins: top={}, bottom={}", detection.margins.top, detection.margins.bottom);
 //! ```
 
+use image::GrayImage;
+
 // Submodules
 mod detect;
 mod group;
@@ -42,7 +45,10 @@ pub mod shadow;
 
 // Re-export public API
 pub use detect::ImageMarginDetector;
-pub use group::{GroupCropAnalyzer, GroupCropRegion, PageBoundingBox, UnifiedCropRegions};
+pub use group::{
+    CropAggregation, GroupCropAnalyzer, GroupCropParams, GroupCropRegion, PageBoundingBox, PageImageSource, PageParity,
+    UnifiedCropRegions,
+};
 pub use types::{
     ContentRect, MarginDetection, MarginDetector, MarginError, Margins, Result, TrimResult,
     UnifiedMargins,
@@ -50,14 +56,14 @@ pub use types::{
 
 // Issue #32: Content-aware margin detection
 pub use content_aware::{
-    ContentAwareBoundaryDetector, ContentAwareOptions, ContentAwareOptionsBuilder,
-    ContentBoundaries, ContentBoundary,
+    ConnectedComponent, ContentAwareBoundaryDetector, ContentAwareOptions,
+    ContentAwareOptionsBuilder, ContentBoundaries, ContentBoundary,
 };
 
 // Issue #33: Shadow detection and removal
 pub use shadow::{
     Edge, ShadowDetectionResult, ShadowDetector, ShadowHsvCriteria, ShadowRegion,
-    ShadowRemovalMethod, ShadowRemovalOptions,
+    ShadowRemovalMethod, ShadowRemovalOptions, ShadowRemovalResult,
 };
 
 // ============================================================
@@ -73,6 +79,9 @@ const DARK_BACKGROUND_THRESHOLD: u8 = 50;
 /// Default minimum margin in pixels
 const DEFAULT_MIN_MARGIN: u32 = 10;
 
+/// Upper bound for `min_margin_percent` (half the shorter side)
+const MAX_MIN_MARGIN_PERCENT: f32 = 50.0;
+
 /// Default trim percentage
 const DEFAULT_TRIM_PERCENT: f32 = 0.5;
 
@@ -94,6 +103,15 @@ const MIN_SENSITIVITY: f32 = 0.0;
 /// Maximum sensitivity value
 const MAX_SENSITIVITY: f32 = 1.0;
 
+/// Border band width for polarity detection (fraction of each dimension)
+const POLARITY_BORDER_RATIO: f32 = 0.05;
+
+/// Minimum border/center brightness difference to call a page inverted
+const POLARITY_MIN_CONTRAST: f32 = 16.0;
+
+/// Pixel stride when sampling brightness for polarity detection
+const POLARITY_SAMPLE_STEP: u32 = 4;
+
 // ============================================================
 // Options
 // ============================================================
@@ -105,12 +123,17 @@ pub struct MarginOptions {
     pub background_threshold: u8,
     /// Minimum margin in pixels
     pub min_margin: u32,
+    /// Minimum margin as a percentage of the shorter image side
+    /// (overrides `min_margin` when set)
+    pub min_margin_percent: Option<f32>,
     /// Default trim percentage
     pub default_trim_percent: f32,
     /// Edge detection sensitivity
     pub edge_sensitivity: f32,
     /// Content detection mode
     pub detection_mode: ContentDetectionMode,
+    /// Page polarity (dark-on-light or light-on-dark)
+    pub polarity: Polarity,
 }
 
 impl Default for MarginOptions {
@@ -118,9 +141,11 @@ impl Default for MarginOptions {
         Self {
             background_threshold: DEFAULT_BACKGROUND_THRESHOLD,
             min_margin: DEFAULT_MIN_MARGIN,
+            min_margin_percent: None,
             default_trim_percent: DEFAULT_TRIM_PERCENT,
             edge_sensitivity: DEFAULT_EDGE_SENSITIVITY,
             detection_mode: ContentDetectionMode::BackgroundColor,
+            polarity: Polarity::Auto,
         }
     }
 }
@@ -131,6 +156,17 @@ impl MarginOptions {
         MarginOptionsBuilder::default()
     }
 
+    /// Minimum margin in pixels for an image of the given size
+    ///
+    /// Resolves `min_margin_percent` against the shorter side so the same
+    /// setting behaves consistently across DPIs; falls back to `min_margin`.
+    pub fn effective_min_margin(&self, width: u32, height: u32) -> u32 {
+        match self.min_margin_percent {
+            Some(percent) => (width.min(height) as f32 * percent / 100.0).round() as u32,
+            None => self.min_margin,
+        }
+    }
+
     /// Create options for dark backgrounds (e.g., scanned old books)
     pub fn for_dark_background() -> Self {
         Self {
@@ -171,6 +207,13 @@ impl MarginOptionsBuilder {
         self
     }
 
+    /// Set minimum margin as a percentage of the shorter image side (0-50)
+    #[must_use]
+    pub fn min_margin_percent(mut self, percent: f32) -> Self {
+        self.options.min_margin_percent = Some(percent.clamp(MIN_PERCENT, MAX_MIN_MARGIN_PERCENT));
+        self
+    }
+
     /// Set default trim percentage
     #[must_use]
     pub fn default_trim_percent(mut self, percent: f32) -> Self {
@@ -192,6 +235,13 @@ impl MarginOptionsBuilder {
         self
     }
 
+    /// Set page polarity
+    #[must_use]
+    pub fn polarity(mut self, polarity: Polarity) -> Self {
+        self.options.polarity = polarity;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> MarginOptions {
@@ -213,6 +263,76 @@ pub enum ContentDetectionMode {
     Combined,
 }
 
+/// Page polarity for background/content separation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Polarity {
+    /// Decide per page from border vs. center brightness
+    #[default]
+    Auto,
+    /// Light background, dark content
+    Normal,
+    /// Dark background, light content (e.g. white text on black)
+    Inverted,
+}
+
+impl Polarity {
+    /// Resolve to `Normal` or `Inverted` for a page
+    pub fn resolve(self, gray: &GrayImage) -> Polarity {
+        match self {
+            Polarity::Auto => Self::detect(gray),
+            fixed => fixed,
+        }
+    }
+
+    /// Whether content is lighter than the background
+    pub fn is_inverted(self) -> bool {
+        self == Polarity::Inverted
+    }
+
+    /// Detect polarity by comparing mean border brightness to the center
+    ///
+    /// A page is inverted when its border is dark and noticeably darker
+    /// than the central region, where light content raises the mean.
+    pub fn detect(gray: &GrayImage) -> Polarity {
+        let (width, height) = gray.dimensions();
+        let band_x = ((width as f32 * POLARITY_BORDER_RATIO) as u32).max(1);
+        let band_y = ((height as f32 * POLARITY_BORDER_RATIO) as u32).max(1);
+        if width <= band_x * 2 || height <= band_y * 2 {
+            return Polarity::Normal;
+        }
+
+        let (mut border_sum, mut border_count) = (0u64, 0u64);
+        let (mut center_sum, mut center_count) = (0u64, 0u64);
+        for y in (0..height).step_by(POLARITY_SAMPLE_STEP as usize) {
+            for x in (0..width).step_by(POLARITY_SAMPLE_STEP as usize) {
+                let value = u64::from(gray.get_pixel(x, y).0[0]);
+                let in_border =
+                    x < band_x || x >= width - band_x || y < band_y || y >= height - band_y;
+                let in_center = x >= width / 4 && x < width * 3 / 4 && y >= height / 4 && y < height * 3 / 4;
+                if in_border {
+                    border_sum += value;
+                    border_count += 1;
+                } else if in_center {
+                    center_sum += value;
+                    center_count += 1;
+                }
+            }
+        }
+
+        if border_count == 0 || center_count == 0 {
+            return Polarity::Normal;
+        }
+        let border_mean = border_sum as f32 / border_count as f32;
+        let center_mean = center_sum as f32 / center_count as f32;
+
+        if border_mean < 128.0 && center_mean - border_mean >= POLARITY_MIN_CONTRAST {
+            Polarity::Inverted
+        } else {
+            Polarity::Normal
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -826,7 +946,7 @@ mod tests {
 
     #[test]
     fn test_decide_group_crop_empty() {
-        let result = GroupCropAnalyzer::decide_group_crop_region(&[]);
+        let result = GroupCropAnalyzer::decide_group_crop_region(&[], &GroupCropParams::default());
         assert!(!result.is_valid());
     }
 
@@ -852,8 +972,101 @@ mod tests {
                 },
             ),
         ];
-        let result = GroupCropAnalyzer::unify_odd_even_regions(&boxes);
+        let result = GroupCropAnalyzer::unify_odd_even_regions(&boxes, &GroupCropParams::default());
         assert!(result.odd_region.is_valid());
         assert!(result.even_region.is_valid());
     }
+
+    // ============ Polarity Tests ============
+
+    /// Black page with a block of white "text" lines in the middle
+    fn inverted_page() -> GrayImage {
+        let mut gray = GrayImage::from_pixel(200, 300, image::Luma([0]));
+        for line in 0..8 {
+            let y0 = 80 + line * 18;
+            for y in y0..y0 + 10 {
+                for x in 50..150 {
+                    gray.put_pixel(x, y, image::Luma([255]));
+                }
+            }
+        }
+        gray
+    }
+
+    #[test]
+    fn test_effective_min_margin() {
+        let absolute = MarginOptions::default();
+        assert_eq!(absolute.effective_min_margin(2480, 3508), DEFAULT_MIN_MARGIN);
+
+        // 1% of the shorter side: 300 DPI A4 -> 25px, 600 DPI -> 50px
+        let relative = MarginOptions::builder().min_margin(99).min_margin_percent(1.0).build();
+        assert_eq!(relative.effective_min_margin(2480, 3508), 25);
+        assert_eq!(relative.effective_min_margin(4960, 7016), 50);
+        assert_eq!(relative.effective_min_margin(7016, 4960), 50);
+
+        let clamped = MarginOptions::builder().min_margin_percent(80.0).build();
+        assert_eq!(clamped.min_margin_percent, Some(50.0));
+    }
+
+    #[test]
+    fn test_polarity_detect() {
+        assert_eq!(Polarity::detect(&inverted_page()), Polarity::Inverted);
+
+        let mut normal = inverted_page();
+        image::imageops::invert(&mut normal);
+        assert_eq!(Polarity::detect(&normal), Polarity::Normal);
+
+        let blank = GrayImage::from_pixel(100, 100, image::Luma([255]));
+        assert_eq!(Polarity::detect(&blank), Polarity::Normal);
+    }
+
+    #[test]
+    fn test_polarity_resolve_fixed() {
+        let page = inverted_page();
+        assert_eq!(Polarity::Normal.resolve(&page), Polarity::Normal);
+        assert_eq!(Polarity::Auto.resolve(&page), Polarity::Inverted);
+        assert!(Polarity::Inverted.resolve(&page).is_inverted());
+    }
+
+    #[test]
+    fn test_detect_margins_inverted_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("inverted.png");
+        inverted_page().save(&path).unwrap();
+
+        let detection = ImageMarginDetector::detect(&path, &MarginOptions::default()).unwrap();
+        assert_eq!(detection.margins.left, 50);
+        assert_eq!(detection.margins.top, 80);
+
+        // Forcing normal polarity treats the black page as content everywhere
+        let options = MarginOptions::builder().polarity(Polarity::Normal).build();
+        let forced = ImageMarginDetector::detect(&path, &options);
+        assert!(forced.map_or(true, |d| d.margins.left != 50));
+    }
+
+    #[test]
+    fn test_content_aware_inverted_page() {
+        // 10x14 white glyphs on black
+        let mut gray = GrayImage::from_pixel(300, 400, image::Luma([0]));
+        for row in 0..6 {
+            for col in 0..10 {
+                let (x0, y0) = (60 + col * 18, 100 + row * 30);
+                for y in y0..y0 + 14 {
+                    for x in x0..x0 + 10 {
+                        gray.put_pixel(x, y, image::Luma([255]));
+                    }
+                }
+            }
+        }
+
+        let options = ContentAwareOptions::default();
+        let boundaries = ContentAwareBoundaryDetector::detect_from_image(&gray, &options).unwrap();
+        assert_eq!(boundaries.total_components, 60);
+        assert_eq!(boundaries.left.aggressive_position, 60);
+        assert_eq!(boundaries.right.aggressive_position, 231);
+        assert_eq!(boundaries.top.aggressive_position, 100);
+
+        let normal = ContentAwareOptions::builder().polarity(Polarity::Normal).build();
+        assert!(ContentAwareBoundaryDetector::detect_from_image(&gray, &normal).is_err());
+    }
 }
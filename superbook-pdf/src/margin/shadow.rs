@@ -16,12 +16,13 @@
 //! 1. Sample edge strips (15% width from each edge)
 //! 2. Calculate brightness gradient (edge → center)
 //! 3. Detect shadow using HSV criteria (low saturation, mid-value)
-//! 4. Apply correction based on selected method
+//! 4. Apply correction based on selected method (the Crop method removes the
+//!    shadow widths as margins instead; see [`ShadowRemovalResult::cropped`])
 
 use image::{Rgb, RgbImage};
 use std::path::Path;
 
-use super::types::{MarginError, Result};
+use super::types::{ContentRect, MarginError, Margins, Result};
 
 // ============================================================
 // Constants
@@ -48,6 +49,15 @@ const GRADIENT_THRESHOLD: f32 = 0.001;
 /// Number of sample rows for brightness profiling
 const SAMPLE_ROWS: u32 = 50;
 
+/// Auto method: shadows at least this fraction of the page width are cropped
+pub const AUTO_CROP_WIDTH_RATIO: f32 = 0.10;
+
+/// Auto method: shadows at least this fraction of the page width use gradient correction
+pub const AUTO_GRADIENT_WIDTH_RATIO: f32 = 0.04;
+
+/// Auto method: brightness gradients (per pixel) at least this steep use gradient correction
+pub const AUTO_STEEP_GRADIENT: f32 = 0.005;
+
 // ============================================================
 // Types
 // ============================================================
@@ -85,6 +95,10 @@ pub enum ShadowRemovalMethod {
 
     /// Simply crop the shadow region
     Crop,
+
+    /// Choose per edge from the shadow's width and gradient
+    /// (see [`ShadowRegion::auto_method`])
+    Auto,
 }
 
 /// HSV criteria for shadow detection
@@ -185,6 +199,14 @@ impl ShadowRemovalOptions {
             ..Default::default()
         }
     }
+
+    /// Create options that pick the method per edge
+    pub fn auto_method() -> Self {
+        Self {
+            method: ShadowRemovalMethod::Auto,
+            ..Default::default()
+        }
+    }
 }
 
 /// Detected shadow region
@@ -217,6 +239,21 @@ impl ShadowRegion {
         }
         (self.max_brightness - self.min_brightness) / self.width as f32
     }
+
+    /// Method chosen by [`ShadowRemovalMethod::Auto`] for a page `page_width` pixels wide
+    ///
+    /// Shadows covering a large part of the page are cropped, steep or wide
+    /// ones get gradient correction and shallow ones brightness equalization.
+    pub fn auto_method(&self, page_width: u32) -> ShadowRemovalMethod {
+        let width_ratio = self.width as f32 / page_width.max(1) as f32;
+        if width_ratio >= AUTO_CROP_WIDTH_RATIO {
+            ShadowRemovalMethod::Crop
+        } else if width_ratio >= AUTO_GRADIENT_WIDTH_RATIO || self.gradient() >= AUTO_STEEP_GRADIENT {
+            ShadowRemovalMethod::GradientCorrection
+        } else {
+            ShadowRemovalMethod::BrightnessEqualization
+        }
+    }
 }
 
 /// Shadow detection result for an image
@@ -244,6 +281,87 @@ impl ShadowDetectionResult {
     pub fn total_shadow_width(&self) -> u32 {
         self.shadows.iter().map(|s| s.width).sum()
     }
+
+    /// Margins that remove every detected shadow
+    ///
+    /// Clamped so that at least one pixel of content remains in each direction.
+    pub fn crop_margins(&self) -> Margins {
+        crop_margins_for(&self.shadows, self.image_size)
+    }
+
+    /// Region kept after cropping the shadows
+    pub fn crop_rect(&self) -> ContentRect {
+        crop_rect_for(&self.crop_margins(), self.image_size)
+    }
+}
+
+/// Margins removing `shadows` from an image of `image_size`, keeping at least one pixel
+fn crop_margins_for<'a>(shadows: impl IntoIterator<Item = &'a ShadowRegion>, image_size: (u32, u32)) -> Margins {
+    let (width, height) = image_size;
+    let mut margins = Margins::default();
+    for shadow in shadows {
+        let margin = match shadow.edge {
+            Edge::Left => &mut margins.left,
+            Edge::Right => &mut margins.right,
+            Edge::Top => &mut margins.top,
+            Edge::Bottom => &mut margins.bottom,
+        };
+        *margin = (*margin).max(shadow.width);
+    }
+    margins.left = margins.left.min(width.saturating_sub(1));
+    margins.right = margins.right.min(width.saturating_sub(1 + margins.left));
+    margins.top = margins.top.min(height.saturating_sub(1));
+    margins.bottom = margins.bottom.min(height.saturating_sub(1 + margins.top));
+    margins
+}
+
+fn crop_rect_for(margins: &Margins, image_size: (u32, u32)) -> ContentRect {
+    let (width, height) = image_size;
+    ContentRect {
+        x: margins.left,
+        y: margins.top,
+        width: width - margins.total_horizontal(),
+        height: height - margins.total_vertical(),
+    }
+}
+
+/// Outcome of shadow removal on one image
+#[derive(Debug, Clone)]
+pub struct ShadowRemovalResult {
+    /// Shadows found before removal
+    pub detection: ShadowDetectionResult,
+
+    /// Method that was requested (possibly `Auto`)
+    pub method: ShadowRemovalMethod,
+
+    /// Method applied to each detected shadow (never `Auto`)
+    pub edge_methods: Vec<(Edge, ShadowRemovalMethod)>,
+
+    /// Edges whose shadow was corrected in place (equalization / gradient)
+    pub corrected: Vec<Edge>,
+
+    /// Margins cropped away (edges handled by Crop; zero otherwise)
+    pub cropped: Margins,
+
+    /// Dimensions of the output image
+    pub output_size: (u32, u32),
+}
+
+impl ShadowRemovalResult {
+    /// Method applied to the shadow on `edge`, if one was detected
+    pub fn method_for(&self, edge: Edge) -> Option<ShadowRemovalMethod> {
+        self.edge_methods.iter().find(|(e, _)| *e == edge).map(|(_, method)| *method)
+    }
+
+    /// Whether the image was cropped
+    pub fn was_cropped(&self) -> bool {
+        self.cropped.total_horizontal() + self.cropped.total_vertical() > 0
+    }
+
+    /// Whether any pixels were changed
+    pub fn has_changes(&self) -> bool {
+        !self.corrected.is_empty() || self.was_cropped()
+    }
 }
 
 // ============================================================
@@ -412,11 +530,13 @@ impl ShadowDetector {
     }
 
     /// Remove shadows from an image file
+    ///
+    /// The Crop method writes the image with the shadow margins removed.
     pub fn remove_shadows(
         image_path: &Path,
         output_path: &Path,
         options: &ShadowRemovalOptions,
-    ) -> Result<ShadowDetectionResult> {
+    ) -> Result<ShadowRemovalResult> {
         if !image_path.exists() {
             return Err(MarginError::ImageNotFound(image_path.to_path_buf()));
         }
@@ -424,49 +544,89 @@ impl ShadowDetector {
         let img = image::open(image_path).map_err(|e| MarginError::InvalidImage(e.to_string()))?;
         let mut rgb = img.to_rgb8();
 
-        let detection = Self::detect_from_image(&rgb, options)?;
-
-        for shadow in &detection.shadows {
-            Self::apply_shadow_removal(&mut rgb, shadow, options);
-        }
+        let result = Self::remove_shadows_in_place(&mut rgb, options)?;
 
         rgb.save(output_path)
             .map_err(|e| MarginError::InvalidImage(e.to_string()))?;
 
-        Ok(detection)
+        Ok(result)
+    }
+
+    /// Crop detected shadows from an image file, whatever `options.method` is
+    pub fn crop_shadows(
+        image_path: &Path,
+        output_path: &Path,
+        options: &ShadowRemovalOptions,
+    ) -> Result<ShadowRemovalResult> {
+        let options = ShadowRemovalOptions {
+            method: ShadowRemovalMethod::Crop,
+            ..options.clone()
+        };
+        Self::remove_shadows(image_path, output_path, &options)
     }
 
     /// Remove shadows from an RGB image in place
+    ///
+    /// The Crop method replaces `image` with the cropped region.
     pub fn remove_shadows_in_place(
         image: &mut RgbImage,
         options: &ShadowRemovalOptions,
-    ) -> Result<ShadowDetectionResult> {
+    ) -> Result<ShadowRemovalResult> {
         let detection = Self::detect_from_image(image, options)?;
+        let (page_width, _) = detection.image_size;
+        let edge_methods: Vec<(Edge, ShadowRemovalMethod)> = detection
+            .shadows
+            .iter()
+            .map(|shadow| {
+                let method = match options.method {
+                    ShadowRemovalMethod::Auto => shadow.auto_method(page_width),
+                    method => method,
+                };
+                (shadow.edge, method)
+            })
+            .collect();
+
+        // Correct first so that shadow coordinates still refer to the full page
+        let mut corrected = Vec::new();
+        let mut to_crop = Vec::new();
+        for (shadow, (_, method)) in detection.shadows.iter().zip(&edge_methods) {
+            match method {
+                ShadowRemovalMethod::Crop => to_crop.push(shadow),
+                // Only left/right shadows are corrected for now
+                method if matches!(shadow.edge, Edge::Left | Edge::Right) => {
+                    Self::apply_shadow_correction(image, shadow, *method);
+                    corrected.push(shadow.edge);
+                }
+                _ => {}
+            }
+        }
 
-        for shadow in &detection.shadows {
-            Self::apply_shadow_removal(image, shadow, options);
+        let cropped = crop_margins_for(to_crop, detection.image_size);
+        if cropped.total_horizontal() + cropped.total_vertical() > 0 {
+            let rect = crop_rect_for(&cropped, detection.image_size);
+            *image = image::imageops::crop_imm(image, rect.x, rect.y, rect.width, rect.height).to_image();
         }
 
-        Ok(detection)
+        Ok(ShadowRemovalResult {
+            detection,
+            method: options.method,
+            edge_methods,
+            corrected,
+            cropped,
+            output_size: image.dimensions(),
+        })
     }
 
-    /// Apply shadow removal to an image
-    fn apply_shadow_removal(
-        image: &mut RgbImage,
-        shadow: &ShadowRegion,
-        options: &ShadowRemovalOptions,
-    ) {
-        match options.method {
+    /// Correct a shadow region in place (Crop is applied by the caller)
+    fn apply_shadow_correction(image: &mut RgbImage, shadow: &ShadowRegion, method: ShadowRemovalMethod) {
+        match method {
             ShadowRemovalMethod::BrightnessEqualization => {
                 Self::apply_brightness_equalization(image, shadow);
             }
             ShadowRemovalMethod::GradientCorrection => {
                 Self::apply_gradient_correction(image, shadow);
             }
-            ShadowRemovalMethod::Crop => {
-                // Crop is handled externally through the margin system
-                // This method just marks the shadow region
-            }
+            ShadowRemovalMethod::Crop | ShadowRemovalMethod::Auto => {}
         }
     }
 
@@ -597,6 +757,9 @@ mod tests {
 
         let gradient = ShadowRemovalOptions::gradient_method();
         assert_eq!(gradient.method, ShadowRemovalMethod::GradientCorrection);
+
+        let auto = ShadowRemovalOptions::auto_method();
+        assert_eq!(auto.method, ShadowRemovalMethod::Auto);
     }
 
     #[test]
@@ -717,7 +880,7 @@ mod tests {
 
         // Add shadow gradient on left edge
         for x in 0..50 {
-            let brightness = (128 + (x as u32 * 2)) as u8;
+            let brightness = (128 + (x * 2)) as u8;
             for y in 0..100 {
                 image.put_pixel(x, y, Rgb([brightness, brightness, brightness]));
             }
@@ -752,6 +915,139 @@ mod tests {
         assert!(profile[0] < profile[20]);
     }
 
+    fn shadowed_page() -> RgbImage {
+        // Dark binding shadow fading into white paper on the left edge
+        let mut image = RgbImage::from_pixel(400, 100, Rgb([255, 255, 255]));
+        for x in 0..40 {
+            let brightness = (80 + x * 4) as u8;
+            for y in 0..100 {
+                image.put_pixel(x, y, Rgb([brightness, brightness, brightness]));
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn test_crop_margins() {
+        let shadow = |edge, width| ShadowRegion {
+            edge,
+            width,
+            brightness_profile: vec![],
+            confidence: 1.0,
+            min_brightness: 0.3,
+            max_brightness: 0.9,
+        };
+        let result = ShadowDetectionResult {
+            shadows: vec![shadow(Edge::Left, 30), shadow(Edge::Right, 20)],
+            image_size: (200, 100),
+        };
+        let margins = result.crop_margins();
+        assert_eq!((margins.left, margins.right, margins.top, margins.bottom), (30, 20, 0, 0));
+        let rect = result.crop_rect();
+        assert_eq!((rect.x, rect.y, rect.width, rect.height), (30, 0, 150, 100));
+
+        // Shadows wider than the page still leave content
+        let wide = ShadowDetectionResult {
+            shadows: vec![shadow(Edge::Left, 150), shadow(Edge::Right, 150)],
+            image_size: (200, 100),
+        };
+        assert_eq!(wide.crop_rect().width, 1);
+    }
+
+    #[test]
+    fn test_crop_method_crops_shadow() {
+        let mut image = shadowed_page();
+        let options = ShadowRemovalOptions::crop_method();
+        let result = ShadowDetector::remove_shadows_in_place(&mut image, &options).unwrap();
+
+        let shadow = result.detection.get_shadow(Edge::Left).expect("left shadow");
+        assert!(result.was_cropped());
+        assert!(result.corrected.is_empty());
+        assert_eq!(result.cropped.left, shadow.width);
+        assert_eq!(result.output_size, (400 - shadow.width, 100));
+        assert_eq!(image.dimensions(), result.output_size);
+    }
+
+    #[test]
+    fn test_correction_methods_report_corrected_edges() {
+        let mut image = shadowed_page();
+        let result =
+            ShadowDetector::remove_shadows_in_place(&mut image, &ShadowRemovalOptions::left_only()).unwrap();
+        assert_eq!(result.corrected, vec![Edge::Left]);
+        assert!(!result.was_cropped());
+        assert_eq!(image.dimensions(), (400, 100));
+    }
+
+    #[test]
+    fn test_auto_method_selection() {
+        let shadow = |width, min_brightness: f32| ShadowRegion {
+            edge: Edge::Left,
+            width,
+            brightness_profile: vec![],
+            confidence: 1.0,
+            min_brightness,
+            max_brightness: 0.9,
+        };
+        // Narrow, shallow shadow
+        assert_eq!(shadow(20, 0.85).auto_method(1000), ShadowRemovalMethod::BrightnessEqualization);
+        // Narrow but steep
+        assert_eq!(shadow(20, 0.5).auto_method(1000), ShadowRemovalMethod::GradientCorrection);
+        // Wide but shallow
+        assert_eq!(shadow(50, 0.85).auto_method(1000), ShadowRemovalMethod::GradientCorrection);
+        // Large fraction of the page
+        assert_eq!(shadow(150, 0.85).auto_method(1000), ShadowRemovalMethod::Crop);
+    }
+
+    #[test]
+    fn test_auto_method_reports_per_edge() {
+        // Left shadow over 10% of a narrow page (cropped) and a short
+        // shallow right one (corrected in place)
+        let mut image = RgbImage::from_pixel(300, 100, Rgb([255, 255, 255]));
+        for x in 0..40 {
+            let brightness = (80 + x * 4) as u8;
+            for y in 0..100 {
+                image.put_pixel(x, y, Rgb([brightness, brightness, brightness]));
+            }
+        }
+        for x in 0..12 {
+            let brightness = 230 + x as u8 * 2;
+            for y in 0..100 {
+                image.put_pixel(299 - x, y, Rgb([brightness, brightness, brightness]));
+            }
+        }
+        let options = ShadowRemovalOptions {
+            min_shadow_width: 5,
+            ..ShadowRemovalOptions::auto_method()
+        };
+        let result = ShadowDetector::remove_shadows_in_place(&mut image, &options).unwrap();
+
+        assert_eq!(result.method, ShadowRemovalMethod::Auto);
+        assert_eq!(result.edge_methods.len(), result.detection.shadows.len());
+        assert!(result.edge_methods.iter().all(|(_, m)| *m != ShadowRemovalMethod::Auto));
+        assert_eq!(result.method_for(Edge::Left), Some(ShadowRemovalMethod::Crop));
+        assert!(result.was_cropped());
+        assert_eq!(result.cropped.right, 0);
+        if let Some(method) = result.method_for(Edge::Right) {
+            assert_ne!(method, ShadowRemovalMethod::Crop);
+            assert_eq!(result.corrected, vec![Edge::Right]);
+        }
+        assert_eq!(image.dimensions(), result.output_size);
+    }
+
+    #[test]
+    fn test_crop_shadows_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("page.png");
+        let output = dir.path().join("cropped.png");
+        shadowed_page().save(&input).unwrap();
+
+        // crop_shadows crops even when the options name another method
+        let result = ShadowDetector::crop_shadows(&input, &output, &ShadowRemovalOptions::left_only()).unwrap();
+        assert_eq!(result.method, ShadowRemovalMethod::Crop);
+        assert!(result.was_cropped());
+        assert_eq!(image::image_dimensions(&output).unwrap(), result.output_size);
+    }
+
     #[test]
     fn test_image_not_found() {
         let result = ShadowDetector::detect(
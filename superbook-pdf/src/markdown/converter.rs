@@ -8,6 +8,7 @@ use super::api_validate::{ApiValidator, ValidationProvider, ValidationResult};
 use super::element_detect::ElementDetector;
 use super::reading_order::{ReadingOrderSorter, TextDirection};
 use super::renderer::{MarkdownRenderer, MarkdownRenderOptions};
+use super::running_heads::{RunningHeadDetector, RunningHeadOptions, RunningHeadTemplate};
 use super::types::{
     BoundingBox, MarkdownError, MarkdownOptions, PageContent, Result, TextBlock,
     TextDirectionOption,
@@ -37,6 +38,9 @@ pub struct MarkdownConversionResult {
 
     /// Metadata file path (if generated)
     pub metadata_path: Option<PathBuf>,
+
+    /// Detected running headers/footers
+    pub running_heads: Vec<RunningHeadTemplate>,
 }
 
 // ============================================================
@@ -82,7 +86,10 @@ impl MarkdownConverter {
         let direction = self.detect_direction(&pages);
 
         // Sort blocks in reading order
-        let sorted_pages = self.sort_pages(pages, direction);
+        let mut sorted_pages = self.sort_pages(pages, direction);
+
+        // Mark running headers/footers
+        let running_heads = RunningHeadDetector::detect(&mut sorted_pages, &RunningHeadOptions::default());
 
         // Render to Markdown
         let renderer = MarkdownRenderer::with_options(MarkdownRenderOptions {
@@ -92,6 +99,9 @@ impl MarkdownConverter {
             } else {
                 String::new()
             },
+            low_confidence_threshold: self.options.mark_low_confidence.map(|p| p as f64 / 100.0),
+            exclude_running_heads: self.options.exclude_running_heads,
+            dehyphenate: self.options.dehyphenate,
             ..Default::default()
         });
 
@@ -111,7 +121,7 @@ impl MarkdownConverter {
         // Generate metadata if enabled
         let metadata_path = if self.options.generate_metadata {
             let path = output_dir.join("metadata.json");
-            self.generate_metadata(&sorted_pages, &path)?;
+            self.generate_metadata(&sorted_pages, &running_heads, &path)?;
             Some(path)
         } else {
             None
@@ -139,6 +149,7 @@ impl MarkdownConverter {
             validation,
             extracted_images,
             metadata_path,
+            running_heads,
         })
     }
 
@@ -196,7 +207,12 @@ impl MarkdownConverter {
     }
 
     /// Generate metadata JSON
-    fn generate_metadata(&self, pages: &[PageContent], path: &Path) -> Result<()> {
+    fn generate_metadata(
+        &self,
+        pages: &[PageContent],
+        running_heads: &[RunningHeadTemplate],
+        path: &Path,
+    ) -> Result<()> {
         let metadata = serde_json::json!({
             "version": "1.0",
             "pages": pages.len(),
@@ -205,6 +221,7 @@ impl MarkdownConverter {
                 TextDirection::Vertical => "vertical",
                 TextDirection::Horizontal => "horizontal",
             },
+            "running_heads": running_heads,
         });
 
         let content =
@@ -229,6 +246,7 @@ impl MarkdownConverter {
     }
 
     /// Convert OCR result to PageContent
+    #[allow(clippy::type_complexity)]
     pub fn ocr_result_to_page_content(
         page_number: usize,
         page_size: (u32, u32),
@@ -358,6 +376,7 @@ mod tests {
             validation: None,
             extracted_images: Vec::new(),
             metadata_path: Some(PathBuf::from("metadata.json")),
+            running_heads: Vec::new(),
         };
 
         assert_eq!(result.pages_processed, 10);
@@ -0,0 +1,147 @@
+//! Line-end hyphen removal for reflowed text
+//!
+//! OCR of justified Western text keeps the hyphens that split words at line
+//! ends ("associ-" / "ation"), which reflowed text (Markdown, the plain text
+//! sidecar) shows mid-line. [`dehyphenate`] joins such a word when the
+//! hyphen follows a letter and the next line starts with a lowercase letter,
+//! moving the rest of the word up to the first line. Compounds broken at
+//! their own hyphen ("self-" / "aware") keep it: the fragment before the
+//! hyphen is looked up in [`COMPOUND_PREFIXES`], unless the continuation
+//! is a suffix ("well-" / "ness"). A soft hyphen (U+00AD) always goes.
+//!
+//! CJK text is left alone: its characters are neither cased nor hyphenated,
+//! so no break qualifies.
+
+/// Words that form hyphenated compounds ("well-known", "self-aware"); a
+/// break after one of them keeps the hyphen
+pub const COMPOUND_PREFIXES: &[&str] = &[
+    "cross", "full", "half", "high", "ill", "long", "quasi", "self", "short", "vice", "well", "wide",
+];
+
+/// Continuations that end the word before the hyphen ("well-" / "ness")
+/// rather than start the second half of a compound
+const SUFFIXES: &[&str] = &[
+    "able", "ed", "er", "est", "ful", "ible", "ing", "ish", "ity", "less", "ly", "ment", "ness", "ous", "tion",
+];
+
+/// Hyphens that end a line in OCR output (ASCII, U+2010, soft hyphen)
+const HYPHENS: [char; 3] = ['-', '\u{2010}', '\u{00AD}'];
+
+const SOFT_HYPHEN: char = '\u{00AD}';
+
+/// Join words hyphenated across line breaks in `text`
+///
+/// Lines are separated by `\n`; other characters (including the form feed
+/// between pages) are kept as they are.
+pub fn dehyphenate(text: &str) -> String {
+    let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+    let mut i = 0;
+    while i + 1 < lines.len() {
+        let Some((stem, keep_hyphen)) = hyphen_break(&lines[i]) else {
+            i += 1;
+            continue;
+        };
+        // Only indentation is skipped: a form feed (page break) blocks the join
+        let next = lines[i + 1].trim_start_matches([' ', '\t']);
+        if !next.chars().next().is_some_and(char::is_lowercase) {
+            i += 1;
+            continue;
+        }
+
+        let (continuation, rest) = next.split_once(char::is_whitespace).unwrap_or((next, ""));
+        let mut joined = stem.to_string();
+        if keep_hyphen && !SUFFIXES.iter().any(|suffix| is_suffix(continuation, suffix)) {
+            joined.push('-');
+        }
+        joined.push_str(continuation);
+        let rest = rest.trim_start().to_string();
+        lines[i] = joined;
+        if rest.is_empty() {
+            // The word ended the next line: the line after may continue it
+            lines.remove(i + 1);
+        } else {
+            lines[i + 1] = rest;
+            i += 1;
+        }
+    }
+    lines.join("\n")
+}
+
+/// Whether `word` (ignoring trailing punctuation) is `suffix`, or `suffix`
+/// followed by "s"
+fn is_suffix(word: &str, suffix: &str) -> bool {
+    let word = word.trim_end_matches(|c: char| !c.is_alphabetic());
+    word == suffix || word.strip_prefix(suffix) == Some("s")
+}
+
+/// Line without its trailing hyphen, and whether the hyphen belongs to a
+/// compound; None unless the line ends in a hyphen right after a letter
+fn hyphen_break(line: &str) -> Option<(&str, bool)> {
+    let line = line.trim_end();
+    let hyphen = line.chars().next_back().filter(|c| HYPHENS.contains(c))?;
+    let stem = &line[..line.len() - hyphen.len_utf8()];
+    let last = stem.chars().next_back()?;
+    if !(last.is_lowercase() || last.is_uppercase()) {
+        return None;
+    }
+
+    let word_start = stem
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphabetic())
+        .last()
+        .map_or(stem.len(), |(i, _)| i);
+    let word = stem[word_start..].to_lowercase();
+    let compound = hyphen != SOFT_HYPHEN && COMPOUND_PREFIXES.contains(&word.as_str());
+    Some((stem, compound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dehyphenate_joins_split_words() {
+        assert_eq!(
+            dehyphenate("the associ-\nation of scanned\nbooks is pre-\nserved here"),
+            "the association\nof scanned\nbooks is preserved\nhere"
+        );
+        // Rest of the next line stays on it; the joined word takes trailing punctuation
+        assert_eq!(dehyphenate("a long exam-\nple, with more"), "a long example,\nwith more");
+        // A word that was the whole next line
+        assert_eq!(dehyphenate("exam-\nple\nnext"), "example\nnext");
+        // Soft hyphen and U+2010
+        assert_eq!(dehyphenate("hyphen\u{00AD}\nation"), "hyphenation");
+        assert_eq!(dehyphenate("hyphen\u{2010}\nation"), "hyphenation");
+    }
+
+    #[test]
+    fn test_dehyphenate_keeps_compounds_and_other_breaks() {
+        assert_eq!(dehyphenate("a well-\nknown result"), "a well-known\nresult");
+        assert_eq!(dehyphenate("Self-\naware"), "Self-aware");
+        assert_eq!(dehyphenate("its well-\nness."), "its wellness.");
+
+        // Next line capitalized, a number, a dash, or a list item
+        for text in [
+            "the end of Anglo-\nSaxon times",
+            "pages 10-\n20",
+            "a pause --\nthen more",
+            "item -\n- next",
+            "no hyphen\nhere",
+            "trailing-",
+        ] {
+            assert_eq!(dehyphenate(text), text);
+        }
+    }
+
+    #[test]
+    fn test_dehyphenate_leaves_cjk_and_page_breaks() {
+        let japanese = "吾輩は猫であ-\nる。名前はまだ無い";
+        assert_eq!(dehyphenate(japanese), japanese);
+
+        // The form feed between pages blocks the join
+        let pages = "last line of a page con-\n\x0ctinued";
+        assert_eq!(dehyphenate(pages), pages);
+        assert_eq!(dehyphenate("inter-\nnational\n"), "international\n");
+    }
+}
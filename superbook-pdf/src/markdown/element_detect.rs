@@ -5,6 +5,12 @@
 
 use super::types::{BoundingBox, TextBlock};
 
+/// OCR confidence below which a large block is taken for a figure
+const FIGURE_MAX_CONFIDENCE: f64 = 0.3;
+
+/// Minimum area (pixels) of a figure block
+const FIGURE_MIN_AREA: u64 = 10000;
+
 // ============================================================
 // Types
 // ============================================================
@@ -187,18 +193,20 @@ impl ElementDetector {
             }
         }
 
-        // Detect figures (low OCR confidence regions)
-        for block in blocks {
-            if block.confidence < 0.3 && block.bbox.area() > 10000 {
-                // Check for figure caption nearby
-                let caption = Self::find_caption_for(block, blocks);
-                elements.push(DetectedElement::figure(block.bbox, caption, None));
-            }
-        }
-
+        elements.extend(Self::detect_figures(blocks));
         elements
     }
 
+    /// Detect figures: large regions the OCR could not read (also used by
+    /// the image pipeline to keep figures in color)
+    pub fn detect_figures(blocks: &[TextBlock]) -> Vec<DetectedElement> {
+        blocks
+            .iter()
+            .filter(|block| block.confidence < FIGURE_MAX_CONFIDENCE && block.bbox.area() > FIGURE_MIN_AREA)
+            .map(|block| DetectedElement::figure(block.bbox, Self::find_caption_for(block, blocks), None))
+            .collect()
+    }
+
     /// Calculate median font size from blocks
     fn median_font_size(blocks: &[TextBlock]) -> f32 {
         if blocks.is_empty() {
@@ -447,6 +455,31 @@ mod tests {
         assert!(!headings.is_empty());
     }
 
+    #[test]
+    fn test_detect_figures() {
+        let mut figure = make_block("", 0, 200, 300, 200, 12.0);
+        figure.confidence = 0.1;
+        let mut smudge = make_block("", 0, 0, 20, 20, 12.0);
+        smudge.confidence = 0.1;
+        let blocks = vec![
+            make_block("Body text", 0, 100, 300, 20, 12.0),
+            figure,
+            smudge,
+            make_block("図1 構成", 0, 420, 200, 20, 12.0),
+        ];
+
+        let figures = ElementDetector::detect_figures(&blocks);
+        assert_eq!(figures.len(), 1);
+        assert_eq!(figures[0].element_type, ElementType::Figure);
+        let bbox = figures[0].bbox;
+        assert_eq!((bbox.x, bbox.y, bbox.width, bbox.height), (0, 200, 300, 200));
+        assert_eq!(figures[0].caption.as_deref(), Some("図1 構成"));
+
+        // detect_elements reports the same figures
+        let elements = ElementDetector::detect_elements(&blocks, (400, 600));
+        assert_eq!(elements.iter().filter(|e| e.element_type == ElementType::Figure).count(), 1);
+    }
+
     #[test]
     fn test_median_font_size() {
         let blocks = vec![
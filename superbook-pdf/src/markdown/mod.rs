@@ -12,20 +12,31 @@
 //! - Figure and table extraction
 //! - Heading level estimation
 //! - Optional external API validation
+//! - hOCR / ALTO export of OCR layout
+//! - Running header/footer detection
+//! - Joining words hyphenated at line ends
 
 mod converter;
+mod dehyphenate;
 mod element_detect;
+mod ocr_export;
 mod reading_order;
 mod renderer;
+mod running_heads;
 mod types;
 
 pub mod api_validate;
 
 // Re-export public API
 pub use converter::{MarkdownConverter, MarkdownConversionResult};
+pub use dehyphenate::{dehyphenate, COMPOUND_PREFIXES};
 pub use element_detect::{ElementDetector, DetectedElement, ElementType, TableStructure};
+pub use ocr_export::{OcrExportFormat, OcrExporter};
 pub use reading_order::{ReadingOrderSorter, TextDirection, ReadingOrderOptions};
 pub use renderer::{MarkdownRenderer, MarkdownRenderOptions};
+pub use running_heads::{
+    RunningHeadDetector, RunningHeadMode, RunningHeadOptions, RunningHeadPosition, RunningHeadTemplate,
+};
 pub use types::{
     MarkdownError, MarkdownOptions, MarkdownOptionsBuilder, PageContent, TextBlock, BoundingBox,
     TextDirectionOption,
@@ -0,0 +1,389 @@
+//! OCR Export module
+//!
+//! Serializes OCR text blocks as hOCR (XHTML) or ALTO v4 XML so that
+//! downstream indexers and digital-library tools can consume the layout,
+//! or as plain text for desktop search.
+//!
+//! Coordinates are emitted in the pixel space of the page image the
+//! blocks were recognized on, and blocks are written in reading order.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::reading_order::{ReadingOrderSorter, TextDirection};
+use super::types::{BoundingBox, MarkdownError, PageContent, Result, TextBlock};
+
+// ============================================================
+// Types
+// ============================================================
+
+/// OCR export format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OcrExportFormat {
+    /// hOCR (XHTML with `ocr_*` classes)
+    Hocr,
+    /// ALTO v4 XML
+    Alto,
+}
+
+impl OcrExportFormat {
+    /// File extension (without leading dot)
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Hocr => "hocr",
+            Self::Alto => "alto.xml",
+        }
+    }
+}
+
+/// Exporter for OCR results
+pub struct OcrExporter;
+
+impl OcrExporter {
+    /// Render pages in the given format
+    pub fn render(pages: &[PageContent], format: OcrExportFormat, title: &str) -> String {
+        match format {
+            OcrExportFormat::Hocr => Self::to_hocr(pages, title),
+            OcrExportFormat::Alto => Self::to_alto(pages, title),
+        }
+    }
+
+    /// Render pages and write them to `path`
+    pub fn write(
+        pages: &[PageContent],
+        format: OcrExportFormat,
+        title: &str,
+        path: &Path,
+    ) -> Result<()> {
+        std::fs::write(path, Self::render(pages, format, title)).map_err(MarkdownError::IoError)
+    }
+
+    /// Render pages as plain text: one line per block in reading order,
+    /// pages separated by a form feed (as `pdftotext` does)
+    pub fn to_text(pages: &[PageContent]) -> String {
+        let mut out = String::new();
+        for (i, page) in pages.iter().enumerate() {
+            if i > 0 {
+                out.push('\x0c');
+            }
+            for block in Self::ordered_blocks(page) {
+                for line in block.text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+
+    /// Render pages as an hOCR document
+    pub fn to_hocr(pages: &[PageContent], title: &str) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" \
+             \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">\n",
+        );
+        out.push_str("<html xmlns=\"http://www.w3.org/1999/xhtml\" xml:lang=\"ja\" lang=\"ja\">\n");
+        out.push_str("<head>\n");
+        out.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+        out.push_str(
+            "<meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-8\" />\n",
+        );
+        out.push_str(&format!(
+            "<meta name=\"ocr-system\" content=\"superbook-pdf {}\" />\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+        out.push_str(
+            "<meta name=\"ocr-capabilities\" content=\"ocr_page ocr_carea ocr_par ocr_line\" />\n",
+        );
+        out.push_str("</head>\n<body>\n");
+
+        for page in pages {
+            let n = page.page_number;
+            let (width, height) = page.page_size;
+            out.push_str(&format!(
+                "<div class=\"ocr_page\" id=\"page_{}\" title=\"bbox 0 0 {} {}; ppageno {}\">\n",
+                n,
+                width,
+                height,
+                n.saturating_sub(1)
+            ));
+            for (b, block) in Self::ordered_blocks(page).iter().enumerate() {
+                let b = b + 1;
+                let wconf = (block.confidence.clamp(0.0, 1.0) * 100.0).round() as u32;
+                out.push_str(&format!(
+                    "<div class=\"ocr_carea\" id=\"block_{}_{}\" title=\"{}\">\n",
+                    n,
+                    b,
+                    hocr_bbox(&block.bbox)
+                ));
+                out.push_str(&format!("<p class=\"ocr_par\" id=\"par_{}_{}\">\n", n, b));
+                for (l, (text, bbox)) in split_lines(block, page.is_vertical).iter().enumerate() {
+                    out.push_str(&format!(
+                        "<span class=\"ocr_line\" id=\"line_{}_{}_{}\" title=\"{}; x_wconf {}\">{}</span>\n",
+                        n,
+                        b,
+                        l + 1,
+                        hocr_bbox(bbox),
+                        wconf,
+                        escape_xml(text)
+                    ));
+                }
+                out.push_str("</p>\n</div>\n");
+            }
+            out.push_str("</div>\n");
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    /// Render pages as an ALTO v4 document
+    pub fn to_alto(pages: &[PageContent], source_name: &str) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(
+            "<alto xmlns=\"http://www.loc.gov/standards/alto/ns-v4#\" \
+             xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" \
+             xsi:schemaLocation=\"http://www.loc.gov/standards/alto/ns-v4# \
+             http://www.loc.gov/standards/alto/v4/alto-4-2.xsd\">\n",
+        );
+        out.push_str("  <Description>\n");
+        out.push_str("    <MeasurementUnit>pixel</MeasurementUnit>\n");
+        out.push_str(&format!(
+            "    <sourceImageInformation>\n      <fileName>{}</fileName>\n    </sourceImageInformation>\n",
+            escape_xml(source_name)
+        ));
+        out.push_str("    <OCRProcessing ID=\"OCR_0\">\n      <ocrProcessingStep>\n");
+        out.push_str(&format!(
+            "        <processingSoftware>\n          <softwareName>superbook-pdf</softwareName>\n          <softwareVersion>{}</softwareVersion>\n        </processingSoftware>\n",
+            env!("CARGO_PKG_VERSION")
+        ));
+        out.push_str("      </ocrProcessingStep>\n    </OCRProcessing>\n");
+        out.push_str("  </Description>\n  <Layout>\n");
+
+        for page in pages {
+            let n = page.page_number;
+            let (width, height) = page.page_size;
+            out.push_str(&format!(
+                "    <Page ID=\"page_{}\" PHYSICAL_IMG_NR=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\">\n",
+                n, n, width, height
+            ));
+            out.push_str(&format!(
+                "      <PrintSpace HPOS=\"0\" VPOS=\"0\" WIDTH=\"{}\" HEIGHT=\"{}\">\n",
+                width, height
+            ));
+            for (b, block) in Self::ordered_blocks(page).iter().enumerate() {
+                let b = b + 1;
+                out.push_str(&format!(
+                    "        <TextBlock ID=\"block_{}_{}\" {}>\n",
+                    n,
+                    b,
+                    alto_bbox(&block.bbox)
+                ));
+                for (l, (text, bbox)) in split_lines(block, page.is_vertical).iter().enumerate() {
+                    let id = format!("{}_{}_{}", n, b, l + 1);
+                    out.push_str(&format!(
+                        "          <TextLine ID=\"line_{}\" {}>\n",
+                        id,
+                        alto_bbox(bbox)
+                    ));
+                    out.push_str(&format!(
+                        "            <String ID=\"string_{}\" {} CONTENT=\"{}\" WC=\"{:.2}\"/>\n",
+                        id,
+                        alto_bbox(bbox),
+                        escape_xml(text),
+                        block.confidence.clamp(0.0, 1.0)
+                    ));
+                    out.push_str("          </TextLine>\n");
+                }
+                out.push_str("        </TextBlock>\n");
+            }
+            out.push_str("      </PrintSpace>\n    </Page>\n");
+        }
+
+        out.push_str("  </Layout>\n</alto>\n");
+        out
+    }
+
+    /// Non-empty blocks of a page, sorted in reading order
+    fn ordered_blocks(page: &PageContent) -> Vec<TextBlock> {
+        let mut blocks: Vec<TextBlock> = page
+            .text_blocks
+            .iter()
+            .filter(|b| !b.is_empty())
+            .cloned()
+            .collect();
+        let direction = if page.is_vertical {
+            TextDirection::Vertical
+        } else {
+            TextDirection::Horizontal
+        };
+        ReadingOrderSorter::sort(&mut blocks, direction);
+        blocks
+    }
+}
+
+// ============================================================
+// Helpers
+// ============================================================
+
+/// Split a block into lines, dividing its box evenly between them.
+/// Horizontal lines stack top-to-bottom; vertical lines right-to-left.
+fn split_lines(block: &TextBlock, vertical: bool) -> Vec<(String, BoundingBox)> {
+    let lines: Vec<&str> = block
+        .text
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    let count = lines.len().max(1) as u32;
+    let bbox = block.bbox;
+
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            let i = i as u32;
+            let line_box = if vertical {
+                let w = bbox.width / count;
+                BoundingBox::new(bbox.right() - w * (i + 1), bbox.y, w, bbox.height)
+            } else {
+                let h = bbox.height / count;
+                BoundingBox::new(bbox.x, bbox.y + h * i, bbox.width, h)
+            };
+            (text.to_string(), line_box)
+        })
+        .collect()
+}
+
+fn hocr_bbox(bbox: &BoundingBox) -> String {
+    format!("bbox {} {} {} {}", bbox.x, bbox.y, bbox.right(), bbox.bottom())
+}
+
+fn alto_bbox(bbox: &BoundingBox) -> String {
+    format!(
+        "HPOS=\"{}\" VPOS=\"{}\" WIDTH=\"{}\" HEIGHT=\"{}\"",
+        bbox.x, bbox.y, bbox.width, bbox.height
+    )
+}
+
+/// Escape text for XML content and attribute values
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+// ============================================================
+// Tests
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(text: &str, x: u32, y: u32, w: u32, h: u32, confidence: f64) -> TextBlock {
+        let mut block = TextBlock::new(text.to_string(), BoundingBox::new(x, y, w, h));
+        block.confidence = confidence;
+        block
+    }
+
+    fn sample_page() -> PageContent {
+        let mut page = PageContent::new(1, (1000, 1400));
+        page.add_block(block("Second", 100, 500, 400, 40, 0.8));
+        page.add_block(block("First & <b>", 100, 100, 400, 40, 0.95));
+        page
+    }
+
+    #[test]
+    fn test_extension() {
+        assert_eq!(OcrExportFormat::Hocr.extension(), "hocr");
+        assert_eq!(OcrExportFormat::Alto.extension(), "alto.xml");
+    }
+
+    #[test]
+    fn test_hocr_structure_and_order() {
+        let hocr = OcrExporter::to_hocr(&[sample_page()], "book");
+
+        assert!(hocr.contains("<title>book</title>"));
+        assert!(hocr.contains("title=\"bbox 0 0 1000 1400; ppageno 0\""));
+        assert!(hocr.contains("title=\"bbox 100 100 500 140; x_wconf 95\">First &amp; &lt;b&gt;</span>"));
+        assert!(hocr.find("First").unwrap() < hocr.find("Second").unwrap());
+    }
+
+    #[test]
+    fn test_alto_structure_and_order() {
+        let alto = OcrExporter::to_alto(&[sample_page()], "book.pdf");
+
+        assert!(alto.contains("<fileName>book.pdf</fileName>"));
+        assert!(alto.contains("<Page ID=\"page_1\" PHYSICAL_IMG_NR=\"1\" WIDTH=\"1000\" HEIGHT=\"1400\">"));
+        assert!(alto.contains(
+            "HPOS=\"100\" VPOS=\"100\" WIDTH=\"400\" HEIGHT=\"40\" CONTENT=\"First &amp; &lt;b&gt;\" WC=\"0.95\""
+        ));
+        assert!(alto.find("First").unwrap() < alto.find("Second").unwrap());
+    }
+
+    #[test]
+    fn test_vertical_reading_order() {
+        let mut page = PageContent::new(1, (1000, 1400));
+        page.is_vertical = true;
+        page.add_block(block("left", 100, 100, 50, 800, 0.9));
+        page.add_block(block("right", 800, 100, 50, 800, 0.9));
+
+        let hocr = OcrExporter::to_hocr(&[page], "v");
+        assert!(hocr.find("right").unwrap() < hocr.find("left").unwrap());
+    }
+
+    #[test]
+    fn test_plain_text_order_and_pages() {
+        let mut vertical = PageContent::new(2, (1000, 1400));
+        vertical.is_vertical = true;
+        vertical.add_block(block("left", 100, 100, 50, 800, 0.9));
+        vertical.add_block(block("right\n  column ", 800, 100, 50, 800, 0.9));
+        vertical.add_block(block("   ", 0, 0, 10, 10, 1.0));
+
+        let text = OcrExporter::to_text(&[sample_page(), vertical]);
+        assert_eq!(text, "First & <b>\nSecond\n\x0cright\ncolumn\nleft\n");
+    }
+
+    #[test]
+    fn test_multiline_block_split() {
+        let horizontal = split_lines(&block("a\nb", 0, 0, 100, 40, 1.0), false);
+        assert_eq!(horizontal.len(), 2);
+        assert_eq!(horizontal[1].1.y, 20);
+        assert_eq!(horizontal[1].1.height, 20);
+
+        let vertical = split_lines(&block("a\nb", 0, 0, 100, 40, 1.0), true);
+        assert_eq!(vertical[0].1.x, 50);
+        assert_eq!(vertical[1].1.x, 0);
+    }
+
+    #[test]
+    fn test_empty_blocks_skipped() {
+        let mut page = PageContent::new(1, (100, 100));
+        page.add_block(block("   ", 0, 0, 10, 10, 1.0));
+        let alto = OcrExporter::to_alto(&[page], "x");
+        assert!(!alto.contains("<TextBlock"));
+    }
+
+    #[test]
+    fn test_write_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.hocr");
+        OcrExporter::write(&[sample_page()], OcrExportFormat::Hocr, "book", &path).unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("ocr_page"));
+    }
+}
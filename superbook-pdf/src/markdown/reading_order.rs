@@ -6,6 +6,9 @@
 use super::types::TextBlock;
 #[cfg(test)]
 use super::types::BoundingBox;
+use image::{Rgb, RgbImage};
+use imageproc::drawing::{draw_filled_circle_mut, draw_hollow_rect_mut, draw_line_segment_mut};
+use imageproc::rect::Rect;
 
 // ============================================================
 // Types
@@ -44,6 +47,15 @@ impl Default for ReadingOrderOptions {
     }
 }
 
+/// Block outline color of the reading order overlay
+const OVERLAY_BOX: Rgb<u8> = Rgb([0, 120, 255]);
+
+/// Arrow and marker color of the reading order overlay
+const OVERLAY_ARROW: Rgb<u8> = Rgb([220, 0, 0]);
+
+/// Marker number color of the reading order overlay
+const OVERLAY_LABEL: Rgb<u8> = Rgb([255, 255, 255]);
+
 // ============================================================
 // Reading Order Sorter
 // ============================================================
@@ -162,10 +174,10 @@ impl ReadingOrderSorter {
         for group in &mut groups {
             match direction {
                 TextDirection::Horizontal => {
-                    group.sort_by(|a, b| a.bbox.x.cmp(&b.bbox.x));
+                    group.sort_by_key(|b| b.bbox.x);
                 }
                 TextDirection::Vertical => {
-                    group.sort_by(|a, b| a.bbox.y.cmp(&b.bbox.y));
+                    group.sort_by_key(|b| b.bbox.y);
                 }
             }
         }
@@ -183,6 +195,66 @@ impl ReadingOrderSorter {
         groups
     }
 
+    /// Draw the reading order of `blocks` over a copy of `image` (debugging aid)
+    ///
+    /// Blocks are ordered with [`Self::sort`]; each gets its box outlined and
+    /// a numbered marker at its centroid, and an arrow runs from each
+    /// centroid to the next. Line width and marker size scale with the page.
+    pub fn overlay(image: &RgbImage, blocks: &[TextBlock], direction: TextDirection) -> RgbImage {
+        let mut canvas = image.clone();
+        let mut blocks = blocks.to_vec();
+        Self::sort(&mut blocks, direction);
+
+        let (width, height) = canvas.dimensions();
+        let stroke = (width.min(height) / 800).max(1) as i32;
+        for block in &blocks {
+            let bbox = block.bbox;
+            for inset in 0..stroke {
+                let (w, h) = (bbox.width as i32 - 2 * inset, bbox.height as i32 - 2 * inset);
+                if w > 0 && h > 0 {
+                    let rect = Rect::at(bbox.x as i32 + inset, bbox.y as i32 + inset).of_size(w as u32, h as u32);
+                    draw_hollow_rect_mut(&mut canvas, rect, OVERLAY_BOX);
+                }
+            }
+        }
+
+        let centroids: Vec<(f32, f32)> = blocks
+            .iter()
+            .map(|b| (b.bbox.x as f32 + b.bbox.width as f32 / 2.0, b.bbox.y as f32 + b.bbox.height as f32 / 2.0))
+            .collect();
+        // Marker radius fits the label ("12" needs more room than "3")
+        let dot = (stroke as u32 * 2).max(2);
+        let label_width = |index: usize| (index + 1).to_string().len() as u32 * 6 * dot;
+        let radius = |index: usize| (label_width(index).max(7 * dot) / 2 + dot) as f32;
+
+        let head = (stroke * 12) as f32;
+        for (index, pair) in centroids.windows(2).enumerate() {
+            let angle = (pair[1].1 - pair[0].1).atan2(pair[1].0 - pair[0].0);
+            let (cos, sin) = (angle.cos(), angle.sin());
+            // Run from marker edge to marker edge so the head stays visible
+            let from = (pair[0].0 + cos * radius(index), pair[0].1 + sin * radius(index));
+            let to = (pair[1].0 - cos * radius(index + 1), pair[1].1 - sin * radius(index + 1));
+            for offset in -stroke / 2..=stroke / 2 {
+                // Thicken perpendicular to the arrow
+                let shift = |p: (f32, f32)| (p.0 - sin * offset as f32, p.1 + cos * offset as f32);
+                draw_line_segment_mut(&mut canvas, shift(from), shift(to), OVERLAY_ARROW);
+                for side in [-1.0f32, 1.0] {
+                    let wing = angle + std::f32::consts::PI + side * 0.45;
+                    let tip = (to.0 + wing.cos() * head, to.1 + wing.sin() * head);
+                    draw_line_segment_mut(&mut canvas, shift(to), shift(tip), OVERLAY_ARROW);
+                }
+            }
+        }
+
+        for (index, &(x, y)) in centroids.iter().enumerate() {
+            draw_filled_circle_mut(&mut canvas, (x as i32, y as i32), radius(index) as i32, OVERLAY_ARROW);
+            let left = (x as u32).saturating_sub(label_width(index) / 2) + dot / 2;
+            let top = (y as u32).saturating_sub(7 * dot / 2);
+            draw_label(&mut canvas, &(index + 1).to_string(), left, top, dot);
+        }
+        canvas
+    }
+
     /// Calculate reading order index for a block
     pub fn reading_order_index(
         block: &TextBlock,
@@ -204,6 +276,27 @@ impl ReadingOrderSorter {
     }
 }
 
+/// Draw `text` in the built-in 5x7 font, one glyph dot as a `dot`-sized square
+fn draw_label(canvas: &mut RgbImage, text: &str, left: u32, top: u32, dot: u32) {
+    for (index, c) in text.chars().enumerate() {
+        let x0 = left + index as u32 * 6 * dot;
+        for (row, bits) in crate::compare::glyph(c).iter().enumerate() {
+            for col in 0..5u32 {
+                if bits & (0x10 >> col) == 0 {
+                    continue;
+                }
+                for y in top + row as u32 * dot..top + (row as u32 + 1) * dot {
+                    for x in x0 + col * dot..x0 + (col + 1) * dot {
+                        if x < canvas.width() && y < canvas.height() {
+                            canvas.put_pixel(x, y, OVERLAY_LABEL);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 // ============================================================
 // Tests
 // ============================================================
@@ -251,6 +344,30 @@ mod tests {
         assert_eq!(blocks[3].text, "B");
     }
 
+    #[test]
+    fn test_overlay_vertical_order() {
+        let page = RgbImage::from_pixel(400, 300, Rgb([255, 255, 255]));
+        let blocks = vec![
+            make_block("A", 40, 40, 40, 200),  // Left column: read second
+            make_block("B", 300, 40, 40, 200), // Right column: read first
+        ];
+        let overlay = ReadingOrderSorter::overlay(&page, &blocks, TextDirection::Vertical);
+        assert_eq!(overlay.dimensions(), (400, 300));
+        assert_eq!(*overlay.get_pixel(40, 100), OVERLAY_BOX);
+        // Markers at both centroids, arrow between them, page untouched elsewhere
+        assert_eq!(*overlay.get_pixel(313, 140), OVERLAY_ARROW);
+        assert_eq!(*overlay.get_pixel(53, 140), OVERLAY_ARROW);
+        assert_eq!(*overlay.get_pixel(190, 140), OVERLAY_ARROW);
+        assert_eq!(*overlay.get_pixel(200, 20), Rgb([255, 255, 255]));
+        // Arrow head at the left marker, pointing left
+        assert_eq!(*overlay.get_pixel(75, 143), OVERLAY_ARROW);
+        assert_eq!(*overlay.get_pixel(190, 100), Rgb([255, 255, 255]));
+
+        // The source image is unchanged and an empty block list only copies it
+        assert_eq!(*page.get_pixel(190, 140), Rgb([255, 255, 255]));
+        assert_eq!(ReadingOrderSorter::overlay(&page, &[], TextDirection::Vertical), page);
+    }
+
     #[test]
     fn test_detect_direction_horizontal() {
         let blocks = vec![
@@ -34,6 +34,15 @@ pub struct MarkdownRenderOptions {
 
     /// Maximum line length for wrapping
     pub max_line_length: usize,
+
+    /// Prefix blocks below this OCR confidence (0.0-1.0) with a review comment
+    pub low_confidence_threshold: Option<f64>,
+
+    /// Skip blocks marked as running headers/footers
+    pub exclude_running_heads: bool,
+
+    /// Join words hyphenated at line ends within a block
+    pub dehyphenate: bool,
 }
 
 impl Default for MarkdownRenderOptions {
@@ -46,6 +55,9 @@ impl Default for MarkdownRenderOptions {
             code_line_numbers: false,
             wrap_lines: false,
             max_line_length: 80,
+            low_confidence_threshold: None,
+            exclude_running_heads: false,
+            dehyphenate: false,
         }
     }
 }
@@ -96,10 +108,28 @@ impl MarkdownRenderer {
         let mut output = String::new();
 
         for block in &page.text_blocks {
+            if self.options.exclude_running_heads && block.running_head.is_some() {
+                continue;
+            }
+            if self
+                .options
+                .low_confidence_threshold
+                .is_some_and(|threshold| block.confidence < threshold)
+            {
+                output.push_str(&format!(
+                    "<!-- low-confidence: {:.0}% -->\n",
+                    block.confidence * 100.0
+                ));
+            }
+            let text = if self.options.dehyphenate {
+                super::dehyphenate::dehyphenate(&block.text)
+            } else {
+                block.text.clone()
+            };
             if block.is_heading && block.heading_level > 0 {
-                output.push_str(&self.render_heading(&block.text, block.heading_level));
+                output.push_str(&self.render_heading(&text, block.heading_level));
             } else {
-                output.push_str(&self.render_paragraph(&block.text));
+                output.push_str(&self.render_paragraph(&text));
             }
             output.push('\n');
         }
@@ -248,13 +278,11 @@ impl MarkdownRenderer {
     pub fn render_list_item(&self, text: &str) -> String {
         // Strip existing bullet if present and add Markdown bullet
         let text = text.trim();
-        let text = if text.starts_with("• ") || text.starts_with("・") {
-            &text[3..]
-        } else if text.starts_with("- ") {
-            &text[2..]
-        } else {
-            text
-        };
+        let text = text
+            .strip_prefix("• ")
+            .or_else(|| text.strip_prefix("・"))
+            .or_else(|| text.strip_prefix("- "))
+            .unwrap_or(text);
 
         format!("- {}\n", text.trim())
     }
@@ -452,6 +480,62 @@ mod tests {
         assert!(output.contains("World"));
     }
 
+    #[test]
+    fn test_render_page_marks_low_confidence() {
+        let mut page = PageContent::new(1, (800, 600));
+        let mut block = crate::markdown::types::TextBlock::new("Blurry".to_string(), BoundingBox::default());
+        block.confidence = 0.42;
+        page.add_block(block);
+        let mut block = crate::markdown::types::TextBlock::new("Clear".to_string(), BoundingBox::default());
+        block.confidence = 0.95;
+        page.add_block(block);
+
+        let output = MarkdownRenderer::new().render_page(&page);
+        assert!(!output.contains("low-confidence"));
+
+        let renderer = MarkdownRenderer::with_options(MarkdownRenderOptions {
+            low_confidence_threshold: Some(0.6),
+            ..Default::default()
+        });
+        let output = renderer.render_page(&page);
+        assert!(output.contains("<!-- low-confidence: 42% -->\nBlurry"));
+        assert_eq!(output.matches("low-confidence").count(), 1);
+    }
+
+    #[test]
+    fn test_render_page_excludes_running_heads() {
+        let mut page = PageContent::new(1, (800, 600));
+        let mut block = crate::markdown::types::TextBlock::new("Chapter 1".to_string(), BoundingBox::default());
+        block.running_head = Some(crate::markdown::RunningHeadPosition::Header);
+        page.add_block(block);
+        page.add_block(crate::markdown::types::TextBlock::new("Body".to_string(), BoundingBox::default()));
+
+        assert!(MarkdownRenderer::new().render_page(&page).contains("Chapter 1"));
+
+        let renderer = MarkdownRenderer::with_options(MarkdownRenderOptions {
+            exclude_running_heads: true,
+            ..Default::default()
+        });
+        let output = renderer.render_page(&page);
+        assert!(!output.contains("Chapter 1"));
+        assert!(output.contains("Body"));
+    }
+
+    #[test]
+    fn test_render_page_dehyphenate() {
+        let mut page = PageContent::new(1, (800, 600));
+        let text = "The associ-\nation of scanned books".to_string();
+        page.add_block(crate::markdown::types::TextBlock::new(text, BoundingBox::default()));
+
+        assert!(MarkdownRenderer::new().render_page(&page).contains("associ-\nation"));
+
+        let renderer = MarkdownRenderer::with_options(MarkdownRenderOptions {
+            dehyphenate: true,
+            ..Default::default()
+        });
+        assert!(renderer.render_page(&page).contains("The association\nof scanned books"));
+    }
+
     #[test]
     fn test_render_pages_with_breaks() {
         let options = MarkdownRenderOptions {
@@ -0,0 +1,356 @@
+//! Running Header/Footer Detection module
+//!
+//! Finds text blocks repeated in the same top/bottom band across many pages
+//! (chapter titles, book titles, page numbers) so they can be left out of
+//! reflowable exports or erased from the page images.
+
+use serde::{Deserialize, Serialize};
+
+use super::types::PageContent;
+use crate::page_number::{calc_overlap_center, Rectangle};
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Default top/bottom band height (fraction of the page height)
+const DEFAULT_BAND_RATIO: f32 = 0.12;
+
+/// Default minimum number of pages a running head appears on
+const DEFAULT_MIN_PAGES: usize = 3;
+
+/// Default minimum text similarity to group blocks
+const DEFAULT_MIN_SIMILARITY: f64 = 0.8;
+
+/// Default distance from the consensus position (fraction of the page size)
+const DEFAULT_POSITION_TOLERANCE: f32 = 0.05;
+
+// ============================================================
+// Types
+// ============================================================
+
+/// Where a running head sits on the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunningHeadPosition {
+    /// Running header (top band)
+    Header,
+    /// Running footer (bottom band)
+    Footer,
+}
+
+impl RunningHeadPosition {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Header => "header",
+            Self::Footer => "footer",
+        }
+    }
+}
+
+/// What the conversion pipeline does with running heads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunningHeadMode {
+    /// Keep them (no detection)
+    #[default]
+    Keep,
+    /// Leave them out of the OCR text layer and hOCR / ALTO export
+    Exclude,
+    /// Also paint them out of the page images
+    Erase,
+}
+
+impl RunningHeadMode {
+    /// Check if running heads are kept
+    pub fn is_keep(&self) -> bool {
+        *self == Self::Keep
+    }
+}
+
+/// A detected running header/footer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunningHeadTemplate {
+    /// Top or bottom band
+    pub position: RunningHeadPosition,
+    /// Normalized text shared by the pages (digit runs as `#`, no whitespace)
+    pub text: String,
+    /// Union of the matched blocks (x, y, width, height)
+    pub region: (u32, u32, u32, u32),
+    /// Pages (1-based) carrying it
+    pub pages: Vec<usize>,
+}
+
+/// Options for running head detection
+#[derive(Debug, Clone)]
+pub struct RunningHeadOptions {
+    /// Top/bottom band searched (fraction of the page height)
+    pub band_ratio: f32,
+    /// Minimum number of pages a running head appears on
+    pub min_pages: usize,
+    /// Minimum text similarity (character bigram Dice, 0.0-1.0) to group blocks
+    pub min_similarity: f64,
+    /// Maximum distance from the consensus position (fraction of the page size)
+    pub position_tolerance: f32,
+}
+
+impl Default for RunningHeadOptions {
+    fn default() -> Self {
+        Self {
+            band_ratio: DEFAULT_BAND_RATIO,
+            min_pages: DEFAULT_MIN_PAGES,
+            min_similarity: DEFAULT_MIN_SIMILARITY,
+            position_tolerance: DEFAULT_POSITION_TOLERANCE,
+        }
+    }
+}
+
+/// A band block waiting to be grouped
+struct Candidate {
+    page: usize,
+    block: usize,
+    text: String,
+}
+
+/// Band blocks with similar text
+struct Cluster {
+    position: RunningHeadPosition,
+    representative: String,
+    members: Vec<Candidate>,
+}
+
+// ============================================================
+// Running Head Detector
+// ============================================================
+
+/// Cross-page running header/footer detector
+pub struct RunningHeadDetector;
+
+impl RunningHeadDetector {
+    /// Detect running heads and mark their blocks (`TextBlock::running_head`)
+    ///
+    /// Blocks in the top/bottom band are grouped by position and similar
+    /// text; within a group, the consensus position of the odd and even
+    /// pages is found as for page numbers (`calc_overlap_center`) and only
+    /// blocks near it are kept. Groups left on at least `min_pages` pages
+    /// become templates.
+    pub fn detect(pages: &mut [PageContent], options: &RunningHeadOptions) -> Vec<RunningHeadTemplate> {
+        let mut clusters: Vec<Cluster> = Vec::new();
+        for (page_idx, page) in pages.iter().enumerate() {
+            let band = (page.page_size.1 as f32 * options.band_ratio) as u32;
+            for (block_idx, block) in page.text_blocks.iter().enumerate() {
+                let position = if block.bbox.bottom() <= band {
+                    RunningHeadPosition::Header
+                } else if block.bbox.y >= page.page_size.1.saturating_sub(band) {
+                    RunningHeadPosition::Footer
+                } else {
+                    continue;
+                };
+                let text = normalize(&block.text);
+                if text.is_empty() {
+                    continue;
+                }
+                let candidate = Candidate {
+                    page: page_idx,
+                    block: block_idx,
+                    text,
+                };
+                match clusters.iter_mut().find(|c| {
+                    c.position == position && similarity(&c.representative, &candidate.text) >= options.min_similarity
+                }) {
+                    Some(cluster) => cluster.members.push(candidate),
+                    None => clusters.push(Cluster {
+                        position,
+                        representative: candidate.text.clone(),
+                        members: vec![candidate],
+                    }),
+                }
+            }
+        }
+
+        let mut templates = Vec::new();
+        for cluster in clusters {
+            let members = Self::consensus_members(pages, cluster.members, options);
+            let mut page_numbers: Vec<usize> = members.iter().map(|m| pages[m.page].page_number).collect();
+            page_numbers.dedup();
+            if page_numbers.len() < options.min_pages {
+                continue;
+            }
+
+            let mut region = pages[members[0].page].text_blocks[members[0].block].bbox;
+            for member in &members {
+                let block = &mut pages[member.page].text_blocks[member.block];
+                region = region.merge(&block.bbox);
+                block.running_head = Some(cluster.position);
+            }
+            templates.push(RunningHeadTemplate {
+                position: cluster.position,
+                text: most_common(members.iter().map(|m| m.text.as_str())),
+                region: (region.x, region.y, region.width, region.height),
+                pages: page_numbers,
+            });
+        }
+        templates
+    }
+
+    /// Members near the consensus position of their side (odd/even pages)
+    fn consensus_members(
+        pages: &[PageContent],
+        members: Vec<Candidate>,
+        options: &RunningHeadOptions,
+    ) -> Vec<Candidate> {
+        let rect = |m: &Candidate| {
+            let bbox = pages[m.page].text_blocks[m.block].bbox;
+            Rectangle::new(bbox.x as i32, bbox.y as i32, bbox.width, bbox.height)
+        };
+        let is_odd = |m: &Candidate| pages[m.page].page_number % 2 == 1;
+        let center = |odd: bool| {
+            let rects: Vec<Rectangle> = members.iter().filter(|m| is_odd(m) == odd).map(rect).collect();
+            calc_overlap_center(&rects)
+        };
+        let (odd_center, even_center) = (center(true), center(false));
+
+        members
+            .into_iter()
+            .filter(|m| {
+                let consensus = if is_odd(m) { odd_center } else { even_center };
+                let (x, y) = rect(m).center();
+                let (width, height) = pages[m.page].page_size;
+                (x - consensus.x).unsigned_abs() as f32 <= width as f32 * options.position_tolerance
+                    && (y - consensus.y).unsigned_abs() as f32 <= height as f32 * options.position_tolerance
+            })
+            .collect()
+    }
+}
+
+/// Text for comparison: no whitespace, lowercase, digit runs as `#`
+fn normalize(text: &str) -> String {
+    let mut normalized = String::new();
+    for c in text.chars().filter(|c| !c.is_whitespace()) {
+        if c.is_ascii_digit() {
+            if !normalized.ends_with('#') {
+                normalized.push('#');
+            }
+        } else {
+            normalized.extend(c.to_lowercase());
+        }
+    }
+    normalized
+}
+
+/// Character bigram Dice coefficient (exact match for single characters)
+fn similarity(a: &str, b: &str) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    let bigrams = |s: &str| {
+        let chars: Vec<char> = s.chars().collect();
+        chars.windows(2).map(|w| (w[0], w[1])).collect::<Vec<_>>()
+    };
+    let (a, mut b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for bigram in a {
+        if let Some(pos) = b.iter().position(|other| *other == bigram) {
+            b.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+/// Most frequent string (first seen wins ties)
+fn most_common<'a>(texts: impl Iterator<Item = &'a str>) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for text in texts {
+        match counts.iter_mut().find(|(t, _)| *t == text) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((text, 1)),
+        }
+    }
+    let best = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    counts
+        .into_iter()
+        .find(|(_, count)| *count == best)
+        .map(|(text, _)| text.to_string())
+        .unwrap_or_default()
+}
+
+// ============================================================
+// Tests
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown::{BoundingBox, TextBlock};
+
+    fn page(number: usize, blocks: &[(&str, u32, u32)]) -> PageContent {
+        let mut page = PageContent::new(number, (1000, 1400));
+        for (text, x, y) in blocks {
+            page.add_block(TextBlock::new(text.to_string(), BoundingBox::new(*x, *y, 300, 40)));
+        }
+        page
+    }
+
+    #[test]
+    fn test_normalize_and_similarity() {
+        assert_eq!(normalize("第 12 章  Cats"), "第#章cats");
+        assert_eq!(similarity("第#章猫", "第#章猫"), 1.0);
+        assert!(similarity("吾輩は猫である", "吾輩は猫でぁる") >= 0.6);
+        assert_eq!(similarity("#", "猫"), 0.0);
+        assert_eq!(most_common(["a", "b", "b", "a", "c"].into_iter()), "a");
+    }
+
+    #[test]
+    fn test_detects_headers_and_footers_per_side() {
+        let mut pages: Vec<PageContent> = (1..=6)
+            .map(|n| {
+                // Book title on even pages (left), chapter title on odd pages (right)
+                let head = if n % 2 == 0 { ("吾輩は猫である", 100, 30) } else { ("第一章 猫の話", 600, 30) };
+                let foot = (n.to_string(), 480, 1330);
+                page(n, &[head, ("本文です。", 100, 600), (foot.0.as_str(), foot.1, foot.2)])
+            })
+            .collect();
+        // A chapter heading near the top of one page only
+        pages[0].add_block(TextBlock::new("序章".to_string(), BoundingBox::new(400, 60, 200, 60)));
+
+        let templates = RunningHeadDetector::detect(&mut pages, &RunningHeadOptions::default());
+        assert_eq!(templates.len(), 3);
+        let footer = templates.iter().find(|t| t.position == RunningHeadPosition::Footer).unwrap();
+        assert_eq!(footer.text, "#");
+        assert_eq!(footer.pages, vec![1, 2, 3, 4, 5, 6]);
+        let book_title = templates.iter().find(|t| t.text == "吾輩は猫である").unwrap();
+        assert_eq!(book_title.pages, vec![2, 4, 6]);
+        assert_eq!(book_title.region, (100, 30, 300, 40));
+
+        assert!(pages.iter().all(|p| p.text_blocks[1].running_head.is_none()));
+        assert_eq!(pages[0].text_blocks[0].running_head, Some(RunningHeadPosition::Header));
+        assert_eq!(pages[0].text_blocks[2].running_head, Some(RunningHeadPosition::Footer));
+        assert_eq!(pages[0].text_blocks[3].running_head, None);
+    }
+
+    #[test]
+    fn test_requires_min_pages_and_consensus_position() {
+        let mut pages = vec![
+            page(1, &[("吾輩は猫である", 100, 30)]),
+            page(3, &[("吾輩は猫である", 100, 30)]),
+            // Same text far from the others
+            page(5, &[("吾輩は猫である", 600, 30)]),
+        ];
+        assert!(RunningHeadDetector::detect(&mut pages, &RunningHeadOptions::default()).is_empty());
+
+        let options = RunningHeadOptions {
+            min_pages: 2,
+            ..Default::default()
+        };
+        let templates = RunningHeadDetector::detect(&mut pages, &options);
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].pages, vec![1, 3]);
+        assert!(pages[2].text_blocks[0].running_head.is_none());
+    }
+}
@@ -1,5 +1,6 @@
 //! Common types for the markdown module
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -131,6 +132,9 @@ pub struct TextBlock {
 
     /// Estimated heading level (1-6, 0 if not a heading)
     pub heading_level: u8,
+
+    /// Set if this block is a running header/footer
+    pub running_head: Option<super::running_heads::RunningHeadPosition>,
 }
 
 impl TextBlock {
@@ -143,6 +147,7 @@ impl TextBlock {
             confidence: 1.0,
             is_heading: false,
             heading_level: 0,
+            running_head: None,
         }
     }
 
@@ -227,6 +232,15 @@ pub struct MarkdownOptions {
 
     /// OCR language (default: Japanese)
     pub ocr_language: String,
+
+    /// Mark text blocks below this OCR confidence (percent) for review
+    pub mark_low_confidence: Option<f32>,
+
+    /// Leave detected running headers/footers out of the output
+    pub exclude_running_heads: bool,
+
+    /// Join words hyphenated at line ends
+    pub dehyphenate: bool,
 }
 
 impl Default for MarkdownOptions {
@@ -241,6 +255,9 @@ impl Default for MarkdownOptions {
             include_page_numbers: true,
             generate_metadata: true,
             ocr_language: "ja".to_string(),
+            mark_low_confidence: None,
+            exclude_running_heads: false,
+            dehyphenate: false,
         }
     }
 }
@@ -253,7 +270,8 @@ impl MarkdownOptions {
 }
 
 /// Text direction option
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TextDirectionOption {
     /// Auto-detect from content
     #[default]
@@ -264,6 +282,31 @@ pub enum TextDirectionOption {
     Horizontal,
 }
 
+impl TextDirectionOption {
+    /// Whether the direction is left to the detector
+    pub fn is_auto(&self) -> bool {
+        *self == TextDirectionOption::Auto
+    }
+
+    /// Forced vertical/horizontal decision, None for auto-detection
+    pub fn forced_vertical(self) -> Option<bool> {
+        match self {
+            TextDirectionOption::Auto => None,
+            TextDirectionOption::Vertical => Some(true),
+            TextDirectionOption::Horizontal => Some(false),
+        }
+    }
+
+    /// Name used on the command line and in config files
+    pub fn name(self) -> &'static str {
+        match self {
+            TextDirectionOption::Auto => "auto",
+            TextDirectionOption::Vertical => "vertical",
+            TextDirectionOption::Horizontal => "horizontal",
+        }
+    }
+}
+
 /// Builder for MarkdownOptions
 #[derive(Debug, Default)]
 pub struct MarkdownOptionsBuilder {
@@ -341,6 +384,27 @@ impl MarkdownOptionsBuilder {
         self
     }
 
+    /// Mark blocks below an OCR confidence (percent, clamped to 0-100)
+    #[must_use]
+    pub fn mark_low_confidence(mut self, percent: Option<f32>) -> Self {
+        self.options.mark_low_confidence = percent.map(|p| p.clamp(0.0, 100.0));
+        self
+    }
+
+    /// Leave running headers/footers out of the output
+    #[must_use]
+    pub fn exclude_running_heads(mut self, exclude: bool) -> Self {
+        self.options.exclude_running_heads = exclude;
+        self
+    }
+
+    /// Join words hyphenated at line ends
+    #[must_use]
+    pub fn dehyphenate(mut self, enabled: bool) -> Self {
+        self.options.dehyphenate = enabled;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> MarkdownOptions {
@@ -31,6 +31,7 @@
 //! ```
 
 use image::{GenericImageView, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -86,19 +87,53 @@ pub type Result<T> = std::result::Result<T, NormalizeError>;
 // ============================================================
 
 /// Resampler type for resizing
-#[derive(Debug, Clone, Copy, Default)]
+///
+/// Ordered from fastest/softest to slowest/sharpest. Lanczos3 keeps text
+/// crisp but can ring (halo) around hard edges; Bicubic is a good
+/// compromise for line art, Gaussian trades detail for smoothness on
+/// photos, and Nearest keeps hard pixel edges (screen tones, pixel art).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Resampler {
     /// Nearest neighbor (fastest, lowest quality)
     Nearest,
     /// Bilinear interpolation
+    #[serde(alias = "triangle")]
     Bilinear,
     /// Bicubic interpolation
+    #[serde(alias = "catmull_rom")]
     Bicubic,
+    /// Gaussian (soft, no ringing)
+    Gaussian,
     /// Lanczos3 (high quality)
     #[default]
     Lanczos3,
 }
 
+impl Resampler {
+    /// Equivalent `image` crate filter
+    pub fn filter_type(self) -> image::imageops::FilterType {
+        match self {
+            Resampler::Nearest => image::imageops::FilterType::Nearest,
+            Resampler::Bilinear => image::imageops::FilterType::Triangle,
+            Resampler::Bicubic => image::imageops::FilterType::CatmullRom,
+            Resampler::Gaussian => image::imageops::FilterType::Gaussian,
+            Resampler::Lanczos3 => image::imageops::FilterType::Lanczos3,
+        }
+    }
+
+    /// Name as used in configuration files
+    pub fn name(self) -> &'static str {
+        match self {
+            Resampler::Nearest => "nearest",
+            Resampler::Bilinear => "bilinear",
+            Resampler::Bicubic => "bicubic",
+            Resampler::Gaussian => "gaussian",
+            Resampler::Lanczos3 => "lanczos3",
+        }
+    }
+}
+
 /// Padding mode for background fill
 #[derive(Debug, Clone, Copy, Default)]
 pub enum PaddingMode {
@@ -510,16 +545,15 @@ impl ImageNormalizer {
             }
         }
 
+        // Fallback to white
         if count == 0 {
-            // Fallback to white
-            PaperColor::new(255, 255, 255)
-        } else {
-            PaperColor::new(
-                (sum_r / count) as u8,
-                (sum_g / count) as u8,
-                (sum_b / count) as u8,
-            )
+            return PaperColor::new(255, 255, 255);
         }
+        PaperColor::new(
+            (sum_r / count) as u8,
+            (sum_g / count) as u8,
+            (sum_b / count) as u8,
+        )
     }
 
     /// Sample paper colors from image corners
@@ -547,14 +581,7 @@ impl ImageNormalizer {
     // ============================================================
 
     fn resize_image(img: &RgbImage, width: u32, height: u32, resampler: Resampler) -> RgbImage {
-        let filter = match resampler {
-            Resampler::Nearest => image::imageops::FilterType::Nearest,
-            Resampler::Bilinear => image::imageops::FilterType::Triangle,
-            Resampler::Bicubic => image::imageops::FilterType::CatmullRom,
-            Resampler::Lanczos3 => image::imageops::FilterType::Lanczos3,
-        };
-
-        image::imageops::resize(img, width, height, filter)
+        image::imageops::resize(img, width, height, resampler.filter_type())
     }
 
     fn average_patch_color(image: &RgbImage, sx: u32, sy: u32, w: u32, h: u32) -> PaperColor {
@@ -626,14 +653,13 @@ impl ImageNormalizer {
         }
 
         if count == 0 {
-            Self::estimate_paper_color(image)
-        } else {
-            PaperColor::new(
-                (sum_r / count) as u8,
-                (sum_g / count) as u8,
-                (sum_b / count) as u8,
-            )
+            return Self::estimate_paper_color(image);
         }
+        PaperColor::new(
+            (sum_r / count) as u8,
+            (sum_g / count) as u8,
+            (sum_b / count) as u8,
+        )
     }
 
     fn average_paper_color(corners: &CornerColors) -> PaperColor {
@@ -1014,6 +1040,22 @@ mod tests {
         let _lan = Resampler::Lanczos3;
     }
 
+    #[test]
+    fn test_resampler_serde_and_filter() {
+        use image::imageops::FilterType;
+
+        assert_eq!(Resampler::default(), Resampler::Lanczos3);
+        assert_eq!(Resampler::Gaussian.filter_type(), FilterType::Gaussian);
+        assert_eq!(Resampler::Bicubic.filter_type(), FilterType::CatmullRom);
+
+        assert_eq!(serde_json::to_string(&Resampler::Lanczos3).unwrap(), "\"lanczos3\"");
+        // image crate filter names are accepted as aliases
+        let triangle: Resampler = serde_json::from_str("\"triangle\"").unwrap();
+        assert_eq!(triangle, Resampler::Bilinear);
+        let catmull: Resampler = serde_json::from_str("\"catmull_rom\"").unwrap();
+        assert_eq!(catmull, Resampler::Bicubic);
+    }
+
     #[test]
     fn test_padding_mode_variants() {
         let _solid = PaddingMode::Solid([255, 255, 255]);
@@ -0,0 +1,431 @@
+//! Output destinations for converted books
+//!
+//! The pipeline always writes into a local directory. An [`OutputSink`]
+//! then stores what one conversion produced (the PDF or CBZ and its
+//! sidecars: hOCR / ALTO exports, cache file, page manifest) at the final
+//! destination:
+//!
+//! - [`LocalSink`]: a directory on disk (the default; files stay in place)
+//! - `S3CompatibleSink` (feature `s3`): an S3-compatible object store
+//!   (AWS S3, MinIO, Ceph RGW, ...), selected with `--output s3://bucket/prefix`.
+//!   Uploads run through `curl --aws-sigv4`, so no HTTP client or AWS SDK
+//!   is linked in
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//! use superbook_pdf::output_sink;
+//!
+//! let sink = output_sink::from_output(Path::new("./output")).unwrap();
+//! assert!(!sink.is_remote());
+//! ```
+
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// URL scheme selecting [`S3CompatibleSink`]
+pub const S3_SCHEME: &str = "s3://";
+
+/// Errors from output sinks
+#[derive(Debug, Error)]
+pub enum OutputSinkError {
+    #[error("Invalid output location {0}: {1}")]
+    InvalidLocation(String, String),
+
+    #[error("{0} output needs the `{1}` feature (rebuild with --features {1})")]
+    FeatureDisabled(String, &'static str),
+
+    #[error("Missing credentials for {0}: set {1}")]
+    MissingCredentials(String, &'static str),
+
+    #[error("Upload of {0} failed: {1}")]
+    UploadFailed(PathBuf, String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Destination for the files of a finished conversion
+pub trait OutputSink: Send + Sync {
+    /// Destination for messages (directory path or `s3://` URL)
+    fn location(&self) -> String;
+
+    /// Store the local file `path` as `name` (relative, `/`-separated);
+    /// returns where it was stored
+    fn put(&self, path: &Path, name: &str) -> Result<String, OutputSinkError>;
+
+    /// Whether outputs leave the local disk; the pipeline then writes into
+    /// a staging directory that [`publish_dir`] empties after each book
+    fn is_remote(&self) -> bool {
+        false
+    }
+}
+
+/// Sink for the `--output` value: `s3://bucket/prefix` or a local directory
+pub fn from_output(output: &Path) -> Result<Box<dyn OutputSink>, OutputSinkError> {
+    let text = output.to_string_lossy();
+    if text.starts_with(S3_SCHEME) {
+        #[cfg(feature = "s3")]
+        return Ok(Box::new(S3CompatibleSink::from_env(&text)?));
+        #[cfg(not(feature = "s3"))]
+        return Err(OutputSinkError::FeatureDisabled(text.to_string(), "s3"));
+    }
+    Ok(Box::new(LocalSink::new(output)))
+}
+
+/// Store every file under `dir` through `sink`, named by its path relative
+/// to `dir`, and delete the local copy (remote sinks only)
+///
+/// Returns the stored locations in file name order.
+pub fn publish_dir(sink: &dyn OutputSink, dir: &Path) -> Result<Vec<String>, OutputSinkError> {
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+    files.sort();
+
+    let mut stored = Vec::with_capacity(files.len());
+    for file in files {
+        let name = file
+            .strip_prefix(dir)
+            .unwrap_or(&file)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        stored.push(sink.put(&file, &name)?);
+        if sink.is_remote() {
+            std::fs::remove_file(&file)?;
+        }
+    }
+    Ok(stored)
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// ============================================================
+// Local directory
+// ============================================================
+
+/// Local output directory
+#[derive(Debug, Clone)]
+pub struct LocalSink {
+    dir: PathBuf,
+}
+
+impl LocalSink {
+    /// Sink writing under `dir`
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Output directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl OutputSink for LocalSink {
+    fn location(&self) -> String {
+        self.dir.display().to_string()
+    }
+
+    /// Copy `path` to `dir/name`; a file already there is left as is
+    fn put(&self, path: &Path, name: &str) -> Result<String, OutputSinkError> {
+        let target = self.dir.join(name);
+        if target != path {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::copy(path, &target)?;
+        }
+        Ok(target.display().to_string())
+    }
+}
+
+// ============================================================
+// S3-compatible object store
+// ============================================================
+
+/// curl command used for uploads
+#[cfg(feature = "s3")]
+pub const CURL_TOOL: &str = "curl";
+
+/// Region used when neither `AWS_REGION` nor `AWS_DEFAULT_REGION` is set
+#[cfg(feature = "s3")]
+pub const DEFAULT_S3_REGION: &str = "us-east-1";
+
+/// S3-compatible object store (`s3://bucket/prefix`)
+///
+/// Credentials and endpoint come from the usual AWS variables:
+/// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, optional
+/// `AWS_SESSION_TOKEN`, `AWS_REGION` / `AWS_DEFAULT_REGION`, and
+/// `AWS_ENDPOINT_URL_S3` / `AWS_ENDPOINT_URL` for non-AWS stores.
+/// Objects are addressed path-style (`endpoint/bucket/key`), which every
+/// S3-compatible store accepts.
+#[cfg(feature = "s3")]
+#[derive(Clone)]
+pub struct S3CompatibleSink {
+    /// Bucket name
+    pub bucket: String,
+    /// Key prefix without leading or trailing `/` (may be empty)
+    pub prefix: String,
+    /// Endpoint URL without trailing `/`
+    pub endpoint: String,
+    /// SigV4 signing region
+    pub region: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+}
+
+#[cfg(feature = "s3")]
+impl std::fmt::Debug for S3CompatibleSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Keys stay out of debug output and logs
+        f.debug_struct("S3CompatibleSink")
+            .field("bucket", &self.bucket)
+            .field("prefix", &self.prefix)
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "s3")]
+impl S3CompatibleSink {
+    /// Sink for `url` with credentials and endpoint from the environment
+    pub fn from_env(url: &str) -> Result<Self, OutputSinkError> {
+        Self::from_vars(url, |name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    /// [`Self::from_env`] with variables looked up through `var`
+    pub fn from_vars(url: &str, var: impl Fn(&str) -> Option<String>) -> Result<Self, OutputSinkError> {
+        let (bucket, prefix) = parse_s3_url(url)?;
+        let access_key = var("AWS_ACCESS_KEY_ID")
+            .ok_or_else(|| OutputSinkError::MissingCredentials(url.to_string(), "AWS_ACCESS_KEY_ID"))?;
+        let secret_key = var("AWS_SECRET_ACCESS_KEY")
+            .ok_or_else(|| OutputSinkError::MissingCredentials(url.to_string(), "AWS_SECRET_ACCESS_KEY"))?;
+        let region = var("AWS_REGION")
+            .or_else(|| var("AWS_DEFAULT_REGION"))
+            .unwrap_or_else(|| DEFAULT_S3_REGION.to_string());
+        let endpoint = var("AWS_ENDPOINT_URL_S3")
+            .or_else(|| var("AWS_ENDPOINT_URL"))
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        Ok(Self {
+            bucket,
+            prefix,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            region,
+            access_key,
+            secret_key,
+            session_token: var("AWS_SESSION_TOKEN"),
+        })
+    }
+
+    /// Object key for `name` under the prefix
+    pub fn key(&self, name: &str) -> String {
+        if self.prefix.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.prefix, name)
+        }
+    }
+
+    /// HTTPS URL of the object `key`
+    pub fn object_url(&self, key: &str) -> String {
+        format!("{}/{}/{}", self.endpoint, self.bucket, encode_key(key))
+    }
+
+    /// curl configuration carrying the credentials, passed as a file so
+    /// the keys do not show up in the process list
+    fn curl_config(&self) -> String {
+        let quote = |value: &str| value.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut config = format!("user = \"{}:{}\"\n", quote(&self.access_key), quote(&self.secret_key));
+        if let Some(token) = &self.session_token {
+            config.push_str(&format!("header = \"x-amz-security-token: {}\"\n", quote(token)));
+        }
+        config
+    }
+
+    /// Upload command for `path` as `key` (credentials in `config_path`)
+    fn upload_command(&self, path: &Path, key: &str, config_path: &Path) -> std::process::Command {
+        let mut cmd = crate::tools::command(CURL_TOOL);
+        cmd.arg("--silent")
+            .arg("--show-error")
+            .arg("--fail")
+            .arg("--config")
+            .arg(config_path)
+            .arg("--aws-sigv4")
+            .arg(format!("aws:amz:{}:s3", self.region))
+            .arg("--upload-file")
+            .arg(path)
+            .arg(self.object_url(key));
+        cmd
+    }
+}
+
+#[cfg(feature = "s3")]
+impl OutputSink for S3CompatibleSink {
+    fn location(&self) -> String {
+        format!("{}{}/{}", S3_SCHEME, self.bucket, self.prefix)
+    }
+
+    fn put(&self, path: &Path, name: &str) -> Result<String, OutputSinkError> {
+        use std::io::Write;
+
+        let key = self.key(name);
+        // tempfile creates the file readable by the owner only
+        let mut config = tempfile::NamedTempFile::new()?;
+        config.write_all(self.curl_config().as_bytes())?;
+        config.flush()?;
+
+        let output = crate::tools::output(&mut self.upload_command(path, &key, config.path()))
+            .map_err(|e| OutputSinkError::UploadFailed(path.to_path_buf(), e.to_string()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(OutputSinkError::UploadFailed(path.to_path_buf(), stderr.trim().to_string()));
+        }
+        Ok(format!("{}{}/{}", S3_SCHEME, self.bucket, key))
+    }
+
+    fn is_remote(&self) -> bool {
+        true
+    }
+}
+
+/// Split `s3://bucket/prefix` into bucket and prefix (without slashes at either end)
+#[cfg(feature = "s3")]
+pub fn parse_s3_url(url: &str) -> Result<(String, String), OutputSinkError> {
+    let invalid = |reason: &str| OutputSinkError::InvalidLocation(url.to_string(), reason.to_string());
+    let rest = url.strip_prefix(S3_SCHEME).ok_or_else(|| invalid("expected s3://bucket/prefix"))?;
+    let (bucket, prefix) = rest.split_once('/').unwrap_or((rest, ""));
+    if bucket.is_empty() {
+        return Err(invalid("missing bucket name"));
+    }
+    if !bucket.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.') {
+        return Err(invalid("bucket names use lowercase letters, digits, '-' and '.'"));
+    }
+    Ok((bucket.to_string(), prefix.trim_matches('/').to_string()))
+}
+
+/// Percent-encode an object key for the URL path (keeps `/` and unreserved characters)
+#[cfg(feature = "s3")]
+fn encode_key(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for byte in key.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => out.push(byte as char),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_sink_publish() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let sink = LocalSink::new(&out);
+        assert!(!sink.is_remote());
+
+        let staging = dir.path().join("staging");
+        std::fs::create_dir_all(staging.join("book_converted")).unwrap();
+        std::fs::write(staging.join("book.pdf"), b"%PDF").unwrap();
+        std::fs::write(staging.join("book_converted").join("manifest.json"), b"{}").unwrap();
+
+        let stored = publish_dir(&sink, &staging).unwrap();
+        assert_eq!(
+            stored,
+            vec![
+                out.join("book.pdf").display().to_string(),
+                out.join("book_converted/manifest.json").display().to_string()
+            ]
+        );
+        assert_eq!(std::fs::read(out.join("book.pdf")).unwrap(), b"%PDF");
+        // Local sinks keep the source files
+        assert!(staging.join("book.pdf").exists());
+
+        // A file already in place is not copied onto itself
+        assert_eq!(sink.put(&out.join("book.pdf"), "book.pdf").unwrap(), out.join("book.pdf").display().to_string());
+        assert_eq!(std::fs::read(out.join("book.pdf")).unwrap(), b"%PDF");
+    }
+
+    #[test]
+    fn test_from_output() {
+        assert!(!from_output(Path::new("./output")).unwrap().is_remote());
+        #[cfg(not(feature = "s3"))]
+        assert!(matches!(
+            from_output(Path::new("s3://bucket/books")),
+            Err(OutputSinkError::FeatureDisabled(_, "s3"))
+        ));
+    }
+
+    #[cfg(feature = "s3")]
+    fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let pairs: Vec<(String, String)> = pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        move |name| pairs.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone())
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_parse_s3_url() {
+        assert_eq!(parse_s3_url("s3://books/scans/2024/").unwrap(), ("books".into(), "scans/2024".into()));
+        assert_eq!(parse_s3_url("s3://books").unwrap(), ("books".into(), String::new()));
+        assert!(parse_s3_url("s3:///prefix").is_err());
+        assert!(parse_s3_url("s3://Books/x").is_err());
+        assert!(parse_s3_url("https://books/x").is_err());
+    }
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_s3_sink_from_vars() {
+        let keys = [("AWS_ACCESS_KEY_ID", "AKID"), ("AWS_SECRET_ACCESS_KEY", "se\"cret")];
+        let sink = S3CompatibleSink::from_vars("s3://books/scans", vars(&keys)).unwrap();
+        assert_eq!(sink.region, DEFAULT_S3_REGION);
+        assert_eq!(sink.endpoint, "https://s3.us-east-1.amazonaws.com");
+        assert_eq!(sink.location(), "s3://books/scans");
+        assert_eq!(
+            sink.object_url(&sink.key("本 1.pdf")),
+            "https://s3.us-east-1.amazonaws.com/books/scans/%E6%9C%AC%201.pdf"
+        );
+        assert_eq!(sink.curl_config(), "user = \"AKID:se\\\"cret\"\n");
+        assert!(!format!("{:?}", sink).contains("AKID"));
+
+        let minio = [
+            ("AWS_ACCESS_KEY_ID", "AKID"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+            ("AWS_SESSION_TOKEN", "token"),
+            ("AWS_REGION", "eu-west-1"),
+            ("AWS_ENDPOINT_URL", "http://localhost:9000/"),
+        ];
+        let sink = S3CompatibleSink::from_vars("s3://books", vars(&minio)).unwrap();
+        assert_eq!(sink.object_url(&sink.key("book.pdf")), "http://localhost:9000/books/book.pdf");
+        assert!(sink.curl_config().contains("x-amz-security-token: token"));
+
+        let cmd = sink.upload_command(Path::new("/tmp/book.pdf"), "book.pdf", Path::new("/tmp/curl.conf"));
+        let args: Vec<String> = cmd.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert!(args.windows(2).any(|w| w == ["--aws-sigv4", "aws:amz:eu-west-1:s3"]));
+        assert!(args.windows(2).any(|w| w == ["--upload-file", "/tmp/book.pdf"]));
+        // Credentials only in the config file
+        assert!(!args.iter().any(|a| a.contains("secret")));
+
+        assert!(matches!(
+            S3CompatibleSink::from_vars("s3://books", vars(&[("AWS_ACCESS_KEY_ID", "AKID")])),
+            Err(OutputSinkError::MissingCredentials(_, "AWS_SECRET_ACCESS_KEY"))
+        ));
+    }
+}
@@ -0,0 +1,667 @@
+//! Page image export for external OCR
+//!
+//! With `--output-format images` the final page images are written to a
+//! directory as `page_0001.png`, `page_0002.png`, ... together with a
+//! `manifest.json` describing each page:
+//!
+//! - physical page (1-based position in the source document) and the
+//!   detected logical page label, if page numbers were detected
+//! - detected text direction
+//! - size of the extracted source page and of the final image
+//! - the geometric transforms (crop, scale, pad, rotate) applied in between
+//!
+//! Final image pixels map to PDF points by `72 / dpi`, so OCR run on the
+//! exported images needs no further mapping; OCR run on the source pages is
+//! mapped forward through the transforms ([`ManifestPage::from_source`]).
+//! The `assemble` command reads the manifest and hOCR files named after
+//! the pages (`page_0001.hocr`) to build the searchable PDF.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::PageManifest;
+//! use std::path::Path;
+//!
+//! let manifest = PageManifest::load(Path::new("book_converted")).unwrap();
+//! for page in &manifest.pages {
+//!     let (x, y) = page.to_source(100.0, 200.0);
+//!     println!("{} (p.{}): ({x:.0}, {y:.0}) in the source", page.file, page.physical_page);
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+use crate::yomitoku::{OcrResult, TextBlock, TextDirection};
+
+/// Manifest file name inside the page directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Manifest format version
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Extensions of returned OCR files, in lookup order
+pub const HOCR_EXTENSIONS: [&str; 2] = ["hocr", "html"];
+
+/// Page manifest errors
+#[derive(Debug, Error)]
+pub enum PageManifestError {
+    #[error("Unsupported manifest version {0} (expected {MANIFEST_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("Invalid manifest: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, PageManifestError>;
+
+/// A geometric transform applied to a page image
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PageTransform {
+    /// Region `(x, y, width, height)` of the input kept
+    Crop { x: u32, y: u32, width: u32, height: u32 },
+    /// Resized by the given factors
+    Scale { x: f64, y: f64 },
+    /// Input placed at `(x, y)` on a `width` x `height` canvas
+    Pad { x: i32, y: i32, width: u32, height: u32 },
+    /// Rotated by `degrees` about the center onto a canvas grown from
+    /// `input_width` x `input_height` to `width` x `height`
+    Rotate {
+        degrees: f64,
+        input_width: u32,
+        input_height: u32,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl PageTransform {
+    /// Map a point of the input image to the output image
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        match *self {
+            PageTransform::Crop { x: left, y: top, .. } => (x - left as f64, y - top as f64),
+            PageTransform::Scale { x: sx, y: sy } => (x * sx, y * sy),
+            PageTransform::Pad { x: left, y: top, .. } => (x + left as f64, y + top as f64),
+            PageTransform::Rotate { .. } => {
+                let (cos_a, sin_a, (cx, cy), (ncx, ncy)) = self.rotation();
+                let (dx, dy) = (x - cx, y - cy);
+                (dx * cos_a - dy * sin_a + ncx, dx * sin_a + dy * cos_a + ncy)
+            }
+        }
+    }
+
+    /// Map a point of the output image back to the input image
+    pub fn invert(&self, x: f64, y: f64) -> (f64, f64) {
+        match *self {
+            PageTransform::Crop { x: left, y: top, .. } => (x + left as f64, y + top as f64),
+            PageTransform::Scale { x: sx, y: sy } => (x / sx, y / sy),
+            PageTransform::Pad { x: left, y: top, .. } => (x - left as f64, y - top as f64),
+            PageTransform::Rotate { .. } => {
+                // Same mapping as the deskew rotation uses per pixel
+                let (cos_a, sin_a, (cx, cy), (ncx, ncy)) = self.rotation();
+                let (dx, dy) = (x - ncx, y - ncy);
+                (dx * cos_a + dy * sin_a + cx, -dx * sin_a + dy * cos_a + cy)
+            }
+        }
+    }
+
+    /// cos, sin, input center and output center of a rotation
+    fn rotation(&self) -> (f64, f64, (f64, f64), (f64, f64)) {
+        match *self {
+            PageTransform::Rotate {
+                degrees,
+                input_width,
+                input_height,
+                width,
+                height,
+            } => {
+                let angle = degrees.to_radians();
+                (
+                    angle.cos(),
+                    angle.sin(),
+                    (input_width as f64 / 2.0, input_height as f64 / 2.0),
+                    (width as f64 / 2.0, height as f64 / 2.0),
+                )
+            }
+            _ => (1.0, 0.0, (0.0, 0.0), (0.0, 0.0)),
+        }
+    }
+}
+
+/// Geometry of one page through the image stages
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PageGeometry {
+    /// Size of the extracted page image
+    pub source_size: (u32, u32),
+    /// Transforms applied, in order
+    pub transforms: Vec<PageTransform>,
+    /// Inverted as a negative scan before the other stages
+    pub inverted: bool,
+    /// Highlighter marks were found and removed
+    pub marked: bool,
+    /// Generated "page N unavailable" image for a page that failed
+    pub placeholder: bool,
+}
+
+impl PageGeometry {
+    /// Geometry of an untransformed page image
+    pub fn of_image(path: &Path) -> Self {
+        Self {
+            source_size: image::image_dimensions(path).unwrap_or((0, 0)),
+            transforms: Vec::new(),
+            inverted: false,
+            marked: false,
+            placeholder: false,
+        }
+    }
+
+    /// Pixels per inch of the extracted page, given the physical page size
+    /// in points (width and height are matched to the image orientation);
+    /// `None` for placeholders and unknown sizes
+    pub fn scan_dpi(&self, page_size_pt: (f64, f64)) -> Option<f64> {
+        let (width, height) = self.source_size;
+        if self.placeholder || width == 0 || height == 0 || page_size_pt.0 <= 0.0 || page_size_pt.1 <= 0.0 {
+            return None;
+        }
+        let (short_pt, long_pt) = (page_size_pt.0.min(page_size_pt.1), page_size_pt.0.max(page_size_pt.1));
+        let height_pt = if width > height { short_pt } else { long_pt };
+        Some(height as f64 / (height_pt / 72.0))
+    }
+
+    /// Final image pixels per inch of the physical page: the scan DPI times
+    /// the vertical scaling applied (crops and padding keep the density)
+    pub fn effective_dpi(&self, page_size_pt: (f64, f64)) -> Option<f64> {
+        let scale: f64 = self
+            .transforms
+            .iter()
+            .map(|transform| match transform {
+                PageTransform::Scale { y, .. } => *y,
+                _ => 1.0,
+            })
+            .product();
+        self.scan_dpi(page_size_pt).map(|dpi| dpi * scale)
+    }
+}
+
+/// Entry of one exported page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestPage {
+    /// Image file name inside the page directory
+    pub file: String,
+    /// Page in the source document (1-based)
+    pub physical_page: usize,
+    /// Logical page label ("iv", "12"; `None` if page numbers were not detected)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_page: Option<String>,
+    /// Vertical (tategaki) text detected
+    pub vertical: bool,
+    /// Extracted source page size (width, height) in pixels
+    pub source_size: (u32, u32),
+    /// Final image size (width, height) in pixels
+    pub size: (u32, u32),
+    /// Transforms from the source page to the final image, in order
+    pub transforms: Vec<PageTransform>,
+    /// Page was a negative scan and got inverted (`--invert`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inverted: bool,
+    /// Placeholder for a page that failed (`--on-page-error placeholder`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub placeholder: bool,
+    /// DPI the page was extracted at when lowered below `dpi` to fit the
+    /// page size cap (`--max-dimension`); `source_size` is at this DPI
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction_dpi: Option<u32>,
+}
+
+impl ManifestPage {
+    /// Map a point of the source page to the final image
+    pub fn from_source(&self, x: f64, y: f64) -> (f64, f64) {
+        self.transforms.iter().fold((x, y), |(x, y), t| t.apply(x, y))
+    }
+
+    /// Map a point of the final image back to the source page
+    pub fn to_source(&self, x: f64, y: f64) -> (f64, f64) {
+        self.transforms.iter().rev().fold((x, y), |(x, y), t| t.invert(x, y))
+    }
+
+    /// Map a source page box `(x1, y1, x2, y2)` to the final image,
+    /// clamped to the image
+    pub fn box_from_source(&self, bbox: (u32, u32, u32, u32)) -> (u32, u32, u32, u32) {
+        let corners = [(bbox.0, bbox.1), (bbox.2, bbox.1), (bbox.0, bbox.3), (bbox.2, bbox.3)]
+            .map(|(x, y)| self.from_source(x as f64, y as f64));
+        let clamp_x = |v: f64| v.round().clamp(0.0, self.size.0 as f64) as u32;
+        let clamp_y = |v: f64| v.round().clamp(0.0, self.size.1 as f64) as u32;
+        (
+            clamp_x(corners.iter().map(|c| c.0).fold(f64::INFINITY, f64::min)),
+            clamp_y(corners.iter().map(|c| c.1).fold(f64::INFINITY, f64::min)),
+            clamp_x(corners.iter().map(|c| c.0).fold(f64::NEG_INFINITY, f64::max)),
+            clamp_y(corners.iter().map(|c| c.1).fold(f64::NEG_INFINITY, f64::max)),
+        )
+    }
+}
+
+/// Manifest of an exported page directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageManifest {
+    /// Format version ([`MANIFEST_VERSION`])
+    pub version: u32,
+    /// Source file name
+    pub source: String,
+    /// Output DPI (final image pixels per inch in the PDF)
+    pub dpi: u32,
+    pub pages: Vec<ManifestPage>,
+    /// Source pages (1-based) that failed, whether left out or replaced by placeholders
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_pages: Vec<usize>,
+    /// Median final image pixels per inch of the physical page (`None`
+    /// when the source has no physical page size, e.g. comic archives)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_dpi: Option<f64>,
+    /// Running headers/footers detected in the OCR text (`--running-heads`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub running_heads: Vec<crate::markdown::RunningHeadTemplate>,
+    /// Written with the quick (draft) preset (`--quick`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub draft: bool,
+}
+
+impl PageManifest {
+    /// File name of the `index`-th (0-based) exported page
+    pub fn page_file_name(index: usize) -> String {
+        format!("page_{:04}.png", index + 1)
+    }
+
+    /// Read `manifest.json` from a page directory
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest: Self = serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST_FILE))?)?;
+        if manifest.version != MANIFEST_VERSION {
+            return Err(PageManifestError::UnsupportedVersion(manifest.version));
+        }
+        Ok(manifest)
+    }
+
+    /// Write `manifest.json` into a page directory
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::write(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Returned OCR file of a page (`page_0001.hocr` or `.html`) in `dir`
+    pub fn ocr_file(dir: &Path, page: &ManifestPage) -> Option<PathBuf> {
+        let stem = Path::new(&page.file).file_stem()?;
+        HOCR_EXTENSIONS
+            .iter()
+            .map(|ext| dir.join(stem).with_extension(ext))
+            .find(|path| path.is_file())
+    }
+}
+
+/// A recognized word of an hOCR page
+#[derive(Debug, Clone, PartialEq)]
+pub struct HocrWord {
+    pub text: String,
+    /// Bounding box (x1, y1, x2, y2) in pixels
+    pub bbox: (u32, u32, u32, u32),
+    /// Confidence (0.0-1.0), if given (`x_wconf`)
+    pub confidence: Option<f32>,
+}
+
+/// Words of one hOCR page
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HocrPage {
+    /// Page size from the `ocr_page` bbox
+    pub size: Option<(u32, u32)>,
+    pub words: Vec<HocrWord>,
+}
+
+impl HocrPage {
+    /// Parse hOCR (words from `ocrx_word`, or `ocr_line` when the engine
+    /// emits no words); only the first page is read
+    pub fn parse(html: &str) -> Self {
+        let mut page = HocrPage::default();
+        let mut lines = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            let Some(end) = rest.find('>') else {
+                break;
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            let Some(class) = quoted_attribute(tag, "class") else {
+                continue;
+            };
+            let title = quoted_attribute(tag, "title").unwrap_or_default();
+            match class {
+                "ocr_page" => {
+                    if page.size.is_some() {
+                        break;
+                    }
+                    page.size = title_bbox(title).map(|(_, _, x2, y2)| (x2, y2));
+                }
+                "ocrx_word" | "ocr_line" | "ocr_caption" | "ocr_header" | "ocr_textfloat" => {
+                    let Some(bbox) = title_bbox(title) else {
+                        continue;
+                    };
+                    let word = HocrWord {
+                        text: element_text(tag, rest),
+                        bbox,
+                        confidence: title_value(title, "x_wconf").map(|c| (c / 100.0).clamp(0.0, 1.0) as f32),
+                    };
+                    if word.text.is_empty() {
+                        continue;
+                    }
+                    if class == "ocrx_word" {
+                        page.words.push(word);
+                    } else {
+                        lines.push(word);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if page.words.is_empty() {
+            page.words = lines;
+        }
+        page
+    }
+
+    /// Convert to an OCR result on `page` of a manifest
+    ///
+    /// Boxes are taken as final image pixels; when the hOCR page has the
+    /// source page size instead, they are mapped through the page
+    /// transforms, and any other size is scaled to the final image.
+    pub fn to_ocr_result(&self, image_path: &Path, page: &ManifestPage, dpi: u32) -> OcrResult {
+        let from_source = matches!(self.size, Some(size)
+            if size != page.size && size == page.source_size && !page.transforms.is_empty());
+        let scale = match self.size {
+            Some((w, h)) if !from_source && (w, h) != page.size && w > 0 && h > 0 => {
+                Some((page.size.0 as f64 / w as f64, page.size.1 as f64 / h as f64))
+            }
+            _ => None,
+        };
+        let map = |bbox: (u32, u32, u32, u32)| match scale {
+            _ if from_source => page.box_from_source(bbox),
+            Some((sx, sy)) => {
+                let x = |v: u32| (v as f64 * sx).round() as u32;
+                let y = |v: u32| (v as f64 * sy).round() as u32;
+                (x(bbox.0), y(bbox.1), x(bbox.2), y(bbox.3))
+            }
+            None => bbox,
+        };
+        let direction = if page.vertical {
+            TextDirection::Vertical
+        } else {
+            TextDirection::Horizontal
+        };
+
+        let text_blocks: Vec<TextBlock> = self
+            .words
+            .iter()
+            .map(|word| {
+                let bbox = map(word.bbox);
+                let (width, height) = (bbox.2.saturating_sub(bbox.0), bbox.3.saturating_sub(bbox.1));
+                // The glyph size is the box's short side
+                let size_px = if page.vertical { width } else { height };
+                TextBlock {
+                    text: word.text.clone(),
+                    bbox,
+                    confidence: word.confidence.unwrap_or(1.0),
+                    direction,
+                    font_size: Some((size_px.max(1) as f64 * 72.0 / dpi.max(1) as f64) as f32),
+                }
+            })
+            .collect();
+        let confidence = if text_blocks.is_empty() {
+            0.0
+        } else {
+            text_blocks.iter().map(|b| b.confidence).sum::<f32>() / text_blocks.len() as f32
+        };
+        OcrResult {
+            input_path: image_path.to_path_buf(),
+            text_blocks,
+            confidence,
+            processing_time: Duration::ZERO,
+            text_direction: direction,
+        }
+    }
+}
+
+/// Value of a quoted attribute (`"` or `'`)
+fn quoted_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+    let quote = tag[start..].chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &tag[start + 1..];
+    Some(&value[..value.find(quote)?])
+}
+
+/// `bbox x1 y1 x2 y2` of an hOCR title
+fn title_bbox(title: &str) -> Option<(u32, u32, u32, u32)> {
+    let values: Vec<u32> = title_property(title, "bbox")?
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<std::result::Result<_, _>>()
+        .ok()?;
+    match values[..] {
+        [x1, y1, x2, y2] if x2 >= x1 && y2 >= y1 => Some((x1, y1, x2, y2)),
+        _ => None,
+    }
+}
+
+/// Numeric hOCR title property
+fn title_value(title: &str, name: &str) -> Option<f64> {
+    title_property(title, name)?.trim().parse().ok()
+}
+
+/// Arguments of a `name ...` property in an hOCR title (`;`-separated)
+fn title_property<'a>(title: &'a str, name: &str) -> Option<&'a str> {
+    title
+        .split(';')
+        .map(str::trim)
+        .find_map(|prop| prop.strip_prefix(name).filter(|args| args.starts_with(' ')))
+}
+
+/// Text content of the element opened by `tag`, with nested tags removed
+fn element_text(tag: &str, rest: &str) -> String {
+    let name = tag.split_whitespace().next().unwrap_or_default();
+    let close = rest.find(&format!("</{}>", name)).unwrap_or(rest.len());
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in rest[..close].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    crate::text_layer::unescape(text.split_whitespace().collect::<Vec<_>>().join(" ").as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOCR: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<html xmlns="http://www.w3.org/1999/xhtml">
+ <body>
+  <div class='ocr_page' id='page_1' title='image "page_0001.png"; bbox 0 0 800 1000; ppageno 0'>
+   <span class='ocr_line' id='line_1_1' title="bbox 100 200 400 240; baseline 0 -5">
+    <span class='ocrx_word' id='word_1_1' title='bbox 100 200 220 240; x_wconf 96'>Chapter</span>
+    <span class='ocrx_word' id='word_1_2' title='bbox 240 200 400 240; x_wconf 88'><strong>One&amp;Two</strong></span>
+   </span>
+  </div>
+ </body>
+</html>"#;
+
+    fn page(transforms: Vec<PageTransform>, source_size: (u32, u32), size: (u32, u32)) -> ManifestPage {
+        ManifestPage {
+            file: PageManifest::page_file_name(0),
+            physical_page: 1,
+            logical_page: Some("iv".to_string()),
+            vertical: false,
+            source_size,
+            size,
+            transforms,
+            inverted: false,
+            placeholder: false,
+            extraction_dpi: None,
+        }
+    }
+
+    #[test]
+    fn test_transforms_round_trip() {
+        let transforms = vec![
+            PageTransform::Crop { x: 10, y: 20, width: 980, height: 1460 },
+            PageTransform::Scale { x: 2.0, y: 2.0 },
+            PageTransform::Rotate {
+                degrees: -1.5,
+                input_width: 1960,
+                input_height: 2920,
+                width: 2036,
+                height: 2970,
+            },
+            PageTransform::Pad { x: 30, y: -12, width: 2100, height: 2950 },
+        ];
+        let page = page(transforms, (1000, 1500), (2100, 2950));
+        for (x, y) in [(10.0, 20.0), (500.0, 750.0), (989.0, 1479.0)] {
+            let (fx, fy) = page.from_source(x, y);
+            let (sx, sy) = page.to_source(fx, fy);
+            assert!((sx - x).abs() < 1e-6 && (sy - y).abs() < 1e-6, "({x}, {y}) -> ({sx}, {sy})");
+        }
+        // Crop then 2x scale
+        let page = self::page(transforms_crop_scale(), (1000, 1500), (1960, 2920));
+        assert_eq!(page.from_source(110.0, 220.0), (200.0, 400.0));
+        assert_eq!(page.box_from_source((110, 220, 2000, 2000)), (200, 400, 1960, 2920));
+    }
+
+    fn transforms_crop_scale() -> Vec<PageTransform> {
+        vec![
+            PageTransform::Crop { x: 10, y: 20, width: 980, height: 1460 },
+            PageTransform::Scale { x: 2.0, y: 2.0 },
+        ]
+    }
+
+    #[test]
+    fn test_geometry_effective_dpi() {
+        // 1000x1500 px scan of a 5x7.5 in page, trimmed and upscaled 2x
+        let geometry = PageGeometry {
+            source_size: (1000, 1500),
+            transforms: transforms_crop_scale(),
+            ..Default::default()
+        };
+        let page_pt = (360.0, 540.0);
+        assert!((geometry.scan_dpi(page_pt).unwrap() - 200.0).abs() < 1e-9);
+        assert!((geometry.effective_dpi(page_pt).unwrap() - 400.0).abs() < 1e-9);
+        // Page size given in the other orientation
+        assert!((geometry.effective_dpi((540.0, 360.0)).unwrap() - 400.0).abs() < 1e-9);
+
+        let landscape = PageGeometry {
+            source_size: (1500, 1000),
+            ..Default::default()
+        };
+        assert!((landscape.effective_dpi(page_pt).unwrap() - 200.0).abs() < 1e-9);
+
+        assert_eq!(geometry.effective_dpi((0.0, 0.0)), None);
+        let placeholder = PageGeometry {
+            placeholder: true,
+            ..geometry
+        };
+        assert_eq!(placeholder.effective_dpi(page_pt), None);
+    }
+
+    #[test]
+    fn test_manifest_save_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = PageManifest {
+            version: MANIFEST_VERSION,
+            source: "book.pdf".to_string(),
+            dpi: 300,
+            pages: vec![page(transforms_crop_scale(), (1000, 1500), (1960, 2920))],
+            failed_pages: vec![2],
+            effective_dpi: Some(412.5),
+            running_heads: vec![crate::markdown::RunningHeadTemplate {
+                position: crate::markdown::RunningHeadPosition::Header,
+                text: "第#章".to_string(),
+                region: (100, 30, 300, 40),
+                pages: vec![1, 3, 5],
+            }],
+            draft: true,
+        };
+        manifest.save(dir.path()).unwrap();
+        let json = std::fs::read_to_string(dir.path().join(MANIFEST_FILE)).unwrap();
+        assert!(json.contains(r#""op": "crop""#));
+        assert!(json.contains(r#""position": "header""#));
+        assert!(json.contains(r#""logical_page": "iv""#));
+        assert!(json.contains(r#""failed_pages""#));
+        assert!(json.contains(r#""effective_dpi": 412.5"#));
+        assert!(json.contains(r#""draft": true"#));
+        assert!(!json.contains(r#""placeholder""#));
+        assert!(!json.contains(r#""extraction_dpi""#));
+        assert_eq!(PageManifest::load(dir.path()).unwrap(), manifest);
+
+        assert!(PageManifest::ocr_file(dir.path(), &manifest.pages[0]).is_none());
+        std::fs::write(dir.path().join("page_0001.hocr"), HOCR).unwrap();
+        assert_eq!(
+            PageManifest::ocr_file(dir.path(), &manifest.pages[0]),
+            Some(dir.path().join("page_0001.hocr"))
+        );
+
+        let future = json.replace(r#""version": 1"#, r#""version": 9"#);
+        std::fs::write(dir.path().join(MANIFEST_FILE), future).unwrap();
+        assert!(matches!(
+            PageManifest::load(dir.path()),
+            Err(PageManifestError::UnsupportedVersion(9))
+        ));
+    }
+
+    #[test]
+    fn test_parse_hocr() {
+        let hocr = HocrPage::parse(HOCR);
+        assert_eq!(hocr.size, Some((800, 1000)));
+        assert_eq!(hocr.words.len(), 2);
+        assert_eq!(hocr.words[0].text, "Chapter");
+        assert_eq!(hocr.words[0].bbox, (100, 200, 220, 240));
+        assert_eq!(hocr.words[0].confidence, Some(0.96));
+        assert_eq!(hocr.words[1].text, "One&Two");
+
+        // Line-level output when there are no words
+        let lines = HocrPage::parse(r#"<div class="ocr_page" title="bbox 0 0 10 10"><span class="ocr_line" title="bbox 1 2 8 4">a b</span></div>"#);
+        assert_eq!(lines.words.len(), 1);
+        assert_eq!(lines.words[0].text, "a b");
+        assert_eq!(lines.words[0].confidence, None);
+    }
+
+    #[test]
+    fn test_hocr_to_ocr_result() {
+        let hocr = HocrPage::parse(HOCR);
+        let image = Path::new("page_0001.png");
+
+        // Recognized on the exported image: boxes kept
+        let exported = page(vec![], (800, 1000), (800, 1000));
+        let result = hocr.to_ocr_result(image, &exported, 300);
+        assert_eq!(result.text_blocks[0].bbox, (100, 200, 220, 240));
+        assert_eq!(result.text_blocks[0].font_size, Some(9.6));
+
+        // Recognized on the source page: mapped through the transforms
+        let transformed = page(
+            vec![PageTransform::Crop { x: 50, y: 100, width: 700, height: 800 }],
+            (800, 1000),
+            (700, 800),
+        );
+        let result = hocr.to_ocr_result(image, &transformed, 300);
+        assert_eq!(result.text_blocks[0].bbox, (50, 100, 170, 140));
+
+        // Other resolutions are scaled
+        let scaled = page(vec![], (400, 500), (400, 500));
+        let result = hocr.to_ocr_result(image, &scaled, 300);
+        assert_eq!(result.text_blocks[1].bbox, (120, 100, 200, 120));
+        assert!((result.confidence - 0.92).abs() < 1e-6);
+    }
+}
@@ -5,12 +5,14 @@
 use super::types::{
     DetectedPageNumber, MatchStage, OffsetCorrection, PageNumberAnalysis, PageNumberCandidate,
     PageNumberError, PageNumberMatch, PageNumberOptions, PageNumberPosition, PageNumberRect,
-    Rectangle, Result,
+    Rectangle, Result, VERTICAL_CORNER_WIDTH_PERCENT,
 };
+use super::offset::PageOffsetAnalyzer;
 use image::GenericImageView;
 use rayon::prelude::*;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // ============================================================
 // Fallback Matching Constants (Phase 2.1)
@@ -26,6 +28,12 @@ pub const SEARCH_REGION_MARGIN_PERCENT: f32 = 3.0;
 #[allow(dead_code)]
 pub const DEFAULT_REFERENCE_Y_RATIO: f32 = 0.95;
 
+/// Luma below which a pixel counts as ink when locating a detected number
+const INK_THRESHOLD: u8 = 128;
+
+/// Unique suffix for OCR temp files (regions are OCR'd in parallel)
+static REGION_TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
 // ============================================================
 // 4-Stage Fallback Matching (Phase 2.1)
 // ============================================================
@@ -99,7 +107,7 @@ fn stage1_exact_match(
             // Check if within expanded region
             if region.contains(cx, cy) {
                 let distance = candidate.distance_to(ref_x, ref_y);
-                if best.as_ref().is_none_or(|(_, d)| distance < *d) {
+                if best.as_ref().map_or(true, |(_, d)| distance < *d) {
                     best = Some((candidate.clone(), distance));
                 }
             }
@@ -175,7 +183,7 @@ fn stage3_ocr_success_match(
         // Only consider OCR success candidates (text was successfully detected)
         if candidate.ocr_success {
             let distance = candidate.distance_to(ref_x, ref_y);
-            if best.as_ref().is_none_or(|(_, d, _)| distance < *d) {
+            if best.as_ref().map_or(true, |(_, d, _)| distance < *d) {
                 best = Some((candidate.clone(), distance, candidate.confidence));
             }
         }
@@ -203,7 +211,7 @@ fn stage4_fallback_match(
 
     for candidate in candidates {
         let distance = candidate.distance_to(ref_x, ref_y);
-        if best.as_ref().is_none_or(|(_, d)| distance < *d) {
+        if best.as_ref().map_or(true, |(_, d)| distance < *d) {
             best = Some((candidate.clone(), distance));
         }
     }
@@ -288,6 +296,117 @@ impl FallbackMatchStats {
     }
 }
 
+/// Bounding box of ink pixels in a grayscale region (region-relative)
+fn ink_bounds(gray: &image::GrayImage) -> Option<PageNumberRect> {
+    let mut bounds: Option<(u32, u32, u32, u32)> = None;
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if pixel.0[0] < INK_THRESHOLD {
+            bounds = Some(match bounds {
+                None => (x, y, x, y),
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x), y1.max(y)),
+            });
+        }
+    }
+    bounds.map(|(x0, y0, x1, y1)| PageNumberRect {
+        x: x0,
+        y: y0,
+        width: x1 - x0 + 1,
+        height: y1 - y0 + 1,
+    })
+}
+
+/// Full-width band of `percent` of the page height centered on `center_y`
+fn focus_band(width: u32, height: u32, center_y: i32, percent: f32) -> Option<PageNumberRect> {
+    let band_height = ((height as f32 * percent / 100.0) as u32).min(height);
+    if band_height == 0 || width == 0 || center_y < 0 || center_y as u32 >= height {
+        return None;
+    }
+    let y = (center_y as u32)
+        .saturating_sub(band_height / 2)
+        .min(height - band_height);
+    Some(PageNumberRect {
+        x: 0,
+        y,
+        width,
+        height: band_height,
+    })
+}
+
+/// A page-number search region and the clockwise quarter turns that bring
+/// its text upright for OCR
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SearchRegion {
+    rect: PageNumberRect,
+    quarter_turns: u8,
+}
+
+impl SearchRegion {
+    fn upright(rect: PageNumberRect) -> Self {
+        Self { rect, quarter_turns: 0 }
+    }
+}
+
+/// Search passes for a page, tried in order until one finds a number
+///
+/// Upright pages search a full-width strip at the bottom (or top); on
+/// vertical-text pages the two outer corners are tried first so the ends of
+/// text columns reaching into the strip don't garble the OCR. Landscape
+/// images of a vertical book are pages extracted sideways: the page's
+/// bottom (or top) edge is then the left or right side, searched as a
+/// vertical strip rotated upright before OCR.
+fn search_passes(width: u32, height: u32, options: &PageNumberOptions) -> Vec<Vec<SearchRegion>> {
+    let top = matches!(
+        options.position_hint,
+        Some(PageNumberPosition::TopCenter | PageNumberPosition::TopOutside)
+    );
+
+    if options.vertical_text && width > height {
+        let w = ((width as f32 * options.search_region_percent / 100.0) as u32).min(width);
+        let left = PageNumberRect { x: 0, y: 0, width: w, height };
+        let right = PageNumberRect { x: width - w, y: 0, width: w, height };
+        // Bottom edge on the left: the page was turned clockwise, undo with three turns
+        let (left_turns, right_turns) = if top { (1, 3) } else { (3, 1) };
+        return vec![vec![
+            SearchRegion { rect: left, quarter_turns: left_turns },
+            SearchRegion { rect: right, quarter_turns: right_turns },
+        ]];
+    }
+
+    let h = ((height as f32 * options.search_region_percent / 100.0) as u32).min(height);
+    let y = if top { 0 } else { height - h };
+    let strip = PageNumberRect { x: 0, y, width, height: h };
+    if !options.vertical_text {
+        return vec![vec![SearchRegion::upright(strip)]];
+    }
+
+    let corner_width = ((width as f32 * VERTICAL_CORNER_WIDTH_PERCENT / 100.0) as u32).min(width);
+    let left = PageNumberRect { width: corner_width, ..strip };
+    let right = PageNumberRect { x: width - corner_width, width: corner_width, ..strip };
+    vec![
+        vec![SearchRegion::upright(left), SearchRegion::upright(right)],
+        vec![SearchRegion::upright(strip)],
+    ]
+}
+
+/// Rotate an image clockwise by `quarter_turns` × 90°
+fn rotate_quarter_turns(img: image::DynamicImage, quarter_turns: u8) -> image::DynamicImage {
+    match quarter_turns % 4 {
+        1 => img.rotate90(),
+        2 => img.rotate180(),
+        3 => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// Whether `candidate` should replace `current` (a number beats none, then confidence)
+fn is_better_detection(candidate: &DetectedPageNumber, current: &DetectedPageNumber) -> bool {
+    match (candidate.number, current.number) {
+        (Some(_), None) => true,
+        (None, Some(_)) => false,
+        _ => candidate.confidence > current.confidence,
+    }
+}
+
 /// Tesseract-based page number detector
 pub struct TesseractPageDetector;
 
@@ -307,68 +426,148 @@ impl TesseractPageDetector {
 
         let (width, height) = img.dimensions();
 
-        // Determine search region based on position hint
-        let (search_y, search_height) = match options.position_hint {
-            Some(PageNumberPosition::TopCenter | PageNumberPosition::TopOutside) => {
-                let h = (height as f32 * options.search_region_percent / 100.0) as u32;
-                (0, h)
+        // Keep the best result of the first pass that finds a number
+        let mut best: Option<DetectedPageNumber> = None;
+        for pass in search_passes(width, height, options) {
+            for region in pass {
+                let detection = Self::detect_in_region(&img, page_index, region, options);
+                if best.as_ref().map_or(true, |b| is_better_detection(&detection, b)) {
+                    best = Some(detection);
+                }
             }
-            _ => {
-                let h = (height as f32 * options.search_region_percent / 100.0) as u32;
-                (height.saturating_sub(h), h)
+            if best.as_ref().is_some_and(|b| b.number.is_some()) {
+                break;
             }
-        };
+        }
+        best.ok_or(PageNumberError::NoPageNumbersDetected)
+    }
 
-        // Crop search region
-        let search_region = img.crop_imm(0, search_y, width, search_height);
+    /// OCR one region of a page
+    ///
+    /// When a number is found its position is narrowed to the ink bounds
+    /// inside the region, so later passes know where numbers actually sit.
+    fn detect_in_region(
+        img: &image::DynamicImage,
+        page_index: usize,
+        region: SearchRegion,
+        options: &PageNumberOptions,
+    ) -> DetectedPageNumber {
+        let SearchRegion { rect: region, quarter_turns } = region;
+        let search_region = img.crop_imm(region.x, region.y, region.width, region.height);
+
+        let (number, raw_text, confidence) = Self::analyze_region_for_numbers(
+            &rotate_quarter_turns(search_region.clone(), quarter_turns),
+            options,
+        );
+        let number = if confidence >= options.min_confidence {
+            number
+        } else {
+            None
+        };
 
-        // For now, use simple image analysis instead of Tesseract
-        // In a full implementation, this would call tesseract OCR
-        let (number, raw_text, confidence) =
-            Self::analyze_region_for_numbers(&search_region, options);
+        let position = match number {
+            Some(_) => ink_bounds(&search_region.to_luma8())
+                .map(|ink| PageNumberRect {
+                    x: region.x + ink.x,
+                    y: region.y + ink.y,
+                    width: ink.width,
+                    height: ink.height,
+                })
+                .unwrap_or(region),
+            None => region,
+        };
 
-        Ok(DetectedPageNumber {
+        DetectedPageNumber {
             page_index,
-            number: if confidence >= options.min_confidence {
-                number
-            } else {
-                None
-            },
-            position: PageNumberRect {
-                x: 0,
-                y: search_y,
-                width,
-                height: search_height,
-            },
+            number,
+            position,
             confidence: confidence / 100.0,
             raw_text,
-        })
+        }
+    }
+
+    /// Focused second pass for pages the coarse pass missed
+    ///
+    /// The odd/even reference positions from [`PageOffsetAnalyzer`] give the
+    /// consensus page-number row; pages without a number are re-OCR'd on a
+    /// band of `focus_band_percent` around it. Each page keeps the better of
+    /// its two results, and nothing happens without a reliable consensus.
+    pub fn refine_detections(
+        images: &[PathBuf],
+        detections: &mut [DetectedPageNumber],
+        options: &PageNumberOptions,
+    ) {
+        if options.focus_band_percent <= 0.0 || detections.iter().all(|d| d.number.is_some()) {
+            return;
+        }
+
+        let analysis = PageOffsetAnalyzer::analyze_offsets(detections, 0);
+        if analysis.match_count == 0 {
+            return;
+        }
+
+        detections
+            .par_iter_mut()
+            .filter(|d| d.number.is_none())
+            .for_each(|detection| {
+                let is_odd = (detection.page_index + 1) % 2 == 1;
+                let center_y = if is_odd {
+                    analysis.odd_avg_y.or(analysis.even_avg_y)
+                } else {
+                    analysis.even_avg_y.or(analysis.odd_avg_y)
+                };
+                let (Some(center_y), Some(path)) = (center_y, images.get(detection.page_index)) else {
+                    return;
+                };
+                let Ok(img) = image::open(path) else {
+                    return;
+                };
+                // The consensus row is meaningless on pages extracted sideways
+                if options.vertical_text && img.width() > img.height() {
+                    return;
+                }
+                let Some(band) = focus_band(img.width(), img.height(), center_y, options.focus_band_percent)
+                else {
+                    return;
+                };
+
+                let focused =
+                    Self::detect_in_region(&img, detection.page_index, SearchRegion::upright(band), options);
+                if is_better_detection(&focused, detection) {
+                    *detection = focused;
+                }
+            });
     }
 
     /// Analyze image region for numbers using Tesseract OCR
     fn analyze_region_for_numbers(
         img: &image::DynamicImage,
-        _options: &PageNumberOptions,
+        options: &PageNumberOptions,
     ) -> (Option<i32>, String, f32) {
         // Create temp file for the cropped region
         let temp_dir = std::env::temp_dir();
-        let temp_path = temp_dir.join(format!("page_num_region_{}.png", std::process::id()));
+        let temp_path = temp_dir.join(format!(
+            "page_num_region_{}_{}.png",
+            std::process::id(),
+            REGION_TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
 
         // Save the region to temp file
         if img.save(&temp_path).is_err() {
             return (None, String::new(), 0.0);
         }
 
-        // Call Tesseract with digits-only configuration
+        // Call Tesseract in single line mode, digits only unless decorated
+        // numbers ("p. 12", "- 12 -") should be read as well
         // tesseract input.png stdout --psm 7 -c tessedit_char_whitelist=0123456789
-        let output = std::process::Command::new("tesseract")
-            .arg(&temp_path)
-            .arg("stdout")
-            .arg("--psm")
-            .arg("7") // Single line mode
-            .arg("-c")
-            .arg("tessedit_char_whitelist=0123456789")
-            .output();
+        let mut command = options.tools.command("tesseract");
+        command.arg(&temp_path).arg("stdout").arg("--psm").arg("7");
+        if options.numbers_only {
+            command.arg("-c").arg("tessedit_char_whitelist=0123456789");
+        } else {
+            command.arg("-l").arg(&options.ocr_language);
+        }
+        let output = options.tools.ocr_output(&mut command);
 
         // Cleanup temp file
         let _ = std::fs::remove_file(&temp_path);
@@ -376,28 +575,8 @@ impl TesseractPageDetector {
         match output {
             Ok(result) if result.status.success() => {
                 let raw_text = String::from_utf8_lossy(&result.stdout).trim().to_string();
-
-                // Extract digits from the text
-                let digits: String = raw_text.chars().filter(|c| c.is_ascii_digit()).collect();
-
-                if digits.is_empty() {
-                    return (None, raw_text, 0.0);
-                }
-
-                // Parse as number
-                match digits.parse::<i32>() {
-                    Ok(num) if num > 0 && num < 10000 => {
-                        // Valid page number range (1-9999)
-                        // Confidence based on text cleanliness
-                        let confidence = if digits == raw_text.trim() {
-                            95.0 // Clean digits only
-                        } else {
-                            70.0 // Had to filter some characters
-                        };
-                        (Some(num), raw_text, confidence)
-                    }
-                    _ => (None, raw_text, 30.0),
-                }
+                let (number, confidence) = Self::parse_number_text(&raw_text, options);
+                (number, raw_text, confidence)
             }
             _ => {
                 // Tesseract not available or failed
@@ -406,17 +585,51 @@ impl TesseractPageDetector {
         }
     }
 
+    /// Parse OCR text into a page number and a confidence (0-100)
+    ///
+    /// The options' strip patterns remove decorations first, so "p. 12",
+    /// "- 12 -" and "12頁" read as clean numbers; anything else left around
+    /// the digits lowers the confidence.
+    pub fn parse_number_text(raw_text: &str, options: &PageNumberOptions) -> (Option<i32>, f32) {
+        let stripped = options.strip_decorations(raw_text);
+
+        // Extract digits from the text
+        let digits: String = stripped.chars().filter(|c| c.is_ascii_digit()).collect();
+
+        if digits.is_empty() {
+            return (None, 0.0);
+        }
+
+        // Parse as number
+        match digits.parse::<i32>() {
+            Ok(num) if num > 0 && num < 10000 => {
+                // Valid page number range (1-9999)
+                // Confidence based on text cleanliness
+                let confidence = if digits == stripped {
+                    95.0 // Clean digits only
+                } else {
+                    70.0 // Had to filter some characters
+                };
+                (Some(num), confidence)
+            }
+            _ => (None, 30.0),
+        }
+    }
+
     /// Analyze multiple images
     pub fn analyze_batch(
         images: &[PathBuf],
         options: &PageNumberOptions,
     ) -> Result<PageNumberAnalysis> {
-        let detections: Vec<DetectedPageNumber> = images
+        let mut detections: Vec<DetectedPageNumber> = images
             .par_iter()
             .enumerate()
             .map(|(i, path)| Self::detect_single(path, i, options))
             .collect::<Result<Vec<_>>>()?;
 
+        // Second pass on the consensus band for pages the coarse pass missed
+        Self::refine_detections(images, &mut detections, options);
+
         // Analyze pattern
         let (position_pattern, odd_offset, even_offset) = Self::analyze_pattern(&detections);
 
@@ -613,6 +826,134 @@ mod tests {
         assert!(matches!(result, Err(PageNumberError::ImageNotFound(_))));
     }
 
+    fn detection(page_index: usize, number: Option<i32>, confidence: f32) -> DetectedPageNumber {
+        DetectedPageNumber {
+            page_index,
+            number,
+            position: PageNumberRect {
+                x: 0,
+                y: 900,
+                width: 100,
+                height: 20,
+            },
+            confidence,
+            raw_text: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_ink_bounds() {
+        let mut gray = image::GrayImage::from_pixel(50, 20, image::Luma([255]));
+        for x in 20..25 {
+            for y in 5..12 {
+                gray.put_pixel(x, y, image::Luma([0]));
+            }
+        }
+        let ink = ink_bounds(&gray).unwrap();
+        assert_eq!((ink.x, ink.y, ink.width, ink.height), (20, 5, 5, 7));
+
+        let blank = image::GrayImage::from_pixel(10, 10, image::Luma([255]));
+        assert!(ink_bounds(&blank).is_none());
+    }
+
+    #[test]
+    fn test_focus_band_stays_inside_page() {
+        let band = focus_band(800, 1000, 950, 4.0).unwrap();
+        assert_eq!((band.y, band.height, band.width), (930, 40, 800));
+
+        // Clamped at the bottom edge
+        let band = focus_band(800, 1000, 995, 4.0).unwrap();
+        assert_eq!(band.y + band.height, 1000);
+
+        assert!(focus_band(800, 1000, 950, 0.0).is_none());
+        assert!(focus_band(800, 1000, 1200, 4.0).is_none());
+    }
+
+    #[test]
+    fn test_search_passes_horizontal() {
+        let passes = search_passes(800, 1000, &PageNumberOptions::default());
+        assert_eq!(passes.len(), 1);
+        assert_eq!(
+            passes[0],
+            vec![SearchRegion::upright(PageNumberRect { x: 0, y: 900, width: 800, height: 100 })]
+        );
+
+        let options = PageNumberOptions::builder().position_hint(PageNumberPosition::TopOutside).build();
+        assert_eq!(search_passes(800, 1000, &options)[0][0].rect.y, 0);
+    }
+
+    #[test]
+    fn test_search_passes_vertical_corners_first() {
+        let options = PageNumberOptions::builder().vertical_text(true).build();
+        let passes = search_passes(800, 1000, &options);
+        assert_eq!(passes.len(), 2);
+
+        let corners: Vec<PageNumberRect> = passes[0].iter().map(|r| r.rect).collect();
+        assert_eq!(
+            corners,
+            vec![
+                PageNumberRect { x: 0, y: 900, width: 280, height: 100 },
+                PageNumberRect { x: 520, y: 900, width: 280, height: 100 },
+            ]
+        );
+        assert!(passes[0].iter().all(|r| r.quarter_turns == 0));
+        assert_eq!(passes[1][0].rect.width, 800);
+    }
+
+    #[test]
+    fn test_search_passes_sideways_page_rotates_edges() {
+        let options = PageNumberOptions::builder().vertical_text(true).build();
+        let passes = search_passes(1000, 800, &options);
+        assert_eq!(passes.len(), 1);
+        assert_eq!(
+            passes[0],
+            vec![
+                SearchRegion { rect: PageNumberRect { x: 0, y: 0, width: 100, height: 800 }, quarter_turns: 3 },
+                SearchRegion { rect: PageNumberRect { x: 900, y: 0, width: 100, height: 800 }, quarter_turns: 1 },
+            ]
+        );
+
+        // Top hint: the top edge sits on the opposite sides
+        let options = PageNumberOptions::builder()
+            .vertical_text(true)
+            .position_hint(PageNumberPosition::TopCenter)
+            .build();
+        let turns: Vec<u8> = search_passes(1000, 800, &options)[0].iter().map(|r| r.quarter_turns).collect();
+        assert_eq!(turns, vec![1, 3]);
+
+        // Landscape spreads of horizontal books keep the bottom strip
+        let passes = search_passes(1000, 800, &PageNumberOptions::default());
+        assert_eq!(passes[0][0].rect.y, 720);
+    }
+
+    #[test]
+    fn test_rotate_quarter_turns_brings_strip_upright() {
+        // Bottom edge of a clockwise-turned page: a tall strip becomes a wide line
+        let strip = image::DynamicImage::new_luma8(10, 80);
+        let upright = rotate_quarter_turns(strip, 3);
+        assert_eq!(upright.dimensions(), (80, 10));
+    }
+
+    #[test]
+    fn test_is_better_detection_keeps_best() {
+        let found = detection(0, Some(3), 0.7);
+        let missing = detection(0, None, 0.9);
+        assert!(is_better_detection(&found, &missing));
+        assert!(!is_better_detection(&missing, &found));
+        assert!(is_better_detection(&detection(0, Some(3), 0.95), &found));
+        assert!(!is_better_detection(&detection(0, None, 0.1), &detection(0, None, 0.3)));
+    }
+
+    #[test]
+    fn test_refine_without_consensus_is_noop() {
+        // Too few matches for a consensus row: detections stay untouched
+        let mut detections = vec![detection(0, Some(1), 0.95), detection(1, None, 0.0)];
+        let images = vec![PathBuf::from("/nonexistent/a.png"), PathBuf::from("/nonexistent/b.png")];
+        TesseractPageDetector::refine_detections(&images, &mut detections, &PageNumberOptions::default());
+        assert_eq!(detections[1].number, None);
+        assert_eq!(detections[1].position.y, 900);
+    }
+
     #[test]
     fn test_roman_numeral_parsing() {
         assert_eq!(TesseractPageDetector::parse_roman_numeral("I"), Some(1));
@@ -661,6 +1002,48 @@ mod tests {
         assert!(duplicates.contains(&4));
     }
 
+    #[test]
+    fn test_parse_decorated_numbers() {
+        let options = PageNumberOptions::default();
+        for text in [
+            "12", "- 12 -", "—12—", "ー 12 ー", "(12)", "[12]", "（12）", "【12】", "p. 12", "P.12",
+            "page 12", "pp. 12", "12頁", "第12頁", "12ページ", "- p. 12 -",
+        ] {
+            assert_eq!(
+                TesseractPageDetector::parse_number_text(text, &options),
+                (Some(12), 95.0),
+                "{}",
+                text
+            );
+        }
+
+        // Leftover noise still parses, with a lower confidence
+        assert_eq!(
+            TesseractPageDetector::parse_number_text("12a", &options),
+            (Some(12), 70.0)
+        );
+        assert_eq!(TesseractPageDetector::parse_number_text("- -", &options), (None, 0.0));
+        assert_eq!(TesseractPageDetector::parse_number_text("0", &options), (None, 30.0));
+    }
+
+    #[test]
+    fn test_parse_custom_strip_patterns() {
+        // Without patterns decorations only lower the confidence
+        let bare = PageNumberOptions::builder().strip_patterns(Vec::new()).build();
+        assert_eq!(
+            TesseractPageDetector::parse_number_text("p. 12", &bare),
+            (Some(12), 70.0)
+        );
+
+        let custom = PageNumberOptions::builder()
+            .add_strip_pattern(regex::Regex::new(r"^No\.\s*").unwrap())
+            .build();
+        assert_eq!(
+            TesseractPageDetector::parse_number_text("No. 7", &custom),
+            (Some(7), 95.0)
+        );
+    }
+
     #[test]
     fn test_analyze_empty_batch() {
         let images: Vec<PathBuf> = vec![];
@@ -0,0 +1,300 @@
+//! PDF page labels from detected page numbers
+//!
+//! Converts the physical-to-logical shift (and any roman-numeral front
+//! matter) into page label ranges, so viewers show the book's own
+//! numbering ("iii", "iv", then "1", "2") instead of physical indices.
+
+use super::types::DetectedPageNumber;
+use super::TesseractPageDetector;
+
+/// Minimum roman-numeral readings that must agree on the front matter numbering
+const MIN_ROMAN_MATCHES: usize = 2;
+
+/// Numbering style of a page label range (PDF `/S` entry)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageLabelStyle {
+    /// Arabic numerals (`/D`)
+    Decimal,
+    /// Lowercase roman numerals (`/r`)
+    LowerRoman,
+    /// Uppercase roman numerals (`/R`)
+    UpperRoman,
+}
+
+impl PageLabelStyle {
+    /// PDF name for the `/S` entry
+    pub fn pdf_name(self) -> &'static [u8] {
+        match self {
+            PageLabelStyle::Decimal => b"D",
+            PageLabelStyle::LowerRoman => b"r",
+            PageLabelStyle::UpperRoman => b"R",
+        }
+    }
+
+    /// Format a page number in this style
+    pub fn format(self, number: u32) -> String {
+        match self {
+            PageLabelStyle::Decimal => number.to_string(),
+            PageLabelStyle::LowerRoman => to_roman(number),
+            PageLabelStyle::UpperRoman => to_roman(number).to_uppercase(),
+        }
+    }
+}
+
+/// A run of pages numbered consecutively in one style
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageLabelRange {
+    /// First page of the range (0-based physical index)
+    pub start_page: usize,
+    /// Numbering style (`None` = unnumbered, e.g. a cover)
+    pub style: Option<PageLabelStyle>,
+    /// Number of the first page of the range
+    pub first_number: u32,
+}
+
+impl PageLabelRange {
+    /// Label of `page_index` (0-based), assuming it lies in this range
+    pub fn label(&self, page_index: usize) -> String {
+        self.style
+            .map(|style| style.format(self.first_number + (page_index - self.start_page) as u32))
+            .unwrap_or_default()
+    }
+}
+
+/// Builds page label ranges from page number detection
+pub struct PageLabeler;
+
+impl PageLabeler {
+    /// Page label ranges for `page_count` pages
+    ///
+    /// Pages from the first with logical number 1 (`index = shift`) are
+    /// numbered in arabic numerals (`logical = index + 1 - shift`). Front
+    /// matter before it is numbered in roman numerals when at least two
+    /// roman readings agree on the offset (case follows the readings),
+    /// otherwise in lowercase roman numerals from i. Returns no ranges when
+    /// the labels would just repeat the physical numbering.
+    pub fn from_detections(detections: &[DetectedPageNumber], shift: i32, page_count: usize) -> Vec<PageLabelRange> {
+        if page_count == 0 {
+            return Vec::new();
+        }
+
+        if shift <= 0 {
+            if shift == 0 {
+                return Vec::new();
+            }
+            // The first page already has a logical number above 1
+            return vec![PageLabelRange {
+                start_page: 0,
+                style: Some(PageLabelStyle::Decimal),
+                first_number: (1 - shift) as u32,
+            }];
+        }
+
+        let body_start = (shift as usize).min(page_count);
+        let mut ranges = Vec::new();
+        match Self::front_matter_numbering(detections, body_start) {
+            Some((roman_start, first_number, style)) => {
+                if roman_start > 0 {
+                    ranges.push(PageLabelRange {
+                        start_page: 0,
+                        style: None,
+                        first_number: 1,
+                    });
+                }
+                ranges.push(PageLabelRange {
+                    start_page: roman_start,
+                    style: Some(style),
+                    first_number,
+                });
+            }
+            None => ranges.push(PageLabelRange {
+                start_page: 0,
+                style: Some(PageLabelStyle::LowerRoman),
+                first_number: 1,
+            }),
+        }
+        if body_start < page_count {
+            ranges.push(PageLabelRange {
+                start_page: body_start,
+                style: Some(PageLabelStyle::Decimal),
+                first_number: 1,
+            });
+        }
+        ranges
+    }
+
+    /// Label of every page (empty strings for unnumbered pages)
+    pub fn labels(ranges: &[PageLabelRange], page_count: usize) -> Vec<String> {
+        (0..page_count)
+            .map(|index| {
+                ranges
+                    .iter()
+                    .rev()
+                    .find(|range| range.start_page <= index)
+                    .map(|range| range.label(index))
+                    .unwrap_or_else(|| (index + 1).to_string())
+            })
+            .collect()
+    }
+
+    /// Roman numbering of the front matter (pages before `body_start`):
+    /// (first numbered page, its number, style)
+    fn front_matter_numbering(
+        detections: &[DetectedPageNumber],
+        body_start: usize,
+    ) -> Option<(usize, u32, PageLabelStyle)> {
+        let readings: Vec<(usize, i32, bool)> = detections
+            .iter()
+            .filter(|det| det.page_index < body_start && det.number.is_none())
+            .filter_map(|det| {
+                let text = det.raw_text.trim();
+                let value = TesseractPageDetector::parse_roman_numeral(text)?;
+                Some((det.page_index, value, text.chars().all(|c| c.is_ascii_uppercase())))
+            })
+            .collect();
+
+        // Most common offset (page index - value), ties to the smallest
+        let mut offsets: Vec<i32> = readings.iter().map(|&(index, value, _)| index as i32 + 1 - value).collect();
+        offsets.sort_unstable();
+        let (offset, votes) = offsets
+            .chunk_by(|a, b| a == b)
+            .map(|run| (run[0], run.len()))
+            .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+        if votes < MIN_ROMAN_MATCHES {
+            return None;
+        }
+
+        let agreeing: Vec<bool> = readings
+            .iter()
+            .filter(|&&(index, value, _)| index as i32 + 1 - value == offset)
+            .map(|&(_, _, upper)| upper)
+            .collect();
+        let style = if agreeing.iter().filter(|&&upper| upper).count() * 2 > agreeing.len() {
+            PageLabelStyle::UpperRoman
+        } else {
+            PageLabelStyle::LowerRoman
+        };
+
+        // Numbering starts at i on page `offset`, or at the first page
+        if offset >= 0 {
+            Some((offset as usize, 1, style))
+        } else {
+            Some((0, (1 - offset) as u32, style))
+        }
+    }
+}
+
+/// Lowercase roman numeral for `number` (empty for 0)
+pub fn to_roman(mut number: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    let mut roman = String::new();
+    for &(value, numeral) in &NUMERALS {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+    roman
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::page_number::PageNumberRect;
+
+    fn detection(page_index: usize, number: Option<i32>, raw_text: &str) -> DetectedPageNumber {
+        DetectedPageNumber {
+            page_index,
+            number,
+            position: PageNumberRect {
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 10,
+            },
+            confidence: 90.0,
+            raw_text: raw_text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_to_roman() {
+        assert_eq!(to_roman(4), "iv");
+        assert_eq!(to_roman(14), "xiv");
+        assert_eq!(to_roman(1999), "mcmxcix");
+        assert_eq!(to_roman(0), "");
+        for n in 1..200 {
+            assert_eq!(TesseractPageDetector::parse_roman_numeral(&to_roman(n)), Some(n as i32));
+        }
+    }
+
+    #[test]
+    fn test_roman_front_matter_then_arabic() {
+        // Cover, then pages ii-iv, then the body from physical page 5
+        let detections = vec![
+            detection(2, None, "iii"),
+            detection(3, None, "iv"),
+            detection(5, Some(2), "2"),
+            detection(6, Some(3), "3"),
+        ];
+        let ranges = PageLabeler::from_detections(&detections, 4, 8);
+        assert_eq!(
+            ranges,
+            vec![
+                PageLabelRange {
+                    start_page: 0,
+                    style: Some(PageLabelStyle::LowerRoman),
+                    first_number: 1,
+                },
+                PageLabelRange {
+                    start_page: 4,
+                    style: Some(PageLabelStyle::Decimal),
+                    first_number: 1,
+                },
+            ]
+        );
+        assert_eq!(PageLabeler::labels(&ranges, 6), vec!["i", "ii", "iii", "iv", "1", "2"]);
+    }
+
+    #[test]
+    fn test_unnumbered_cover_and_upper_roman() {
+        let detections = vec![detection(2, None, "I"), detection(3, None, "II"), detection(4, None, "X")];
+        let ranges = PageLabeler::from_detections(&detections, 5, 7);
+        assert_eq!(PageLabeler::labels(&ranges, 7), vec!["", "", "I", "II", "III", "1", "2"]);
+        assert_eq!(ranges[0].style, None);
+    }
+
+    #[test]
+    fn test_shift_without_roman_readings() {
+        let ranges = PageLabeler::from_detections(&[detection(3, Some(2), "2")], 2, 4);
+        assert_eq!(PageLabeler::labels(&ranges, 4), vec!["i", "ii", "1", "2"]);
+
+        // A single roman reading is not trusted
+        let ranges = PageLabeler::from_detections(&[detection(1, None, "v")], 2, 3);
+        assert_eq!(PageLabeler::labels(&ranges, 3), vec!["i", "ii", "1"]);
+    }
+
+    #[test]
+    fn test_aligned_and_negative_shift() {
+        assert!(PageLabeler::from_detections(&[], 0, 10).is_empty());
+
+        // Excerpt starting at page 41
+        let ranges = PageLabeler::from_detections(&[], -40, 3);
+        assert_eq!(PageLabeler::labels(&ranges, 3), vec!["41", "42", "43"]);
+        assert!(PageLabeler::from_detections(&[], 3, 0).is_empty());
+    }
+}
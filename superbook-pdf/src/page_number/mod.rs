@@ -9,6 +9,7 @@
 //! - Physical-to-logical page number shift calculation
 //! - Per-page offset alignment
 //! - Odd/even page grouping
+//! - PDF page labels from logical numbering
 //!
 //! # Example
 //!
@@ -32,6 +33,7 @@
 
 // Submodules
 mod detect;
+mod labels;
 mod offset;
 mod types;
 
@@ -40,6 +42,7 @@ pub use detect::{
     find_page_number_with_fallback, find_page_numbers_batch, FallbackMatchStats,
     TesseractPageDetector,
 };
+pub use labels::{to_roman, PageLabelRange, PageLabelStyle, PageLabeler};
 pub use offset::{
     calc_group_reference_position, calc_overlap_center, BookOffsetAnalysis, PageOffsetAnalyzer,
     PageOffsetResult,
@@ -48,6 +51,7 @@ pub use types::{
     DetectedPageNumber, MatchStage, OffsetCorrection, PageNumberAnalysis, PageNumberCandidate,
     PageNumberDetector, PageNumberError, PageNumberMatch, PageNumberOptions,
     PageNumberOptionsBuilder, PageNumberPosition, PageNumberRect, Point, Rectangle, Result,
+    DEFAULT_STRIP_PATTERNS,
 };
 
 #[cfg(test)]
@@ -28,6 +28,21 @@ const MIN_CONTAINMENT_RATIO: f64 = 0.70;
 /// Top percentage of smallest bboxes to consider (30%)
 const TOP_SMALL_BBOX_RATIO: f64 = 0.30;
 
+/// Maximum digit edit distance for repairing a misread page number
+const MAX_REPAIR_DISTANCE: usize = 1;
+
+/// Pages (either side) searched for a same-parity exact match confirming a repair
+const REPAIR_NEIGHBOR_WINDOW: usize = 4;
+
+/// Maximum center distance from the confirming match (% of image height)
+const REPAIR_POSITION_TOLERANCE_PERCENT: f64 = 3.0;
+
+/// Maximum center distance in bbox heights when the image height is unknown
+const REPAIR_POSITION_TOLERANCE_BBOXES: f64 = 3.0;
+
+/// Confidence weight of a repaired match relative to an exact one
+const REPAIR_WEIGHT: f64 = 0.5;
+
 // ============================================================
 // Group-Based Reference Position (Phase 2.2)
 // ============================================================
@@ -183,6 +198,8 @@ pub struct PageOffsetResult {
     pub page_number_position: Option<PageNumberRect>,
     /// Whether this is an odd page (in physical order)
     pub is_odd: bool,
+    /// Whether `logical_page` was repaired from a near-miss OCR reading
+    pub repaired: bool,
 }
 
 impl PageOffsetResult {
@@ -195,6 +212,7 @@ impl PageOffsetResult {
             shift_y: 0,
             page_number_position: None,
             is_odd: physical_page % 2 == 1,
+            repaired: false,
         }
     }
 }
@@ -215,8 +233,10 @@ pub struct BookOffsetAnalysis {
     pub odd_avg_y: Option<i32>,
     /// Average Y position for even pages
     pub even_avg_y: Option<i32>,
-    /// Number of pages with matched page numbers
+    /// Number of pages with matched page numbers (including repaired ones)
     pub match_count: usize,
+    /// Number of matches repaired from near-miss OCR readings
+    pub repaired_count: usize,
     /// Confidence in the analysis (0.0-1.0)
     pub confidence: f64,
 }
@@ -231,6 +251,7 @@ impl Default for BookOffsetAnalysis {
             odd_avg_y: None,
             even_avg_y: None,
             match_count: 0,
+            repaired_count: 0,
             confidence: 0.0,
         }
     }
@@ -266,16 +287,32 @@ impl PageOffsetAnalyzer {
     /// 2. Groups pages into odd/even
     /// 3. Calculates average positions for each group
     /// 4. Computes per-page shift to align with the average
+    ///
+    /// After the shift is found, near-miss readings confirmed by a nearby
+    /// exact match are repaired (see [`Self::classify_matches`]); they count
+    /// as matches with a lower confidence weight but do not contribute to the
+    /// reference positions. `image_height` scales the position tolerance of
+    /// the repair (0 = unknown).
     pub fn analyze_offsets(
         detections: &[DetectedPageNumber],
-        _image_height: u32,
+        image_height: u32,
     ) -> BookOffsetAnalysis {
         if detections.is_empty() {
             return BookOffsetAnalysis::default();
         }
 
-        // Step 1: Find the best physical-to-logical shift
-        let (best_shift, match_count, confidence) = Self::find_best_page_number_shift(detections);
+        // Step 1: Find the best physical-to-logical shift, then repair near misses
+        let (best_shift, exact_count, exact_confidence) = Self::find_best_page_number_shift(detections);
+        let matches = Self::classify_matches(detections, best_shift, image_height);
+        let repaired_count = matches.iter().filter(|m| **m == SequenceMatch::Repaired).count();
+        let match_count = exact_count + repaired_count;
+        let repaired_score: f64 = detections
+            .iter()
+            .zip(&matches)
+            .filter(|(_, m)| **m == SequenceMatch::Repaired)
+            .map(|(det, _)| det.confidence as f64 * REPAIR_WEIGHT)
+            .sum();
+        let confidence = exact_confidence + repaired_score / (detections.len() as f64 * 100.0);
 
         // Check if we have enough matches
         if match_count < MIN_MATCH_COUNT
@@ -294,13 +331,11 @@ impl PageOffsetAnalyzer {
             };
         }
 
-        // Step 2: Build matched page data with positions
+        // Step 2: Build exactly matched page data with positions
         let mut matched_pages: Vec<(usize, PageNumberRect, bool)> = Vec::new();
-        for det in detections {
+        for (det, kind) in detections.iter().zip(&matches) {
             let physical_page = det.page_index + 1;
-            let expected_logical = physical_page as i32 - best_shift;
-
-            if expected_logical >= 1 && det.number == Some(expected_logical) {
+            if *kind == SequenceMatch::Exact {
                 matched_pages.push((physical_page, det.position, physical_page % 2 == 1));
             }
         }
@@ -344,6 +379,7 @@ impl PageOffsetAnalyzer {
         // Step 5: Calculate per-page offsets
         let page_offsets = Self::calculate_per_page_offsets(
             detections,
+            &matches,
             best_shift,
             odd_avg_x,
             even_avg_x,
@@ -359,10 +395,61 @@ impl PageOffsetAnalyzer {
             odd_avg_y: final_odd_avg_y,
             even_avg_y: final_even_avg_y,
             match_count,
+            repaired_count,
             confidence,
         }
     }
 
+    /// Classify each detection against the expected logical number for `shift`
+    ///
+    /// A reading that is not exact is repaired when its digits are within
+    /// `MAX_REPAIR_DISTANCE` edits of the expected number (e.g. 8 read as 3,
+    /// a dropped or extra digit) and the nearest same-parity exact match
+    /// within `REPAIR_NEIGHBOR_WINDOW` pages has its number at a consistent
+    /// position.
+    fn classify_matches(detections: &[DetectedPageNumber], shift: i32, image_height: u32) -> Vec<SequenceMatch> {
+        let expected = |det: &DetectedPageNumber| Some(det.page_index as i32 + 1 - shift).filter(|&n| n >= 1);
+        let exact: Vec<bool> = detections
+            .iter()
+            .map(|det| expected(det).is_some_and(|n| det.number == Some(n)))
+            .collect();
+        let exact_by_page: std::collections::HashMap<usize, &DetectedPageNumber> = detections
+            .iter()
+            .zip(&exact)
+            .filter(|(_, is_exact)| **is_exact)
+            .map(|(det, _)| (det.page_index, det))
+            .collect();
+
+        detections
+            .iter()
+            .zip(&exact)
+            .map(|(det, &is_exact)| {
+                if is_exact {
+                    return SequenceMatch::Exact;
+                }
+                let (Some(expected), Some(number)) = (expected(det), det.number) else {
+                    return SequenceMatch::None;
+                };
+                if number < 0 || digit_edit_distance(number, expected) > MAX_REPAIR_DISTANCE {
+                    return SequenceMatch::None;
+                }
+                let neighbor = (1..=REPAIR_NEIGHBOR_WINDOW / 2)
+                    .flat_map(|step| {
+                        let offset = step * 2;
+                        [det.page_index.checked_sub(offset), Some(det.page_index + offset)]
+                    })
+                    .flatten()
+                    .find_map(|page| exact_by_page.get(&page));
+                match neighbor {
+                    Some(neighbor) if positions_consistent(&det.position, &neighbor.position, image_height) => {
+                        SequenceMatch::Repaired
+                    }
+                    _ => SequenceMatch::None,
+                }
+            })
+            .collect()
+    }
+
     /// Find the best physical-to-logical page number shift
     ///
     /// Tests shifts from -MAX_SHIFT_TEST to +MAX_SHIFT_TEST and returns
@@ -428,6 +515,7 @@ impl PageOffsetAnalyzer {
     /// Calculate per-page offsets based on averages
     fn calculate_per_page_offsets(
         detections: &[DetectedPageNumber],
+        matches: &[SequenceMatch],
         shift: i32,
         odd_avg_x: Option<i32>,
         even_avg_x: Option<i32>,
@@ -436,15 +524,13 @@ impl PageOffsetAnalyzer {
     ) -> Vec<PageOffsetResult> {
         detections
             .iter()
-            .map(|det| {
+            .zip(matches)
+            .map(|(det, kind)| {
                 let physical_page = det.page_index + 1;
                 let is_odd = physical_page % 2 == 1;
                 let expected_logical = physical_page as i32 - shift;
 
-                // Check if this page's detected number matches the expected
-                let matched = expected_logical >= 1 && det.number == Some(expected_logical);
-
-                if matched {
+                if *kind != SequenceMatch::None {
                     let avg_x = if is_odd { odd_avg_x } else { even_avg_x };
                     let avg_y = if is_odd { odd_avg_y } else { even_avg_y };
 
@@ -463,6 +549,7 @@ impl PageOffsetAnalyzer {
                         shift_y,
                         page_number_position: Some(det.position),
                         is_odd,
+                        repaired: *kind == SequenceMatch::Repaired,
                     }
                 } else {
                     PageOffsetResult::no_offset(physical_page)
@@ -495,6 +582,46 @@ impl PageOffsetAnalyzer {
     }
 }
 
+/// How a detection relates to the expected page number sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SequenceMatch {
+    /// Detected number equals the expected one
+    Exact,
+    /// Near-miss reading confirmed by a nearby exact match
+    Repaired,
+    /// No usable match
+    None,
+}
+
+/// Levenshtein distance between the decimal digits of two numbers
+fn digit_edit_distance(a: i32, b: i32) -> usize {
+    let a = a.to_string().into_bytes();
+    let b = b.to_string().into_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether two page number boxes are centered close enough to be the same
+/// printed position
+fn positions_consistent(a: &PageNumberRect, b: &PageNumberRect, image_height: u32) -> bool {
+    let center = |r: &PageNumberRect| (r.x as f64 + r.width as f64 / 2.0, r.y as f64 + r.height as f64 / 2.0);
+    let ((ax, ay), (bx, by)) = (center(a), center(b));
+    let tolerance = if image_height > 0 {
+        image_height as f64 * REPAIR_POSITION_TOLERANCE_PERCENT / 100.0
+    } else {
+        a.height.max(b.height) as f64 * REPAIR_POSITION_TOLERANCE_BBOXES
+    };
+    (ax - bx).hypot(ay - by) <= tolerance
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -648,6 +775,7 @@ mod tests {
                     shift_y: 5,
                     page_number_position: Some(PageNumberRect { x: 100, y: 50, width: 30, height: 20 }),
                     is_odd: true,
+                    repaired: false,
                 },
                 // Page 2 is missing
                 PageOffsetResult {
@@ -657,6 +785,7 @@ mod tests {
                     shift_y: 5,
                     page_number_position: Some(PageNumberRect { x: 100, y: 50, width: 30, height: 20 }),
                     is_odd: true,
+                    repaired: false,
                 },
             ],
             page_number_shift: 0,
@@ -665,6 +794,7 @@ mod tests {
             odd_avg_y: Some(50),
             even_avg_y: Some(50),
             match_count: 2,
+            repaired_count: 0,
             confidence: 0.8,
         };
 
@@ -687,6 +817,7 @@ mod tests {
             shift_y: 0,
             page_number_position: None,
             is_odd: true,
+            repaired: false,
         };
 
         assert_eq!(result.physical_page, 5);
@@ -704,6 +835,7 @@ mod tests {
             shift_y: 0,
             page_number_position: None,
             is_odd: true,
+            repaired: false,
         };
 
         assert!(result.logical_page.is_none());
@@ -899,4 +1031,69 @@ mod tests {
         assert!((center.x - expected_x).abs() <= 20, "X deviation too large");
         assert!((center.y - expected_y).abs() <= 20, "Y deviation too large");
     }
+
+    fn sequence(numbers: &[Option<i32>], x_of: impl Fn(usize) -> u32) -> Vec<DetectedPageNumber> {
+        numbers
+            .iter()
+            .enumerate()
+            .map(|(i, &number)| DetectedPageNumber {
+                page_index: i,
+                number,
+                position: PageNumberRect { x: x_of(i), y: 6800, width: 50, height: 30 },
+                confidence: 90.0,
+                raw_text: number.map(|n| n.to_string()).unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_digit_edit_distance() {
+        assert_eq!(digit_edit_distance(8, 8), 0);
+        assert_eq!(digit_edit_distance(8, 3), 1);
+        assert_eq!(digit_edit_distance(12, 1), 1);
+        assert_eq!(digit_edit_distance(17, 117), 1);
+        assert_eq!(digit_edit_distance(18, 81), 2);
+    }
+
+    #[test]
+    fn test_off_by_one_ocr_misreads_are_repaired() {
+        // Page 8 read as 3 and page 12 read as 1; the rest exact
+        let mut numbers: Vec<Option<i32>> = (1..=12).map(Some).collect();
+        numbers[7] = Some(3);
+        numbers[11] = Some(1);
+        let detections = sequence(&numbers, |i| if i % 2 == 0 { 2300 } else { 150 });
+
+        let analysis = PageOffsetAnalyzer::analyze_offsets(&detections, 7000);
+        assert_eq!(analysis.page_number_shift, 0);
+        assert_eq!(analysis.repaired_count, 2);
+        assert_eq!(analysis.match_count, 12);
+        let page8 = analysis.get_offset(8).unwrap();
+        assert_eq!(page8.logical_page, Some(8));
+        assert!(page8.repaired);
+        assert!(!analysis.get_offset(7).unwrap().repaired);
+
+        // Exact matches weigh more than repaired ones
+        let exact = sequence(&(1..=12).map(Some).collect::<Vec<_>>(), |i| if i % 2 == 0 { 2300 } else { 150 });
+        assert!(PageOffsetAnalyzer::analyze_offsets(&exact, 7000).confidence > analysis.confidence);
+    }
+
+    #[test]
+    fn test_repair_requires_consistent_neighbor() {
+        let mut numbers: Vec<Option<i32>> = (1..=12).map(Some).collect();
+        // Too far from the expected number
+        numbers[5] = Some(93);
+        // Near miss, but printed somewhere else on the page
+        numbers[7] = Some(3);
+        let detections = sequence(&numbers, |i| if i == 7 { 1200 } else if i % 2 == 0 { 2300 } else { 150 });
+
+        let analysis = PageOffsetAnalyzer::analyze_offsets(&detections, 7000);
+        assert_eq!(analysis.repaired_count, 0);
+        assert_eq!(analysis.match_count, 10);
+        assert_eq!(analysis.get_offset(8).unwrap().logical_page, None);
+
+        // Without confirming same-parity exact matches nearby, nothing is repaired
+        let sparse = sequence(&[Some(1), Some(7), None, None, None, None], |_| 150);
+        let classified = PageOffsetAnalyzer::classify_matches(&sparse, 0, 7000);
+        assert_eq!(classified[1], SequenceMatch::None);
+    }
 }
@@ -2,6 +2,7 @@
 //!
 //! Contains basic data structures for page number detection and offset analysis.
 
+use regex::Regex;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -15,6 +16,10 @@ pub const DEFAULT_SEARCH_REGION_PERCENT: f32 = 10.0;
 /// Larger search region for vertical text (Japanese books)
 pub const VERTICAL_SEARCH_REGION_PERCENT: f32 = 12.0;
 
+/// Width of each bottom/top corner searched on vertical-text pages
+/// (percentage of image width)
+pub const VERTICAL_CORNER_WIDTH_PERCENT: f32 = 35.0;
+
 /// Default minimum OCR confidence threshold
 pub const DEFAULT_MIN_CONFIDENCE: f32 = 60.0;
 
@@ -27,12 +32,26 @@ pub const MIN_SEARCH_REGION: f32 = 5.0;
 /// Maximum search region clamp value
 pub const MAX_SEARCH_REGION: f32 = 50.0;
 
+/// Default height of the focused re-OCR band (percentage of image height)
+pub const DEFAULT_FOCUS_BAND_PERCENT: f32 = 4.0;
+
 /// Minimum confidence clamp value
 pub const MIN_CONFIDENCE_CLAMP: f32 = 0.0;
 
 /// Maximum confidence clamp value
 pub const MAX_CONFIDENCE_CLAMP: f32 = 100.0;
 
+/// Decorations stripped from OCR text before it is parsed as a page number:
+/// surrounding dashes ("- 12 -"), brackets ("(12)", "【12】"), a "p." /
+/// "page" prefix and a "第" / "頁" / "ページ" wrapper
+pub const DEFAULT_STRIP_PATTERNS: &[&str] = &[
+    r"^[\s\-‐‑–—―ー~〜]+|[\s\-‐‑–—―ー~〜]+$",
+    r"[()\[\]{}<>（）［］｛｝〈〉《》「」『』【】〔〕]",
+    r"(?i)^\s*p(?:age|p)?\s*\.?\s*",
+    r"^\s*第\s*",
+    r"\s*(?:頁|ページ)\s*$",
+];
+
 // ============================================================
 // Error Types
 // ============================================================
@@ -78,7 +97,7 @@ pub enum PageNumberPosition {
 }
 
 /// Page number rectangle
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct PageNumberRect {
     pub x: u32,
     pub y: u32,
@@ -386,6 +405,17 @@ pub struct PageNumberOptions {
     pub numbers_only: bool,
     /// Position hint
     pub position_hint: Option<PageNumberPosition>,
+    /// Height of the focused second-pass band around the consensus
+    /// page-number position (percentage of image height, 0 = single pass)
+    pub focus_band_percent: f32,
+    /// Book is vertically written (search the outer corners first, and
+    /// treat landscape page images as pages extracted sideways)
+    pub vertical_text: bool,
+    /// Patterns removed from the OCR text, in order, before it is parsed
+    /// as a number (see [`DEFAULT_STRIP_PATTERNS`])
+    pub strip_patterns: Vec<Regex>,
+    /// Context the tesseract calls run in (niceness, OCR limit)
+    pub tools: crate::tools::ToolContext,
 }
 
 impl Default for PageNumberOptions {
@@ -396,6 +426,13 @@ impl Default for PageNumberOptions {
             min_confidence: DEFAULT_MIN_CONFIDENCE,
             numbers_only: true,
             position_hint: None,
+            focus_band_percent: DEFAULT_FOCUS_BAND_PERCENT,
+            vertical_text: false,
+            strip_patterns: DEFAULT_STRIP_PATTERNS
+                .iter()
+                .map(|pattern| Regex::new(pattern).expect("default strip pattern is valid"))
+                .collect(),
+            tools: crate::tools::ToolContext::default(),
         }
     }
 }
@@ -430,6 +467,15 @@ impl PageNumberOptions {
             ..Default::default()
         }
     }
+
+    /// OCR text with every strip pattern removed, trimmed
+    pub fn strip_decorations(&self, text: &str) -> String {
+        self.strip_patterns
+            .iter()
+            .fold(text.trim().to_string(), |text, pattern| {
+                pattern.replace_all(&text, "").trim().to_string()
+            })
+    }
 }
 
 /// Builder for PageNumberOptions
@@ -474,6 +520,42 @@ impl PageNumberOptionsBuilder {
         self
     }
 
+    /// Set focused second-pass band height (percentage of image height,
+    /// clamped to 0-50, 0 disables the second pass)
+    #[must_use]
+    pub fn focus_band_percent(mut self, percent: f32) -> Self {
+        self.options.focus_band_percent = percent.clamp(0.0, MAX_SEARCH_REGION);
+        self
+    }
+
+    /// Set whether the book is vertically written
+    #[must_use]
+    pub fn vertical_text(mut self, vertical: bool) -> Self {
+        self.options.vertical_text = vertical;
+        self
+    }
+
+    /// Replace the decoration strip patterns (empty = parse the raw text)
+    #[must_use]
+    pub fn strip_patterns(mut self, patterns: Vec<Regex>) -> Self {
+        self.options.strip_patterns = patterns;
+        self
+    }
+
+    /// Add a decoration strip pattern after the current ones
+    #[must_use]
+    pub fn add_strip_pattern(mut self, pattern: Regex) -> Self {
+        self.options.strip_patterns.push(pattern);
+        self
+    }
+
+    /// Set the context tesseract calls run in
+    #[must_use]
+    pub fn tools(mut self, tools: crate::tools::ToolContext) -> Self {
+        self.options.tools = tools;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> PageNumberOptions {
@@ -518,6 +600,7 @@ mod tests {
         assert_eq!(opts.search_region_percent, 10.0);
         assert_eq!(opts.min_confidence, 60.0);
         assert!(opts.numbers_only);
+        assert!(!opts.vertical_text);
     }
 
     #[test]
@@ -547,12 +630,14 @@ mod tests {
             .min_confidence(75.0)
             .numbers_only(false)
             .position_hint(PageNumberPosition::BottomCenter)
+            .vertical_text(true)
             .build();
 
         assert_eq!(opts.search_region_percent, 15.0);
         assert_eq!(opts.ocr_language, "fra");
         assert_eq!(opts.min_confidence, 75.0);
         assert!(!opts.numbers_only);
+        assert!(opts.vertical_text);
         assert!(matches!(
             opts.position_hint,
             Some(PageNumberPosition::BottomCenter)
@@ -578,6 +663,12 @@ mod tests {
 
         let opts = PageNumberOptions::builder().min_confidence(-10.0).build();
         assert_eq!(opts.min_confidence, 0.0);
+
+        // Focus band clamped to 0-50
+        let opts = PageNumberOptions::builder().focus_band_percent(80.0).build();
+        assert_eq!(opts.focus_band_percent, 50.0);
+        let opts = PageNumberOptions::builder().focus_band_percent(-1.0).build();
+        assert_eq!(opts.focus_band_percent, 0.0);
     }
 
     #[test]
@@ -97,6 +97,59 @@ impl ParallelOptions {
     }
 }
 
+/// Split of a worker budget between rayon and external tools
+///
+/// Page stages run on a rayon pool while CPU tools (ImageMagick, tesseract,
+/// pdftoppm, ...) are spawned as separate processes, many of them OpenMP
+/// programs that start one thread per core. Without a split both sides size
+/// themselves to the whole machine and oversubscribe it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThreadBudget {
+    /// Threads of the internal rayon pool
+    pub internal: usize,
+    /// Threads shared by concurrently running external tools
+    pub external: usize,
+}
+
+impl ThreadBudget {
+    /// Split `total` threads (0 = CPU count), giving `external_fraction`
+    /// (clamped to 0.0-1.0) to external tools
+    ///
+    /// Each side gets at least one thread, so a budget of one thread is
+    /// exceeded by one.
+    pub fn split(total: usize, external_fraction: f32) -> Self {
+        let total = if total == 0 { num_cpus::get() } else { total };
+        let fraction = if external_fraction.is_nan() { 0.0 } else { external_fraction.clamp(0.0, 1.0) };
+        let external = ((total as f32 * fraction).round() as usize).clamp(1, total);
+        Self {
+            internal: total.saturating_sub(external).max(1),
+            external,
+        }
+    }
+
+    /// Threads of both sides together
+    pub fn total(&self) -> usize {
+        self.internal + self.external
+    }
+
+    /// Build a rayon pool with the internal share
+    pub fn build_pool(&self) -> Result<rayon::ThreadPool, ParallelError> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.internal)
+            .build()
+            .map_err(|e| ParallelError::ThreadPoolError(e.to_string()))
+    }
+}
+
+/// Parse an external thread fraction (`0.0`-`1.0`)
+pub fn parse_thread_fraction(value: &str) -> Result<f32, String> {
+    let fraction: f32 = value.trim().parse().map_err(|_| format!("invalid fraction '{}'", value))?;
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(format!("fraction must be between 0.0 and 1.0, got '{}'", value));
+    }
+    Ok(fraction)
+}
+
 /// Result of parallel processing
 #[derive(Debug)]
 pub struct ParallelResult<T> {
@@ -351,6 +404,28 @@ mod tests {
         assert_eq!(options.effective_threads(), num_cpus::get());
     }
 
+    #[test]
+    fn test_thread_budget_split() {
+        let budget = ThreadBudget::split(64, 0.25);
+        assert_eq!(budget, ThreadBudget { internal: 48, external: 16 });
+        assert_eq!(budget.total(), 64);
+
+        // Both sides keep at least one thread
+        assert_eq!(ThreadBudget::split(8, 0.0), ThreadBudget { internal: 7, external: 1 });
+        assert_eq!(ThreadBudget::split(8, 1.0), ThreadBudget { internal: 1, external: 8 });
+        assert_eq!(ThreadBudget::split(1, 0.5), ThreadBudget { internal: 1, external: 1 });
+        assert_eq!(ThreadBudget::split(8, f32::NAN).external, 1);
+        assert_eq!(ThreadBudget::split(0, 0.5).total(), num_cpus::get().max(2));
+
+        let pool = ThreadBudget::split(4, 0.5).build_pool().unwrap();
+        assert_eq!(pool.current_num_threads(), 2);
+
+        assert_eq!(parse_thread_fraction("0.25"), Ok(0.25));
+        assert!(parse_thread_fraction("1.5").is_err());
+        assert!(parse_thread_fraction("NaN").is_err());
+        assert!(parse_thread_fraction("half").is_err());
+    }
+
     // ============ TC PAR-007: Error handling ============
 
     #[test]
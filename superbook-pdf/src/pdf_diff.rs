@@ -0,0 +1,429 @@
+//! Page-by-page comparison of two PDFs
+//!
+//! Renders both documents to grayscale page images at a fixed DPI and
+//! measures each page pair with the mean absolute difference (in gray levels)
+//! and SSIM, so a change of options or a tool upgrade can be checked for
+//! pages that changed unexpectedly.
+//!
+//! - Pages are compared at the size of the page from the first PDF; a page
+//!   of the second PDF with another size is stretched to it
+//! - A page whose mean absolute difference exceeds the threshold counts as
+//!   changed and can get a heatmap (the first page faded, differences in red)
+//! - With differing page counts the common pages are compared and the
+//!   mismatch is reported
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::pdf_diff::PdfDiff;
+//! use std::path::Path;
+//!
+//! let report = PdfDiff::new()
+//!     .with_threshold(1.0)
+//!     .run(Path::new("before.pdf"), Path::new("after.pdf"), Path::new("/tmp/diff"))
+//!     .unwrap();
+//! println!("Changed pages: {:?}", report.changed_pages());
+//! ```
+
+use image::{imageops::FilterType, GrayImage, Rgb, RgbImage};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::image_extract::{ColorSpace, ExtractOptions, ImageFormat, LopdfExtractor};
+use crate::quality::QualityError;
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// DPI both PDFs are rendered at
+pub const DEFAULT_DIFF_DPI: u32 = 100;
+
+/// Mean absolute difference (gray levels) above which a page counts as changed
+///
+/// Re-encoding the same page as JPEG stays well below it.
+pub const DEFAULT_DIFF_THRESHOLD: f64 = 2.0;
+
+/// Factor applied to a pixel difference before it is drawn in the heatmap
+const HEATMAP_GAIN: u32 = 4;
+
+// ============================================================
+// Error Types
+// ============================================================
+
+/// PDF comparison error
+#[derive(Debug, Error)]
+pub enum DiffError {
+    #[error("PDF not found: {0}")]
+    PdfNotFound(PathBuf),
+
+    #[error("Could not render {0}: {1}")]
+    Render(String, String),
+
+    #[error("Image error: {0}")]
+    ImageError(String),
+
+    #[error(transparent)]
+    Quality(#[from] QualityError),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("{changed} page(s) changed, page count {pages_a} vs {pages_b}")]
+    Differs {
+        /// Number of changed pages
+        changed: usize,
+        /// Page count of the first PDF
+        pages_a: usize,
+        /// Page count of the second PDF
+        pages_b: usize,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, DiffError>;
+
+// ============================================================
+// Data Structures
+// ============================================================
+
+/// Comparison of one page pair
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PageDiff {
+    /// Page number (1-based)
+    pub page: usize,
+    /// Mean absolute difference in gray levels (0-255)
+    pub mean_abs_diff: f64,
+    /// Mean structural similarity (0.0-1.0, 1.0 = identical)
+    pub ssim: f64,
+    /// Rendered size of the page in the first PDF
+    pub size_a: (u32, u32),
+    /// Rendered size of the page in the second PDF
+    pub size_b: (u32, u32),
+    /// Mean absolute difference exceeds the threshold
+    pub changed: bool,
+    /// Heatmap written for a changed page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heatmap: Option<PathBuf>,
+}
+
+/// Result of comparing two PDFs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffReport {
+    /// First PDF
+    pub a: PathBuf,
+    /// Second PDF
+    pub b: PathBuf,
+    /// Render DPI
+    pub dpi: u32,
+    /// Mean absolute difference above which a page counts as changed
+    pub threshold: f64,
+    /// Page count of the first PDF
+    pub pages_a: usize,
+    /// Page count of the second PDF
+    pub pages_b: usize,
+    /// Comparison of the pages both PDFs have
+    pub pages: Vec<PageDiff>,
+}
+
+impl DiffReport {
+    /// Whether the PDFs have different page counts
+    pub fn page_count_mismatch(&self) -> bool {
+        self.pages_a != self.pages_b
+    }
+
+    /// Changed pages (1-based)
+    pub fn changed_pages(&self) -> Vec<usize> {
+        self.pages.iter().filter(|page| page.changed).map(|page| page.page).collect()
+    }
+
+    /// Whether no page changed and the page counts match
+    pub fn is_identical(&self) -> bool {
+        !self.page_count_mismatch() && self.pages.iter().all(|page| !page.changed)
+    }
+
+    /// Error describing the differences, None if the PDFs match
+    pub fn differences(&self) -> Option<DiffError> {
+        (!self.is_identical()).then(|| DiffError::Differs {
+            changed: self.changed_pages().len(),
+            pages_a: self.pages_a,
+            pages_b: self.pages_b,
+        })
+    }
+}
+
+// ============================================================
+// Comparison
+// ============================================================
+
+/// Page-by-page PDF comparison
+#[derive(Debug, Clone)]
+pub struct PdfDiff {
+    dpi: u32,
+    threshold: f64,
+    heatmap_dir: Option<PathBuf>,
+}
+
+impl Default for PdfDiff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PdfDiff {
+    pub fn new() -> Self {
+        Self {
+            dpi: DEFAULT_DIFF_DPI,
+            threshold: DEFAULT_DIFF_THRESHOLD,
+            heatmap_dir: None,
+        }
+    }
+
+    /// Render DPI
+    #[must_use]
+    pub fn with_dpi(mut self, dpi: u32) -> Self {
+        self.dpi = dpi;
+        self
+    }
+
+    /// Mean absolute difference (gray levels) above which a page counts as changed
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Write a heatmap for each changed page into `dir`
+    #[must_use]
+    pub fn with_heatmap_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.heatmap_dir = Some(dir.into());
+        self
+    }
+
+    /// Render `a` and `b` into `work_dir` and compare their common pages
+    pub fn run(&self, a: &Path, b: &Path, work_dir: &Path) -> Result<DiffReport> {
+        let pages_a = self.render(a, &work_dir.join("a"))?;
+        let pages_b = self.render(b, &work_dir.join("b"))?;
+        if let Some(dir) = &self.heatmap_dir {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let common = pages_a.len().min(pages_b.len());
+        let pages = (0..common)
+            .map(|index| self.compare(index, &pages_a[index], &pages_b[index]))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DiffReport {
+            a: a.to_path_buf(),
+            b: b.to_path_buf(),
+            dpi: self.dpi,
+            threshold: self.threshold,
+            pages_a: pages_a.len(),
+            pages_b: pages_b.len(),
+            pages,
+        })
+    }
+
+    /// Render every page of `pdf` to a grayscale PNG, in page order
+    fn render(&self, pdf: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        if !pdf.exists() {
+            return Err(DiffError::PdfNotFound(pdf.to_path_buf()));
+        }
+        let page_count = crate::LopdfReader::new(pdf)
+            .map_err(|e| DiffError::Render(pdf.display().to_string(), e.to_string()))?
+            .info
+            .page_count;
+
+        let options = ExtractOptions::builder()
+            .dpi(self.dpi)
+            .format(ImageFormat::Png)
+            .colorspace(ColorSpace::Grayscale)
+            .build();
+        let mut rendered: HashMap<usize, PathBuf> = LopdfExtractor::extract_auto(pdf, output_dir, &options)
+            .map_err(|e| DiffError::Render(pdf.display().to_string(), e.to_string()))?
+            .into_iter()
+            .map(|page| (page.page_index, page.path))
+            .collect();
+
+        (0..page_count)
+            .map(|index| {
+                rendered.remove(&index).ok_or_else(|| {
+                    DiffError::Render(pdf.display().to_string(), format!("page {} was not rendered", index + 1))
+                })
+            })
+            .collect()
+    }
+
+    fn compare(&self, index: usize, path_a: &Path, path_b: &Path) -> Result<PageDiff> {
+        let a = load_gray(path_a)?;
+        let b = load_gray(path_b)?;
+        let (size_a, size_b) = (a.dimensions(), b.dimensions());
+        let b = if size_a == size_b {
+            b
+        } else {
+            image::imageops::resize(&b, size_a.0, size_a.1, FilterType::Triangle)
+        };
+
+        let mean_abs_diff = mean_abs_diff(&a, &b)?;
+        let changed = mean_abs_diff > self.threshold;
+        let heatmap = match &self.heatmap_dir {
+            Some(dir) if changed => {
+                let path = dir.join(format!("diff_page_{:04}.png", index + 1));
+                heatmap(&a, &b)?
+                    .save(&path)
+                    .map_err(|e| DiffError::ImageError(format!("{}: {}", path.display(), e)))?;
+                Some(path)
+            }
+            _ => None,
+        };
+
+        Ok(PageDiff {
+            page: index + 1,
+            mean_abs_diff,
+            ssim: crate::quality::ssim(&a, &b)?,
+            size_a,
+            size_b,
+            changed,
+            heatmap,
+        })
+    }
+}
+
+/// Mean absolute difference of two equally sized images in gray levels
+pub fn mean_abs_diff(a: &GrayImage, b: &GrayImage) -> Result<f64> {
+    if a.dimensions() != b.dimensions() {
+        return Err(QualityError::SizeMismatch(a.dimensions(), b.dimensions()).into());
+    }
+    let pixels = a.as_raw().len();
+    if pixels == 0 {
+        return Ok(0.0);
+    }
+    let total: u64 = a.as_raw().iter().zip(b.as_raw()).map(|(&va, &vb)| u64::from(va.abs_diff(vb))).sum();
+    Ok(total as f64 / pixels as f64)
+}
+
+/// Difference heatmap: `a` faded to light gray with differences drawn in red,
+/// stronger where the pixels differ more
+pub fn heatmap(a: &GrayImage, b: &GrayImage) -> Result<RgbImage> {
+    if a.dimensions() != b.dimensions() {
+        return Err(QualityError::SizeMismatch(a.dimensions(), b.dimensions()).into());
+    }
+    Ok(RgbImage::from_fn(a.width(), a.height(), |x, y| {
+        let va = a.get_pixel(x, y).0[0];
+        let vb = b.get_pixel(x, y).0[0];
+        let base = 160 + u32::from(va) * 95 / 255;
+        let weight = (u32::from(va.abs_diff(vb)) * HEATMAP_GAIN).min(255);
+        let blend = |target: u32| ((base * (255 - weight) + target * weight) / 255) as u8;
+        Rgb([blend(255), blend(0), blend(0)])
+    }))
+}
+
+fn load_gray(path: &Path) -> Result<GrayImage> {
+    image::open(path)
+        .map(|img| img.to_luma8())
+        .map_err(|e| DiffError::ImageError(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    #[test]
+    fn test_mean_abs_diff_and_heatmap() {
+        let a = GrayImage::from_pixel(16, 8, Luma([200]));
+        let mut b = a.clone();
+        assert_eq!(mean_abs_diff(&a, &b).unwrap(), 0.0);
+
+        // Half the image 100 levels darker
+        for y in 0..8 {
+            for x in 0..8 {
+                b.put_pixel(x, y, Luma([100]));
+            }
+        }
+        assert_eq!(mean_abs_diff(&a, &b).unwrap(), 50.0);
+
+        let map = heatmap(&a, &b).unwrap();
+        assert_eq!(map.dimensions(), (16, 8));
+        // Unchanged pixels stay gray, changed ones turn red
+        let unchanged = map.get_pixel(12, 4).0;
+        assert_eq!(unchanged[0], unchanged[1]);
+        let changed = map.get_pixel(2, 4).0;
+        assert_eq!(changed, [255, 0, 0]);
+
+        let small = GrayImage::new(4, 4);
+        assert!(matches!(mean_abs_diff(&a, &small), Err(DiffError::Quality(_))));
+        assert!(heatmap(&a, &small).is_err());
+    }
+
+    #[test]
+    fn test_report_differences() {
+        let page = |page, changed| PageDiff {
+            page,
+            mean_abs_diff: if changed { 10.0 } else { 0.0 },
+            ssim: 1.0,
+            size_a: (10, 10),
+            size_b: (10, 10),
+            changed,
+            heatmap: None,
+        };
+        let mut report = DiffReport {
+            a: PathBuf::from("a.pdf"),
+            b: PathBuf::from("b.pdf"),
+            dpi: DEFAULT_DIFF_DPI,
+            threshold: DEFAULT_DIFF_THRESHOLD,
+            pages_a: 2,
+            pages_b: 2,
+            pages: vec![page(1, false), page(2, false)],
+        };
+        assert!(report.is_identical());
+        assert!(report.differences().is_none());
+
+        report.pages[1].changed = true;
+        assert_eq!(report.changed_pages(), vec![2]);
+        assert!(matches!(report.differences(), Some(DiffError::Differs { changed: 1, .. })));
+
+        report.pages[1].changed = false;
+        report.pages_b = 3;
+        assert!(report.page_count_mismatch());
+        assert!(!report.is_identical());
+    }
+
+    #[test]
+    fn test_diff_pdfs() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.pdf");
+        let same = dir.path().join("same.pdf");
+        let longer = dir.path().join("longer.pdf");
+        crate::selftest::write_synthetic_pdf(&a, 2).unwrap();
+        crate::selftest::write_synthetic_pdf(&same, 2).unwrap();
+        crate::selftest::write_synthetic_pdf(&longer, 3).unwrap();
+
+        let report = PdfDiff::new().run(&a, &same, &dir.path().join("work1")).unwrap();
+        assert_eq!((report.pages_a, report.pages_b), (2, 2));
+        assert_eq!(report.pages.len(), 2);
+        assert!(report.is_identical());
+        assert!(report.pages.iter().all(|page| page.mean_abs_diff == 0.0 && page.ssim > 0.999));
+
+        // Extra page in the second PDF; a negative threshold marks every
+        // page as changed so heatmaps are written
+        let heatmaps = dir.path().join("heatmaps");
+        let report = PdfDiff::new()
+            .with_threshold(-1.0)
+            .with_heatmap_dir(&heatmaps)
+            .run(&a, &longer, &dir.path().join("work2"))
+            .unwrap();
+        assert!(report.page_count_mismatch());
+        assert_eq!(report.pages.len(), 2);
+        assert_eq!(report.changed_pages(), vec![1, 2]);
+        let heatmap = report.pages[0].heatmap.as_ref().unwrap();
+        assert_eq!(heatmap, &heatmaps.join("diff_page_0001.png"));
+        assert!(heatmap.exists());
+
+        assert!(matches!(
+            PdfDiff::new().run(&dir.path().join("missing.pdf"), &a, &dir.path().join("work3")),
+            Err(DiffError::PdfNotFound(_))
+        ));
+    }
+}
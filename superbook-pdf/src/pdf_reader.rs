@@ -19,7 +19,9 @@
 //! println!("Title: {:?}", reader.info.metadata.title);
 //! ```
 
-use lopdf::Document;
+use lopdf::{Document, Object, ObjectId};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -40,10 +42,74 @@ pub enum PdfReaderError {
 
     #[error("PDF parse error: {0}")]
     ParseError(String),
+
+    #[error("Not a valid PDF: {0}")]
+    InvalidPdf(#[from] InvalidPdf),
 }
 
 pub type Result<T> = std::result::Result<T, PdfReaderError>;
 
+/// Bytes searched for the `%PDF-` header and the `%%EOF` marker
+const PDF_MARKER_WINDOW: usize = 1024;
+
+/// Nesting limit when searching the named destination tree
+const MAX_NAME_TREE_DEPTH: usize = 32;
+
+/// Why a file cannot be a PDF, found without parsing it
+///
+/// Checked before any extraction tool runs, so a zero-byte or truncated
+/// download fails with this instead of an obscure tool error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum InvalidPdf {
+    #[error("file is empty")]
+    Empty,
+
+    #[error("missing %PDF- header")]
+    MissingHeader,
+
+    #[error("missing %%EOF marker (truncated file?)")]
+    Truncated,
+}
+
+/// Check for a `%PDF-` header in the first and an `%%EOF` marker in the
+/// last 1024 bytes of a PDF
+pub fn validate_pdf_bytes(data: &[u8]) -> std::result::Result<(), InvalidPdf> {
+    validate_pdf_ends(data, data, data.len() as u64)
+}
+
+/// [`validate_pdf_bytes`] for a file, reading only its first and last 1024 bytes
+pub fn validate_pdf_file(path: impl AsRef<Path>) -> Result<()> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let path = path.as_ref();
+    let mut file = std::fs::File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => PdfReaderError::FileNotFound(path.to_path_buf()),
+        _ => PdfReaderError::IoError(e),
+    })?;
+    let len = file.metadata()?.len();
+    let window = len.min(PDF_MARKER_WINDOW as u64);
+    let mut head = vec![0; window as usize];
+    file.read_exact(&mut head)?;
+    let mut tail = vec![0; window as usize];
+    file.seek(SeekFrom::End(-(window as i64)))?;
+    file.read_exact(&mut tail)?;
+    Ok(validate_pdf_ends(&head, &tail, len)?)
+}
+
+fn validate_pdf_ends(head: &[u8], tail: &[u8], len: u64) -> std::result::Result<(), InvalidPdf> {
+    let contains = |window: &[u8], marker: &[u8]| window.windows(marker.len()).any(|w| w == marker);
+    if len == 0 {
+        return Err(InvalidPdf::Empty);
+    }
+    if !contains(&head[..head.len().min(PDF_MARKER_WINDOW)], b"%PDF-") {
+        return Err(InvalidPdf::MissingHeader);
+    }
+    if !contains(&tail[tail.len().saturating_sub(PDF_MARKER_WINDOW)..], b"%%EOF") {
+        return Err(InvalidPdf::Truncated);
+    }
+    Ok(())
+}
+
 /// PDF document information
 #[derive(Debug, Clone)]
 pub struct PdfDocument {
@@ -55,18 +121,65 @@ pub struct PdfDocument {
 }
 
 /// PDF metadata
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PdfMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub subject: Option<String>,
+    /// Comma-separated keywords
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub keywords: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub creator: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub producer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub creation_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub modification_date: Option<String>,
 }
 
+impl PdfMetadata {
+    /// Overlay `overrides` on top of `self`; fields set in `overrides` win
+    #[must_use]
+    pub fn merged_with(&self, overrides: &PdfMetadata) -> PdfMetadata {
+        fn pick(base: &Option<String>, over: &Option<String>) -> Option<String> {
+            over.clone().or_else(|| base.clone())
+        }
+        PdfMetadata {
+            title: pick(&self.title, &overrides.title),
+            author: pick(&self.author, &overrides.author),
+            subject: pick(&self.subject, &overrides.subject),
+            keywords: pick(&self.keywords, &overrides.keywords),
+            creator: pick(&self.creator, &overrides.creator),
+            producer: pick(&self.producer, &overrides.producer),
+            creation_date: pick(&self.creation_date, &overrides.creation_date),
+            modification_date: pick(&self.modification_date, &overrides.modification_date),
+        }
+    }
+
+    /// Keywords split on `,` / `;`, trimmed and without empties
+    pub fn keyword_list(&self) -> Vec<String> {
+        self.keywords
+            .as_deref()
+            .unwrap_or_default()
+            .split([',', ';'])
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Check whether no field is set
+    pub fn is_empty(&self) -> bool {
+        *self == PdfMetadata::default()
+    }
+}
+
 /// Page information
 #[derive(Debug, Clone)]
 pub struct PdfPage {
@@ -84,6 +197,32 @@ pub struct PdfPage {
     pub has_text: bool,
 }
 
+/// Document outline (bookmark) entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineItem {
+    pub title: String,
+    /// 0-indexed destination page (None when the entry has no page destination)
+    pub page: Option<usize>,
+    pub children: Vec<OutlineItem>,
+}
+
+impl OutlineItem {
+    /// Copy of the entry and its children with destinations passed through `map`
+    #[must_use]
+    pub fn remapped(&self, map: &impl Fn(usize) -> Option<usize>) -> OutlineItem {
+        OutlineItem {
+            title: self.title.clone(),
+            page: self.page.and_then(map),
+            children: self.children.iter().map(|child| child.remapped(map)).collect(),
+        }
+    }
+
+    /// Number of entries in the subtree, this one included
+    pub fn count(&self) -> usize {
+        1 + self.children.iter().map(OutlineItem::count).sum::<usize>()
+    }
+}
+
 /// PDF Reader trait
 pub trait PdfReader {
     /// Open a PDF file
@@ -104,7 +243,6 @@ pub trait PdfReader {
 
 /// lopdf-based PDF reader implementation
 pub struct LopdfReader {
-    #[allow(dead_code)]
     document: Document,
     pub info: PdfDocument,
 }
@@ -171,12 +309,7 @@ impl LopdfReader {
     fn get_string_from_dict(dict: &lopdf::Dictionary, key: &[u8]) -> Option<String> {
         dict.get(key).ok().and_then(|obj| {
             match obj {
-                lopdf::Object::String(bytes, _) => {
-                    // Try UTF-8 first, then Latin-1
-                    String::from_utf8(bytes.clone())
-                        .ok()
-                        .or_else(|| Some(bytes.iter().map(|&b| b as char).collect()))
-                }
+                lopdf::Object::String(bytes, _) => Some(decode_text_string(bytes)),
                 _ => None,
             }
         })
@@ -292,6 +425,211 @@ impl LopdfReader {
     pub fn is_encrypted(&self) -> bool {
         self.info.is_encrypted
     }
+
+    /// Resolution (pixels per inch) of the largest image on a page
+    ///
+    /// Assumes the image covers the page, as a scan does; None when the page
+    /// has no image XObject.
+    pub fn page_image_dpi(&self, index: usize) -> Option<f64> {
+        let page_id = *self.document.get_pages().get(&(index as u32 + 1))?;
+        self.image_dpi(self.get_page(index).ok()?, page_id)
+    }
+
+    /// Median [`page_image_dpi`](Self::page_image_dpi) over the pages with an image
+    pub fn source_image_dpi(&self) -> Option<f64> {
+        let mut values: Vec<f64> = self
+            .document
+            .get_pages()
+            .values()
+            .zip(&self.info.pages)
+            .filter_map(|(&page_id, page)| self.image_dpi(page, page_id))
+            .collect();
+        values.sort_by(f64::total_cmp);
+        values.get(values.len() / 2).copied()
+    }
+
+    fn image_dpi(&self, page: &PdfPage, page_id: lopdf::ObjectId) -> Option<f64> {
+        let (width, height) = self
+            .document
+            .get_page_images(page_id)
+            .ok()?
+            .iter()
+            .map(|image| (image.width, image.height))
+            .filter(|&(width, height)| width > 0 && height > 0)
+            .max_by_key(|&(width, height)| width * height)?;
+        // Area-based, so images placed rotated on the page give the same value
+        let page_area_in = (page.width_pt / 72.0) * (page.height_pt / 72.0);
+        (page_area_in > 0.0).then(|| ((width * height) as f64 / page_area_in).sqrt())
+    }
+
+    /// Text drawn by a page's content stream (no positions)
+    pub fn page_text(&self, index: usize) -> Result<String> {
+        self.get_page(index)?;
+        self.document
+            .extract_text(&[index as u32 + 1])
+            .map_err(|e| PdfReaderError::ParseError(e.to_string()))
+    }
+
+    /// Document outline with destinations resolved to page indices (empty
+    /// when the document has none)
+    ///
+    /// Explicit destinations, GoTo actions and named destinations (catalog
+    /// `/Dests` or the `/Names` tree) are followed; other actions leave the
+    /// entry without a page.
+    pub fn outline(&self) -> Vec<OutlineItem> {
+        let pages: HashMap<ObjectId, usize> = self
+            .document
+            .get_pages()
+            .values()
+            .enumerate()
+            .map(|(index, &page_id)| (page_id, index))
+            .collect();
+        let first = self
+            .document
+            .catalog()
+            .ok()
+            .and_then(|catalog| self.resolve(catalog.get(b"Outlines").ok()?))
+            .and_then(|outlines| outlines.as_dict().ok()?.get(b"First").ok());
+        match first {
+            Some(first) => self.outline_siblings(first, &pages, &mut HashSet::new()),
+            None => vec![],
+        }
+    }
+
+    /// Entries of the `/Next` chain starting at `node` (`visited` stops
+    /// cyclic links)
+    fn outline_siblings(
+        &self,
+        node: &Object,
+        pages: &HashMap<ObjectId, usize>,
+        visited: &mut HashSet<ObjectId>,
+    ) -> Vec<OutlineItem> {
+        let mut items = Vec::new();
+        let mut next = Some(node);
+        while let Some(node) = next {
+            let Ok(id) = node.as_reference() else {
+                break;
+            };
+            let Ok(entry) = self.document.get_dictionary(id) else {
+                break;
+            };
+            if !visited.insert(id) {
+                break;
+            }
+            let children = match entry.get(b"First") {
+                Ok(first) => self.outline_siblings(first, pages, visited),
+                Err(_) => vec![],
+            };
+            items.push(OutlineItem {
+                title: entry
+                    .get(b"Title")
+                    .ok()
+                    .and_then(|title| self.resolve(title)?.as_str().ok())
+                    .map(decode_text_string)
+                    .unwrap_or_default(),
+                page: self.outline_page(entry).and_then(|page_id| pages.get(&page_id).copied()),
+                children,
+            });
+            next = entry.get(b"Next").ok();
+        }
+        items
+    }
+
+    /// Page object an outline entry points to
+    fn outline_page(&self, entry: &lopdf::Dictionary) -> Option<ObjectId> {
+        let dest = match entry.get(b"Dest") {
+            Ok(dest) => dest,
+            Err(_) => {
+                let action = self.resolve(entry.get(b"A").ok()?)?.as_dict().ok()?;
+                if action.get(b"S").and_then(Object::as_name).ok()? != b"GoTo" {
+                    return None;
+                }
+                action.get(b"D").ok()?
+            }
+        };
+        let dest = match self.resolve(dest)? {
+            Object::String(name, _) | Object::Name(name) => self.named_destination(name)?,
+            explicit => explicit,
+        };
+        // Named destinations may be wrapped as `<< /D [...] >>`
+        let dest = match dest {
+            Object::Dictionary(dict) => self.resolve(dict.get(b"D").ok()?)?,
+            explicit => explicit,
+        };
+        dest.as_array().ok()?.first()?.as_reference().ok()
+    }
+
+    /// Look up a named destination in the catalog `/Dests` dictionary or
+    /// the `/Names /Dests` name tree
+    fn named_destination(&self, name: &[u8]) -> Option<&Object> {
+        let catalog = self.document.catalog().ok()?;
+        let legacy = catalog
+            .get(b"Dests")
+            .ok()
+            .and_then(|dests| self.resolve(dests)?.as_dict().ok()?.get(name).ok());
+        if let Some(dest) = legacy {
+            return self.resolve(dest);
+        }
+        let names = self.resolve(catalog.get(b"Names").ok()?)?.as_dict().ok()?;
+        let tree = self.resolve(names.get(b"Dests").ok()?)?.as_dict().ok()?;
+        self.name_tree_lookup(tree, name, 0)
+    }
+
+    fn name_tree_lookup<'a>(&'a self, node: &'a lopdf::Dictionary, name: &[u8], depth: usize) -> Option<&'a Object> {
+        if let Some(names) = node.get(b"Names").ok().and_then(|names| self.resolve(names)?.as_array().ok()) {
+            for pair in names.chunks_exact(2) {
+                if pair[0].as_str().is_ok_and(|key| key == name) {
+                    return self.resolve(&pair[1]);
+                }
+            }
+        }
+        if depth >= MAX_NAME_TREE_DEPTH {
+            return None;
+        }
+        self.resolve(node.get(b"Kids").ok()?)?
+            .as_array()
+            .ok()?
+            .iter()
+            .filter_map(|kid| self.resolve(kid)?.as_dict().ok())
+            .find_map(|kid| self.name_tree_lookup(kid, name, depth + 1))
+    }
+
+    /// Follow references to the object they point to
+    fn resolve<'a>(&'a self, object: &'a Object) -> Option<&'a Object> {
+        self.document.dereference(object).ok().map(|(_, object)| object)
+    }
+}
+
+/// Decode a PDF text string: UTF-16BE with BOM, else UTF-8 (with or without
+/// BOM, as written by printpdf), else PDFDocEncoding
+pub(crate) fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_string(),
+        Err(_) => bytes.iter().map(|&b| pdf_doc_char(b)).collect(),
+    }
+}
+
+/// PDFDocEncoding: Latin-1 except for the ranges 0x18-0x1F and 0x80-0xA0
+fn pdf_doc_char(byte: u8) -> char {
+    const ACCENTS: [char; 8] = ['\u{02D8}', '\u{02C7}', '\u{02C6}', '\u{02D9}', '\u{02DD}', '\u{02DB}', '\u{02DA}', '\u{02DC}'];
+    const HIGH: [char; 33] = [
+        '\u{2022}', '\u{2020}', '\u{2021}', '\u{2026}', '\u{2014}', '\u{2013}', '\u{0192}', '\u{2044}',
+        '\u{2039}', '\u{203A}', '\u{2212}', '\u{2030}', '\u{201E}', '\u{201C}', '\u{201D}', '\u{2018}',
+        '\u{2019}', '\u{201A}', '\u{2122}', '\u{FB01}', '\u{FB02}', '\u{0141}', '\u{0152}', '\u{0160}',
+        '\u{0178}', '\u{017D}', '\u{0131}', '\u{0142}', '\u{0153}', '\u{0161}', '\u{017E}', '\u{FFFD}',
+        '\u{20AC}',
+    ];
+    match byte {
+        0x18..=0x1F => ACCENTS[(byte - 0x18) as usize],
+        0x80..=0xA0 => HIGH[(byte - 0x80) as usize],
+        0xAD => '\u{FFFD}',
+        _ => byte as char,
+    }
 }
 
 #[cfg(test)]
@@ -405,6 +743,27 @@ mod tests {
         assert!(results.iter().all(|r| r.is_ok()));
     }
 
+    // TC-PDR-011: 埋め込み画像の解像度
+    #[test]
+    fn test_source_image_dpi() {
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("page.png");
+        image::GrayImage::from_pixel(300, 420, image::Luma([200])).save(&image).unwrap();
+        let output = dir.path().join("scan.pdf");
+        let options = crate::PdfWriterOptions::builder().dpi(100).build();
+        crate::PrintPdfWriter::create_from_images(&[image.clone(), image], &output, &options).unwrap();
+
+        let doc = LopdfReader::new(&output).unwrap();
+        let dpi = doc.page_image_dpi(0).unwrap();
+        assert!((dpi - 100.0).abs() < 1.0, "{}", dpi);
+        assert!((doc.source_image_dpi().unwrap() - 100.0).abs() < 1.0);
+        assert!(doc.page_image_dpi(5).is_none());
+
+        // Fixture pages are 72 DPI scans
+        let fixture = LopdfReader::new("tests/fixtures/10pages.pdf").unwrap();
+        assert!((fixture.source_image_dpi().unwrap() - 72.0).abs() < 1.0);
+    }
+
     // Additional structure tests
 
     #[test]
@@ -1007,6 +1366,46 @@ mod tests {
         assert!(meta.author.as_ref().unwrap().contains("山田"));
     }
 
+    #[test]
+    fn test_metadata_merged_with() {
+        let base = PdfMetadata {
+            title: Some("scan_001".to_string()),
+            creator: Some("superbook-pdf".to_string()),
+            ..Default::default()
+        };
+        let overrides = PdfMetadata {
+            title: Some("Real Title".to_string()),
+            author: Some("Author".to_string()),
+            ..Default::default()
+        };
+
+        let merged = base.merged_with(&overrides);
+        assert_eq!(merged.title.as_deref(), Some("Real Title"));
+        assert_eq!(merged.author.as_deref(), Some("Author"));
+        assert_eq!(merged.creator.as_deref(), Some("superbook-pdf"));
+        assert!(PdfMetadata::default().is_empty());
+        assert!(!merged.is_empty());
+    }
+
+    #[test]
+    fn test_metadata_keyword_list() {
+        let meta = PdfMetadata {
+            keywords: Some("novel, 小説;; history ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(meta.keyword_list(), vec!["novel", "小説", "history"]);
+        assert!(PdfMetadata::default().keyword_list().is_empty());
+    }
+
+    #[test]
+    fn test_metadata_toml_roundtrip() {
+        let meta: PdfMetadata = toml::from_str("title = \"Book\"\nkeywords = \"a,b\"").unwrap();
+        assert_eq!(meta.title.as_deref(), Some("Book"));
+        assert_eq!(meta.author, None);
+        let text = toml::to_string(&meta).unwrap();
+        assert!(!text.contains("author"));
+    }
+
     #[test]
     fn test_page_aspect_ratios() {
         // Portrait
@@ -1168,6 +1567,151 @@ mod tests {
         assert!(msg.contains("Invalid") || msg.contains("format"));
     }
 
+    // TC-PDR-012: 空・ヘッダーなし・途中で切れたファイルの検出
+    #[test]
+    fn test_validate_pdf() {
+        assert_eq!(validate_pdf_bytes(b""), Err(InvalidPdf::Empty));
+        assert_eq!(validate_pdf_bytes(b"GIF89a"), Err(InvalidPdf::MissingHeader));
+        assert_eq!(validate_pdf_bytes(b"%PDF-1.4\n1 0 obj"), Err(InvalidPdf::Truncated));
+        assert_eq!(validate_pdf_bytes(b"%PDF-1.4\n%%EOF\n"), Ok(()));
+        // Junk before the header and after the marker is tolerated
+        assert_eq!(validate_pdf_bytes(b"\x00\x00%PDF-1.7\n%%EOF\r\n\x00"), Ok(()));
+
+        let fixture = std::fs::read("tests/fixtures/sample.pdf").unwrap();
+        assert!(validate_pdf_file("tests/fixtures/sample.pdf").is_ok());
+
+        let dir = tempfile::tempdir().unwrap();
+        let truncated = dir.path().join("truncated.pdf");
+        std::fs::write(&truncated, &fixture[..fixture.len() - 100]).unwrap();
+        let err = validate_pdf_file(&truncated).unwrap_err();
+        assert!(matches!(err, PdfReaderError::InvalidPdf(InvalidPdf::Truncated)));
+        assert!(err.to_string().contains("truncated"));
+
+        let empty = dir.path().join("empty.pdf");
+        std::fs::write(&empty, b"").unwrap();
+        assert!(matches!(validate_pdf_file(&empty), Err(PdfReaderError::InvalidPdf(InvalidPdf::Empty))));
+        assert!(matches!(
+            validate_pdf_file(dir.path().join("missing.pdf")),
+            Err(PdfReaderError::FileNotFound(_))
+        ));
+    }
+
+    // TC-PDR-013: アウトライン (しおり) の読み取り
+    #[test]
+    fn test_outline() {
+        use lopdf::{dictionary, StringFormat};
+
+        let mut doc = Document::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page_ids: Vec<ObjectId> = (0..3)
+            .map(|_| doc.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id }))
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => page_ids.iter().map(|&id| Object::Reference(id)).collect::<Vec<_>>(),
+                "Count" => 3,
+                "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+            }),
+        );
+
+        let outlines_id = doc.new_object_id();
+        let [chapter_id, section_id, appendix_id] = [(); 3].map(|_| doc.new_object_id());
+        let utf16_title = |text: &str| {
+            let mut bytes = vec![0xFE, 0xFF];
+            bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+            Object::String(bytes, StringFormat::Hexadecimal)
+        };
+        // Explicit destination
+        doc.objects.insert(
+            chapter_id,
+            Object::Dictionary(dictionary! {
+                "Title" => utf16_title("第一章"),
+                "Parent" => outlines_id,
+                "Next" => appendix_id,
+                "First" => section_id,
+                "Last" => section_id,
+                "Dest" => vec![page_ids[0].into(), "Fit".into()],
+            }),
+        );
+        // GoTo action to a named destination; `Next` loops back to the chapter
+        doc.objects.insert(
+            section_id,
+            Object::Dictionary(dictionary! {
+                "Title" => Object::string_literal("Section"),
+                "Parent" => chapter_id,
+                "Next" => chapter_id,
+                "A" => dictionary! { "S" => "GoTo", "D" => Object::string_literal("sec") },
+            }),
+        );
+        // URI action: no page
+        doc.objects.insert(
+            appendix_id,
+            Object::Dictionary(dictionary! {
+                "Title" => Object::string_literal("Link"),
+                "Parent" => outlines_id,
+                "A" => dictionary! { "S" => "URI", "URI" => Object::string_literal("https://example.com") },
+            }),
+        );
+        doc.objects.insert(
+            outlines_id,
+            Object::Dictionary(dictionary! { "Type" => "Outlines", "First" => chapter_id, "Last" => appendix_id }),
+        );
+        let names = dictionary! {
+            "Dests" => dictionary! {
+                "Kids" => vec![Object::Dictionary(dictionary! {
+                    "Names" => vec![
+                        Object::string_literal("sec"),
+                        Object::Dictionary(dictionary! { "D" => vec![page_ids[2].into(), "XYZ".into()] }),
+                    ],
+                })],
+            },
+        };
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "Outlines" => outlines_id,
+            "Names" => names,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("outline.pdf");
+        doc.save(&path).unwrap();
+
+        let outline = LopdfReader::new(&path).unwrap().outline();
+        let section = OutlineItem {
+            title: "Section".to_string(),
+            page: Some(2),
+            children: vec![],
+        };
+        assert_eq!(
+            outline,
+            vec![
+                OutlineItem {
+                    title: "第一章".to_string(),
+                    page: Some(0),
+                    children: vec![section],
+                },
+                OutlineItem {
+                    title: "Link".to_string(),
+                    page: None,
+                    children: vec![],
+                },
+            ]
+        );
+        assert_eq!(outline[0].count(), 2);
+
+        // Remapping drops destinations the map does not know
+        let remapped = outline[0].remapped(&|page| (page < 2).then_some(page + 1));
+        assert_eq!(remapped.page, Some(1));
+        assert_eq!(remapped.children[0].page, None);
+
+        let fixture = LopdfReader::new("tests/fixtures/sample.pdf").unwrap();
+        assert!(fixture.outline().is_empty());
+    }
+
     #[test]
     fn test_page_content_combinations() {
         // All combinations of has_images and has_text
@@ -1755,4 +2299,13 @@ mod tests {
         assert_eq!(cloned.path, doc.path);
         assert_eq!(cloned.metadata.title, doc.metadata.title);
     }
+
+    #[test]
+    fn test_decode_text_string_encodings() {
+        assert_eq!(decode_text_string(&[0xFE, 0xFF, 0x67, 0x2C]), "本");
+        assert_eq!(decode_text_string("本".as_bytes()), "本");
+        assert_eq!(decode_text_string(b"\xEF\xBB\xBFBook"), "Book");
+        // PDFDocEncoding: bullet, em dash, euro, then Latin-1 e-acute
+        assert_eq!(decode_text_string(&[0x80, 0x84, 0xA0, 0xE9]), "\u{2022}\u{2014}\u{20AC}\u{E9}");
+    }
 }
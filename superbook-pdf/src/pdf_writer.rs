@@ -9,6 +9,15 @@
 //! - Optional OCR text layer for searchable PDFs
 //! - Metadata embedding
 //! - Multiple page size modes
+//! - Deterministic output (fixed dates and content-derived IDs)
+//! - Per-page color mode (grayscale text pages in mixed books)
+//! - Right-to-left binding hint (`/ViewerPreferences << /Direction /R2L >>`)
+//! - Identical pages share a single image XObject (lossless deduplication)
+//! - Bilevel (pure black-and-white) pages as 1-bit images, optionally
+//!   JBIG2-compressed with an external `jbig2enc`
+//! - Optional adaptive binarization of grayscale pages (see [`crate::binarize`])
+//! - PDF/A-1b / PDF/A-2b output for archival (see [`crate::pdfa`])
+//! - Document outline (bookmarks) pointing at output pages
 //!
 //! # Example
 //!
@@ -24,9 +33,15 @@
 //! PrintPdfWriter::create_from_images(&images, std::path::Path::new("output.pdf"), &options).unwrap();
 //! ```
 
-use crate::pdf_reader::PdfMetadata;
+use crate::binarize::AdaptiveMethod;
+use crate::color_stats::PageColorMode;
+use crate::page_number::PageLabelRange;
+use crate::pdf_reader::{OutlineItem, PdfMetadata};
+use crate::pdfa::PdfaLevel;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -67,6 +82,81 @@ const MIN_JPEG_QUALITY: u8 = 1;
 /// Maximum JPEG quality
 const MAX_JPEG_QUALITY: u8 = 100;
 
+/// Info dictionary key recording the generator version in deterministic mode
+const VERSION_INFO_KEY: &str = "SuperbookVersion";
+
+/// Info dictionary key recording the processing options
+const OPTIONS_INFO_KEY: &str = "SuperbookOptions";
+
+/// jbig2enc executable name (resolved through [`crate::tools`])
+pub const JBIG2_TOOL: &str = "jbig2";
+
+/// Page binding (reading) direction of the output book
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Binding {
+    /// Right-to-left when vertical text was detected, left-to-right otherwise
+    #[default]
+    Auto,
+    /// Western binding (pages turn to the left)
+    LeftToRight,
+    /// Japanese/manga binding (pages turn to the right)
+    RightToLeft,
+}
+
+impl Binding {
+    /// Resolve to right-to-left using the vertical-writing verdict
+    pub fn is_right_to_left(self, is_vertical: bool) -> bool {
+        match self {
+            Binding::Auto => is_vertical,
+            Binding::LeftToRight => false,
+            Binding::RightToLeft => true,
+        }
+    }
+
+    /// Short name for logs and the dry-run plan
+    pub fn name(self) -> &'static str {
+        match self {
+            Binding::Auto => "auto",
+            Binding::LeftToRight => "ltr",
+            Binding::RightToLeft => "rtl",
+        }
+    }
+}
+
+/// How bilevel (pure black-and-white) pages are embedded
+///
+/// Other pages are unaffected. JBIG2 uses jbig2enc's lossless generic
+/// region coding; without the encoder (or if it fails on a page) the page
+/// falls back to 1-bit Flate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageEncoding {
+    /// Same as other pages (8-bit)
+    #[default]
+    Standard,
+    /// 1 bit per pixel, Flate-compressed
+    Flate,
+    /// 1 bit per pixel, JBIG2-compressed (`/JBIG2Decode`)
+    Jbig2,
+}
+
+impl ImageEncoding {
+    /// Whether bilevel pages are written as-is (8-bit)
+    pub fn is_standard(&self) -> bool {
+        *self == ImageEncoding::Standard
+    }
+
+    /// Short name for logs and the dry-run plan
+    pub fn name(self) -> &'static str {
+        match self {
+            ImageEncoding::Standard => "standard",
+            ImageEncoding::Flate => "flate",
+            ImageEncoding::Jbig2 => "jbig2",
+        }
+    }
+}
+
 /// PDF writing error types
 #[derive(Debug, Error)]
 pub enum PdfWriterError {
@@ -103,6 +193,32 @@ pub struct PdfWriterOptions {
     pub metadata: Option<PdfMetadata>,
     /// OCR text layer
     pub ocr_layer: Option<OcrLayer>,
+    /// Produce byte-identical output for identical input
+    pub deterministic: bool,
+    /// Processing options to record in the Info dictionary (JSON)
+    pub recorded_options: Option<String>,
+    /// Per-page color mode (pages beyond the list are written in color)
+    pub page_color_modes: Vec<PageColorMode>,
+    /// Per-page regions `(x, y, width, height)` in image pixels kept in
+    /// color over a grayscale page (figures with `ColorPolicy::FiguresOnly`)
+    pub page_color_regions: Vec<Vec<(u32, u32, u32, u32)>>,
+    /// Mark the document as right-to-left bound for viewers
+    pub right_to_left: bool,
+    /// Encoding of bilevel pages
+    pub image_encoding: ImageEncoding,
+    /// PDF/A conformance level (see [`PdfWriterOptions::pdfa_level`])
+    pub pdfa: Option<PdfaLevel>,
+    /// Page label ranges written to the catalog `/PageLabels`
+    pub page_labels: Vec<PageLabelRange>,
+    /// Outline entries (destinations are 0-based output page indices)
+    pub outline: Vec<OutlineItem>,
+    /// Binarize grayscale pages with a local threshold (they become bilevel
+    /// and are encoded per `image_encoding`)
+    pub binarize: Option<AdaptiveMethod>,
+    /// Color transparent pixels of the page images are composited over
+    pub background: [u8; 3],
+    /// Context the JBIG2 encoder runs in (niceness, limits)
+    pub tools: crate::tools::ToolContext,
 }
 
 impl Default for PdfWriterOptions {
@@ -114,11 +230,56 @@ impl Default for PdfWriterOptions {
             page_size_mode: PageSizeMode::FirstPage,
             metadata: None,
             ocr_layer: None,
+            deterministic: false,
+            recorded_options: None,
+            page_color_modes: Vec::new(),
+            page_color_regions: Vec::new(),
+            right_to_left: false,
+            image_encoding: ImageEncoding::Standard,
+            pdfa: None,
+            page_labels: Vec::new(),
+            outline: Vec::new(),
+            binarize: None,
+            background: crate::util::DEFAULT_FLATTEN_BACKGROUND,
+            tools: crate::tools::ToolContext::default(),
         }
     }
 }
 
 impl PdfWriterOptions {
+    /// Color mode for a page (color unless listed otherwise)
+    pub fn color_mode(&self, page_index: usize) -> PageColorMode {
+        self.page_color_modes.get(page_index).copied().unwrap_or_default()
+    }
+
+    /// Regions of a page kept in color (empty for most pages)
+    pub fn color_regions(&self, page_index: usize) -> &[(u32, u32, u32, u32)] {
+        self.page_color_regions.get(page_index).map_or(&[], Vec::as_slice)
+    }
+
+    /// PDF/A level actually written: PDF/A-1b is raised to PDF/A-2b when
+    /// there is an OCR text layer, since its font is not embedded
+    pub fn pdfa_level(&self) -> Option<PdfaLevel> {
+        let level = self.pdfa?;
+        let has_text = self.ocr_layer.as_ref().is_some_and(|layer| layer.pages.iter().any(|p| !p.blocks.is_empty()));
+        if has_text && !level.allows_unembedded_invisible_text() {
+            Some(PdfaLevel::A2b)
+        } else {
+            Some(level)
+        }
+    }
+
+    /// Human-readable notes on how the requested PDF/A level was adjusted
+    pub fn pdfa_adjustments(&self) -> Vec<String> {
+        match (self.pdfa, self.pdfa_level()) {
+            (Some(requested), Some(written)) if requested != written => vec![format!(
+                "{} requires embedded fonts but the OCR text layer uses an unembedded font; writing {} instead",
+                requested, written
+            )],
+            _ => Vec::new(),
+        }
+    }
+
     /// Create a new options builder
     pub fn builder() -> PdfWriterOptionsBuilder {
         PdfWriterOptionsBuilder::default()
@@ -194,6 +355,91 @@ impl PdfWriterOptionsBuilder {
         self
     }
 
+    /// Enable deterministic output
+    #[must_use]
+    pub fn deterministic(mut self, enabled: bool) -> Self {
+        self.options.deterministic = enabled;
+        self
+    }
+
+    /// Record processing options (JSON) in the document Info dictionary
+    #[must_use]
+    pub fn recorded_options(mut self, options: impl Into<String>) -> Self {
+        self.options.recorded_options = Some(options.into());
+        self
+    }
+
+    /// Set per-page color modes (grayscale pages are embedded as DeviceGray)
+    #[must_use]
+    pub fn page_color_modes(mut self, modes: Vec<PageColorMode>) -> Self {
+        self.options.page_color_modes = modes;
+        self
+    }
+
+    /// Set per-page regions kept in color; the rest of such a page is
+    /// written per its color mode with the regions blanked
+    #[must_use]
+    pub fn page_color_regions(mut self, regions: Vec<Vec<(u32, u32, u32, u32)>>) -> Self {
+        self.options.page_color_regions = regions;
+        self
+    }
+
+    /// Set right-to-left binding (viewer preference `Direction /R2L`)
+    #[must_use]
+    pub fn right_to_left(mut self, enabled: bool) -> Self {
+        self.options.right_to_left = enabled;
+        self
+    }
+
+    /// Set page label ranges (the viewer shows the book's own numbering)
+    #[must_use]
+    pub fn page_labels(mut self, ranges: Vec<PageLabelRange>) -> Self {
+        self.options.page_labels = ranges;
+        self
+    }
+
+    /// Set the document outline (bookmarks)
+    #[must_use]
+    pub fn outline(mut self, items: Vec<OutlineItem>) -> Self {
+        self.options.outline = items;
+        self
+    }
+
+    /// Set the encoding of bilevel pages
+    #[must_use]
+    pub fn image_encoding(mut self, encoding: ImageEncoding) -> Self {
+        self.options.image_encoding = encoding;
+        self
+    }
+
+    /// Write PDF/A output at the given level
+    #[must_use]
+    pub fn pdfa(mut self, level: PdfaLevel) -> Self {
+        self.options.pdfa = Some(level);
+        self
+    }
+
+    /// Binarize grayscale pages with a local threshold
+    #[must_use]
+    pub fn binarize(mut self, method: AdaptiveMethod) -> Self {
+        self.options.binarize = Some(method);
+        self
+    }
+
+    /// Set the color transparent pixels are composited over
+    #[must_use]
+    pub fn background(mut self, rgb: [u8; 3]) -> Self {
+        self.options.background = rgb;
+        self
+    }
+
+    /// Set the context the JBIG2 encoder runs in
+    #[must_use]
+    pub fn tools(mut self, tools: crate::tools::ToolContext) -> Self {
+        self.options.tools = tools;
+        self
+    }
+
     /// Build the options
     #[must_use]
     pub fn build(self) -> PdfWriterOptions {
@@ -223,6 +469,9 @@ pub enum PageSizeMode {
     Fixed { width_pt: f64, height_pt: f64 },
     /// Keep original size for each page
     Original,
+    /// Match the first page in the majority orientation; pages in the
+    /// other orientation (fold-outs) keep their own size
+    MainOrientation,
 }
 
 /// OCR text layer
@@ -274,6 +523,41 @@ pub trait PdfWriter {
     ) -> Result<()>;
 }
 
+/// Summary of a generated PDF
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PdfWriteStats {
+    /// Number of pages written
+    pub page_count: usize,
+    /// Pages whose image reuses an earlier identical page's XObject
+    pub deduplicated_pages: usize,
+    /// Bilevel page compression (empty with [`ImageEncoding::Standard`])
+    pub bilevel: BilevelStats,
+}
+
+/// Compression achieved on bilevel pages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BilevelStats {
+    /// Pages embedded as 1-bit images
+    pub pages: usize,
+    /// Of those, pages JBIG2-encoded (the rest use Flate)
+    pub jbig2_pages: usize,
+    /// Uncompressed 1-bit size of those pages
+    pub raw_bytes: u64,
+    /// Size of their image streams in the PDF
+    pub encoded_bytes: u64,
+}
+
+impl BilevelStats {
+    /// Uncompressed / encoded size (0.0 without bilevel pages)
+    pub fn compression_ratio(&self) -> f64 {
+        if self.encoded_bytes == 0 {
+            0.0
+        } else {
+            self.raw_bytes as f64 / self.encoded_bytes as f64
+        }
+    }
+}
+
 /// printpdf-based PDF writer implementation
 pub struct PrintPdfWriter;
 
@@ -289,6 +573,19 @@ impl PrintPdfWriter {
         output: &Path,
         options: &PdfWriterOptions,
     ) -> Result<()> {
+        Self::create_from_images_with_stats(images, output, options).map(|_| ())
+    }
+
+    /// Create PDF from images and report page/deduplication counts
+    ///
+    /// Each page file is hashed together with its color mode as it is added;
+    /// if any page repeats, identical image streams are merged after saving
+    /// so the duplicate pages reference a single XObject.
+    pub fn create_from_images_with_stats(
+        images: &[PathBuf],
+        output: &Path,
+        options: &PdfWriterOptions,
+    ) -> Result<PdfWriteStats> {
         if images.is_empty() {
             return Err(PdfWriterError::NoImages);
         }
@@ -301,8 +598,8 @@ impl PrintPdfWriter {
         }
 
         // Load first image to determine initial page size
-        let first_img =
-            image::open(&images[0]).map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        let first_img = crate::util::load_image_flattened(&images[0], options.background)
+            .map_err(PdfWriterError::GenerationError)?;
 
         let (width_px, height_px) = (first_img.width(), first_img.height());
         let dpi = options.dpi as f64;
@@ -324,9 +621,66 @@ impl PrintPdfWriter {
             printpdf::Mm(height_mm),
             "Layer 1",
         );
+        let doc = match &options.metadata {
+            Some(metadata) => Self::apply_metadata(doc, metadata),
+            None => doc,
+        };
+
+        // Content hash for the deterministic document ID
+        let mut content_hash = options.deterministic.then(|| {
+            let mut hasher = Sha256::new();
+            hasher.update(title.as_bytes());
+            hasher
+        });
+        if let Some(hasher) = content_hash.as_mut() {
+            hash_image(hasher, &first_img);
+        }
+
+        // Page file hashes: a repeat triggers image deduplication
+        let mut page_hashes = HashSet::new();
+        let mut has_duplicates = false;
+        let mut track_page = |path: &Path, color_mode: PageColorMode| -> Result<()> {
+            has_duplicates |= !page_hashes.insert(hash_page_file(path, color_mode)?);
+            Ok(())
+        };
+        track_page(&images[0], options.color_mode(0))?;
+
+        // Bilevel pages are packed to 1 bit; JBIG2 streams replace them after saving
+        let use_jbig2 =
+            options.image_encoding == ImageEncoding::Jbig2 && crate::tools::is_available(JBIG2_TOOL);
+        let mut bilevel = BilevelStats::default();
+        let mut jbig2_streams: HashMap<usize, Vec<u8>> = HashMap::new();
+        let mut page_image = |index: usize, img: &image::DynamicImage| {
+            let regions = options.color_regions(index);
+            let blanked;
+            let img = if regions.is_empty() {
+                img
+            } else {
+                blanked = blank_regions(img, regions);
+                &blanked
+            };
+            let binarized;
+            let img = match options.binarize {
+                Some(method) if options.color_mode(index) == PageColorMode::Grayscale => {
+                    binarized = image::DynamicImage::ImageLuma8(crate::binarize::adaptive(&img.to_luma8(), method));
+                    &binarized
+                }
+                _ => img,
+            };
+            let gray = (!options.image_encoding.is_standard() && is_bilevel(img)).then(|| img.to_luma8());
+            if let Some(gray) = &gray {
+                bilevel.pages += 1;
+                bilevel.raw_bytes += ((gray.width() as u64).div_ceil(8)) * gray.height() as u64;
+                if let Some(data) = use_jbig2.then(|| encode_jbig2(gray, &options.tools)).flatten() {
+                    jbig2_streams.insert(index, data);
+                }
+            }
+            page_xobject(img, options.color_mode(index), gray.as_ref())
+        };
 
         // Add first image to first page
-        Self::add_image_to_layer(&doc, page1, layer1, &first_img, width_mm, height_mm)?;
+        Self::add_image_to_layer(&doc, page1, layer1, page_image(0, &first_img), width_mm, height_mm)?;
+        Self::add_color_regions(&doc, page1, layer1, &first_img, options.color_regions(0), width_mm, height_mm);
 
         // Add OCR text layer for first page if available
         if let Some(ref ocr_layer) = options.ocr_layer {
@@ -336,16 +690,35 @@ impl PrintPdfWriter {
             }
         }
 
+        // Size of the first page in the majority orientation
+        let main_size = matches!(options.page_size_mode, PageSizeMode::MainOrientation).then(|| {
+            let sizes: Vec<(u32, u32)> =
+                images.iter().map(|path| image::image_dimensions(path).unwrap_or((0, 0))).collect();
+            let main = crate::PageOrientation::majority(&sizes);
+            sizes.into_iter().find(|&size| crate::PageOrientation::of(size) == main)
+        });
+
         // Add remaining images
         for (img_idx, img_path) in images.iter().enumerate().skip(1) {
-            let img = image::open(img_path)
-                .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+            let img = crate::util::load_image_flattened(img_path, options.background)
+                .map_err(PdfWriterError::GenerationError)?;
+            if let Some(hasher) = content_hash.as_mut() {
+                hash_image(hasher, &img);
+            }
+            track_page(img_path, options.color_mode(img_idx))?;
 
             let dpi_f32 = options.dpi as f32;
             let (w_px, h_px) = match options.page_size_mode {
                 PageSizeMode::FirstPage => (width_px, height_px),
                 PageSizeMode::Original => (img.width(), img.height()),
                 PageSizeMode::MaxSize => (width_px.max(img.width()), height_px.max(img.height())),
+                PageSizeMode::MainOrientation => {
+                    let own = (img.width(), img.height());
+                    match main_size.flatten() {
+                        Some(main) if crate::PageOrientation::of(main) == crate::PageOrientation::of(own) => main,
+                        _ => own,
+                    }
+                }
                 PageSizeMode::Fixed {
                     width_pt,
                     height_pt,
@@ -362,7 +735,8 @@ impl PrintPdfWriter {
 
             let (page, layer) = doc.add_page(printpdf::Mm(w_mm), printpdf::Mm(h_mm), "Layer 1");
 
-            Self::add_image_to_layer(&doc, page, layer, &img, w_mm, h_mm)?;
+            Self::add_image_to_layer(&doc, page, layer, page_image(img_idx, &img), w_mm, h_mm)?;
+            Self::add_color_regions(&doc, page, layer, &img, options.color_regions(img_idx), w_mm, h_mm);
 
             // Add OCR text layer if available
             if let Some(ref ocr_layer) = options.ocr_layer {
@@ -373,12 +747,350 @@ impl PrintPdfWriter {
             }
         }
 
+        // Deterministic mode: fixed timestamps and a content-derived document ID
+        let document_id = content_hash.map(|hasher| format!("{:x}", hasher.finalize())[..32].to_string());
+        let doc = match &document_id {
+            Some(id) => {
+                let timestamp = printpdf::OffsetDateTime::from_unix_timestamp(reproducible_timestamp())
+                    .unwrap_or(printpdf::OffsetDateTime::UNIX_EPOCH);
+                doc.with_creation_date(timestamp)
+                    .with_mod_date(timestamp)
+                    .with_metadata_date(timestamp)
+                    .with_document_id(id.clone())
+            }
+            None => doc,
+        };
+        // Dates carried over in the metadata replace the save time
+        let doc = match &options.metadata {
+            Some(metadata) => Self::apply_dates(doc, metadata),
+            None => doc,
+        };
+
         // Save PDF
-        let file = File::create(output)?;
-        let mut writer = BufWriter::new(file);
-        doc.save(&mut writer)
+        let bytes = doc
+            .save_to_bytes()
+            .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        let mut stats = PdfWriteStats {
+            page_count: images.len(),
+            deduplicated_pages: 0,
+            bilevel,
+        };
+        let bytes = if has_duplicates
+            || document_id.is_some()
+            || options.recorded_options.is_some()
+            || options.right_to_left
+            || !options.page_labels.is_empty()
+            || !options.outline.is_empty()
+            || bilevel.pages > 0
+            || options.pdfa.is_some()
+        {
+            Self::annotate(&bytes, document_id.as_deref(), options, has_duplicates, &jbig2_streams, &mut stats)?
+        } else {
+            bytes
+        };
+        let mut writer = BufWriter::new(File::create(output)?);
+        writer.write_all(&bytes)?;
+        writer.flush()?;
+
+        Ok(stats)
+    }
+
+    /// Write catalog metadata (Info dictionary and XMP)
+    fn apply_metadata(
+        mut doc: printpdf::PdfDocumentReference,
+        metadata: &PdfMetadata,
+    ) -> printpdf::PdfDocumentReference {
+        if let Some(author) = &metadata.author {
+            doc = doc.with_author(author.as_str());
+        }
+        if let Some(subject) = &metadata.subject {
+            doc = doc.with_subject(subject.as_str());
+        }
+        let keywords = metadata.keyword_list();
+        if !keywords.is_empty() {
+            doc = doc.with_keywords(keywords);
+        }
+        if let Some(creator) = &metadata.creator {
+            doc = doc.with_creator(creator.as_str());
+        }
+        if let Some(producer) = &metadata.producer {
+            doc = doc.with_producer(producer.as_str());
+        }
+        doc
+    }
+
+    /// Write the creation and modification dates of `metadata` (PDF date
+    /// strings; unparsable dates are ignored)
+    fn apply_dates(
+        mut doc: printpdf::PdfDocumentReference,
+        metadata: &PdfMetadata,
+    ) -> printpdf::PdfDocumentReference {
+        let parse = |date: &Option<String>| {
+            date.as_deref()
+                .and_then(pdf_date_timestamp)
+                .and_then(|timestamp| printpdf::OffsetDateTime::from_unix_timestamp(timestamp).ok())
+        };
+        if let Some(created) = parse(&metadata.creation_date) {
+            doc = doc.with_creation_date(created);
+        }
+        if let Some(modified) = parse(&metadata.modification_date) {
+            doc = doc.with_mod_date(modified);
+        }
+        doc
+    }
+
+    /// Post-process saved bytes: swap in JBIG2 streams (by 0-based page
+    /// index) and measure the bilevel images, merge identical images (if
+    /// `deduplicate`), replace the per-save random instance IDs written by
+    /// printpdf with `document_id` (if given), record the generator version
+    /// and processing options in the Info dictionary and set the binding
+    /// direction, page labels and outline in the catalog, then convert to PDF/A if requested. Merged
+    /// pages and bilevel sizes are recorded in `stats`.
+    fn annotate(
+        bytes: &[u8],
+        document_id: Option<&str>,
+        options: &PdfWriterOptions,
+        deduplicate: bool,
+        jbig2_streams: &HashMap<usize, Vec<u8>>,
+        stats: &mut PdfWriteStats,
+    ) -> Result<Vec<u8>> {
+        let mut pdf = lopdf::Document::load_mem(bytes)
+            .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+
+        if stats.bilevel.pages > 0 {
+            stats.bilevel.jbig2_pages = Self::embed_jbig2(&mut pdf, jbig2_streams);
+            stats.bilevel.encoded_bytes = Self::compress_bilevel(&mut pdf)?;
+        }
+        stats.deduplicated_pages = if deduplicate { Self::deduplicate_images(&mut pdf) } else { 0 };
+
+        if let Some(document_id) = document_id {
+            Self::stabilize_ids(&mut pdf, document_id)?;
+        }
+
+        Self::record_info(
+            &mut pdf,
+            VERSION_INFO_KEY,
+            &format!("superbook-pdf {}", env!("CARGO_PKG_VERSION")),
+        )?;
+        if let Some(recorded) = &options.recorded_options {
+            Self::record_info(&mut pdf, OPTIONS_INFO_KEY, recorded)?;
+        }
+        if options.right_to_left {
+            Self::set_right_to_left(&mut pdf)?;
+        }
+        if !options.page_labels.is_empty() {
+            Self::set_page_labels(&mut pdf, &options.page_labels)?;
+        }
+        if !options.outline.is_empty() {
+            Self::set_outline(&mut pdf, &options.outline)?;
+        }
+        if let Some(level) = options.pdfa_level() {
+            crate::pdfa::convert(&mut pdf, level)?;
+        }
+
+        let mut out = Vec::new();
+        pdf.save_to(&mut out)?;
+        Ok(out)
+    }
+
+    /// Replace the 1-bit image of each listed page (0-based index) with its
+    /// JBIG2 stream, returning the number of pages replaced
+    fn embed_jbig2(pdf: &mut lopdf::Document, streams: &HashMap<usize, Vec<u8>>) -> usize {
+        use lopdf::Object;
+
+        let pages = pdf.get_pages();
+        let mut embedded = 0;
+        for (&index, data) in streams {
+            let Some(&page_id) = pages.get(&(index as u32 + 1)) else {
+                continue;
+            };
+            for image_id in page_image_ids(pdf, page_id) {
+                let Ok(stream) = pdf.get_object_mut(image_id).and_then(Object::as_stream_mut) else {
+                    continue;
+                };
+                if stream.dict.get(b"BitsPerComponent").and_then(Object::as_i64).ok() != Some(1) {
+                    continue;
+                }
+                stream.dict.set("Filter", Object::Name(b"JBIG2Decode".to_vec()));
+                stream.dict.remove(b"DecodeParms");
+                stream.set_content(data.clone());
+                stream.allows_compression = false;
+                embedded += 1;
+            }
+        }
+        embedded
+    }
+
+    /// Point every reference to a repeated image stream at its first
+    /// occurrence and drop the copies
+    ///
+    /// Streams match only when both the dictionary (size, color space,
+    /// filter) and the encoded data are identical, so pages with different
+    /// encodings are never merged.
+    fn deduplicate_images(pdf: &mut lopdf::Document) -> usize {
+        use lopdf::Object;
+
+        let mut first_by_hash: HashMap<[u8; 32], lopdf::ObjectId> = HashMap::new();
+        let mut duplicates = HashMap::new();
+        for (&id, object) in &pdf.objects {
+            let Object::Stream(stream) = object else {
+                continue;
+            };
+            let is_image = stream
+                .dict
+                .get(b"Subtype")
+                .and_then(Object::as_name)
+                .is_ok_and(|name| name == b"Image");
+            if !is_image {
+                continue;
+            }
+            let mut hasher = Sha256::new();
+            hasher.update(format!("{:?}", stream.dict).as_bytes());
+            hasher.update(&stream.content);
+            let first = *first_by_hash.entry(hasher.finalize().into()).or_insert(id);
+            if first != id {
+                duplicates.insert(id, first);
+            }
+        }
+
+        if !duplicates.is_empty() {
+            for object in pdf.objects.values_mut() {
+                redirect_references(object, &duplicates);
+            }
+            for id in duplicates.keys() {
+                pdf.objects.remove(id);
+            }
+        }
+        duplicates.len()
+    }
+
+    /// Use the document ID for both trailer IDs and the XMP InstanceID
+    fn stabilize_ids(pdf: &mut lopdf::Document, document_id: &str) -> Result<()> {
+        use lopdf::{Object, StringFormat};
+
+        let to_err = |e: lopdf::Error| PdfWriterError::GenerationError(e.to_string());
+        let id = Object::String(document_id.as_bytes().to_vec(), StringFormat::Literal);
+        pdf.trailer.set("ID", Object::Array(vec![id.clone(), id]));
+
+        // XMP packet carries its own InstanceID
+        let metadata_id = pdf
+            .catalog()
+            .and_then(|catalog| catalog.get(b"Metadata"))
+            .and_then(Object::as_reference)
+            .ok();
+        if let Some(metadata_id) = metadata_id {
+            if let Ok(stream) = pdf.get_object_mut(metadata_id).and_then(Object::as_stream_mut) {
+                let compressed = stream.dict.has(b"Filter");
+                let xmp = String::from_utf8_lossy(&stream.get_plain_content().map_err(to_err)?).to_string();
+                let xmp = replace_between(&xmp, "<xmpMM:InstanceID>uuid:", "</xmpMM:InstanceID>", document_id);
+                stream.set_plain_content(xmp.into_bytes());
+                if compressed {
+                    stream.compress().map_err(to_err)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Flate-compress 1-bit image streams that have no filter yet (printpdf
+    /// leaves streams uncompressed in debug builds) and return their total
+    /// encoded size
+    fn compress_bilevel(pdf: &mut lopdf::Document) -> Result<u64> {
+        let mut total = 0;
+        for object in pdf.objects.values_mut() {
+            let Ok(stream) = object.as_stream_mut() else {
+                continue;
+            };
+            if stream.dict.get(b"BitsPerComponent").and_then(lopdf::Object::as_i64).ok() != Some(1) {
+                continue;
+            }
+            if !stream.dict.has(b"Filter") {
+                stream.compress().map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+            }
+            total += stream.content.len() as u64;
+        }
+        Ok(total)
+    }
+
+    /// Add `/ViewerPreferences << /Direction /R2L >>` to the catalog
+    fn set_right_to_left(pdf: &mut lopdf::Document) -> Result<()> {
+        let catalog = pdf
+            .catalog_mut()
             .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        let mut preferences = match catalog.get(b"ViewerPreferences") {
+            Ok(lopdf::Object::Dictionary(dict)) => dict.clone(),
+            _ => lopdf::Dictionary::new(),
+        };
+        preferences.set("Direction", lopdf::Object::Name(b"R2L".to_vec()));
+        catalog.set("ViewerPreferences", lopdf::Object::Dictionary(preferences));
+        Ok(())
+    }
+
+    /// Add `/PageLabels << /Nums [start << /S style /St first >> ...] >>`
+    /// to the catalog (ranges without a style get an empty label)
+    fn set_page_labels(pdf: &mut lopdf::Document, ranges: &[PageLabelRange]) -> Result<()> {
+        use lopdf::Object;
+
+        let mut nums = Vec::with_capacity(ranges.len() * 2);
+        for range in ranges {
+            let mut label = lopdf::Dictionary::new();
+            if let Some(style) = range.style {
+                label.set("S", Object::Name(style.pdf_name().to_vec()));
+                if range.first_number != 1 {
+                    label.set("St", Object::Integer(i64::from(range.first_number)));
+                }
+            }
+            nums.push(Object::Integer(range.start_page as i64));
+            nums.push(Object::Dictionary(label));
+        }
+        let mut page_labels = lopdf::Dictionary::new();
+        page_labels.set("Nums", Object::Array(nums));
 
+        let catalog = pdf
+            .catalog_mut()
+            .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        catalog.set("PageLabels", Object::Dictionary(page_labels));
+        Ok(())
+    }
+
+    /// Add `/Outlines` to the catalog, each entry going to its page with a
+    /// `/Fit` destination (entries start collapsed)
+    fn set_outline(pdf: &mut lopdf::Document, items: &[OutlineItem]) -> Result<()> {
+        use lopdf::Object;
+
+        let pages: Vec<lopdf::ObjectId> = pdf.get_pages().into_values().collect();
+        let root_id = pdf.new_object_id();
+        let mut root = lopdf::Dictionary::new();
+        root.set("Type", Object::Name(b"Outlines".to_vec()));
+        if let Some((first, last)) = add_outline_items(pdf, root_id, items, &pages) {
+            root.set("First", first);
+            root.set("Last", last);
+            root.set("Count", Object::Integer(items.len() as i64));
+        }
+        pdf.objects.insert(root_id, Object::Dictionary(root));
+
+        let catalog = pdf
+            .catalog_mut()
+            .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        catalog.set("Outlines", root_id);
+        Ok(())
+    }
+
+    /// Set a custom string entry in the document Info dictionary
+    fn record_info(pdf: &mut lopdf::Document, key: &str, value: &str) -> Result<()> {
+        let info_id = pdf
+            .trailer
+            .get(b"Info")
+            .and_then(lopdf::Object::as_reference)
+            .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        let info = pdf
+            .get_object_mut(info_id)
+            .and_then(lopdf::Object::as_dict_mut)
+            .map_err(|e| PdfWriterError::GenerationError(e.to_string()))?;
+        info.set(
+            key,
+            lopdf::Object::String(value.as_bytes().to_vec(), lopdf::StringFormat::Literal),
+        );
         Ok(())
     }
 
@@ -387,29 +1099,55 @@ impl PrintPdfWriter {
         doc: &printpdf::PdfDocumentReference,
         page: printpdf::PdfPageIndex,
         layer: printpdf::PdfLayerIndex,
-        img: &image::DynamicImage,
+        image_data: printpdf::ImageXObject,
         width_mm: f32,
         height_mm: f32,
     ) -> Result<()> {
-        use printpdf::{Image, ImageTransform, Mm, Px};
-
-        // Convert image to RGB8 format
-        let rgb_img = img.to_rgb8();
-        let (img_width, img_height) = rgb_img.dimensions();
-
-        // Create printpdf Image from raw RGB data
-        let image_data = printpdf::ImageXObject {
-            width: Px(img_width as usize),
-            height: Px(img_height as usize),
-            color_space: printpdf::ColorSpace::Rgb,
-            bits_per_component: printpdf::ColorBits::Bit8,
-            interpolate: true,
-            image_data: rgb_img.into_raw(),
-            image_filter: None,
-            clipping_bbox: None,
-            smask: None,
-        };
+        Self::place_image(doc, page, layer, image_data, (0.0, 0.0), width_mm, height_mm);
+        Ok(())
+    }
+
+    /// Overlay color crops of `img` over the page image, each at its
+    /// position on the `width_mm` x `height_mm` page
+    fn add_color_regions(
+        doc: &printpdf::PdfDocumentReference,
+        page: printpdf::PdfPageIndex,
+        layer: printpdf::PdfLayerIndex,
+        img: &image::DynamicImage,
+        regions: &[(u32, u32, u32, u32)],
+        width_mm: f32,
+        height_mm: f32,
+    ) {
+        let (img_width, img_height) = (img.width(), img.height());
+        for &(x, y, width, height) in regions {
+            let (width, height) = (width.min(img_width.saturating_sub(x)), height.min(img_height.saturating_sub(y)));
+            if width == 0 || height == 0 {
+                continue;
+            }
+            let crop = image::DynamicImage::ImageRgb8(img.crop_imm(x, y, width, height).to_rgb8());
+            let mm_x = width_mm / img_width as f32;
+            let mm_y = height_mm / img_height as f32;
+            // PDF origin is the bottom-left corner
+            let origin = (x as f32 * mm_x, (img_height - y - height) as f32 * mm_y);
+            let xobject = page_xobject(&crop, PageColorMode::Color, None);
+            Self::place_image(doc, page, layer, xobject, origin, width as f32 * mm_x, height as f32 * mm_y);
+        }
+    }
+
+    /// Draw an image stretched to `width_mm` x `height_mm` with its
+    /// bottom-left corner at `origin_mm`
+    fn place_image(
+        doc: &printpdf::PdfDocumentReference,
+        page: printpdf::PdfPageIndex,
+        layer: printpdf::PdfLayerIndex,
+        image_data: printpdf::ImageXObject,
+        origin_mm: (f32, f32),
+        width_mm: f32,
+        height_mm: f32,
+    ) {
+        use printpdf::{Image, ImageTransform, Mm};
 
+        let (img_width, img_height) = (image_data.width.0 as u32, image_data.height.0 as u32);
         let image = Image::from(image_data);
 
         // Get layer reference
@@ -431,8 +1169,8 @@ impl PrintPdfWriter {
         let scale_y = height_pt / img_height_pt;
 
         let transform = ImageTransform {
-            translate_x: Some(Mm(0.0)),
-            translate_y: Some(Mm(0.0)),
+            translate_x: Some(Mm(origin_mm.0)),
+            translate_y: Some(Mm(origin_mm.1)),
             scale_x: Some(scale_x),
             scale_y: Some(scale_y),
             rotate: None,
@@ -440,8 +1178,6 @@ impl PrintPdfWriter {
         };
 
         image.add_to_layer(layer_ref, transform);
-
-        Ok(())
     }
 
     /// Add OCR text layer to a PDF page
@@ -497,6 +1233,242 @@ impl Default for PrintPdfWriter {
     }
 }
 
+/// Image XObject for a page: RGB8 or 8-bit gray by color mode, or 1 bit per
+/// pixel when `bilevel` (the page's gray image) is given
+fn page_xobject(
+    img: &image::DynamicImage,
+    color_mode: PageColorMode,
+    bilevel: Option<&image::GrayImage>,
+) -> printpdf::ImageXObject {
+    use printpdf::{ColorBits, ColorSpace, Px};
+
+    let (color_space, bits_per_component, raw) = match (bilevel, color_mode) {
+        (Some(gray), _) => (ColorSpace::Greyscale, ColorBits::Bit1, pack_bilevel(gray)),
+        (None, PageColorMode::Color) => (ColorSpace::Rgb, ColorBits::Bit8, img.to_rgb8().into_raw()),
+        (None, PageColorMode::Grayscale) => (ColorSpace::Greyscale, ColorBits::Bit8, img.to_luma8().into_raw()),
+    };
+
+    printpdf::ImageXObject {
+        width: Px(img.width() as usize),
+        height: Px(img.height() as usize),
+        color_space,
+        bits_per_component,
+        // Smoothing a 1-bit image blurs glyph edges
+        interpolate: bilevel.is_none(),
+        image_data: raw,
+        image_filter: None,
+        clipping_bbox: None,
+        smask: None,
+    }
+}
+
+/// Grayscale copy of a page with the color regions painted white (they are
+/// drawn over it in color)
+fn blank_regions(img: &image::DynamicImage, regions: &[(u32, u32, u32, u32)]) -> image::DynamicImage {
+    let mut gray = img.to_luma8();
+    let (width, height) = gray.dimensions();
+    for &(x, y, w, h) in regions {
+        for py in y.min(height)..y.saturating_add(h).min(height) {
+            for px in x.min(width)..x.saturating_add(w).min(width) {
+                gray.put_pixel(px, py, image::Luma([255]));
+            }
+        }
+    }
+    image::DynamicImage::ImageLuma8(gray)
+}
+
+/// Whether every pixel is pure black or pure white
+pub fn is_bilevel(img: &image::DynamicImage) -> bool {
+    match img {
+        image::DynamicImage::ImageLuma8(gray) => gray.pixels().all(|p| p.0[0] == 0 || p.0[0] == 255),
+        _ => img.to_rgb8().pixels().all(|p| p.0 == [0, 0, 0] || p.0 == [255, 255, 255]),
+    }
+}
+
+/// Pack a black-and-white image to 1 bit per pixel (rows padded to a byte,
+/// most significant bit first, 1 = white as in DeviceGray)
+fn pack_bilevel(gray: &image::GrayImage) -> Vec<u8> {
+    let row_bytes = (gray.width() as usize).div_ceil(8);
+    let mut packed = vec![0u8; row_bytes * gray.height() as usize];
+    for (x, y, pixel) in gray.enumerate_pixels() {
+        if pixel.0[0] >= 128 {
+            packed[y as usize * row_bytes + x as usize / 8] |= 0x80 >> (x % 8);
+        }
+    }
+    packed
+}
+
+/// Encode a bilevel page with jbig2enc (lossless generic region, PDF
+/// fragment on stdout); None if the encoder fails
+fn encode_jbig2(gray: &image::GrayImage, tools: &crate::tools::ToolContext) -> Option<Vec<u8>> {
+    let dir = tempfile::tempdir().ok()?;
+    let input = dir.path().join("page.png");
+    gray.save(&input).ok()?;
+    let output = tools.output(tools.command(JBIG2_TOOL).arg("-p").arg(&input)).ok()?;
+    (output.status.success() && !output.stdout.is_empty()).then_some(output.stdout)
+}
+
+/// Image XObjects referenced from a page's resources
+fn page_image_ids(pdf: &lopdf::Document, page_id: lopdf::ObjectId) -> Vec<lopdf::ObjectId> {
+    use lopdf::Object;
+
+    let dictionary = |object: &Object| match object {
+        Object::Reference(id) => pdf.get_dictionary(*id).ok().cloned(),
+        Object::Dictionary(dict) => Some(dict.clone()),
+        _ => None,
+    };
+    let xobjects = pdf
+        .get_dictionary(page_id)
+        .ok()
+        .and_then(|page| page.get(b"Resources").ok())
+        .and_then(dictionary)
+        .and_then(|resources| resources.get(b"XObject").ok().and_then(dictionary));
+    let Some(xobjects) = xobjects else {
+        return Vec::new();
+    };
+    xobjects
+        .iter()
+        .filter_map(|(_, value)| value.as_reference().ok())
+        .filter(|id| {
+            pdf.get_object(*id)
+                .and_then(Object::as_stream)
+                .and_then(|stream| stream.dict.get(b"Subtype"))
+                .and_then(Object::as_name)
+                .is_ok_and(|name| name == b"Image")
+        })
+        .collect()
+}
+
+/// Write `items` as the children of outline node `parent`, returning the
+/// first and last entry IDs
+fn add_outline_items(
+    pdf: &mut lopdf::Document,
+    parent: lopdf::ObjectId,
+    items: &[OutlineItem],
+    pages: &[lopdf::ObjectId],
+) -> Option<(lopdf::ObjectId, lopdf::ObjectId)> {
+    use lopdf::Object;
+
+    let ids: Vec<lopdf::ObjectId> = items.iter().map(|_| pdf.new_object_id()).collect();
+    for (index, (item, &id)) in items.iter().zip(&ids).enumerate() {
+        let mut entry = lopdf::Dictionary::new();
+        entry.set("Title", crate::pdfa::text_string(&item.title));
+        entry.set("Parent", parent);
+        if index > 0 {
+            entry.set("Prev", ids[index - 1]);
+        }
+        if let Some(&next) = ids.get(index + 1) {
+            entry.set("Next", next);
+        }
+        if let Some(&page_id) = item.page.and_then(|page| pages.get(page)) {
+            entry.set("Dest", vec![page_id.into(), Object::Name(b"Fit".to_vec())]);
+        }
+        if let Some((first, last)) = add_outline_items(pdf, id, &item.children, pages) {
+            entry.set("First", first);
+            entry.set("Last", last);
+            // Negative: closed, with this many children shown when opened
+            entry.set("Count", Object::Integer(-(item.children.len() as i64)));
+        }
+        pdf.objects.insert(id, Object::Dictionary(entry));
+    }
+    Some((*ids.first()?, *ids.last()?))
+}
+
+/// Unix timestamp of a PDF date (`D:YYYYMMDDHHmmSS+HH'mm'`; fields after
+/// the year are optional)
+fn pdf_date_timestamp(pdf_date: &str) -> Option<i64> {
+    let date = pdf_date.trim();
+    let date = date.strip_prefix("D:").unwrap_or(date);
+    let digit_count = date.bytes().take_while(u8::is_ascii_digit).count();
+    if !(4..=14).contains(&digit_count) || digit_count % 2 != 0 {
+        return None;
+    }
+    let (digits, zone) = date.split_at(digit_count);
+    let field = |start: usize, default: u32| digits.get(start..start + 2).map_or(Some(default), |d| d.parse().ok());
+    let local = chrono::NaiveDate::from_ymd_opt(digits[..4].parse().ok()?, field(4, 1)?, field(6, 1)?)?
+        .and_hms_opt(field(8, 0)?, field(10, 0)?, field(12, 0)?)?;
+    let offset_seconds = match zone {
+        "" | "Z" => 0,
+        zone => {
+            let sign = match zone.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let mut parts = zone[1..].split('\'').filter(|part| !part.is_empty());
+            let hours: i64 = parts.next()?.parse().ok()?;
+            let minutes: i64 = parts.next().map_or(Some(0), |m| m.parse().ok())?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+    Some(local.and_utc().timestamp() - offset_seconds)
+}
+
+/// Timestamp used for deterministic output: `SOURCE_DATE_EPOCH` if set, else the Unix epoch
+fn reproducible_timestamp() -> i64 {
+    std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn hash_image(hasher: &mut Sha256, img: &image::DynamicImage) {
+    hasher.update(img.width().to_le_bytes());
+    hasher.update(img.height().to_le_bytes());
+    hasher.update(img.as_bytes());
+}
+
+/// Hash a page image file together with the color mode it is encoded in
+fn hash_page_file(path: &Path, color_mode: PageColorMode) -> Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    hasher.update([color_mode as u8]);
+    hasher.update(std::fs::read(path)?);
+    Ok(hasher.finalize().into())
+}
+
+/// Rewrite references to keys of `targets` (recursively)
+fn redirect_references(
+    object: &mut lopdf::Object,
+    targets: &HashMap<lopdf::ObjectId, lopdf::ObjectId>,
+) {
+    use lopdf::Object;
+
+    match object {
+        Object::Reference(id) => {
+            if let Some(target) = targets.get(id) {
+                *id = *target;
+            }
+        }
+        Object::Array(items) => {
+            for item in items {
+                redirect_references(item, targets);
+            }
+        }
+        Object::Dictionary(dict) => {
+            for (_, value) in dict.iter_mut() {
+                redirect_references(value, targets);
+            }
+        }
+        Object::Stream(stream) => {
+            for (_, value) in stream.dict.iter_mut() {
+                redirect_references(value, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace the text between `start` and `end` markers (first occurrence)
+fn replace_between(text: &str, start: &str, end: &str, replacement: &str) -> String {
+    let Some(from) = text.find(start).map(|i| i + start.len()) else {
+        return text.to_string();
+    };
+    let Some(to) = text[from..].find(end).map(|i| from + i) else {
+        return text.to_string();
+    };
+    format!("{}{}{}", &text[..from], replacement, &text[to..])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -572,6 +1544,79 @@ mod tests {
         assert_eq!(doc.get_pages().len(), 10);
     }
 
+    #[test]
+    fn test_grayscale_pages_embedded_as_device_gray() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("output.pdf");
+
+        let images = vec![
+            PathBuf::from("tests/fixtures/book_page_1.png"),
+            PathBuf::from("tests/fixtures/book_page_2.png"),
+        ];
+        let options = PdfWriterOptions::builder()
+            .page_color_modes(vec![PageColorMode::Color, PageColorMode::Grayscale])
+            .build();
+        assert_eq!(options.color_mode(1), PageColorMode::Grayscale);
+        assert_eq!(options.color_mode(5), PageColorMode::Color);
+
+        PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        let mut color_spaces: Vec<Vec<u8>> = doc
+            .objects
+            .values()
+            .filter_map(|obj| obj.as_stream().ok())
+            .filter(|stream| stream.dict.get(b"Subtype").and_then(lopdf::Object::as_name).ok() == Some(b"Image"))
+            .filter_map(|stream| stream.dict.get(b"ColorSpace").and_then(lopdf::Object::as_name).ok())
+            .map(|name| name.to_vec())
+            .collect();
+        color_spaces.sort();
+        assert_eq!(color_spaces, vec![b"DeviceGray".to_vec(), b"DeviceRGB".to_vec()]);
+    }
+
+    #[test]
+    fn test_color_regions_overlaid_on_grayscale_page() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("output.pdf");
+
+        let images = vec![PathBuf::from("tests/fixtures/book_page_1.png")];
+        let options = PdfWriterOptions::builder()
+            .page_color_modes(vec![PageColorMode::Grayscale])
+            .page_color_regions(vec![vec![(10, 20, 40, 30), (100_000, 0, 5, 5)]])
+            .build();
+        assert_eq!(options.color_regions(0).len(), 2);
+        assert!(options.color_regions(1).is_empty());
+
+        PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        let mut images: Vec<(Vec<u8>, i64)> = doc
+            .objects
+            .values()
+            .filter_map(|obj| obj.as_stream().ok())
+            .filter(|stream| stream.dict.get(b"Subtype").and_then(lopdf::Object::as_name).ok() == Some(b"Image"))
+            .map(|stream| {
+                let space = stream.dict.get(b"ColorSpace").and_then(lopdf::Object::as_name).unwrap().to_vec();
+                (space, stream.dict.get(b"Width").and_then(lopdf::Object::as_i64).unwrap())
+            })
+            .collect();
+        images.sort();
+        // The out-of-bounds region is skipped
+        assert_eq!(images.len(), 2);
+        assert_eq!(images[0].0, b"DeviceGray".to_vec());
+        assert_eq!(images[1], (b"DeviceRGB".to_vec(), 40));
+    }
+
+    #[test]
+    fn test_blank_regions_whitens_only_regions() {
+        let img = image::DynamicImage::ImageLuma8(image::GrayImage::from_pixel(20, 20, image::Luma([0])));
+        let blanked = blank_regions(&img, &[(5, 5, 4, 4), (18, 18, 10, 10)]).to_luma8();
+        assert_eq!(blanked.get_pixel(6, 6).0, [255]);
+        assert_eq!(blanked.get_pixel(19, 19).0, [255]);
+        assert_eq!(blanked.get_pixel(0, 0).0, [0]);
+        assert_eq!(blanked.get_pixel(9, 9).0, [0]);
+    }
+
     // TC-PDW-005: メタデータ設定
     #[test]
     fn test_metadata_setting() {
@@ -594,6 +1639,547 @@ mod tests {
         assert!(output.exists());
     }
 
+    #[test]
+    fn test_transparent_page_flattened_to_background() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("output.pdf");
+        let images = vec![PathBuf::from("tests/fixtures/transparent_page.png")];
+        PrintPdfWriter::create_from_images(&images, &output, &PdfWriterOptions::default()).unwrap();
+
+        // The embedded page shows white paper where the PNG was transparent
+        let doc = lopdf::Document::load(&output).unwrap();
+        let page = doc.get_pages()[&1];
+        let id = page_image_ids(&doc, page)[0];
+        let stream = doc.get_object(id).unwrap().as_stream().unwrap();
+        let pixels = match stream.dict.get(b"Filter").and_then(lopdf::Object::as_name) {
+            Ok(b"DCTDecode") => image::load_from_memory(&stream.content).unwrap().to_rgb8().into_raw(),
+            _ => stream.decompressed_content().unwrap_or_else(|_| stream.content.clone()),
+        };
+        assert!(pixels[..3].iter().all(|&value| value > 250), "{:?}", &pixels[..3]);
+    }
+
+    #[test]
+    fn test_metadata_written_to_info() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("output.pdf");
+        let options = PdfWriterOptions::builder()
+            .metadata(PdfMetadata {
+                title: Some("Book".to_string()),
+                author: Some("Author".to_string()),
+                subject: Some("Subject".to_string()),
+                keywords: Some("one, two".to_string()),
+                creator: Some("superbook-pdf v0".to_string()),
+                ..Default::default()
+            })
+            .build();
+
+        PrintPdfWriter::create_from_images(&[PathBuf::from("tests/fixtures/book_page_1.png")], &output, &options)
+            .unwrap();
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary(info_id).unwrap();
+        let field = |key: &[u8]| String::from_utf8_lossy(info.get(key).unwrap().as_str().unwrap()).to_string();
+        assert_eq!(field(b"Title"), "Book");
+        assert_eq!(field(b"Author"), "Author");
+        assert_eq!(field(b"Subject"), "Subject");
+        assert_eq!(field(b"Creator"), "superbook-pdf v0");
+        assert!(field(b"Keywords").contains("two"));
+    }
+
+    // TC-PDW-018: アウトラインと元の日付の書き込み
+    #[test]
+    fn test_outline_and_source_dates() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("output.pdf");
+        let images = vec![
+            PathBuf::from("tests/fixtures/book_page_1.png"),
+            PathBuf::from("tests/fixtures/book_page_2.png"),
+            PathBuf::from("tests/fixtures/book_page_3.png"),
+        ];
+        let outline = vec![
+            OutlineItem {
+                title: "第一章".to_string(),
+                page: Some(0),
+                children: vec![OutlineItem {
+                    title: "1.1".to_string(),
+                    page: Some(1),
+                    children: vec![],
+                }],
+            },
+            OutlineItem {
+                title: "Index".to_string(),
+                page: Some(2),
+                children: vec![],
+            },
+        ];
+        let options = PdfWriterOptions::builder()
+            .outline(outline.clone())
+            .metadata(PdfMetadata {
+                creation_date: Some("D:20200102030405+09'00'".to_string()),
+                modification_date: Some("D:2021".to_string()),
+                ..Default::default()
+            })
+            .build();
+
+        PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        let reader = crate::LopdfReader::new(&output).unwrap();
+        assert_eq!(reader.outline(), outline);
+        let metadata = &reader.info.metadata;
+        assert_eq!(metadata.creation_date.as_deref(), Some("D:20200101180405+00'00'"));
+        assert_eq!(metadata.modification_date.as_deref(), Some("D:20210101000000+00'00'"));
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        let outlines = doc.catalog().unwrap().get(b"Outlines").unwrap().as_reference().unwrap();
+        let root = doc.get_dictionary(outlines).unwrap();
+        assert_eq!(root.get(b"Count").unwrap().as_i64().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_pdf_date_timestamp() {
+        assert_eq!(pdf_date_timestamp("D:19700101000000Z"), Some(0));
+        assert_eq!(pdf_date_timestamp("D:19700101090000+09'00'"), Some(0));
+        assert_eq!(pdf_date_timestamp("19700101000000-01'30"), Some(5400));
+        assert_eq!(pdf_date_timestamp("D:1970"), Some(0));
+        assert_eq!(pdf_date_timestamp("D:197001"), Some(0));
+        assert_eq!(pdf_date_timestamp("D:19701"), None);
+        assert_eq!(pdf_date_timestamp("D:19701301"), None);
+        assert_eq!(pdf_date_timestamp("yesterday"), None);
+    }
+
+    // TC-PDW-011: 決定論的出力
+    #[test]
+    fn test_deterministic_output_is_byte_identical() {
+        let temp_dir = tempdir().unwrap();
+        let images = vec![
+            PathBuf::from("tests/fixtures/book_page_1.png"),
+            PathBuf::from("tests/fixtures/book_page_2.png"),
+        ];
+        let options = PdfWriterOptions::builder()
+            .deterministic(true)
+            .recorded_options("{\"dpi\":300}")
+            .build();
+
+        let first = temp_dir.path().join("first.pdf");
+        let second = temp_dir.path().join("second.pdf");
+        PrintPdfWriter::create_from_images(&images, &first, &options).unwrap();
+        PrintPdfWriter::create_from_images(&images, &second, &options).unwrap();
+
+        assert_eq!(std::fs::read(&first).unwrap(), std::fs::read(&second).unwrap());
+
+        let doc = lopdf::Document::load(&first).unwrap();
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary(info_id).unwrap();
+        let version = info.get(VERSION_INFO_KEY.as_bytes()).unwrap().as_str().unwrap();
+        assert!(std::str::from_utf8(version).unwrap().starts_with("superbook-pdf "));
+        let recorded = info.get(OPTIONS_INFO_KEY.as_bytes()).unwrap().as_str().unwrap();
+        assert_eq!(recorded, b"{\"dpi\":300}");
+    }
+
+    #[test]
+    fn test_deterministic_id_depends_on_content() {
+        let temp_dir = tempdir().unwrap();
+        let options = PdfWriterOptions::builder().deterministic(true).build();
+        let first = temp_dir.path().join("first.pdf");
+        let second = temp_dir.path().join("second.pdf");
+        PrintPdfWriter::create_from_images(&[PathBuf::from("tests/fixtures/book_page_1.png")], &first, &options)
+            .unwrap();
+        PrintPdfWriter::create_from_images(&[PathBuf::from("tests/fixtures/book_page_2.png")], &second, &options)
+            .unwrap();
+
+        let id = |path: &Path| {
+            let doc = lopdf::Document::load(path).unwrap();
+            doc.trailer.get(b"ID").unwrap().as_array().unwrap()[0].clone()
+        };
+        assert_ne!(id(&first), id(&second));
+    }
+
+    #[test]
+    fn test_replace_between() {
+        let xmp = "<a>uuid:123</a><b>uuid:456</b>";
+        assert_eq!(replace_between(xmp, "<b>uuid:", "</b>", "x"), "<a>uuid:123</a><b>uuid:x</b>");
+        assert_eq!(replace_between(xmp, "<c>", "</c>", "x"), xmp);
+    }
+
+    // TC-PDW-012: 右綴じ (Direction R2L)
+    #[test]
+    fn test_right_to_left_viewer_preference() {
+        let temp_dir = tempdir().unwrap();
+        let images = vec![PathBuf::from("tests/fixtures/book_page_1.png")];
+        let direction = |path: &Path| {
+            let doc = lopdf::Document::load(path).unwrap();
+            let catalog = doc.catalog().unwrap();
+            catalog
+                .get(b"ViewerPreferences")
+                .and_then(lopdf::Object::as_dict)
+                .and_then(|prefs| prefs.get(b"Direction"))
+                .and_then(lopdf::Object::as_name)
+                .map(|name| name.to_vec())
+                .ok()
+        };
+
+        let rtl = temp_dir.path().join("rtl.pdf");
+        let options = PdfWriterOptions::builder().right_to_left(true).build();
+        PrintPdfWriter::create_from_images(&images, &rtl, &options).unwrap();
+        assert_eq!(direction(&rtl), Some(b"R2L".to_vec()));
+
+        let ltr = temp_dir.path().join("ltr.pdf");
+        PrintPdfWriter::create_from_images(&images, &ltr, &PdfWriterOptions::default()).unwrap();
+        assert_eq!(direction(&ltr), None);
+    }
+
+    #[test]
+    fn test_binding_resolve() {
+        assert!(Binding::Auto.is_right_to_left(true));
+        assert!(!Binding::Auto.is_right_to_left(false));
+        assert!(Binding::RightToLeft.is_right_to_left(false));
+        assert!(!Binding::LeftToRight.is_right_to_left(true));
+        assert_eq!(serde_json::to_string(&Binding::RightToLeft).unwrap(), "\"right-to-left\"");
+    }
+
+    // TC-PDW-016: ページラベル (PageLabels)
+    #[test]
+    fn test_page_labels() {
+        use crate::page_number::PageLabelStyle;
+
+        let temp_dir = tempdir().unwrap();
+        let images = vec![
+            PathBuf::from("tests/fixtures/book_page_1.png"),
+            PathBuf::from("tests/fixtures/book_page_1.png"),
+        ];
+        let ranges = vec![
+            PageLabelRange {
+                start_page: 0,
+                style: Some(PageLabelStyle::LowerRoman),
+                first_number: 3,
+            },
+            PageLabelRange {
+                start_page: 1,
+                style: Some(PageLabelStyle::Decimal),
+                first_number: 1,
+            },
+        ];
+        let output = temp_dir.path().join("labels.pdf");
+        let options = PdfWriterOptions::builder().page_labels(ranges).build();
+        PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        let nums = doc
+            .catalog()
+            .unwrap()
+            .get(b"PageLabels")
+            .and_then(lopdf::Object::as_dict)
+            .and_then(|labels| labels.get(b"Nums"))
+            .and_then(lopdf::Object::as_array)
+            .unwrap()
+            .clone();
+        assert_eq!(nums.len(), 4);
+        assert_eq!(nums[0].as_i64().unwrap(), 0);
+        let roman = nums[1].as_dict().unwrap();
+        assert_eq!(roman.get(b"S").unwrap().as_name().unwrap(), b"r");
+        assert_eq!(roman.get(b"St").unwrap().as_i64().unwrap(), 3);
+        assert_eq!(nums[2].as_i64().unwrap(), 1);
+        let decimal = nums[3].as_dict().unwrap();
+        assert_eq!(decimal.get(b"S").unwrap().as_name().unwrap(), b"D");
+        assert!(decimal.get(b"St").is_err());
+
+        let plain = temp_dir.path().join("plain.pdf");
+        PrintPdfWriter::create_from_images(&images, &plain, &PdfWriterOptions::default()).unwrap();
+        let doc = lopdf::Document::load(&plain).unwrap();
+        assert!(doc.catalog().unwrap().get(b"PageLabels").is_err());
+    }
+
+    fn image_xobject_count(path: &Path) -> usize {
+        let doc = lopdf::Document::load(path).unwrap();
+        doc.objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .filter(|stream| {
+                stream
+                    .dict
+                    .get(b"Subtype")
+                    .and_then(lopdf::Object::as_name)
+                    .is_ok_and(|name| name == b"Image")
+            })
+            .count()
+    }
+
+    // TC-PDW-013: 同一ページ画像の共有
+    #[test]
+    fn test_identical_pages_share_image() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("dedup.pdf");
+        let blank = PathBuf::from("tests/fixtures/blank_white.png");
+        let page = PathBuf::from("tests/fixtures/book_page_1.png");
+        let images = vec![blank.clone(), page, blank.clone(), blank];
+
+        let stats =
+            PrintPdfWriter::create_from_images_with_stats(&images, &output, &PdfWriterOptions::default()).unwrap();
+        assert_eq!(stats.page_count, 4);
+        assert_eq!(stats.deduplicated_pages, 2);
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        assert_eq!(doc.get_pages().len(), 4);
+        assert_eq!(image_xobject_count(&output), 2);
+    }
+
+    #[test]
+    fn test_dedup_respects_color_mode() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("modes.pdf");
+        let page = PathBuf::from("tests/fixtures/book_page_1.png");
+        let images = vec![page.clone(), page.clone(), page];
+        let options = PdfWriterOptions::builder()
+            .page_color_modes(vec![PageColorMode::Color, PageColorMode::Grayscale, PageColorMode::Color])
+            .build();
+
+        let stats = PrintPdfWriter::create_from_images_with_stats(&images, &output, &options).unwrap();
+        assert_eq!(stats.deduplicated_pages, 1);
+        assert_eq!(image_xobject_count(&output), 2);
+
+        let unique = temp_dir.path().join("unique.pdf");
+        let images = vec![
+            PathBuf::from("tests/fixtures/book_page_1.png"),
+            PathBuf::from("tests/fixtures/book_page_2.png"),
+        ];
+        let stats =
+            PrintPdfWriter::create_from_images_with_stats(&images, &unique, &PdfWriterOptions::default()).unwrap();
+        assert_eq!(stats.deduplicated_pages, 0);
+        assert_eq!(image_xobject_count(&unique), 2);
+    }
+
+    /// Black text block on white, saved as an 8-bit PNG
+    fn bilevel_page(dir: &Path) -> PathBuf {
+        let page = image::GrayImage::from_fn(203, 120, |x, y| {
+            image::Luma([if (40..160).contains(&x) && (30..90).contains(&y) && x % 7 != 0 { 0 } else { 255 }])
+        });
+        let path = dir.join("bilevel.png");
+        page.save(&path).unwrap();
+        path
+    }
+
+    /// BitsPerComponent of each image XObject in page order
+    fn image_bits(path: &Path) -> Vec<i64> {
+        let doc = lopdf::Document::load(path).unwrap();
+        doc.get_pages()
+            .values()
+            .flat_map(|&page| page_image_ids(&doc, page))
+            .map(|id| {
+                let stream = doc.get_object(id).unwrap().as_stream().unwrap();
+                stream.dict.get(b"BitsPerComponent").unwrap().as_i64().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_is_bilevel_and_pack() {
+        let temp_dir = tempdir().unwrap();
+        let page = image::open(bilevel_page(temp_dir.path())).unwrap();
+        assert!(is_bilevel(&page));
+        assert!(!is_bilevel(&image::open("tests/fixtures/book_page_1.png").unwrap()));
+
+        let gray = image::GrayImage::from_raw(10, 2, vec![0, 255, 0, 0, 0, 0, 0, 0, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0])
+            .unwrap();
+        // Rows padded to 2 bytes, 1 = white
+        assert_eq!(pack_bilevel(&gray), vec![0b0100_0000, 0b1100_0000, 0b1000_0000, 0b0000_0000]);
+    }
+
+    #[test]
+    fn test_standard_encoding_keeps_bilevel_pages_8bit() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("standard.pdf");
+        let images = vec![bilevel_page(temp_dir.path())];
+
+        let stats =
+            PrintPdfWriter::create_from_images_with_stats(&images, &output, &PdfWriterOptions::default()).unwrap();
+        assert_eq!(stats.bilevel, BilevelStats::default());
+        assert_eq!(image_bits(&output), vec![8]);
+    }
+
+    #[test]
+    fn test_flate_encoding_packs_bilevel_pages() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("flate.pdf");
+        let images = vec![bilevel_page(temp_dir.path()), PathBuf::from("tests/fixtures/book_page_1.png")];
+        let options = PdfWriterOptions::builder().image_encoding(ImageEncoding::Flate).build();
+
+        let stats = PrintPdfWriter::create_from_images_with_stats(&images, &output, &options).unwrap();
+        assert_eq!(stats.bilevel.pages, 1);
+        assert_eq!(stats.bilevel.jbig2_pages, 0);
+        assert_eq!(stats.bilevel.raw_bytes, 26 * 120);
+        assert!(stats.bilevel.encoded_bytes > 0);
+        assert!(stats.bilevel.compression_ratio() > 1.0);
+        // Only the black-and-white page is packed
+        assert_eq!(image_bits(&output), vec![1, 8]);
+    }
+
+    #[test]
+    fn test_binarize_grayscale_pages() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("binarized.pdf");
+        let (page, _) = crate::binarize::tests::gradient_lit_page();
+        let path = temp_dir.path().join("gradient.png");
+        page.save(&path).unwrap();
+        let images = vec![path.clone(), path];
+        let options = PdfWriterOptions::builder()
+            .image_encoding(ImageEncoding::Flate)
+            .page_color_modes(vec![PageColorMode::Grayscale, PageColorMode::Color])
+            .binarize(AdaptiveMethod::default())
+            .build();
+
+        let stats = PrintPdfWriter::create_from_images_with_stats(&images, &output, &options).unwrap();
+        // Only the grayscale page is binarized (and then packed)
+        assert_eq!(stats.bilevel.pages, 1);
+        assert_eq!(image_bits(&output), vec![1, 8]);
+    }
+
+    #[test]
+    fn test_jbig2_without_encoder_falls_back_to_flate() {
+        if crate::tools::is_available(JBIG2_TOOL) {
+            return;
+        }
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("fallback.pdf");
+        let images = vec![bilevel_page(temp_dir.path())];
+        let options = PdfWriterOptions::builder().image_encoding(ImageEncoding::Jbig2).build();
+
+        let stats = PrintPdfWriter::create_from_images_with_stats(&images, &output, &options).unwrap();
+        assert_eq!(stats.bilevel.pages, 1);
+        assert_eq!(stats.bilevel.jbig2_pages, 0);
+        assert_eq!(image_bits(&output), vec![1]);
+    }
+
+    #[test]
+    fn test_embed_jbig2_replaces_page_image() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("flate.pdf");
+        let blank = temp_dir.path().join("blank.png");
+        image::GrayImage::from_pixel(64, 64, image::Luma([255])).save(&blank).unwrap();
+        let images = vec![blank, bilevel_page(temp_dir.path())];
+        let options = PdfWriterOptions::builder().image_encoding(ImageEncoding::Flate).build();
+        PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        let mut doc = lopdf::Document::load(&output).unwrap();
+        let streams = HashMap::from([(1, vec![0x97, 0x4a, 0x42])]);
+        assert_eq!(PrintPdfWriter::embed_jbig2(&mut doc, &streams), 1);
+
+        let pages = doc.get_pages();
+        let image = |page: u32| {
+            let id = page_image_ids(&doc, pages[&page])[0];
+            doc.get_object(id).unwrap().as_stream().unwrap().clone()
+        };
+        let replaced = image(2);
+        assert_eq!(replaced.dict.get(b"Filter").unwrap().as_name().unwrap(), b"JBIG2Decode");
+        assert_eq!(replaced.content, vec![0x97, 0x4a, 0x42]);
+        assert!(!replaced.allows_compression);
+        assert_eq!(image(1).dict.get(b"Filter").unwrap().as_name().unwrap(), b"FlateDecode");
+    }
+
+    #[test]
+    #[ignore = "requires external tool"]
+    fn test_jbig2_encoding() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("jbig2.pdf");
+        let images = vec![bilevel_page(temp_dir.path())];
+        let options = PdfWriterOptions::builder().image_encoding(ImageEncoding::Jbig2).build();
+
+        let stats = PrintPdfWriter::create_from_images_with_stats(&images, &output, &options).unwrap();
+        assert_eq!(stats.bilevel.jbig2_pages, 1);
+        assert!(stats.bilevel.encoded_bytes < stats.bilevel.raw_bytes);
+        let doc = lopdf::Document::load(&output).unwrap();
+        assert!(doc.objects.values().filter_map(|o| o.as_stream().ok()).any(|stream| {
+            stream.dict.get(b"Filter").and_then(lopdf::Object::as_name).ok() == Some(b"JBIG2Decode")
+        }));
+    }
+
+    fn ocr_text_layer() -> OcrLayer {
+        OcrLayer {
+            pages: vec![OcrPageText {
+                page_index: 0,
+                blocks: vec![TextBlock {
+                    x: 100.0,
+                    y: 100.0,
+                    width: 200.0,
+                    height: 20.0,
+                    text: "Archive".to_string(),
+                    font_size: 12.0,
+                    vertical: false,
+                }],
+            }],
+        }
+    }
+
+    // TC-PDW-015: PDF/A 出力
+    #[test]
+    fn test_pdfa_output() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("pdfa.pdf");
+        let options = PdfWriterOptions::builder()
+            .metadata(PdfMetadata {
+                title: Some("本のタイトル".to_string()),
+                author: Some("Author".to_string()),
+                ..Default::default()
+            })
+            .pdfa(PdfaLevel::A1b)
+            .build();
+        assert!(options.pdfa_adjustments().is_empty());
+        PrintPdfWriter::create_from_images(&[PathBuf::from("tests/fixtures/book_page_1.png")], &output, &options)
+            .unwrap();
+
+        let bytes = std::fs::read(&output).unwrap();
+        assert!(bytes.starts_with(b"%PDF-1.4\n%"));
+        assert!(bytes[10..14].iter().all(|&b| b >= 128));
+
+        let doc = lopdf::Document::load(&output).unwrap();
+        let catalog = doc.catalog().unwrap();
+        assert!(!catalog.has(b"OCProperties"));
+
+        let metadata_id = catalog.get(b"Metadata").unwrap().as_reference().unwrap();
+        let metadata = doc.get_object(metadata_id).unwrap().as_stream().unwrap();
+        assert!(!metadata.dict.has(b"Filter"));
+        let xmp = String::from_utf8(metadata.content.clone()).unwrap();
+        assert!(xmp.contains("<pdfaid:part>1</pdfaid:part>"));
+        assert!(xmp.contains("本のタイトル"));
+
+        let intents = catalog.get(b"OutputIntents").unwrap().as_array().unwrap();
+        assert_eq!(intents.len(), 1);
+        let intent = intents[0].as_dict().unwrap();
+        assert_eq!(intent.get(b"S").unwrap().as_name().unwrap(), b"GTS_PDFA1");
+        let profile_id = intent.get(b"DestOutputProfile").unwrap().as_reference().unwrap();
+        let profile = doc.get_object(profile_id).unwrap().as_stream().unwrap();
+        assert_eq!(profile.dict.get(b"N").unwrap().as_i64().unwrap(), 3);
+
+        // Info agrees with XMP: UTF-16 title, no PDF/X or empty entries
+        let info_id = doc.trailer.get(b"Info").unwrap().as_reference().unwrap();
+        let info = doc.get_dictionary(info_id).unwrap();
+        assert_eq!(&info.get(b"Title").unwrap().as_str().unwrap()[..2], &[0xFE, 0xFF]);
+        assert!(!info.has(b"GTS_PDFXVersion"));
+        assert!(!info.has(b"Subject"));
+
+        assert!(doc.objects.values().filter_map(|o| o.as_stream().ok()).all(|s| !s.dict.has(b"Interpolate")));
+    }
+
+    #[test]
+    fn test_pdfa_1b_with_ocr_text_becomes_2b() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("pdfa_ocr.pdf");
+        let options = PdfWriterOptions::builder().ocr_layer(ocr_text_layer()).pdfa(PdfaLevel::A1b).build();
+        assert_eq!(options.pdfa_level(), Some(PdfaLevel::A2b));
+        assert_eq!(options.pdfa_adjustments().len(), 1);
+        PrintPdfWriter::create_from_images(&[PathBuf::from("tests/fixtures/book_page_1.png")], &output, &options)
+            .unwrap();
+
+        assert!(std::fs::read(&output).unwrap().starts_with(b"%PDF-1.7\n"));
+        let doc = lopdf::Document::load(&output).unwrap();
+        let properties = doc.catalog().unwrap().get(b"OCProperties").unwrap().as_dict().unwrap();
+        assert!(properties.get(b"D").unwrap().as_dict().unwrap().has(b"Name"));
+
+        let without_text = PdfWriterOptions::builder().pdfa(PdfaLevel::A1b).build();
+        assert_eq!(without_text.pdfa_level(), Some(PdfaLevel::A1b));
+        assert_eq!(PdfWriterOptions::default().pdfa_level(), None);
+    }
+
     // TC-PDW-006: JPEG品質設定
     #[test]
     fn test_jpeg_quality() {
@@ -753,6 +2339,38 @@ mod tests {
         assert_eq!(doc.get_pages().len(), 2);
     }
 
+    #[test]
+    fn test_main_orientation_page_size() {
+        let temp_dir = tempdir().unwrap();
+        let output = temp_dir.path().join("output.pdf");
+        let sizes = [(200, 300), (330, 220), (210, 300), (200, 300)];
+        let images: Vec<PathBuf> = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &(width, height))| {
+                let path = temp_dir.path().join(format!("page_{}.png", i));
+                image::RgbImage::from_pixel(width, height, image::Rgb([255, 255, 255])).save(&path).unwrap();
+                path
+            })
+            .collect();
+
+        let options = PdfWriterOptions::builder()
+            .dpi(72)
+            .page_size_mode(PageSizeMode::MainOrientation)
+            .build();
+        PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        // Portrait pages share the first portrait page's size; the fold-out keeps its own
+        let pages: Vec<(f64, f64)> = crate::LopdfReader::new(&output)
+            .unwrap()
+            .info
+            .pages
+            .iter()
+            .map(|page| (page.width_pt.round(), page.height_pt.round()))
+            .collect();
+        assert_eq!(pages, vec![(200.0, 300.0), (330.0, 220.0), (200.0, 300.0), (200.0, 300.0)]);
+    }
+
     // Additional structure tests
 
     #[test]
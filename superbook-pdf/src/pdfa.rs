@@ -0,0 +1,606 @@
+//! PDF/A conformance for archival output
+//!
+//! [`PrintPdfWriter`](crate::PrintPdfWriter) output is converted after
+//! saving rather than through printpdf's own conformance setting, which
+//! writes a PDF/X output intent (CMYK) and no PDF/A identification:
+//!
+//! - XMP metadata with the `pdfaid` schema, mirroring the Info dictionary
+//!   (Info strings are re-encoded as UTF-16 so both agree on non-ASCII text)
+//! - An sRGB output intent with an embedded ICC profile
+//! - A binary comment after the `%PDF-` header
+//! - Removal of disallowed features: optional content (PDF/A-1), image
+//!   interpolation, PDF/X-only Info entries
+//!
+//! Conformance can be checked afterwards with veraPDF, or with Ghostscript
+//! as a weaker render check when veraPDF is not installed.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::{PdfWriterOptions, PdfaLevel};
+//!
+//! let options = PdfWriterOptions::builder().pdfa(PdfaLevel::A2b).build();
+//! ```
+
+use crate::pdf_reader::decode_text_string;
+use crate::pdf_writer::{PdfWriterError, Result};
+use lopdf::{Dictionary, Object, ObjectId, Stream, StringFormat};
+use std::path::Path;
+
+/// veraPDF command (PDF/A validator)
+pub const VERAPDF_TOOL: &str = "verapdf";
+
+/// Ghostscript command (render check when veraPDF is unavailable)
+pub const GHOSTSCRIPT_TOOL: &str = "gs";
+
+/// Output condition identifier of the embedded ICC profile
+const OUTPUT_CONDITION: &str = "sRGB IEC61966-2.1";
+
+/// Maximum number of validator output lines kept in a failure report
+const MAX_REPORT_LINES: usize = 20;
+
+/// PDF/A conformance level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PdfaLevel {
+    /// PDF/A-1b (ISO 19005-1, PDF 1.4): every font must be embedded
+    #[serde(rename = "1b")]
+    A1b,
+    /// PDF/A-2b (ISO 19005-2, PDF 1.7): fonts used only for invisible
+    /// text may stay unembedded
+    #[serde(rename = "2b")]
+    A2b,
+}
+
+impl PdfaLevel {
+    /// Display name ("PDF/A-1b")
+    pub fn name(self) -> &'static str {
+        match self {
+            PdfaLevel::A1b => "PDF/A-1b",
+            PdfaLevel::A2b => "PDF/A-2b",
+        }
+    }
+
+    /// Short name, as used in the config file and veraPDF's `--flavour`
+    pub fn flavour(self) -> &'static str {
+        match self {
+            PdfaLevel::A1b => "1b",
+            PdfaLevel::A2b => "2b",
+        }
+    }
+
+    /// ISO 19005 part number (`pdfaid:part`)
+    pub fn part(self) -> u8 {
+        match self {
+            PdfaLevel::A1b => 1,
+            PdfaLevel::A2b => 2,
+        }
+    }
+
+    /// PDF version written in the header
+    pub fn pdf_version(self) -> &'static str {
+        match self {
+            PdfaLevel::A1b => "1.4",
+            PdfaLevel::A2b => "1.7",
+        }
+    }
+
+    /// Whether an unembedded font may be used for invisible (OCR) text
+    pub fn allows_unembedded_invisible_text(self) -> bool {
+        self == PdfaLevel::A2b
+    }
+
+    /// Whether optional content (layers) may be used
+    pub fn allows_optional_content(self) -> bool {
+        self == PdfaLevel::A2b
+    }
+}
+
+impl std::fmt::Display for PdfaLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Outcome of checking a written file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfaValidation {
+    /// veraPDF reported the file compliant
+    Compliant,
+    /// veraPDF reported violations (leading lines of its report)
+    NonCompliant(String),
+    /// Only Ghostscript was available and rendered the file without errors;
+    /// conformance itself was not checked
+    Rendered,
+    /// The validator could not run, or Ghostscript reported errors
+    Error(String),
+}
+
+impl PdfaValidation {
+    /// Whether no problem was found
+    pub fn is_ok(&self) -> bool {
+        matches!(self, PdfaValidation::Compliant | PdfaValidation::Rendered)
+    }
+}
+
+/// Check `path` against `level` with veraPDF, falling back to a Ghostscript
+/// render check (both run in `tools`); `None` when neither tool is installed
+pub fn validate(path: &Path, level: PdfaLevel, tools: &crate::tools::ToolContext) -> Option<PdfaValidation> {
+    if crate::tools::is_available(VERAPDF_TOOL) {
+        let output = tools.output(
+            tools
+                .command(VERAPDF_TOOL)
+                .args(["--flavour", level.flavour(), "--format", "text"])
+                .arg(path),
+        );
+        return Some(match output {
+            Ok(output) => {
+                let report = String::from_utf8_lossy(&output.stdout);
+                if report.lines().any(|line| line.starts_with("PASS")) {
+                    PdfaValidation::Compliant
+                } else if report.lines().any(|line| line.starts_with("FAIL")) {
+                    PdfaValidation::NonCompliant(leading_lines(&report))
+                } else {
+                    PdfaValidation::Error(leading_lines(&String::from_utf8_lossy(&output.stderr)))
+                }
+            }
+            Err(e) => PdfaValidation::Error(e.to_string()),
+        });
+    }
+
+    if crate::tools::is_available(GHOSTSCRIPT_TOOL) {
+        let output = tools.output(
+            tools
+                .command(GHOSTSCRIPT_TOOL)
+                .args(["-q", "-dNOPAUSE", "-dBATCH", "-dPDFSTOPONERROR", "-sDEVICE=nullpage"])
+                .arg(path),
+        );
+        return Some(match output {
+            Ok(output) if output.status.success() => PdfaValidation::Rendered,
+            Ok(output) => PdfaValidation::Error(leading_lines(&format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            ))),
+            Err(e) => PdfaValidation::Error(e.to_string()),
+        });
+    }
+
+    None
+}
+
+/// Convert a saved printpdf document to `level` in place
+pub(crate) fn convert(pdf: &mut lopdf::Document, level: PdfaLevel) -> Result<()> {
+    let to_err = |e: lopdf::Error| PdfWriterError::GenerationError(e.to_string());
+
+    // PDF/A requires a comment of four or more bytes >= 128 right after the
+    // header; lopdf writes the version verbatim, so the comment rides along
+    pdf.version = format!("{}\n%\u{e2}\u{e3}\u{cf}\u{d3}", level.pdf_version());
+
+    let info = normalize_info(pdf)?;
+    let document_ids = trailer_ids(pdf);
+    let xmp = xmp_packet(level, &info, &document_ids);
+
+    let mut metadata = Stream::new(
+        Dictionary::from_iter(vec![
+            ("Type", Object::Name(b"Metadata".to_vec())),
+            ("Subtype", Object::Name(b"XML".to_vec())),
+        ]),
+        xmp.into_bytes(),
+    );
+    // PDF/A-1 forbids filters on the metadata stream
+    metadata.allows_compression = false;
+    let metadata_id = pdf.add_object(Object::Stream(metadata));
+
+    let mut profile = Stream::new(
+        Dictionary::from_iter(vec![("N", Object::Integer(3))]),
+        srgb_icc_profile(),
+    );
+    profile.compress().map_err(to_err)?;
+    let profile_id = pdf.add_object(Object::Stream(profile));
+    let output_intent = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"OutputIntent".to_vec())),
+        ("S", Object::Name(b"GTS_PDFA1".to_vec())),
+        ("OutputConditionIdentifier", text_string(OUTPUT_CONDITION)),
+        ("Info", text_string(OUTPUT_CONDITION)),
+        ("RegistryName", text_string("http://www.color.org")),
+        ("DestOutputProfile", Object::Reference(profile_id)),
+    ]);
+
+    let catalog = pdf.catalog_mut().map_err(to_err)?;
+    let replaced = [b"Metadata".as_slice(), b"OutputIntents"]
+        .iter()
+        .filter_map(|key| catalog.get(key).ok().cloned())
+        .collect::<Vec<_>>();
+    catalog.set("Metadata", Object::Reference(metadata_id));
+    catalog.set("OutputIntents", Object::Array(vec![Object::Dictionary(output_intent)]));
+    if level.allows_optional_content() {
+        // PDF/A-2 requires a name on every optional content configuration
+        if let Ok(Object::Dictionary(properties)) = catalog.get_mut(b"OCProperties") {
+            if let Ok(Object::Dictionary(config)) = properties.get_mut(b"D") {
+                config.set("Name", text_string("Default"));
+            }
+        }
+    } else {
+        catalog.remove(b"OCProperties");
+    }
+
+    // Drop printpdf's XMP packet and CMYK profile
+    for object in replaced {
+        for id in referenced_ids(&object) {
+            pdf.objects.remove(&id);
+        }
+    }
+
+    // Interpolation is disallowed on images
+    for object in pdf.objects.values_mut() {
+        if let Ok(stream) = object.as_stream_mut() {
+            if stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image".as_slice()) {
+                stream.dict.remove(b"Interpolate");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Document Info values mirrored into XMP
+#[derive(Debug, Default)]
+struct InfoValues {
+    title: Option<String>,
+    author: Option<String>,
+    subject: Option<String>,
+    keywords: Option<String>,
+    creator: Option<String>,
+    producer: Option<String>,
+    creation_date: Option<String>,
+    mod_date: Option<String>,
+}
+
+/// Clean up the Info dictionary for PDF/A and return its XMP-mirrored values
+///
+/// PDF/X entries and empty strings are removed, text strings are re-encoded
+/// as UTF-16BE when not ASCII (printpdf writes raw UTF-8), and dates that
+/// cannot be expressed in XMP are dropped.
+fn normalize_info(pdf: &mut lopdf::Document) -> Result<InfoValues> {
+    let to_err = |e: lopdf::Error| PdfWriterError::GenerationError(e.to_string());
+    let info_id = pdf.trailer.get(b"Info").and_then(Object::as_reference).map_err(to_err)?;
+    let info = pdf.get_object_mut(info_id).and_then(Object::as_dict_mut).map_err(to_err)?;
+
+    for key in [b"GTS_PDFXVersion".as_slice(), b"Trapped", b"Identifier"] {
+        info.remove(key);
+    }
+
+    let mut take_text = |key: &[u8]| -> Option<String> {
+        let bytes = info.get(key).and_then(Object::as_str).ok()?.to_vec();
+        let text = decode_text_string(&bytes);
+        if text.is_empty() {
+            info.remove(key);
+            return None;
+        }
+        info.set(key.to_vec(), text_string(&text));
+        Some(text)
+    };
+
+    let mut values = InfoValues {
+        title: take_text(b"Title"),
+        author: take_text(b"Author"),
+        subject: take_text(b"Subject"),
+        keywords: take_text(b"Keywords"),
+        creator: take_text(b"Creator"),
+        producer: take_text(b"Producer"),
+        ..Default::default()
+    };
+
+    let mut take_date = |key: &[u8]| -> Option<String> {
+        let bytes = info.get(key).and_then(Object::as_str).ok()?.to_vec();
+        let date = xmp_date(&String::from_utf8_lossy(&bytes));
+        if date.is_none() {
+            info.remove(key);
+        }
+        date
+    };
+    values.creation_date = take_date(b"CreationDate");
+    values.mod_date = take_date(b"ModDate");
+
+    Ok(values)
+}
+
+/// First and second trailer ID (document and instance)
+fn trailer_ids(pdf: &lopdf::Document) -> (String, String) {
+    let ids: Vec<String> = pdf
+        .trailer
+        .get(b"ID")
+        .and_then(Object::as_array)
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| id.as_str().ok())
+                .map(|id| String::from_utf8_lossy(id).to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let document = ids.first().cloned().unwrap_or_default();
+    let instance = ids.get(1).cloned().unwrap_or_else(|| document.clone());
+    (document, instance)
+}
+
+/// XMP packet identifying the PDF/A level and mirroring the Info values
+fn xmp_packet(level: PdfaLevel, info: &InfoValues, (document_id, instance_id): &(String, String)) -> String {
+    let mut properties = Vec::new();
+    let mut property = |name: &str, value: String| properties.push(format!("   <{name}>{value}</{name}>"));
+
+    property("pdfaid:part", level.part().to_string());
+    property("pdfaid:conformance", "B".to_string());
+    property("dc:format", "application/pdf".to_string());
+    let lang_alt = |text: &str| format!("<rdf:Alt><rdf:li xml:lang=\"x-default\">{}</rdf:li></rdf:Alt>", xml_escape(text));
+    if let Some(title) = &info.title {
+        property("dc:title", lang_alt(title));
+    }
+    if let Some(author) = &info.author {
+        property("dc:creator", format!("<rdf:Seq><rdf:li>{}</rdf:li></rdf:Seq>", xml_escape(author)));
+    }
+    if let Some(subject) = &info.subject {
+        property("dc:description", lang_alt(subject));
+    }
+    if let Some(keywords) = &info.keywords {
+        property("pdf:Keywords", xml_escape(keywords));
+    }
+    if let Some(producer) = &info.producer {
+        property("pdf:Producer", xml_escape(producer));
+    }
+    if let Some(creator) = &info.creator {
+        property("xmp:CreatorTool", xml_escape(creator));
+    }
+    if let Some(date) = &info.creation_date {
+        property("xmp:CreateDate", date.clone());
+    }
+    if let Some(date) = &info.mod_date {
+        property("xmp:ModifyDate", date.clone());
+    }
+    if !document_id.is_empty() {
+        property("xmpMM:DocumentID", format!("uuid:{}", xml_escape(document_id)));
+        property("xmpMM:InstanceID", format!("uuid:{}", xml_escape(instance_id)));
+    }
+
+    format!(
+        concat!(
+            "<?xpacket begin=\"\u{feff}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n",
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n",
+            " <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n",
+            "  <rdf:Description rdf:about=\"\"\n",
+            "    xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\"\n",
+            "    xmlns:dc=\"http://purl.org/dc/elements/1.1/\"\n",
+            "    xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\"\n",
+            "    xmlns:xmp=\"http://ns.adobe.com/xap/1.0/\"\n",
+            "    xmlns:xmpMM=\"http://ns.adobe.com/xap/1.0/mm/\">\n",
+            "{}\n",
+            "  </rdf:Description>\n",
+            " </rdf:RDF>\n",
+            "</x:xmpmeta>\n",
+            "<?xpacket end=\"w\"?>"
+        ),
+        properties.join("\n")
+    )
+}
+
+/// Convert a PDF date (`D:YYYYMMDDHHmmSS+HH'mm'`) to XMP (ISO 8601)
+fn xmp_date(pdf_date: &str) -> Option<String> {
+    let date = pdf_date.strip_prefix("D:").unwrap_or(pdf_date);
+    let digits = date.get(..14).filter(|d| d.bytes().all(|b| b.is_ascii_digit()))?;
+    let offset = match &date[14..] {
+        "" | "Z" => "Z".to_string(),
+        zone => {
+            let sign = zone.chars().next().filter(|c| *c == '+' || *c == '-')?;
+            let parts: Vec<&str> = zone[1..].split('\'').filter(|p| !p.is_empty()).collect();
+            match parts.as_slice() {
+                [hours] if hours.len() == 2 => format!("{sign}{hours}:00"),
+                [hours, minutes] if hours.len() == 2 && minutes.len() == 2 => format!("{sign}{hours}:{minutes}"),
+                _ => return None,
+            }
+        }
+    };
+    Some(format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &digits[..4],
+        &digits[4..6],
+        &digits[6..8],
+        &digits[8..10],
+        &digits[10..12],
+        &digits[12..14],
+        offset
+    ))
+}
+
+/// PDF text string: literal when ASCII, UTF-16BE with BOM otherwise
+pub(crate) fn text_string(text: &str) -> Object {
+    if text.is_ascii() {
+        return Object::String(text.as_bytes().to_vec(), StringFormat::Literal);
+    }
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn leading_lines(text: &str) -> String {
+    text.lines().filter(|l| !l.trim().is_empty()).take(MAX_REPORT_LINES).collect::<Vec<_>>().join("\n")
+}
+
+/// Object IDs referenced directly by `object` (or inside its arrays/dictionaries)
+fn referenced_ids(object: &Object) -> Vec<ObjectId> {
+    match object {
+        Object::Reference(id) => vec![*id],
+        Object::Array(items) => items.iter().flat_map(referenced_ids).collect(),
+        Object::Dictionary(dict) => dict.iter().flat_map(|(_, value)| referenced_ids(value)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Minimal ICC v2 display profile for sRGB (D50-adapted primaries and the
+/// sRGB tone curve), used as the output intent
+fn srgb_icc_profile() -> Vec<u8> {
+    fn s15_fixed16(value: f64) -> [u8; 4] {
+        ((value * 65536.0).round() as i32).to_be_bytes()
+    }
+    fn xyz(x: f64, y: f64, z: f64) -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for v in [x, y, z] {
+            tag.extend(s15_fixed16(v));
+        }
+        tag
+    }
+    fn ascii(signature: &[u8; 4], text: &str) -> Vec<u8> {
+        let mut tag = signature.to_vec();
+        tag.extend([0; 4]);
+        if signature == b"desc" {
+            // textDescriptionType: ASCII, empty Unicode and ScriptCode parts
+            tag.extend((text.len() as u32 + 1).to_be_bytes());
+            tag.extend(text.as_bytes());
+            tag.push(0);
+            tag.extend([0; 8]);
+            tag.extend([0; 3]);
+            tag.extend([0; 67]);
+        } else {
+            tag.extend(text.as_bytes());
+            tag.push(0);
+        }
+        tag
+    }
+
+    const CURVE_POINTS: usize = 1024;
+    let mut curve = b"curv\0\0\0\0".to_vec();
+    curve.extend((CURVE_POINTS as u32).to_be_bytes());
+    for i in 0..CURVE_POINTS {
+        let v = i as f64 / (CURVE_POINTS - 1) as f64;
+        let linear = if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) };
+        curve.extend(((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+
+    // (signature, data index); the three TRC tags share one curve
+    let data = [
+        ascii(b"desc", OUTPUT_CONDITION),
+        ascii(b"text", "No copyright, use freely"),
+        xyz(0.9642, 1.0, 0.8249),
+        xyz(0.4361, 0.2225, 0.0139),
+        xyz(0.3851, 0.7169, 0.0971),
+        xyz(0.1431, 0.0606, 0.7141),
+        curve,
+    ];
+    let tags: [(&[u8; 4], usize); 9] = [
+        (b"desc", 0),
+        (b"cprt", 1),
+        (b"wtpt", 2),
+        (b"rXYZ", 3),
+        (b"gXYZ", 4),
+        (b"bXYZ", 5),
+        (b"rTRC", 6),
+        (b"gTRC", 6),
+        (b"bTRC", 6),
+    ];
+
+    let mut offsets = Vec::with_capacity(data.len());
+    let mut offset = 128 + 4 + 12 * tags.len();
+    for block in &data {
+        offsets.push(offset);
+        offset += block.len().div_ceil(4) * 4;
+    }
+    let size = offset;
+
+    let mut profile = Vec::with_capacity(size);
+    profile.extend((size as u32).to_be_bytes());
+    profile.extend([0; 4]); // preferred CMM
+    profile.extend([2, 0x10, 0, 0]); // version 2.1
+    profile.extend(b"mntrRGB XYZ ");
+    profile.extend([0; 12]); // creation date
+    profile.extend(b"acsp");
+    profile.extend([0; 24]); // platform, flags, device manufacturer/model/attributes
+    profile.extend([0; 4]); // rendering intent: perceptual
+    profile.extend(s15_fixed16(0.9642));
+    profile.extend(s15_fixed16(1.0));
+    profile.extend(s15_fixed16(0.8249));
+    profile.resize(128, 0);
+
+    profile.extend((tags.len() as u32).to_be_bytes());
+    for (signature, index) in tags {
+        profile.extend(signature);
+        profile.extend((offsets[index] as u32).to_be_bytes());
+        profile.extend((data[index].len() as u32).to_be_bytes());
+    }
+    for block in &data {
+        profile.extend(block);
+        profile.resize(profile.len().div_ceil(4) * 4, 0);
+    }
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_names() {
+        assert_eq!(PdfaLevel::A1b.name(), "PDF/A-1b");
+        assert_eq!(PdfaLevel::A2b.flavour(), "2b");
+        assert_eq!(PdfaLevel::A2b.part(), 2);
+        assert_eq!(serde_json::to_string(&PdfaLevel::A1b).unwrap(), "\"1b\"");
+        assert_eq!(serde_json::from_str::<PdfaLevel>("\"2b\"").unwrap(), PdfaLevel::A2b);
+        assert!(!PdfaLevel::A1b.allows_unembedded_invisible_text());
+    }
+
+    #[test]
+    fn test_xmp_date() {
+        assert_eq!(xmp_date("D:20240102030405+09'00'").as_deref(), Some("2024-01-02T03:04:05+09:00"));
+        assert_eq!(xmp_date("D:20240102030405-05'30'").as_deref(), Some("2024-01-02T03:04:05-05:30"));
+        assert_eq!(xmp_date("D:20240102030405Z").as_deref(), Some("2024-01-02T03:04:05Z"));
+        assert_eq!(xmp_date("D:2024"), None);
+        assert_eq!(xmp_date("D:20240102030405*"), None);
+    }
+
+    #[test]
+    fn test_text_string_round_trip() {
+        assert_eq!(text_string("Book"), Object::String(b"Book".to_vec(), StringFormat::Literal));
+        let Object::String(bytes, StringFormat::Hexadecimal) = text_string("本のタイトル") else {
+            panic!("expected UTF-16 string");
+        };
+        assert_eq!(&bytes[..2], &[0xFE, 0xFF]);
+        assert_eq!(decode_text_string(&bytes), "本のタイトル");
+        assert_eq!(decode_text_string("本".as_bytes()), "本");
+    }
+
+    #[test]
+    fn test_xmp_packet_escapes_and_identifies() {
+        let info = InfoValues {
+            title: Some("A & B <1>".into()),
+            creation_date: Some("2024-01-02T03:04:05Z".into()),
+            ..Default::default()
+        };
+        let xmp = xmp_packet(PdfaLevel::A1b, &info, &("doc".into(), "inst".into()));
+        assert!(xmp.contains("<pdfaid:part>1</pdfaid:part>"));
+        assert!(xmp.contains("<pdfaid:conformance>B</pdfaid:conformance>"));
+        assert!(xmp.contains("A &amp; B &lt;1&gt;"));
+        assert!(xmp.contains("<xmp:CreateDate>2024-01-02T03:04:05Z</xmp:CreateDate>"));
+        assert!(xmp.contains("<xmpMM:InstanceID>uuid:inst</xmpMM:InstanceID>"));
+        assert!(!xmp.contains("dc:creator"));
+    }
+
+    #[test]
+    fn test_srgb_icc_profile_structure() {
+        let profile = srgb_icc_profile();
+        assert_eq!(u32::from_be_bytes(profile[..4].try_into().unwrap()) as usize, profile.len());
+        assert_eq!(&profile[12..24], b"mntrRGB XYZ ");
+        assert_eq!(&profile[36..40], b"acsp");
+        assert_eq!(u32::from_be_bytes(profile[128..132].try_into().unwrap()), 9);
+        assert_eq!(profile.len() % 4, 0);
+
+        // Every tag lies inside the profile and starts with its type signature
+        for tag in profile[132..132 + 9 * 12].chunks_exact(12) {
+            let offset = u32::from_be_bytes(tag[4..8].try_into().unwrap()) as usize;
+            let size = u32::from_be_bytes(tag[8..12].try_into().unwrap()) as usize;
+            assert!(offset + size <= profile.len());
+            let kind = &profile[offset..offset + 4];
+            assert!([b"desc", b"text", b"XYZ ", b"curv"].iter().any(|k| kind == *k));
+        }
+    }
+}
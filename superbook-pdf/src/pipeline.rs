@@ -7,11 +7,12 @@
 //!
 //! 1. PDF読み込み・メタデータ抽出
 //! 2. 画像抽出 (pdftoppm/ImageMagick)
+//!    (スキャナーのゴミ・髪の毛による線の除去)
 //! 3. 傾き補正 (Deskew)
 //! 4. マージントリミング
 //! 5. AI超解像 (RealESRGAN)
 //! 6. 内部解像度正規化
-//! 7. 色統計分析・グローバル色補正
+//! 7. 色統計分析・グローバル色補正 / 自動コントラスト
 //! 8. Tukey fenceグループクロップ
 //! 9. ページ番号オフセット計算
 //! 10. 最終出力リサイズ
@@ -21,13 +22,15 @@
 
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 use crate::cli::ConvertArgs;
+use crate::progress::StageTimings;
 
 // ============================================================
 // Memory Management Utilities (Phase 3 optimization)
@@ -42,6 +45,52 @@ const MIN_CHUNK_SIZE: usize = 4;
 /// Default memory limit if not specified (4GB)
 const DEFAULT_MEMORY_LIMIT_MB: usize = 4096;
 
+/// Default number of extracted pages buffered ahead of processing
+pub const DEFAULT_EXTRACT_QUEUE_DEPTH: usize = 4;
+
+/// Number of sampled pages for auto contrast previews
+const CONTRAST_PREVIEW_SAMPLES: usize = 3;
+
+/// RNG seed passed to AI tools in deterministic mode
+const DETERMINISTIC_SEED: u64 = 42;
+
+/// Extension of the per-book metadata override file
+const METADATA_OVERRIDE_EXTENSION: &str = "metadata.toml";
+
+/// Suffix [`PdfPipeline::get_output_path`] appends to the input file stem
+pub const OUTPUT_SUFFIX: &str = "_converted";
+
+/// Maximum length of the OCR-derived Subject summary (characters)
+const OCR_SUMMARY_CHARS: usize = 200;
+
+/// Default OCR confidence (percent) below which pages are flagged for review
+pub const DEFAULT_OCR_MIN_CONFIDENCE: f32 = 60.0;
+
+/// Default effective output DPI below which a book is flagged as too soft
+pub const DEFAULT_MIN_OUTPUT_DPI: u32 = 150;
+
+/// Extraction DPI of the quick (draft) preset
+pub const QUICK_DPI: u32 = 150;
+
+/// Output height of the quick (draft) preset (A4 at 150 DPI)
+pub const QUICK_OUTPUT_HEIGHT: u32 = 1754;
+
+/// Requested DPI above this multiple of the source image resolution is
+/// reported as interpolating detail that is not there
+pub const LOW_SOURCE_DPI_RATIO: f64 = 1.5;
+
+/// Default per-page pixel budget (megapixels, about 1 GiB as RGBA)
+pub const DEFAULT_MAX_PAGE_MEGAPIXELS: u32 = 256;
+
+/// Default cap on the longest edge of an extracted page (pixels)
+pub const DEFAULT_MAX_PAGE_DIMENSION: u32 = 16384;
+
+/// Page size assumed when estimating image sizes from the config (A4, inches)
+const REFERENCE_PAGE_INCHES: (f64, f64) = (8.27, 11.69);
+
+/// Bytes per pixel of a decoded page image (RGBA)
+const BYTES_PER_PIXEL: f64 = 4.0;
+
 /// Calculate optimal chunk size based on memory constraints
 ///
 /// # Arguments
@@ -102,6 +151,30 @@ fn get_available_memory_mb() -> Option<usize> {
     Some(DEFAULT_MEMORY_LIMIT_MB)
 }
 
+/// Total physical memory in MB (None where it cannot be determined)
+///
+/// Linux reads `/proc/meminfo`, macOS asks `sysctl hw.memsize`; other
+/// platforms report None so callers can say so instead of omitting it.
+pub fn total_memory_mb() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        let line = meminfo.lines().find(|l| l.starts_with("MemTotal:"))?;
+        let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kb / 1024)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("sysctl").args(["-n", "hw.memsize"]).output().ok()?;
+        let bytes: u64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+        Some(bytes / 1_048_576)
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
 /// Process items in chunks for memory-controlled parallel execution
 ///
 /// This function processes items in batches to prevent memory exhaustion.
@@ -165,8 +238,43 @@ where
     indexed_results.into_iter().map(|(_, r)| r).collect()
 }
 
+/// File being processed, passed to [`ProgressCallback::on_file_start`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineContext {
+    /// Input PDF
+    pub input: PathBuf,
+    /// 0-based position of this file in the run
+    pub file_index: usize,
+    /// Number of files in the run
+    pub file_count: usize,
+}
+
+impl PipelineContext {
+    /// Context for file `file_index` (0-based) of `file_count`
+    pub fn new(input: impl Into<PathBuf>, file_index: usize, file_count: usize) -> Self {
+        Self {
+            input: input.into(),
+            file_index,
+            file_count: file_count.max(file_index + 1),
+        }
+    }
+
+    /// Context for a run with a single file
+    pub fn single(input: impl Into<PathBuf>) -> Self {
+        Self::new(input, 0, 1)
+    }
+
+    /// 1-based position label, e.g. `[2/5]`
+    pub fn position(&self) -> String {
+        format!("[{}/{}]", self.file_index + 1, self.file_count)
+    }
+}
+
 /// Progress callback for pipeline steps
 pub trait ProgressCallback: Send + Sync {
+    /// Called before any step of a file; later calls belong to this file
+    /// until the next `on_file_start`
+    fn on_file_start(&self, _context: &PipelineContext) {}
     /// Called when a new step starts
     fn on_step_start(&self, step: &str);
     /// Called to report progress within a step
@@ -175,6 +283,220 @@ pub trait ProgressCallback: Send + Sync {
     fn on_step_complete(&self, step: &str, message: &str);
     /// Called for debug/verbose messages
     fn on_debug(&self, message: &str);
+    /// Called when page `page` (0-based) has an image on disk: first after
+    /// the per-page stages (`is_final = false`), then again with the final
+    /// image once the document-wide stages finish
+    fn on_page_complete(&self, _page: usize, _image: &Path, _is_final: bool) {}
+    /// Called when a setting was adjusted or a check found a problem but
+    /// processing continues
+    fn on_warning(&self, _message: &str) {}
+}
+
+/// Report `on_page_complete` for consecutive pages starting at `first_page`
+fn report_pages_complete<P: ProgressCallback>(progress: &P, first_page: usize, images: &[PathBuf], is_final: bool) {
+    for (offset, image) in images.iter().enumerate() {
+        progress.on_page_complete(first_page + offset, image, is_final);
+    }
+}
+
+/// Warn that page `page` (0-based) is skipped
+/// Logical page label of each page: from the page label ranges, else from
+/// the page number shift (`None` before logical page 1 or when page
+/// numbers were not detected)
+fn logical_page_labels(
+    shift: Option<i32>,
+    page_labels: &[crate::PageLabelRange],
+    page_count: usize,
+) -> Vec<Option<String>> {
+    if !page_labels.is_empty() {
+        return crate::PageLabeler::labels(page_labels, page_count)
+            .into_iter()
+            .map(|label| Some(label).filter(|label| !label.is_empty()))
+            .collect();
+    }
+    (0..page_count)
+        .map(|index| {
+            let number = index as i64 + 1 - shift? as i64;
+            (number > 0).then(|| number.to_string())
+        })
+        .collect()
+}
+
+/// Size of the output file, or of the files in an output directory
+fn output_size(path: &Path) -> u64 {
+    if !path.is_dir() {
+        return std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    }
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|entry| entry.metadata().ok())
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// OCR results as markdown page contents (blocks in OCR order)
+fn ocr_page_contents(
+    images: &[PathBuf],
+    ocr_results: &[Option<crate::OcrResult>],
+    is_vertical: bool,
+) -> Vec<crate::markdown::PageContent> {
+    use crate::markdown::{BoundingBox, PageContent, TextBlock};
+
+    images
+        .iter()
+        .zip(ocr_results)
+        .enumerate()
+        .map(|(idx, (img_path, result))| {
+            let page_size = image::image_dimensions(img_path).unwrap_or((0, 0));
+            let mut page = PageContent::new(idx + 1, page_size);
+            page.is_vertical = is_vertical;
+            if let Some(result) = result {
+                for b in &result.text_blocks {
+                    let (x, y, w, h) = b.bbox;
+                    let mut block = TextBlock::new(b.text.clone(), BoundingBox::new(x, y, w, h));
+                    block.confidence = b.confidence as f64;
+                    block.font_size = b.font_size.unwrap_or(0.0);
+                    page.add_block(block);
+                }
+            }
+            page
+        })
+        .collect()
+}
+
+/// Paint regions of a page image white (grown by a few pixels for glyph edges)
+fn erase_regions(path: &Path, regions: &[crate::markdown::BoundingBox]) -> image::ImageResult<()> {
+    use image::GenericImage;
+
+    const GROW: u32 = 4;
+    let mut img = image::open(path)?;
+    let (width, height) = (img.width(), img.height());
+    for region in regions {
+        let (x0, y0) = (region.x.saturating_sub(GROW), region.y.saturating_sub(GROW));
+        let (x1, y1) = ((region.right() + GROW).min(width), (region.bottom() + GROW).min(height));
+        for y in y0..y1 {
+            for x in x0..x1 {
+                img.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+            }
+        }
+    }
+    img.save(path)
+}
+
+/// Append one stage's per-page transforms to the page geometry
+fn record_transforms<I: IntoIterator<Item = crate::PageTransform>>(geometry: &mut [crate::PageGeometry], transforms: Vec<I>) {
+    for (page, transforms) in geometry.iter_mut().zip(transforms) {
+        page.transforms.extend(transforms);
+    }
+}
+
+/// Move a page `dx` pixels horizontally on a canvas of the same size,
+/// filling the uncovered strip with the estimated paper color
+fn shift_page(img: &image::DynamicImage, dx: i32) -> image::DynamicImage {
+    let rgb = img.to_rgb8();
+    let paper = crate::ImageNormalizer::estimate_paper_color(&rgb);
+    let (width, height) = rgb.dimensions();
+    if let image::DynamicImage::ImageLuma8(gray) = img {
+        let mut canvas = image::GrayImage::from_pixel(width, height, image::Luma([paper.luminance()]));
+        image::imageops::overlay(&mut canvas, gray, dx as i64, 0);
+        return image::DynamicImage::ImageLuma8(canvas);
+    }
+    let mut canvas = image::RgbImage::from_pixel(width, height, image::Rgb(paper.to_rgb()));
+    image::imageops::overlay(&mut canvas, &rgb, dx as i64, 0);
+    image::DynamicImage::ImageRgb8(canvas)
+}
+
+/// Scale and padding applied by resolution normalization
+fn normalize_transforms(result: &crate::NormalizeResult) -> Vec<crate::PageTransform> {
+    let mut transforms = Vec::new();
+    if result.fitted_size != result.original_size {
+        transforms.push(crate::PageTransform::Scale {
+            x: result.fitted_size.0 as f64 / result.original_size.0.max(1) as f64,
+            y: result.fitted_size.1 as f64 / result.original_size.1.max(1) as f64,
+        });
+    }
+    if result.normalized_size != result.fitted_size || result.offset != (0, 0) {
+        transforms.push(crate::PageTransform::Pad {
+            x: result.offset.0,
+            y: result.offset.1,
+            width: result.normalized_size.0,
+            height: result.normalized_size.1,
+        });
+    }
+    transforms
+}
+
+/// Crop or padding applied by aspect ratio fitting
+fn aspect_transform(result: &crate::AspectFitResult) -> Option<crate::PageTransform> {
+    let (width, height) = result.final_size;
+    match result.adjustment {
+        crate::AspectAdjustment::Unchanged => None,
+        crate::AspectAdjustment::Cropped { .. } => Some(crate::PageTransform::Crop {
+            x: result.offset.0,
+            y: result.offset.1,
+            width,
+            height,
+        }),
+        crate::AspectAdjustment::Padded { .. } => Some(crate::PageTransform::Pad {
+            x: result.offset.0 as i32,
+            y: result.offset.1 as i32,
+            width,
+            height,
+        }),
+    }
+}
+
+/// Page groups cropped together in Step 8: the main orientation, then
+/// (per-orientation only) the minority pages; preserved minority pages
+/// are in no group
+fn mixed_size_groups(mixed_size: MixedSize, minority: &[bool]) -> Vec<Vec<usize>> {
+    let (main, other): (Vec<_>, Vec<_>) = (0..minority.len()).partition(|&i| !minority[i]);
+    match mixed_size {
+        MixedSize::Uniform => vec![(0..minority.len()).collect()],
+        MixedSize::PerOrientation if !other.is_empty() => vec![main, other],
+        _ => vec![main],
+    }
+}
+
+/// Step 10 target height of each page of `sizes`
+///
+/// Main pages get `output_height`. Minority pages are scaled like the
+/// median main page (preserve) or to fit inside it turned by 90°
+/// (per-orientation), instead of being squashed to the main page height.
+fn finalize_heights(mixed_size: MixedSize, sizes: &[(u32, u32)], minority: &[bool], output_height: u32) -> Vec<u32> {
+    let median = |mut values: Vec<u32>| {
+        values.sort_unstable();
+        values.get(values.len() / 2).copied().unwrap_or(0)
+    };
+    let main: Vec<(u32, u32)> = sizes.iter().zip(minority).filter(|(_, &m)| !m).map(|(&size, _)| size).collect();
+    let main_height = median(main.iter().map(|size| size.1).collect());
+    let main_width = median(main.iter().map(|size| size.0).collect());
+    if mixed_size.is_uniform() || main_height == 0 {
+        return vec![output_height; sizes.len()];
+    }
+
+    let target = output_height as f64;
+    let main_scale = target / main_height as f64;
+    let main_width = main_width as f64 * main_scale;
+    sizes
+        .iter()
+        .zip(minority)
+        .map(|(&(width, height), &is_minority)| {
+            if !is_minority {
+                return output_height;
+            }
+            let scale = match mixed_size {
+                MixedSize::PerOrientation => (target / width.max(1) as f64).min(main_width / height.max(1) as f64),
+                _ => main_scale,
+            };
+            ((height as f64 * scale).round() as u32).max(1)
+        })
+        .collect()
 }
 
 /// No-op progress callback (silent mode)
@@ -205,6 +527,28 @@ pub enum PipelineError {
     #[error("PDF generation failed: {0}")]
     PdfGenerationFailed(String),
 
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Invalid metadata override file {0}: {1}")]
+    InvalidMetadata(PathBuf, String),
+
+    #[error("{what} requested but {tool} not found; install {tool} or drop {flag} (--ocr-optional skips it instead)")]
+    OcrUnavailable {
+        what: &'static str,
+        tool: &'static str,
+        flag: &'static str,
+    },
+
+    #[error("Hook failed: {0}")]
+    HookFailed(#[from] crate::hooks::HookError),
+
+    #[error("Page manifest error: {0}")]
+    PageManifest(#[from] crate::PageManifestError),
+
+    #[error("Output error: {0}")]
+    Output(#[from] crate::OutputSinkError),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -246,6 +590,279 @@ pub struct PipelineConfig {
     /// Chunk size for batch processing (0 = auto based on memory)
     #[serde(default)]
     pub chunk_size: usize,
+    /// Extracted pages buffered ahead of processing (0 = extract all pages first)
+    #[serde(default = "default_extract_queue_depth")]
+    pub extract_queue_depth: usize,
+    /// Pages rendered per pdftoppm process (1 = one process per page)
+    #[serde(
+        default = "default_extract_batch_pages",
+        skip_serializing_if = "is_default_extract_batch_pages"
+    )]
+    pub extract_batch_pages: usize,
+    /// Auto contrast mode
+    #[serde(default)]
+    pub auto_contrast: crate::AutoContrast,
+    /// Apply auto contrast per R/G/B channel instead of luminance
+    #[serde(default)]
+    pub auto_contrast_per_channel: bool,
+    /// Manual tone curve applied after auto contrast
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tone_curve: Option<crate::ToneCurve>,
+    /// Extra OCR layout exports (hOCR / ALTO) written next to the PDF
+    #[serde(default)]
+    pub ocr_formats: Vec<crate::markdown::OcrExportFormat>,
+    /// Reproducible output: fixed seeds, stable ordering and options recorded in the PDF
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Catalog metadata for the output PDF (overrides source/derived values)
+    #[serde(default)]
+    pub metadata: crate::PdfMetadata,
+    /// Fill an empty Subject with a summary of the OCR text
+    #[serde(default)]
+    pub metadata_from_ocr: bool,
+    /// Write the OCR text in reading order to `<output>.txt`
+    #[serde(default)]
+    pub write_text_sidecar: bool,
+    /// Join words hyphenated at line ends in the text sidecar (horizontal
+    /// text only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dehyphenate: bool,
+    /// Source pages (1-based) to OCR; other pages get no text layer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocr_pages: Option<crate::PageRanges>,
+    /// Skip OCR on pages that are predominantly pictures (image plates)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ocr_skip_pictures: bool,
+    /// Skip OCR when YomiToku is missing instead of failing
+    #[serde(default)]
+    pub ocr_optional: bool,
+    /// Keep visibly colored pages in color and write the rest as grayscale
+    #[serde(default)]
+    pub mixed_color: bool,
+    /// Keep only detected figures in color on otherwise grayscale pages
+    /// (figures are found in the OCR results)
+    #[serde(default, skip_serializing_if = "ColorPolicy::is_per_page")]
+    pub color_policy: ColorPolicy,
+    /// Sizing of pages in the minority orientation (fold-outs)
+    #[serde(default, skip_serializing_if = "MixedSize::is_uniform")]
+    pub mixed_size: MixedSize,
+    /// OCR confidence (percent) below which pages are flagged as suspect
+    #[serde(default = "default_ocr_min_confidence")]
+    pub ocr_min_confidence: f32,
+    /// Executable run on the finished PDF (path argument, manifest JSON on stdin)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_hook: Option<String>,
+    /// Executable run on each final page image (`<image> <page number>`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_hook: Option<String>,
+    /// Compute SSIM/PSNR between each extracted page and its final image
+    #[serde(default)]
+    pub quality_metrics: bool,
+    /// Page binding direction written to the PDF (Auto = from vertical text detection)
+    #[serde(default)]
+    pub binding: crate::Binding,
+    /// Vertical/horizontal text override for books the detector gets wrong
+    /// (applies to page number search, binding, reading order and OCR output)
+    #[serde(default, skip_serializing_if = "crate::markdown::TextDirectionOption::is_auto")]
+    pub text_direction: crate::markdown::TextDirectionOption,
+    /// Color transparent page pixels are composited over (RGB); dropping the
+    /// alpha channel instead turns transparent paper black
+    #[serde(default = "default_flatten_background", skip_serializing_if = "is_default_flatten_background")]
+    pub flatten_background: [u8; 3],
+    /// Filter for internal normalization and the output-height resize
+    #[serde(default)]
+    pub resize_filter: crate::Resampler,
+    /// Encoding of bilevel (pure black-and-white) pages in the PDF
+    #[serde(default, skip_serializing_if = "crate::ImageEncoding::is_standard")]
+    pub image_encoding: crate::ImageEncoding,
+    /// Reuse the source PDF's text layer instead of running OCR when most
+    /// pages already have text
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub skip_existing_ocr: bool,
+    /// Inpaint isolated full-length thin dark lines (scanner dust/hair)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub remove_line_artifacts: bool,
+    /// Remove highlighter marks
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub remove_markers: bool,
+    /// Highlighter colors to remove by name (empty = all built-in colors)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marker_colors: Vec<String>,
+    /// Page area markers are removed from (whole page or margins only)
+    #[serde(default, skip_serializing_if = "crate::cleanup::MarkerArea::is_page")]
+    pub marker_region: crate::cleanup::MarkerArea,
+    /// Named highlighter colors from `[[highlighter]]` config tables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlighters: Vec<crate::cleanup::CustomHighlighter>,
+    /// Gray out colors below this chroma (0.0-1.0) before OCR and binarization
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desaturate_below: Option<f32>,
+    /// Fit pages to this width:height ratio after group crop (pad rather than clip content)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_aspect: Option<(u32, u32)>,
+    /// Write PDF/A output at this level (PDF/A-1b is raised to PDF/A-2b
+    /// when there is an OCR text layer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pdfa: Option<crate::PdfaLevel>,
+    /// Check PDF/A output with veraPDF (or Ghostscript); not
+    /// output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub pdfa_validate: bool,
+    /// Output container (PDF, or CBZ of the final page images)
+    #[serde(default, skip_serializing_if = "crate::BookFormat::is_pdf")]
+    pub output_format: crate::BookFormat,
+    /// Write PDF page labels from the detected logical page numbers
+    /// (runs page number detection even without `offset_alignment`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub page_labels: bool,
+    /// Detect running headers/footers in the OCR text and leave them out
+    /// of the text layer (`Exclude`) or also erase them (`Erase`)
+    #[serde(default, skip_serializing_if = "crate::markdown::RunningHeadMode::is_keep")]
+    pub running_heads: crate::markdown::RunningHeadMode,
+    /// Process only odd or even pages (output name gets an `_odd` /
+    /// `_even` suffix; page number detection is skipped)
+    #[serde(default, skip_serializing_if = "crate::PageParity::is_all")]
+    pub parity: crate::PageParity,
+    /// Invert negative (white-on-black) scans before the other page stages
+    #[serde(default, skip_serializing_if = "crate::Invert::is_off")]
+    pub invert: crate::Invert,
+    /// Handling of a page that fails (abort the file, leave the page out,
+    /// or insert a "page N unavailable" placeholder)
+    #[serde(default, skip_serializing_if = "PageErrorPolicy::is_skip")]
+    pub on_page_error: PageErrorPolicy,
+    /// Tukey fence / fallback parameters for the group crop step
+    #[serde(default, skip_serializing_if = "crate::GroupCropParams::is_default")]
+    pub group_crop: crate::GroupCropParams,
+    /// Shift odd and even pages horizontally so their content lines up
+    /// (constant front/back offset of duplex scans, estimated from the
+    /// content bounding boxes; independent of page number detection)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub register_sides: bool,
+    /// External tool locations by name (e.g. "pdftoppm"); environment, not
+    /// output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub tool_paths: HashMap<String, PathBuf>,
+    /// Niceness of spawned CPU tools (`nice -n`, 0 = unchanged); not
+    /// output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub nice: i32,
+    /// Kill an external tool call (pdftoppm, ImageMagick, tesseract, ...)
+    /// after this many seconds and retry it once; a page that still times
+    /// out is handled per `on_page_error` and reported in
+    /// `PipelineResult::failed_pages`.
+    /// Not output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub tool_timeout_secs: Option<u64>,
+    /// Warn through `on_warning` when no step reports progress for this
+    /// many seconds (possible hang; nothing is stopped). Not
+    /// output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub stall_warning_secs: Option<u64>,
+    /// Tesseract calls run at once during page number detection (None =
+    /// one per pool thread), separate from `threads` so OCR can be bounded
+    /// on its own; not output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub ocr_concurrency: Option<usize>,
+    /// Run GPU stages (upscale, OCR) one at a time across all pipelines in
+    /// the process; not output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub gpu_wait: bool,
+    /// Share (0.0-1.0) of the thread budget (`threads`, default CPU count)
+    /// reserved for external tools; the rest sizes the rayon pool. 0 leaves
+    /// both unbounded. Not output-affecting, so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub external_thread_fraction: f32,
+    /// Effective output DPI (final pixels per inch of the physical page)
+    /// below which a book is handled per `on_low_dpi` (0 = no check).
+    /// Not output-affecting, so kept out of the cache digest
+    #[serde(default = "default_min_output_dpi", skip_serializing)]
+    pub min_output_dpi: u32,
+    /// Handling of a book below `min_output_dpi` (warn, or refuse to write it)
+    #[serde(default, skip_serializing)]
+    pub on_low_dpi: LowDpiPolicy,
+    /// Largest page image (megapixels) the `dpi` / upscale / `output_height`
+    /// combination may produce; larger combinations fail validation (0 = no
+    /// limit). Not output-affecting, so kept out of the cache digest
+    #[serde(default = "default_max_page_megapixels", skip_serializing)]
+    pub max_page_megapixels: u32,
+    /// Longest edge (pixels) of an extracted page; a page whose media box
+    /// at `dpi` would exceed it is rendered at a lower DPI, with a warning
+    /// and a record in the manifests (0 = no cap)
+    #[serde(
+        default = "default_max_page_dimension",
+        skip_serializing_if = "is_default_max_page_dimension"
+    )]
+    pub max_page_dimension: u32,
+    /// Carry the source PDF's creator, producer and dates into the output
+    /// instead of stamping superbook-pdf (title, author, subject and
+    /// keywords are always carried over)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve_source_metadata: bool,
+    /// Copy the source PDF's outline (bookmarks) into the output, with
+    /// destinations moved to the output pages
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve_source_outline: bool,
+    /// Directory of the content-addressed cache of per-page stage results
+    /// shared across books and runs (`None` = off). Not output-affecting,
+    /// so kept out of the cache digest
+    #[serde(default, skip_serializing)]
+    pub work_cache: Option<PathBuf>,
+    /// Size limit of `work_cache` in MB; the least recently used entries
+    /// are evicted beyond it
+    #[serde(default = "default_work_cache_max_mb", skip_serializing)]
+    pub work_cache_max_mb: u64,
+    /// Continue an interrupted run from the progress file in its work
+    /// directory (streamed pages only; `crate::resume`)
+    #[serde(default, skip_serializing)]
+    pub resume: bool,
+    /// Quick (draft) preset applied; recorded in the manifests so the
+    /// draft is not mistaken for the final output
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub quick: bool,
+}
+
+fn default_extract_queue_depth() -> usize {
+    DEFAULT_EXTRACT_QUEUE_DEPTH
+}
+
+fn default_extract_batch_pages() -> usize {
+    crate::image_extract::DEFAULT_BATCH_PAGES
+}
+
+fn is_default_extract_batch_pages(pages: &usize) -> bool {
+    *pages == crate::image_extract::DEFAULT_BATCH_PAGES
+}
+
+fn default_ocr_min_confidence() -> f32 {
+    DEFAULT_OCR_MIN_CONFIDENCE
+}
+
+fn default_min_output_dpi() -> u32 {
+    DEFAULT_MIN_OUTPUT_DPI
+}
+
+fn default_max_page_megapixels() -> u32 {
+    DEFAULT_MAX_PAGE_MEGAPIXELS
+}
+
+fn default_max_page_dimension() -> u32 {
+    DEFAULT_MAX_PAGE_DIMENSION
+}
+
+fn is_default_max_page_dimension(pixels: &u32) -> bool {
+    *pixels == DEFAULT_MAX_PAGE_DIMENSION
+}
+
+fn default_flatten_background() -> [u8; 3] {
+    crate::util::DEFAULT_FLATTEN_BACKGROUND
+}
+
+fn is_default_flatten_background(rgb: &[u8; 3]) -> bool {
+    *rgb == crate::util::DEFAULT_FLATTEN_BACKGROUND
+}
+
+fn default_work_cache_max_mb() -> u64 {
+    crate::work_cache::DEFAULT_WORK_CACHE_MAX_MB
 }
 
 impl Default for PipelineConfig {
@@ -267,6 +884,67 @@ impl Default for PipelineConfig {
             threads: None,
             max_memory_mb: 0,  // 0 = unlimited
             chunk_size: 0,    // 0 = auto
+            extract_queue_depth: DEFAULT_EXTRACT_QUEUE_DEPTH,
+            extract_batch_pages: crate::image_extract::DEFAULT_BATCH_PAGES,
+            auto_contrast: crate::AutoContrast::Off,
+            auto_contrast_per_channel: false,
+            tone_curve: None,
+            ocr_formats: Vec::new(),
+            deterministic: false,
+            metadata: crate::PdfMetadata::default(),
+            metadata_from_ocr: false,
+            write_text_sidecar: false,
+            dehyphenate: false,
+            ocr_pages: None,
+            ocr_skip_pictures: false,
+            ocr_optional: false,
+            mixed_color: false,
+            color_policy: ColorPolicy::PerPage,
+            mixed_size: MixedSize::Uniform,
+            ocr_min_confidence: DEFAULT_OCR_MIN_CONFIDENCE,
+            post_hook: None,
+            page_hook: None,
+            quality_metrics: false,
+            binding: crate::Binding::Auto,
+            text_direction: crate::markdown::TextDirectionOption::Auto,
+            flatten_background: crate::util::DEFAULT_FLATTEN_BACKGROUND,
+            resize_filter: crate::Resampler::Lanczos3,
+            image_encoding: crate::ImageEncoding::Standard,
+            skip_existing_ocr: false,
+            remove_line_artifacts: false,
+            remove_markers: false,
+            marker_colors: vec![],
+            marker_region: crate::cleanup::MarkerArea::Page,
+            highlighters: vec![],
+            desaturate_below: None,
+            target_aspect: None,
+            pdfa: None,
+            pdfa_validate: false,
+            output_format: crate::BookFormat::Pdf,
+            page_labels: false,
+            running_heads: crate::markdown::RunningHeadMode::Keep,
+            parity: crate::PageParity::All,
+            invert: crate::Invert::Off,
+            on_page_error: PageErrorPolicy::Skip,
+            group_crop: crate::GroupCropParams::default(),
+            register_sides: false,
+            tool_paths: HashMap::new(),
+            nice: 0,
+            tool_timeout_secs: None,
+            stall_warning_secs: Some(crate::DEFAULT_STALL_WARNING_SECS),
+            ocr_concurrency: None,
+            gpu_wait: false,
+            external_thread_fraction: 0.0,
+            min_output_dpi: DEFAULT_MIN_OUTPUT_DPI,
+            on_low_dpi: LowDpiPolicy::Warn,
+            max_page_megapixels: DEFAULT_MAX_PAGE_MEGAPIXELS,
+            max_page_dimension: DEFAULT_MAX_PAGE_DIMENSION,
+            preserve_source_metadata: false,
+            preserve_source_outline: false,
+            work_cache: None,
+            work_cache_max_mb: crate::work_cache::DEFAULT_WORK_CACHE_MAX_MB,
+            resume: false,
+            quick: false,
         }
     }
 }
@@ -292,7 +970,104 @@ impl PipelineConfig {
             threads: args.threads,
             max_memory_mb: 0,  // Auto-detect based on available memory
             chunk_size: 0,    // Auto-calculate based on memory limit
+            extract_queue_depth: args.extract_queue_depth,
+            extract_batch_pages: args.extract_batch_pages.max(1),
+            auto_contrast: args.effective_auto_contrast(),
+            auto_contrast_per_channel: args.contrast_per_channel,
+            tone_curve: args.effective_tone_curve(),
+            ocr_formats: args.effective_ocr_formats(),
+            deterministic: args.deterministic,
+            metadata: crate::PdfMetadata::default(),
+            metadata_from_ocr: args.metadata_from_ocr,
+            write_text_sidecar: args.text_sidecar,
+            dehyphenate: args.dehyphenate,
+            ocr_pages: args.ocr_pages.clone(),
+            ocr_skip_pictures: args.ocr_skip_pictures,
+            ocr_optional: args.ocr_optional,
+            mixed_color: args.mixed_color,
+            color_policy: args.color_policy.into(),
+            mixed_size: args.mixed_size.into(),
+            ocr_min_confidence: args.ocr_min_confidence,
+            post_hook: args.post_hook.clone(),
+            page_hook: args.page_hook.clone(),
+            quality_metrics: args.quality_metrics,
+            binding: args.page_order.into(),
+            text_direction: args.text_direction.into(),
+            flatten_background: args.flatten_background,
+            resize_filter: args.resize_filter.into(),
+            image_encoding: args.image_encoding.into(),
+            skip_existing_ocr: args.skip_existing_ocr,
+            remove_line_artifacts: args.remove_line_artifacts,
+            remove_markers: args.remove_markers,
+            marker_colors: if args.remove_markers { args.marker_colors.clone() } else { vec![] },
+            marker_region: if args.remove_markers { args.marker_region.into() } else { Default::default() },
+            highlighters: vec![],
+            desaturate_below: args.desaturate_below.map(|s| s.clamp(0.0, 1.0)).filter(|&s| s > 0.0),
+            target_aspect: args.target_aspect,
+            pdfa: args.pdfa.map(Into::into),
+            pdfa_validate: args.pdfa_validate,
+            output_format: args.output_format.into(),
+            page_labels: args.page_labels,
+            running_heads: args.running_heads.into(),
+            parity: args.parity.into(),
+            invert: args.invert.into(),
+            on_page_error: args.on_page_error.into(),
+            group_crop: crate::GroupCropParams::default(),
+            register_sides: args.register_sides,
+            tool_paths: HashMap::new(),
+            nice: args.nice,
+            tool_timeout_secs: args.tool_timeout.filter(|&secs| secs > 0),
+            stall_warning_secs: Some(args.stall_warning).filter(|&secs| secs > 0),
+            ocr_concurrency: args.ocr_concurrency.filter(|&limit| limit > 0),
+            gpu_wait: args.gpu_wait,
+            external_thread_fraction: args.external_thread_fraction.unwrap_or(0.0).clamp(0.0, 1.0),
+            min_output_dpi: args.min_output_dpi,
+            on_low_dpi: args.on_low_dpi.into(),
+            max_page_megapixels: args.max_page_megapixels,
+            max_page_dimension: args.max_dimension,
+            preserve_source_metadata: args.preserve_metadata,
+            preserve_source_outline: args.preserve_outline,
+            work_cache: args.work_cache.clone(),
+            work_cache_max_mb: args.work_cache_max_mb,
+            resume: args.resume,
+            quick: false,
+        }
+        .with_quick(args.quick)
+    }
+
+    /// Quick (draft) preset: the defaults with [`with_quick`](Self::with_quick)
+    pub fn quick() -> Self {
+        Self::default().with_quick(true)
+    }
+
+    /// Builder pattern: apply the quick (draft) preset for fast previews
+    ///
+    /// Lowers the DPI and output height, disables AI upscaling, OCR and the
+    /// stages that need it, internal resolution normalization and quality
+    /// metrics, trims 1% margins, lowers JPEG quality and skips the
+    /// output DPI check. `false` leaves the config unchanged.
+    pub fn with_quick(mut self, enabled: bool) -> Self {
+        if !enabled {
+            return self;
         }
+        self.quick = true;
+        self.dpi = QUICK_DPI;
+        self.output_height = QUICK_OUTPUT_HEIGHT;
+        self.upscale = false;
+        self.ocr = false;
+        self.ocr_formats.clear();
+        self.metadata_from_ocr = false;
+        self.write_text_sidecar = false;
+        self.dehyphenate = false;
+        self.skip_existing_ocr = false;
+        self.running_heads = crate::markdown::RunningHeadMode::Keep;
+        self.color_policy = ColorPolicy::PerPage;
+        self.internal_resolution = false;
+        self.quality_metrics = false;
+        self.margin_trim = self.margin_trim.max(1.0);
+        self.jpeg_quality = self.jpeg_quality.min(75);
+        self.min_output_dpi = 0;
+        self
     }
 
     /// Convert to JSON string for cache digest
@@ -300,6 +1075,176 @@ impl PipelineConfig {
         serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
     }
 
+    /// Every setting of the run as a JSON object: the fields of
+    /// [`Self::to_json`] plus the environment settings it leaves out of the
+    /// cache digest (`tool_paths`, `nice`, `work_cache`, ...)
+    ///
+    /// Options left at a default that `to_json` omits stay omitted. The
+    /// keys are the field names `--set` and option profiles accept, and the
+    /// object deserializes back into the same configuration.
+    pub fn to_resolved_json(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_else(|_| serde_json::json!({}));
+        if let Some(fields) = value.as_object_mut() {
+            let environment = serde_json::json!({
+                "pdfa_validate": self.pdfa_validate,
+                "tool_paths": self.tool_paths,
+                "nice": self.nice,
+                "tool_timeout_secs": self.tool_timeout_secs,
+                "stall_warning_secs": self.stall_warning_secs,
+                "ocr_concurrency": self.ocr_concurrency,
+                "gpu_wait": self.gpu_wait,
+                "external_thread_fraction": self.external_thread_fraction,
+                "min_output_dpi": self.min_output_dpi,
+                "on_low_dpi": self.on_low_dpi,
+                "max_page_megapixels": self.max_page_megapixels,
+                "work_cache": self.work_cache,
+                "work_cache_max_mb": self.work_cache_max_mb,
+                "resume": self.resume,
+            });
+            if let serde_json::Value::Object(environment) = environment {
+                fields.extend(environment);
+            }
+            // Unset options are left out (TOML has no null)
+            fields.retain(|_, value| !value.is_null());
+        }
+        value
+    }
+
+    /// [`Self::to_resolved_json`] as TOML
+    pub fn to_resolved_toml(&self) -> Result<String, PipelineError> {
+        toml::to_string_pretty(&self.to_resolved_json())
+            .map_err(|e| PipelineError::InvalidConfig(format!("cannot write config as TOML: {}", e)))
+    }
+
+    /// Create a builder starting from the defaults; `build()` validates
+    pub fn builder() -> PipelineConfigBuilder {
+        PipelineConfigBuilder::default()
+    }
+
+    /// Check option ranges that clap enforces for CLI arguments but a
+    /// config file or library caller can get wrong
+    ///
+    /// Also resolves `marker_colors`, so unknown highlighter names fail here.
+    pub fn validate(&self) -> Result<(), PipelineError> {
+        let invalid = |msg: String| Err(PipelineError::InvalidConfig(msg));
+        if !(1..=4800).contains(&self.dpi) {
+            return invalid(format!("dpi must be 1-4800, got {}", self.dpi));
+        }
+        if !(1..=100).contains(&self.jpeg_quality) {
+            return invalid(format!("jpeg_quality must be 1-100, got {}", self.jpeg_quality));
+        }
+        if self.output_height == 0 {
+            return invalid("output_height must be greater than 0".to_string());
+        }
+        if !(0.0..50.0).contains(&self.margin_trim) {
+            return invalid(format!("margin_trim must be 0-50 (percent), got {}", self.margin_trim));
+        }
+        if self.threads == Some(0) {
+            return invalid("threads must be greater than 0".to_string());
+        }
+        if self.extract_batch_pages == 0 {
+            return invalid("extract_batch_pages must be greater than 0".to_string());
+        }
+        if !(0.0..=100.0).contains(&self.ocr_min_confidence) {
+            return invalid(format!("ocr_min_confidence must be 0-100, got {}", self.ocr_min_confidence));
+        }
+        if let Some(threshold) = self.desaturate_below.filter(|t| !(0.0..=1.0).contains(t)) {
+            return invalid(format!("desaturate_below must be 0.0-1.0, got {}", threshold));
+        }
+        if let Some((w, h)) = self.target_aspect.filter(|&(w, h)| w == 0 || h == 0) {
+            return invalid(format!("target_aspect must be positive, got {}:{}", w, h));
+        }
+        if !crate::tools::NICENESS_RANGE.contains(&self.nice) {
+            return invalid(format!("nice must be -20 to 19, got {}", self.nice));
+        }
+        if self.tool_timeout_secs == Some(0) {
+            return invalid("tool_timeout_secs must be greater than 0".to_string());
+        }
+        if self.stall_warning_secs == Some(0) {
+            return invalid("stall_warning_secs must be greater than 0".to_string());
+        }
+        if self.ocr_concurrency == Some(0) {
+            return invalid("ocr_concurrency must be greater than 0".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.external_thread_fraction) {
+            return invalid(format!(
+                "external_thread_fraction must be 0.0-1.0, got {}",
+                self.external_thread_fraction
+            ));
+        }
+        if self.min_output_dpi > 4800 {
+            return invalid(format!("min_output_dpi must be 0-4800, got {}", self.min_output_dpi));
+        }
+        if let Some(budget) = self.page_pixel_budget() {
+            let estimate = self.estimated_page_megapixels();
+            if estimate > budget {
+                return invalid(self.pixel_budget_message(estimate, budget));
+            }
+        }
+        self.marker_removal_options()
+            .map_err(|e| PipelineError::InvalidConfig(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Largest page image (megapixels) this config is expected to produce:
+    /// the extracted page (A4 at `dpi`, doubled per side when upscaling) or
+    /// the output page (`output_height` at A4 proportions)
+    pub fn estimated_page_megapixels(&self) -> f64 {
+        let (width_in, height_in) = REFERENCE_PAGE_INCHES;
+        let upscale = if self.upscale { 2.0 } else { 1.0 };
+        let dpi = self.dpi as f64 * upscale;
+        let extracted = width_in * dpi * height_in * dpi;
+        let height = self.output_height as f64;
+        let output = height * height * width_in / height_in;
+        extracted.max(output) / 1_000_000.0
+    }
+
+    /// Per-page pixel budget (megapixels): `max_page_megapixels`, tightened
+    /// by `max_memory_mb` when set (None = no limit)
+    fn page_pixel_budget(&self) -> Option<f64> {
+        let pixels = (self.max_page_megapixels > 0).then_some(self.max_page_megapixels as f64);
+        let memory = (self.max_memory_mb > 0)
+            .then(|| self.max_memory_mb as f64 * 1024.0 * 1024.0 / BYTES_PER_PIXEL / 1_000_000.0);
+        match (pixels, memory) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Error message for a config over the pixel budget, with the largest
+    /// `dpi` and `output_height` that fit
+    fn pixel_budget_message(&self, estimate: f64, budget: f64) -> String {
+        let (width_in, height_in) = REFERENCE_PAGE_INCHES;
+        let upscale = if self.upscale { 2.0 } else { 1.0 };
+        let pixels = budget * 1_000_000.0;
+        let max_dpi = ((pixels / (width_in * height_in)).sqrt() / upscale).floor() as u32;
+        let max_height = (pixels * height_in / width_in).sqrt().floor() as u32;
+
+        let mut suggestions = Vec::new();
+        let extracted_dpi = self.dpi as f64 * upscale;
+        if width_in * extracted_dpi * height_in * extracted_dpi > pixels {
+            suggestions.push(format!("dpi {} or less", max_dpi));
+            if self.upscale && self.dpi as f64 * self.dpi as f64 * width_in * height_in <= pixels {
+                suggestions.push("disabling upscaling".to_string());
+            }
+        }
+        if self.output_height > max_height {
+            suggestions.push(format!("output_height {} or less", max_height));
+        }
+        suggestions.push("raising max_page_megapixels".to_string());
+
+        format!(
+            "dpi {}{} with output_height {} needs about {:.0} MP ({:.0} MB) per page, over the {:.0} MP budget; try {}",
+            self.dpi,
+            if self.upscale { " (upscaled 2x)" } else { "" },
+            self.output_height,
+            estimate,
+            estimate * BYTES_PER_PIXEL,
+            budget,
+            suggestions.join(", ")
+        )
+    }
+
     /// Builder pattern: set DPI
     pub fn with_dpi(mut self, dpi: u32) -> Self {
         self.dpi = dpi;
@@ -342,105 +1287,1757 @@ impl PipelineConfig {
         self
     }
 
-    /// Enable all advanced features
-    pub fn with_advanced(mut self) -> Self {
-        self.internal_resolution = true;
-        self.color_correction = true;
-        self.offset_alignment = true;
+    /// Builder pattern: set auto contrast mode
+    pub fn with_auto_contrast(mut self, mode: crate::AutoContrast) -> Self {
+        self.auto_contrast = mode;
         self
     }
-}
 
-/// Result of pipeline processing
-#[derive(Debug, Clone)]
-pub struct PipelineResult {
-    /// Number of pages processed
-    pub page_count: usize,
-    /// Detected page number shift
-    pub page_number_shift: Option<i32>,
-    /// Whether vertical text was detected
-    pub is_vertical: bool,
-    /// Processing time in seconds
-    pub elapsed_seconds: f64,
-    /// Output file path
-    pub output_path: PathBuf,
-    /// Output file size in bytes
-    pub output_size: u64,
-}
+    /// Builder pattern: set manual tone curve
+    pub fn with_tone_curve(mut self, curve: Option<crate::ToneCurve>) -> Self {
+        self.tone_curve = curve;
+        self
+    }
 
-impl PipelineResult {
-    /// Create a new pipeline result
-    pub fn new(
-        page_count: usize,
-        page_number_shift: Option<i32>,
-        is_vertical: bool,
-        elapsed_seconds: f64,
-        output_path: PathBuf,
-        output_size: u64,
-    ) -> Self {
-        Self {
-            page_count,
-            page_number_shift,
-            is_vertical,
-            elapsed_seconds,
-            output_path,
-            output_size,
-        }
+    /// Builder pattern: set OCR export formats
+    pub fn with_ocr_formats(mut self, formats: Vec<crate::markdown::OcrExportFormat>) -> Self {
+        self.ocr_formats = formats;
+        self
     }
 
-    /// Convert to cache ProcessingResult
-    pub fn to_cache_result(&self) -> crate::cache::ProcessingResult {
-        crate::cache::ProcessingResult::new(
-            self.page_count,
-            self.page_number_shift,
-            self.is_vertical,
-            self.elapsed_seconds,
-            self.output_size,
-        )
+    /// Builder pattern: set streaming extraction queue depth (0 = disabled)
+    pub fn with_extract_queue_depth(mut self, depth: usize) -> Self {
+        self.extract_queue_depth = depth;
+        self
     }
-}
 
-/// Processing context for intermediate data
-#[derive(Debug)]
-pub struct ProcessingContext {
-    /// Working directory for this PDF
-    pub work_dir: PathBuf,
-    /// PDF document info
-    pub pdf_info: crate::PdfDocument,
-    /// Extracted page images
-    pub current_images: Vec<PathBuf>,
-    /// Detected vertical writing
-    pub is_vertical: bool,
-    /// Page number shift
-    pub page_number_shift: Option<i32>,
-}
+    /// Builder pattern: set pages rendered per pdftoppm process (minimum 1)
+    pub fn with_extract_batch_pages(mut self, pages: usize) -> Self {
+        self.extract_batch_pages = pages.max(1);
+        self
+    }
 
-/// PDF processing pipeline
-pub struct PdfPipeline {
-    config: PipelineConfig,
-}
+    /// Builder pattern: set deterministic mode
+    pub fn with_deterministic(mut self, enabled: bool) -> Self {
+        self.deterministic = enabled;
+        self
+    }
 
-impl PdfPipeline {
-    /// Create a new pipeline with the given configuration
-    pub fn new(config: PipelineConfig) -> Self {
-        Self { config }
+    /// Builder pattern: skip OCR-dependent stages when their tools are missing
+    pub fn with_ocr_optional(mut self, enabled: bool) -> Self {
+        self.ocr_optional = enabled;
+        self
     }
 
-    /// Get the pipeline configuration
-    pub fn config(&self) -> &PipelineConfig {
-        &self.config
+    /// Builder pattern: set mixed color/grayscale output
+    pub fn with_mixed_color(mut self, enabled: bool) -> Self {
+        self.mixed_color = enabled;
+        self
     }
 
-    /// Get the output PDF path for a given input PDF
-    pub fn get_output_path(&self, input: &Path, output_dir: &Path) -> PathBuf {
-        let pdf_name = input.file_stem().unwrap_or_default().to_string_lossy();
-        output_dir.join(format!("{}_converted.pdf", pdf_name))
+    /// Builder pattern: set the color policy (figures-only keeps just figures in color)
+    pub fn with_color_policy(mut self, policy: ColorPolicy) -> Self {
+        self.color_policy = policy;
+        self
     }
 
-    /// Get the working directory for a PDF
-    pub fn get_work_dir(&self, input: &Path, output_dir: &Path) -> PathBuf {
-        let pdf_name = input.file_stem().unwrap_or_default().to_string_lossy();
-        output_dir.join(format!(".work_{}", pdf_name))
+    /// Builder pattern: set the sizing of pages in the minority orientation
+    pub fn with_mixed_size(mut self, mixed_size: MixedSize) -> Self {
+        self.mixed_size = mixed_size;
+        self
+    }
+
+    /// Builder pattern: write the OCR text next to the output
+    pub fn with_text_sidecar(mut self, enabled: bool) -> Self {
+        self.write_text_sidecar = enabled;
+        self
+    }
+
+    /// Builder pattern: join words hyphenated at line ends in the text sidecar
+    pub fn with_dehyphenate(mut self, enabled: bool) -> Self {
+        self.dehyphenate = enabled;
+        self
+    }
+
+    /// Builder pattern: limit OCR to source pages (1-based)
+    pub fn with_ocr_pages(mut self, pages: Option<crate::PageRanges>) -> Self {
+        self.ocr_pages = pages;
+        self
+    }
+
+    /// Builder pattern: skip OCR on picture pages
+    pub fn with_ocr_skip_pictures(mut self, enabled: bool) -> Self {
+        self.ocr_skip_pictures = enabled;
+        self
+    }
+
+    /// Builder pattern: set OCR confidence threshold (percent, clamped to 0-100)
+    pub fn with_ocr_min_confidence(mut self, percent: f32) -> Self {
+        self.ocr_min_confidence = percent.clamp(0.0, 100.0);
+        self
+    }
+
+    /// Builder pattern: set hook run on the finished PDF
+    pub fn with_post_hook(mut self, command: Option<String>) -> Self {
+        self.post_hook = command;
+        self
+    }
+
+    /// Builder pattern: set hook run on each final page image
+    pub fn with_page_hook(mut self, command: Option<String>) -> Self {
+        self.page_hook = command;
+        self
+    }
+
+    /// Builder pattern: enable per-page SSIM/PSNR reporting
+    pub fn with_quality_metrics(mut self, enabled: bool) -> Self {
+        self.quality_metrics = enabled;
+        self
+    }
+
+    /// Builder pattern: set the location of an external tool (e.g. "pdftoppm")
+    pub fn with_tool_path(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.tool_paths.insert(name.into(), path.into());
+        self
+    }
+
+    /// Builder pattern: set the niceness of spawned CPU tools
+    pub fn with_nice(mut self, niceness: i32) -> Self {
+        self.nice = niceness.clamp(*crate::tools::NICENESS_RANGE.start(), *crate::tools::NICENESS_RANGE.end());
+        self
+    }
+
+    /// Builder pattern: set the external tool call timeout in seconds (None or 0 = no timeout)
+    pub fn with_tool_timeout_secs(mut self, secs: Option<u64>) -> Self {
+        self.tool_timeout_secs = secs.filter(|&secs| secs > 0);
+        self
+    }
+
+    /// Builder pattern: set the silence before a stalled-step warning in seconds (None or 0 = no warning)
+    pub fn with_stall_warning_secs(mut self, secs: Option<u64>) -> Self {
+        self.stall_warning_secs = secs.filter(|&secs| secs > 0);
+        self
+    }
+
+    /// Builder pattern: set how many Tesseract calls run at once (None or 0 = one per pool thread)
+    pub fn with_ocr_concurrency(mut self, limit: Option<usize>) -> Self {
+        self.ocr_concurrency = limit.filter(|&limit| limit > 0);
+        self
+    }
+
+    /// Builder pattern: serialize GPU stages across concurrent pipelines
+    pub fn with_gpu_wait(mut self, enabled: bool) -> Self {
+        self.gpu_wait = enabled;
+        self
+    }
+
+    /// Builder pattern: reserve a share of the thread budget for external tools (0 = no budget)
+    pub fn with_external_thread_fraction(mut self, fraction: f32) -> Self {
+        self.external_thread_fraction = if fraction.is_nan() { 0.0 } else { fraction.clamp(0.0, 1.0) };
+        self
+    }
+
+    /// Split of `threads` between the rayon pool and external tools
+    ///
+    /// None when `external_thread_fraction` is 0.
+    pub fn thread_budget(&self) -> Option<crate::ThreadBudget> {
+        (self.external_thread_fraction > 0.0)
+            .then(|| crate::ThreadBudget::split(self.threads.unwrap_or(0), self.external_thread_fraction))
+    }
+
+    /// External tool settings (`nice`, `tool_timeout_secs`, thread budget, `ocr_concurrency`) for one pipeline
+    pub fn tool_context(&self) -> crate::tools::ToolContext {
+        crate::tools::ToolContext::default()
+            .with_niceness(self.nice)
+            .with_timeout(self.tool_timeout_secs.map(Duration::from_secs))
+            .with_thread_budget(self.thread_budget().map(|budget| budget.external))
+            .with_ocr_concurrency(self.ocr_concurrency)
+    }
+
+    /// Builder pattern: set group crop outlier parameters
+    pub fn with_group_crop(mut self, params: crate::GroupCropParams) -> Self {
+        self.group_crop = params;
+        self
+    }
+
+    /// Builder pattern: align odd and even pages horizontally
+    pub fn with_register_sides(mut self, enabled: bool) -> Self {
+        self.register_sides = enabled;
+        self
+    }
+
+    /// Builder pattern: write PDF page labels from logical page numbers
+    pub fn with_page_labels(mut self, enabled: bool) -> Self {
+        self.page_labels = enabled;
+        self
+    }
+
+    /// Builder pattern: set page binding direction
+    pub fn with_binding(mut self, binding: crate::Binding) -> Self {
+        self.binding = binding;
+        self
+    }
+
+    /// Builder pattern: force vertical or horizontal text instead of detecting it
+    pub fn with_text_direction(mut self, direction: crate::markdown::TextDirectionOption) -> Self {
+        self.text_direction = direction;
+        self
+    }
+
+    /// Builder pattern: set the color transparent pixels are composited over
+    pub fn with_flatten_background(mut self, rgb: [u8; 3]) -> Self {
+        self.flatten_background = rgb;
+        self
+    }
+
+    /// Builder pattern: set resize filter
+    pub fn with_resize_filter(mut self, filter: crate::Resampler) -> Self {
+        self.resize_filter = filter;
+        self
+    }
+
+    /// Builder pattern: set bilevel page encoding
+    pub fn with_image_encoding(mut self, encoding: crate::ImageEncoding) -> Self {
+        self.image_encoding = encoding;
+        self
+    }
+
+    /// Builder pattern: reuse an existing text layer instead of OCR
+    pub fn with_skip_existing_ocr(mut self, enabled: bool) -> Self {
+        self.skip_existing_ocr = enabled;
+        self
+    }
+
+    /// Builder pattern: remove scanner dust/hair lines before other stages
+    pub fn with_remove_line_artifacts(mut self, enabled: bool) -> Self {
+        self.remove_line_artifacts = enabled;
+        self
+    }
+
+    /// Builder pattern: remove highlighter marks
+    pub fn with_remove_markers(mut self, enabled: bool) -> Self {
+        self.remove_markers = enabled;
+        self
+    }
+
+    /// Builder pattern: highlighter colors to remove by name (empty = all built-in colors)
+    pub fn with_marker_colors(mut self, colors: Vec<String>) -> Self {
+        self.marker_colors = colors;
+        self
+    }
+
+    /// Builder pattern: set the page area markers are removed from
+    pub fn with_marker_region(mut self, area: crate::cleanup::MarkerArea) -> Self {
+        self.marker_region = area;
+        self
+    }
+
+    /// Builder pattern: named highlighter colors usable in `marker_colors`
+    pub fn with_highlighters(mut self, highlighters: Vec<crate::cleanup::CustomHighlighter>) -> Self {
+        self.highlighters = highlighters;
+        self
+    }
+
+    /// Marker removal options resolved from `marker_colors` (None when disabled)
+    ///
+    /// Fails on a color name that is neither built-in nor in `highlighters`.
+    pub fn marker_removal_options(&self) -> Result<Option<crate::cleanup::MarkerRemovalOptions>, crate::cleanup::CleanupError> {
+        if !self.remove_markers {
+            return Ok(None);
+        }
+        let mut options = crate::cleanup::MarkerRemovalOptions::from_names(&self.marker_colors, &self.highlighters)?;
+        options.region = self.marker_region.into();
+        Ok(Some(options))
+    }
+
+    /// Builder pattern: desaturate colors below a chroma threshold (None = off)
+    pub fn with_desaturate_below(mut self, threshold: Option<f32>) -> Self {
+        self.desaturate_below = threshold.map(|s| s.clamp(0.0, 1.0)).filter(|&s| s > 0.0);
+        self
+    }
+
+    /// Builder pattern: fit pages to a `width:height` aspect ratio (None = keep)
+    pub fn with_target_aspect(mut self, aspect: Option<(u32, u32)>) -> Self {
+        self.target_aspect = aspect.filter(|&(w, h)| w > 0 && h > 0);
+        self
+    }
+
+    /// Builder pattern: write PDF/A output (None = plain PDF)
+    pub fn with_pdfa(mut self, level: Option<crate::PdfaLevel>) -> Self {
+        self.pdfa = level;
+        self
+    }
+
+    /// Builder pattern: validate PDF/A output after writing
+    pub fn with_pdfa_validate(mut self, enabled: bool) -> Self {
+        self.pdfa_validate = enabled;
+        self
+    }
+
+    /// Builder pattern: set the output container
+    pub fn with_output_format(mut self, format: crate::BookFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Builder pattern: set running header/footer handling
+    pub fn with_running_heads(mut self, mode: crate::markdown::RunningHeadMode) -> Self {
+        self.running_heads = mode;
+        self
+    }
+
+    /// Builder pattern: process only odd or even pages
+    pub fn with_parity(mut self, parity: crate::PageParity) -> Self {
+        self.parity = parity;
+        self
+    }
+
+    /// Builder pattern: set negative scan inversion
+    pub fn with_invert(mut self, invert: crate::Invert) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Builder pattern: set the handling of failed pages
+    pub fn with_on_page_error(mut self, policy: PageErrorPolicy) -> Self {
+        self.on_page_error = policy;
+        self
+    }
+
+    /// Builder pattern: set the minimum effective output DPI (0 = no check)
+    pub fn with_min_output_dpi(mut self, dpi: u32) -> Self {
+        self.min_output_dpi = dpi;
+        self
+    }
+
+    /// Builder pattern: set the handling of books below the minimum output DPI
+    pub fn with_on_low_dpi(mut self, policy: LowDpiPolicy) -> Self {
+        self.on_low_dpi = policy;
+        self
+    }
+
+    /// Builder pattern: set the per-page pixel budget in megapixels (0 = no limit)
+    pub fn with_max_page_megapixels(mut self, megapixels: u32) -> Self {
+        self.max_page_megapixels = megapixels;
+        self
+    }
+
+    /// Builder pattern: set the cap on the longest extracted page edge in pixels (0 = no cap)
+    pub fn with_max_page_dimension(mut self, pixels: u32) -> Self {
+        self.max_page_dimension = pixels;
+        self
+    }
+
+    /// Builder pattern: keep the source PDF's creator, producer and dates
+    pub fn with_preserve_source_metadata(mut self, enabled: bool) -> Self {
+        self.preserve_source_metadata = enabled;
+        self
+    }
+
+    /// Builder pattern: copy the source PDF's outline into the output
+    pub fn with_preserve_source_outline(mut self, enabled: bool) -> Self {
+        self.preserve_source_outline = enabled;
+        self
+    }
+
+    /// Builder pattern: set the work cache directory (`None` = off)
+    pub fn with_work_cache(mut self, dir: Option<PathBuf>) -> Self {
+        self.work_cache = dir;
+        self
+    }
+
+    /// Builder pattern: set the work cache size limit in MB
+    pub fn with_work_cache_max_mb(mut self, max_mb: u64) -> Self {
+        self.work_cache_max_mb = max_mb;
+        self
+    }
+
+    /// Builder pattern: continue an interrupted run from its progress file
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// Builder pattern: set output PDF metadata
+    pub fn with_metadata(mut self, metadata: crate::PdfMetadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Enable all advanced features
+    pub fn with_advanced(mut self) -> Self {
+        self.internal_resolution = true;
+        self.color_correction = true;
+        self.offset_alignment = true;
+        self
+    }
+}
+
+/// Builder for [`PipelineConfig`]
+///
+/// Setters store values as given; [`PipelineConfigBuilder::build`] runs
+/// [`PipelineConfig::validate`] instead of clamping.
+#[derive(Debug, Default)]
+pub struct PipelineConfigBuilder {
+    config: PipelineConfig,
+}
+
+impl PipelineConfigBuilder {
+    /// Set output DPI (1-4800)
+    #[must_use]
+    pub fn dpi(mut self, value: u32) -> Self {
+        self.config.dpi = value;
+        self
+    }
+
+    /// Enable deskew
+    #[must_use]
+    pub fn deskew(mut self, value: bool) -> Self {
+        self.config.deskew = value;
+        self
+    }
+
+    /// Set margin trim percentage (0-50)
+    #[must_use]
+    pub fn margin_trim(mut self, value: f64) -> Self {
+        self.config.margin_trim = value;
+        self
+    }
+
+    /// Enable AI upscaling
+    #[must_use]
+    pub fn upscale(mut self, value: bool) -> Self {
+        self.config.upscale = value;
+        self
+    }
+
+    /// Enable GPU
+    #[must_use]
+    pub fn gpu(mut self, value: bool) -> Self {
+        self.config.gpu = value;
+        self
+    }
+
+    /// Enable internal resolution normalization
+    #[must_use]
+    pub fn internal_resolution(mut self, value: bool) -> Self {
+        self.config.internal_resolution = value;
+        self
+    }
+
+    /// Enable color correction
+    #[must_use]
+    pub fn color_correction(mut self, value: bool) -> Self {
+        self.config.color_correction = value;
+        self
+    }
+
+    /// Enable page number offset alignment
+    #[must_use]
+    pub fn offset_alignment(mut self, value: bool) -> Self {
+        self.config.offset_alignment = value;
+        self
+    }
+
+    /// Set output height in pixels (greater than 0)
+    #[must_use]
+    pub fn output_height(mut self, value: u32) -> Self {
+        self.config.output_height = value;
+        self
+    }
+
+    /// Enable OCR
+    #[must_use]
+    pub fn ocr(mut self, value: bool) -> Self {
+        self.config.ocr = value;
+        self
+    }
+
+    /// Process at most this many pages
+    #[must_use]
+    pub fn max_pages(mut self, value: usize) -> Self {
+        self.config.max_pages = Some(value);
+        self
+    }
+
+    /// Save debug images
+    #[must_use]
+    pub fn save_debug(mut self, value: bool) -> Self {
+        self.config.save_debug = value;
+        self
+    }
+
+    /// Set JPEG quality (1-100)
+    #[must_use]
+    pub fn jpeg_quality(mut self, value: u8) -> Self {
+        self.config.jpeg_quality = value;
+        self
+    }
+
+    /// Set thread count (greater than 0; default: CPU count)
+    #[must_use]
+    pub fn threads(mut self, value: usize) -> Self {
+        self.config.threads = Some(value);
+        self
+    }
+
+    /// Set maximum memory usage in MB (0 = unlimited)
+    #[must_use]
+    pub fn max_memory_mb(mut self, value: usize) -> Self {
+        self.config.max_memory_mb = value;
+        self
+    }
+
+    /// Set chunk size for batch processing (0 = auto)
+    #[must_use]
+    pub fn chunk_size(mut self, value: usize) -> Self {
+        self.config.chunk_size = value;
+        self
+    }
+
+    /// Set streaming extraction queue depth (0 = disabled)
+    #[must_use]
+    pub fn extract_queue_depth(mut self, value: usize) -> Self {
+        self.config.extract_queue_depth = value;
+        self
+    }
+
+    /// Set pages rendered per pdftoppm process (greater than 0)
+    #[must_use]
+    pub fn extract_batch_pages(mut self, value: usize) -> Self {
+        self.config.extract_batch_pages = value;
+        self
+    }
+
+    /// Set auto contrast mode
+    #[must_use]
+    pub fn auto_contrast(mut self, value: crate::AutoContrast) -> Self {
+        self.config.auto_contrast = value;
+        self
+    }
+
+    /// Apply auto contrast per R/G/B channel
+    #[must_use]
+    pub fn auto_contrast_per_channel(mut self, value: bool) -> Self {
+        self.config.auto_contrast_per_channel = value;
+        self
+    }
+
+    /// Set manual tone curve
+    #[must_use]
+    pub fn tone_curve(mut self, value: crate::ToneCurve) -> Self {
+        self.config.tone_curve = Some(value);
+        self
+    }
+
+    /// Set extra OCR layout exports
+    #[must_use]
+    pub fn ocr_formats(mut self, value: Vec<crate::markdown::OcrExportFormat>) -> Self {
+        self.config.ocr_formats = value;
+        self
+    }
+
+    /// Enable reproducible output
+    #[must_use]
+    pub fn deterministic(mut self, value: bool) -> Self {
+        self.config.deterministic = value;
+        self
+    }
+
+    /// Set output PDF metadata
+    #[must_use]
+    pub fn metadata(mut self, value: crate::PdfMetadata) -> Self {
+        self.config.metadata = value;
+        self
+    }
+
+    /// Fill an empty Subject from the OCR text
+    #[must_use]
+    pub fn metadata_from_ocr(mut self, value: bool) -> Self {
+        self.config.metadata_from_ocr = value;
+        self
+    }
+
+    /// Write the OCR text to `<output>.txt`
+    #[must_use]
+    pub fn write_text_sidecar(mut self, value: bool) -> Self {
+        self.config.write_text_sidecar = value;
+        self
+    }
+
+    /// Join words hyphenated at line ends in the text sidecar
+    #[must_use]
+    pub fn dehyphenate(mut self, value: bool) -> Self {
+        self.config.dehyphenate = value;
+        self
+    }
+
+    /// OCR only these source pages (1-based)
+    #[must_use]
+    pub fn ocr_pages(mut self, value: Option<crate::PageRanges>) -> Self {
+        self.config.ocr_pages = value;
+        self
+    }
+
+    /// Skip OCR on pages that are predominantly pictures
+    #[must_use]
+    pub fn ocr_skip_pictures(mut self, value: bool) -> Self {
+        self.config.ocr_skip_pictures = value;
+        self
+    }
+
+    /// Skip OCR-dependent stages when their tools are missing
+    #[must_use]
+    pub fn ocr_optional(mut self, value: bool) -> Self {
+        self.config.ocr_optional = value;
+        self
+    }
+
+    /// Write colorless pages as grayscale
+    #[must_use]
+    pub fn mixed_color(mut self, value: bool) -> Self {
+        self.config.mixed_color = value;
+        self
+    }
+
+    /// Set the color policy
+    #[must_use]
+    pub fn color_policy(mut self, value: ColorPolicy) -> Self {
+        self.config.color_policy = value;
+        self
+    }
+
+    /// Set the sizing of pages in the minority orientation
+    #[must_use]
+    pub fn mixed_size(mut self, value: MixedSize) -> Self {
+        self.config.mixed_size = value;
+        self
+    }
+
+    /// Set OCR confidence threshold (percent, 0-100)
+    #[must_use]
+    pub fn ocr_min_confidence(mut self, value: f32) -> Self {
+        self.config.ocr_min_confidence = value;
+        self
+    }
+
+    /// Set hook run on the finished PDF
+    #[must_use]
+    pub fn post_hook(mut self, value: impl Into<String>) -> Self {
+        self.config.post_hook = Some(value.into());
+        self
+    }
+
+    /// Set hook run on each final page image
+    #[must_use]
+    pub fn page_hook(mut self, value: impl Into<String>) -> Self {
+        self.config.page_hook = Some(value.into());
+        self
+    }
+
+    /// Enable per-page SSIM/PSNR reporting
+    #[must_use]
+    pub fn quality_metrics(mut self, value: bool) -> Self {
+        self.config.quality_metrics = value;
+        self
+    }
+
+    /// Set page binding direction
+    #[must_use]
+    pub fn binding(mut self, value: crate::Binding) -> Self {
+        self.config.binding = value;
+        self
+    }
+
+    /// Force vertical or horizontal text instead of detecting it
+    #[must_use]
+    pub fn text_direction(mut self, value: crate::markdown::TextDirectionOption) -> Self {
+        self.config.text_direction = value;
+        self
+    }
+
+    /// Set the color transparent pixels are composited over
+    #[must_use]
+    pub fn flatten_background(mut self, rgb: [u8; 3]) -> Self {
+        self.config.flatten_background = rgb;
+        self
+    }
+
+    /// Set resize filter
+    #[must_use]
+    pub fn resize_filter(mut self, value: crate::Resampler) -> Self {
+        self.config.resize_filter = value;
+        self
+    }
+
+    /// Set bilevel page encoding
+    #[must_use]
+    pub fn image_encoding(mut self, value: crate::ImageEncoding) -> Self {
+        self.config.image_encoding = value;
+        self
+    }
+
+    /// Reuse an existing text layer instead of OCR
+    #[must_use]
+    pub fn skip_existing_ocr(mut self, value: bool) -> Self {
+        self.config.skip_existing_ocr = value;
+        self
+    }
+
+    /// Remove scanner dust/hair lines
+    #[must_use]
+    pub fn remove_line_artifacts(mut self, value: bool) -> Self {
+        self.config.remove_line_artifacts = value;
+        self
+    }
+
+    /// Remove highlighter marks
+    #[must_use]
+    pub fn remove_markers(mut self, value: bool) -> Self {
+        self.config.remove_markers = value;
+        self
+    }
+
+    /// Set highlighter colors to remove by name
+    #[must_use]
+    pub fn marker_colors(mut self, value: Vec<String>) -> Self {
+        self.config.marker_colors = value;
+        self
+    }
+
+    /// Set the page area markers are removed from
+    #[must_use]
+    pub fn marker_region(mut self, value: crate::cleanup::MarkerArea) -> Self {
+        self.config.marker_region = value;
+        self
+    }
+
+    /// Set named highlighter colors usable in `marker_colors`
+    #[must_use]
+    pub fn highlighters(mut self, value: Vec<crate::cleanup::CustomHighlighter>) -> Self {
+        self.config.highlighters = value;
+        self
+    }
+
+    /// Gray out colors below this chroma (0.0-1.0)
+    #[must_use]
+    pub fn desaturate_below(mut self, value: f32) -> Self {
+        self.config.desaturate_below = Some(value);
+        self
+    }
+
+    /// Fit pages to a width:height ratio
+    #[must_use]
+    pub fn target_aspect(mut self, value: (u32, u32)) -> Self {
+        self.config.target_aspect = Some(value);
+        self
+    }
+
+    /// Write PDF/A output at this level
+    #[must_use]
+    pub fn pdfa(mut self, value: crate::PdfaLevel) -> Self {
+        self.config.pdfa = Some(value);
+        self
+    }
+
+    /// Check PDF/A output with veraPDF (or Ghostscript)
+    #[must_use]
+    pub fn pdfa_validate(mut self, value: bool) -> Self {
+        self.config.pdfa_validate = value;
+        self
+    }
+
+    /// Set output container
+    #[must_use]
+    pub fn output_format(mut self, value: crate::BookFormat) -> Self {
+        self.config.output_format = value;
+        self
+    }
+
+    /// Write PDF page labels from logical page numbers
+    #[must_use]
+    pub fn page_labels(mut self, value: bool) -> Self {
+        self.config.page_labels = value;
+        self
+    }
+
+    /// Set running header/footer handling
+    #[must_use]
+    pub fn running_heads(mut self, value: crate::markdown::RunningHeadMode) -> Self {
+        self.config.running_heads = value;
+        self
+    }
+
+    /// Process only odd or even pages
+    #[must_use]
+    pub fn parity(mut self, value: crate::PageParity) -> Self {
+        self.config.parity = value;
+        self
+    }
+
+    /// Set negative scan inversion
+    #[must_use]
+    pub fn invert(mut self, value: crate::Invert) -> Self {
+        self.config.invert = value;
+        self
+    }
+
+    /// Set the handling of failed pages
+    #[must_use]
+    pub fn on_page_error(mut self, value: PageErrorPolicy) -> Self {
+        self.config.on_page_error = value;
+        self
+    }
+
+    /// Set the minimum effective output DPI (0 = no check)
+    #[must_use]
+    pub fn min_output_dpi(mut self, value: u32) -> Self {
+        self.config.min_output_dpi = value;
+        self
+    }
+
+    /// Set the handling of books below the minimum output DPI
+    #[must_use]
+    pub fn on_low_dpi(mut self, value: LowDpiPolicy) -> Self {
+        self.config.on_low_dpi = value;
+        self
+    }
+
+    /// Set the per-page pixel budget in megapixels (0 = no limit)
+    #[must_use]
+    pub fn max_page_megapixels(mut self, value: u32) -> Self {
+        self.config.max_page_megapixels = value;
+        self
+    }
+
+    /// Set the cap on the longest extracted page edge in pixels (0 = no cap)
+    #[must_use]
+    pub fn max_page_dimension(mut self, value: u32) -> Self {
+        self.config.max_page_dimension = value;
+        self
+    }
+
+    /// Keep the source PDF's creator, producer and dates
+    #[must_use]
+    pub fn preserve_source_metadata(mut self, value: bool) -> Self {
+        self.config.preserve_source_metadata = value;
+        self
+    }
+
+    /// Copy the source PDF's outline into the output
+    #[must_use]
+    pub fn preserve_source_outline(mut self, value: bool) -> Self {
+        self.config.preserve_source_outline = value;
+        self
+    }
+
+    /// Set the work cache directory (`None` = off)
+    #[must_use]
+    pub fn work_cache(mut self, value: Option<PathBuf>) -> Self {
+        self.config.work_cache = value;
+        self
+    }
+
+    /// Set the work cache size limit in MB
+    #[must_use]
+    pub fn work_cache_max_mb(mut self, value: u64) -> Self {
+        self.config.work_cache_max_mb = value;
+        self
+    }
+
+    /// Continue an interrupted run from its progress file
+    #[must_use]
+    pub fn resume(mut self, value: bool) -> Self {
+        self.config.resume = value;
+        self
+    }
+
+    /// Set group crop outlier parameters
+    #[must_use]
+    pub fn group_crop(mut self, value: crate::GroupCropParams) -> Self {
+        self.config.group_crop = value;
+        self
+    }
+
+    /// Align odd and even pages horizontally
+    #[must_use]
+    pub fn register_sides(mut self, value: bool) -> Self {
+        self.config.register_sides = value;
+        self
+    }
+
+    /// Set niceness of spawned CPU tools (-20 to 19)
+    #[must_use]
+    pub fn nice(mut self, value: i32) -> Self {
+        self.config.nice = value;
+        self
+    }
+
+    /// Set external tool call timeout in seconds (greater than 0)
+    #[must_use]
+    pub fn tool_timeout_secs(mut self, value: u64) -> Self {
+        self.config.tool_timeout_secs = Some(value);
+        self
+    }
+
+    /// Set the silence before a stalled-step warning in seconds (0 = no warning)
+    #[must_use]
+    pub fn stall_warning_secs(mut self, value: u64) -> Self {
+        self.config.stall_warning_secs = Some(value).filter(|&secs| secs > 0);
+        self
+    }
+
+    /// Set how many Tesseract calls run at once (greater than 0)
+    #[must_use]
+    pub fn ocr_concurrency(mut self, value: usize) -> Self {
+        self.config.ocr_concurrency = Some(value);
+        self
+    }
+
+    /// Serialize GPU stages across concurrent pipelines
+    #[must_use]
+    pub fn gpu_wait(mut self, value: bool) -> Self {
+        self.config.gpu_wait = value;
+        self
+    }
+
+    /// Reserve a share (0.0-1.0) of the threads for external tools
+    #[must_use]
+    pub fn external_thread_fraction(mut self, value: f32) -> Self {
+        self.config.external_thread_fraction = value;
+        self
+    }
+
+    /// Set the location of an external tool (e.g. "pdftoppm")
+    #[must_use]
+    pub fn tool_path(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.config.tool_paths.insert(name.into(), path.into());
+        self
+    }
+
+    /// Enable all advanced features
+    #[must_use]
+    pub fn advanced(mut self) -> Self {
+        self.config = self.config.with_advanced();
+        self
+    }
+
+    /// Validate and build the configuration
+    pub fn build(self) -> Result<PipelineConfig, PipelineError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Result of pipeline processing
+#[derive(Debug, Clone)]
+pub struct PipelineResult {
+    /// Number of pages processed
+    pub page_count: usize,
+    /// Detected page number shift
+    pub page_number_shift: Option<i32>,
+    /// Whether vertical text was detected
+    pub is_vertical: bool,
+    /// Processing time in seconds
+    pub elapsed_seconds: f64,
+    /// Output file path
+    pub output_path: PathBuf,
+    /// Output file size in bytes
+    pub output_size: u64,
+    /// Elapsed time per stage
+    pub stage_timings: StageTimings,
+    /// OCR-dependent stages skipped because their tool was missing
+    pub skipped_stages: Vec<SkippedStage>,
+    /// Per-page output color mode (empty unless `mixed_color`)
+    pub page_color_modes: Vec<crate::PageColorMode>,
+    /// Per-page mean OCR confidence (0.0-1.0, None = no OCR result; empty without OCR)
+    pub ocr_confidence: Vec<Option<f32>>,
+    /// Per-page SSIM/PSNR against the extracted page (empty unless `quality_metrics`)
+    pub page_quality: Vec<crate::quality::PageQuality>,
+    /// Pages whose image reuses an identical earlier page in the PDF
+    pub deduplicated_pages: usize,
+    /// Compression of bilevel pages (empty unless `image_encoding` is set)
+    pub bilevel: crate::BilevelStats,
+    /// OCR was replaced by the source PDF's text layer (`skip_existing_ocr`)
+    pub text_layer_reused: bool,
+    /// Per-page aspect ratio adjustment (empty unless `target_aspect` is set)
+    pub aspect_adjustments: Vec<crate::AspectAdjustment>,
+    /// Tone curve LUT applied to every page (empty unless `tone_curve` is set)
+    pub tone_curve_lut: Vec<u8>,
+    /// Pages (0-based) that failed because a tool call timed out; left out
+    /// of the output or replaced by placeholders per `on_page_error`
+    pub failed_pages: Vec<usize>,
+    /// Running headers/footers removed from the output (empty unless `running_heads` is set)
+    pub running_heads: Vec<crate::markdown::RunningHeadTemplate>,
+    /// Output pages (0-based) inverted as negative scans (empty unless `invert` is set)
+    pub inverted_pages: Vec<usize>,
+    /// Output pages (0-based) with highlighter marks removed (empty unless `remove_markers` is set)
+    pub marked_pages: Vec<usize>,
+    /// Output pages (0-based) left out of OCR by `ocr_pages` / `ocr_skip_pictures`
+    pub ocr_skipped_pages: Vec<usize>,
+    /// Confidence of the page number offset analysis (0.0-1.0, `None` if not run
+    /// or no page numbers were found)
+    pub page_number_confidence: Option<f64>,
+    /// Median final image pixels per inch of the physical page (`None`
+    /// without a physical page size, e.g. comic archives)
+    pub effective_dpi: Option<f64>,
+    /// Written with the quick (draft) preset
+    pub draft: bool,
+    /// Work cache hits and misses (all zero without `work_cache`)
+    pub work_cache: crate::WorkCacheStats,
+    /// Sizing of the minority-orientation pages (empty with uniform `mixed_size`)
+    pub mixed_size_pages: Vec<MixedSizeDecision>,
+    /// Plain text file written next to the output (`write_text_sidecar`)
+    pub text_sidecar: Option<PathBuf>,
+    /// Pages extracted below `dpi` to fit `max_page_dimension`
+    pub clamped_pages: Vec<ClampedPage>,
+    /// Pages taken from an interrupted run's progress file (`resume`)
+    pub resumed_pages: usize,
+}
+
+impl PipelineResult {
+    /// Create a new pipeline result
+    pub fn new(
+        page_count: usize,
+        page_number_shift: Option<i32>,
+        is_vertical: bool,
+        elapsed_seconds: f64,
+        output_path: PathBuf,
+        output_size: u64,
+    ) -> Self {
+        Self {
+            page_count,
+            page_number_shift,
+            is_vertical,
+            elapsed_seconds,
+            output_path,
+            output_size,
+            stage_timings: StageTimings::new(),
+            skipped_stages: Vec::new(),
+            page_color_modes: Vec::new(),
+            ocr_confidence: Vec::new(),
+            page_quality: Vec::new(),
+            deduplicated_pages: 0,
+            bilevel: crate::BilevelStats::default(),
+            text_layer_reused: false,
+            aspect_adjustments: Vec::new(),
+            tone_curve_lut: Vec::new(),
+            failed_pages: Vec::new(),
+            running_heads: Vec::new(),
+            inverted_pages: Vec::new(),
+            marked_pages: Vec::new(),
+            ocr_skipped_pages: Vec::new(),
+            page_number_confidence: None,
+            effective_dpi: None,
+            draft: false,
+            work_cache: crate::WorkCacheStats::default(),
+            mixed_size_pages: Vec::new(),
+            text_sidecar: None,
+            clamped_pages: Vec::new(),
+            resumed_pages: 0,
+        }
+    }
+
+    /// Builder pattern: set per-stage timings
+    pub fn with_stage_timings(mut self, timings: StageTimings) -> Self {
+        self.stage_timings = timings;
+        self
+    }
+
+    /// Builder pattern: set skipped stages
+    pub fn with_skipped_stages(mut self, stages: Vec<SkippedStage>) -> Self {
+        self.skipped_stages = stages;
+        self
+    }
+
+    /// Builder pattern: set per-page color modes
+    pub fn with_page_color_modes(mut self, modes: Vec<crate::PageColorMode>) -> Self {
+        self.page_color_modes = modes;
+        self
+    }
+
+    /// Builder pattern: set per-page OCR confidence
+    pub fn with_ocr_confidence(mut self, confidence: Vec<Option<f32>>) -> Self {
+        self.ocr_confidence = confidence;
+        self
+    }
+
+    /// Builder pattern: set per-page quality metrics
+    pub fn with_page_quality(mut self, quality: Vec<crate::quality::PageQuality>) -> Self {
+        self.page_quality = quality;
+        self
+    }
+
+    /// Builder pattern: set number of deduplicated pages
+    pub fn with_deduplicated_pages(mut self, count: usize) -> Self {
+        self.deduplicated_pages = count;
+        self
+    }
+
+    /// Builder pattern: mark OCR as replaced by the source text layer
+    pub fn with_text_layer_reused(mut self, reused: bool) -> Self {
+        self.text_layer_reused = reused;
+        self
+    }
+
+    /// Builder pattern: set per-page aspect ratio adjustments
+    pub fn with_aspect_adjustments(mut self, adjustments: Vec<crate::AspectAdjustment>) -> Self {
+        self.aspect_adjustments = adjustments;
+        self
+    }
+
+    /// Builder pattern: set the applied tone curve LUT
+    pub fn with_tone_curve_lut(mut self, lut: Vec<u8>) -> Self {
+        self.tone_curve_lut = lut;
+        self
+    }
+
+    /// Builder pattern: set pages skipped after a tool timeout
+    pub fn with_failed_pages(mut self, pages: Vec<usize>) -> Self {
+        self.failed_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set the detected running headers/footers
+    pub fn with_running_heads(mut self, templates: Vec<crate::markdown::RunningHeadTemplate>) -> Self {
+        self.running_heads = templates;
+        self
+    }
+
+    /// Builder pattern: set pages inverted as negative scans
+    pub fn with_inverted_pages(mut self, pages: Vec<usize>) -> Self {
+        self.inverted_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set pages with highlighter marks removed
+    pub fn with_marked_pages(mut self, pages: Vec<usize>) -> Self {
+        self.marked_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set pages left out of OCR
+    pub fn with_ocr_skipped_pages(mut self, pages: Vec<usize>) -> Self {
+        self.ocr_skipped_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set the page number analysis confidence
+    pub fn with_page_number_confidence(mut self, confidence: Option<f64>) -> Self {
+        self.page_number_confidence = confidence;
+        self
+    }
+
+    /// Builder pattern: set the effective output DPI
+    pub fn with_effective_dpi(mut self, dpi: Option<f64>) -> Self {
+        self.effective_dpi = dpi;
+        self
+    }
+
+    /// Builder pattern: mark the output as a quick (draft) conversion
+    pub fn with_draft(mut self, draft: bool) -> Self {
+        self.draft = draft;
+        self
+    }
+
+    /// Builder pattern: set the written text sidecar
+    pub fn with_text_sidecar(mut self, path: Option<PathBuf>) -> Self {
+        self.text_sidecar = path;
+        self
+    }
+
+    /// Builder pattern: set pages extracted below the configured DPI
+    pub fn with_clamped_pages(mut self, pages: Vec<ClampedPage>) -> Self {
+        self.clamped_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set work cache statistics
+    pub fn with_work_cache(mut self, stats: crate::WorkCacheStats) -> Self {
+        self.work_cache = stats;
+        self
+    }
+
+    /// Builder pattern: set the number of pages resumed from a progress file
+    pub fn with_resumed_pages(mut self, pages: usize) -> Self {
+        self.resumed_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set the minority-orientation page decisions
+    pub fn with_mixed_size_pages(mut self, pages: Vec<MixedSizeDecision>) -> Self {
+        self.mixed_size_pages = pages;
+        self
+    }
+
+    /// Builder pattern: set PDF writer statistics (deduplication, bilevel compression)
+    pub fn with_write_stats(mut self, stats: crate::PdfWriteStats) -> Self {
+        self.deduplicated_pages = stats.deduplicated_pages;
+        self.bilevel = stats.bilevel;
+        self
+    }
+
+    /// 1-based pages whose OCR confidence is below `min_percent` (0-100)
+    pub fn low_confidence_pages(&self, min_percent: f32) -> Vec<usize> {
+        crate::cache::low_confidence_pages(&self.ocr_confidence, min_percent)
+    }
+
+    /// Aggregate quality metrics (None unless `quality_metrics` was enabled)
+    pub fn quality_summary(&self) -> Option<crate::quality::QualitySummary> {
+        crate::quality::QualitySummary::from_pages(&self.page_quality, crate::quality::DEFAULT_MIN_SSIM)
+    }
+
+    /// 1-based numbers of pages kept in color
+    pub fn color_pages(&self) -> Vec<usize> {
+        self.page_color_modes
+            .iter()
+            .enumerate()
+            .filter(|(_, mode)| **mode == crate::PageColorMode::Color)
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+
+    /// Convert to cache ProcessingResult
+    pub fn to_cache_result(&self) -> crate::cache::ProcessingResult {
+        crate::cache::ProcessingResult::new(
+            self.page_count,
+            self.page_number_shift,
+            self.is_vertical,
+            self.elapsed_seconds,
+            self.output_size,
+        )
+        .with_stage_timings(self.stage_timings.clone())
+        .with_ocr_confidence(self.ocr_confidence.clone())
+        .with_page_quality(self.page_quality.clone())
+        .with_deduplicated_pages(self.deduplicated_pages)
+        .with_tone_curve_lut(self.tone_curve_lut.clone())
+        .with_failed_pages(self.failed_pages.clone())
+        .with_effective_dpi(self.effective_dpi)
+        .with_draft(self.draft)
+        .with_text_sidecar(self.text_sidecar.clone())
+        .with_clamped_pages(self.clamped_pages.clone())
+    }
+}
+
+/// Processing context for intermediate data
+#[derive(Debug)]
+pub struct ProcessingContext {
+    /// Working directory for this PDF
+    pub work_dir: PathBuf,
+    /// PDF document info
+    pub pdf_info: crate::PdfDocument,
+    /// Extracted page images
+    pub current_images: Vec<PathBuf>,
+    /// Detected vertical writing
+    pub is_vertical: bool,
+    /// Page number shift
+    pub page_number_shift: Option<i32>,
+}
+
+/// Output of the image processing stages
+#[derive(Debug, Clone, Default)]
+pub struct ProcessedImages {
+    /// Final page images
+    pub images: Vec<PathBuf>,
+    /// Detected page number shift
+    pub page_number_shift: Option<i32>,
+    /// Confidence of the page number offset analysis
+    pub page_number_confidence: Option<f64>,
+    /// Per-page aspect ratio adjustment (empty unless `target_aspect` is set)
+    pub aspect_adjustments: Vec<crate::AspectAdjustment>,
+    /// Text direction detected ahead of page number detection (`None` if not run)
+    pub is_vertical: Option<bool>,
+    /// PDF page label ranges (empty unless `page_labels` is set)
+    pub page_labels: Vec<crate::PageLabelRange>,
+    /// Pages (0-based) that failed because extraction timed out
+    pub failed_pages: Vec<usize>,
+    /// Per-page source size and geometric transforms (for the page manifest)
+    pub geometry: Vec<crate::PageGeometry>,
+    /// Work cache hits and misses of the per-page stages
+    pub work_cache: crate::WorkCacheStats,
+    /// Sizing of the minority-orientation pages (empty with uniform `mixed_size`)
+    pub mixed_size: Vec<MixedSizeDecision>,
+    /// Pages taken from an interrupted run's progress file (`resume`)
+    pub resumed_pages: usize,
+}
+
+/// Availability of the external tools behind OCR-dependent stages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OcrTools {
+    /// YomiToku importable from the AI venv (`--ocr`)
+    pub yomitoku: bool,
+    /// `tesseract` on PATH (page number detection)
+    pub tesseract: bool,
+}
+
+/// Handling of a page that fails (currently: its extraction timed out)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PageErrorPolicy {
+    /// Fail the whole file
+    Abort,
+    /// Leave the page out; later pages move up
+    #[default]
+    Skip,
+    /// Insert a "page N unavailable" image so numbering stays intact
+    Placeholder,
+}
+
+impl PageErrorPolicy {
+    /// Whether failed pages are left out (the default)
+    pub fn is_skip(&self) -> bool {
+        *self == PageErrorPolicy::Skip
+    }
+}
+
+/// Handling of a book whose effective output DPI is below `min_output_dpi`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LowDpiPolicy {
+    /// Write the output and warn
+    #[default]
+    Warn,
+    /// Fail the file before writing the output
+    Refuse,
+}
+
+/// Which parts of the output keep their color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorPolicy {
+    /// Whole pages are written in color (or grayscale with `mixed_color`)
+    #[default]
+    PerPage,
+    /// Pages are written in grayscale except for detected figures
+    FiguresOnly,
+}
+
+impl ColorPolicy {
+    /// Whether this is the default per-page policy
+    pub fn is_per_page(&self) -> bool {
+        *self == ColorPolicy::PerPage
+    }
+
+    /// Short name for reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorPolicy::PerPage => "per-page",
+            ColorPolicy::FiguresOnly => "figures-only",
+        }
+    }
+}
+
+/// Output sizing of books that mix portrait and landscape pages (e.g.
+/// text pages with fold-out maps)
+///
+/// The pages in the minority orientation are the ones handled specially;
+/// a book in a single orientation is unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MixedSize {
+    /// Every page is cropped, sized and written like the others
+    #[default]
+    Uniform,
+    /// Minority pages are group cropped on their own and fitted into the
+    /// main page size turned by 90°
+    PerOrientation,
+    /// Minority pages are not cropped and keep their size relative to the
+    /// main pages
+    Preserve,
+}
+
+impl MixedSize {
+    /// Whether this is the default uniform sizing
+    pub fn is_uniform(&self) -> bool {
+        *self == MixedSize::Uniform
+    }
+
+    /// Short name for reports
+    pub fn name(&self) -> &'static str {
+        match self {
+            MixedSize::Uniform => "uniform",
+            MixedSize::PerOrientation => "per-orientation",
+            MixedSize::Preserve => "preserve",
+        }
+    }
+}
+
+/// Sizing of a page in the minority orientation (`mixed_size` other than uniform)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MixedSizeDecision {
+    /// Output page (0-based)
+    pub page: usize,
+    /// Orientation of the page
+    pub orientation: crate::PageOrientation,
+    /// Final image size in pixels
+    pub size: (u32, u32),
+}
+
+/// Resolution of the images embedded in a source PDF, compared with the
+/// requested extraction DPI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SourceResolution {
+    /// Median image resolution over the pages (pixels per inch)
+    pub source_dpi: f64,
+    /// Extraction DPI of the config
+    pub requested_dpi: u32,
+}
+
+impl SourceResolution {
+    /// Compare the images of an opened PDF with `requested_dpi` (None when
+    /// no page has an image)
+    pub fn from_reader(reader: &crate::LopdfReader, requested_dpi: u32) -> Option<Self> {
+        let source_dpi = reader.source_image_dpi()?;
+        Some(Self {
+            source_dpi,
+            requested_dpi,
+        })
+    }
+
+    /// Open `input` and compare its images with `requested_dpi` (None for
+    /// unreadable PDFs, comic archives and PDFs without images)
+    pub fn probe(input: &Path, requested_dpi: u32) -> Option<Self> {
+        if crate::comic_archive::is_comic_archive(input) {
+            return None;
+        }
+        let reader = crate::LopdfReader::new(input).ok()?;
+        Self::from_reader(&reader, requested_dpi)
+    }
+
+    /// Whether the requested DPI is well above what the source holds
+    pub fn is_low(&self) -> bool {
+        self.requested_dpi as f64 > self.source_dpi * LOW_SOURCE_DPI_RATIO
+    }
+
+    /// Extraction DPI matching the source (rounded to 10)
+    pub fn suggested_dpi(&self) -> u32 {
+        ((self.source_dpi / 10.0).round() as u32 * 10).max(10)
+    }
+
+    /// Warning for a low-resolution source
+    pub fn warning(&self) -> String {
+        format!(
+            "source images are about {:.0} DPI, well below the requested {} DPI; extraction only interpolates them (use --dpi {} to save time; upscaling still applies)",
+            self.source_dpi,
+            self.requested_dpi,
+            self.suggested_dpi()
+        )
+    }
+}
+
+/// Page extracted below the configured DPI because its media box at that
+/// DPI exceeds `max_page_dimension`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClampedPage {
+    /// Source page (1-based)
+    pub page: usize,
+    /// Image size (width, height) the configured DPI would have produced
+    pub requested_size: (u32, u32),
+    /// DPI the page was extracted at
+    pub dpi: u32,
+}
+
+impl ClampedPage {
+    /// Pages of `page_sizes` (points) whose longest edge at `dpi` exceeds
+    /// `max_dimension` pixels, with the highest DPI that fits (none when
+    /// `max_dimension` is 0)
+    pub fn find(page_sizes: &[(f64, f64)], dpi: u32, max_dimension: u32) -> Vec<Self> {
+        if max_dimension == 0 {
+            return vec![];
+        }
+        let pixels = |points: f64| (points * dpi as f64 / 72.0).round() as u32;
+        page_sizes
+            .iter()
+            .enumerate()
+            .filter(|(_, &(width, height))| pixels(width.max(height)) > max_dimension)
+            .map(|(index, &(width, height))| Self {
+                page: index + 1,
+                requested_size: (pixels(width), pixels(height)),
+                dpi: ((max_dimension as f64 * 72.0 / width.max(height)).floor() as u32).max(1),
+            })
+            .collect()
+    }
+
+    /// Warning for the downscaled page
+    pub fn warning(&self, max_dimension: u32) -> String {
+        format!(
+            "page {} would extract at {}x{} px, above the {} px limit (--max-dimension); extracting it at {} DPI",
+            self.page, self.requested_size.0, self.requested_size.1, max_dimension, self.dpi
+        )
+    }
+}
+
+/// Stage skipped because its external tool is missing (`--ocr-optional`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedStage {
+    /// Stage name (as used in stage timings)
+    pub stage: &'static str,
+    /// Missing tool
+    pub tool: &'static str,
+}
+
+impl std::fmt::Display for SkippedStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({} not found)", self.stage, self.tool)
+    }
+}
+
+/// File locked by every superbook-pdf process for GPU stages (`gpu_wait`)
+fn gpu_lock_path() -> PathBuf {
+    std::env::temp_dir().join("superbook-pdf-gpu.lock")
+}
+
+/// Exclusive hold on the GPU, released when dropped
+struct GpuGuard {
+    #[cfg(unix)]
+    _file: std::fs::File,
+    #[cfg(not(unix))]
+    _guard: std::sync::MutexGuard<'static, ()>,
+}
+
+impl GpuGuard {
+    /// Lock the GPU, calling `on_wait` first when another holder must finish
+    ///
+    /// An `flock` on [`gpu_lock_path`], so pipelines in other processes
+    /// (a second `convert`, the web server's workers) wait as well.
+    #[cfg(unix)]
+    fn acquire(on_wait: impl FnOnce()) -> std::io::Result<Self> {
+        use rustix::fs::{flock, FlockOperation};
+        use rustix::io::Errno;
+
+        let path = gpu_lock_path();
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .or_else(|_| std::fs::File::open(&path))?;
+        match flock(&file, FlockOperation::NonBlockingLockExclusive) {
+            Ok(()) => {}
+            Err(errno) if errno == Errno::WOULDBLOCK => {
+                on_wait();
+                loop {
+                    match flock(&file, FlockOperation::LockExclusive) {
+                        Ok(()) => break,
+                        Err(errno) if errno == Errno::INTR => continue,
+                        Err(errno) => return Err(errno.into()),
+                    }
+                }
+            }
+            Err(errno) => return Err(errno.into()),
+        }
+        Ok(Self { _file: file })
+    }
+
+    /// Lock the GPU, calling `on_wait` first when another holder must finish
+    ///
+    /// Without `flock` only pipelines in this process are serialized.
+    #[cfg(not(unix))]
+    fn acquire(on_wait: impl FnOnce()) -> std::io::Result<Self> {
+        use std::sync::{Mutex, OnceLock, PoisonError, TryLockError};
+
+        static GPU: OnceLock<Mutex<()>> = OnceLock::new();
+        let lock = GPU.get_or_init(|| Mutex::new(()));
+        let guard = match lock.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::WouldBlock) => {
+                on_wait();
+                lock.lock().unwrap_or_else(PoisonError::into_inner)
+            }
+            Err(TryLockError::Poisoned(poisoned)) => poisoned.into_inner(),
+        };
+        Ok(Self { _guard: guard })
+    }
+}
+
+/// PDF processing pipeline
+pub struct PdfPipeline {
+    config: PipelineConfig,
+    skipped_stages: Vec<SkippedStage>,
+    tools: crate::tools::ToolContext,
+}
+
+impl PdfPipeline {
+    /// Create a new pipeline with the given configuration
+    ///
+    /// The niceness, timeout, thread budget and OCR limit belong to this
+    /// pipeline's [`crate::tools::ToolContext`]; `tool_paths` overrides are
+    /// registered for the whole process and accumulate.
+    pub fn new(config: PipelineConfig) -> Self {
+        crate::tools::register_overrides(&config.tool_paths);
+        let tools = config.tool_context();
+        Self {
+            config,
+            skipped_stages: Vec::new(),
+            tools,
+        }
+    }
+
+    /// Get the pipeline configuration
+    pub fn config(&self) -> &PipelineConfig {
+        &self.config
+    }
+
+    /// External tool context shared by this pipeline's stages
+    pub fn tools(&self) -> &crate::tools::ToolContext {
+        &self.tools
+    }
+
+    /// Hold the shared GPU lock for a stage when `gpu_wait` is set
+    ///
+    /// Returns None (no serialization) when `gpu_wait` or `gpu` is off, or
+    /// with a warning when the lock file cannot be used.
+    fn acquire_gpu<P: ProgressCallback>(&self, stage: &str, progress: &P) -> Option<GpuGuard> {
+        if !(self.config.gpu_wait && self.config.gpu) {
+            return None;
+        }
+        match GpuGuard::acquire(|| progress.on_debug(&format!("{}: waiting for the GPU", stage))) {
+            Ok(guard) => Some(guard),
+            Err(e) => {
+                progress.on_warning(&format!("{}: GPU lock unavailable, not waiting: {}", stage, e));
+                None
+            }
+        }
+    }
+
+    /// Check the tools needed by OCR-dependent stages before processing
+    ///
+    /// Only tools required by the configuration are probed: YomiToku for
+    /// `ocr`, tesseract for page number detection (`offset_alignment` or
+    /// `page_labels`).
+    pub fn check_ocr_dependencies(self) -> Result<Self, PipelineError> {
+        let tools = self.probe_ocr_tools();
+        self.with_ocr_tools(tools)
+    }
+
+    /// Probe the OCR tools required by the configuration (unneeded tools report true)
+    pub fn probe_ocr_tools(&self) -> OcrTools {
+        let yomitoku = !self.config.ocr
+            || crate::SubprocessBridge::new(self.bridge_config())
+                .ok()
+                .and_then(|bridge| bridge.check_tool(crate::AiTool::YomiToku).ok())
+                .unwrap_or(false);
+        let tesseract = !self.detects_page_numbers() || crate::tools::is_available("tesseract");
+        OcrTools { yomitoku, tesseract }
+    }
+
+    /// Apply known OCR tool availability
+    ///
+    /// A missing YomiToku is an error for `ocr` unless `ocr_optional` is
+    /// set. Page number detection without tesseract is always skipped (with
+    /// a warning during processing), as it was before the check existed.
+    /// Skipped stages are reported in the result.
+    pub fn with_ocr_tools(mut self, tools: OcrTools) -> Result<Self, PipelineError> {
+        let mut skipped = Vec::new();
+        if self.config.ocr && !tools.yomitoku {
+            if !self.config.ocr_optional {
+                return Err(PipelineError::OcrUnavailable {
+                    what: "OCR",
+                    tool: "yomitoku",
+                    flag: "--ocr",
+                });
+            }
+            skipped.push(SkippedStage { stage: "ocr", tool: "yomitoku" });
+        }
+        if self.detects_page_numbers() && !tools.tesseract {
+            skipped.push(SkippedStage { stage: "page_numbers", tool: "tesseract" });
+        }
+
+        self.skipped_stages = skipped;
+        Ok(self)
+    }
+
+    /// Stages skipped because their tools are missing
+    pub fn skipped_stages(&self) -> &[SkippedStage] {
+        &self.skipped_stages
+    }
+
+    fn is_skipped(&self, stage: &str) -> bool {
+        self.skipped_stages.iter().any(|s| s.stage == stage)
+    }
+
+    /// Get the output path for a given input PDF or archive (a directory
+    /// for page image output; `_odd` / `_even` suffixed for parity runs)
+    pub fn get_output_path(&self, input: &Path, output_dir: &Path) -> PathBuf {
+        let mut pdf_name = input.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        if let Some(suffix) = self.config.parity.suffix() {
+            pdf_name = format!("{}_{}", pdf_name, suffix);
+        }
+        if self.config.output_format.is_directory() {
+            return output_dir.join(format!("{}{}", pdf_name, OUTPUT_SUFFIX));
+        }
+        output_dir.join(format!("{}{}.{}", pdf_name, OUTPUT_SUFFIX, self.config.output_format.extension()))
+    }
+
+    /// Get the per-book metadata override file for a PDF (`<stem>.metadata.toml`)
+    pub fn get_metadata_override_path(&self, input: &Path) -> PathBuf {
+        input.with_extension(METADATA_OVERRIDE_EXTENSION)
+    }
+
+    /// Resolve output PDF metadata
+    ///
+    /// Precedence (lowest first): derived defaults (title from the file
+    /// stem, creator with crate version), source PDF metadata, configured
+    /// metadata, then the per-book override file next to the input.
+    pub fn resolve_metadata(
+        &self,
+        input: &Path,
+        source: &crate::PdfMetadata,
+    ) -> Result<crate::PdfMetadata, PipelineError> {
+        let defaults = crate::PdfMetadata {
+            title: input.file_stem().map(|s| s.to_string_lossy().to_string()),
+            creator: Some(format!("superbook-pdf v{}", env!("CARGO_PKG_VERSION"))),
+            producer: Some("superbook-pdf".to_string()),
+            ..Default::default()
+        };
+        let mut carried = crate::PdfMetadata {
+            title: source.title.clone().filter(|t| !t.trim().is_empty()),
+            author: source.author.clone(),
+            subject: source.subject.clone(),
+            keywords: source.keywords.clone(),
+            ..Default::default()
+        };
+        if self.config.preserve_source_metadata {
+            carried.creator = source.creator.clone();
+            carried.producer = source.producer.clone();
+            carried.creation_date = source.creation_date.clone();
+            carried.modification_date = source.modification_date.clone();
+        }
+
+        let mut metadata = defaults.merged_with(&carried).merged_with(&self.config.metadata);
+
+        let override_path = self.get_metadata_override_path(input);
+        if override_path.exists() {
+            let content = std::fs::read_to_string(&override_path)?;
+            let book: crate::PdfMetadata = toml::from_str(&content)
+                .map_err(|e| PipelineError::InvalidMetadata(override_path.clone(), e.to_string()))?;
+            metadata = metadata.merged_with(&book);
+        }
+
+        Ok(metadata)
+    }
+
+    /// Get the working directory for a PDF
+    pub fn get_work_dir(&self, input: &Path, output_dir: &Path) -> PathBuf {
+        let pdf_name = input.file_stem().unwrap_or_default().to_string_lossy();
+        output_dir.join(format!(".work_{}", pdf_name))
+    }
+
+    /// Extraction options, with the renderer processes capped to the
+    /// external thread budget and `clamped_pages` rendered at their DPI
+    fn extract_options(&self, clamped_pages: &[ClampedPage]) -> crate::ExtractOptions {
+        let builder = clamped_pages.iter().fold(
+            crate::ExtractOptions::builder()
+                .dpi(self.config.dpi)
+                .background(self.config.flatten_background)
+                .batch_pages(self.config.extract_batch_pages)
+                .tools(self.tools.clone()),
+            |builder, clamped| builder.page_dpi(clamped.page - 1, clamped.dpi),
+        );
+        match self.config.thread_budget() {
+            Some(budget) => builder.parallel(budget.external).build(),
+            None => builder.build(),
+        }
     }
 
     /// Process a single PDF file (silent mode)
@@ -448,705 +3045,4105 @@ impl PdfPipeline {
         self.process_with_progress(input, output_dir, &SilentProgress)
     }
 
-    /// Process a single PDF file with progress callback
+    /// Process a single PDF file with progress callback
+    ///
+    /// This is the main entry point for PDF processing.
+    pub fn process_with_progress<P: ProgressCallback>(
+        &self,
+        input: &Path,
+        output_dir: &Path,
+        progress: &P,
+    ) -> Result<PipelineResult, PipelineError> {
+        self.process_file(&PipelineContext::single(input), output_dir, progress)
+    }
+
+    /// Process one file of a multi-file run
+    ///
+    /// Reports `context` through [`ProgressCallback::on_file_start`] so the
+    /// callback can attribute the following steps to the file.
+    ///
+    /// With a thread budget (`external_thread_fraction`), the page stages run
+    /// on a rayon pool of the budget's internal share. With
+    /// `stall_warning_secs`, steps that stop reporting progress are flagged
+    /// through [`ProgressCallback::on_warning`].
+    pub fn process_file<P: ProgressCallback>(
+        &self,
+        context: &PipelineContext,
+        output_dir: &Path,
+        progress: &P,
+    ) -> Result<PipelineResult, PipelineError> {
+        match self.config.stall_warning_secs {
+            Some(secs) => crate::StallWatchdog::watch(progress, Duration::from_secs(secs), |progress| {
+                self.process_file_budgeted(context, output_dir, progress)
+            }),
+            None => self.process_file_budgeted(context, output_dir, progress),
+        }
+    }
+
+    fn process_file_budgeted<P: ProgressCallback>(
+        &self,
+        context: &PipelineContext,
+        output_dir: &Path,
+        progress: &P,
+    ) -> Result<PipelineResult, PipelineError> {
+        match self.config.thread_budget() {
+            Some(budget) => {
+                let pool = budget.build_pool().map_err(|e| std::io::Error::other(e.to_string()))?;
+                pool.install(|| self.process_file_in_pool(context, output_dir, progress))
+            }
+            None => self.process_file_in_pool(context, output_dir, progress),
+        }
+    }
+
+    fn process_file_in_pool<P: ProgressCallback>(
+        &self,
+        context: &PipelineContext,
+        output_dir: &Path,
+        progress: &P,
+    ) -> Result<PipelineResult, PipelineError> {
+        let start_time = Instant::now();
+        let input = context.input.as_path();
+        progress.on_file_start(context);
+
+        // Validate input
+        if !input.exists() {
+            return Err(PipelineError::InputNotFound(input.to_path_buf()));
+        }
+
+        // Create output directory
+        std::fs::create_dir_all(output_dir)?;
+
+        let output_path = self.get_output_path(input, output_dir);
+        let work_dir = self.get_work_dir(input, output_dir);
+        std::fs::create_dir_all(&work_dir)?;
+
+        // Step 1: Read PDF metadata (or the page list of a comic archive)
+        let archive = if crate::comic_archive::is_comic_archive(input) {
+            progress.on_step_start("Reading archive...");
+            let archive = crate::ComicArchive::open_with_tools(input, self.tools.clone())
+                .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
+            progress.on_step_complete("Reading archive", &format!("{} pages", archive.page_count()));
+            Some(archive)
+        } else {
+            None
+        };
+        let (total_pages, mut metadata, page_sizes, source_outline) = match &archive {
+            Some(archive) => (
+                archive.page_count(),
+                self.resolve_metadata(input, &crate::PdfMetadata::default())?,
+                vec![],
+                vec![],
+            ),
+            None => {
+                progress.on_step_start("Reading PDF...");
+                let reader = crate::LopdfReader::new(input)
+                    .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
+                let total_pages = reader.info.page_count;
+                match SourceResolution::from_reader(&reader, self.config.dpi) {
+                    Some(resolution) => {
+                        progress.on_step_complete(
+                            "Reading PDF",
+                            &format!("{} pages, source images ~{:.0} DPI", total_pages, resolution.source_dpi),
+                        );
+                        if resolution.is_low() {
+                            progress.on_warning(&resolution.warning());
+                        }
+                    }
+                    None => progress.on_step_complete("Reading PDF", &format!("{} pages", total_pages)),
+                }
+                let page_sizes = reader.info.pages.iter().map(|page| (page.width_pt, page.height_pt)).collect();
+                let source_outline = if self.config.preserve_source_outline {
+                    reader.outline()
+                } else {
+                    vec![]
+                };
+                let metadata = self.resolve_metadata(input, &reader.info.metadata)?;
+                (total_pages, metadata, page_sizes, source_outline)
+            }
+        };
+
+        // Step 1b: Existing text layer (replaces OCR when sufficient)
+        let source_text = if self.config.skip_existing_ocr && archive.is_none() {
+            self.step_probe_text_layer(input, progress)
+        } else {
+            None
+        };
+
+        let page_limit = self
+            .config
+            .max_pages
+            .map_or(total_pages, |max_pages| max_pages.min(total_pages));
+
+        // Step 1c: Pages too large at the extraction DPI are rendered lower
+        let clamped_pages: Vec<ClampedPage> =
+            ClampedPage::find(&page_sizes, self.config.dpi, self.config.max_page_dimension)
+                .into_iter()
+                .filter(|clamped| clamped.page <= page_limit && self.config.parity.includes(clamped.page))
+                .collect();
+        for clamped in &clamped_pages {
+            progress.on_warning(&clamped.warning(self.config.max_page_dimension));
+        }
+
+        let mut timings = StageTimings::new();
+
+        // Steps 2-10: extraction and image processing stages
+        let streaming = archive.is_none()
+            && self.config.extract_queue_depth > 0
+            && crate::LopdfExtractor::supports_page_extraction();
+        if self.config.resume && !streaming {
+            progress.on_warning("Resuming needs streaming extraction (pdftoppm or ImageMagick, --extract-queue-depth > 0); processing every page");
+        }
+        let processed = if let Some(archive) = &archive {
+            let images = timings.time("extract", || self.step_extract_archive(archive, &work_dir, page_limit, progress))?;
+            self.process_images(&work_dir, images, progress, &mut timings)?
+        } else if streaming {
+            self.process_streaming(input, &work_dir, page_limit, &clamped_pages, progress, &mut timings)?
+        } else {
+            let (images, failed_pages) = timings.time("extract", || {
+                self.step_extract(input, &work_dir, page_limit, &clamped_pages, progress)
+            })?;
+            self.process_extracted(&work_dir, images, &failed_pages, progress, &mut timings)?
+        };
+        let ProcessedImages {
+            images: current_images,
+            page_number_shift,
+            page_number_confidence,
+            aspect_adjustments,
+            is_vertical,
+            page_labels,
+            failed_pages,
+            geometry,
+            work_cache,
+            mixed_size,
+            resumed_pages,
+        } = processed;
+        let page_count = current_images.len();
+        let inverted_pages: Vec<usize> = geometry
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| page.inverted)
+            .map(|(index, _)| index)
+            .collect();
+        let marked_pages: Vec<usize> = geometry
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| page.marked)
+            .map(|(index, _)| index)
+            .collect();
+
+        // Step 10a: Effective output DPI against the physical page size
+        let effective_dpi = self.step_effective_dpi(&geometry, &page_sizes, &failed_pages, progress)?;
+
+        // Step 10b: Per-page external hook (if configured)
+        if let Some(command) = &self.config.page_hook {
+            timings.time("page_hook", || self.step_page_hook(command, &current_images, progress))?;
+        }
+
+        // Step 10c: Quality metrics against the extracted pages (if enabled)
+        let page_quality = if self.config.quality_metrics {
+            timings.time("quality", || self.step_quality_metrics(&work_dir, &current_images, progress))?
+        } else {
+            vec![]
+        };
+
+        // Step 11: Vertical Text Detection (already done for page numbers)
+        let is_vertical = match is_vertical {
+            Some(is_vertical) => is_vertical,
+            None => timings.time("vertical_detect", || self.step_vertical_detection(&current_images, progress))?,
+        };
+
+        // Step 12: OCR with YomiToku (if enabled), or the source text layer
+        let mut ocr_skipped_pages = vec![];
+        let mut ocr_results = if let Some(layer) = &source_text {
+            self.step_reuse_text_layer(layer, &current_images, progress)
+        } else if self.config.ocr && !self.is_skipped("ocr") {
+            let skip = timings.time("ocr_select", || self.step_ocr_selection(&current_images, &failed_pages, progress));
+            ocr_skipped_pages = skip.iter().enumerate().filter(|(_, &skip)| skip).map(|(page, _)| page).collect();
+            timings.time("ocr", || self.step_ocr(&current_images, &skip, progress))?
+        } else {
+            vec![]
+        };
+
+        // Step 12a: Running headers/footers (if enabled)
+        let running_heads = if self.config.running_heads.is_keep() {
+            vec![]
+        } else if ocr_results.is_empty() {
+            progress.on_warning("Running head detection skipped: no OCR results (enable --ocr)");
+            vec![]
+        } else {
+            timings.time("running_heads", || {
+                self.step_running_heads(&current_images, &mut ocr_results, is_vertical, progress)
+            })?
+        };
+
+        // Step 12b: Reading order overlays (kept only with --save-debug)
+        if self.config.save_debug && !ocr_results.is_empty() {
+            self.step_debug_reading_order(&work_dir, &current_images, &ocr_results, is_vertical, progress);
+        }
+
+        // Step 12b: OCR layout export (hOCR / ALTO)
+        if !self.config.ocr_formats.is_empty() {
+            if ocr_results.is_empty() {
+                progress.on_debug("OCR export skipped: no OCR results (enable --ocr)");
+            } else {
+                timings.time("ocr_export", || {
+                    self.step_export_ocr(&current_images, &output_path, &ocr_results, is_vertical, progress)
+                })?;
+            }
+        }
+
+        // Step 12b: Plain text sidecar
+        let text_sidecar = if !self.config.write_text_sidecar {
+            None
+        } else if ocr_results.is_empty() {
+            progress.on_debug("Text sidecar skipped: no OCR results (enable --ocr)");
+            None
+        } else {
+            Some(timings.time("text_sidecar", || {
+                self.step_text_sidecar(&current_images, &output_path, &ocr_results, is_vertical, progress)
+            })?)
+        };
+
+        if self.config.metadata_from_ocr && metadata.subject.is_none() {
+            metadata.subject = ocr_summary(&ocr_results, OCR_SUMMARY_CHARS);
+        }
+
+        // Step 12c: Per-page color/grayscale decision (if mixed_color enabled)
+        let mut page_color_modes = if self.config.mixed_color {
+            timings.time("color_detect", || self.step_detect_color_modes(&current_images, progress))
+        } else {
+            vec![]
+        };
+        let page_color_regions = self.color_regions(
+            &current_images,
+            &ocr_results,
+            &mut page_color_modes,
+            is_vertical,
+            &mut timings,
+            progress,
+        );
+
+        // Step 13: Generate PDF (or CBZ, or the page image directory)
+        let format_name = self.config.output_format.name();
+        progress.on_step_start(&format!("Generating output {}...", format_name));
+        let write_stats = timings.time("pdf", || {
+            if self.config.output_format.is_directory() {
+                let logical_pages = logical_page_labels(page_number_shift, &page_labels, page_count);
+                let mut manifest =
+                    self.page_manifest(input, &current_images, &geometry, logical_pages, &failed_pages, is_vertical);
+                manifest.running_heads = running_heads.clone();
+                manifest.effective_dpi = effective_dpi;
+                for page in &mut manifest.pages {
+                    page.extraction_dpi = clamped_pages
+                        .iter()
+                        .find(|clamped| clamped.page == page.physical_page)
+                        .map(|clamped| clamped.dpi);
+                }
+                let has_text = ocr_results.iter().any(Option::is_some);
+                return self.step_export_pages(&current_images, &output_path, &manifest, has_text, progress);
+            }
+            let outline = self.remap_outline(&source_outline, &failed_pages, page_count);
+            self.step_generate_pdf(
+                &current_images,
+                &output_path,
+                metadata,
+                &ocr_results,
+                &page_color_modes,
+                &page_color_regions,
+                &page_labels,
+                &outline,
+                is_vertical,
+                progress,
+            )
+        })?;
+
+        // Get output file size
+        let output_size = output_size(&output_path);
+        progress.on_step_complete(&format!("Generating {}", format_name), &format!("{} bytes", output_size));
+
+        // Cleanup work directory (unless save_debug)
+        if !self.config.save_debug {
+            std::fs::remove_dir_all(&work_dir).ok();
+        }
+
+        let elapsed = start_time.elapsed().as_secs_f64();
+
+        let mut result = PipelineResult::new(
+            page_count,
+            page_number_shift,
+            is_vertical,
+            elapsed,
+            output_path,
+            output_size,
+        )
+        .with_stage_timings(timings)
+        .with_skipped_stages(self.skipped_stages.clone())
+        .with_page_color_modes(page_color_modes)
+        .with_ocr_confidence(ocr_results.iter().map(|r| r.as_ref().map(|r| r.mean_confidence())).collect())
+        .with_page_quality(page_quality)
+        .with_write_stats(write_stats)
+        .with_text_layer_reused(source_text.is_some())
+        .with_aspect_adjustments(aspect_adjustments)
+        .with_tone_curve_lut(self.config.tone_curve.as_ref().map(|c| c.lut().to_vec()).unwrap_or_default())
+        .with_failed_pages(failed_pages)
+        .with_running_heads(running_heads)
+        .with_inverted_pages(inverted_pages)
+        .with_marked_pages(marked_pages)
+        .with_ocr_skipped_pages(ocr_skipped_pages)
+        .with_page_number_confidence(page_number_confidence)
+        .with_effective_dpi(effective_dpi)
+        .with_draft(self.config.quick)
+        .with_work_cache(work_cache)
+        .with_resumed_pages(resumed_pages)
+        .with_mixed_size_pages(mixed_size)
+        .with_text_sidecar(text_sidecar)
+        .with_clamped_pages(clamped_pages);
+
+        // Step 14: Post-generation external hook (if configured)
+        if let Some(command) = &self.config.post_hook {
+            let hook_start = Instant::now();
+            self.step_post_hook(command, input, &result, progress)?;
+            result.stage_timings.add("post_hook", hook_start.elapsed());
+        }
+
+        Ok(result)
+    }
+
+    /// Build a PDF directly from already-processed page images
+    ///
+    /// `image_dir` is either a directory of page images, a page image
+    /// directory written with `--output-format images`, or a work directory
+    /// kept with `--save-debug`, in which case the latest stage directory is
+    /// used. Only PDF-generation settings (DPI, JPEG quality, metadata,
+    /// mixed color, deterministic) apply; no image stages or OCR run.
+    ///
+    /// With a `manifest.json`, pages follow the manifest and returned hOCR
+    /// files (`page_0001.hocr`, looked up in `ocr_dir`, default
+    /// `image_dir`) become the text layer.
+    pub fn assemble_with_progress<P: ProgressCallback>(
+        &self,
+        image_dir: &Path,
+        ocr_dir: Option<&Path>,
+        output_path: &Path,
+        progress: &P,
+    ) -> Result<PipelineResult, PipelineError> {
+        let start_time = Instant::now();
+
+        let manifest = if image_dir.join(crate::page_manifest::MANIFEST_FILE).is_file() {
+            Some(crate::PageManifest::load(image_dir)?)
+        } else {
+            None
+        };
+        let mut images = match &manifest {
+            Some(manifest) => manifest.pages.iter().map(|page| image_dir.join(&page.file)).collect(),
+            None => collect_page_images(image_dir)?,
+        };
+        if let Some(missing) = images.iter().find(|image| !image.is_file()) {
+            return Err(PipelineError::InputNotFound(missing.clone()));
+        }
+        if images.is_empty() {
+            if let Some(stage_dir) = latest_stage_dir(image_dir) {
+                progress.on_debug(&format!("Using stage directory {}", stage_dir.display()));
+                images = collect_page_images(&stage_dir)?;
+            }
+        }
+        if images.is_empty() {
+            return Err(PipelineError::InputNotFound(image_dir.to_path_buf()));
+        }
+        progress.on_step_complete("Collecting pages", &format!("{} pages", images.len()));
+
+        let (ocr_results, is_vertical) = match &manifest {
+            Some(manifest) => (
+                self.step_import_ocr(manifest, &images, ocr_dir.unwrap_or(image_dir), progress)?,
+                manifest.pages.iter().any(|page| page.vertical),
+            ),
+            None => (vec![], false),
+        };
+
+        if let Some(parent) = output_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let metadata = self.resolve_metadata(output_path, &crate::PdfMetadata::default())?;
+
+        let mut timings = StageTimings::new();
+        let mut page_color_modes = if self.config.mixed_color {
+            timings.time("color_detect", || self.step_detect_color_modes(&images, progress))
+        } else {
+            vec![]
+        };
+        let page_color_regions =
+            self.color_regions(&images, &ocr_results, &mut page_color_modes, is_vertical, &mut timings, progress);
+
+        progress.on_step_start("Generating output PDF...");
+        let write_stats = timings.time("pdf", || {
+            self.step_generate_pdf(
+                &images,
+                output_path,
+                metadata,
+                &ocr_results,
+                &page_color_modes,
+                &page_color_regions,
+                &[],
+                &[],
+                is_vertical,
+                progress,
+            )
+        })?;
+        let output_size = std::fs::metadata(output_path).map(|m| m.len()).unwrap_or(0);
+        progress.on_step_complete("Generating PDF", &format!("{} bytes", output_size));
+
+        Ok(PipelineResult::new(
+            images.len(),
+            None,
+            is_vertical,
+            start_time.elapsed().as_secs_f64(),
+            output_path.to_path_buf(),
+            output_size,
+        )
+        .with_stage_timings(timings)
+        .with_page_color_modes(page_color_modes)
+        .with_ocr_confidence(ocr_results.iter().map(|r| r.as_ref().map(|r| r.mean_confidence())).collect())
+        .with_write_stats(write_stats))
+    }
+
+    /// Read returned hOCR files for the pages of a manifest (pages without
+    /// one get no text)
+    fn step_import_ocr<P: ProgressCallback>(
+        &self,
+        manifest: &crate::PageManifest,
+        images: &[PathBuf],
+        ocr_dir: &Path,
+        progress: &P,
+    ) -> Result<Vec<Option<crate::OcrResult>>, PipelineError> {
+        progress.on_step_start("Reading returned OCR...");
+        let mut results = Vec::with_capacity(manifest.pages.len());
+        for (page, image) in manifest.pages.iter().zip(images) {
+            let result = match crate::PageManifest::ocr_file(ocr_dir, page) {
+                Some(path) => {
+                    let hocr = crate::HocrPage::parse(&std::fs::read_to_string(&path)?);
+                    Some(hocr.to_ocr_result(image, page, manifest.dpi))
+                }
+                None => None,
+            };
+            results.push(result);
+        }
+        let found = results.iter().filter(|r| r.is_some()).count();
+        if found == 0 {
+            progress.on_warning(&format!("No hOCR files found in {}; PDF has no text layer", ocr_dir.display()));
+            return Ok(vec![]);
+        }
+        progress.on_step_complete("Returned OCR", &format!("{} of {} pages", found, results.len()));
+        Ok(results)
+    }
+
+    /// Step 10a: Effective output DPI (median over the pages with a known
+    /// physical size), handled per `on_low_dpi` when below `min_output_dpi`
+    fn step_effective_dpi<P: ProgressCallback>(
+        &self,
+        geometry: &[crate::PageGeometry],
+        page_sizes: &[(f64, f64)],
+        failed_pages: &[usize],
+        progress: &P,
+    ) -> Result<Option<f64>, PipelineError> {
+        let sized: Vec<(&crate::PageGeometry, (f64, f64))> = geometry
+            .iter()
+            .zip(self.physical_pages(failed_pages))
+            .filter_map(|(page, physical)| Some((page, *page_sizes.get(physical)?)))
+            .collect();
+        let median = |mut values: Vec<f64>| {
+            values.sort_by(f64::total_cmp);
+            values.get(values.len() / 2).copied()
+        };
+        let dpi = median(sized.iter().filter_map(|(page, size)| page.effective_dpi(*size)).collect());
+        let scan_dpi = median(sized.iter().filter_map(|(page, size)| page.scan_dpi(*size)).collect());
+        let (Some(dpi), Some(scan_dpi)) = (dpi, scan_dpi) else {
+            progress.on_debug("Effective output DPI unknown: no physical page size");
+            return Ok(None);
+        };
+        progress.on_debug(&format!("Effective output DPI: {:.0} (scan: {:.0})", dpi, scan_dpi));
+
+        let min = self.config.min_output_dpi;
+        if min == 0 || dpi >= min as f64 {
+            return Ok(Some(dpi));
+        }
+        let mut message = format!("effective output DPI {:.0} is below the minimum {}", dpi, min);
+        if scan_dpi < min as f64 {
+            message.push_str(&format!(
+                "; the scan itself is only {:.0} DPI and upscaling cannot restore the detail (rescan, or lower --min-output-dpi)",
+                scan_dpi
+            ));
+        } else if self.config.output_height != 0 && self.config.output_height != 7016 {
+            let needed = (self.config.output_height as f64 * min as f64 / dpi).ceil();
+            message.push_str(&format!("; raise --output-height to at least {}", needed));
+        }
+        match self.config.on_low_dpi {
+            LowDpiPolicy::Warn => {
+                progress.on_warning(&message);
+                Ok(Some(dpi))
+            }
+            LowDpiPolicy::Refuse => Err(PipelineError::InvalidConfig(message)),
+        }
+    }
+
+    /// Step 10b: Run the page hook on each final page image
+    fn step_page_hook<P: ProgressCallback>(
+        &self,
+        command: &str,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<(), PipelineError> {
+        progress.on_step_start("Running page hook...");
+        let runner = crate::hooks::HookRunner::new(command);
+        for (i, image) in images.iter().enumerate() {
+            let output = runner.run_page(image, i + 1)?;
+            for line in output.lines() {
+                progress.on_debug(&format!("[page-hook p{}] {}", i + 1, line));
+            }
+            progress.on_step_progress(i + 1, images.len());
+        }
+        progress.on_step_complete("Page hook", &format!("{} pages", images.len()));
+        Ok(())
+    }
+
+    /// Step 10c: SSIM/PSNR between each extracted page and its final image
+    ///
+    /// Pages are matched by order with the sorted `extracted` directory.
+    fn step_quality_metrics<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<Vec<crate::quality::PageQuality>, PipelineError> {
+        progress.on_step_start("Computing quality metrics...");
+        let originals = collect_page_images(&work_dir.join("extracted"))?;
+        if originals.len() < images.len() {
+            progress.on_debug(&format!(
+                "Quality metrics skipped: {} extracted pages for {} final pages",
+                originals.len(),
+                images.len()
+            ));
+            return Ok(vec![]);
+        }
+
+        let quality = images
+            .par_iter()
+            .zip(originals.par_iter())
+            .map(|(processed, original)| crate::quality::compare_pages(original, processed))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| PipelineError::ImageProcessingFailed(e.to_string()))?;
+
+        if let Some(summary) = crate::quality::QualitySummary::from_pages(&quality, crate::quality::DEFAULT_MIN_SSIM) {
+            progress.on_step_complete(
+                "Quality metrics",
+                &format!(
+                    "mean SSIM {:.3}, min {:.3} (page {}), mean PSNR {:.1} dB",
+                    summary.mean_ssim, summary.min_ssim, summary.min_ssim_page, summary.mean_psnr
+                ),
+            );
+        }
+        Ok(quality)
+    }
+
+    /// Step 14: Run the post hook on the generated PDF
+    ///
+    /// The hook receives the output path as its argument and a JSON manifest
+    /// (`input`, `output`, `result`) on stdin; a non-zero exit fails the file.
+    fn step_post_hook<P: ProgressCallback>(
+        &self,
+        command: &str,
+        input: &Path,
+        result: &PipelineResult,
+        progress: &P,
+    ) -> Result<(), PipelineError> {
+        progress.on_step_start("Running post hook...");
+        let manifest = serde_json::json!({
+            "input": input,
+            "output": result.output_path,
+            "result": result.to_cache_result(),
+        });
+        let output = crate::hooks::HookRunner::new(command)
+            .run_post(&result.output_path, &manifest.to_string())?;
+        for line in output.lines() {
+            progress.on_debug(&format!("[post-hook] {}", line));
+        }
+        progress.on_step_complete("Post hook", command);
+        Ok(())
+    }
+
+    /// Step 2: Extract all page images before processing
+    ///
+    /// Returns the images of the first `page_limit` pages (only the
+    /// selected parity) and the pages (0-based) skipped after a tool timeout.
+    fn step_extract<P: ProgressCallback>(
+        &self,
+        input: &Path,
+        work_dir: &Path,
+        page_limit: usize,
+        clamped_pages: &[ClampedPage],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<usize>), PipelineError> {
+        progress.on_step_start(&format!("Extracting images (DPI: {})...", self.config.dpi));
+        let extract_options = self.extract_options(clamped_pages);
+        let extracted_dir = work_dir.join("extracted");
+        std::fs::create_dir_all(&extracted_dir)?;
+
+        let mut extracted_pages = crate::LopdfExtractor::extract_auto(input, &extracted_dir, &extract_options)
+            .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
+
+        // Stable page order regardless of extraction backend
+        if self.config.deterministic {
+            extracted_pages.sort_by_key(|p| p.page_index);
+        }
+
+        // Pages left out after a tool timeout
+        let parity = self.config.parity;
+        let failed_pages: Vec<usize> = if self.config.tool_timeout_secs.is_some() {
+            let extracted: HashSet<usize> = extracted_pages.iter().map(|p| p.page_index).collect();
+            (0..page_limit)
+                .filter(|page| parity.includes(page + 1) && !extracted.contains(page))
+                .collect()
+        } else {
+            vec![]
+        };
+        for &page in &failed_pages {
+            self.page_failed(progress, page, "extraction timed out")?;
+        }
+
+        // Apply max_pages limit
+        if let Some(max_pages) = self.config.max_pages {
+            if extracted_pages.len() + failed_pages.len() > max_pages {
+                progress.on_debug(&format!("Limiting to {} pages (--max-pages)", max_pages));
+                extracted_pages.retain(|p| p.page_index < page_limit);
+                extracted_pages.truncate(max_pages);
+            }
+        }
+        if !parity.is_all() {
+            extracted_pages.retain(|p| {
+                let keep = parity.includes(p.page_index + 1);
+                if !keep {
+                    std::fs::remove_file(&p.path).ok();
+                }
+                keep
+            });
+        }
+        progress.on_step_complete("Extracting images", &format!("{} pages", extracted_pages.len()));
+
+        Ok((extracted_pages.into_iter().map(|p| p.path).collect(), failed_pages))
+    }
+
+    /// Step 2 for comic archives: decode the first `page_count` page entries
+    /// (only the selected parity)
+    fn step_extract_archive<P: ProgressCallback>(
+        &self,
+        archive: &crate::ComicArchive,
+        work_dir: &Path,
+        page_count: usize,
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        progress.on_step_start("Extracting archive pages...");
+        if page_count < archive.page_count() {
+            progress.on_debug(&format!("Limiting to {} pages (--max-pages)", page_count));
+        }
+        let parity = self.config.parity;
+        let pages = if parity.is_all() {
+            archive.extract_pages(&work_dir.join("extracted"), Some(page_count))
+        } else {
+            (0..page_count)
+                .filter(|index| parity.includes(index + 1))
+                .map(|index| archive.extract_page(index, &work_dir.join("extracted")))
+                .collect()
+        }
+        .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
+        progress.on_step_complete("Extracting archive pages", &format!("{} pages", pages.len()));
+        Ok(pages)
+    }
+
+    /// Steps 2-10 with streaming extraction
+    ///
+    /// A background producer renders pages into a bounded channel while the
+    /// per-page stages (trim → upscale → normalize → deskew) run on batches of
+    /// `chunk_size` pages as they arrive. Raw extracted pages are deleted once
+    /// processed, so extraction temp usage stays bounded by the queue depth
+    /// plus one batch. Stages that need every page (color statistics, group
+    /// crop, page numbers) run after the stream is drained.
+    ///
+    /// Completed pages are recorded in the work directory's progress file;
+    /// with `resume`, pages an interrupted run finished are taken from it
+    /// instead of being rendered again.
+    fn process_streaming<P: ProgressCallback>(
+        &self,
+        input: &Path,
+        work_dir: &Path,
+        page_count: usize,
+        clamped_pages: &[ClampedPage],
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<ProcessedImages, PipelineError> {
+        let depth = self.config.extract_queue_depth;
+        let mut progress_file = self.open_progress_file(input, work_dir, clamped_pages, progress);
+        progress.on_step_start(&format!(
+            "Streaming extraction (DPI: {}, queue: {} pages)...",
+            self.config.dpi, depth
+        ));
+        let extract_options = self.extract_options(clamped_pages);
+        let extracted_dir = work_dir.join("extracted");
+
+        let batch_size = if self.config.chunk_size > 0 {
+            self.config.chunk_size
+        } else {
+            calculate_optimal_chunk_size(
+                page_count,
+                self.config.max_memory_mb,
+                self.config.threads.unwrap_or_else(num_cpus::get),
+            )
+        };
+
+        // Pages of the other parity are rendered but dropped on arrival
+        let parity = self.config.parity;
+        let selected_count = (1..=page_count).filter(|&page| parity.includes(page)).count();
+        let mut processed = Vec::with_capacity(selected_count);
+        let mut geometry = Vec::with_capacity(selected_count);
+        // 0-based source page of each processed page
+        let mut source_pages = Vec::with_capacity(selected_count);
+        let mut batch = Vec::with_capacity(batch_size);
+
+        // Pages an interrupted run finished (or rendered) are not rendered again
+        let mut render = Vec::with_capacity(page_count);
+        for page in 0..page_count {
+            let recorded = progress_file.as_ref().filter(|_| parity.includes(page + 1));
+            if let Some((image, done)) =
+                recorded.and_then(|file| file.completed(page, crate::PageStage::PageStages).zip(file.page(page)))
+            {
+                processed.push(image);
+                geometry.push(done.geometry());
+                source_pages.push(page);
+            } else if let Some(image) = recorded.and_then(|file| file.completed(page, crate::PageStage::Extract)) {
+                batch.push((page, image));
+            } else {
+                render.push(page);
+            }
+        }
+        let resumed_pages = processed.len();
+        if self.config.resume && progress_file.as_ref().is_some_and(|file| !file.is_empty()) {
+            progress.on_debug(&format!(
+                "Resuming: {} pages processed and {} extracted before the interruption",
+                resumed_pages,
+                batch.len()
+            ));
+        }
+        report_pages_complete(progress, 0, &processed, false);
+
+        let mut stream = crate::PageStream::spawn_pages(input, render, &extracted_dir, extract_options, depth);
+        // Extraction time is the time spent waiting on the producer
+        let mut failed_pages = Vec::new();
+        let mut work_cache = crate::WorkCacheStats::default();
+        while let Some(page) = timings.time("extract", || stream.next()) {
+            let page = match page {
+                Ok(page) if !parity.includes(page.page_index + 1) => {
+                    std::fs::remove_file(&page.path).ok();
+                    continue;
+                }
+                Ok(page) => page,
+                Err(crate::ExtractError::TimedOut { page, .. }) if !parity.includes(page + 1) => continue,
+                Err(crate::ExtractError::TimedOut { page, reason }) => {
+                    self.page_failed(progress, page, &reason)?;
+                    failed_pages.push(page);
+                    continue;
+                }
+                Err(e) => return Err(PipelineError::ExtractionFailed(e.to_string())),
+            };
+            if let Some(file) = progress_file.as_mut() {
+                file.record(page.page_index, crate::PageStage::Extract, &page.path, &crate::PageGeometry::default());
+                if let Err(e) = file.save_throttled() {
+                    progress.on_debug(&format!("Progress file not written: {}", e));
+                }
+            }
+            batch.push((page.page_index, page.path));
+            if batch.len() >= batch_size {
+                let first_page = processed.len();
+                source_pages.extend(batch.iter().map(|(page, _)| *page));
+                let (images, pages) = self.process_page_batch(
+                    work_dir,
+                    std::mem::take(&mut batch),
+                    &mut progress_file,
+                    &mut work_cache,
+                    progress,
+                    timings,
+                )?;
+                processed.extend(images);
+                geometry.extend(pages);
+                report_pages_complete(progress, first_page, &processed[first_page..], false);
+                progress.on_step_progress(processed.len(), selected_count);
+            }
+        }
+        if !batch.is_empty() {
+            let first_page = processed.len();
+            source_pages.extend(batch.iter().map(|(page, _)| *page));
+            let (images, pages) =
+                self.process_page_batch(work_dir, batch, &mut progress_file, &mut work_cache, progress, timings)?;
+            processed.extend(images);
+            geometry.extend(pages);
+            report_pages_complete(progress, first_page, &processed[first_page..], false);
+            progress.on_step_progress(processed.len(), selected_count);
+        }
+        progress.on_step_complete("Streaming extraction", &format!("{} pages", processed.len()));
+
+        // Resumed pages were taken first: restore the page order
+        let mut pages: Vec<_> = source_pages.into_iter().zip(processed).zip(geometry).collect();
+        pages.sort_by_key(|((page, _), _)| *page);
+        let (mut processed, mut geometry): (Vec<_>, Vec<_>) =
+            pages.into_iter().map(|((_, image), geometry)| (image, geometry)).unzip();
+
+        self.insert_placeholders(work_dir, &mut processed, &mut geometry, &failed_pages, progress)?;
+        let mut result = self.process_document_stages(work_dir, processed, geometry, progress, timings)?;
+        result.failed_pages = failed_pages;
+        result.work_cache = work_cache;
+        result.resumed_pages = resumed_pages;
+        Ok(result)
+    }
+
+    /// Run per-page stages on one streamed batch of (0-based source page,
+    /// extracted image), drop its raw pages and record the finished pages
+    /// in the progress file
+    fn process_page_batch<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        extracted: Vec<(usize, PathBuf)>,
+        progress_file: &mut Option<crate::ProgressFile>,
+        work_cache: &mut crate::WorkCacheStats,
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<(Vec<PathBuf>, Vec<crate::PageGeometry>), PipelineError> {
+        let (pages, extracted): (Vec<usize>, Vec<PathBuf>) = extracted.into_iter().unzip();
+        let (processed, geometry) =
+            self.cached_page_stages(work_dir, extracted.clone(), work_cache, progress, timings)?;
+        // Quality metrics compare against the extracted pages later
+        if !self.config.save_debug && !self.config.quality_metrics {
+            for path in extracted.iter().filter(|path| !processed.contains(path)) {
+                std::fs::remove_file(path).ok();
+            }
+        }
+        if let Some(file) = progress_file.as_mut() {
+            for ((&page, image), page_geometry) in pages.iter().zip(&processed).zip(&geometry) {
+                file.record(page, crate::PageStage::PageStages, image, page_geometry);
+            }
+            if let Err(e) = file.save() {
+                progress.on_debug(&format!("Progress file not written: {}", e));
+            }
+        }
+        Ok((processed, geometry))
+    }
+
+    /// Progress file of a streamed run: the interrupted run's with `resume`
+    /// (a fresh one, with a warning, when it does not match), else a fresh
+    /// one; `None` when the source file cannot be identified
+    fn open_progress_file<P: ProgressCallback>(
+        &self,
+        input: &Path,
+        work_dir: &Path,
+        clamped_pages: &[ClampedPage],
+        progress: &P,
+    ) -> Option<crate::ProgressFile> {
+        // Everything that shapes the page images up to the per-page stages
+        let options = serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "dpi": self.config.dpi,
+            "clamped_pages": clamped_pages,
+            "page_stages": self.page_stage_options(),
+        })
+        .to_string();
+        let digest = match crate::CacheDigest::new(input, &options) {
+            Ok(digest) => digest,
+            Err(e) => {
+                progress.on_debug(&format!("Progress file disabled: {}", e));
+                return None;
+            }
+        };
+        if !self.config.resume {
+            return Some(crate::ProgressFile::new(work_dir, digest));
+        }
+        match crate::ProgressFile::resume(work_dir, digest.clone()) {
+            Ok(file) => Some(file),
+            Err(e) => {
+                progress.on_warning(&format!("Nothing to resume ({}); processing every page", e));
+                Some(crate::ProgressFile::new(work_dir, digest))
+            }
+        }
+    }
+
+    /// Run the image processing stages (trim → upscale → normalize →
+    /// deskew → color → contrast → group crop → page numbers → finalize)
+    ///
+    /// Returns the final page images, the detected page number shift and
+    /// any aspect ratio adjustments; time spent in each stage is added to
+    /// `timings`.
+    pub fn process_images<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: Vec<PathBuf>,
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<ProcessedImages, PipelineError> {
+        self.process_extracted(work_dir, images, &[], progress, timings)
+    }
+
+    /// [`Self::process_images`] for extracted pages with `failed_pages`
+    /// (0-based source pages) missing
+    fn process_extracted<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: Vec<PathBuf>,
+        failed_pages: &[usize],
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<ProcessedImages, PipelineError> {
+        let mut work_cache = crate::WorkCacheStats::default();
+        let (mut images, mut geometry) = self.cached_page_stages(work_dir, images, &mut work_cache, progress, timings)?;
+        report_pages_complete(progress, 0, &images, false);
+        self.insert_placeholders(work_dir, &mut images, &mut geometry, failed_pages, progress)?;
+        let mut processed = self.process_document_stages(work_dir, images, geometry, progress, timings)?;
+        processed.failed_pages = failed_pages.to_vec();
+        processed.work_cache = work_cache;
+        Ok(processed)
+    }
+
+    /// Apply `on_page_error` to a page (0-based) that failed
+    fn page_failed<P: ProgressCallback>(&self, progress: &P, page: usize, reason: &str) -> Result<(), PipelineError> {
+        match self.config.on_page_error {
+            PageErrorPolicy::Abort => Err(PipelineError::ExtractionFailed(format!("page {}: {}", page + 1, reason))),
+            PageErrorPolicy::Skip => {
+                progress.on_warning(&format!("Page {}: {}; page skipped", page + 1, reason));
+                Ok(())
+            }
+            PageErrorPolicy::Placeholder => {
+                progress.on_warning(&format!("Page {}: {}; placeholder inserted", page + 1, reason));
+                Ok(())
+            }
+        }
+    }
+
+    /// Put a placeholder image where each failed page (0-based source
+    /// page) belongs, ahead of the document stages so page numbers and
+    /// labels see the full page count (`PageErrorPolicy::Placeholder` only)
+    ///
+    /// Placeholders take the size of a neighbouring page, or A4 at the
+    /// output DPI when every page failed.
+    fn insert_placeholders<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &mut Vec<PathBuf>,
+        geometry: &mut Vec<crate::PageGeometry>,
+        failed_pages: &[usize],
+        progress: &P,
+    ) -> Result<(), PipelineError> {
+        if self.config.on_page_error != PageErrorPolicy::Placeholder || failed_pages.is_empty() {
+            return Ok(());
+        }
+        let placeholder_dir = work_dir.join("placeholders");
+        std::fs::create_dir_all(&placeholder_dir)?;
+
+        let parity = self.config.parity;
+        let a4 = ((self.config.dpi as f64 * 8.27) as u32, (self.config.dpi as f64 * 11.69) as u32);
+        let mut failed_pages = failed_pages.to_vec();
+        failed_pages.sort_unstable();
+        for page in failed_pages {
+            // Every selected page before it is in place (processed or a placeholder)
+            let index = (0..page).filter(|p| parity.includes(p + 1)).count().min(images.len());
+            let neighbour = index.checked_sub(1).into_iter().chain([index]).filter_map(|i| images.get(i));
+            let size = neighbour
+                .filter_map(|path| image::image_dimensions(path).ok())
+                .next()
+                .unwrap_or(a4);
+            let path = placeholder_dir.join(format!("page_{:05}.png", page));
+            crate::PageFinalizer::write_placeholder(&path, size.0, size.1, page + 1)
+                .map_err(|e| PipelineError::ImageProcessingFailed(e.to_string()))?;
+            images.insert(index, path);
+            geometry.insert(
+                index,
+                crate::PageGeometry {
+                    source_size: size,
+                    placeholder: true,
+                    ..Default::default()
+                },
+            );
+        }
+        progress.on_debug(&format!("Placeholders written to {}", placeholder_dir.display()));
+        Ok(())
+    }
+
+    /// [`Self::process_page_stages`] through the work cache (`work_cache`)
+    ///
+    /// Pages whose content and page-stage options were seen before are
+    /// copied from the cache into `cached/`; the others run the stages and
+    /// are stored. Cache failures only cost the reuse: they are reported as
+    /// debug output and the page is processed.
+    fn cached_page_stages<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: Vec<PathBuf>,
+        stats: &mut crate::WorkCacheStats,
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<(Vec<PathBuf>, Vec<crate::PageGeometry>), PipelineError> {
+        let Some(dir) = &self.config.work_cache else {
+            return self.process_page_stages(work_dir, images, progress, timings);
+        };
+        let cache = match crate::WorkCache::open(dir, self.config.work_cache_max_mb, &self.page_stage_options()) {
+            Ok(cache) => cache,
+            Err(e) => {
+                progress.on_warning(&format!("Work cache {} unavailable: {}", dir.display(), e));
+                return self.process_page_stages(work_dir, images, progress, timings);
+            }
+        };
+
+        let cached_dir = work_dir.join("cached");
+        let (keys, hits): (Vec<_>, Vec<_>) = timings.time("work_cache", || {
+            images
+                .par_iter()
+                .map(|image| {
+                    let key = cache.key(image).ok();
+                    let hit = key.as_deref().zip(image.file_name()).and_then(|(key, name)| {
+                        let dest = cached_dir.join(name);
+                        cache.get(key, &dest).map(|entry| (dest, entry))
+                    });
+                    (key, hit)
+                })
+                .unzip()
+        });
+
+        let mut results: Vec<Option<(PathBuf, crate::PageGeometry)>> = vec![None; images.len()];
+        let mut misses = Vec::new();
+        for (index, hit) in hits.into_iter().enumerate() {
+            match hit {
+                Some((path, entry)) => {
+                    let geometry = crate::PageGeometry {
+                        transforms: entry.transforms,
+                        inverted: entry.inverted,
+                        marked: entry.marked,
+                        ..crate::PageGeometry::of_image(&images[index])
+                    };
+                    results[index] = Some((path, geometry));
+                }
+                None => misses.push(index),
+            }
+        }
+
+        let mut batch = crate::WorkCacheStats {
+            hits: images.len() - misses.len(),
+            misses: misses.len(),
+            evicted: 0,
+        };
+        if !misses.is_empty() {
+            let inputs: Vec<PathBuf> = misses.iter().map(|&index| images[index].clone()).collect();
+            let (processed, geometry) = self.process_page_stages(work_dir, inputs, progress, timings)?;
+            timings.time("work_cache", || {
+                for ((&index, path), geometry) in misses.iter().zip(processed).zip(geometry) {
+                    let complete = self.page_stages_complete(work_dir, &images[index], &path);
+                    if let Some(key) = keys[index].as_deref().filter(|_| complete) {
+                        let entry = crate::WorkCacheEntry {
+                            transforms: geometry.transforms.clone(),
+                            inverted: geometry.inverted,
+                            marked: geometry.marked,
+                        };
+                        if let Err(e) = cache.put(key, &path, &entry) {
+                            progress.on_debug(&format!("Work cache: page {} not stored: {}", path.display(), e));
+                        }
+                    }
+                    results[index] = Some((path, geometry));
+                }
+                match cache.evict() {
+                    Ok(evicted) => batch.evicted = evicted,
+                    Err(e) => progress.on_debug(&format!("Work cache: eviction failed: {}", e)),
+                }
+            });
+        }
+        progress.on_debug(&format!(
+            "Work cache: {}/{} pages reused from {}",
+            batch.hits,
+            batch.lookups(),
+            cache.dir().display()
+        ));
+        stats.add(batch);
+        Ok(results.into_iter().flatten().unzip())
+    }
+
+    /// Options the per-page stages depend on (part of every work cache key)
+    fn page_stage_options(&self) -> String {
+        let config = &self.config;
+        serde_json::json!({
+            "flatten_background": config.flatten_background,
+            "invert": config.invert,
+            "remove_line_artifacts": config.remove_line_artifacts,
+            "remove_markers": config.remove_markers,
+            "marker_colors": config.marker_colors,
+            "marker_region": config.marker_region,
+            "highlighters": config.highlighters,
+            "margin_trim": config.margin_trim,
+            "upscale": config.upscale,
+            "internal_resolution": config.internal_resolution,
+            "resize_filter": config.resize_filter,
+            "deskew": config.deskew,
+            "deterministic": config.deterministic,
+        })
+        .to_string()
+    }
+
+    /// Whether a page's stage result may be stored in the work cache: some
+    /// stage produced it, and the upscale (which falls back to the input
+    /// page when RealESRGAN fails) actually ran
+    fn page_stages_complete(&self, work_dir: &Path, input: &Path, output: &Path) -> bool {
+        output != input
+            && (!self.config.upscale
+                || input
+                    .file_name()
+                    .is_some_and(|name| work_dir.join("upscaled").join(name).exists()))
+    }
+
+    /// Stages 2-5 that only look at one page at a time
+    ///
+    /// Output files keep the input file names, so batches of a streamed
+    /// document can share the stage directories.
+    fn process_page_stages<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: Vec<PathBuf>,
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<(Vec<PathBuf>, Vec<crate::PageGeometry>), PipelineError> {
+        let mut geometry: Vec<crate::PageGeometry> = images.iter().map(|path| crate::PageGeometry::of_image(path)).collect();
+        let mut current_images = images;
+
+        // ================================================================
+        // C#版互換処理順序:
+        // 1. PDF抽出 (上で完了)
+        // 2. マージントリム (0.5%)
+        // 3. AI超解像 (RealESRGAN 2x)
+        // 4. 内部解像度正規化 (4960x7016)
+        // 5. 傾き補正 (Deskew) ← C#はここで実行
+        // 6. 色補正
+        // ================================================================
+
+        // Step 2 (cont.): Transparent pages, before any stage drops the alpha channel
+        current_images = self.step_flatten(work_dir, &current_images, progress)?;
+
+        // Step 2 (cont.): Negative scans, before every stage that assumes dark-on-light
+        if !self.config.invert.is_off() {
+            let (images, inverted) = timings.time("invert", || self.step_invert(work_dir, &current_images, progress))?;
+            current_images = images;
+            for (page, inverted) in geometry.iter_mut().zip(inverted) {
+                page.inverted = inverted;
+            }
+        }
+
+        // Step 2a: Scanner line artifacts, while lines are still axis-aligned
+        if self.config.remove_line_artifacts {
+            current_images =
+                timings.time("line_artifacts", || self.step_line_artifacts(work_dir, &current_images, progress))?;
+        }
+
+        // Step 2b: Highlighter marks, on the original colors
+        if let Some(options) = self
+            .config
+            .marker_removal_options()
+            .map_err(|e| PipelineError::ImageProcessingFailed(e.to_string()))?
+        {
+            let (images, marked) =
+                timings.time("markers", || self.step_remove_markers(work_dir, &current_images, &options, progress))?;
+            current_images = images;
+            for (page, marked) in geometry.iter_mut().zip(marked) {
+                page.marked = marked;
+            }
+        }
+
+        // Step 2: Margin Trimming (C# does this first)
+        // Note: margin_trim is a percentage, skip if 0
+        if self.config.margin_trim > 0.0 {
+            let (images, transforms) = timings.time("trim", || self.step_margin_trim(work_dir, &current_images, progress))?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        // Step 3: AI Upscaling (if enabled)
+        if self.config.upscale {
+            let (images, transforms) = timings.time("upscale", || self.step_upscale(work_dir, &current_images, progress))?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        // Step 4: Internal Resolution Normalization (if enabled)
+        // C#: Fit to 4960x7016 with Lanczos3, padding with paper color
+        if self.config.internal_resolution {
+            let (images, transforms) =
+                timings.time("normalize", || self.step_normalize(work_dir, &current_images, progress))?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        // Step 5: Deskew (if enabled) - C# does deskew AFTER normalization
+        if self.config.deskew {
+            let (images, transforms) = timings.time("deskew", || self.step_deskew(work_dir, &current_images, progress))?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        Ok((current_images, geometry))
+    }
+
+    /// Stages 6-10 that need statistics across all pages
+    fn process_document_stages<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: Vec<PathBuf>,
+        mut geometry: Vec<crate::PageGeometry>,
+        progress: &P,
+        timings: &mut StageTimings,
+    ) -> Result<ProcessedImages, PipelineError> {
+        let mut current_images = images;
+
+        // Step 6: Color Correction (if enabled)
+        if self.config.color_correction {
+            current_images = timings.time("color", || self.step_color_correction(work_dir, &current_images, progress))?;
+        }
+
+        // Step 7: Auto Contrast (if enabled)
+        if self.config.auto_contrast.is_enabled() {
+            current_images = timings.time("contrast", || self.step_auto_contrast(work_dir, &current_images, progress))?;
+        }
+
+        // Step 7a: Manual tone curve (if configured)
+        if let Some(curve) = &self.config.tone_curve {
+            current_images = timings.time("tone_curve", || self.step_tone_curve(work_dir, &current_images, curve, progress))?;
+        }
+
+        // Step 7b: Desaturate scanner color noise (if configured)
+        if let Some(threshold) = self.config.desaturate_below {
+            current_images =
+                timings.time("desaturate", || self.step_desaturate(work_dir, &current_images, threshold, progress))?;
+        }
+
+        // Pages in the minority orientation (none with uniform sizing)
+        let minority = self.mixed_size_minority(&current_images);
+
+        // Step 7d: Odd/even side registration (if register_sides enabled)
+        if self.config.register_sides {
+            let (images, transforms) = timings.time("register_sides", || {
+                self.step_register_sides(work_dir, &current_images, &minority, progress)
+            })?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        // Step 8: Tukey Fence Group Crop (if offset_alignment enabled)
+        if self.config.offset_alignment {
+            let (images, transforms) = timings.time("group_crop", || {
+                self.step_group_crop(work_dir, &current_images, &minority, progress)
+            })?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        // Step 8b: Fit to target aspect ratio (if configured)
+        let aspect_adjustments = if let Some(aspect) = self.config.target_aspect {
+            let (images, results) = timings.time("aspect", || {
+                self.step_fit_aspect(work_dir, &current_images, aspect, &minority, progress)
+            })?;
+            current_images = images;
+            record_transforms(&mut geometry, results.iter().map(aspect_transform).collect());
+            results.into_iter().map(|result| result.adjustment).collect()
+        } else {
+            vec![]
+        };
+
+        if (self.config.offset_alignment || self.config.page_labels) && !self.config.parity.is_all() {
+            progress.on_warning("Page number detection skipped for odd/even-only runs (offset alignment, page labels)");
+        }
+
+        // Step 9: Page Number Offset Calculation (search regions follow the text direction)
+        let (page_number_shift, page_number_confidence, page_labels, is_vertical) =
+            if self.detects_page_numbers() && !self.is_skipped("page_numbers") {
+                let is_vertical =
+                    timings.time("vertical_detect", || self.step_vertical_detection(&current_images, progress))?;
+                let (shift, confidence, labels) = timings.time("page_numbers", || {
+                    self.step_page_number_detection(&current_images, is_vertical, progress)
+                })?;
+                (shift, confidence, labels, Some(is_vertical))
+            } else {
+                if self.detects_page_numbers() {
+                    progress.on_warning("Page number detection skipped: tesseract not found");
+                }
+                (None, None, vec![], None)
+            };
+
+        // Step 10: Final Output (resize)
+        if self.config.output_height != 0 && self.config.output_height != 7016 {
+            let (images, transforms) =
+                timings.time("finalize", || self.step_finalize(work_dir, &current_images, &minority, progress))?;
+            current_images = images;
+            record_transforms(&mut geometry, transforms);
+        }
+
+        let mixed_size: Vec<MixedSizeDecision> = minority
+            .iter()
+            .zip(&current_images)
+            .enumerate()
+            .filter(|(_, (&is_minority, _))| is_minority)
+            .map(|(page, (_, path))| {
+                let size = image::image_dimensions(path).unwrap_or((0, 0));
+                MixedSizeDecision {
+                    page,
+                    orientation: crate::PageOrientation::of(size),
+                    size,
+                }
+            })
+            .collect();
+        for decision in &mixed_size {
+            progress.on_debug(&format!(
+                "Page {}: {} ({}), {}x{}",
+                decision.page + 1,
+                decision.orientation,
+                self.config.mixed_size.name(),
+                decision.size.0,
+                decision.size.1
+            ));
+        }
+
+        report_pages_complete(progress, 0, &current_images, true);
+
+        Ok(ProcessedImages {
+            images: current_images,
+            page_number_shift,
+            page_number_confidence,
+            aspect_adjustments,
+            is_vertical,
+            page_labels,
+            failed_pages: vec![],
+            geometry,
+            work_cache: crate::WorkCacheStats::default(),
+            mixed_size,
+            resumed_pages: 0,
+        })
+    }
+
+    /// Process a single page of a PDF through the image stages
+    ///
+    /// Extracts page `page_index` (0-based) into `work_dir` and returns the
+    /// path of the final processed image. Used for previews and A/B comparisons.
+    pub fn process_page<P: ProgressCallback>(
+        &self,
+        input: &Path,
+        page_index: usize,
+        work_dir: &Path,
+        progress: &P,
+    ) -> Result<PathBuf, PipelineError> {
+        if !input.exists() {
+            return Err(PipelineError::InputNotFound(input.to_path_buf()));
+        }
+        std::fs::create_dir_all(work_dir)?;
+
+        progress.on_step_start(&format!("Extracting page {} (DPI: {})...", page_index + 1, self.config.dpi));
+        let page_sizes: Vec<(f64, f64)> = crate::LopdfReader::new(input)
+            .map(|reader| reader.info.pages.iter().map(|page| (page.width_pt, page.height_pt)).collect())
+            .unwrap_or_default();
+        let clamped_pages: Vec<ClampedPage> =
+            ClampedPage::find(&page_sizes, self.config.dpi, self.config.max_page_dimension)
+                .into_iter()
+                .filter(|clamped| clamped.page == page_index + 1)
+                .collect();
+        for clamped in &clamped_pages {
+            progress.on_warning(&clamped.warning(self.config.max_page_dimension));
+        }
+        let extract_options = self.extract_options(&clamped_pages);
+        let extracted_dir = work_dir.join("extracted");
+        let page = crate::LopdfExtractor::extract_page_auto(input, page_index, &extracted_dir, &extract_options)
+            .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
+        progress.on_step_complete("Extracting page", &page.path.display().to_string());
+
+        let processed = self.process_images(work_dir, vec![page.path], progress, &mut StageTimings::new())?;
+        processed
+            .images
+            .into_iter()
+            .next()
+            .ok_or_else(|| PipelineError::ImageProcessingFailed("No output image".to_string()))
+    }
+
+    // ============ Processing Step Implementations ============
+
+    /// AI bridge configuration (venv from `SUPERBOOK_VENV`, seeded in deterministic mode)
+    fn bridge_config(&self) -> crate::AiBridgeConfig {
+        let venv_path = std::env::var("SUPERBOOK_VENV")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from("./venv"));
+
+        let mut builder = crate::AiBridgeConfig::builder()
+            .venv_path(venv_path)
+            .thread_budget(self.tools.thread_budget());
+        if self.config.deterministic {
+            builder = builder.seed(DETERMINISTIC_SEED);
+        }
+        builder.build()
+    }
+
+    /// Step 3: Deskew correction
+    fn step_deskew<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<Option<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start("Applying deskew correction...");
+        let deskewed_dir = work_dir.join("deskewed");
+        std::fs::create_dir_all(&deskewed_dir)?;
+
+        // Use PageEdge algorithm for scanned book pages (detects page boundary skew)
+        let deskew_options = crate::DeskewOptions::builder()
+            .algorithm(crate::DeskewAlgorithm::PageEdge)
+            .build();
+        let output_paths: Vec<PathBuf> = images
+            .iter()
+            .enumerate()
+            .map(|(idx, img)| {
+                let name = img
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
+                deskewed_dir.join(name)
+            })
+            .collect();
+
+        let results: Vec<(PathBuf, Option<crate::PageTransform>)> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .map(|(img_path, output_path)| {
+                let transform = match crate::ImageProcDeskewer::correct_skew(img_path, output_path, &deskew_options) {
+                    Ok(result) if result.corrected => Some(crate::PageTransform::Rotate {
+                        degrees: -result.detection.angle,
+                        input_width: result.original_size.0,
+                        input_height: result.original_size.1,
+                        width: result.corrected_size.0,
+                        height: result.corrected_size.1,
+                    }),
+                    Ok(_) => None,
+                    Err(_) => {
+                        std::fs::copy(img_path, output_path).ok();
+                        None
+                    }
+                };
+                (output_path.clone(), transform)
+            })
+            .collect();
+
+        progress.on_step_complete("Deskew", &format!("{} images", results.len()));
+        Ok(results.into_iter().unzip())
+    }
+
+    /// Step 2a: Remove scanner dust/hair lines
+    fn step_line_artifacts<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        progress.on_step_start("Removing scanner line artifacts...");
+        let cleaned_dir = work_dir.join("line_artifacts");
+        std::fs::create_dir_all(&cleaned_dir)?;
+
+        let options = crate::cleanup::LineArtifactOptions::default();
+        let lines = AtomicUsize::new(0);
+        let results: Vec<PathBuf> = images
+            .par_iter()
+            .enumerate()
+            .map(|(idx, img_path)| {
+                let name = img_path
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
+                let output_path = cleaned_dir.join(name);
+                match crate::cleanup::LineArtifactRemover::remove(img_path, &output_path, &options) {
+                    Ok(result) => {
+                        lines.fetch_add(result.artifacts.len(), Ordering::Relaxed);
+                        output_path
+                    }
+                    Err(e) => {
+                        progress.on_debug(&format!("Line artifact removal failed for {}: {}", img_path.display(), e));
+                        img_path.clone()
+                    }
+                }
+            })
+            .collect();
+
+        progress.on_step_complete(
+            "Line artifacts",
+            &format!("{} lines removed from {} images", lines.load(Ordering::Relaxed), results.len()),
+        );
+        Ok(results)
+    }
+
+    /// Step 2 (cont.): Composite pages with an alpha channel over
+    /// `flatten_background`
+    ///
+    /// Pages without alpha keep their input path.
+    fn step_flatten<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        // Only the header is read for pages without alpha (nearly all)
+        if !images.par_iter().any(crate::util::image_has_alpha) {
+            return Ok(images.to_vec());
+        }
+        let flattened_dir = work_dir.join("flattened");
+        std::fs::create_dir_all(&flattened_dir)?;
+
+        let background = self.config.flatten_background;
+        let results: Vec<(PathBuf, bool)> = images
+            .par_iter()
+            .enumerate()
+            .map(|(idx, img_path)| {
+                if !crate::util::image_has_alpha(img_path) {
+                    return Ok((img_path.clone(), false));
+                }
+                let name = img_path
+                    .file_stem()
+                    .map(|stem| format!("{}.png", stem.to_string_lossy()))
+                    .unwrap_or_else(|| format!("page_{:04}.png", idx));
+                let output_path = flattened_dir.join(name);
+                crate::util::load_image_flattened(img_path, background)
+                    .and_then(|image| image.save(&output_path).map_err(|e| e.to_string()))
+                    .map_err(|e| {
+                        PipelineError::ImageProcessingFailed(format!("{}: {}", img_path.display(), e))
+                    })?;
+                Ok((output_path, true))
+            })
+            .collect::<Result<_, PipelineError>>()?;
+
+        let flattened = results.iter().filter(|(_, flattened)| *flattened).count();
+        let [r, g, b] = background;
+        progress.on_debug(&format!(
+            "Transparency: {}/{} pages flattened over #{:02x}{:02x}{:02x}",
+            flattened,
+            results.len(),
+            r,
+            g,
+            b
+        ));
+        Ok(results.into_iter().map(|(path, _)| path).collect())
+    }
+
+    /// Step 2 (cont.): Invert negative scans
+    ///
+    /// Returns the page images and whether each one was inverted; pages
+    /// left as they are keep their input path.
+    fn step_invert<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<bool>), PipelineError> {
+        progress.on_step_start("Checking for negative scans...");
+        let inverted_dir = work_dir.join("inverted");
+        std::fs::create_dir_all(&inverted_dir)?;
+
+        let mode = self.config.invert;
+        let results: Vec<(PathBuf, bool)> = images
+            .par_iter()
+            .enumerate()
+            .map(|(idx, img_path)| {
+                let name = img_path
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
+                let output_path = inverted_dir.join(name);
+                match crate::NegativeScan::process(img_path, &output_path, mode) {
+                    Ok(true) => (output_path, true),
+                    Ok(false) => (img_path.clone(), false),
+                    Err(e) => {
+                        progress.on_debug(&format!("Negative scan check failed for {}: {}", img_path.display(), e));
+                        (img_path.clone(), false)
+                    }
+                }
+            })
+            .collect();
+
+        let (results, inverted): (Vec<PathBuf>, Vec<bool>) = results.into_iter().unzip();
+        progress.on_step_complete(
+            "Negative scans",
+            &format!("{}/{} images inverted", inverted.iter().filter(|&&i| i).count(), results.len()),
+        );
+        Ok((results, inverted))
+    }
+
+    /// Step 2b: Remove highlighter marks
+    ///
+    /// Returns the page images and whether marks were found on each one.
+    fn step_remove_markers<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        options: &crate::cleanup::MarkerRemovalOptions,
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<bool>), PipelineError> {
+        progress.on_step_start("Removing highlighter marks...");
+        let cleaned_dir = work_dir.join("markers");
+        std::fs::create_dir_all(&cleaned_dir)?;
+
+        let results: Vec<(PathBuf, bool)> = images
+            .par_iter()
+            .enumerate()
+            .map(|(idx, img_path)| {
+                let name = img_path
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
+                let output_path = cleaned_dir.join(name);
+                match crate::cleanup::MarkerRemover::remove(img_path, &output_path, options) {
+                    Ok(result) => (output_path, result.has_markers()),
+                    Err(e) => {
+                        progress.on_debug(&format!("Marker removal failed for {}: {}", img_path.display(), e));
+                        (img_path.clone(), false)
+                    }
+                }
+            })
+            .collect();
+
+        let (results, marked): (Vec<PathBuf>, Vec<bool>) = results.into_iter().unzip();
+        progress.on_step_complete(
+            "Highlighter marks",
+            &format!("{} of {} images had marks", marked.iter().filter(|&&m| m).count(), results.len()),
+        );
+        Ok((results, marked))
+    }
+
+    /// Step 2: Margin trimming (C#互換: 単純な固定%カット)
+    ///
+    /// C#版と同様に、各辺から指定%を単純にカットする。
+    /// 複雑なマージン検出は行わない。
+    fn step_margin_trim<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<Option<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start(&format!("Trimming margins ({}%)...", self.config.margin_trim));
+        let trimmed_dir = work_dir.join("trimmed");
+        std::fs::create_dir_all(&trimmed_dir)?;
+
+        let trim_percent = self.config.margin_trim / 100.0; // Convert to ratio
+
+        let output_paths: Vec<PathBuf> = images
+            .iter()
+            .enumerate()
+            .map(|(idx, img)| {
+                let name = img
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
+                trimmed_dir.join(name)
+            })
+            .collect();
+
+        let results: Vec<(PathBuf, Option<crate::PageTransform>)> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .map(|(img_path, output_path)| {
+                let mut transform = None;
+                // C#互換: 単純な固定%カット
+                if let Ok(img) = image::open(img_path) {
+                    let (w, h) = (img.width(), img.height());
+                    let trim_x = (w as f64 * trim_percent) as u32;
+                    let trim_y = (h as f64 * trim_percent) as u32;
+
+                    let new_x = trim_x;
+                    let new_y = trim_y;
+                    let new_w = w.saturating_sub(trim_x * 2);
+                    let new_h = h.saturating_sub(trim_y * 2);
+
+                    if new_w > 0 && new_h > 0 {
+                        let cropped = img.crop_imm(new_x, new_y, new_w, new_h);
+                        cropped.save(output_path).ok();
+                        transform = Some(crate::PageTransform::Crop {
+                            x: new_x,
+                            y: new_y,
+                            width: new_w,
+                            height: new_h,
+                        });
+                    } else {
+                        img.save(output_path).ok();
+                    }
+                } else {
+                    std::fs::copy(img_path, output_path).ok();
+                }
+                (output_path.clone(), transform)
+            })
+            .collect();
+
+        progress.on_step_complete("Margin trim", &format!("{} images", results.len()));
+        Ok(results.into_iter().unzip())
+    }
+
+    /// Step 5: AI Upscaling
+    fn step_upscale<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<Option<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start("AI Upscaling (RealESRGAN)...");
+        let _gpu = self.acquire_gpu("Upscaling", progress);
+        let upscaled_dir = work_dir.join("upscaled");
+        std::fs::create_dir_all(&upscaled_dir)?;
+
+        // Try to initialize RealESRGAN
+        let bridge = match crate::SubprocessBridge::new(self.bridge_config()) {
+            Ok(b) => b,
+            Err(e) => {
+                progress.on_debug(&format!("RealESRGAN not available: {}", e));
+                return Ok((images.to_vec(), vec![None; images.len()]));
+            }
+        };
+
+        let esrgan = crate::RealEsrgan::new(bridge);
+        let mut options = crate::RealEsrganOptions::builder().scale(2);
+        if self.config.gpu {
+            options = options.gpu_id(0);
+        }
+        let options = options.build();
+
+        match esrgan.upscale_batch(images, &upscaled_dir, &options, None) {
+            Ok(result) => {
+                progress.on_step_complete("Upscaling", &format!("{} images", result.successful.len()));
+                // Pages that failed to upscale keep their input image
+                Ok(images
+                    .iter()
+                    .map(|img_path| match result.successful.iter().find(|r| &r.input_path == img_path) {
+                        Some(r) => (
+                            r.output_path.clone(),
+                            Some(crate::PageTransform::Scale {
+                                x: r.upscaled_size.0 as f64 / r.original_size.0.max(1) as f64,
+                                y: r.upscaled_size.1 as f64 / r.original_size.1.max(1) as f64,
+                            }),
+                        ),
+                        None => (img_path.clone(), None),
+                    })
+                    .unzip())
+            }
+            Err(e) => {
+                progress.on_debug(&format!("Upscaling failed: {}", e));
+                Ok((images.to_vec(), vec![None; images.len()]))
+            }
+        }
+    }
+
+    /// Step 6: Internal resolution normalization
+    fn step_normalize<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<Vec<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start("Normalizing to internal resolution (4960x7016)...");
+        let normalized_dir = work_dir.join("normalized");
+        std::fs::create_dir_all(&normalized_dir)?;
+
+        let normalize_options = crate::NormalizeOptions::builder()
+            .target_width(4960)
+            .target_height(7016)
+            .resampler(self.config.resize_filter)
+            .build();
+
+        let output_paths: Vec<PathBuf> = images
+            .iter()
+            .enumerate()
+            .map(|(idx, img)| {
+                let stem = img
+                    .file_stem()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| format!("page_{:04}", idx));
+                normalized_dir.join(format!("{}.png", stem))
+            })
+            .collect();
+
+        let completed = Arc::new(AtomicUsize::new(0));
+        let _total = images.len();
+
+        let results: Vec<(PathBuf, Vec<crate::PageTransform>)> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .map(|(img_path, output_path)| {
+                let transforms = match crate::ImageNormalizer::normalize(img_path, output_path, &normalize_options) {
+                    Ok(result) => normalize_transforms(&result),
+                    Err(_) => {
+                        std::fs::copy(img_path, output_path).ok();
+                        vec![]
+                    }
+                };
+                completed.fetch_add(1, Ordering::Relaxed);
+                (output_path.clone(), transforms)
+            })
+            .collect();
+
+        progress.on_step_complete("Normalization", &format!("{} images", results.len()));
+        Ok(results.into_iter().unzip())
+    }
+
+    /// Step 7: Color correction
+    fn step_color_correction<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        progress.on_step_start("Analyzing color statistics...");
+        let color_corrected_dir = work_dir.join("color_corrected");
+        std::fs::create_dir_all(&color_corrected_dir)?;
+
+        // Collect color statistics
+        let stats_results: Vec<_> = images
+            .par_iter()
+            .map(|img_path| crate::ColorAnalyzer::calculate_stats(img_path))
+            .collect();
+
+        let all_stats: Vec<_> = stats_results
+            .into_iter()
+            .filter_map(|r| r.ok())
+            .collect();
+
+        if all_stats.is_empty() {
+            progress.on_debug("Color analysis failed, skipping correction");
+            return Ok(images.to_vec());
+        }
+
+        let global_param = crate::ColorAnalyzer::decide_global_adjustment(&all_stats);
+
+        // Debug: 実際のカラー補正パラメータを出力
+        progress.on_debug(&format!(
+            "Color params: scale=({:.3},{:.3},{:.3}), offset=({:.1},{:.1},{:.1}), ghost_thr={}, paper=({},{},{})",
+            global_param.scale_r, global_param.scale_g, global_param.scale_b,
+            global_param.offset_r, global_param.offset_g, global_param.offset_b,
+            global_param.ghost_suppress_threshold,
+            global_param.paper_r, global_param.paper_g, global_param.paper_b
+        ));
+
+        let output_paths: Vec<PathBuf> = (0..images.len())
+            .map(|i| color_corrected_dir.join(format!("page_{:04}.png", i)))
+            .collect();
+
+        let results: Vec<PathBuf> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .map(|(img_path, output_path)| {
+                if let Ok(img) = image::open(img_path) {
+                    let mut rgb_img = img.to_rgb8();
+                    crate::ColorAnalyzer::apply_adjustment(&mut rgb_img, &global_param);
+                    rgb_img.save(output_path).ok();
+                } else {
+                    std::fs::copy(img_path, output_path).ok();
+                }
+                output_path.clone()
+            })
+            .collect();
+
+        progress.on_step_complete("Color correction", &format!("{} images", results.len()));
+        Ok(results)
+    }
+
+    /// Step 7: Auto contrast
+    fn step_auto_contrast<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        progress.on_step_start(&format!(
+            "Applying auto contrast ({})...",
+            self.config.auto_contrast.describe()
+        ));
+        let contrast_dir = work_dir.join("auto_contrast");
+        std::fs::create_dir_all(&contrast_dir)?;
+
+        let options = crate::AutoContrastOptions::new(self.config.auto_contrast)
+            .with_per_channel(self.config.auto_contrast_per_channel);
+
+        // Sampled-page before/after previews (kept only with --save-debug)
+        if self.config.save_debug {
+            let preview_dir = work_dir.join("auto_contrast_preview");
+            match crate::ContrastEnhancer::preview(images, &preview_dir, &options, CONTRAST_PREVIEW_SAMPLES) {
+                Ok(previews) => progress.on_debug(&format!(
+                    "Auto contrast previews: {} pages in {}",
+                    previews.len(),
+                    preview_dir.display()
+                )),
+                Err(e) => progress.on_debug(&format!("Auto contrast preview failed: {}", e)),
+            }
+        }
+
+        let output_paths: Vec<PathBuf> = (0..images.len())
+            .map(|i| contrast_dir.join(format!("page_{:04}.png", i)))
+            .collect();
+
+        let applied = AtomicUsize::new(0);
+        let results: Vec<PathBuf> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .map(|(img_path, output_path)| {
+                match crate::ContrastEnhancer::process(img_path, output_path, &options) {
+                    Ok(result) => {
+                        if result.applied {
+                            applied.fetch_add(1, Ordering::Relaxed);
+                        }
+                        output_path.clone()
+                    }
+                    Err(_) => img_path.clone(),
+                }
+            })
+            .collect();
+
+        progress.on_step_complete(
+            "Auto contrast",
+            &format!("{}/{} images adjusted", applied.load(Ordering::Relaxed), results.len()),
+        );
+        Ok(results)
+    }
+
+    /// Step 7a: Apply the manual tone curve LUT
+    fn step_tone_curve<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        curve: &crate::ToneCurve,
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        progress.on_step_start(&format!("Applying tone curve ({})...", curve.describe()));
+        let curve_dir = work_dir.join("tone_curve");
+        std::fs::create_dir_all(&curve_dir)?;
+
+        let results: Vec<PathBuf> = images
+            .par_iter()
+            .enumerate()
+            .map(|(i, img_path)| {
+                let output_path = curve_dir.join(format!("page_{:04}.png", i));
+                match curve.process(img_path, &output_path) {
+                    Ok(()) => output_path,
+                    Err(e) => {
+                        progress.on_debug(&format!("Tone curve failed on page {}: {}", i + 1, e));
+                        img_path.clone()
+                    }
+                }
+            })
+            .collect();
+
+        progress.on_step_complete("Tone curve", &format!("{} images", results.len()));
+        Ok(results)
+    }
+
+    /// Step 7b: Gray out low-saturation color noise around text
+    fn step_desaturate<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        threshold: f32,
+        progress: &P,
+    ) -> Result<Vec<PathBuf>, PipelineError> {
+        progress.on_step_start(&format!("Desaturating colors below {:.2}...", threshold));
+        let desaturated_dir = work_dir.join("desaturated");
+        std::fs::create_dir_all(&desaturated_dir)?;
+
+        let output_paths: Vec<PathBuf> = (0..images.len())
+            .map(|i| desaturated_dir.join(format!("page_{:04}.png", i)))
+            .collect();
+
+        let changed = AtomicUsize::new(0);
+        let results: Vec<PathBuf> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .map(|(img_path, output_path)| {
+                match crate::ColorAnalyzer::desaturate_file(img_path, output_path, threshold) {
+                    Ok(pixels) => {
+                        if pixels > 0 {
+                            changed.fetch_add(1, Ordering::Relaxed);
+                        }
+                        output_path.clone()
+                    }
+                    Err(_) => img_path.clone(),
+                }
+            })
+            .collect();
+
+        progress.on_step_complete(
+            "Desaturate",
+            &format!("{}/{} images adjusted", changed.load(Ordering::Relaxed), results.len()),
+        );
+        Ok(results)
+    }
+
+    /// Step 7d: Shift odd and even pages so their content lines up
     ///
-    /// This is the main entry point for PDF processing.
-    pub fn process_with_progress<P: ProgressCallback>(
+    /// The shift between the sides comes from the odd/even group crop
+    /// regions of the main orientation group ([`GroupCropAnalyzer::side_shift`]);
+    /// each side moves by half of it. Minority orientation pages and
+    /// parity runs (one side only) are left unchanged.
+    ///
+    /// [`GroupCropAnalyzer::side_shift`]: crate::GroupCropAnalyzer::side_shift
+    fn step_register_sides<P: ProgressCallback>(
         &self,
-        input: &Path,
-        output_dir: &Path,
+        work_dir: &Path,
+        images: &[PathBuf],
+        minority: &[bool],
         progress: &P,
-    ) -> Result<PipelineResult, PipelineError> {
-        let start_time = Instant::now();
+    ) -> Result<(Vec<PathBuf>, Vec<Option<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start("Registering odd/even sides...");
+        let unchanged = || Ok((images.to_vec(), vec![None; images.len()]));
+        if !self.config.parity.is_all() {
+            progress.on_step_complete("Side registration", "skipped (one side only)");
+            return unchanged();
+        }
 
-        // Validate input
-        if !input.exists() {
-            return Err(PipelineError::InputNotFound(input.to_path_buf()));
+        let main: Vec<usize> = (0..images.len()).filter(|&i| !minority.get(i).copied().unwrap_or(false)).collect();
+        let pages: Vec<PathBuf> = main.iter().map(|&i| images[i].clone()).collect();
+        let threads = match self.config.thread_budget() {
+            Some(_) => 0,
+            None => self.config.threads.unwrap_or(0),
+        };
+        let report = |done: usize, _: usize| progress.on_step_progress(done, images.len());
+        let mut bounding_boxes =
+            crate::GroupCropAnalyzer::detect_all_bounding_boxes_with(&pages, 240, threads, Some(&report));
+        for bbox in &mut bounding_boxes {
+            bbox.is_odd = main[bbox.page_number - 1] % 2 == 0;
         }
+        let shift = crate::GroupCropAnalyzer::side_shift(&bounding_boxes, &self.config.group_crop).unwrap_or(0);
+        if shift == 0 {
+            progress.on_step_complete("Side registration", "no shift");
+            return unchanged();
+        }
+        progress.on_debug(&format!("Odd pages sit {}px right of even pages", shift));
 
-        // Create output directory
-        std::fs::create_dir_all(output_dir)?;
+        let registered_dir = work_dir.join("registered");
+        std::fs::create_dir_all(&registered_dir)?;
+        // Odd pages move left by half the shift, even pages right by the rest
+        let (odd_dx, even_dx) = (-(shift / 2), shift - shift / 2);
+        let results: Vec<(PathBuf, Option<crate::PageTransform>)> = images
+            .par_iter()
+            .enumerate()
+            .map(|(i, img_path)| {
+                if minority.get(i).copied().unwrap_or(false) {
+                    return (img_path.clone(), None);
+                }
+                let dx = if i % 2 == 0 { odd_dx } else { even_dx };
+                let output_path = registered_dir.join(format!("page_{:04}.png", i));
+                match image::open(img_path) {
+                    Ok(img) if dx != 0 && shift_page(&img, dx).save(&output_path).is_ok() => {
+                        let transform = crate::PageTransform::Pad {
+                            x: dx,
+                            y: 0,
+                            width: img.width(),
+                            height: img.height(),
+                        };
+                        (output_path, Some(transform))
+                    }
+                    _ => (img_path.clone(), None),
+                }
+            })
+            .collect();
 
-        let output_path = self.get_output_path(input, output_dir);
-        let work_dir = self.get_work_dir(input, output_dir);
-        std::fs::create_dir_all(&work_dir)?;
+        progress.on_step_complete("Side registration", &format!("{}px shift", shift));
+        Ok(results.into_iter().unzip())
+    }
 
-        // Step 1: Read PDF metadata
-        progress.on_step_start("Reading PDF...");
-        let reader = crate::LopdfReader::new(input)
-            .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
-        let total_pages = reader.info.page_count;
-        progress.on_step_complete("Reading PDF", &format!("{} pages", total_pages));
+    /// Step 8: Tukey fence group crop
+    ///
+    /// Each orientation group of [`mixed_size_groups`] gets its own crop
+    /// regions; pages outside every group are left uncropped.
+    fn step_group_crop<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        minority: &[bool],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<Option<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start("Detecting text bounding boxes...");
+        let cropped_dir = work_dir.join("cropped");
+        std::fs::create_dir_all(&cropped_dir)?;
 
-        // Step 2: Extract images
-        progress.on_step_start(&format!("Extracting images (DPI: {})...", self.config.dpi));
-        let extract_options = crate::ExtractOptions::builder()
-            .dpi(self.config.dpi)
-            .build();
-        let extracted_dir = work_dir.join("extracted");
-        std::fs::create_dir_all(&extracted_dir)?;
+        // Pages of a parity run all belong to one side of the book. Within a
+        // thread budget the pool is already sized; otherwise honor `threads`
+        let parity = self.config.parity;
+        let threads = match self.config.thread_budget() {
+            Some(_) => 0,
+            None => self.config.threads.unwrap_or(0),
+        };
+        let mut regions: Vec<Option<crate::GroupCropRegion>> = vec![None; images.len()];
+        let mut detected = 0;
+        for group in mixed_size_groups(self.config.mixed_size, minority) {
+            let pages: Vec<PathBuf> = group.iter().map(|&i| images[i].clone()).collect();
+            let report = |done: usize, _: usize| progress.on_step_progress(detected + done, images.len());
+            let mut bounding_boxes =
+                crate::GroupCropAnalyzer::detect_all_bounding_boxes_with(&pages, 240, threads, Some(&report));
+            detected += pages.len();
+            for bbox in &mut bounding_boxes {
+                bbox.is_odd = parity.is_odd_at(group[bbox.page_number - 1]);
+            }
+            if bounding_boxes.is_empty() {
+                continue;
+            }
 
-        let mut extracted_pages = crate::LopdfExtractor::extract_auto(input, &extracted_dir, &extract_options)
-            .map_err(|e| PipelineError::ExtractionFailed(e.to_string()))?;
+            // Landscape groups are limited by the internal page turned by 90°
+            let landscape = image::image_dimensions(&images[group[0]])
+                .is_ok_and(|size| crate::PageOrientation::of(size) == crate::PageOrientation::Landscape);
+            let (max_width, max_height) = if landscape { (7016, 4960) } else { (4960, 7016) };
+            let unified = crate::GroupCropAnalyzer::unify_and_expand_regions(
+                &bounding_boxes,
+                &self.config.group_crop,
+                5, // 5% margin expansion
+                max_width,
+                max_height,
+            );
+            for &i in &group {
+                let region = if parity.is_odd_at(i) { &unified.odd_region } else { &unified.even_region };
+                regions[i] = Some(region.clone());
+            }
+        }
+        progress.on_debug(&format!(
+            "Crop regions found for {}/{} pages",
+            regions.iter().filter(|region| region.is_some()).count(),
+            images.len()
+        ));
 
-        // Apply max_pages limit
-        if let Some(max_pages) = self.config.max_pages {
-            if extracted_pages.len() > max_pages {
-                progress.on_debug(&format!("Limiting to {} pages (--max-pages)", max_pages));
-                extracted_pages.truncate(max_pages);
+        if regions.iter().all(Option::is_none) {
+            progress.on_debug("No bounding boxes detected, skipping crop");
+            return Ok((images.to_vec(), vec![None; images.len()]));
+        }
+
+        let output_paths: Vec<PathBuf> = (0..images.len())
+            .map(|i| cropped_dir.join(format!("page_{:04}.png", i)))
+            .collect();
+
+        let results: Vec<(PathBuf, Option<crate::PageTransform>)> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .zip(regions.par_iter())
+            .map(|((img_path, output_path), region)| {
+                let Some(region) = region else {
+                    std::fs::copy(img_path, output_path).ok();
+                    return (output_path.clone(), None);
+                };
+
+                if let Ok(img) = image::open(img_path) {
+                    let (width, height) = (
+                        region.width.min(img.width() - region.left),
+                        region.height.min(img.height() - region.top),
+                    );
+                    let cropped = img.crop_imm(region.left, region.top, width, height);
+                    cropped.save(output_path).ok();
+                    let transform = crate::PageTransform::Crop {
+                        x: region.left,
+                        y: region.top,
+                        width,
+                        height,
+                    };
+                    (output_path.clone(), Some(transform))
+                } else {
+                    std::fs::copy(img_path, output_path).ok();
+                    (output_path.clone(), None)
+                }
+            })
+            .collect();
+
+        progress.on_step_complete("Group crop", &format!("{} images", results.len()));
+        Ok(results.into_iter().unzip())
+    }
+
+    /// Step 8b: Fit each page to the target aspect ratio
+    fn step_fit_aspect<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        aspect: (u32, u32),
+        minority: &[bool],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<crate::AspectFitResult>), PipelineError> {
+        progress.on_step_start(&format!("Fitting pages to {}:{}...", aspect.0, aspect.1));
+        let fitted_dir = work_dir.join("aspect");
+        std::fs::create_dir_all(&fitted_dir)?;
+
+        let results: Vec<(PathBuf, crate::AspectFitResult)> = images
+            .par_iter()
+            .enumerate()
+            .map(|(idx, img_path)| {
+                let name = img_path
+                    .file_name()
+                    .map(|n| n.to_os_string())
+                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
+                let output_path = fitted_dir.join(name);
+                // Minority pages fit the ratio turned by 90° (or keep their shape)
+                let page_aspect = match self.config.mixed_size {
+                    _ if !minority.get(idx).copied().unwrap_or(false) => Some(aspect),
+                    MixedSize::PerOrientation => Some((aspect.1, aspect.0)),
+                    _ => None,
+                };
+                match page_aspect.map(|page_aspect| crate::PageFinalizer::fit_aspect(img_path, &output_path, page_aspect)) {
+                    Some(Ok(result)) => (output_path, result),
+                    _ => {
+                        std::fs::copy(img_path, &output_path).ok();
+                        let size = image::image_dimensions(&output_path).unwrap_or((0, 0));
+                        let result = crate::AspectFitResult {
+                            original_size: size,
+                            final_size: size,
+                            adjustment: crate::AspectAdjustment::Unchanged,
+                            offset: (0, 0),
+                        };
+                        (output_path, result)
+                    }
+                }
+            })
+            .collect();
+        let (output_paths, results): (Vec<PathBuf>, Vec<crate::AspectFitResult>) = results.into_iter().unzip();
+        let adjustments: Vec<crate::AspectAdjustment> = results.iter().map(|result| result.adjustment).collect();
+
+        for (idx, adjustment) in adjustments.iter().enumerate() {
+            if *adjustment != crate::AspectAdjustment::Unchanged {
+                progress.on_debug(&format!("Page {}: {}", idx + 1, adjustment));
             }
         }
-        let page_count = extracted_pages.len();
-        progress.on_step_complete("Extracting images", &format!("{} pages", page_count));
+        let padded = adjustments.iter().filter(|a| a.is_padded()).count();
+        let cropped = adjustments.iter().filter(|a| a.is_cropped()).count();
+        progress.on_step_complete("Aspect ratio", &format!("{} padded, {} cropped", padded, cropped));
+        Ok((output_paths, results))
+    }
 
-        // Convert to PathBuf list
-        let mut current_images: Vec<PathBuf> = extracted_pages.iter().map(|p| p.path.clone()).collect();
+    /// Pages whose orientation differs from the majority (all false with
+    /// uniform `mixed_size`)
+    fn mixed_size_minority(&self, images: &[PathBuf]) -> Vec<bool> {
+        if self.config.mixed_size.is_uniform() {
+            return vec![false; images.len()];
+        }
+        let sizes: Vec<(u32, u32)> =
+            images.iter().map(|path| image::image_dimensions(path).unwrap_or((0, 0))).collect();
+        let main = crate::PageOrientation::majority(&sizes);
+        sizes.iter().map(|&size| crate::PageOrientation::of(size) != main).collect()
+    }
 
-        // ================================================================
-        // C#版互換処理順序:
-        // 1. PDF抽出 (上で完了)
-        // 2. マージントリム (0.5%)
-        // 3. AI超解像 (RealESRGAN 2x)
-        // 4. 内部解像度正規化 (4960x7016)
-        // 5. 傾き補正 (Deskew) ← C#はここで実行
-        // 6. 色補正
-        // ================================================================
+    /// Whether Step 9 (page number detection) runs (not for parity runs,
+    /// whose pages are not consecutive)
+    fn detects_page_numbers(&self) -> bool {
+        (self.config.offset_alignment || self.config.page_labels) && self.config.parity.is_all()
+    }
+
+    /// Step 9: Page number detection (returns the shift, the confidence of
+    /// the offset analysis and, if `page_labels` is set, the page label ranges)
+    #[allow(clippy::type_complexity)]
+    fn step_page_number_detection<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        is_vertical: bool,
+        progress: &P,
+    ) -> Result<(Option<i32>, Option<f64>, Vec<crate::PageLabelRange>), PipelineError> {
+        progress.on_step_start("Detecting page numbers...");
+
+        let page_options = crate::PageNumberOptions::builder()
+            .vertical_text(is_vertical)
+            .tools(self.tools.clone())
+            .build();
+
+        // Pages in parallel; the Tesseract calls are bounded by `ocr_concurrency`
+        let mut page_detections: Vec<_> = images
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, img_path)| crate::TesseractPageDetector::detect_single(img_path, i, &page_options).ok())
+            .collect();
+        crate::TesseractPageDetector::refine_detections(images, &mut page_detections, &page_options);
+
+        if page_detections.is_empty() {
+            progress.on_step_complete("Page number detection", "no pages detected");
+            return Ok((None, None, vec![]));
+        }
+
+        let first_img = image::open(&images[0]).ok();
+        let img_height = first_img.as_ref().map(|img| img.height()).unwrap_or(7016);
+
+        let analysis = crate::PageOffsetAnalyzer::analyze_offsets(&page_detections, img_height);
+        let shift_msg = if analysis.page_number_shift == 0 {
+            "aligned".to_string()
+        } else {
+            format!("offset: {}px", analysis.page_number_shift)
+        };
+        let direction = if is_vertical { "vertical" } else { "horizontal" };
+        let repaired = if analysis.repaired_count > 0 {
+            format!(", {} repaired", analysis.repaired_count)
+        } else {
+            String::new()
+        };
+        progress.on_step_complete(
+            "Page number detection",
+            &format!(
+                "{} ({}/{} matched{}, {} text)",
+                shift_msg,
+                analysis.match_count,
+                page_detections.len(),
+                repaired,
+                direction
+            ),
+        );
+
+        let page_labels = if self.config.page_labels {
+            let ranges =
+                crate::PageLabeler::from_detections(&page_detections, analysis.page_number_shift, images.len());
+            if !ranges.is_empty() {
+                let labels = crate::PageLabeler::labels(&ranges, images.len());
+                progress.on_debug(&format!("Page labels: {} ranges (first page \"{}\")", ranges.len(), labels[0]));
+            }
+            ranges
+        } else {
+            vec![]
+        };
+
+        Ok((Some(analysis.page_number_shift), Some(analysis.confidence), page_labels))
+    }
+
+    /// Step 10: Finalize output
+    fn step_finalize<P: ProgressCallback>(
+        &self,
+        work_dir: &Path,
+        images: &[PathBuf],
+        minority: &[bool],
+        progress: &P,
+    ) -> Result<(Vec<PathBuf>, Vec<Option<crate::PageTransform>>), PipelineError> {
+        progress.on_step_start(&format!("Finalizing output (height: {})...", self.config.output_height));
+        let finalized_dir = work_dir.join("finalized");
+        std::fs::create_dir_all(&finalized_dir)?;
 
-        // Step 2: Margin Trimming (C# does this first)
-        // Note: margin_trim is a percentage, skip if 0
-        if self.config.margin_trim > 0.0 {
-            current_images = self.step_margin_trim(&work_dir, &current_images, progress)?;
-        }
+        let heights = if minority.contains(&true) {
+            let sizes: Vec<(u32, u32)> =
+                images.iter().map(|path| image::image_dimensions(path).unwrap_or((0, 0))).collect();
+            finalize_heights(self.config.mixed_size, &sizes, minority, self.config.output_height)
+        } else {
+            vec![self.config.output_height; images.len()]
+        };
 
-        // Step 3: AI Upscaling (if enabled)
-        if self.config.upscale {
-            current_images = self.step_upscale(&work_dir, &current_images, progress)?;
-        }
+        let output_paths: Vec<PathBuf> = (0..images.len())
+            .map(|i| finalized_dir.join(format!("page_{:04}.png", i)))
+            .collect();
 
-        // Step 4: Internal Resolution Normalization (if enabled)
-        // C#: Fit to 4960x7016 with Lanczos3, padding with paper color
-        if self.config.internal_resolution {
-            current_images = self.step_normalize(&work_dir, &current_images, progress)?;
-        }
+        let results: Vec<(PathBuf, Option<crate::PageTransform>)> = images
+            .par_iter()
+            .zip(output_paths.par_iter())
+            .zip(heights.par_iter())
+            .map(|((img_path, output_path), &height)| {
+                let finalize_options = crate::FinalizeOptions::builder()
+                    .target_height(height)
+                    .resampler(self.config.resize_filter)
+                    .build();
+                let transform = match crate::PageFinalizer::finalize(img_path, output_path, &finalize_options, None, 0, 0) {
+                    Ok(result) => Some(crate::PageTransform::Scale {
+                        x: result.final_size.0 as f64 / result.original_size.0.max(1) as f64,
+                        y: result.final_size.1 as f64 / result.original_size.1.max(1) as f64,
+                    }),
+                    Err(_) => {
+                        std::fs::copy(img_path, output_path).ok();
+                        None
+                    }
+                };
+                (output_path.clone(), transform)
+            })
+            .collect();
 
-        // Step 5: Deskew (if enabled) - C# does deskew AFTER normalization
-        if self.config.deskew {
-            current_images = self.step_deskew(&work_dir, &current_images, progress)?;
-        }
+        progress.on_step_complete("Output finalized", &format!("{} pages", results.len()));
+        Ok(results.into_iter().unzip())
+    }
 
-        // Step 6: Color Correction (if enabled)
-        if self.config.color_correction {
-            current_images = self.step_color_correction(&work_dir, &current_images, progress)?;
+    /// Step 11: Vertical text detection
+    fn step_vertical_detection<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Result<bool, PipelineError> {
+        let forced = self.config.text_direction.forced_vertical();
+        if images.is_empty() {
+            return Ok(forced.unwrap_or(false));
         }
 
-        // Step 8: Tukey Fence Group Crop (if offset_alignment enabled)
-        if self.config.offset_alignment {
-            current_images = self.step_group_crop(&work_dir, &current_images, progress)?;
+        progress.on_step_start("Detecting text direction...");
+
+        let mut gray_images = Vec::new();
+        for img_path in images.iter().take(20) {
+            if let Ok(img) = image::open(img_path) {
+                gray_images.push(img.to_luma8());
+            }
         }
 
-        // Step 9: Page Number Offset Calculation
-        let page_number_shift = if self.config.offset_alignment {
-            self.step_page_number_detection(&current_images, progress)?
-        } else {
+        let detected = if gray_images.is_empty() {
             None
+        } else {
+            crate::detect_book_vertical_writing(&gray_images, &crate::VerticalDetectOptions::default())
+                .ok()
+                .map(|result| result.is_vertical)
+        };
+        let name = |is_vertical: bool| if is_vertical { "vertical" } else { "horizontal" };
+
+        let Some(is_vertical) = forced else {
+            let message = match detected {
+                Some(is_vertical) => name(is_vertical),
+                None if gray_images.is_empty() => "no images to analyze",
+                None => "detection failed",
+            };
+            progress.on_step_complete("Text direction", message);
+            return Ok(detected.unwrap_or(false));
         };
 
-        // Step 10: Final Output (resize)
-        if self.config.output_height != 0 && self.config.output_height != 7016 {
-            current_images = self.step_finalize(&work_dir, &current_images, progress)?;
+        if detected == Some(!is_vertical) {
+            progress.on_warning(&format!(
+                "Text direction forced to {}, but the pages look {}",
+                name(is_vertical),
+                name(!is_vertical)
+            ));
         }
+        progress.on_step_complete("Text direction", &format!("{} (forced)", name(is_vertical)));
+        Ok(is_vertical)
+    }
 
-        // Step 11: Vertical Text Detection
-        let is_vertical = self.step_vertical_detection(&current_images, progress)?;
+    /// Step 12: OCR with YomiToku
+    fn step_ocr<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        skip: &[bool],
+        progress: &P,
+    ) -> Result<Vec<Option<crate::OcrResult>>, PipelineError> {
+        if !skip.is_empty() && skip.iter().all(|&skip| skip) {
+            progress.on_step_complete("OCR", "every page skipped");
+            return Ok(vec![None; images.len()]);
+        }
+        progress.on_step_start("Running OCR (YomiToku)...");
+        let _gpu = self.acquire_gpu("OCR", progress);
 
-        // Step 12: OCR with YomiToku (if enabled)
-        let ocr_results = if self.config.ocr {
-            self.step_ocr(&current_images, progress)?
-        } else {
-            vec![]
+        let bridge = match crate::SubprocessBridge::new(self.bridge_config()) {
+            Ok(b) => b,
+            Err(e) => {
+                progress.on_debug(&format!("YomiToku not available: {}", e));
+                return Ok(vec![]);
+            }
         };
 
-        // Step 13: Generate PDF
-        progress.on_step_start("Generating output PDF...");
-        self.step_generate_pdf(&current_images, &output_path, &reader.info, &ocr_results, progress)?;
-
-        // Get output file size
-        let output_size = std::fs::metadata(&output_path)
-            .map(|m| m.len())
-            .unwrap_or(0);
-        progress.on_step_complete("Generating PDF", &format!("{} bytes", output_size));
+        let yomitoku = crate::YomiToku::new(bridge);
+        let mut ocr_opts = crate::YomiTokuOptions::builder();
+        if self.config.gpu {
+            ocr_opts = ocr_opts.use_gpu(true).gpu_id(0);
+        }
+        let ocr_opts = ocr_opts.build();
 
-        // Cleanup work directory (unless save_debug)
-        if !self.config.save_debug {
-            std::fs::remove_dir_all(&work_dir).ok();
+        let mut results = Vec::new();
+        for (index, img_path) in images.iter().enumerate() {
+            if skip.get(index).copied().unwrap_or(false) {
+                results.push(None);
+                continue;
+            }
+            match yomitoku.ocr(img_path, &ocr_opts) {
+                Ok(result) => results.push(Some(result)),
+                Err(_) => results.push(None),
+            }
         }
 
-        let elapsed = start_time.elapsed().as_secs_f64();
+        let skipped = skip.iter().filter(|&&skip| skip).count();
+        let success_count = results.iter().filter(|r| r.is_some()).count();
+        let confidence: Vec<Option<f32>> = results.iter().map(|r| r.as_ref().map(|r| r.mean_confidence())).collect();
+        let low_pages = crate::cache::low_confidence_pages(&confidence, self.config.ocr_min_confidence);
+        for page in &low_pages {
+            if let Some(Some(c)) = confidence.get(page - 1) {
+                progress.on_debug(&format!("Page {}: OCR confidence {:.0}%", page, c * 100.0));
+            }
+        }
+        progress.on_step_complete(
+            "OCR",
+            &format!(
+                "{}/{} pages, {} below {:.0}% confidence, {} skipped",
+                success_count,
+                results.len() - skipped,
+                low_pages.len(),
+                self.config.ocr_min_confidence,
+                skipped
+            ),
+        );
+        Ok(results)
+    }
 
-        Ok(PipelineResult::new(
-            page_count,
-            page_number_shift,
-            is_vertical,
-            elapsed,
-            output_path,
-            output_size,
-        ))
+    /// Step 12 (pre): Pages left out of OCR, outside `ocr_pages` (source
+    /// page numbers) or predominantly pictures with `ocr_skip_pictures`
+    fn step_ocr_selection<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        failed_pages: &[usize],
+        progress: &P,
+    ) -> Vec<bool> {
+        let mut skip: Vec<bool> = match &self.config.ocr_pages {
+            Some(ranges) => self
+                .physical_pages(failed_pages)
+                .take(images.len())
+                .map(|page| !ranges.contains(page + 1))
+                .collect(),
+            None => vec![false; images.len()],
+        };
+        if self.config.ocr_skip_pictures {
+            let pictures =
+                crate::ColorAnalyzer::detect_picture_pages(images, crate::color_stats::DEFAULT_PICTURE_PAGE_RATIO);
+            for (index, (skip, picture)) in skip.iter_mut().zip(pictures).enumerate() {
+                if picture && !*skip {
+                    progress.on_debug(&format!("Page {}: picture page, OCR skipped", index + 1));
+                    *skip = true;
+                }
+            }
+        }
+        skip
     }
 
-    // ============ Processing Step Implementations ============
+    /// Step 1b: Read the source text layer; Some only if it can replace OCR
+    fn step_probe_text_layer<P: ProgressCallback>(&self, input: &Path, progress: &P) -> Option<crate::TextLayer> {
+        progress.on_step_start("Checking for an existing text layer...");
+        let layer = match crate::TextLayer::read_with_tools(input, &self.tools) {
+            Ok(layer) => layer,
+            Err(e) => {
+                progress.on_step_complete("Text layer", &format!("not readable ({}); OCR as configured", e));
+                return None;
+            }
+        };
+        let summary = format!(
+            "{}/{} pages with text, {} characters",
+            layer.pages_with_text(),
+            layer.pages.len(),
+            layer.char_count()
+        );
+        if layer.is_sufficient() {
+            progress.on_step_complete("Text layer", &format!("{}; reusing it instead of OCR", summary));
+            Some(layer)
+        } else {
+            progress.on_step_complete("Text layer", &format!("{}; OCR as configured", summary));
+            None
+        }
+    }
 
-    /// Step 3: Deskew correction
-    fn step_deskew<P: ProgressCallback>(
+    /// Step 12 (alternative): OCR results from the source text layer,
+    /// scaled to the final page images
+    fn step_reuse_text_layer<P: ProgressCallback>(
         &self,
-        work_dir: &Path,
+        layer: &crate::TextLayer,
         images: &[PathBuf],
         progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start("Applying deskew correction...");
-        let deskewed_dir = work_dir.join("deskewed");
-        std::fs::create_dir_all(&deskewed_dir)?;
-
-        // Use PageEdge algorithm for scanned book pages (detects page boundary skew)
-        let deskew_options = crate::DeskewOptions::builder()
-            .algorithm(crate::DeskewAlgorithm::PageEdge)
-            .build();
-        let output_paths: Vec<PathBuf> = images
+    ) -> Vec<Option<crate::OcrResult>> {
+        let results: Vec<Option<crate::OcrResult>> = images
             .iter()
             .enumerate()
-            .map(|(idx, img)| {
-                let name = img
-                    .file_name()
-                    .map(|n| n.to_os_string())
-                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
-                deskewed_dir.join(name)
+            .map(|(index, path)| {
+                let page = layer.pages.get(index).filter(|page| page.has_text())?;
+                let (width, height) = image::image_dimensions(path).ok()?;
+                Some(page.to_ocr_result(path, width, height))
             })
             .collect();
+        progress.on_debug(&format!(
+            "OCR skipped: reused source text on {}/{} pages",
+            results.iter().filter(|r| r.is_some()).count(),
+            results.len()
+        ));
+        results
+    }
 
-        let results: Vec<PathBuf> = images
-            .par_iter()
-            .zip(output_paths.par_iter())
-            .map(|(img_path, output_path)| {
-                match crate::ImageProcDeskewer::correct_skew(img_path, output_path, &deskew_options) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        std::fs::copy(img_path, output_path).ok();
-                    }
-                }
-                output_path.clone()
-            })
-            .collect();
+    /// Step 12a: Detect running headers/footers in the OCR results and drop
+    /// their blocks (with `Erase`, also painting them out of the page images)
+    fn step_running_heads<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        ocr_results: &mut [Option<crate::OcrResult>],
+        is_vertical: bool,
+        progress: &P,
+    ) -> Result<Vec<crate::markdown::RunningHeadTemplate>, PipelineError> {
+        progress.on_step_start("Detecting running headers/footers...");
+
+        let mut pages = ocr_page_contents(images, ocr_results, is_vertical);
+        let templates =
+            crate::markdown::RunningHeadDetector::detect(&mut pages, &crate::markdown::RunningHeadOptions::default());
+        let erase = self.config.running_heads == crate::markdown::RunningHeadMode::Erase;
+
+        let mut removed = 0;
+        for ((page, result), image) in pages.iter().zip(ocr_results.iter_mut()).zip(images) {
+            let Some(result) = result else { continue };
+            let heads: Vec<crate::markdown::BoundingBox> =
+                page.text_blocks.iter().filter(|b| b.running_head.is_some()).map(|b| b.bbox).collect();
+            if heads.is_empty() {
+                continue;
+            }
+            let mut blocks = page.text_blocks.iter();
+            result.text_blocks.retain(|_| blocks.next().is_some_and(|b| b.running_head.is_none()));
+            removed += heads.len();
+            if erase {
+                erase_regions(image, &heads).map_err(|e| PipelineError::ImageProcessingFailed(e.to_string()))?;
+            }
+        }
+        for template in &templates {
+            progress.on_debug(&format!(
+                "Running {} \"{}\" on {} pages",
+                template.position.name(),
+                template.text,
+                template.pages.len()
+            ));
+        }
 
-        progress.on_step_complete("Deskew", &format!("{} images", results.len()));
-        Ok(results)
+        progress.on_step_complete(
+            "Running heads",
+            &format!(
+                "{} found, {} blocks {}",
+                templates.len(),
+                removed,
+                if erase { "erased" } else { "excluded" }
+            ),
+        );
+        Ok(templates)
     }
 
-    /// Step 2: Margin trimming (C#互換: 単純な固定%カット)
+    /// Step 12b: Draw the reading order of the OCR blocks over each page
+    /// into `reading_order/` in the work directory
     ///
-    /// C#版と同様に、各辺から指定%を単純にカットする。
-    /// 複雑なマージン検出は行わない。
-    fn step_margin_trim<P: ProgressCallback>(
+    /// Uses the block order of the hOCR / ALTO export; failures are only
+    /// reported as debug messages.
+    fn step_debug_reading_order<P: ProgressCallback>(
         &self,
         work_dir: &Path,
         images: &[PathBuf],
+        ocr_results: &[Option<crate::OcrResult>],
+        is_vertical: bool,
         progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start(&format!("Trimming margins ({}%)...", self.config.margin_trim));
-        let trimmed_dir = work_dir.join("trimmed");
-        std::fs::create_dir_all(&trimmed_dir)?;
-
-        let trim_percent = self.config.margin_trim / 100.0; // Convert to ratio
+    ) {
+        use crate::markdown::{ReadingOrderSorter, TextBlock, TextDirection};
 
-        let output_paths: Vec<PathBuf> = images
-            .iter()
+        let overlay_dir = work_dir.join("reading_order");
+        if let Err(e) = std::fs::create_dir_all(&overlay_dir) {
+            progress.on_debug(&format!("Reading order overlay failed: {}", e));
+            return;
+        }
+        let direction = if is_vertical { TextDirection::Vertical } else { TextDirection::Horizontal };
+        let pages = ocr_page_contents(images, ocr_results, is_vertical);
+        let written = images
+            .par_iter()
+            .zip(pages.par_iter())
             .enumerate()
-            .map(|(idx, img)| {
-                let name = img
-                    .file_name()
-                    .map(|n| n.to_os_string())
-                    .unwrap_or_else(|| std::ffi::OsString::from(format!("page_{:04}.png", idx)));
-                trimmed_dir.join(name)
+            .filter(|(_, (_, page))| !page.text_blocks.is_empty())
+            .filter(|(i, (img_path, page))| {
+                let blocks: Vec<TextBlock> = page.text_blocks.iter().filter(|b| !b.is_empty()).cloned().collect();
+                let path = overlay_dir.join(format!("page_{:04}.png", i));
+                let saved = image::open(img_path)
+                    .and_then(|img| ReadingOrderSorter::overlay(&img.to_rgb8(), &blocks, direction).save(&path));
+                if let Err(e) = &saved {
+                    progress.on_debug(&format!("Reading order overlay failed for page {}: {}", i + 1, e));
+                }
+                saved.is_ok()
             })
-            .collect();
+            .count();
+        progress.on_debug(&format!("Reading order overlays: {} pages in {}", written, overlay_dir.display()));
+    }
 
-        let results: Vec<PathBuf> = images
-            .par_iter()
-            .zip(output_paths.par_iter())
-            .map(|(img_path, output_path)| {
-                // C#互換: 単純な固定%カット
-                if let Ok(img) = image::open(img_path) {
-                    let (w, h) = (img.width(), img.height());
-                    let trim_x = (w as f64 * trim_percent) as u32;
-                    let trim_y = (h as f64 * trim_percent) as u32;
+    /// Step 12b: Export OCR layout as hOCR / ALTO next to the output PDF
+    ///
+    /// Boxes are in the pixel space of the final page images, i.e. after
+    /// deskew, trimming and resizing.
+    fn step_export_ocr<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        output_path: &Path,
+        ocr_results: &[Option<crate::OcrResult>],
+        is_vertical: bool,
+        progress: &P,
+    ) -> Result<(), PipelineError> {
+        progress.on_step_start("Exporting OCR layout...");
 
-                    let new_x = trim_x;
-                    let new_y = trim_y;
-                    let new_w = w.saturating_sub(trim_x * 2);
-                    let new_h = h.saturating_sub(trim_y * 2);
+        let pages = ocr_page_contents(images, ocr_results, is_vertical);
 
-                    if new_w > 0 && new_h > 0 {
-                        let cropped = img.crop_imm(new_x, new_y, new_w, new_h);
-                        cropped.save(output_path).ok();
-                    } else {
-                        img.save(output_path).ok();
-                    }
-                } else {
-                    std::fs::copy(img_path, output_path).ok();
-                }
-                output_path.clone()
-            })
-            .collect();
+        let title = output_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut written = Vec::new();
+        for format in &self.config.ocr_formats {
+            let path = output_path.with_extension(format.extension());
+            crate::markdown::OcrExporter::write(&pages, *format, &title, &path)
+                .map_err(|e| PipelineError::PdfGenerationFailed(e.to_string()))?;
+            written.push(path.display().to_string());
+        }
+
+        progress.on_step_complete("OCR export", &written.join(", "));
+        Ok(())
+    }
+
+    /// Step 12b: Write the OCR text to `<output>.txt` (one line per block in
+    /// reading order, pages separated by a form feed)
+    fn step_text_sidecar<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        output_path: &Path,
+        ocr_results: &[Option<crate::OcrResult>],
+        is_vertical: bool,
+        progress: &P,
+    ) -> Result<PathBuf, PipelineError> {
+        progress.on_step_start("Writing text sidecar...");
+
+        let pages = ocr_page_contents(images, ocr_results, is_vertical);
+        let path = output_path.with_extension("txt");
+        let mut text = crate::markdown::OcrExporter::to_text(&pages);
+        if self.config.dehyphenate && !is_vertical {
+            text = crate::markdown::dehyphenate(&text);
+        }
+        std::fs::write(&path, text)?;
+
+        progress.on_step_complete("Text sidecar", &path.display().to_string());
+        Ok(path)
+    }
+
+    /// Step 12c: Decide per page whether to keep color (saturation histogram)
+    fn step_detect_color_modes<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        progress: &P,
+    ) -> Vec<crate::PageColorMode> {
+        progress.on_step_start("Detecting color pages...");
+
+        let decisions =
+            crate::ColorAnalyzer::detect_book_color_modes(images, crate::color_stats::DEFAULT_COLOR_PAGE_RATIO);
+        for (i, (mode, ratio)) in decisions.iter().enumerate() {
+            progress.on_debug(&format!(
+                "Page {}: {} ({:.1}% colored)",
+                i + 1,
+                mode.name(),
+                ratio * 100.0
+            ));
+        }
+
+        let modes: Vec<crate::PageColorMode> = decisions.into_iter().map(|(mode, _)| mode).collect();
+        let color_count = modes.iter().filter(|m| **m == crate::PageColorMode::Color).count();
+        progress.on_step_complete(
+            "Color detection",
+            &format!("{} color, {} grayscale", color_count, modes.len() - color_count),
+        );
+        modes
+    }
+
+    /// Step 12d: Figures kept in color on grayscale pages (figures-only color
+    /// policy); needs OCR results, otherwise pages keep their color mode
+    #[allow(clippy::too_many_arguments)]
+    fn color_regions<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        ocr_results: &[Option<crate::OcrResult>],
+        page_color_modes: &mut Vec<crate::PageColorMode>,
+        is_vertical: bool,
+        timings: &mut StageTimings,
+        progress: &P,
+    ) -> Vec<Vec<(u32, u32, u32, u32)>> {
+        if self.config.color_policy.is_per_page() {
+            return vec![];
+        }
+        if ocr_results.is_empty() {
+            progress.on_warning("Figures-only color skipped: no OCR results (enable --ocr)");
+            return vec![];
+        }
+        timings.time("figure_detect", || {
+            self.step_detect_color_figures(images, ocr_results, page_color_modes, is_vertical, progress)
+        })
+    }
+
+    fn step_detect_color_figures<P: ProgressCallback>(
+        &self,
+        images: &[PathBuf],
+        ocr_results: &[Option<crate::OcrResult>],
+        page_color_modes: &mut Vec<crate::PageColorMode>,
+        is_vertical: bool,
+        progress: &P,
+    ) -> Vec<Vec<(u32, u32, u32, u32)>> {
+        use crate::PageColorMode;
+
+        progress.on_step_start("Detecting color figures...");
+        page_color_modes.resize(images.len(), PageColorMode::Color);
+
+        let pages = ocr_page_contents(images, ocr_results, is_vertical);
+        let mut regions = vec![Vec::new(); images.len()];
+        let mut figure_count = 0;
+        for (idx, page) in pages.iter().enumerate() {
+            // Pages without OCR text cannot be split; grayscale pages have no color to keep
+            if !matches!(ocr_results.get(idx), Some(Some(_))) || page_color_modes[idx] != PageColorMode::Color {
+                continue;
+            }
+            let figures = crate::markdown::ElementDetector::detect_figures(&page.text_blocks);
+            progress.on_debug(&format!("Page {}: {} figure(s) kept in color", idx + 1, figures.len()));
+            figure_count += figures.len();
+            regions[idx] = figures.iter().map(|f| (f.bbox.x, f.bbox.y, f.bbox.width, f.bbox.height)).collect();
+            page_color_modes[idx] = PageColorMode::Grayscale;
+        }
 
-        progress.on_step_complete("Margin trim", &format!("{} images", results.len()));
-        Ok(results)
+        progress.on_step_complete("Figure detection", &format!("{} figures kept in color", figure_count));
+        regions
     }
 
-    /// Step 5: AI Upscaling
-    fn step_upscale<P: ProgressCallback>(
+    /// Step 13: Generate PDF (returns deduplication and bilevel compression stats)
+    ///
+    /// Writes a CBZ instead when `output_format` is CBZ.
+    #[allow(clippy::too_many_arguments)]
+    fn step_generate_pdf<P: ProgressCallback>(
         &self,
-        work_dir: &Path,
         images: &[PathBuf],
+        output_path: &Path,
+        metadata: crate::PdfMetadata,
+        ocr_results: &[Option<crate::OcrResult>],
+        page_color_modes: &[crate::PageColorMode],
+        page_color_regions: &[Vec<(u32, u32, u32, u32)>],
+        page_labels: &[crate::PageLabelRange],
+        outline: &[crate::OutlineItem],
+        is_vertical: bool,
         progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start("AI Upscaling (RealESRGAN)...");
-        let upscaled_dir = work_dir.join("upscaled");
-        std::fs::create_dir_all(&upscaled_dir)?;
+    ) -> Result<crate::PdfWriteStats, PipelineError> {
+        use crate::pdf_writer::{OcrLayer, OcrPageText, TextBlock};
 
-        // Try to initialize RealESRGAN
-        let venv_path = std::env::var("SUPERBOOK_VENV")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("./venv"));
+        if self.config.output_format == crate::BookFormat::Cbz {
+            let has_text = ocr_results.iter().any(Option::is_some);
+            return self.step_generate_cbz(images, output_path, &metadata, has_text, is_vertical, progress);
+        }
 
-        let bridge_config = crate::AiBridgeConfig::builder()
-            .venv_path(venv_path)
-            .build();
+        // Convert OCR results to OcrLayer
+        let ocr_layer = if !ocr_results.is_empty() {
+            let pages: Vec<OcrPageText> = ocr_results
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, result): (usize, &Option<crate::OcrResult>)| {
+                    result.as_ref().map(|r| OcrPageText {
+                        page_index: idx,
+                        blocks: r
+                            .text_blocks
+                            .iter()
+                            .map(|b| TextBlock {
+                                x: b.bbox.0 as f64,
+                                y: b.bbox.1 as f64,
+                                width: (b.bbox.2 - b.bbox.0) as f64,
+                                height: (b.bbox.3 - b.bbox.1) as f64,
+                                text: b.text.clone(),
+                                font_size: b.font_size.unwrap_or(12.0) as f64,
+                                vertical: matches!(b.direction, crate::TextDirection::Vertical),
+                            })
+                            .collect(),
+                    })
+                })
+                .collect();
 
-        let bridge = match crate::SubprocessBridge::new(bridge_config) {
-            Ok(b) => b,
-            Err(e) => {
-                progress.on_debug(&format!("RealESRGAN not available: {}", e));
-                return Ok(images.to_vec());
-            }
+            if pages.is_empty() { None } else { Some(OcrLayer { pages }) }
+        } else {
+            None
         };
 
-        let esrgan = crate::RealEsrgan::new(bridge);
-        let mut options = crate::RealEsrganOptions::builder().scale(2);
-        if self.config.gpu {
-            options = options.gpu_id(0);
+        let mut pdf_builder = crate::PdfWriterOptions::builder()
+            .dpi(self.config.dpi)
+            .jpeg_quality(self.config.jpeg_quality)
+            .metadata(metadata)
+            .deterministic(self.config.deterministic)
+            .page_color_modes(page_color_modes.to_vec())
+            .page_color_regions(page_color_regions.to_vec())
+            .page_labels(page_labels.to_vec())
+            .outline(outline.to_vec())
+            .image_encoding(self.config.image_encoding)
+            .background(self.config.flatten_background)
+            .tools(self.tools.clone());
+
+        if !outline.is_empty() {
+            let entries: usize = outline.iter().map(crate::OutlineItem::count).sum();
+            progress.on_debug(&format!("Outline: {} entries from the source PDF", entries));
         }
-        let options = options.build();
 
-        match esrgan.upscale_batch(images, &upscaled_dir, &options, None) {
-            Ok(result) => {
-                progress.on_step_complete("Upscaling", &format!("{} images", result.successful.len()));
-                Ok(result.successful.iter().map(|r| r.output_path.clone()).collect())
-            }
-            Err(e) => {
-                progress.on_debug(&format!("Upscaling failed: {}", e));
-                Ok(images.to_vec())
-            }
+        if self.config.binding.is_right_to_left(is_vertical) {
+            progress.on_debug("Binding: right-to-left");
+            pdf_builder = pdf_builder.right_to_left(true);
+        }
+
+        if self.config.deterministic {
+            pdf_builder = pdf_builder.recorded_options(self.config.to_json());
+        }
+
+        if let Some(layer) = ocr_layer {
+            pdf_builder = pdf_builder.ocr_layer(layer);
+        }
+
+        if let Some(level) = self.config.pdfa {
+            pdf_builder = pdf_builder.pdfa(level);
+        }
+
+        if !self.config.mixed_size.is_uniform() {
+            pdf_builder = pdf_builder.page_size_mode(crate::pdf_writer::PageSizeMode::MainOrientation);
+        }
+
+        let pdf_options = pdf_builder.build();
+        for adjustment in pdf_options.pdfa_adjustments() {
+            progress.on_warning(&adjustment);
         }
+
+        let stats = crate::PrintPdfWriter::create_from_images_with_stats(images, output_path, &pdf_options)
+            .map_err(|e| PipelineError::PdfGenerationFailed(e.to_string()))?;
+        if stats.deduplicated_pages > 0 {
+            progress.on_debug(&format!(
+                "Deduplicated {} of {} page images",
+                stats.deduplicated_pages, stats.page_count
+            ));
+        }
+        let bilevel = stats.bilevel;
+        if bilevel.pages > 0 {
+            progress.on_debug(&format!(
+                "Bilevel pages: {} ({} JBIG2), {} -> {} bytes ({:.1}x)",
+                bilevel.pages,
+                bilevel.jbig2_pages,
+                bilevel.raw_bytes,
+                bilevel.encoded_bytes,
+                bilevel.compression_ratio()
+            ));
+        }
+
+        if let (Some(level), true) = (pdf_options.pdfa_level(), self.config.pdfa_validate) {
+            self.step_validate_pdfa(output_path, level, progress);
+        }
+
+        Ok(stats)
     }
 
-    /// Step 6: Internal resolution normalization
-    fn step_normalize<P: ProgressCallback>(
+    /// Step 13 for CBZ output: store the final page images with a
+    /// `ComicInfo.xml`; PDF-only settings are reported and ignored
+    fn step_generate_cbz<P: ProgressCallback>(
         &self,
-        work_dir: &Path,
         images: &[PathBuf],
+        output_path: &Path,
+        metadata: &crate::PdfMetadata,
+        has_text: bool,
+        is_vertical: bool,
         progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start("Normalizing to internal resolution (4960x7016)...");
-        let normalized_dir = work_dir.join("normalized");
-        std::fs::create_dir_all(&normalized_dir)?;
-
-        let normalize_options = crate::NormalizeOptions::builder()
-            .target_width(4960)
-            .target_height(7016)
-            .build();
+    ) -> Result<crate::PdfWriteStats, PipelineError> {
+        if self.config.pdfa.is_some() {
+            progress.on_warning("PDF/A conformance ignored for CBZ output");
+        }
+        if self.config.page_labels {
+            progress.on_warning("Page labels not written to CBZ output");
+        }
+        if has_text {
+            progress.on_warning("OCR text layer not embedded in CBZ output (use --format hocr,alto to export it)");
+        }
 
-        let output_paths: Vec<PathBuf> = (0..images.len())
-            .map(|i| normalized_dir.join(format!("page_{:04}.png", i)))
-            .collect();
+        let right_to_left = self.config.binding.is_right_to_left(is_vertical);
+        let comic_info = crate::comic_archive::comic_info_xml(metadata, images.len(), right_to_left);
+        crate::comic_archive::write_cbz(images, output_path, Some(&comic_info))
+            .map_err(|e| PipelineError::PdfGenerationFailed(e.to_string()))?;
 
-        let completed = Arc::new(AtomicUsize::new(0));
-        let _total = images.len();
+        Ok(crate::PdfWriteStats {
+            page_count: images.len(),
+            ..Default::default()
+        })
+    }
 
-        let results: Vec<PathBuf> = images
-            .par_iter()
-            .zip(output_paths.par_iter())
-            .map(|(img_path, output_path)| {
-                match crate::ImageNormalizer::normalize(img_path, output_path, &normalize_options) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        std::fs::copy(img_path, output_path).ok();
-                    }
+    /// Page manifest of the final images: physical page, logical label,
+    /// text direction and geometry of each page
+    fn page_manifest(
+        &self,
+        input: &Path,
+        images: &[PathBuf],
+        geometry: &[crate::PageGeometry],
+        logical_pages: Vec<Option<String>>,
+        failed_pages: &[usize],
+        is_vertical: bool,
+    ) -> crate::PageManifest {
+        let pages = images
+            .iter()
+            .zip(self.physical_pages(failed_pages))
+            .zip(logical_pages)
+            .enumerate()
+            .map(|(index, ((image, physical), logical_page))| {
+                let geometry = geometry.get(index).cloned().unwrap_or_default();
+                crate::ManifestPage {
+                    file: crate::PageManifest::page_file_name(index),
+                    physical_page: physical + 1,
+                    logical_page,
+                    vertical: is_vertical,
+                    source_size: geometry.source_size,
+                    size: image::image_dimensions(image).unwrap_or((0, 0)),
+                    transforms: geometry.transforms,
+                    inverted: geometry.inverted,
+                    placeholder: geometry.placeholder,
+                    extraction_dpi: None,
                 }
-                completed.fetch_add(1, Ordering::Relaxed);
-                output_path.clone()
             })
             .collect();
+        crate::PageManifest {
+            version: crate::page_manifest::MANIFEST_VERSION,
+            source: input.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+            dpi: self.config.dpi,
+            pages,
+            failed_pages: failed_pages.iter().map(|page| page + 1).collect(),
+            effective_dpi: None,
+            running_heads: vec![],
+            draft: self.config.quick,
+        }
+    }
 
-        progress.on_step_complete("Normalization", &format!("{} images", results.len()));
-        Ok(results)
+    /// Source pages (0-based) of the final images, in order
+    ///
+    /// Pages skipped during extraction (or of the other parity) leave gaps
+    /// in the physical numbering; placeholders keep their page.
+    fn physical_pages<'a>(&self, failed_pages: &'a [usize]) -> impl Iterator<Item = usize> + 'a {
+        let parity = self.config.parity;
+        let skipped: &[usize] = if self.config.on_page_error == PageErrorPolicy::Placeholder {
+            &[]
+        } else {
+            failed_pages
+        };
+        (0..).filter(move |page| parity.includes(page + 1) && !skipped.contains(page))
     }
 
-    /// Step 7: Color correction
-    fn step_color_correction<P: ProgressCallback>(
+    /// Source outline with destinations moved to the output pages
+    ///
+    /// An entry whose page was dropped (failed, other parity or beyond
+    /// `max_pages`) points at the next output page, or the last one when
+    /// none follows.
+    fn remap_outline(
+        &self,
+        outline: &[crate::OutlineItem],
+        failed_pages: &[usize],
+        page_count: usize,
+    ) -> Vec<crate::OutlineItem> {
+        let physical: Vec<usize> = self.physical_pages(failed_pages).take(page_count).collect();
+        let map = |page: usize| {
+            physical
+                .iter()
+                .position(|&physical_page| physical_page >= page)
+                .or(physical.len().checked_sub(1))
+        };
+        outline.iter().map(|item| item.remapped(&map)).collect()
+    }
+
+    /// Step 13 for page image output: copy the final images under
+    /// deterministic names and write `manifest.json`
+    fn step_export_pages<P: ProgressCallback>(
         &self,
-        work_dir: &Path,
         images: &[PathBuf],
+        output_dir: &Path,
+        manifest: &crate::PageManifest,
+        has_text: bool,
         progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start("Analyzing color statistics...");
-        let color_corrected_dir = work_dir.join("color_corrected");
-        std::fs::create_dir_all(&color_corrected_dir)?;
+    ) -> Result<crate::PdfWriteStats, PipelineError> {
+        if self.config.pdfa.is_some() {
+            progress.on_warning("PDF/A conformance ignored for page image output");
+        }
+        if has_text {
+            progress.on_warning("OCR text layer not written with page images (use --format hocr,alto to export it)");
+        }
 
-        // Collect color statistics
-        let stats_results: Vec<_> = images
+        std::fs::create_dir_all(output_dir)?;
+        // Pages of an earlier, longer export; returned OCR files are kept
+        for stale in collect_page_images(output_dir)? {
+            let name = stale.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with("page_") && !manifest.pages.iter().any(|page| page.file == name) {
+                std::fs::remove_file(&stale).ok();
+            }
+        }
+
+        images
             .par_iter()
-            .map(|img_path| crate::ColorAnalyzer::calculate_stats(img_path))
-            .collect();
+            .zip(manifest.pages.par_iter())
+            .try_for_each(|(image, page)| {
+                let target = output_dir.join(&page.file);
+                if image.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")) {
+                    std::fs::copy(image, &target).map(|_| ())
+                } else {
+                    image::open(image)
+                        .and_then(|img| img.save(&target))
+                        .map_err(|e| std::io::Error::other(e.to_string()))
+                }
+            })?;
+        manifest
+            .save(output_dir)
+            .map_err(|e| PipelineError::PdfGenerationFailed(e.to_string()))?;
 
-        let all_stats: Vec<_> = stats_results
-            .into_iter()
-            .filter_map(|r| r.ok())
-            .collect();
+        Ok(crate::PdfWriteStats {
+            page_count: images.len(),
+            ..Default::default()
+        })
+    }
 
-        if all_stats.is_empty() {
-            progress.on_debug("Color analysis failed, skipping correction");
-            return Ok(images.to_vec());
+    /// Step 13b: Check PDF/A output with veraPDF (or Ghostscript); problems
+    /// are reported as warnings and keep the file
+    fn step_validate_pdfa<P: ProgressCallback>(&self, output_path: &Path, level: crate::PdfaLevel, progress: &P) {
+        progress.on_step_start("Validating PDF/A...");
+        match crate::pdfa::validate(output_path, level, &self.tools) {
+            Some(crate::PdfaValidation::Compliant) => {
+                progress.on_step_complete("PDF/A validation", &format!("{} compliant (veraPDF)", level));
+            }
+            Some(crate::PdfaValidation::Rendered) => {
+                progress.on_step_complete("PDF/A validation", "rendered by Ghostscript (conformance not checked)");
+            }
+            Some(crate::PdfaValidation::NonCompliant(report)) => {
+                progress.on_warning(&format!("veraPDF reports the output is not {}:\n{}", level, report));
+            }
+            Some(crate::PdfaValidation::Error(message)) => {
+                progress.on_warning(&format!("PDF/A validation failed: {}", message));
+            }
+            None => progress.on_warning(&format!(
+                "PDF/A validation skipped: neither {} nor {} is installed",
+                crate::pdfa::VERAPDF_TOOL,
+                crate::pdfa::GHOSTSCRIPT_TOOL
+            )),
         }
+    }
+}
 
-        let global_param = crate::ColorAnalyzer::decide_global_adjustment(&all_stats);
+/// Work directory stage subdirectories, in processing order
+pub const STAGE_DIRS: &[&str] = &[
+    "extracted",
+    "trimmed",
+    "upscaled",
+    "normalized",
+    "deskewed",
+    "color_corrected",
+    "auto_contrast",
+    "cropped",
+    "finalized",
+];
+
+/// Page image files directly inside `dir`, sorted by name
+pub fn collect_page_images(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    const EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "tif", "tiff"];
+
+    let mut images = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_image = path
+            .extension()
+            .is_some_and(|ext| EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str()));
+        if path.is_file() && is_image {
+            images.push(path);
+        }
+    }
+    images.sort();
+    Ok(images)
+}
 
-        // Debug: 実際のカラー補正パラメータを出力
-        progress.on_debug(&format!(
-            "Color params: scale=({:.3},{:.3},{:.3}), offset=({:.1},{:.1},{:.1}), ghost_thr={}, paper=({},{},{})",
-            global_param.scale_r, global_param.scale_g, global_param.scale_b,
-            global_param.offset_r, global_param.offset_g, global_param.offset_b,
-            global_param.ghost_suppress_threshold,
-            global_param.paper_r, global_param.paper_g, global_param.paper_b
-        ));
+/// Latest stage directory in a kept work directory that contains images
+pub fn latest_stage_dir(work_dir: &Path) -> Option<PathBuf> {
+    STAGE_DIRS
+        .iter()
+        .rev()
+        .map(|name| work_dir.join(name))
+        .find(|dir| collect_page_images(dir).is_ok_and(|images| !images.is_empty()))
+}
 
-        let output_paths: Vec<PathBuf> = (0..images.len())
-            .map(|i| color_corrected_dir.join(format!("page_{:04}.png", i)))
-            .collect();
+/// Whether `path` was written by a previous run: an output named by
+/// [`PdfPipeline::get_output_path`] (any format or parity) or one of its
+/// sidecars (hOCR / ALTO, text, cache file)
+pub fn is_generated_output(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    name.ends_with(crate::cache::CACHE_EXTENSION)
+        || name.ends_with(OUTPUT_SUFFIX)
+        || name.contains(&format!("{}.", OUTPUT_SUFFIX))
+}
 
-        let results: Vec<PathBuf> = images
-            .par_iter()
-            .zip(output_paths.par_iter())
-            .map(|(img_path, output_path)| {
-                if let Ok(img) = image::open(img_path) {
-                    let mut rgb_img = img.to_rgb8();
-                    crate::ColorAnalyzer::apply_adjustment(&mut rgb_img, &global_param);
-                    rgb_img.save(output_path).ok();
-                } else {
-                    std::fs::copy(img_path, output_path).ok();
-                }
-                output_path.clone()
-            })
-            .collect();
+/// Whether two directories are the same or one contains the other, after
+/// resolving symlinks and `..` (a path that does not exist never overlaps)
+pub fn paths_overlap(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a.starts_with(&b) || b.starts_with(&a),
+        _ => false,
+    }
+}
 
-        progress.on_step_complete("Color correction", &format!("{} images", results.len()));
-        Ok(results)
+/// Summarize OCR text for the PDF Subject: leading text in reading order,
+/// whitespace collapsed, truncated to `max_chars`
+fn ocr_summary(ocr_results: &[Option<crate::OcrResult>], max_chars: usize) -> Option<String> {
+    let text = ocr_results
+        .iter()
+        .flatten()
+        .flat_map(|r| r.text_blocks.iter())
+        .flat_map(|b| b.text.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.is_empty() {
+        return None;
     }
 
-    /// Step 8: Tukey fence group crop
-    fn step_group_crop<P: ProgressCallback>(
-        &self,
-        work_dir: &Path,
-        images: &[PathBuf],
-        progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start("Detecting text bounding boxes...");
-        let cropped_dir = work_dir.join("cropped");
-        std::fs::create_dir_all(&cropped_dir)?;
+    let mut summary: String = text.chars().take(max_chars).collect();
+    if text.chars().count() > max_chars {
+        summary.push('…');
+    }
+    Some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ============ PipelineConfig Tests ============
+
+    #[test]
+    fn test_pipeline_config_default() {
+        // TC: PIPE-003
+        let config = PipelineConfig::default();
+
+        assert_eq!(config.dpi, 300);
+        assert!(config.deskew);
+        assert_eq!(config.margin_trim, 0.5);
+        assert!(config.upscale);
+        assert!(config.gpu);
+        assert!(!config.internal_resolution);
+        assert!(!config.color_correction);
+        assert!(!config.offset_alignment);
+        assert_eq!(config.output_height, 3508);
+        assert!(!config.ocr);
+        assert!(config.max_pages.is_none());
+        assert!(!config.save_debug);
+        assert_eq!(config.jpeg_quality, 90);
+        assert!(config.threads.is_none());
+        // Phase 3: Memory management fields
+        assert_eq!(config.max_memory_mb, 0);
+        assert_eq!(config.chunk_size, 0);
+        assert_eq!(config.extract_queue_depth, DEFAULT_EXTRACT_QUEUE_DEPTH);
+        assert_eq!(config.extract_batch_pages, crate::image_extract::DEFAULT_BATCH_PAGES);
+    }
 
-        let bounding_boxes = crate::GroupCropAnalyzer::detect_all_bounding_boxes(images, 240);
+    #[test]
+    fn test_extract_queue_depth_serde_default() {
+        let mut value = serde_json::to_value(PipelineConfig::default().with_extract_queue_depth(0)).unwrap();
+        value.as_object_mut().unwrap().remove("extract_queue_depth");
 
-        if bounding_boxes.is_empty() {
-            progress.on_debug("No bounding boxes detected, skipping crop");
-            return Ok(images.to_vec());
-        }
+        let config: PipelineConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.extract_queue_depth, DEFAULT_EXTRACT_QUEUE_DEPTH);
+    }
 
-        let unified = crate::GroupCropAnalyzer::unify_and_expand_regions(
-            &bounding_boxes,
-            5,    // 5% margin expansion
-            4960, // internal width limit
-            7016, // internal height limit
-        );
+    #[test]
+    fn test_pipeline_context() {
+        let context = PipelineContext::new("vol2.pdf", 1, 3);
+        assert_eq!(context.position(), "[2/3]");
+        assert_eq!(PipelineContext::single("book.pdf").position(), "[1/1]");
+        // Total never falls below the index
+        assert_eq!(PipelineContext::new("x.pdf", 4, 2).file_count, 5);
+    }
 
-        let output_paths: Vec<PathBuf> = (0..images.len())
-            .map(|i| cropped_dir.join(format!("page_{:04}.png", i)))
-            .collect();
+    #[test]
+    fn test_external_thread_fraction() {
+        let config = PipelineConfig::default();
+        assert!(config.thread_budget().is_none());
 
-        let results: Vec<PathBuf> = images
-            .par_iter()
-            .zip(output_paths.par_iter())
-            .enumerate()
-            .map(|(i, (img_path, output_path))| {
-                let region = if i % 2 == 0 { &unified.odd_region } else { &unified.even_region };
+        let config = PipelineConfig {
+            threads: Some(10),
+            ..config
+        }
+        .with_external_thread_fraction(0.3);
+        assert_eq!(config.thread_budget(), Some(crate::ThreadBudget { internal: 7, external: 3 }));
+        assert_eq!(PipelineConfig::default().with_external_thread_fraction(7.0).external_thread_fraction, 1.0);
+
+        // Environment only: the cache digest is unchanged
+        let unbudgeted = PipelineConfig {
+            threads: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(config.to_json(), unbudgeted.to_json());
+
+        // Renderer processes share the external budget (set directly to keep
+        // the process-wide tool budget untouched)
+        let pipeline = PdfPipeline {
+            tools: config.tool_context(),
+            config,
+            skipped_stages: Vec::new(),
+        };
+        assert_eq!(pipeline.extract_options(&[]).parallel, 3);
+    }
 
-                if let Ok(img) = image::open(img_path) {
-                    let cropped = img.crop_imm(
-                        region.left,
-                        region.top,
-                        region.width.min(img.width() - region.left),
-                        region.height.min(img.height() - region.top),
-                    );
-                    cropped.save(output_path).ok();
-                } else {
-                    std::fs::copy(img_path, output_path).ok();
-                }
-                output_path.clone()
-            })
-            .collect();
+    #[test]
+    fn test_gpu_wait_serializes_gpu_stages() {
+        let waiting = PdfPipeline::new(PipelineConfig::default().with_gpu_wait(true));
+        let unserialized = PdfPipeline::new(PipelineConfig::default());
+        assert!(unserialized.acquire_gpu("Upscaling", &SilentProgress).is_none());
+        assert!(PdfPipeline::new(PipelineConfig::default().with_gpu_wait(true).with_gpu(false))
+            .acquire_gpu("Upscaling", &SilentProgress)
+            .is_none());
+
+        let guard = waiting.acquire_gpu("Upscaling", &SilentProgress);
+        assert!(guard.is_some());
+
+        // The lock is on the shared file, so another process opening it
+        // (a separate descriptor here) cannot take it either
+        #[cfg(unix)]
+        {
+            use rustix::fs::{flock, FlockOperation};
+            let file = std::fs::File::open(gpu_lock_path()).unwrap();
+            assert!(flock(&file, FlockOperation::NonBlockingLockExclusive).is_err());
+        }
 
-        progress.on_step_complete("Group crop", &format!("{} images", results.len()));
-        Ok(results)
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                let other = PdfPipeline::new(PipelineConfig::default().with_gpu_wait(true));
+                let _gpu = other.acquire_gpu("OCR", &SilentProgress);
+                tx.send(()).unwrap();
+            });
+            // The second pipeline blocks until the first releases the GPU
+            assert!(rx.recv_timeout(std::time::Duration::from_millis(100)).is_err());
+            drop(guard);
+            assert!(rx.recv_timeout(std::time::Duration::from_secs(5)).is_ok());
+        });
+
+        // Scheduling options stay out of the cache digest
+        let json = PipelineConfig::default().with_gpu_wait(true).with_nice(10).to_json();
+        assert_eq!(json, PipelineConfig::default().to_json());
+        assert_eq!(PipelineConfig::default().with_nice(50).nice, 19);
     }
 
-    /// Step 9: Page number detection
-    fn step_page_number_detection<P: ProgressCallback>(
-        &self,
-        images: &[PathBuf],
-        progress: &P,
-    ) -> Result<Option<i32>, PipelineError> {
-        progress.on_step_start("Detecting page numbers...");
-
-        let page_options = crate::PageNumberOptions::default();
-        let mut page_detections = Vec::new();
+    #[test]
+    fn test_reuse_text_layer() {
+        let dir = tempfile::tempdir().unwrap();
+        let images: Vec<PathBuf> = (0..3)
+            .map(|i| {
+                let path = dir.path().join(format!("page_{:05}.png", i));
+                image::GrayImage::from_pixel(300, 400, image::Luma([255])).save(&path).unwrap();
+                path
+            })
+            .collect();
+        let page = |text: &str| crate::PageText {
+            width_pt: 150.0,
+            height_pt: 200.0,
+            words: vec![crate::TextWord {
+                text: text.to_string(),
+                x_min: 15.0,
+                y_min: 20.0,
+                x_max: 135.0,
+                y_max: 30.0,
+            }],
+        };
+        let layer = crate::TextLayer {
+            pages: vec![page("A born-digital page with plenty of text"), page("short")],
+        };
+        assert!(layer.is_sufficient());
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_skip_existing_ocr(true));
+        let results = pipeline.step_reuse_text_layer(&layer, &images, &SilentProgress);
+        assert_eq!(results.len(), 3);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.text_blocks[0].bbox, (30, 40, 240, 20));
+        // Pages without enough text (or beyond the layer) get no result
+        assert!(results[1].is_none());
+        assert!(results[2].is_none());
+
+        // Off by default and kept out of the cache digest
+        assert!(!PipelineConfig::default().to_json().contains("skip_existing_ocr"));
+        assert!(pipeline.config().to_json().contains(r#""skip_existing_ocr":true"#));
+    }
 
-        for (i, img_path) in images.iter().enumerate() {
-            if let Ok(detection) = crate::TesseractPageDetector::detect_single(img_path, i, &page_options) {
-                page_detections.push(detection);
+    #[test]
+    fn test_process_file_reports_context() {
+        struct Recorder(std::sync::Mutex<Vec<PipelineContext>>);
+        impl ProgressCallback for Recorder {
+            fn on_file_start(&self, context: &PipelineContext) {
+                self.0.lock().unwrap().push(context.clone());
             }
+            fn on_step_start(&self, _step: &str) {}
+            fn on_step_progress(&self, _current: usize, _total: usize) {}
+            fn on_step_complete(&self, _step: &str, _message: &str) {}
+            fn on_debug(&self, _message: &str) {}
         }
 
-        if page_detections.is_empty() {
-            progress.on_step_complete("Page number detection", "no pages detected");
-            return Ok(None);
+        let dir = tempfile::tempdir().unwrap();
+        let recorder = Recorder(std::sync::Mutex::new(Vec::new()));
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        let context = PipelineContext::new(dir.path().join("missing.pdf"), 1, 3);
+        let result = pipeline.process_file(&context, dir.path(), &recorder);
+        assert!(matches!(result, Err(PipelineError::InputNotFound(_))));
+        assert_eq!(*recorder.0.lock().unwrap(), vec![context]);
+
+        // Single-file entry point reports a 1-of-1 context
+        recorder.0.lock().unwrap().clear();
+        let _ = pipeline.process_with_progress(&dir.path().join("other.pdf"), dir.path(), &recorder);
+        assert_eq!(recorder.0.lock().unwrap()[0].position(), "[1/1]");
+    }
+
+    #[test]
+    fn test_process_images_reports_page_completion() {
+        struct Recorder(std::sync::Mutex<Vec<(usize, PathBuf, bool)>>);
+        impl ProgressCallback for Recorder {
+            fn on_step_start(&self, _step: &str) {}
+            fn on_step_progress(&self, _current: usize, _total: usize) {}
+            fn on_step_complete(&self, _step: &str, _message: &str) {}
+            fn on_debug(&self, _message: &str) {}
+            fn on_page_complete(&self, page: usize, image: &Path, is_final: bool) {
+                self.0.lock().unwrap().push((page, image.to_path_buf(), is_final));
+            }
         }
 
-        let first_img = image::open(&images[0]).ok();
-        let img_height = first_img.as_ref().map(|img| img.height()).unwrap_or(7016);
+        let dir = tempfile::tempdir().unwrap();
+        let pages: Vec<PathBuf> = (0..2)
+            .map(|i| {
+                let path = dir.path().join(format!("page_{:04}.png", i));
+                image::RgbImage::from_pixel(40, 60, image::Rgb([200, 200, 200]))
+                    .save(&path)
+                    .unwrap();
+                path
+            })
+            .collect();
 
-        let analysis = crate::PageOffsetAnalyzer::analyze_offsets(&page_detections, img_height);
-        let shift_msg = if analysis.page_number_shift == 0 {
-            "aligned".to_string()
-        } else {
-            format!("offset: {}px", analysis.page_number_shift)
+        let config = PipelineConfig {
+            margin_trim: 5.0,
+            upscale: false,
+            deskew: false,
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 0,
+            ..Default::default()
         };
-        progress.on_step_complete("Page number detection", &shift_msg);
-
-        Ok(Some(analysis.page_number_shift))
+        let recorder = Recorder(std::sync::Mutex::new(Vec::new()));
+        let processed = PdfPipeline::new(config)
+            .process_images(dir.path(), pages, &recorder, &mut StageTimings::new())
+            .unwrap();
+
+        let events = recorder.0.lock().unwrap();
+        let order: Vec<(usize, bool)> = events.iter().map(|(page, _, is_final)| (*page, *is_final)).collect();
+        assert_eq!(order, vec![(0, false), (1, false), (0, true), (1, true)]);
+        assert!(events.iter().all(|(_, image, _)| image.exists()));
+        assert_eq!(events[3].1, processed.images[1]);
     }
 
-    /// Step 10: Finalize output
-    fn step_finalize<P: ProgressCallback>(
-        &self,
-        work_dir: &Path,
-        images: &[PathBuf],
-        progress: &P,
-    ) -> Result<Vec<PathBuf>, PipelineError> {
-        progress.on_step_start(&format!("Finalizing output (height: {})...", self.config.output_height));
-        let finalized_dir = work_dir.join("finalized");
-        std::fs::create_dir_all(&finalized_dir)?;
+    #[test]
+    fn test_pdfa_serde() {
+        assert!(!PipelineConfig::default().to_json().contains("pdfa"));
+        let config = PipelineConfig::default().with_pdfa(Some(crate::PdfaLevel::A2b));
+        let json = config.to_json();
+        assert!(json.contains(r#""pdfa":"2b""#));
+        // Validation does not change the output, so not the digest either
+        assert_eq!(config.clone().with_pdfa_validate(true).to_json(), json);
+        let parsed: PipelineConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.pdfa, Some(crate::PdfaLevel::A2b));
+    }
 
-        let finalize_options = crate::FinalizeOptions::builder()
-            .target_height(self.config.output_height)
-            .build();
+    #[test]
+    fn test_image_encoding_serde() {
+        // Standard encoding leaves the cache digest unchanged
+        assert!(!PipelineConfig::default().to_json().contains("image_encoding"));
+
+        let json = PipelineConfig::default().with_image_encoding(crate::ImageEncoding::Jbig2).to_json();
+        assert!(json.contains(r#""image_encoding":"jbig2""#));
+        let parsed: PipelineConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.image_encoding, crate::ImageEncoding::Jbig2);
+    }
 
-        let output_paths: Vec<PathBuf> = (0..images.len())
-            .map(|i| finalized_dir.join(format!("page_{:04}.png", i)))
-            .collect();
+    #[test]
+    fn test_extract_batch_pages_serde() {
+        // Default batching leaves the cache digest unchanged
+        let json = PipelineConfig::default().to_json();
+        assert!(!json.contains("extract_batch_pages"));
+
+        let config = PipelineConfig::default().with_extract_batch_pages(0);
+        assert_eq!(config.extract_batch_pages, 1);
+        let parsed: PipelineConfig = serde_json::from_str(&config.to_json()).unwrap();
+        assert_eq!(parsed.extract_batch_pages, 1);
+    }
 
-        let results: Vec<PathBuf> = images
-            .par_iter()
-            .zip(output_paths.par_iter())
-            .map(|(img_path, output_path)| {
-                match crate::PageFinalizer::finalize(img_path, output_path, &finalize_options, None, 0, 0) {
-                    Ok(_) => {}
-                    Err(_) => {
-                        std::fs::copy(img_path, output_path).ok();
-                    }
-                }
-                output_path.clone()
+    #[test]
+    fn test_process_page_batch_removes_raw_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let extracted_dir = dir.path().join("extracted");
+        std::fs::create_dir_all(&extracted_dir).unwrap();
+        let raw: Vec<PathBuf> = (0..2)
+            .map(|i| {
+                let path = extracted_dir.join(format!("page_{:05}.png", i));
+                image::RgbImage::from_pixel(40, 60, image::Rgb([200, 200, 200]))
+                    .save(&path)
+                    .unwrap();
+                path
             })
             .collect();
 
-        progress.on_step_complete("Output finalized", &format!("{} pages", results.len()));
-        Ok(results)
+        let config = PipelineConfig {
+            margin_trim: 5.0,
+            upscale: false,
+            deskew: false,
+            ..Default::default()
+        };
+        let mut timings = StageTimings::new();
+        let (processed, geometry) = PdfPipeline::new(config)
+            .process_page_batch(
+                dir.path(),
+                raw.iter().cloned().enumerate().collect(),
+                &mut None,
+                &mut Default::default(),
+                &SilentProgress,
+                &mut timings,
+            )
+            .unwrap();
+
+        assert_eq!(processed.len(), 2);
+        assert!(processed.iter().all(|p| p.exists()));
+        assert_eq!(processed[1].file_name(), raw[1].file_name());
+        assert!(raw.iter().all(|p| !p.exists()));
+        // 5% trim recorded for the page manifest
+        assert_eq!(geometry[1].source_size, (40, 60));
+        assert_eq!(
+            geometry[1].transforms,
+            vec![crate::PageTransform::Crop { x: 2, y: 3, width: 36, height: 54 }]
+        );
+        assert!(timings.get("trim").is_some());
+        assert!(timings.get("deskew").is_none());
     }
 
-    /// Step 11: Vertical text detection
-    fn step_vertical_detection<P: ProgressCallback>(
-        &self,
-        images: &[PathBuf],
-        progress: &P,
-    ) -> Result<bool, PipelineError> {
-        if images.is_empty() {
-            return Ok(false);
+    #[test]
+    fn test_work_cache_reuses_identical_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("work-cache");
+        let page = |book: &str| {
+            let path = dir.path().join(book).join("page_00000.png");
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            image::RgbImage::from_pixel(40, 60, image::Rgb([200, 200, 200]))
+                .save(&path)
+                .unwrap();
+            path
+        };
+        let config = PipelineConfig {
+            margin_trim: 5.0,
+            upscale: false,
+            deskew: false,
+            ..Default::default()
         }
+        .with_work_cache(Some(cache_dir.clone()));
+        let run = |config: &PipelineConfig, book: &str, stats: &mut crate::WorkCacheStats| {
+            let work_dir = dir.path().join(format!("{}-work", book));
+            let mut timings = StageTimings::new();
+            PdfPipeline::new(config.clone())
+                .cached_page_stages(&work_dir, vec![page(book)], stats, &SilentProgress, &mut timings)
+                .unwrap()
+        };
 
-        progress.on_step_start("Detecting text direction...");
+        let mut stats = crate::WorkCacheStats::default();
+        let (first, first_geometry) = run(&config, "a", &mut stats);
+        assert_eq!(stats, crate::WorkCacheStats { hits: 0, misses: 1, evicted: 0 });
+
+        // The same page of another book comes from the cache
+        let (second, second_geometry) = run(&config, "b", &mut stats);
+        assert_eq!(stats.hits, 1);
+        assert!(second[0].starts_with(dir.path().join("b-work").join("cached")));
+        assert_eq!(std::fs::read(&second[0]).unwrap(), std::fs::read(&first[0]).unwrap());
+        assert_eq!(second_geometry, first_geometry);
+
+        // Changed page-stage options miss
+        let mut stats = crate::WorkCacheStats::default();
+        run(&config.clone().with_margin_trim(2.0), "c", &mut stats);
+        assert_eq!(stats.misses, 1);
+
+        // Nothing is stored when no stage ran
+        let passthrough = PipelineConfig {
+            margin_trim: 0.0,
+            ..config.clone()
+        };
+        run(&passthrough, "d", &mut stats);
+        run(&passthrough, "e", &mut stats);
+        assert_eq!(stats.hits, 0);
+    }
 
-        let mut gray_images = Vec::new();
-        for img_path in images.iter().take(20) {
-            if let Ok(img) = image::open(img_path) {
-                gray_images.push(img.to_luma8());
+    #[test]
+    fn test_invert_step_records_negative_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let negative = image::RgbImage::from_fn(60, 80, |x, y| {
+            if (10..50).contains(&x) && y % 10 < 3 {
+                image::Rgb([230, 230, 230])
+            } else {
+                image::Rgb([20, 20, 20])
             }
+        });
+        let pages: Vec<PathBuf> = (0..2).map(|i| dir.path().join(format!("page_{:05}.png", i))).collect();
+        image::RgbImage::from_pixel(60, 80, image::Rgb([240, 240, 240]))
+            .save(&pages[0])
+            .unwrap();
+        negative.save(&pages[1]).unwrap();
+
+        let config = PipelineConfig {
+            margin_trim: 0.0,
+            upscale: false,
+            deskew: false,
+            ..Default::default()
         }
+        .with_invert(crate::Invert::Auto);
+        let mut timings = StageTimings::new();
+        let (processed, geometry) = PdfPipeline::new(config)
+            .process_page_stages(dir.path(), pages.clone(), &SilentProgress, &mut timings)
+            .unwrap();
+
+        assert_eq!(processed[0], pages[0]);
+        assert_eq!(processed[1], dir.path().join("inverted").join("page_00001.png"));
+        assert_eq!(image::open(&processed[1]).unwrap().to_rgb8().get_pixel(0, 0).0, [235, 235, 235]);
+        assert_eq!(geometry.iter().map(|g| g.inverted).collect::<Vec<_>>(), vec![false, true]);
+        assert!(timings.get("invert").is_some());
+    }
 
-        if gray_images.is_empty() {
-            progress.on_step_complete("Text direction", "no images to analyze");
-            return Ok(false);
+    #[test]
+    fn test_on_page_error_placeholder() {
+        let dir = tempfile::tempdir().unwrap();
+        // Source pages 0, 2 and 3 extracted; page 1 failed
+        let pages: Vec<PathBuf> = [0, 2, 3].iter().map(|i| dir.path().join(format!("page_{:05}.png", i))).collect();
+        for page in &pages {
+            image::RgbImage::new(60, 80).save(page).unwrap();
         }
+        let mut images = pages.clone();
+        let mut geometry = vec![crate::PageGeometry::default(); 3];
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_on_page_error(PageErrorPolicy::Placeholder));
+        assert!(pipeline.page_failed(&SilentProgress, 1, "extraction timed out").is_ok());
+        pipeline
+            .insert_placeholders(dir.path(), &mut images, &mut geometry, &[1], &SilentProgress)
+            .unwrap();
+        let placeholder = dir.path().join("placeholders").join("page_00001.png");
+        assert_eq!(images, vec![pages[0].clone(), placeholder.clone(), pages[1].clone(), pages[2].clone()]);
+        assert_eq!(image::image_dimensions(&placeholder).unwrap(), (60, 80));
+        assert_eq!(geometry.iter().map(|g| g.placeholder).collect::<Vec<_>>(), vec![false, true, false, false]);
+
+        // Skip leaves the page list alone
+        let mut images = pages.clone();
+        PdfPipeline::new(PipelineConfig::default())
+            .insert_placeholders(dir.path(), &mut images, &mut vec![], &[1], &SilentProgress)
+            .unwrap();
+        assert_eq!(images, pages);
+
+        let abort = PdfPipeline::new(PipelineConfig::default().with_on_page_error(PageErrorPolicy::Abort));
+        assert!(matches!(
+            abort.page_failed(&SilentProgress, 1, "extraction timed out"),
+            Err(PipelineError::ExtractionFailed(msg)) if msg.contains("page 2")
+        ));
+    }
 
-        let vd_options = crate::VerticalDetectOptions::default();
-        match crate::detect_book_vertical_writing(&gray_images, &vd_options) {
-            Ok(result) => {
-                let direction = if result.is_vertical { "vertical" } else { "horizontal" };
-                progress.on_step_complete("Text direction", direction);
-                Ok(result.is_vertical)
-            }
-            Err(_) => {
-                progress.on_step_complete("Text direction", "detection failed");
-                Ok(false)
+    #[test]
+    fn test_source_resolution() {
+        // Fixture pages are 72 DPI scans
+        let low = SourceResolution::probe(Path::new("tests/fixtures/10pages.pdf"), 300).unwrap();
+        assert!((low.source_dpi - 72.0).abs() < 1.0);
+        assert!(low.is_low());
+        assert_eq!(low.suggested_dpi(), 70);
+        assert!(low.warning().contains("--dpi 70"), "{}", low.warning());
+
+        assert!(!SourceResolution::probe(Path::new("tests/fixtures/10pages.pdf"), 100).unwrap().is_low());
+        assert!(SourceResolution::probe(Path::new("/nonexistent.pdf"), 300).is_none());
+
+        let matched = SourceResolution {
+            source_dpi: 298.6,
+            requested_dpi: 300,
+        };
+        assert!(!matched.is_low());
+        assert_eq!(matched.suggested_dpi(), 300);
+    }
+
+    #[test]
+    fn test_clamped_pages() {
+        // An A4 page and a 200-inch media box at 300 DPI
+        let page_sizes = [(595.0, 842.0), (14400.0, 7200.0)];
+        let clamped = ClampedPage::find(&page_sizes, 300, DEFAULT_MAX_PAGE_DIMENSION);
+        assert_eq!(
+            clamped,
+            vec![ClampedPage {
+                page: 2,
+                requested_size: (60000, 30000),
+                dpi: 81,
+            }]
+        );
+        let warning = clamped[0].warning(DEFAULT_MAX_PAGE_DIMENSION);
+        assert!(warning.contains("page 2") && warning.contains("at 81 DPI"), "{}", warning);
+
+        // The cap is on the longest edge; 0 disables it
+        assert!(ClampedPage::find(&page_sizes[..1], 300, 3508).is_empty());
+        assert_eq!(ClampedPage::find(&page_sizes[..1], 300, 3507)[0].dpi, 299);
+        assert!(ClampedPage::find(&page_sizes, 300, 0).is_empty());
+
+        // Clamped pages are rendered at their own DPI
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        let options = pipeline.extract_options(&clamped);
+        assert_eq!((options.dpi_for(0), options.dpi_for(1)), (300, 81));
+
+        // Recorded for the cache manifest
+        let result = PipelineResult::new(2, None, false, 1.0, PathBuf::from("out.pdf"), 0).with_clamped_pages(clamped);
+        let json = serde_json::to_string(&result.to_cache_result()).unwrap();
+        assert!(json.contains(r#""clamped_pages":[{"page":2,"requested_size":[60000,30000],"dpi":81}]"#), "{}", json);
+    }
+
+    #[test]
+    fn test_effective_dpi_guard() {
+        struct Warnings(std::sync::Mutex<Vec<String>>);
+        impl ProgressCallback for Warnings {
+            fn on_step_start(&self, _step: &str) {}
+            fn on_step_progress(&self, _current: usize, _total: usize) {}
+            fn on_step_complete(&self, _step: &str, _message: &str) {}
+            fn on_debug(&self, _message: &str) {}
+            fn on_warning(&self, message: &str) {
+                self.0.lock().unwrap().push(message.to_string());
             }
         }
-    }
 
-    /// Step 12: OCR with YomiToku
-    fn step_ocr<P: ProgressCallback>(
-        &self,
-        images: &[PathBuf],
-        progress: &P,
-    ) -> Result<Vec<Option<crate::OcrResult>>, PipelineError> {
-        progress.on_step_start("Running OCR (YomiToku)...");
+        // A5 pages (420 x 595 pt) scanned at 300 DPI and finalized to 1000 px high
+        let page = crate::PageGeometry {
+            source_size: (1750, 2480),
+            transforms: vec![crate::PageTransform::Scale { x: 0.4, y: 1000.0 / 2480.0 }],
+            ..Default::default()
+        };
+        let geometry = vec![page.clone(), page.clone(), page];
+        let sizes = vec![(420.0, 595.0); 4];
+        let config = PipelineConfig {
+            output_height: 1000,
+            ..Default::default()
+        };
 
-        let venv_path = std::env::var("SUPERBOOK_VENV")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from("./venv"));
+        let warnings = Warnings(std::sync::Mutex::new(Vec::new()));
+        let dpi = PdfPipeline::new(config.clone())
+            .step_effective_dpi(&geometry, &sizes, &[1], &warnings)
+            .unwrap()
+            .unwrap();
+        assert!((dpi - 121.0).abs() < 0.5, "{}", dpi);
+        let messages = warnings.0.lock().unwrap().clone();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains("below the minimum 150"), "{}", messages[0]);
+        assert!(messages[0].contains("--output-height to at least 1240"), "{}", messages[0]);
+
+        let refuse = PdfPipeline::new(config.clone().with_on_low_dpi(LowDpiPolicy::Refuse));
+        assert!(matches!(
+            refuse.step_effective_dpi(&geometry, &sizes, &[], &SilentProgress),
+            Err(PipelineError::InvalidConfig(msg)) if msg.contains("effective output DPI 121")
+        ));
+        let unchecked = PdfPipeline::new(config.with_on_low_dpi(LowDpiPolicy::Refuse).with_min_output_dpi(0));
+        assert!(unchecked.step_effective_dpi(&geometry, &sizes, &[], &SilentProgress).is_ok());
 
-        let bridge_config = crate::AiBridgeConfig::builder()
-            .venv_path(venv_path)
-            .build();
+        // No physical page size (comic archives): nothing to check
+        assert_eq!(refuse.step_effective_dpi(&geometry, &[], &[], &SilentProgress).unwrap(), None);
+    }
 
-        let bridge = match crate::SubprocessBridge::new(bridge_config) {
-            Ok(b) => b,
-            Err(e) => {
-                progress.on_debug(&format!("YomiToku not available: {}", e));
-                return Ok(vec![]);
+    #[test]
+    fn test_remove_markers_step_resolves_custom_color() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("page_0000.png");
+        image::RgbImage::from_fn(40, 20, |x, _| {
+            if (10..30).contains(&x) {
+                image::Rgb([120, 230, 210])
+            } else {
+                image::Rgb([255, 255, 255])
             }
+        })
+        .save(&page)
+        .unwrap();
+
+        let brand = crate::cleanup::CustomHighlighter {
+            name: "mybrand".to_string(),
+            hue_min: 160,
+            hue_max: 180,
+            sat_min: 25,
+            sat_max: 90,
+            val_min: 60,
+            val_max: 100,
         };
-
-        let yomitoku = crate::YomiToku::new(bridge);
-        let mut ocr_opts = crate::YomiTokuOptions::builder();
-        if self.config.gpu {
-            ocr_opts = ocr_opts.use_gpu(true).gpu_id(0);
+        let config = PipelineConfig {
+            margin_trim: 0.0,
+            upscale: false,
+            deskew: false,
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 0,
+            ..Default::default()
         }
-        let ocr_opts = ocr_opts.build();
+        .with_remove_markers(true)
+        .with_marker_colors(vec!["yellow".to_string(), "mybrand".to_string()])
+        .with_highlighters(vec![brand]);
+        let mut timings = StageTimings::new();
+        let processed = PdfPipeline::new(config.clone())
+            .process_images(dir.path(), vec![page.clone()], &SilentProgress, &mut timings)
+            .unwrap();
+
+        let out = image::open(&processed.images[0]).unwrap().to_rgb8();
+        assert_eq!(*out.get_pixel(20, 10), image::Rgb([255, 255, 255]));
+        assert!(timings.get("markers").is_some());
+        assert!(processed.geometry[0].marked);
+
+        // Unknown names fail instead of silently removing nothing
+        let config = config.with_marker_colors(vec!["teal".to_string()]);
+        assert!(config.marker_removal_options().is_err());
+        assert!(PdfPipeline::new(config)
+            .process_images(dir.path(), vec![page], &SilentProgress, &mut StageTimings::new())
+            .is_err());
+    }
 
-        let mut results = Vec::new();
-        for img_path in images {
-            match yomitoku.ocr(img_path, &ocr_opts) {
-                Ok(result) => results.push(Some(result)),
-                Err(_) => results.push(None),
+    #[test]
+    fn test_remove_markers_step_margins_only() {
+        // Highlighted heading inside a text block and a scribble in the left margin
+        let mark = image::Rgb([255, 255, 100]);
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("page_0000.png");
+        image::RgbImage::from_fn(200, 200, |x, y| {
+            if (60..140).contains(&x) && (64..70).contains(&y) {
+                mark
+            } else if (60..140).contains(&x) && (72..140).contains(&y) && x % 12 < 8 && y % 14 < 10 {
+                let ink = ((x % 12) * 10) as u8;
+                image::Rgb([ink, ink, ink])
+            } else if (10..25).contains(&x) && (90..110).contains(&y) {
+                mark
+            } else {
+                image::Rgb([255, 255, 255])
             }
+        })
+        .save(&page)
+        .unwrap();
+
+        let config = PipelineConfig {
+            margin_trim: 0.0,
+            upscale: false,
+            deskew: false,
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 0,
+            ..Default::default()
         }
-
-        let success_count = results.iter().filter(|r| r.is_some()).count();
-        progress.on_step_complete("OCR", &format!("{}/{} pages", success_count, results.len()));
-        Ok(results)
+        .with_remove_markers(true)
+        .with_marker_colors(vec!["yellow".to_string()])
+        .with_marker_region(crate::cleanup::MarkerArea::Margins);
+        let pipeline = PdfPipeline::new(config.clone());
+        let processed = pipeline
+            .process_images(dir.path(), vec![page], &SilentProgress, &mut StageTimings::new())
+            .unwrap();
+
+        let out = image::open(&processed.images[0]).unwrap().to_rgb8();
+        assert_eq!(*out.get_pixel(15, 100), image::Rgb([255, 255, 255]));
+        assert_eq!(*out.get_pixel(100, 66), mark);
+        assert!(processed.geometry[0].marked);
+
+        // The region is part of the work cache key
+        let whole_page = PdfPipeline::new(config.with_marker_region(crate::cleanup::MarkerArea::Page));
+        assert_ne!(pipeline.page_stage_options(), whole_page.page_stage_options());
     }
 
-    /// Step 13: Generate PDF
-    fn step_generate_pdf<P: ProgressCallback>(
-        &self,
-        images: &[PathBuf],
-        output_path: &Path,
-        pdf_info: &crate::PdfDocument,
-        ocr_results: &[Option<crate::OcrResult>],
-        _progress: &P,
-    ) -> Result<(), PipelineError> {
-        use crate::pdf_writer::{OcrLayer, OcrPageText, TextBlock};
-
-        // Convert OCR results to OcrLayer
-        let ocr_layer = if !ocr_results.is_empty() {
-            let pages: Vec<OcrPageText> = ocr_results
-                .iter()
-                .enumerate()
-                .filter_map(|(idx, result): (usize, &Option<crate::OcrResult>)| {
-                    result.as_ref().map(|r| OcrPageText {
-                        page_index: idx,
-                        blocks: r
-                            .text_blocks
-                            .iter()
-                            .map(|b| TextBlock {
-                                x: b.bbox.0 as f64,
-                                y: b.bbox.1 as f64,
-                                width: (b.bbox.2 - b.bbox.0) as f64,
-                                height: (b.bbox.3 - b.bbox.1) as f64,
-                                text: b.text.clone(),
-                                font_size: b.font_size.unwrap_or(12.0) as f64,
-                                vertical: matches!(b.direction, crate::TextDirection::Vertical),
-                            })
-                            .collect(),
-                    })
-                })
-                .collect();
+    #[test]
+    fn test_desaturate_step() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("page_0000.png");
+        // Dark purple fringe next to saturated red ink
+        image::RgbImage::from_fn(20, 10, |x, _| {
+            if x < 10 {
+                image::Rgb([70, 40, 95])
+            } else {
+                image::Rgb([210, 30, 40])
+            }
+        })
+        .save(&page)
+        .unwrap();
 
-            if pages.is_empty() { None } else { Some(OcrLayer { pages }) }
-        } else {
-            None
-        };
+        let config = PipelineConfig {
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 0,
+            ..Default::default()
+        }
+        .with_desaturate_below(Some(0.25));
+        let mut timings = StageTimings::new();
+        let processed = PdfPipeline::new(config)
+            .process_document_stages(dir.path(), vec![page], vec![Default::default()], &SilentProgress, &mut timings)
+            .unwrap();
+
+        let out = image::open(&processed.images[0]).unwrap().to_rgb8();
+        let image::Rgb([r, g, b]) = *out.get_pixel(2, 2);
+        assert!(r == g && g == b);
+        assert_eq!(*out.get_pixel(15, 2), image::Rgb([210, 30, 40]));
+        assert!(timings.get("desaturate").is_some());
+        assert!(!PipelineConfig::default().to_json().contains("desaturate_below"));
+    }
 
-        let mut pdf_builder = crate::PdfWriterOptions::builder()
-            .dpi(self.config.dpi)
-            .jpeg_quality(self.config.jpeg_quality)
-            .metadata(pdf_info.metadata.clone());
+    #[test]
+    fn test_target_aspect_reports_per_page() {
+        let dir = tempfile::tempdir().unwrap();
+        // Page 1: narrow content (blank sides cropped); page 2: full-width content (padded)
+        let pages: Vec<PathBuf> = [(150, 250), (10, 390)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(x0, x1))| {
+                let path = dir.path().join(format!("page_{:04}.png", i));
+                image::RgbImage::from_fn(400, 300, |x, y| {
+                    if (x0..x1).contains(&x) && (50..250).contains(&y) {
+                        image::Rgb([20, 20, 20])
+                    } else {
+                        image::Rgb([250, 250, 250])
+                    }
+                })
+                .save(&path)
+                .unwrap();
+                path
+            })
+            .collect();
 
-        if let Some(layer) = ocr_layer {
-            pdf_builder = pdf_builder.ocr_layer(layer);
+        let config = PipelineConfig {
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 0,
+            ..Default::default()
         }
+        .with_target_aspect(Some((3, 4)));
+        let mut timings = StageTimings::new();
+        let processed = PdfPipeline::new(config)
+            .process_document_stages(dir.path(), pages, vec![Default::default(); 2], &SilentProgress, &mut timings)
+            .unwrap();
+
+        assert_eq!(
+            processed.aspect_adjustments,
+            vec![
+                crate::AspectAdjustment::Cropped { width: 175, height: 0 },
+                crate::AspectAdjustment::Padded { width: 0, height: 233 },
+            ]
+        );
+        assert_eq!(image::image_dimensions(&processed.images[0]).unwrap(), (225, 300));
+        assert_eq!(image::image_dimensions(&processed.images[1]).unwrap(), (400, 533));
+        assert!(timings.get("aspect").is_some());
+        assert!(matches!(
+            processed.geometry[0].transforms[..],
+            [crate::PageTransform::Crop { y: 0, width: 225, height: 300, .. }]
+        ));
+        assert_eq!(
+            processed.geometry[1].transforms,
+            vec![crate::PageTransform::Pad { x: 0, y: 116, width: 400, height: 533 }]
+        );
 
-        let pdf_options = pdf_builder.build();
+        // Disabled by default and for degenerate ratios
+        assert!(!PipelineConfig::default().to_json().contains("target_aspect"));
+        assert_eq!(PipelineConfig::default().with_target_aspect(Some((0, 4))).target_aspect, None);
+    }
 
-        crate::PrintPdfWriter::create_from_images(images, output_path, &pdf_options)
-            .map_err(|e| PipelineError::PdfGenerationFailed(e.to_string()))?;
+    #[test]
+    fn test_register_sides() {
+        let dir = tempfile::tempdir().unwrap();
+        // Front sides (odd pages) scanned 40px further right than the backs
+        let pages: Vec<PathBuf> = (0..4)
+            .map(|i| {
+                let path = dir.path().join(format!("page_{:04}.png", i));
+                let x0 = if i % 2 == 0 { 120 } else { 80 };
+                image::RgbImage::from_fn(400, 300, |x, y| {
+                    if (x0..x0 + 180).contains(&x) && (50..250).contains(&y) {
+                        image::Rgb([20, 20, 20])
+                    } else {
+                        image::Rgb([250, 250, 250])
+                    }
+                })
+                .save(&path)
+                .unwrap();
+                path
+            })
+            .collect();
+
+        let config = PipelineConfig {
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 0,
+            ..Default::default()
+        }
+        .with_register_sides(true);
+        let mut timings = StageTimings::new();
+        let processed = PdfPipeline::new(config)
+            .process_document_stages(dir.path(), pages, vec![Default::default(); 4], &SilentProgress, &mut timings)
+            .unwrap();
+
+        // Both sides meet halfway, on an unchanged canvas
+        let first_dark = |path: &Path| {
+            let img = image::open(path).unwrap().to_rgb8();
+            assert_eq!(img.dimensions(), (400, 300));
+            (0..400).find(|&x| img.get_pixel(x, 150).0[0] < 128).unwrap()
+        };
+        for path in &processed.images {
+            assert_eq!(first_dark(path), 100);
+        }
+        assert_eq!(
+            processed.geometry[0].transforms,
+            vec![crate::PageTransform::Pad { x: -20, y: 0, width: 400, height: 300 }]
+        );
+        assert_eq!(
+            processed.geometry[1].transforms,
+            vec![crate::PageTransform::Pad { x: 20, y: 0, width: 400, height: 300 }]
+        );
+        assert!(timings.get("register_sides").is_some());
+        assert!(!PipelineConfig::default().to_json().contains("register_sides"));
+    }
 
-        Ok(())
+    #[test]
+    fn test_mixed_size_groups_and_heights() {
+        let minority = [false, true, false, false];
+        assert_eq!(mixed_size_groups(MixedSize::Uniform, &minority), vec![vec![0, 1, 2, 3]]);
+        assert_eq!(mixed_size_groups(MixedSize::PerOrientation, &minority), vec![vec![0, 2, 3], vec![1]]);
+        assert_eq!(mixed_size_groups(MixedSize::PerOrientation, &[false; 2]), vec![vec![0, 1]]);
+        assert_eq!(mixed_size_groups(MixedSize::Preserve, &minority), vec![vec![0, 2, 3]]);
+
+        // 1000x1400 text pages with a 2000x1400 fold-out, output height 2800
+        let sizes = [(1000, 1400), (2000, 1400), (1000, 1400), (1000, 1400)];
+        assert_eq!(finalize_heights(MixedSize::Uniform, &sizes, &minority, 2800), vec![2800; 4]);
+        // Same scale as the text pages
+        assert_eq!(finalize_heights(MixedSize::Preserve, &sizes, &minority, 2800), vec![2800, 2800, 2800, 2800]);
+        // Fits a 2800x2000 page (the text page turned by 90°)
+        assert_eq!(finalize_heights(MixedSize::PerOrientation, &sizes, &minority, 2800), vec![2800, 1960, 2800, 2800]);
+        assert_eq!(
+            finalize_heights(MixedSize::PerOrientation, &[(2000, 1400)], &[true], 2800),
+            vec![2800],
+            "no main pages"
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_mixed_size_reports_minority_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let pages: Vec<PathBuf> = [(100, 140), (200, 140), (100, 140)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(width, height))| {
+                let path = dir.path().join(format!("page_{:04}.png", i));
+                image::RgbImage::from_pixel(width, height, image::Rgb([250, 250, 250])).save(&path).unwrap();
+                path
+            })
+            .collect();
 
-    // ============ PipelineConfig Tests ============
+        let config = PipelineConfig {
+            color_correction: false,
+            offset_alignment: false,
+            output_height: 280,
+            ..Default::default()
+        };
+        let run = |config: PipelineConfig, pages: Vec<PathBuf>| {
+            PdfPipeline::new(config)
+                .process_document_stages(dir.path(), pages, vec![Default::default(); 3], &SilentProgress, &mut StageTimings::new())
+                .unwrap()
+        };
 
-    #[test]
-    fn test_pipeline_config_default() {
-        // TC: PIPE-003
-        let config = PipelineConfig::default();
+        let uniform = run(config.clone(), pages.clone());
+        assert!(uniform.mixed_size.is_empty());
+        assert_eq!(image::image_dimensions(&uniform.images[1]).unwrap(), (400, 280));
+
+        let processed = run(config.with_mixed_size(MixedSize::PerOrientation), pages);
+        assert_eq!(
+            processed.mixed_size,
+            vec![MixedSizeDecision {
+                page: 1,
+                orientation: crate::PageOrientation::Landscape,
+                size: (280, 196),
+            }]
+        );
+        assert_eq!(image::image_dimensions(&processed.images[0]).unwrap(), (200, 280));
+    }
 
-        assert_eq!(config.dpi, 300);
-        assert!(config.deskew);
-        assert_eq!(config.margin_trim, 0.5);
-        assert!(config.upscale);
-        assert!(config.gpu);
-        assert!(!config.internal_resolution);
-        assert!(!config.color_correction);
-        assert!(!config.offset_alignment);
-        assert_eq!(config.output_height, 3508);
-        assert!(!config.ocr);
-        assert!(config.max_pages.is_none());
-        assert!(!config.save_debug);
-        assert_eq!(config.jpeg_quality, 90);
-        assert!(config.threads.is_none());
-        // Phase 3: Memory management fields
-        assert_eq!(config.max_memory_mb, 0);
-        assert_eq!(config.chunk_size, 0);
+    #[test]
+    fn test_process_page_batch_keeps_raw_pages_with_debug() {
+        let dir = tempfile::tempdir().unwrap();
+        let raw = dir.path().join("page_00000.png");
+        image::RgbImage::from_pixel(40, 60, image::Rgb([200, 200, 200]))
+            .save(&raw)
+            .unwrap();
+
+        let config = PipelineConfig {
+            margin_trim: 5.0,
+            upscale: false,
+            deskew: false,
+            save_debug: true,
+            ..Default::default()
+        };
+        PdfPipeline::new(config)
+            .process_page_batch(
+                dir.path(),
+                vec![(0, raw.clone())],
+                &mut None,
+                &mut Default::default(),
+                &SilentProgress,
+                &mut StageTimings::new(),
+            )
+            .unwrap();
+
+        assert!(raw.exists());
     }
 
     #[test]
@@ -1160,6 +7157,57 @@ mod tests {
         assert!(json.contains("\"upscale\":true"));
     }
 
+    #[test]
+    fn test_pipeline_tool_context() {
+        // Each pipeline runs its tools with its own settings
+        let config = PipelineConfig {
+            threads: Some(8),
+            ..PipelineConfig::default()
+        }
+        .with_nice(5)
+        .with_tool_timeout_secs(Some(90))
+        .with_external_thread_fraction(0.5)
+        .with_ocr_concurrency(Some(2));
+        let first = PdfPipeline::new(config);
+        let second = PdfPipeline::new(PipelineConfig::default());
+        assert_eq!(first.tools().niceness(), 5);
+        assert_eq!(first.tools().ocr_concurrency(), Some(2));
+        assert_eq!(first.tools().timeout(), Some(Duration::from_secs(90)));
+        assert_eq!(first.tools().thread_budget(), Some(4));
+        assert_eq!(first.bridge_config().thread_budget, Some(4));
+        assert_eq!(second.tools().niceness(), 0);
+        assert_eq!(second.tools().timeout(), None);
+        assert_eq!(second.tools().thread_budget(), None);
+        assert_eq!(second.tools().ocr_concurrency(), None);
+    }
+
+    #[test]
+    fn test_pipeline_config_to_resolved() {
+        // TC: PIPE-033
+        let config = PipelineConfig::default()
+            .with_dpi(450)
+            .with_nice(5)
+            .with_tool_timeout_secs(Some(90))
+            .with_target_aspect(Some((2, 3)));
+        let resolved = config.to_resolved_json();
+
+        // Environment settings are included, unset options left out
+        assert_eq!(resolved["dpi"], 450);
+        assert_eq!(resolved["nice"], 5);
+        assert_eq!(resolved["tool_timeout_secs"], 90);
+        assert!(resolved.get("work_cache").is_none());
+        assert!(!config.to_json().contains("nice"));
+
+        // Both dumps load back into the same configuration
+        let from_json: PipelineConfig = serde_json::from_value(resolved.clone()).unwrap();
+        let toml = config.to_resolved_toml().unwrap();
+        assert!(toml.contains("dpi = 450"));
+        let from_toml: PipelineConfig = toml::from_str(&toml).unwrap();
+        for loaded in [from_json, from_toml] {
+            assert_eq!(loaded.to_resolved_json(), resolved);
+        }
+    }
+
     #[test]
     fn test_pipeline_config_builder() {
         let config = PipelineConfig::default()
@@ -1174,6 +7222,376 @@ mod tests {
         assert!(config.ocr);
     }
 
+    #[test]
+    fn test_pipeline_config_builder_build() {
+        let config = PipelineConfig::builder()
+            .dpi(600)
+            .deskew(false)
+            .ocr(true)
+            .jpeg_quality(80)
+            .threads(4)
+            .tool_path("pdftoppm", "/opt/poppler/bin/pdftoppm")
+            .build()
+            .unwrap();
+        assert_eq!(config.dpi, 600);
+        assert!(!config.deskew);
+        assert!(config.ocr);
+        assert_eq!(config.jpeg_quality, 80);
+        assert_eq!(config.threads, Some(4));
+        assert_eq!(config.tool_paths["pdftoppm"], PathBuf::from("/opt/poppler/bin/pdftoppm"));
+        assert!(PipelineConfig::builder().build().is_ok());
+    }
+
+    #[test]
+    fn test_quick_preset() {
+        let config = PipelineConfig::quick();
+        assert!(config.quick);
+        assert_eq!(config.dpi, QUICK_DPI);
+        assert_eq!(config.output_height, QUICK_OUTPUT_HEIGHT);
+        assert!(!config.upscale && !config.ocr && !config.internal_resolution);
+        assert_eq!(config.margin_trim, 1.0);
+        assert_eq!(config.jpeg_quality, 75);
+        assert_eq!(config.min_output_dpi, 0);
+        assert!(config.validate().is_ok());
+        assert!(config.to_json().contains("\"quick\":true"));
+        assert!(!PipelineConfig::default().to_json().contains("quick"));
+
+        // OCR-dependent stages are dropped; stronger trims are kept
+        let config = PipelineConfig::default()
+            .with_ocr(true)
+            .with_ocr_formats(vec![crate::markdown::OcrExportFormat::Hocr])
+            .with_color_policy(ColorPolicy::FiguresOnly)
+            .with_margin_trim(3.0)
+            .with_quick(true);
+        assert!(config.ocr_formats.is_empty());
+        assert!(config.color_policy.is_per_page());
+        assert_eq!(config.margin_trim, 3.0);
+        assert!(!PipelineConfig::default().with_quick(false).quick);
+
+        let result = PipelineResult::new(1, None, false, 0.0, PathBuf::from("out.pdf"), 0).with_draft(true);
+        assert!(result.to_cache_result().draft);
+    }
+
+    #[test]
+    fn test_step_text_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = |text: &str, x: u32| crate::yomitoku::TextBlock {
+            text: text.to_string(),
+            bbox: (x, 50, 40, 300),
+            confidence: 0.9,
+            direction: crate::TextDirection::Vertical,
+            font_size: None,
+        };
+        let images: Vec<PathBuf> = (0..2).map(|i| dir.path().join(format!("page_{:04}.png", i))).collect();
+        for path in &images {
+            image::RgbImage::from_pixel(400, 400, image::Rgb([255, 255, 255])).save(path).unwrap();
+        }
+        let ocr_results = vec![
+            Some(crate::OcrResult {
+                input_path: images[0].clone(),
+                text_blocks: vec![block("猫である", 60), block("吾輩は", 300)],
+                confidence: 0.9,
+                processing_time: std::time::Duration::ZERO,
+                text_direction: crate::TextDirection::Vertical,
+            }),
+            None,
+        ];
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_text_sidecar(true));
+        let output = dir.path().join("book.pdf");
+        let path = pipeline
+            .step_text_sidecar(&images, &output, &ocr_results, true, &SilentProgress)
+            .unwrap();
+
+        assert_eq!(path, dir.path().join("book.txt"));
+        // Right column first; the page without text still gets its form feed
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "吾輩は\n猫である\n\x0c");
+        let result = PipelineResult::new(2, None, true, 0.0, output, 0).with_text_sidecar(Some(path.clone()));
+        assert_eq!(result.to_cache_result().text_sidecar, Some(path));
+    }
+
+    #[test]
+    fn test_step_text_sidecar_dehyphenate() {
+        // TC: PIPE-034
+        let dir = tempfile::tempdir().unwrap();
+        let image = dir.path().join("page_0000.png");
+        image::RgbImage::from_pixel(400, 400, image::Rgb([255, 255, 255])).save(&image).unwrap();
+        let block = |text: &str, y: u32| crate::yomitoku::TextBlock {
+            text: text.to_string(),
+            bbox: (20, y, 300, 30),
+            confidence: 0.9,
+            direction: crate::TextDirection::Horizontal,
+            font_size: None,
+        };
+        let ocr_results = vec![Some(crate::OcrResult {
+            input_path: image.clone(),
+            text_blocks: vec![block("The associ-", 50), block("ation of well-", 100), block("known books", 150)],
+            confidence: 0.9,
+            processing_time: std::time::Duration::ZERO,
+            text_direction: crate::TextDirection::Horizontal,
+        })];
+        let output = dir.path().join("book.pdf");
+
+        for (dehyphenate, expected) in [
+            (false, "The associ-\nation of well-\nknown books\n"),
+            (true, "The association\nof well-known\nbooks\n"),
+        ] {
+            let config = PipelineConfig::default().with_text_sidecar(true).with_dehyphenate(dehyphenate);
+            let path = PdfPipeline::new(config)
+                .step_text_sidecar(std::slice::from_ref(&image), &output, &ocr_results, false, &SilentProgress)
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_process_streaming_resume() {
+        // TC: PIPE-036
+        struct Warnings(std::sync::Mutex<Vec<String>>);
+        impl ProgressCallback for Warnings {
+            fn on_step_start(&self, _step: &str) {}
+            fn on_step_progress(&self, _current: usize, _total: usize) {}
+            fn on_step_complete(&self, _step: &str, _message: &str) {}
+            fn on_debug(&self, _message: &str) {}
+            fn on_warning(&self, message: &str) {
+                self.0.lock().unwrap().push(message.to_string());
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let pdf = dir.path().join("book.pdf");
+        crate::selftest::write_synthetic_pdf(&pdf, 2).unwrap();
+        let work_dir = dir.path().join(".work_book");
+        let config = PipelineConfig {
+            output_height: 400,
+            ..PipelineConfig::default().with_dpi(72).with_upscale(false).with_deskew(false)
+        };
+        let run = |config: PipelineConfig, progress: &Warnings| {
+            PdfPipeline::new(config)
+                .process_streaming(&pdf, &work_dir, 2, &[], progress, &mut StageTimings::new())
+                .unwrap()
+        };
+        let quiet = Warnings(std::sync::Mutex::new(Vec::new()));
+
+        // Every page is recorded as it completes
+        let first = run(config.clone(), &quiet);
+        assert_eq!(first.images.len(), 2);
+        assert_eq!(first.resumed_pages, 0);
+        assert!(crate::ProgressFile::path(&work_dir).exists());
+
+        // Resumed pages are not rendered again; a page whose image is gone is
+        let resumed_config = config.clone().with_resume(true);
+        let resumed = run(resumed_config.clone(), &quiet);
+        assert_eq!(resumed.resumed_pages, 2);
+        assert_eq!(resumed.geometry, first.geometry);
+        assert_eq!(std::fs::read_dir(work_dir.join("extracted")).unwrap().count(), 0);
+
+        let trimmed = std::fs::read_dir(work_dir.join("trimmed")).unwrap().next().unwrap().unwrap().path();
+        std::fs::remove_file(&trimmed).unwrap();
+        let resumed = run(resumed_config, &quiet);
+        assert_eq!(resumed.resumed_pages, 1);
+        assert_eq!(resumed.images.len(), 2);
+        assert!(quiet.0.lock().unwrap().is_empty());
+
+        // Other options: nothing to resume
+        let warnings = Warnings(std::sync::Mutex::new(Vec::new()));
+        let changed = run(config.with_dpi(96).with_resume(true), &warnings);
+        assert_eq!(changed.resumed_pages, 0);
+        let warnings = warnings.0.lock().unwrap().clone();
+        assert!(warnings[0].contains("options changed"), "{:?}", warnings);
+    }
+
+    #[test]
+    fn test_step_vertical_detection_forced() {
+        // TC: PIPE-035
+        use crate::markdown::TextDirectionOption;
+
+        struct Messages(std::sync::Mutex<Vec<String>>);
+        impl ProgressCallback for Messages {
+            fn on_step_start(&self, _step: &str) {}
+            fn on_step_progress(&self, _current: usize, _total: usize) {}
+            fn on_step_complete(&self, _step: &str, message: &str) {
+                self.0.lock().unwrap().push(message.to_string());
+            }
+            fn on_debug(&self, _message: &str) {}
+            fn on_warning(&self, message: &str) {
+                self.0.lock().unwrap().push(format!("warning: {}", message));
+            }
+        }
+
+        let images = vec![PathBuf::from("tests/fixtures/book_page_1.png")];
+        let detected = PdfPipeline::new(PipelineConfig::default())
+            .step_vertical_detection(&images, &SilentProgress)
+            .unwrap();
+
+        // Agreeing override: no warning
+        let agree = if detected { TextDirectionOption::Vertical } else { TextDirectionOption::Horizontal };
+        let messages = Messages(std::sync::Mutex::new(Vec::new()));
+        let config = PipelineConfig::default().with_text_direction(agree);
+        assert_eq!(PdfPipeline::new(config).step_vertical_detection(&images, &messages).unwrap(), detected);
+        assert_eq!(*messages.0.lock().unwrap(), vec![format!("{} (forced)", agree.name())]);
+
+        // Contradicting override wins and is reported
+        let disagree = if detected { TextDirectionOption::Horizontal } else { TextDirectionOption::Vertical };
+        let messages = Messages(std::sync::Mutex::new(Vec::new()));
+        let config = PipelineConfig::default().with_text_direction(disagree);
+        assert_eq!(PdfPipeline::new(config).step_vertical_detection(&images, &messages).unwrap(), !detected);
+        let messages = messages.0.lock().unwrap().clone();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("warning: Text direction forced to"), "{}", messages[0]);
+
+        // No pages to analyze
+        let config = PipelineConfig::default().with_text_direction(TextDirectionOption::Vertical);
+        assert!(PdfPipeline::new(config).step_vertical_detection(&[], &SilentProgress).unwrap());
+    }
+
+    #[test]
+    fn test_step_flatten_transparent_pages() {
+        // TC: PIPE-037
+        let temp_dir = tempfile::tempdir().unwrap();
+        let work_dir = temp_dir.path();
+        let transparent = PathBuf::from("tests/fixtures/transparent_page.png");
+        let opaque = PathBuf::from("tests/fixtures/book_page_1.png");
+
+        // Pages without alpha are left alone
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        let images = pipeline.step_flatten(work_dir, std::slice::from_ref(&opaque), &SilentProgress).unwrap();
+        assert_eq!(images, vec![opaque.clone()]);
+        assert!(!work_dir.join("flattened").exists());
+
+        // Transparent paper becomes white, not black
+        let images = pipeline
+            .step_flatten(work_dir, &[transparent.clone(), opaque.clone()], &SilentProgress)
+            .unwrap();
+        assert_eq!(images, vec![work_dir.join("flattened").join("transparent_page.png"), opaque]);
+        let flat = image::open(&images[0]).unwrap();
+        assert!(!flat.color().has_alpha());
+        assert_eq!(flat.to_rgb8().get_pixel(5, 5).0, [255, 255, 255]);
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_flatten_background([245, 235, 220]));
+        let images = pipeline.step_flatten(work_dir, &[transparent], &SilentProgress).unwrap();
+        assert_eq!(image::open(&images[0]).unwrap().to_rgb8().get_pixel(5, 5).0, [245, 235, 220]);
+    }
+
+    #[test]
+    fn test_step_ocr_selection() {
+        // TC: PIPE-038
+        let dir = tempfile::tempdir().unwrap();
+        let text = image::RgbImage::from_fn(120, 160, |x, y| {
+            if y % 20 < 4 && (10..110).contains(&x) {
+                image::Rgb([20, 20, 20])
+            } else {
+                image::Rgb([250, 250, 250])
+            }
+        });
+        let plate = image::RgbImage::from_fn(120, 160, |x, y| {
+            let level = (40 + (x * 3 + y) % 150) as u8;
+            image::Rgb([level, level, level])
+        });
+        let images: Vec<PathBuf> = [&text, &plate, &text, &text]
+            .iter()
+            .enumerate()
+            .map(|(index, img)| {
+                let path = dir.path().join(format!("page_{:04}.png", index));
+                img.save(&path).unwrap();
+                path
+            })
+            .collect();
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        assert_eq!(pipeline.step_ocr_selection(&images, &[], &SilentProgress), vec![false; 4]);
+
+        // Ranges are source page numbers: page 2 failed, so output pages are 1, 3, 4, 5
+        let ranges = crate::parse_page_ranges("1-3").unwrap();
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_ocr_pages(Some(ranges.clone())));
+        assert_eq!(pipeline.step_ocr_selection(&images, &[1], &SilentProgress), vec![false, false, true, true]);
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_ocr_skip_pictures(true));
+        assert_eq!(pipeline.step_ocr_selection(&images, &[], &SilentProgress), vec![false, true, false, false]);
+
+        let pipeline =
+            PdfPipeline::new(PipelineConfig::default().with_ocr_pages(Some(ranges)).with_ocr_skip_pictures(true));
+        assert_eq!(pipeline.step_ocr_selection(&images, &[], &SilentProgress), vec![false, true, false, true]);
+
+        // Every page skipped: no OCR tool needed
+        let results = pipeline.step_ocr(&images, &[true; 4], &SilentProgress).unwrap();
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_page_pixel_budget() {
+        let config = PipelineConfig::default();
+        assert!(config.validate().is_ok());
+        assert!(config.estimated_page_megapixels() < 40.0);
+
+        // Extraction-bound: A4 at 1000 DPI is about 96.7 MP
+        let config = PipelineConfig::default().with_upscale(false).with_dpi(1000);
+        assert!(config.clone().with_max_page_megapixels(97).validate().is_ok());
+        let err = config.with_max_page_megapixels(96).validate().unwrap_err().to_string();
+        assert!(err.contains("over the 96 MP budget"), "{}", err);
+        assert!(err.contains("dpi 996 or less"), "{}", err);
+        assert!(err.ends_with("try dpi 996 or less, raising max_page_megapixels"), "{}", err);
+
+        // Output-bound: 10000px at A4 proportions is about 70.7 MP
+        let config = PipelineConfig {
+            output_height: 10000,
+            ..PipelineConfig::default().with_upscale(false).with_dpi(100)
+        };
+        assert!(config.clone().with_max_page_megapixels(71).validate().is_ok());
+        let err = config.clone().with_max_page_megapixels(70).validate().unwrap_err().to_string();
+        assert!(err.contains("output_height 9947 or less"), "{}", err);
+        let fitted = PipelineConfig { output_height: 9947, ..config.clone() };
+        assert!(fitted.with_max_page_megapixels(70).validate().is_ok());
+        assert!(config.with_max_page_megapixels(0).validate().is_ok());
+
+        // Upscaling quadruples the extracted page; dropping it alone fits
+        let err = PipelineConfig::default().with_dpi(1200).validate().unwrap_err().to_string();
+        assert!(err.contains("(upscaled 2x)"), "{}", err);
+        assert!(err.contains("disabling upscaling"), "{}", err);
+        assert!(PipelineConfig::default().with_dpi(1200).with_upscale(false).validate().is_ok());
+
+        // max_memory_mb tightens the budget (256 MB is about 67 MP of RGBA)
+        let mut config = PipelineConfig::default().with_upscale(false).with_dpi(1000).with_max_page_megapixels(0);
+        config.max_memory_mb = 256;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("over the 67 MP budget"), "{}", err);
+    }
+
+    #[test]
+    fn test_pipeline_config_builder_validation() {
+        let invalid = [
+            PipelineConfig::builder().jpeg_quality(0),
+            PipelineConfig::builder().jpeg_quality(101),
+            PipelineConfig::builder().dpi(0),
+            PipelineConfig::builder().output_height(0),
+            PipelineConfig::builder().margin_trim(-1.0),
+            PipelineConfig::builder().threads(0),
+            PipelineConfig::builder().extract_batch_pages(0),
+            PipelineConfig::builder().ocr_min_confidence(150.0),
+            PipelineConfig::builder().desaturate_below(1.5),
+            PipelineConfig::builder().target_aspect((3, 0)),
+            PipelineConfig::builder().nice(20),
+            PipelineConfig::builder().tool_timeout_secs(0),
+            PipelineConfig::builder().ocr_concurrency(0),
+            PipelineConfig::builder().external_thread_fraction(f32::NAN),
+            PipelineConfig::builder().min_output_dpi(5000),
+            PipelineConfig::builder().output_height(20000).dpi(1200),
+            PipelineConfig::builder().remove_markers(true).marker_colors(vec!["mauve".to_string()]),
+        ];
+        for builder in invalid {
+            let debug = format!("{:?}", builder);
+            assert!(
+                matches!(builder.build(), Err(PipelineError::InvalidConfig(_))),
+                "{}",
+                debug
+            );
+        }
+
+        let err = PipelineConfig::builder().jpeg_quality(0).build().unwrap_err();
+        assert_eq!(err.to_string(), "Invalid configuration: jpeg_quality must be 1-100, got 0");
+    }
+
     #[test]
     fn test_pipeline_config_with_advanced() {
         let config = PipelineConfig::default().with_advanced();
@@ -1253,6 +7671,17 @@ mod tests {
         assert!(cache_result.is_vertical);
         assert_eq!(cache_result.elapsed_seconds, 45.5);
         assert_eq!(cache_result.output_size, 1000);
+        assert!(cache_result.stage_timings.is_empty());
+    }
+
+    #[test]
+    fn test_pipeline_result_to_cache_keeps_timings() {
+        let mut timings = StageTimings::new();
+        timings.add("upscale", std::time::Duration::from_secs(2));
+        let result = PipelineResult::new(1, None, false, 2.0, PathBuf::from("/out.pdf"), 10)
+            .with_stage_timings(timings.clone());
+
+        assert_eq!(result.to_cache_result().stage_timings, timings);
     }
 
     // ============ PdfPipeline Tests ============
@@ -1290,6 +7719,422 @@ mod tests {
         let output_path = pipeline.get_output_path(input, output_dir);
 
         assert_eq!(output_path, PathBuf::from("/output/document_converted.pdf"));
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_output_format(crate::BookFormat::Images));
+        assert_eq!(pipeline.get_output_path(input, output_dir), PathBuf::from("/output/document_converted"));
+    }
+
+    #[test]
+    fn test_is_generated_output() {
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_parity(crate::PageParity::Odd));
+        let output = pipeline.get_output_path(Path::new("/books/vol1.pdf"), Path::new("/books"));
+        assert!(is_generated_output(&output));
+        assert!(is_generated_output(&crate::ProcessingCache::cache_path(&output)));
+        assert!(is_generated_output(&output.with_extension("alto.xml")));
+        assert!(is_generated_output(Path::new("/books/vol1_converted")));
+        assert!(!is_generated_output(Path::new("/books/vol1.pdf")));
+        assert!(!is_generated_output(Path::new("/books/converted.pdf")));
+    }
+
+    #[test]
+    fn test_paths_overlap() {
+        let dir = tempfile::tempdir().unwrap();
+        let inner = dir.path().join("out");
+        std::fs::create_dir(&inner).unwrap();
+
+        assert!(paths_overlap(dir.path(), dir.path()));
+        assert!(paths_overlap(dir.path(), &inner.join("..")));
+        assert!(paths_overlap(&inner, dir.path()));
+        assert!(paths_overlap(dir.path(), &inner));
+        let sibling = tempfile::tempdir().unwrap();
+        assert!(!paths_overlap(dir.path(), sibling.path()));
+        assert!(!paths_overlap(dir.path(), &dir.path().join("missing")));
+    }
+
+    #[test]
+    fn test_parity_output_path_and_manifest() {
+        let input = Path::new("/input/document.pdf");
+        let output_dir = Path::new("/output");
+        let config = PipelineConfig::default().with_parity(crate::PageParity::Odd);
+        let pipeline = PdfPipeline::new(config.clone());
+        assert_eq!(pipeline.get_output_path(input, output_dir), PathBuf::from("/output/document_odd_converted.pdf"));
+        assert!(!pipeline.detects_page_numbers());
+
+        let pipeline = PdfPipeline::new(
+            config
+                .with_parity(crate::PageParity::Even)
+                .with_page_labels(true)
+                .with_output_format(crate::BookFormat::Images),
+        );
+        assert_eq!(pipeline.get_output_path(input, output_dir), PathBuf::from("/output/document_even_converted"));
+        assert!(!pipeline.detects_page_numbers());
+
+        // Even pages keep their source page numbers; page 4 timed out
+        let images = vec![PathBuf::from("/missing/a.png"); 3];
+        let manifest = pipeline.page_manifest(input, &images, &[], vec![None; 3], &[3], false);
+        assert_eq!(
+            manifest.pages.iter().map(|p| p.physical_page).collect::<Vec<_>>(),
+            vec![2, 6, 8]
+        );
+    }
+
+    #[test]
+    fn test_logical_page_labels() {
+        assert_eq!(logical_page_labels(None, &[], 2), vec![None, None]);
+        assert_eq!(
+            logical_page_labels(Some(2), &[], 4),
+            vec![None, None, Some("1".to_string()), Some("2".to_string())]
+        );
+        let ranges = crate::PageLabeler::from_detections(&[], 1, 3);
+        assert_eq!(
+            logical_page_labels(Some(1), &ranges, 3),
+            vec![Some("i".to_string()), Some("1".to_string()), Some("2".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_export_pages_and_assemble_with_returned_ocr() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let work_dir = temp_dir.path().join("work");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let images: Vec<PathBuf> = (0..2)
+            .map(|i| {
+                let path = work_dir.join(format!("page_{:04}.png", i));
+                image::RgbImage::from_pixel(300, 400, image::Rgb([255, 255, 255])).save(&path).unwrap();
+                path
+            })
+            .collect();
+        let geometry = vec![
+            crate::PageGeometry {
+                source_size: (320, 420),
+                transforms: vec![crate::PageTransform::Crop { x: 10, y: 10, width: 300, height: 400 }],
+                inverted: false,
+                marked: false,
+                placeholder: false,
+            };
+            2
+        ];
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_output_format(crate::BookFormat::Images));
+        let pages_dir = temp_dir.path().join("book_converted");
+        let logical = logical_page_labels(Some(1), &[], 2);
+        // Physical page 2 timed out during extraction
+        let manifest = pipeline.page_manifest(Path::new("/in/book.pdf"), &images, &geometry, logical, &[1], true);
+        pipeline
+            .step_export_pages(&images, &pages_dir, &manifest, false, &SilentProgress)
+            .unwrap();
+
+        let loaded = crate::PageManifest::load(&pages_dir).unwrap();
+        assert_eq!(loaded, manifest);
+        assert_eq!(loaded.source, "book.pdf");
+        assert_eq!(loaded.pages[0].file, "page_0001.png");
+        assert_eq!(
+            loaded.pages.iter().map(|p| p.physical_page).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(loaded.failed_pages, vec![2]);
+        assert_eq!(loaded.pages[0].logical_page, None);
+        assert_eq!(loaded.pages[1].logical_page.as_deref(), Some("1"));
+        assert_eq!(loaded.pages[1].size, (300, 400));
+        assert!(pages_dir.join("page_0002.png").exists());
+
+        // Returned OCR for the second page only, recognized on the source page
+        std::fs::write(
+            pages_dir.join("page_0002.hocr"),
+            "<div class='ocr_page' title='bbox 0 0 320 420'>\
+             <span class='ocrx_word' title='bbox 60 110 160 140; x_wconf 90'>吾輩</span></div>",
+        )
+        .unwrap();
+        let output = temp_dir.path().join("book.pdf");
+        let result = pipeline
+            .assemble_with_progress(&pages_dir, None, &output, &SilentProgress)
+            .unwrap();
+        assert_eq!(result.page_count, 2);
+        assert!(result.is_vertical);
+        assert_eq!(result.ocr_confidence, vec![None, Some(0.9)]);
+        assert!(output.exists());
+
+        // Pages listed in the manifest must exist
+        std::fs::remove_file(pages_dir.join("page_0001.png")).unwrap();
+        assert!(matches!(
+            pipeline.assemble_with_progress(&pages_dir, None, &output, &SilentProgress),
+            Err(PipelineError::InputNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_resolve_metadata_defaults() {
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        let source = crate::PdfMetadata {
+            title: Some("  ".to_string()),
+            author: Some("Source Author".to_string()),
+            ..Default::default()
+        };
+
+        let metadata = pipeline
+            .resolve_metadata(Path::new("/nonexistent/my_book.pdf"), &source)
+            .unwrap();
+
+        assert_eq!(metadata.title.as_deref(), Some("my_book"));
+        assert_eq!(metadata.author.as_deref(), Some("Source Author"));
+        assert_eq!(
+            metadata.creator,
+            Some(format!("superbook-pdf v{}", env!("CARGO_PKG_VERSION")))
+        );
+    }
+
+    #[test]
+    fn test_preserve_source_metadata() {
+        let source = crate::PdfMetadata {
+            title: Some("Source Title".to_string()),
+            creator: Some("Typesetter".to_string()),
+            producer: Some("Distiller".to_string()),
+            creation_date: Some("D:20200101000000Z".to_string()),
+            ..Default::default()
+        };
+        let input = Path::new("/nonexistent/my_book.pdf");
+
+        let metadata = PdfPipeline::new(PipelineConfig::default())
+            .resolve_metadata(input, &source)
+            .unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Source Title"));
+        assert_eq!(metadata.producer.as_deref(), Some("superbook-pdf"));
+        assert!(metadata.creation_date.is_none());
+
+        let config = PipelineConfig::default().with_preserve_source_metadata(true);
+        let metadata = PdfPipeline::new(config).resolve_metadata(input, &source).unwrap();
+        assert_eq!(metadata.creator.as_deref(), Some("Typesetter"));
+        assert_eq!(metadata.producer.as_deref(), Some("Distiller"));
+        assert_eq!(metadata.creation_date.as_deref(), Some("D:20200101000000Z"));
+    }
+
+    #[test]
+    fn test_remap_outline() {
+        let item = |title: &str, page: usize| crate::OutlineItem {
+            title: title.to_string(),
+            page: Some(page),
+            children: vec![],
+        };
+        let outline = vec![
+            crate::OutlineItem {
+                children: vec![item("1.1", 1), item("1.2", 3)],
+                ..item("1", 0)
+            },
+            item("2", 5),
+        ];
+        let pages = |outline: &[crate::OutlineItem]| -> Vec<Option<usize>> {
+            fn collect(items: &[crate::OutlineItem], out: &mut Vec<Option<usize>>) {
+                for item in items {
+                    out.push(item.page);
+                    collect(&item.children, out);
+                }
+            }
+            let mut out = vec![];
+            collect(outline, &mut out);
+            out
+        };
+
+        // Every page kept: unchanged
+        let pipeline = PdfPipeline::new(PipelineConfig::default());
+        assert_eq!(pipeline.remap_outline(&outline, &[], 6), outline);
+
+        // Odd pages only with page 3 (0-based 2) failed: output pages are
+        // source pages 0 and 4; dropped pages move to the next kept page
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_parity(crate::PageParity::Odd));
+        let remapped = pipeline.remap_outline(&outline, &[2], 2);
+        assert_eq!(pages(&remapped), vec![Some(0), Some(1), Some(1), Some(1)]);
+        assert_eq!(remapped[0].children[1].title, "1.2");
+
+        assert!(pipeline.remap_outline(&[], &[], 2).is_empty());
+    }
+
+    #[test]
+    fn test_resolve_metadata_override_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("book.pdf");
+        std::fs::write(dir.path().join("book.metadata.toml"), "title = \"Real Title\"\n").unwrap();
+
+        let config = PipelineConfig::default().with_metadata(crate::PdfMetadata {
+            title: Some("Config Title".to_string()),
+            author: Some("Config Author".to_string()),
+            ..Default::default()
+        });
+        let pipeline = PdfPipeline::new(config);
+        assert_eq!(
+            pipeline.get_metadata_override_path(&input),
+            dir.path().join("book.metadata.toml")
+        );
+
+        let metadata = pipeline
+            .resolve_metadata(&input, &crate::PdfMetadata::default())
+            .unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Real Title"));
+        assert_eq!(metadata.author.as_deref(), Some("Config Author"));
+
+        std::fs::write(dir.path().join("book.metadata.toml"), "title = [").unwrap();
+        let result = pipeline.resolve_metadata(&input, &crate::PdfMetadata::default());
+        assert!(matches!(result, Err(PipelineError::InvalidMetadata(..))));
+    }
+
+    #[test]
+    fn test_ocr_summary() {
+        let block = |text: &str| crate::yomitoku::TextBlock {
+            text: text.to_string(),
+            bbox: (0, 0, 10, 10),
+            confidence: 0.9,
+            direction: crate::TextDirection::Horizontal,
+            font_size: None,
+        };
+        let result = crate::OcrResult {
+            input_path: PathBuf::from("page.png"),
+            text_blocks: vec![block("吾輩は 猫である。"), block("名前は\nまだ無い。")],
+            confidence: 0.9,
+            processing_time: std::time::Duration::ZERO,
+            text_direction: crate::TextDirection::Horizontal,
+        };
+
+        assert_eq!(ocr_summary(&[], 10), None);
+        assert_eq!(
+            ocr_summary(&[None, Some(result.clone())], 100).as_deref(),
+            Some("吾輩は 猫である。 名前は まだ無い。")
+        );
+        assert_eq!(ocr_summary(&[Some(result)], 3).as_deref(), Some("吾輩は…"));
+    }
+
+    #[test]
+    fn test_step_running_heads_erase() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = |text: &str, y: u32| crate::yomitoku::TextBlock {
+            text: text.to_string(),
+            bbox: (100, y, 200, 30),
+            confidence: 0.9,
+            direction: crate::TextDirection::Horizontal,
+            font_size: None,
+        };
+        let mut images = Vec::new();
+        let mut ocr_results = Vec::new();
+        for i in 0..4 {
+            let path = dir.path().join(format!("page_{:04}.png", i));
+            image::RgbImage::from_pixel(600, 800, image::Rgb([0, 0, 0])).save(&path).unwrap();
+            ocr_results.push(Some(crate::OcrResult {
+                input_path: path.clone(),
+                text_blocks: vec![block("吾輩は猫である", 20), block(&format!("本文 {}", i), 400)],
+                confidence: 0.9,
+                processing_time: std::time::Duration::ZERO,
+                text_direction: crate::TextDirection::Horizontal,
+            }));
+            images.push(path);
+        }
+
+        let pipeline = PdfPipeline::new(
+            PipelineConfig::default().with_running_heads(crate::markdown::RunningHeadMode::Erase),
+        );
+        let templates = pipeline
+            .step_running_heads(&images, &mut ocr_results, false, &SilentProgress)
+            .unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].text, "吾輩は猫である");
+        assert_eq!(templates[0].pages, vec![1, 2, 3, 4]);
+
+        for (result, image) in ocr_results.iter().zip(&images) {
+            let blocks = &result.as_ref().unwrap().text_blocks;
+            assert_eq!(blocks.len(), 1);
+            assert!(blocks[0].text.starts_with("本文"));
+            let img = image::open(image).unwrap().to_rgb8();
+            assert_eq!(img.get_pixel(150, 30).0, [255, 255, 255]);
+            assert_eq!(img.get_pixel(150, 410).0, [0, 0, 0]);
+        }
+    }
+
+    #[test]
+    fn test_color_regions_figures_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = |text: &str, bbox: (u32, u32, u32, u32), confidence: f32| crate::yomitoku::TextBlock {
+            text: text.to_string(),
+            bbox,
+            confidence,
+            direction: crate::TextDirection::Horizontal,
+            font_size: None,
+        };
+        let mut images = Vec::new();
+        for i in 0..3 {
+            let path = dir.path().join(format!("page_{:04}.png", i));
+            image::RgbImage::from_pixel(600, 800, image::Rgb([255, 255, 255])).save(&path).unwrap();
+            images.push(path);
+        }
+        let ocr_results = vec![
+            Some(crate::OcrResult {
+                input_path: images[0].clone(),
+                text_blocks: vec![block("本文", (50, 50, 500, 30), 0.9), block("", (100, 200, 300, 200), 0.1)],
+                confidence: 0.9,
+                processing_time: std::time::Duration::ZERO,
+                text_direction: crate::TextDirection::Horizontal,
+            }),
+            Some(crate::OcrResult {
+                input_path: images[1].clone(),
+                text_blocks: vec![block("本文", (50, 50, 500, 30), 0.9)],
+                confidence: 0.9,
+                processing_time: std::time::Duration::ZERO,
+                text_direction: crate::TextDirection::Horizontal,
+            }),
+            None,
+        ];
+
+        let mut timings = StageTimings::new();
+        let per_page = PdfPipeline::new(PipelineConfig::default());
+        let mut modes = vec![];
+        assert!(per_page.color_regions(&images, &ocr_results, &mut modes, false, &mut timings, &SilentProgress).is_empty());
+        assert!(modes.is_empty());
+
+        let pipeline = PdfPipeline::new(PipelineConfig::default().with_color_policy(ColorPolicy::FiguresOnly));
+        assert!(pipeline.color_regions(&images, &[], &mut modes, false, &mut timings, &SilentProgress).is_empty());
+
+        let regions = pipeline.color_regions(&images, &ocr_results, &mut modes, false, &mut timings, &SilentProgress);
+        assert_eq!(regions, vec![vec![(100, 200, 300, 200)], vec![], vec![]]);
+        use crate::PageColorMode::{Color, Grayscale};
+        assert_eq!(modes, vec![Grayscale, Grayscale, Color]);
+
+        // Pages already grayscale under mixed_color keep no regions
+        let mut modes = vec![Grayscale, Color, Color];
+        let regions = pipeline.color_regions(&images, &ocr_results, &mut modes, false, &mut timings, &SilentProgress);
+        assert!(regions[0].is_empty());
+        assert_eq!(modes, vec![Grayscale, Grayscale, Color]);
+    }
+
+    #[test]
+    fn test_step_debug_reading_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let block = |text: &str, x: u32| crate::yomitoku::TextBlock {
+            text: text.to_string(),
+            bbox: (x, 50, 40, 300),
+            confidence: 0.9,
+            direction: crate::TextDirection::Vertical,
+            font_size: None,
+        };
+        let images: Vec<PathBuf> = (0..2).map(|i| dir.path().join(format!("page_{:04}.png", i))).collect();
+        for path in &images {
+            image::RgbImage::from_pixel(400, 400, image::Rgb([255, 255, 255])).save(path).unwrap();
+        }
+        // Second page has no OCR text: no overlay
+        let ocr_results = vec![
+            Some(crate::OcrResult {
+                input_path: images[0].clone(),
+                text_blocks: vec![block("吾輩は", 300), block("猫である", 60)],
+                confidence: 0.9,
+                processing_time: std::time::Duration::ZERO,
+                text_direction: crate::TextDirection::Vertical,
+            }),
+            None,
+        ];
+
+        let pipeline = PdfPipeline::new(PipelineConfig { save_debug: true, ..Default::default() });
+        pipeline.step_debug_reading_order(dir.path(), &images, &ocr_results, true, &SilentProgress);
+
+        let overlay_dir = dir.path().join("reading_order");
+        let overlay = image::open(overlay_dir.join("page_0000.png")).unwrap().to_rgb8();
+        // Arrow from the right column to the left one
+        assert_ne!(overlay.get_pixel(200, 200).0, [255, 255, 255]);
+        assert!(!overlay_dir.join("page_0001.png").exists());
     }
 
     #[test]
@@ -1389,6 +8234,54 @@ mod tests {
         assert_eq!(results, vec![10]);
     }
 
+    #[test]
+    fn test_assemble_from_work_dir_uses_latest_stage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let work_dir = temp_dir.path().join(".work_book");
+        for (stage, count) in [("extracted", 3), ("finalized", 2)] {
+            let dir = work_dir.join(stage);
+            std::fs::create_dir_all(&dir).unwrap();
+            for i in 0..count {
+                image::RgbImage::from_pixel(40, 60, image::Rgb([255, 255, 255]))
+                    .save(dir.join(format!("page_{:04}.png", i)))
+                    .unwrap();
+            }
+        }
+        assert_eq!(latest_stage_dir(&work_dir), Some(work_dir.join("finalized")));
+
+        let output = temp_dir.path().join("out").join("book.pdf");
+        let result = PdfPipeline::new(PipelineConfig::default())
+            .assemble_with_progress(&work_dir, None, &output, &SilentProgress)
+            .unwrap();
+        assert_eq!(result.page_count, 2);
+        assert!(output.exists());
+        assert_eq!(result.output_size, std::fs::metadata(&output).unwrap().len());
+    }
+
+    #[test]
+    fn test_assemble_empty_dir_fails() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let result = PdfPipeline::new(PipelineConfig::default()).assemble_with_progress(
+            temp_dir.path(),
+            None,
+            &temp_dir.path().join("book.pdf"),
+            &SilentProgress,
+        );
+        assert!(matches!(result, Err(PipelineError::InputNotFound(_))));
+    }
+
+    #[test]
+    fn test_hooks_do_not_change_default_digest() {
+        let config = PipelineConfig::default();
+        assert!(!config.to_json().contains("hook"));
+
+        let config = config.with_post_hook(Some("./publish.sh".into()));
+        assert!(config.to_json().contains("\"post_hook\":\"./publish.sh\""));
+        let parsed: PipelineConfig = serde_json::from_str(&config.to_json()).unwrap();
+        assert_eq!(parsed.post_hook.as_deref(), Some("./publish.sh"));
+        assert!(parsed.page_hook.is_none());
+    }
+
     #[test]
     fn test_process_in_chunks_maintains_order() {
         let items: Vec<i32> = (0..20).collect();
@@ -1426,6 +8319,65 @@ mod tests {
         assert_eq!(results, expected);
     }
 
+    #[test]
+    fn test_pipeline_result_color_pages() {
+        use crate::PageColorMode::{Color, Grayscale};
+
+        let result = PipelineResult::new(3, None, false, 0.0, PathBuf::from("out.pdf"), 0)
+            .with_page_color_modes(vec![Grayscale, Color, Grayscale]);
+        assert_eq!(result.color_pages(), vec![2]);
+        assert!(!PipelineConfig::default().mixed_color);
+    }
+
+    #[test]
+    fn test_pipeline_result_ocr_confidence() {
+        let result = PipelineResult::new(3, None, false, 0.0, PathBuf::from("out.pdf"), 0)
+            .with_ocr_confidence(vec![Some(0.95), Some(0.35), None]);
+        assert_eq!(result.low_confidence_pages(DEFAULT_OCR_MIN_CONFIDENCE), vec![2]);
+        assert_eq!(result.to_cache_result().ocr_confidence, result.ocr_confidence);
+        assert_eq!(PipelineConfig::default().with_ocr_min_confidence(150.0).ocr_min_confidence, 100.0);
+    }
+
+    #[test]
+    fn test_missing_ocr_tool_is_error() {
+        let missing = OcrTools { yomitoku: false, tesseract: true };
+        let config = PipelineConfig::default().with_ocr(true);
+        let err = PdfPipeline::new(config).with_ocr_tools(missing).err().unwrap();
+        assert!(matches!(err, PipelineError::OcrUnavailable { tool: "yomitoku", .. }));
+        assert!(err.to_string().contains("yomitoku not found; install yomitoku or drop --ocr"));
+
+        // Tools are only required by the stages that use them
+        let pipeline = PdfPipeline::new(PipelineConfig::default()).with_ocr_tools(missing).unwrap();
+        assert!(pipeline.skipped_stages().is_empty());
+    }
+
+    #[test]
+    fn test_missing_tesseract_skips_page_numbers() {
+        // --advanced (offset alignment) keeps running without tesseract
+        let missing = OcrTools { yomitoku: true, tesseract: false };
+        let config = PipelineConfig::default().with_advanced();
+        let pipeline = PdfPipeline::new(config).with_ocr_tools(missing).unwrap();
+        assert_eq!(
+            pipeline.skipped_stages(),
+            &[SkippedStage { stage: "page_numbers", tool: "tesseract" }]
+        );
+        assert!(pipeline.is_skipped("page_numbers"));
+    }
+
+    #[test]
+    fn test_missing_ocr_tool_optional_skips_stage() {
+        let missing = OcrTools { yomitoku: false, tesseract: false };
+        let config = PipelineConfig::default()
+            .with_ocr(true)
+            .with_advanced()
+            .with_ocr_optional(true);
+        let pipeline = PdfPipeline::new(config).with_ocr_tools(missing).unwrap();
+        let stages: Vec<&str> = pipeline.skipped_stages().iter().map(|s| s.stage).collect();
+        assert_eq!(stages, vec!["ocr", "page_numbers"]);
+        assert!(pipeline.is_skipped("ocr"));
+        assert_eq!(pipeline.skipped_stages()[0].to_string(), "ocr (yomitoku not found)");
+    }
+
     #[test]
     fn test_pipeline_config_memory_fields() {
         let config = PipelineConfig::default();
@@ -1433,6 +8385,12 @@ mod tests {
         assert_eq!(config.chunk_size, 0);
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_total_memory_linux() {
+        assert!(total_memory_mb().is_some_and(|mb| mb > 0));
+    }
+
     #[cfg(target_os = "linux")]
     #[test]
     fn test_get_available_memory_linux() {
@@ -3,9 +3,14 @@
 //! This module provides structured progress tracking and display,
 //! ported from the C# ProgressTracker.cs implementation.
 
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::io::{self, Write};
-use std::time::Instant;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::i18n::{Lang, Msg};
 
 /// Processing stages for PDF conversion
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -146,6 +151,8 @@ pub struct ProgressTracker {
     start_time: Instant,
     /// Output mode
     output_mode: OutputMode,
+    /// Message language
+    lang: Lang,
 }
 
 impl Default for ProgressTracker {
@@ -167,9 +174,16 @@ impl ProgressTracker {
             current_item: String::new(),
             start_time: Instant::now(),
             output_mode,
+            lang: Lang::default(),
         }
     }
 
+    /// Set the message language
+    pub fn with_lang(mut self, lang: Lang) -> Self {
+        self.lang = lang;
+        self
+    }
+
     /// Start processing a new file
     pub fn start_file(&mut self, file_number: usize, filename: &str) {
         self.current_file = file_number;
@@ -216,7 +230,11 @@ impl ProgressTracker {
 
         if self.output_mode.should_show(OutputMode::Normal) {
             let elapsed = self.start_time.elapsed();
-            println!("  Completed in {:.2}s", elapsed.as_secs_f64());
+            println!(
+                "  {} {:.2}s",
+                Msg::CompletedIn.text(self.lang),
+                elapsed.as_secs_f64()
+            );
             println!();
         }
     }
@@ -231,15 +249,18 @@ impl ProgressTracker {
         println!();
         println!("{}", "=".repeat(80));
         println!(
-            "[File {}/{}] {}",
-            self.current_file, self.total_files, self.current_filename
+            "[{} {}/{}] {}",
+            Msg::File.text(self.lang),
+            self.current_file,
+            self.total_files,
+            self.current_filename
         );
         println!("{}", "=".repeat(80));
     }
 
     /// Print current stage
     fn print_stage(&self) {
-        println!("  Stage: {}", self.current_stage);
+        println!("  {}: {}", Msg::Stage.text(self.lang), self.current_stage);
     }
 
     /// Print progress
@@ -265,20 +286,515 @@ impl ProgressTracker {
         ok_count: usize,
         skip_count: usize,
         error_count: usize,
+    ) {
+        Self::print_summary_lang(Lang::En, total_files, ok_count, skip_count, error_count, 0);
+    }
+
+    /// Print final summary in the given language
+    pub fn print_summary_lang(
+        lang: Lang,
+        total_files: usize,
+        ok_count: usize,
+        skip_count: usize,
+        error_count: usize,
+        invalid_count: usize,
     ) {
         println!();
         println!("{}", "=".repeat(80));
-        println!("Processing Summary");
+        println!("{}", Msg::SummaryTitle.text(lang));
         println!("{}", "=".repeat(80));
-        println!("  Total files:  {}", total_files);
-        println!("  Succeeded:    {}", ok_count);
-        println!("  Skipped:      {}", skip_count);
-        println!("  Errors:       {}", error_count);
+        println!("  {:<14}{}", format!("{}:", Msg::TotalFiles.text(lang)), total_files);
+        println!("  {:<14}{}", format!("{}:", Msg::Succeeded.text(lang)), ok_count);
+        println!("  {:<14}{}", format!("{}:", Msg::Skipped.text(lang)), skip_count);
+        println!("  {:<14}{}", format!("{}:", Msg::Errors.text(lang)), error_count);
+        if invalid_count > 0 {
+            println!("  {:<14}{}", format!("{}:", Msg::InvalidPdfs.text(lang)), invalid_count);
+        }
         println!("{}", "=".repeat(80));
         println!();
     }
 }
 
+/// Wall-clock time accumulated per pipeline stage (seconds)
+///
+/// Keys are stable stage names (`extract`, `upscale`, `ocr`, `pdf`, ...).
+/// Stages that run more than once, e.g. per streamed batch, accumulate.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct StageTimings {
+    stages: BTreeMap<String, f64>,
+}
+
+impl StageTimings {
+    /// Create empty timings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add elapsed time to a stage
+    pub fn add(&mut self, stage: &str, elapsed: Duration) {
+        *self.stages.entry(stage.to_string()).or_insert(0.0) += elapsed.as_secs_f64();
+    }
+
+    /// Run `f` and add its elapsed time to `stage`
+    pub fn time<T>(&mut self, stage: &str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.add(stage, start.elapsed());
+        result
+    }
+
+    /// Merge another set of timings into this one
+    pub fn merge(&mut self, other: &StageTimings) {
+        for (stage, secs) in &other.stages {
+            *self.stages.entry(stage.clone()).or_insert(0.0) += secs;
+        }
+    }
+
+    /// Seconds spent in a stage
+    pub fn get(&self, stage: &str) -> Option<f64> {
+        self.stages.get(stage).copied()
+    }
+
+    /// Sum over all stages
+    pub fn total(&self) -> f64 {
+        self.stages.values().sum()
+    }
+
+    /// Whether no stage was timed
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Stages sorted by time, slowest first
+    pub fn sorted(&self) -> Vec<(&str, f64)> {
+        let mut stages: Vec<(&str, f64)> =
+            self.stages.iter().map(|(stage, secs)| (stage.as_str(), *secs)).collect();
+        stages.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        stages
+    }
+
+    /// Print the sorted breakdown with each stage's share of the total
+    pub fn print_breakdown(&self, lang: Lang) {
+        let total = self.total();
+        println!("    {}:", Msg::StageTiming.text(lang));
+        for (stage, secs) in self.sorted() {
+            let share = if total > 0.0 { secs / total * 100.0 } else { 0.0 };
+            println!("      {:<16}{:>9.2}s {:>5.1}%", stage, secs, share);
+        }
+        println!("      {:<16}{:>9.2}s", Msg::TotalTime.text(lang), total);
+    }
+}
+
+/// Outcome of one input file in a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileStatus {
+    /// Processed successfully
+    Ok,
+    /// Skipped (output exists or cache valid)
+    Skip,
+    /// Processing failed
+    Error,
+    /// Not a valid PDF (empty, no header or truncated); never processed
+    Invalid,
+}
+
+/// Per-file entry of a [`BatchReport`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileReport {
+    /// Input PDF
+    pub input: PathBuf,
+    /// Output PDF
+    pub output: PathBuf,
+    /// Outcome
+    pub status: FileStatus,
+    /// Pages in the output (cached count for skipped files, 0 if unknown)
+    pub page_count: usize,
+    /// Processing time in seconds (0 for skipped files)
+    pub elapsed_seconds: f64,
+    /// Output file size in bytes (0 if no output was written)
+    pub output_size: u64,
+    /// Error message for failed files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What the pipeline detected (processed files only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<FileFeatures>,
+}
+
+/// Detection outcomes of one processed file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileFeatures {
+    /// Vertical (tategaki) text
+    pub vertical: bool,
+    /// Pages inverted as negative scans
+    pub inverted_pages: usize,
+    /// Pages with highlighter marks removed
+    pub marked_pages: usize,
+    /// Confidence of the page number offset analysis (0.0-1.0, `None` if not run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_number_confidence: Option<f64>,
+}
+
+impl FileFeatures {
+    /// Detection outcomes of a pipeline run
+    pub fn of(result: &crate::PipelineResult) -> Self {
+        Self {
+            vertical: result.is_vertical,
+            inverted_pages: result.inverted_pages.len(),
+            marked_pages: result.marked_pages.len(),
+            page_number_confidence: result.page_number_confidence,
+        }
+    }
+}
+
+impl FileReport {
+    fn new(input: &Path, output: &Path, status: FileStatus) -> Self {
+        Self {
+            input: input.to_path_buf(),
+            output: output.to_path_buf(),
+            status,
+            page_count: 0,
+            elapsed_seconds: 0.0,
+            output_size: 0,
+            error: None,
+            features: None,
+        }
+    }
+
+    /// Builder pattern: attach the detection outcomes
+    pub fn with_features(mut self, features: FileFeatures) -> Self {
+        self.features = Some(features);
+        self
+    }
+
+    /// Successfully processed file
+    pub fn ok(input: &Path, output: &Path, page_count: usize, elapsed_seconds: f64, output_size: u64) -> Self {
+        Self {
+            page_count,
+            elapsed_seconds,
+            output_size,
+            ..Self::new(input, output, FileStatus::Ok)
+        }
+    }
+
+    /// Skipped file; the existing output's size is recorded when present
+    pub fn skip(input: &Path, output: &Path, page_count: usize) -> Self {
+        Self {
+            page_count,
+            output_size: std::fs::metadata(output).map(|m| m.len()).unwrap_or(0),
+            ..Self::new(input, output, FileStatus::Skip)
+        }
+    }
+
+    /// Failed file
+    pub fn error(input: &Path, output: &Path, message: impl Into<String>) -> Self {
+        Self {
+            error: Some(message.into()),
+            ..Self::new(input, output, FileStatus::Error)
+        }
+    }
+
+    /// Input rejected by the PDF header/trailer check
+    pub fn invalid(input: &Path, output: &Path, reason: crate::InvalidPdf) -> Self {
+        Self {
+            error: Some(crate::PdfReaderError::from(reason).to_string()),
+            ..Self::new(input, output, FileStatus::Invalid)
+        }
+    }
+}
+
+/// Batch totals of a [`BatchReport`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchTotals {
+    /// Files in the batch
+    pub files: usize,
+    /// Successfully processed files
+    pub ok: usize,
+    /// Skipped files
+    pub skip: usize,
+    /// Failed files
+    pub error: usize,
+    /// Inputs that are not valid PDFs
+    #[serde(default)]
+    pub invalid: usize,
+    /// Pages over processed and skipped files
+    pub page_count: usize,
+    /// Output bytes over processed and skipped files
+    pub output_size: u64,
+    /// Wall-clock time of the whole batch in seconds
+    pub elapsed_seconds: f64,
+}
+
+/// Detection outcomes aggregated over the processed files of a [`BatchReport`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchFeatures {
+    /// Processed files with detection outcomes
+    pub books: usize,
+    /// Books with vertical text
+    pub vertical: usize,
+    /// Books with horizontal text
+    pub horizontal: usize,
+    /// Books with at least one inverted page
+    pub books_with_inverted_pages: usize,
+    /// Inverted pages over all books
+    pub inverted_pages: usize,
+    /// Books with at least one page with highlighter marks
+    pub books_with_marks: usize,
+    /// Pages with highlighter marks over all books
+    pub marked_pages: usize,
+    /// Books the page number analysis ran on
+    pub page_number_books: usize,
+    /// Mean page number analysis confidence over those books
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_page_number_confidence: Option<f64>,
+    /// Mean processing time per book in seconds
+    pub mean_seconds_per_book: f64,
+    /// Mean processing time per page in seconds
+    pub mean_seconds_per_page: f64,
+}
+
+impl BatchFeatures {
+    /// Aggregate the processed files among `files`
+    pub fn from_files(files: &[FileReport]) -> Self {
+        let mut features = Self::default();
+        let mut confidence_sum = 0.0;
+        let mut seconds = 0.0;
+        let mut pages = 0;
+        for (file, detected) in files.iter().filter_map(|f| f.features.as_ref().map(|d| (f, d))) {
+            features.books += 1;
+            if detected.vertical {
+                features.vertical += 1;
+            } else {
+                features.horizontal += 1;
+            }
+            if detected.inverted_pages > 0 {
+                features.books_with_inverted_pages += 1;
+                features.inverted_pages += detected.inverted_pages;
+            }
+            if detected.marked_pages > 0 {
+                features.books_with_marks += 1;
+                features.marked_pages += detected.marked_pages;
+            }
+            if let Some(confidence) = detected.page_number_confidence {
+                features.page_number_books += 1;
+                confidence_sum += confidence;
+            }
+            seconds += file.elapsed_seconds;
+            pages += file.page_count;
+        }
+        if features.page_number_books > 0 {
+            features.mean_page_number_confidence = Some(confidence_sum / features.page_number_books as f64);
+        }
+        if features.books > 0 {
+            features.mean_seconds_per_book = seconds / features.books as f64;
+        }
+        if pages > 0 {
+            features.mean_seconds_per_page = seconds / pages as f64;
+        }
+        features
+    }
+
+    /// Print the aggregate as a summary section
+    pub fn print_lang(&self, lang: Lang) {
+        if self.books == 0 {
+            return;
+        }
+        println!("{}:", Msg::DetectedFeatures.text(lang));
+        println!(
+            "  {:<34}{} / {}",
+            format!("{}:", Msg::VerticalHorizontal.text(lang)),
+            self.vertical,
+            self.horizontal
+        );
+        if self.inverted_pages > 0 {
+            println!(
+                "  {:<34}{} ({} {})",
+                format!("{}:", Msg::InvertedPages.text(lang)),
+                self.inverted_pages,
+                self.books_with_inverted_pages,
+                Msg::Books.text(lang)
+            );
+        }
+        if self.marked_pages > 0 {
+            println!(
+                "  {:<34}{} ({} {})",
+                format!("{}:", Msg::MarkedPages.text(lang)),
+                self.marked_pages,
+                self.books_with_marks,
+                Msg::Books.text(lang)
+            );
+        }
+        if let Some(confidence) = self.mean_page_number_confidence {
+            println!(
+                "  {:<34}{:.0}% ({} {})",
+                format!("{}:", Msg::PageNumberConfidence.text(lang)),
+                confidence * 100.0,
+                self.page_number_books,
+                Msg::Books.text(lang)
+            );
+        }
+        println!(
+            "  {:<34}{:.2}s / {:.2}s",
+            format!("{}:", Msg::MeanTimePerBookPage.text(lang)),
+            self.mean_seconds_per_book,
+            self.mean_seconds_per_page
+        );
+    }
+}
+
+/// Machine-readable summary of a `convert` run (`--report`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BatchReport {
+    /// Per-file outcomes in processing order
+    pub files: Vec<FileReport>,
+    /// Totals over `files`
+    pub totals: BatchTotals,
+    /// Detection outcomes over the processed files
+    #[serde(default)]
+    pub features: BatchFeatures,
+}
+
+impl BatchReport {
+    /// Create an empty report for a batch of `total_files` inputs
+    pub fn new(total_files: usize) -> Self {
+        Self {
+            files: Vec::with_capacity(total_files),
+            totals: BatchTotals {
+                files: total_files,
+                ..BatchTotals::default()
+            },
+            features: BatchFeatures::default(),
+        }
+    }
+
+    /// Record one file's outcome
+    pub fn push(&mut self, file: FileReport) {
+        match file.status {
+            FileStatus::Ok => self.totals.ok += 1,
+            FileStatus::Skip => self.totals.skip += 1,
+            FileStatus::Error => self.totals.error += 1,
+            FileStatus::Invalid => self.totals.invalid += 1,
+        }
+        self.totals.page_count += file.page_count;
+        self.totals.output_size += file.output_size;
+        let detected = file.features.is_some();
+        self.files.push(file);
+        if detected {
+            self.features = BatchFeatures::from_files(&self.files);
+        }
+    }
+
+    /// Set the batch wall-clock time
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.totals.elapsed_seconds = elapsed.as_secs_f64();
+    }
+
+    /// Write the report as pretty-printed JSON
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        std::fs::write(path, json)
+    }
+}
+
+/// One event of the `--event-log` NDJSON file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum RunEvent {
+    /// The run started
+    RunStart {
+        /// Input files found
+        files: usize,
+        /// superbook-pdf version
+        version: String,
+    },
+    /// Processing of a file started (not sent for skipped files)
+    FileStart {
+        input: PathBuf,
+        /// 1-based position in the run
+        index: usize,
+        /// Files in the run
+        total: usize,
+    },
+    /// A pipeline step started
+    StepStart { input: Option<PathBuf>, step: String },
+    /// A pipeline step finished
+    StepComplete {
+        input: Option<PathBuf>,
+        step: String,
+        message: String,
+    },
+    /// A warning (same text as on the console)
+    Warning { input: Option<PathBuf>, message: String },
+    /// A file failed, or the run stopped (`input` is `None` for run-level errors)
+    Error { input: Option<PathBuf>, message: String },
+    /// Elapsed seconds per stage of a processed file
+    StageTimings { input: PathBuf, timings: StageTimings },
+    /// Outcome of a file (also sent for skipped and failed files)
+    FileFinish(FileReport),
+    /// The run finished (also after `--fail-fast`)
+    RunFinish(BatchTotals),
+}
+
+/// Timestamped line of the event log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    /// Seconds since the Unix epoch
+    pub time: f64,
+    /// The event
+    #[serde(flatten)]
+    pub event: RunEvent,
+}
+
+/// Append-only NDJSON event log (`convert --event-log`)
+///
+/// Each event is written as one line and flushed immediately, so the file
+/// can be followed with `tail -f` and survives a crash mid-run. Runs
+/// appending to the same file are separated by their `run_start` events.
+#[derive(Debug)]
+pub struct EventLog {
+    file: std::sync::Mutex<std::fs::File>,
+}
+
+impl EventLog {
+    /// Open `path` for appending (creating it and its parent directories)
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: std::sync::Mutex::new(file),
+        })
+    }
+
+    /// Append one event with the current time
+    pub fn log(&self, event: RunEvent) -> io::Result<()> {
+        let time = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |d| d.as_secs_f64());
+        let mut line = serde_json::to_string(&EventRecord { time, event }).map_err(io::Error::other)?;
+        line.push('\n');
+        let mut file = self.file.lock().map_err(|_| io::Error::other("event log lock poisoned"))?;
+        // One write per line keeps concurrent appenders from interleaving
+        file.write_all(line.as_bytes())?;
+        file.flush()
+    }
+
+    /// Read every event of an event log file
+    pub fn read(path: &Path) -> io::Result<Vec<EventRecord>> {
+        std::fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(io::Error::other))
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -481,4 +997,181 @@ mod tests {
         assert!(!mode.should_show(OutputMode::Verbose));
         assert!(!mode.should_show(OutputMode::VeryVerbose));
     }
+
+    #[test]
+    fn test_progress_tracker_with_lang() {
+        let tracker = ProgressTracker::new(1, OutputMode::Quiet).with_lang(Lang::Ja);
+        assert_eq!(tracker.lang, Lang::Ja);
+
+        let tracker = ProgressTracker::new(1, OutputMode::Quiet);
+        assert_eq!(tracker.lang, Lang::En);
+    }
+
+    #[test]
+    fn test_stage_timings_accumulate_and_sort() {
+        let mut timings = StageTimings::new();
+        assert!(timings.is_empty());
+
+        timings.add("deskew", Duration::from_millis(500));
+        timings.add("upscale", Duration::from_secs(3));
+        timings.add("deskew", Duration::from_millis(500));
+
+        assert_eq!(timings.get("deskew"), Some(1.0));
+        assert_eq!(timings.get("ocr"), None);
+        assert!((timings.total() - 4.0).abs() < 1e-9);
+        assert_eq!(timings.sorted(), vec![("upscale", 3.0), ("deskew", 1.0)]);
+    }
+
+    #[test]
+    fn test_stage_timings_time_and_merge() {
+        let mut timings = StageTimings::new();
+        let value = timings.time("pdf", || 42);
+        assert_eq!(value, 42);
+        assert!(timings.get("pdf").is_some());
+
+        let mut other = StageTimings::new();
+        other.add("pdf", Duration::from_secs(1));
+        other.add("ocr", Duration::from_secs(2));
+        timings.merge(&other);
+        assert!(timings.get("pdf").unwrap() >= 1.0);
+        assert_eq!(timings.get("ocr"), Some(2.0));
+    }
+
+    #[test]
+    fn test_stage_timings_serde() {
+        let mut timings = StageTimings::new();
+        timings.add("upscale", Duration::from_secs(2));
+        let json = serde_json::to_string(&timings).unwrap();
+        assert_eq!(json, r#"{"upscale":2.0}"#);
+
+        let parsed: StageTimings = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, timings);
+    }
+
+    #[test]
+    fn test_batch_report_totals() {
+        let mut report = BatchReport::new(4);
+        report.push(FileReport::ok(Path::new("a.pdf"), Path::new("out/a.pdf"), 10, 2.5, 1000));
+        report.push(FileReport::skip(Path::new("b.pdf"), Path::new("/nonexistent/b.pdf"), 4));
+        report.push(FileReport::error(Path::new("c.pdf"), Path::new("out/c.pdf"), "broken"));
+        report.push(FileReport::invalid(Path::new("d.pdf"), Path::new("out/d.pdf"), crate::InvalidPdf::Truncated));
+        report.set_elapsed(Duration::from_secs(3));
+
+        assert_eq!(report.files.len(), 4);
+        assert_eq!(report.totals.files, 4);
+        assert_eq!((report.totals.ok, report.totals.skip, report.totals.error), (1, 1, 1));
+        assert_eq!(report.totals.invalid, 1);
+        assert_eq!(report.files[3].error.as_deref(), Some("Not a valid PDF: missing %%EOF marker (truncated file?)"));
+        assert_eq!(report.totals.page_count, 14);
+        assert_eq!(report.totals.output_size, 1000);
+        assert_eq!(report.totals.elapsed_seconds, 3.0);
+    }
+
+    #[test]
+    fn test_batch_report_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("reports/batch.json");
+        let mut report = BatchReport::new(2);
+        report.push(FileReport::ok(Path::new("a.pdf"), Path::new("out/a.pdf"), 1, 0.5, 10));
+        report.push(FileReport::error(Path::new("b.pdf"), Path::new("out/b.pdf"), "broken"));
+        report.save(&path).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["files"][0]["status"], "ok");
+        assert!(json["files"][0].get("error").is_none());
+        assert_eq!(json["files"][1]["status"], "error");
+        assert_eq!(json["files"][1]["error"], "broken");
+        assert_eq!(json["totals"]["error"], 1);
+
+        let parsed: BatchReport = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(parsed, report);
+    }
+
+    // PROG-016: 検出結果のバッチ集計
+    #[test]
+    fn test_batch_features_aggregate() {
+        let mut report = BatchReport::new(4);
+        report.push(FileReport::ok(Path::new("a.pdf"), Path::new("out/a.pdf"), 10, 4.0, 1000).with_features(
+            FileFeatures {
+                vertical: true,
+                inverted_pages: 2,
+                marked_pages: 0,
+                page_number_confidence: Some(0.9),
+            },
+        ));
+        report.push(FileReport::ok(Path::new("b.pdf"), Path::new("out/b.pdf"), 30, 8.0, 1000).with_features(
+            FileFeatures {
+                marked_pages: 5,
+                page_number_confidence: Some(0.5),
+                ..FileFeatures::default()
+            },
+        ));
+        report.push(FileReport::ok(Path::new("c.pdf"), Path::new("out/c.pdf"), 10, 3.0, 1000).with_features(
+            FileFeatures::default(),
+        ));
+        report.push(FileReport::skip(Path::new("d.pdf"), Path::new("/nonexistent/d.pdf"), 100));
+
+        let features = &report.features;
+        assert_eq!(features.books, 3);
+        assert_eq!((features.vertical, features.horizontal), (1, 2));
+        assert_eq!((features.books_with_inverted_pages, features.inverted_pages), (1, 2));
+        assert_eq!((features.books_with_marks, features.marked_pages), (1, 5));
+        assert_eq!(features.page_number_books, 2);
+        assert!((features.mean_page_number_confidence.unwrap() - 0.7).abs() < 1e-9);
+        assert!((features.mean_seconds_per_book - 5.0).abs() < 1e-9);
+        assert!((features.mean_seconds_per_page - 0.3).abs() < 1e-9);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["features"]["marked_pages"], 5);
+        assert_eq!(json["files"][1]["features"]["marked_pages"], 5);
+        assert!(json["files"][3].get("features").is_none());
+    }
+
+    #[test]
+    fn test_batch_features_empty() {
+        let mut report = BatchReport::new(1);
+        report.push(FileReport::error(Path::new("a.pdf"), Path::new("out/a.pdf"), "broken"));
+        assert_eq!(report.features, BatchFeatures::default());
+        assert_eq!(report.features.mean_page_number_confidence, None);
+    }
+
+    // PROG-015: EventLogの追記
+    #[test]
+    fn test_event_log_appends_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs/run.ndjson");
+        let mut timings = StageTimings::new();
+        timings.add("upscale", Duration::from_secs(2));
+        let events = vec![
+            RunEvent::RunStart { files: 1, version: "0.1.0".to_string() },
+            RunEvent::FileStart { input: PathBuf::from("a.pdf"), index: 1, total: 1 },
+            RunEvent::Warning { input: Some(PathBuf::from("a.pdf")), message: "low DPI".to_string() },
+            RunEvent::StageTimings { input: PathBuf::from("a.pdf"), timings },
+            RunEvent::Error { input: Some(PathBuf::from("a.pdf")), message: "broken".to_string() },
+            RunEvent::FileFinish(FileReport::error(Path::new("a.pdf"), Path::new("out/a.pdf"), "broken")),
+        ];
+
+        let log = EventLog::open(&path).unwrap();
+        for event in &events[..2] {
+            log.log(event.clone()).unwrap();
+        }
+        // Visible before the log is closed, and a second writer appends
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        let other = EventLog::open(&path).unwrap();
+        for event in &events[2..] {
+            other.log(event.clone()).unwrap();
+        }
+
+        let text = std::fs::read_to_string(&path).unwrap();
+        let first: serde_json::Value = serde_json::from_str(text.lines().next().unwrap()).unwrap();
+        assert_eq!(first["event"], "run_start");
+        assert!(first["time"].as_f64().unwrap() > 0.0);
+        let last: serde_json::Value = serde_json::from_str(text.lines().last().unwrap()).unwrap();
+        assert_eq!(last["event"], "file_finish");
+        assert_eq!(last["status"], "error");
+        assert_eq!(last["error"], "broken");
+
+        let records = EventLog::read(&path).unwrap();
+        assert_eq!(records.iter().map(|r| r.event.clone()).collect::<Vec<_>>(), events);
+    }
 }
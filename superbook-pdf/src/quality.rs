@@ -0,0 +1,311 @@
+//! Page quality metrics
+//!
+//! Compares each extracted page with its final processed page using SSIM and
+//! PSNR on downscaled grayscale copies, so stages that drastically alter an
+//! outlier page (over-processing, bad crops, failed deskew) stand out.
+//!
+//! Both images are resized to the same small grid before comparison; the
+//! metrics therefore measure overall structural change rather than
+//! pixel-exact differences, and geometry changes (trim, crop) lower SSIM too.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::quality::{compare_pages, QualitySummary, DEFAULT_MIN_SSIM};
+//! use std::path::Path;
+//!
+//! let page = compare_pages(Path::new("extracted.png"), Path::new("final.png")).unwrap();
+//! println!("SSIM {:.3}, PSNR {:.1} dB", page.ssim, page.psnr);
+//!
+//! let summary = QualitySummary::from_pages(&[page], DEFAULT_MIN_SSIM).unwrap();
+//! println!("Low SSIM pages: {:?}", summary.low_ssim_pages);
+//! ```
+
+use image::{imageops::FilterType, GrayImage};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use thiserror::Error;
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Longer side of the comparison grid (pixels)
+pub const METRIC_SIZE: u32 = 512;
+
+/// SSIM below which a page is flagged as drastically changed
+pub const DEFAULT_MIN_SSIM: f64 = 0.5;
+
+/// PSNR reported for identical images (dB)
+pub const MAX_PSNR: f64 = 100.0;
+
+/// SSIM window size (non-overlapping blocks)
+const SSIM_WINDOW: u32 = 8;
+
+/// SSIM stabilizing constants for 8-bit data: (0.01 * 255)^2, (0.03 * 255)^2
+const SSIM_C1: f64 = 6.5025;
+const SSIM_C2: f64 = 58.5225;
+
+// ============================================================
+// Error Types
+// ============================================================
+
+/// Quality metric error types
+#[derive(Debug, Error)]
+pub enum QualityError {
+    #[error("Failed to load image {0}: {1}")]
+    ImageLoad(String, String),
+
+    #[error("Image dimensions differ: {0:?} vs {1:?}")]
+    SizeMismatch((u32, u32), (u32, u32)),
+}
+
+pub type Result<T> = std::result::Result<T, QualityError>;
+
+// ============================================================
+// Data Structures
+// ============================================================
+
+/// Quality metrics for one page
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PageQuality {
+    /// Mean structural similarity (0.0-1.0, 1.0 = unchanged)
+    pub ssim: f64,
+    /// Peak signal-to-noise ratio in dB (capped at `MAX_PSNR`)
+    pub psnr: f64,
+}
+
+/// Aggregate quality metrics for a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualitySummary {
+    pub mean_ssim: f64,
+    pub min_ssim: f64,
+    /// 1-based page with the lowest SSIM
+    pub min_ssim_page: usize,
+    pub mean_psnr: f64,
+    /// 1-based pages with SSIM below the threshold
+    pub low_ssim_pages: Vec<usize>,
+}
+
+impl QualitySummary {
+    /// Summarize per-page metrics (None when empty)
+    pub fn from_pages(pages: &[PageQuality], min_ssim: f64) -> Option<Self> {
+        if pages.is_empty() {
+            return None;
+        }
+        let count = pages.len() as f64;
+        let (min_idx, min) = pages
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.ssim.total_cmp(&b.1.ssim))?;
+
+        Some(Self {
+            mean_ssim: pages.iter().map(|p| p.ssim).sum::<f64>() / count,
+            min_ssim: min.ssim,
+            min_ssim_page: min_idx + 1,
+            mean_psnr: pages.iter().map(|p| p.psnr).sum::<f64>() / count,
+            low_ssim_pages: low_ssim_pages(pages, min_ssim),
+        })
+    }
+}
+
+/// 1-based pages whose SSIM is below `min_ssim`
+pub fn low_ssim_pages(pages: &[PageQuality], min_ssim: f64) -> Vec<usize> {
+    pages
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.ssim < min_ssim)
+        .map(|(i, _)| i + 1)
+        .collect()
+}
+
+// ============================================================
+// Metrics
+// ============================================================
+
+/// Compare an original page with its processed version
+///
+/// The processed page fixes the comparison aspect ratio; the original is
+/// stretched to the same grid.
+pub fn compare_pages(original: &Path, processed: &Path) -> Result<PageQuality> {
+    let processed = load_gray(processed)?;
+    let original = load_gray(original)?;
+
+    let (width, height) = metric_dimensions(processed.dimensions());
+    let a = image::imageops::resize(&original, width, height, FilterType::Triangle);
+    let b = image::imageops::resize(&processed, width, height, FilterType::Triangle);
+
+    Ok(PageQuality {
+        ssim: ssim(&a, &b)?,
+        psnr: psnr(&a, &b)?,
+    })
+}
+
+/// Mean SSIM over non-overlapping 8x8 windows
+pub fn ssim(a: &GrayImage, b: &GrayImage) -> Result<f64> {
+    check_dimensions(a, b)?;
+    let (width, height) = a.dimensions();
+    let window = SSIM_WINDOW.min(width).min(height).max(1);
+
+    let mut total = 0.0;
+    let mut windows = 0usize;
+    for y0 in (0..=height - window).step_by(window as usize) {
+        for x0 in (0..=width - window).step_by(window as usize) {
+            let (mut sum_a, mut sum_b) = (0.0, 0.0);
+            let (mut sum_aa, mut sum_bb, mut sum_ab) = (0.0, 0.0, 0.0);
+            for y in y0..y0 + window {
+                for x in x0..x0 + window {
+                    let va = f64::from(a.get_pixel(x, y).0[0]);
+                    let vb = f64::from(b.get_pixel(x, y).0[0]);
+                    sum_a += va;
+                    sum_b += vb;
+                    sum_aa += va * va;
+                    sum_bb += vb * vb;
+                    sum_ab += va * vb;
+                }
+            }
+            let n = f64::from(window * window);
+            let (mean_a, mean_b) = (sum_a / n, sum_b / n);
+            let var_a = sum_aa / n - mean_a * mean_a;
+            let var_b = sum_bb / n - mean_b * mean_b;
+            let cov = sum_ab / n - mean_a * mean_b;
+
+            total += ((2.0 * mean_a * mean_b + SSIM_C1) * (2.0 * cov + SSIM_C2))
+                / ((mean_a * mean_a + mean_b * mean_b + SSIM_C1) * (var_a + var_b + SSIM_C2));
+            windows += 1;
+        }
+    }
+
+    Ok(if windows == 0 { 1.0 } else { total / windows as f64 })
+}
+
+/// PSNR in dB (`MAX_PSNR` for identical images)
+pub fn psnr(a: &GrayImage, b: &GrayImage) -> Result<f64> {
+    check_dimensions(a, b)?;
+    let pixels = a.as_raw().len();
+    if pixels == 0 {
+        return Ok(MAX_PSNR);
+    }
+    let sse: f64 = a
+        .as_raw()
+        .iter()
+        .zip(b.as_raw())
+        .map(|(&va, &vb)| (f64::from(va) - f64::from(vb)).powi(2))
+        .sum();
+    let mse = sse / pixels as f64;
+    if mse == 0.0 {
+        return Ok(MAX_PSNR);
+    }
+    Ok((10.0 * (255.0 * 255.0 / mse).log10()).min(MAX_PSNR))
+}
+
+fn load_gray(path: &Path) -> Result<GrayImage> {
+    image::open(path)
+        .map(|img| img.to_luma8())
+        .map_err(|e| QualityError::ImageLoad(path.display().to_string(), e.to_string()))
+}
+
+fn check_dimensions(a: &GrayImage, b: &GrayImage) -> Result<()> {
+    if a.dimensions() == b.dimensions() {
+        Ok(())
+    } else {
+        Err(QualityError::SizeMismatch(a.dimensions(), b.dimensions()))
+    }
+}
+
+/// Scale so the longer side is at most `METRIC_SIZE`
+fn metric_dimensions((width, height): (u32, u32)) -> (u32, u32) {
+    let longer = width.max(height).max(1);
+    if longer <= METRIC_SIZE {
+        return (width.max(1), height.max(1));
+    }
+    let scale = f64::from(METRIC_SIZE) / f64::from(longer);
+    (
+        ((f64::from(width) * scale).round() as u32).max(1),
+        ((f64::from(height) * scale).round() as u32).max(1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Luma;
+
+    fn pattern(width: u32, height: u32) -> GrayImage {
+        GrayImage::from_fn(width, height, |x, y| {
+            if (x / 16 + y / 16) % 2 == 0 {
+                Luma([20])
+            } else {
+                Luma([230])
+            }
+        })
+    }
+
+    #[test]
+    fn test_identical_images() {
+        let img = pattern(64, 64);
+        assert!((ssim(&img, &img).unwrap() - 1.0).abs() < 1e-9);
+        assert_eq!(psnr(&img, &img).unwrap(), MAX_PSNR);
+    }
+
+    #[test]
+    fn test_degraded_image_scores_lower() {
+        let img = pattern(64, 64);
+        let noisy = GrayImage::from_fn(64, 64, |x, y| {
+            let v = img.get_pixel(x, y).0[0] as i32 + if (x + y) % 2 == 0 { 5 } else { -5 };
+            Luma([v.clamp(0, 255) as u8])
+        });
+        let inverted = GrayImage::from_fn(64, 64, |x, y| Luma([255 - img.get_pixel(x, y).0[0]]));
+
+        let noisy_ssim = ssim(&img, &noisy).unwrap();
+        let inverted_ssim = ssim(&img, &inverted).unwrap();
+        assert!(noisy_ssim < 0.9 && noisy_ssim > 0.5, "{noisy_ssim}");
+        assert!(inverted_ssim < DEFAULT_MIN_SSIM, "{inverted_ssim}");
+
+        let noisy_psnr = psnr(&img, &noisy).unwrap();
+        assert!((noisy_psnr - 34.15).abs() < 0.1, "{noisy_psnr}");
+    }
+
+    #[test]
+    fn test_size_mismatch() {
+        let result = ssim(&pattern(8, 8), &pattern(16, 8));
+        assert!(matches!(result, Err(QualityError::SizeMismatch(..))));
+    }
+
+    #[test]
+    fn test_compare_pages_resizes_to_processed() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = dir.path().join("original.png");
+        let processed = dir.path().join("processed.png");
+        // Same content at a different resolution (e.g. after upscaling)
+        pattern(256, 256).save(&original).unwrap();
+        image::imageops::resize(&pattern(256, 256), 1024, 1024, FilterType::Nearest)
+            .save(&processed)
+            .unwrap();
+
+        let page = compare_pages(&original, &processed).unwrap();
+        assert!(page.ssim > 0.8, "{}", page.ssim);
+        assert!(page.psnr > 20.0, "{}", page.psnr);
+
+        assert!(matches!(
+            compare_pages(&dir.path().join("missing.png"), &processed),
+            Err(QualityError::ImageLoad(..))
+        ));
+    }
+
+    #[test]
+    fn test_summary() {
+        let pages = [
+            PageQuality { ssim: 0.9, psnr: 30.0 },
+            PageQuality { ssim: 0.3, psnr: 12.0 },
+            PageQuality { ssim: 0.8, psnr: 27.0 },
+        ];
+        let summary = QualitySummary::from_pages(&pages, DEFAULT_MIN_SSIM).unwrap();
+        assert!((summary.mean_ssim - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(summary.min_ssim, 0.3);
+        assert_eq!(summary.min_ssim_page, 2);
+        assert_eq!(summary.mean_psnr, 23.0);
+        assert_eq!(summary.low_ssim_pages, vec![2]);
+        assert!(QualitySummary::from_pages(&[], DEFAULT_MIN_SSIM).is_none());
+    }
+}
@@ -0,0 +1,308 @@
+//! Per-book progress file for resuming an interrupted conversion (`--resume`)
+//!
+//! A book of a few thousand pages runs for hours, and an interrupted run used
+//! to start over from the first page. While pages stream through extraction
+//! and the per-page stages, [`ProgressFile`] records in the work directory
+//! which pages completed which stage and where the result is. A rerun with
+//! `--resume` reads it back and renders and processes only the missing pages.
+//!
+//! Unlike the work cache ([`crate::work_cache`]), which is shared across books
+//! and keyed by page content, this belongs to one book's work directory and is
+//! keyed by page number, so resumed pages are not even rendered.
+//!
+//! - The file carries a [`crate::CacheDigest`] of the source file and the
+//!   options that shape the page images; progress with another digest is
+//!   discarded
+//! - Writes are throttled to one per [`SAVE_INTERVAL`] (callers force one
+//!   after each processed batch) and go through a temporary file and a
+//!   rename, so an interruption never leaves a partial file
+//! - A recorded page whose image has gone missing is processed again
+//!
+//! The document-wide stages (color statistics, group crop, page numbers) and
+//! OCR need every page and always run again.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//! use superbook_pdf::{CacheDigest, PageStage, ProgressFile};
+//!
+//! let work_dir = Path::new("out/.work_book");
+//! let digest = CacheDigest::new("book.pdf", "{\"dpi\":300}").unwrap();
+//! let progress = ProgressFile::resume(work_dir, digest).unwrap();
+//! if let Some(image) = progress.completed(0, PageStage::PageStages) {
+//!     println!("page 1 is done: {}", image.display());
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Name of the progress file in the work directory
+pub const PROGRESS_FILE_NAME: &str = "progress.json";
+
+/// Minimum time between two throttled writes of the progress file
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Progress file format version
+const PROGRESS_VERSION: u32 = 1;
+
+/// Why earlier progress cannot be resumed
+#[derive(Debug, Error)]
+pub enum ResumeError {
+    #[error("no progress file in {0}")]
+    NotFound(PathBuf),
+
+    #[error("source file changed since the interrupted run")]
+    SourceChanged,
+
+    #[error("options changed since the interrupted run")]
+    OptionsChanged,
+
+    #[error("Invalid progress file: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Page-level stages recorded in the progress file, in processing order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStage {
+    /// Page image rendered from the source
+    Extract,
+    /// Per-page stages (inversion through deskew) finished
+    PageStages,
+}
+
+/// Last stage one page completed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageProgress {
+    /// Completed stage (earlier stages are implied)
+    pub stage: PageStage,
+    /// Output of the stage, relative to the work directory
+    pub image: PathBuf,
+    /// Size of the extracted page image
+    #[serde(default)]
+    pub source_size: (u32, u32),
+    /// Transforms the page stages applied, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<crate::PageTransform>,
+    /// Inverted as a negative scan
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub inverted: bool,
+    /// Highlighter marks were removed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub marked: bool,
+}
+
+impl PageProgress {
+    /// Page geometry as recorded
+    pub fn geometry(&self) -> crate::PageGeometry {
+        crate::PageGeometry {
+            source_size: self.source_size,
+            transforms: self.transforms.clone(),
+            inverted: self.inverted,
+            marked: self.marked,
+            placeholder: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProgressState {
+    version: u32,
+    digest: crate::CacheDigest,
+    /// 0-based source page → progress
+    pages: BTreeMap<usize, PageProgress>,
+}
+
+/// Progress of one book, persisted in its work directory
+#[derive(Debug)]
+pub struct ProgressFile {
+    work_dir: PathBuf,
+    state: ProgressState,
+    last_save: Option<Instant>,
+}
+
+impl ProgressFile {
+    /// Start recording a run identified by `digest` (earlier progress is
+    /// overwritten on the first save)
+    pub fn new(work_dir: &Path, digest: crate::CacheDigest) -> Self {
+        Self {
+            work_dir: work_dir.to_path_buf(),
+            state: ProgressState {
+                version: PROGRESS_VERSION,
+                digest,
+                pages: BTreeMap::new(),
+            },
+            last_save: None,
+        }
+    }
+
+    /// Continue the progress of an interrupted run with the same `digest`
+    pub fn resume(work_dir: &Path, digest: crate::CacheDigest) -> Result<Self, ResumeError> {
+        let path = Self::path(work_dir);
+        if !path.exists() {
+            return Err(ResumeError::NotFound(work_dir.to_path_buf()));
+        }
+        let state: ProgressState = serde_json::from_str(&fs::read_to_string(&path)?)?;
+        if state.version != PROGRESS_VERSION || state.digest.options_hash != digest.options_hash {
+            return Err(ResumeError::OptionsChanged);
+        }
+        if state.digest != digest {
+            return Err(ResumeError::SourceChanged);
+        }
+        Ok(Self {
+            work_dir: work_dir.to_path_buf(),
+            state,
+            last_save: None,
+        })
+    }
+
+    /// Path of the progress file in `work_dir`
+    pub fn path(work_dir: &Path) -> PathBuf {
+        work_dir.join(PROGRESS_FILE_NAME)
+    }
+
+    /// Number of pages with recorded progress
+    pub fn len(&self) -> usize {
+        self.state.pages.len()
+    }
+
+    /// Whether no page has recorded progress
+    pub fn is_empty(&self) -> bool {
+        self.state.pages.is_empty()
+    }
+
+    /// Recorded progress of `page` (0-based source page)
+    pub fn page(&self, page: usize) -> Option<&PageProgress> {
+        self.state.pages.get(&page)
+    }
+
+    /// Image of `page` if it completed `stage` or a later one and the image
+    /// is still on disk
+    pub fn completed(&self, page: usize, stage: PageStage) -> Option<PathBuf> {
+        let progress = self.page(page).filter(|progress| progress.stage >= stage)?;
+        Some(self.work_dir.join(&progress.image)).filter(|path| path.is_file())
+    }
+
+    /// Record that `page` completed `stage` with `image` (absolute or
+    /// relative to the work directory) and the page's geometry so far
+    pub fn record(&mut self, page: usize, stage: PageStage, image: &Path, geometry: &crate::PageGeometry) {
+        let image = image.strip_prefix(&self.work_dir).unwrap_or(image).to_path_buf();
+        let progress = PageProgress {
+            stage,
+            image,
+            source_size: geometry.source_size,
+            transforms: geometry.transforms.clone(),
+            inverted: geometry.inverted,
+            marked: geometry.marked,
+        };
+        self.state.pages.insert(page, progress);
+    }
+
+    /// Write the file unless it was written less than [`SAVE_INTERVAL`] ago
+    pub fn save_throttled(&mut self) -> Result<(), ResumeError> {
+        if self.last_save.is_some_and(|last| last.elapsed() < SAVE_INTERVAL) {
+            return Ok(());
+        }
+        self.save()
+    }
+
+    /// Write the file now
+    pub fn save(&mut self) -> Result<(), ResumeError> {
+        let path = Self::path(&self.work_dir);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string(&self.state)?)?;
+        fs::rename(&tmp, &path)?;
+        self.last_save = Some(Instant::now());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest(options: &str) -> crate::CacheDigest {
+        crate::CacheDigest::with_values(1_700_000_000, 4096, &crate::hash_options(options))
+    }
+
+    #[test]
+    fn test_record_save_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let work_dir = dir.path();
+        fs::create_dir_all(work_dir.join("deskewed")).unwrap();
+        let image = work_dir.join("deskewed").join("page_00001.png");
+        fs::write(&image, b"png").unwrap();
+
+        let mut progress = ProgressFile::new(work_dir, digest("{}"));
+        let geometry = crate::PageGeometry {
+            source_size: (32, 48),
+            transforms: vec![crate::PageTransform::Crop { x: 1, y: 2, width: 30, height: 44 }],
+            inverted: true,
+            marked: true,
+            placeholder: false,
+        };
+        progress.record(1, PageStage::PageStages, &image, &geometry);
+        let extracted = work_dir.join("extracted").join("page_00002.png");
+        progress.record(2, PageStage::Extract, &extracted, &crate::PageGeometry::default());
+        progress.save().unwrap();
+
+        let resumed = ProgressFile::resume(work_dir, digest("{}")).unwrap();
+        assert_eq!(resumed.len(), 2);
+        // Stored relative to the work directory
+        let page = resumed.page(1).unwrap();
+        assert_eq!(page.image, Path::new("deskewed").join("page_00001.png"));
+        assert_eq!(page.geometry(), geometry);
+        assert_eq!(resumed.completed(1, PageStage::Extract), Some(image.clone()));
+        assert_eq!(resumed.completed(1, PageStage::PageStages), Some(image));
+        // Stage not reached, image missing, page not recorded
+        assert!(resumed.completed(2, PageStage::PageStages).is_none());
+        assert!(resumed.completed(2, PageStage::Extract).is_none());
+        assert!(resumed.completed(0, PageStage::Extract).is_none());
+        // No temporary file is left behind
+        assert_eq!(fs::read_dir(work_dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_resume_rejects_other_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            ProgressFile::resume(dir.path(), digest("{}")),
+            Err(ResumeError::NotFound(_))
+        ));
+
+        ProgressFile::new(dir.path(), digest("{}")).save().unwrap();
+        assert!(matches!(
+            ProgressFile::resume(dir.path(), digest("{\"dpi\":600}")),
+            Err(ResumeError::OptionsChanged)
+        ));
+        let touched = crate::CacheDigest::with_values(1_700_000_001, 4096, &crate::hash_options("{}"));
+        assert!(matches!(ProgressFile::resume(dir.path(), touched), Err(ResumeError::SourceChanged)));
+
+        fs::write(ProgressFile::path(dir.path()), "{").unwrap();
+        assert!(matches!(ProgressFile::resume(dir.path(), digest("{}")), Err(ResumeError::Json(_))));
+    }
+
+    #[test]
+    fn test_save_throttled() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut progress = ProgressFile::new(dir.path(), digest("{}"));
+        progress.save_throttled().unwrap();
+        assert!(ProgressFile::path(dir.path()).exists());
+
+        // Within the interval the file is left as it is
+        progress.record(0, PageStage::Extract, Path::new("extracted/page_00000.png"), &Default::default());
+        progress.save_throttled().unwrap();
+        assert!(ProgressFile::resume(dir.path(), digest("{}")).unwrap().is_empty());
+        progress.save().unwrap();
+        assert_eq!(ProgressFile::resume(dir.path(), digest("{}")).unwrap().len(), 1);
+    }
+}
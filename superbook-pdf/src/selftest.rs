@@ -0,0 +1,485 @@
+//! End-to-end self test (`superbook-pdf selftest`)
+//!
+//! Writes a small synthetic book to a PDF (lines of "text" inside wide
+//! margins, with a dark shadow along the binding edge as on a flatbed
+//! scan), converts it with the given configuration and checks that the
+//! output PDF has one page per input page. Every stage the configuration
+//! runs is reported with the tool that served it, a fallback or a skip, so
+//! a run shows what actually works on this install rather than only which
+//! executables are on PATH (`superbook-pdf stages`).
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::selftest::SelfTest;
+//! use superbook_pdf::pipeline::SilentProgress;
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let report = SelfTest::new().run(dir.path(), &SilentProgress).unwrap();
+//! for stage in &report.stages {
+//!     println!("{}: {}", stage.key, stage.outcome);
+//! }
+//! assert!(report.passed());
+//! ```
+
+use image::{Rgb, RgbImage};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+use crate::pipeline::{PdfPipeline, PipelineConfig, PipelineResult, ProgressCallback};
+use crate::stages::{stage_statuses, Requirement, StageStatus};
+
+// ============================================================
+// Constants
+// ============================================================
+
+/// Pages in the synthetic book
+pub const DEFAULT_PAGES: usize = 4;
+
+/// Resolution the synthetic pages are written at
+const PAGE_DPI: u32 = 150;
+
+/// A5 at `PAGE_DPI`
+const PAGE_WIDTH: u32 = 874;
+const PAGE_HEIGHT: u32 = 1240;
+
+/// Text block inset from each edge (share of the page size)
+const MARGIN: f64 = 0.12;
+
+/// Distance between baselines and height of a text line (pixels)
+const LINE_PITCH: u32 = 30;
+const LINE_HEIGHT: u32 = 14;
+
+/// Width of the binding shadow (pixels) and how much it darkens the paper at the edge
+const SHADOW_WIDTH: u32 = 70;
+const SHADOW_DEPTH: f64 = 150.0;
+
+/// Quality of the page JPEGs
+const JPEG_QUALITY: u8 = 85;
+
+const PAPER: Rgb<u8> = Rgb([246, 244, 238]);
+const INK: Rgb<u8> = Rgb([30, 30, 30]);
+
+// ============================================================
+// Error Types
+// ============================================================
+
+/// Self test errors
+#[derive(Debug, Error)]
+pub enum SelftestError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Could not write the test PDF: {0}")]
+    TestPdf(String),
+
+    #[error("Self test failed: {0}")]
+    Failed(String),
+}
+
+pub type Result<T> = std::result::Result<T, SelftestError>;
+
+// ============================================================
+// Report
+// ============================================================
+
+/// How a stage fared in the self test
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageOutcome {
+    /// Ran, using the tool if it needs one
+    Passed(Option<Requirement>),
+    /// Ran without its tool, degraded as described
+    Fallback(&'static str),
+    /// Skipped because the tool is missing
+    Skipped(&'static str),
+    /// The conversion failed before the stage could be confirmed
+    NotRun,
+}
+
+impl StageOutcome {
+    /// Whether the stage did its job with its own tool (or needs none)
+    pub fn is_pass(&self) -> bool {
+        matches!(self, StageOutcome::Passed(_))
+    }
+}
+
+impl fmt::Display for StageOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StageOutcome::Passed(Some(tool)) => write!(f, "PASS ({})", tool),
+            StageOutcome::Passed(None) => write!(f, "PASS"),
+            StageOutcome::Fallback(fallback) => write!(f, "FALLBACK ({})", fallback),
+            StageOutcome::Skipped(tool) => write!(f, "SKIP ({} not found)", tool),
+            StageOutcome::NotRun => write!(f, "FAIL"),
+        }
+    }
+}
+
+/// One stage of the self test
+#[derive(Debug, Clone)]
+pub struct StageReport {
+    /// Stage key (see [`crate::stages::STAGES`])
+    pub key: &'static str,
+    /// Seconds spent in the stage, when timed
+    pub seconds: Option<f64>,
+    pub outcome: StageOutcome,
+    /// How to install the missing tool (fallbacks and skips)
+    pub hint: Option<String>,
+}
+
+/// Result of a self test run
+#[derive(Debug, Clone)]
+pub struct SelftestReport {
+    /// Synthetic input PDF
+    pub input: PathBuf,
+    /// Output of the conversion (when it finished)
+    pub output: Option<PathBuf>,
+    /// Pages written to the input
+    pub expected_pages: usize,
+    /// Pages found in the output (when it could be read)
+    pub output_pages: Option<usize>,
+    /// Stages run by the configuration, in pipeline order
+    pub stages: Vec<StageReport>,
+    /// Why the run failed (conversion error or page count mismatch)
+    pub failure: Option<String>,
+    pub elapsed_seconds: f64,
+}
+
+impl SelftestReport {
+    /// Whether the conversion produced the expected output
+    ///
+    /// Fallbacks and skipped stages do not fail the test; they are listed
+    /// so the missing tools can be installed.
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+
+    /// Tools and Python modules the passing stages used, without repeats
+    pub fn tools_exercised(&self) -> Vec<Requirement> {
+        let mut tools = Vec::new();
+        for stage in &self.stages {
+            if let StageOutcome::Passed(Some(tool)) = stage.outcome {
+                if !tools.contains(&tool) {
+                    tools.push(tool);
+                }
+            }
+        }
+        tools
+    }
+}
+
+// ============================================================
+// Self Test
+// ============================================================
+
+/// Converts a synthetic book and reports per stage
+pub struct SelfTest {
+    config: PipelineConfig,
+    pages: usize,
+}
+
+impl Default for SelfTest {
+    fn default() -> Self {
+        Self {
+            // Missing OCR tools are reported as skips rather than aborting
+            config: PipelineConfig::default().with_ocr_optional(true),
+            pages: DEFAULT_PAGES,
+        }
+    }
+}
+
+impl SelfTest {
+    /// Self test of the default pipeline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder pattern: set the pipeline configuration
+    pub fn with_config(mut self, config: PipelineConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Builder pattern: set the number of synthetic pages (minimum 1)
+    pub fn with_pages(mut self, pages: usize) -> Self {
+        self.pages = pages.max(1);
+        self
+    }
+
+    /// Write the synthetic book under `work_dir`, convert it and check the output
+    ///
+    /// Errors only when the test input cannot be prepared; a failing
+    /// conversion is recorded in the report. The book is extracted at the
+    /// resolution it was written at (`PAGE_DPI`), so a healthy install
+    /// reports no low-source-DPI warning.
+    pub fn run<P: ProgressCallback>(&self, work_dir: &Path, progress: &P) -> Result<SelftestReport> {
+        let start = std::time::Instant::now();
+        progress.on_step_start("Writing test PDF...");
+        let input = work_dir.join("selftest.pdf");
+        write_synthetic_pdf(&input, self.pages)?;
+        progress.on_step_complete("Writing test PDF", &format!("{} pages", self.pages));
+
+        let output_dir = work_dir.join("output");
+        std::fs::create_dir_all(&output_dir)?;
+        let config = self.config.clone().with_dpi(PAGE_DPI);
+        let result = PdfPipeline::new(config.clone())
+            .check_ocr_dependencies()
+            .and_then(|pipeline| pipeline.process_with_progress(&input, &output_dir, progress));
+
+        let statuses = stage_statuses(&config);
+        let (result, mut failure) = match result {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        let output_pages = result
+            .as_ref()
+            .and_then(|result| crate::LopdfReader::new(&result.output_path).ok())
+            .map(|reader| reader.info.page_count);
+        if result.is_some() && failure.is_none() && output_pages != Some(self.pages) {
+            failure = Some(match output_pages {
+                Some(pages) => format!("output has {} pages, expected {}", pages, self.pages),
+                None => "output PDF could not be read".to_string(),
+            });
+        }
+
+        Ok(SelftestReport {
+            input,
+            output: result.as_ref().map(|result| result.output_path.clone()),
+            expected_pages: self.pages,
+            output_pages,
+            stages: statuses
+                .iter()
+                .filter(|status| status.enabled)
+                .map(|status| stage_report(status, result.as_ref()))
+                .collect(),
+            failure,
+            elapsed_seconds: start.elapsed().as_secs_f64(),
+        })
+    }
+}
+
+fn stage_report(status: &StageStatus, result: Option<&PipelineResult>) -> StageReport {
+    let stage = status.stage;
+    let hint = || stage.requires.first().map(Requirement::hint);
+    let (outcome, hint) = match result {
+        None => (StageOutcome::NotRun, None),
+        Some(result) => match result.skipped_stages.iter().find(|skipped| skipped.stage == stage.key) {
+            Some(skipped) => (StageOutcome::Skipped(skipped.tool), hint()),
+            None => match (status.available.first(), stage.fallback) {
+                (Some(&tool), _) => (StageOutcome::Passed(Some(tool)), None),
+                (None, Some(fallback)) if !stage.requires.is_empty() => (StageOutcome::Fallback(fallback), hint()),
+                (None, _) => (StageOutcome::Passed(None), None),
+            },
+        },
+    };
+    StageReport {
+        key: stage.key,
+        seconds: result.and_then(|result| result.stage_timings.get(stage.key)),
+        outcome,
+        hint,
+    }
+}
+
+// ============================================================
+// Synthetic Book
+// ============================================================
+
+/// Write a `pages`-page synthetic book to `path`
+///
+/// Each page is one full-page JPEG image, as in a scanned book, so even
+/// the pure Rust extraction (no pdftoppm / ImageMagick) can read it.
+pub fn write_synthetic_pdf(path: &Path, pages: usize) -> Result<()> {
+    use lopdf::{dictionary, Object, Stream};
+
+    let to_err = |e: String| SelftestError::TestPdf(e);
+    let width_pt = PAGE_WIDTH as f64 * 72.0 / PAGE_DPI as f64;
+    let height_pt = PAGE_HEIGHT as f64 * 72.0 / PAGE_DPI as f64;
+
+    let mut doc = lopdf::Document::with_version("1.5");
+    let pages_id = doc.new_object_id();
+    let mut kids = Vec::with_capacity(pages);
+    for index in 0..pages {
+        let mut jpeg = Vec::new();
+        image::DynamicImage::ImageRgb8(synthetic_page(index))
+            .write_with_encoder(image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY))
+            .map_err(|e| to_err(e.to_string()))?;
+        let image_id = doc.add_object(Stream::new(
+            dictionary! {
+                "Type" => "XObject",
+                "Subtype" => "Image",
+                "Width" => PAGE_WIDTH,
+                "Height" => PAGE_HEIGHT,
+                "ColorSpace" => "DeviceRGB",
+                "BitsPerComponent" => 8,
+                "Filter" => "DCTDecode",
+            },
+            jpeg,
+        ));
+        let content = format!("q {:.2} 0 0 {:.2} 0 0 cm /Im0 Do Q", width_pt, height_pt);
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.into_bytes()));
+        kids.push(Object::Reference(doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![0.into(), 0.into(), Object::Real(width_pt as f32), Object::Real(height_pt as f32)],
+            "Resources" => dictionary! { "XObject" => dictionary! { "Im0" => image_id } },
+            "Contents" => content_id,
+        })));
+    }
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! { "Type" => "Pages", "Count" => pages as i64, "Kids" => kids }),
+    );
+    let catalog_id = doc.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+    doc.trailer.set("Root", catalog_id);
+    let info_id = doc.add_object(dictionary! { "Title" => Object::string_literal("superbook-pdf self test") });
+    doc.trailer.set("Info", info_id);
+
+    doc.save(path)?;
+    Ok(())
+}
+
+/// Page `index` (0-based) of the synthetic book
+///
+/// Text lines of varying word lengths fill the area inside the margins; the
+/// binding shadow is on the left of odd pages and the right of even pages.
+pub fn synthetic_page(index: usize) -> RgbImage {
+    let mut page = RgbImage::from_pixel(PAGE_WIDTH, PAGE_HEIGHT, PAPER);
+    let left = (PAGE_WIDTH as f64 * MARGIN) as u32;
+    let right = PAGE_WIDTH - left;
+    let top = (PAGE_HEIGHT as f64 * MARGIN) as u32;
+    let bottom = PAGE_HEIGHT - top;
+
+    for (line, y) in (top..bottom - LINE_HEIGHT).step_by(LINE_PITCH as usize).enumerate() {
+        let mut x = left;
+        for word in 0.. {
+            let width = 24 + ((index * 7 + line * 13 + word * 29) % 5) as u32 * 18;
+            if x + width > right {
+                break;
+            }
+            for px in x..x + width {
+                for py in y..y + LINE_HEIGHT {
+                    page.put_pixel(px, py, INK);
+                }
+            }
+            x += width + 12;
+        }
+    }
+
+    let binding_on_left = index % 2 == 0;
+    for distance in 0..SHADOW_WIDTH {
+        let falloff = 1.0 - distance as f64 / SHADOW_WIDTH as f64;
+        let darken = SHADOW_DEPTH * falloff * falloff;
+        let x = if binding_on_left { distance } else { PAGE_WIDTH - 1 - distance };
+        for y in 0..PAGE_HEIGHT {
+            let pixel = page.get_pixel_mut(x, y);
+            for channel in pixel.0.iter_mut() {
+                *channel = (*channel as f64 - darken).max(0.0) as u8;
+            }
+        }
+    }
+    page
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stages::STAGES;
+
+    #[test]
+    fn test_synthetic_page() {
+        let odd = synthetic_page(0);
+        let even = synthetic_page(1);
+        assert_eq!(odd.dimensions(), (PAGE_WIDTH, PAGE_HEIGHT));
+
+        // Margins are paper, the text block has ink
+        assert_eq!(*odd.get_pixel(PAGE_WIDTH / 2, 20), PAPER);
+        assert_eq!(*odd.get_pixel(PAGE_WIDTH / 2, PAGE_HEIGHT - 20), PAPER);
+        let top = (PAGE_HEIGHT as f64 * MARGIN) as u32;
+        let left = (PAGE_WIDTH as f64 * MARGIN) as u32;
+        assert_eq!(*odd.get_pixel(left + 2, top + 2), INK);
+
+        // The shadow darkens the binding edge only
+        assert!(odd.get_pixel(0, 10).0[0] < 120);
+        assert_eq!(*odd.get_pixel(PAGE_WIDTH - 1, 10), PAPER);
+        assert!(even.get_pixel(PAGE_WIDTH - 1, 10).0[0] < 120);
+        assert_eq!(*even.get_pixel(0, 10), PAPER);
+    }
+
+    #[test]
+    fn test_write_synthetic_pdf() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("book.pdf");
+        write_synthetic_pdf(&path, 3).unwrap();
+
+        let reader = crate::LopdfReader::new(&path).unwrap();
+        assert_eq!(reader.info.page_count, 3);
+        assert!(reader.info.pages.iter().all(|page| page.width_pt < page.height_pt));
+    }
+
+    #[test]
+    fn test_stage_report() {
+        let status = |key: &str, available: Vec<Requirement>| StageStatus {
+            stage: STAGES.iter().find(|stage| stage.key == key).unwrap(),
+            enabled: true,
+            available,
+        };
+        let result = PipelineResult::new(1, None, false, 0.0, PathBuf::from("out.pdf"), 0);
+
+        let deskew = stage_report(&status("deskew", vec![]), Some(&result));
+        assert_eq!(deskew.outcome, StageOutcome::Passed(None));
+        assert_eq!(deskew.outcome.to_string(), "PASS");
+
+        let tool = Requirement::PythonModule("realesrgan");
+        let upscale = stage_report(&status("upscale", vec![tool]), Some(&result));
+        assert_eq!(upscale.outcome, StageOutcome::Passed(Some(tool)));
+
+        let missing = stage_report(&status("upscale", vec![]), Some(&result));
+        assert!(matches!(missing.outcome, StageOutcome::Fallback(_)));
+        assert_eq!(missing.hint.as_deref(), Some("pip install realesrgan"));
+
+        let skipped_result = result.clone().with_skipped_stages(vec![crate::pipeline::SkippedStage {
+            stage: "page_numbers",
+            tool: "tesseract",
+        }]);
+        let skipped = stage_report(&status("page_numbers", vec![]), Some(&skipped_result));
+        assert_eq!(skipped.outcome.to_string(), "SKIP (tesseract not found)");
+
+        assert_eq!(stage_report(&status("deskew", vec![]), None).outcome, StageOutcome::NotRun);
+    }
+
+    #[derive(Default)]
+    struct Warnings(std::sync::Mutex<Vec<String>>);
+
+    impl ProgressCallback for Warnings {
+        fn on_step_start(&self, _step: &str) {}
+        fn on_step_progress(&self, _current: usize, _total: usize) {}
+        fn on_step_complete(&self, _step: &str, _message: &str) {}
+        fn on_debug(&self, _message: &str) {}
+        fn on_warning(&self, message: &str) {
+            self.0.lock().unwrap().push(message.to_string());
+        }
+    }
+
+    #[test]
+    fn test_selftest_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = PipelineConfig::quick().with_ocr_optional(true).with_dpi(300);
+        let warnings = Warnings::default();
+        let report = SelfTest::new()
+            .with_config(config)
+            .with_pages(2)
+            .run(dir.path(), &warnings)
+            .unwrap();
+
+        // The fixture is extracted at its own resolution
+        let warnings = warnings.0.into_inner().unwrap();
+        assert!(warnings.iter().all(|w| !w.contains("DPI")), "{:?}", warnings);
+
+        assert!(report.passed(), "{:?}", report.failure);
+        assert_eq!(report.output_pages, Some(2));
+        assert!(report.output.as_ref().is_some_and(|output| output.exists()));
+        assert!(report.stages.iter().any(|stage| stage.key == "extract"));
+        assert!(report.stages.iter().all(|stage| stage.outcome != StageOutcome::NotRun));
+    }
+}
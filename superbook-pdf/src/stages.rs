@@ -0,0 +1,404 @@
+//! Catalog of pipeline stages for `superbook-pdf stages`
+//!
+//! Lists every stage in pipeline order with the option that controls it,
+//! whether the default configuration runs it, and the external tools or
+//! Python modules it needs. [`stage_statuses`] probes those requirements
+//! with the same checks as `superbook-pdf info`, so users can see what to
+//! install before enabling a stage.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::stages::stage_statuses;
+//! use superbook_pdf::PipelineConfig;
+//!
+//! for status in stage_statuses(&PipelineConfig::default()) {
+//!     println!("{}: {}", status.stage.key, if status.is_ready() { "ready" } else { "missing" });
+//! }
+//! ```
+
+use crate::pipeline::PipelineConfig;
+use std::collections::HashMap;
+use std::fmt;
+use std::process::Command;
+
+/// External dependency of a stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Requirement {
+    /// Executable resolved through [`crate::tools`]
+    Tool(&'static str),
+    /// Python module of the AI tools
+    PythonModule(&'static str),
+}
+
+impl Requirement {
+    /// Whether the requirement is currently available
+    pub fn is_available(&self) -> bool {
+        match self {
+            Requirement::Tool(name) => crate::tools::is_available(name),
+            Requirement::PythonModule(module) => python_module_available(module),
+        }
+    }
+
+    /// How to make a missing requirement available
+    pub fn hint(&self) -> String {
+        match self {
+            Requirement::Tool(name) => format!(
+                "install {} or set {} / [tools] {}",
+                name,
+                crate::tools::env_var_name(name),
+                name
+            ),
+            Requirement::PythonModule(module) => format!("pip install {}", module),
+        }
+    }
+}
+
+impl fmt::Display for Requirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Requirement::Tool(name) => write!(f, "{}", name),
+            Requirement::PythonModule(module) => write!(f, "{} (Python)", module),
+        }
+    }
+}
+
+/// One pipeline stage
+#[derive(Debug, Clone, Copy)]
+pub struct Stage {
+    /// Stage key (matches stage timing keys where one exists)
+    pub key: &'static str,
+    /// What the stage does
+    pub description: &'static str,
+    /// Convert option that enables or configures it
+    pub option: &'static str,
+    /// Whether a configuration runs the stage
+    pub enabled: fn(&PipelineConfig) -> bool,
+    /// Requirements, any one of which suffices (empty = built in)
+    pub requires: &'static [Requirement],
+    /// What happens when no requirement is available (`None` = the stage fails)
+    pub fallback: Option<&'static str>,
+}
+
+/// Stages in pipeline order
+pub const STAGES: &[Stage] = &[
+    Stage {
+        key: "extract",
+        description: "Render PDF pages to images",
+        option: "--dpi",
+        enabled: |_| true,
+        requires: &[Requirement::Tool("pdftoppm"), Requirement::Tool("magick")],
+        fallback: Some("embedded JPEG page images only"),
+    },
+    Stage {
+        key: "invert",
+        description: "Invert negative scans",
+        option: "--invert",
+        enabled: |config| !config.invert.is_off(),
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "line_artifacts",
+        description: "Remove scanner line artifacts",
+        option: "--remove-line-artifacts",
+        enabled: |config| config.remove_line_artifacts,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "markers",
+        description: "Remove highlighter marks",
+        option: "--remove-markers",
+        enabled: |config| config.remove_markers,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "trim",
+        description: "Trim page margins",
+        option: "--margin-trim",
+        enabled: |config| config.margin_trim > 0.0,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "upscale",
+        description: "AI upscaling (RealESRGAN 2x)",
+        option: "--upscale",
+        enabled: |config| config.upscale,
+        requires: &[Requirement::PythonModule("realesrgan")],
+        fallback: Some("pages keep their size"),
+    },
+    Stage {
+        key: "normalize",
+        description: "Normalize to the internal resolution",
+        option: "--internal-resolution",
+        enabled: |config| config.internal_resolution,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "deskew",
+        description: "Correct page skew",
+        option: "--deskew",
+        enabled: |config| config.deskew,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "color",
+        description: "Global color correction",
+        option: "--color-correction",
+        enabled: |config| config.color_correction,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "contrast",
+        description: "Automatic contrast",
+        option: "--auto-contrast",
+        enabled: |config| config.auto_contrast.is_enabled(),
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "tone_curve",
+        description: "Apply a tone curve",
+        option: "--tone-curve",
+        enabled: |config| config.tone_curve.is_some(),
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "desaturate",
+        description: "Remove low-saturation color noise",
+        option: "--desaturate-below",
+        enabled: |config| config.desaturate_below.is_some(),
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "register_sides",
+        description: "Shift odd/even pages so their content lines up",
+        option: "--register-sides",
+        enabled: |config| config.register_sides,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "group_crop",
+        description: "Crop odd/even pages to a common content area",
+        option: "--offset-alignment",
+        enabled: |config| config.offset_alignment,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "aspect",
+        description: "Fit pages to an aspect ratio",
+        option: "--target-aspect",
+        enabled: |config| config.target_aspect.is_some(),
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "page_numbers",
+        description: "Detect page numbers (offset alignment, page labels)",
+        option: "--offset-alignment / --page-labels",
+        enabled: |config| config.offset_alignment || config.page_labels,
+        requires: &[Requirement::Tool("tesseract")],
+        fallback: Some("skipped with --ocr-optional"),
+    },
+    Stage {
+        key: "finalize",
+        description: "Resize to the output height",
+        option: "--output-height",
+        enabled: |config| config.output_height != 0 && config.output_height != 7016,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "quality",
+        description: "SSIM / PSNR against the extracted pages",
+        option: "--quality-metrics",
+        enabled: |config| config.quality_metrics,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "ocr",
+        description: "Japanese OCR (YomiToku)",
+        option: "--ocr",
+        enabled: |config| config.ocr,
+        requires: &[Requirement::PythonModule("yomitoku")],
+        fallback: Some("skipped with --ocr-optional"),
+    },
+    Stage {
+        key: "running_heads",
+        description: "Detect running headers and footers",
+        option: "--running-heads",
+        enabled: |config| config.running_heads != crate::markdown::RunningHeadMode::Keep,
+        requires: &[Requirement::PythonModule("yomitoku")],
+        fallback: Some("skipped with a warning"),
+    },
+    Stage {
+        key: "ocr_export",
+        description: "Write hOCR / ALTO",
+        option: "--ocr-format",
+        enabled: |config| !config.ocr_formats.is_empty(),
+        requires: &[Requirement::PythonModule("yomitoku")],
+        fallback: Some("skipped with --ocr-optional"),
+    },
+    Stage {
+        key: "text_sidecar",
+        description: "Write the OCR text to <output>.txt",
+        option: "--text-sidecar",
+        enabled: |config| config.write_text_sidecar,
+        requires: &[Requirement::PythonModule("yomitoku")],
+        fallback: Some("skipped with --ocr-optional"),
+    },
+    Stage {
+        key: "color_detect",
+        description: "Write text-only pages in grayscale",
+        option: "--mixed-color",
+        enabled: |config| config.mixed_color,
+        requires: &[],
+        fallback: None,
+    },
+    Stage {
+        key: "figure_detect",
+        description: "Keep only detected figures in color",
+        option: "--color-policy figures-only",
+        enabled: |config| config.color_policy == crate::ColorPolicy::FiguresOnly,
+        requires: &[Requirement::PythonModule("yomitoku")],
+        fallback: Some("skipped with a warning"),
+    },
+    Stage {
+        key: "jbig2",
+        description: "JBIG2 compression of bilevel pages",
+        option: "--image-encoding jbig2",
+        enabled: |config| config.image_encoding == crate::ImageEncoding::Jbig2,
+        requires: &[Requirement::Tool(crate::pdf_writer::JBIG2_TOOL)],
+        fallback: Some("Flate compression"),
+    },
+    Stage {
+        key: "text_layer",
+        description: "Reuse the source PDF's text layer instead of OCR",
+        option: "--skip-existing-ocr",
+        enabled: |config| config.skip_existing_ocr,
+        requires: &[Requirement::Tool(crate::text_layer::PDFTOTEXT_TOOL)],
+        fallback: Some("page text without word positions"),
+    },
+    Stage {
+        key: "pdfa_validate",
+        description: "Validate PDF/A output",
+        option: "--pdfa-validate",
+        enabled: |config| config.pdfa_validate,
+        requires: &[
+            Requirement::Tool(crate::pdfa::VERAPDF_TOOL),
+            Requirement::Tool(crate::pdfa::GHOSTSCRIPT_TOOL),
+        ],
+        fallback: Some("skipped with a warning"),
+    },
+];
+
+/// A stage with its requirements probed
+#[derive(Debug, Clone)]
+pub struct StageStatus {
+    /// The stage
+    pub stage: &'static Stage,
+    /// Whether the configuration runs it
+    pub enabled: bool,
+    /// Requirements that are available
+    pub available: Vec<Requirement>,
+}
+
+impl StageStatus {
+    /// Whether the stage can run as intended (built in, or a requirement is available)
+    pub fn is_ready(&self) -> bool {
+        self.stage.requires.is_empty() || !self.available.is_empty()
+    }
+}
+
+/// Probe every stage's requirements (each requirement once) and evaluate
+/// whether `config` runs it
+pub fn stage_statuses(config: &PipelineConfig) -> Vec<StageStatus> {
+    let mut probed: HashMap<Requirement, bool> = HashMap::new();
+    STAGES
+        .iter()
+        .map(|stage| StageStatus {
+            stage,
+            enabled: (stage.enabled)(config),
+            available: stage
+                .requires
+                .iter()
+                .copied()
+                .filter(|requirement| *probed.entry(*requirement).or_insert_with(|| requirement.is_available()))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Python interpreter on PATH (`python3`, then `python`)
+pub fn python_command() -> Option<&'static str> {
+    ["python3", "python"].into_iter().find(|cmd| which::which(cmd).is_ok())
+}
+
+/// Whether `module` imports under [`python_command`]
+pub fn python_module_available(module: &str) -> bool {
+    python_command().is_some_and(|python| {
+        Command::new(python)
+            .args(["-c", &format!("import {}", module)])
+            .output()
+            .is_ok_and(|output| output.status.success())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_keys_unique() {
+        let mut keys: Vec<&str> = STAGES.iter().map(|stage| stage.key).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), STAGES.len());
+    }
+
+    #[test]
+    fn test_stage_enabled_follows_config() {
+        let enabled = |config: &PipelineConfig, key: &str| {
+            let stage = STAGES.iter().find(|stage| stage.key == key).unwrap();
+            (stage.enabled)(config)
+        };
+        let defaults = PipelineConfig::default();
+        assert!(enabled(&defaults, "extract"));
+        assert!(enabled(&defaults, "upscale"));
+        assert!(!enabled(&defaults, "ocr"));
+        assert!(!enabled(&defaults, "jbig2"));
+
+        let quick = PipelineConfig::quick();
+        assert!(!enabled(&quick, "upscale"));
+        let ocr = PipelineConfig::default().with_ocr(true);
+        assert!(enabled(&ocr, "ocr"));
+    }
+
+    #[test]
+    fn test_statuses_built_in_stages_ready() {
+        let statuses = stage_statuses(&PipelineConfig::quick());
+        assert_eq!(statuses.len(), STAGES.len());
+        for status in statuses.iter().filter(|status| status.stage.requires.is_empty()) {
+            assert!(status.is_ready(), "{}", status.stage.key);
+        }
+    }
+
+    #[test]
+    fn test_requirement_display_and_hint() {
+        assert_eq!(Requirement::Tool("jbig2").to_string(), "jbig2");
+        assert_eq!(Requirement::PythonModule("yomitoku").to_string(), "yomitoku (Python)");
+        assert!(Requirement::Tool("jbig2").hint().contains("[tools] jbig2"));
+        assert_eq!(Requirement::PythonModule("realesrgan").hint(), "pip install realesrgan");
+    }
+}
@@ -0,0 +1,353 @@
+//! Existing text layer detection
+//!
+//! Born-digital PDFs (and scans that were OCR'd before) already carry
+//! text. Re-running OCR on them wastes time and usually recognizes the
+//! rendered page worse than the original text, so the pipeline can reuse
+//! the source text instead (`PipelineConfig::skip_existing_ocr`).
+//!
+//! Words and their positions are read with `pdftotext -bbox` (poppler);
+//! without it, the page text is taken from the content streams with
+//! `lopdf` and each page becomes a single full-page block.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::TextLayer;
+//!
+//! let layer = TextLayer::read(std::path::Path::new("book.pdf")).unwrap();
+//! if layer.is_sufficient() {
+//!     println!("{} of {} pages have text", layer.pages_with_text(), layer.pages.len());
+//! }
+//! ```
+
+use crate::yomitoku::{OcrResult, TextBlock, TextDirection};
+use std::path::Path;
+use std::time::Duration;
+use thiserror::Error;
+
+/// poppler text extractor (resolved through [`crate::tools`])
+pub const PDFTOTEXT_TOOL: &str = "pdftotext";
+
+/// Non-whitespace characters for a page to count as having text
+pub const MIN_PAGE_CHARS: usize = 20;
+
+/// Share of pages with text for the document's text layer to be reused
+pub const MIN_TEXT_PAGE_RATIO: f64 = 0.5;
+
+/// Text layer errors
+#[derive(Debug, Error)]
+pub enum TextLayerError {
+    #[error("pdftotext failed: {0}")]
+    ToolFailed(String),
+
+    #[error("PDF read error: {0}")]
+    Pdf(#[from] crate::PdfReaderError),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, TextLayerError>;
+
+/// A word (or run of text) on a source page
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextWord {
+    pub text: String,
+    /// Left edge in points (top-left origin)
+    pub x_min: f64,
+    /// Top edge in points
+    pub y_min: f64,
+    /// Right edge in points
+    pub x_max: f64,
+    /// Bottom edge in points
+    pub y_max: f64,
+}
+
+/// Text of one source page
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageText {
+    /// Page width in points
+    pub width_pt: f64,
+    /// Page height in points
+    pub height_pt: f64,
+    pub words: Vec<TextWord>,
+}
+
+impl PageText {
+    /// Non-whitespace characters on the page
+    pub fn char_count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|w| w.text.chars().filter(|c| !c.is_whitespace()).count())
+            .sum()
+    }
+
+    /// Whether the page has enough text to stand in for OCR
+    pub fn has_text(&self) -> bool {
+        self.char_count() >= MIN_PAGE_CHARS
+    }
+
+    /// Convert to an OCR result in the pixel space of a `width` x `height`
+    /// page image
+    ///
+    /// Positions are scaled from the source page; trimming and deskew in
+    /// between shift them slightly, which only matters for the invisible
+    /// text layer's placement.
+    pub fn to_ocr_result(&self, image_path: &Path, width: u32, height: u32) -> OcrResult {
+        let scale_x = width as f64 / self.width_pt.max(1.0);
+        let scale_y = height as f64 / self.height_pt.max(1.0);
+        let text_blocks = self
+            .words
+            .iter()
+            .filter(|w| !w.text.trim().is_empty())
+            .map(|w| {
+                let x = (w.x_min * scale_x).clamp(0.0, width as f64);
+                let y = (w.y_min * scale_y).clamp(0.0, height as f64);
+                let w_px = ((w.x_max - w.x_min) * scale_x).clamp(0.0, width as f64 - x);
+                let h_px = ((w.y_max - w.y_min) * scale_y).clamp(0.0, height as f64 - y);
+                TextBlock {
+                    text: w.text.clone(),
+                    bbox: (x as u32, y as u32, w_px as u32, h_px as u32),
+                    confidence: 1.0,
+                    direction: if h_px > w_px * 2.0 {
+                        TextDirection::Vertical
+                    } else {
+                        TextDirection::Horizontal
+                    },
+                    font_size: Some((w.y_max - w.y_min).min(w.x_max - w.x_min).max(1.0) as f32),
+                }
+            })
+            .collect();
+        OcrResult {
+            input_path: image_path.to_path_buf(),
+            text_blocks,
+            confidence: 1.0,
+            processing_time: Duration::ZERO,
+            text_direction: TextDirection::Horizontal,
+        }
+    }
+}
+
+/// Text layer of a source PDF
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextLayer {
+    pub pages: Vec<PageText>,
+}
+
+impl TextLayer {
+    /// Read the text layer (`pdftotext -bbox`, else the content streams)
+    pub fn read(pdf: &Path) -> Result<Self> {
+        Self::read_with_tools(pdf, &crate::tools::ToolContext::default())
+    }
+
+    /// Read the text layer, running `pdftotext` in `tools`
+    pub fn read_with_tools(pdf: &Path, tools: &crate::tools::ToolContext) -> Result<Self> {
+        if crate::tools::is_available(PDFTOTEXT_TOOL) {
+            Self::read_pdftotext(pdf, tools)
+        } else {
+            Self::read_content_streams(pdf)
+        }
+    }
+
+    /// Read word boxes with `pdftotext -bbox`
+    pub fn read_pdftotext(pdf: &Path, tools: &crate::tools::ToolContext) -> Result<Self> {
+        let output = tools.output(
+            tools
+                .command(PDFTOTEXT_TOOL)
+                .arg("-bbox")
+                .arg("-enc")
+                .arg("UTF-8")
+                .arg(pdf)
+                .arg("-"),
+        )?;
+        if !output.status.success() {
+            return Err(TextLayerError::ToolFailed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(Self::parse_bbox_html(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// Read page text from the content streams (one full-page word per page)
+    pub fn read_content_streams(pdf: &Path) -> Result<Self> {
+        let reader = crate::LopdfReader::new(pdf)?;
+        let mut pages = Vec::with_capacity(reader.info.pages.len());
+        for page in &reader.info.pages {
+            let text = reader.page_text(page.index).unwrap_or_default();
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let words = if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![TextWord {
+                    text,
+                    x_min: 0.0,
+                    y_min: 0.0,
+                    x_max: page.width_pt,
+                    y_max: page.height_pt,
+                }]
+            };
+            pages.push(PageText {
+                width_pt: page.width_pt,
+                height_pt: page.height_pt,
+                words,
+            });
+        }
+        Ok(Self { pages })
+    }
+
+    /// Parse the XHTML written by `pdftotext -bbox`
+    pub fn parse_bbox_html(html: &str) -> Self {
+        let mut pages: Vec<PageText> = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            let Some(end) = rest.find('>') else {
+                break;
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if tag.starts_with("page ") {
+                pages.push(PageText {
+                    width_pt: attribute(tag, "width").unwrap_or(0.0),
+                    height_pt: attribute(tag, "height").unwrap_or(0.0),
+                    words: Vec::new(),
+                });
+            } else if tag.starts_with("word ") {
+                let close = rest.find("</word>").unwrap_or(rest.len());
+                let word = TextWord {
+                    text: unescape(&rest[..close]),
+                    x_min: attribute(tag, "xMin").unwrap_or(0.0),
+                    y_min: attribute(tag, "yMin").unwrap_or(0.0),
+                    x_max: attribute(tag, "xMax").unwrap_or(0.0),
+                    y_max: attribute(tag, "yMax").unwrap_or(0.0),
+                };
+                if let Some(page) = pages.last_mut() {
+                    page.words.push(word);
+                }
+                rest = &rest[close..];
+            }
+        }
+        Self { pages }
+    }
+
+    /// Non-whitespace characters in the document
+    pub fn char_count(&self) -> usize {
+        self.pages.iter().map(PageText::char_count).sum()
+    }
+
+    /// Pages with at least [`MIN_PAGE_CHARS`] characters
+    pub fn pages_with_text(&self) -> usize {
+        self.pages.iter().filter(|p| p.has_text()).count()
+    }
+
+    /// Whether at least [`MIN_TEXT_PAGE_RATIO`] of the pages have text
+    pub fn is_sufficient(&self) -> bool {
+        !self.pages.is_empty()
+            && self.pages_with_text() as f64 >= self.pages.len() as f64 * MIN_TEXT_PAGE_RATIO
+    }
+}
+
+/// Numeric attribute value (`name="1.5"`) of a tag
+fn attribute(tag: &str, name: &str) -> Option<f64> {
+    let key = format!("{}=\"", name);
+    let start = tag.find(&key)? + key.len();
+    let end = tag[start..].find('"')? + start;
+    tag[start..end].parse().ok()
+}
+
+/// Decode the XML entities pdftotext escapes
+pub(crate) fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BBOX_HTML: &str = r#"<!DOCTYPE html PUBLIC "-//W3C//DTD XHTML 1.0 Transitional//EN" "http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd">
+<html xmlns="http://www.w3.org/1999/xhtml">
+<head>
+<title></title>
+</head>
+<body>
+<doc>
+  <page width="600.000000" height="800.000000">
+    <word xMin="60.000000" yMin="80.000000" xMax="180.000000" yMax="100.000000">Chapter&amp;One</word>
+    <word xMin="60.000000" yMin="120.000000" xMax="300.000000" yMax="140.000000">吾輩は猫である。名前はまだ無い。</word>
+  </page>
+  <page width="600.000000" height="800.000000">
+  </page>
+</doc>
+</body>
+</html>
+"#;
+
+    #[test]
+    fn test_parse_bbox_html() {
+        let layer = TextLayer::parse_bbox_html(BBOX_HTML);
+        assert_eq!(layer.pages.len(), 2);
+        assert_eq!(layer.pages[0].width_pt, 600.0);
+        assert_eq!(layer.pages[0].words.len(), 2);
+        assert_eq!(layer.pages[0].words[0].text, "Chapter&One");
+        assert_eq!(layer.pages[0].words[1].x_max, 300.0);
+        assert!(layer.pages[1].words.is_empty());
+
+        assert_eq!(layer.char_count(), 27);
+        assert_eq!(layer.pages_with_text(), 1);
+        // One of two pages is enough
+        assert!(layer.is_sufficient());
+        assert!(!TextLayer::default().is_sufficient());
+    }
+
+    #[test]
+    fn test_to_ocr_result_scales_to_image() {
+        let layer = TextLayer::parse_bbox_html(BBOX_HTML);
+        let result = layer.pages[0].to_ocr_result(Path::new("page_00001.png"), 1200, 1600);
+        assert_eq!(result.text_blocks.len(), 2);
+        assert_eq!(result.text_blocks[0].bbox, (120, 160, 240, 40));
+        assert_eq!(result.text_blocks[0].direction, TextDirection::Horizontal);
+        assert_eq!(result.mean_confidence(), 1.0);
+    }
+
+    #[test]
+    fn test_read_content_streams() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("text.pdf");
+        let text = "The quick brown fox jumps over the lazy dog";
+        let layer = crate::pdf_writer::OcrLayer {
+            pages: vec![crate::pdf_writer::OcrPageText {
+                page_index: 0,
+                blocks: vec![crate::pdf_writer::TextBlock {
+                    x: 10.0,
+                    y: 10.0,
+                    width: 200.0,
+                    height: 12.0,
+                    text: text.to_string(),
+                    font_size: 12.0,
+                    vertical: false,
+                }],
+            }],
+        };
+        let options = crate::PdfWriterOptions::builder().ocr_layer(layer).build();
+        let images = vec![
+            std::path::PathBuf::from("tests/fixtures/book_page_1.png"),
+            std::path::PathBuf::from("tests/fixtures/book_page_2.png"),
+        ];
+        crate::PrintPdfWriter::create_from_images(&images, &output, &options).unwrap();
+
+        let layer = TextLayer::read_content_streams(&output).unwrap();
+        assert_eq!(layer.pages.len(), 2);
+        assert!(layer.pages[0].words[0].text.contains("quick brown fox"));
+        assert!(layer.pages[1].words.is_empty());
+        assert!(layer.is_sufficient());
+
+        // Scanned fixture without text
+        let scanned = TextLayer::read_content_streams(Path::new("tests/fixtures/10pages.pdf")).unwrap();
+        assert_eq!(scanned.pages_with_text(), 0);
+        assert!(!scanned.is_sufficient());
+    }
+}
@@ -0,0 +1,507 @@
+//! External tool discovery
+//!
+//! Resolves the executables used for extraction and OCR (`pdftoppm`,
+//! `pdfinfo`, `magick`, `tesseract`, `nvidia-smi`, ...) so that installs
+//! outside `PATH` - common on Windows and macOS - can be pointed at
+//! explicitly.
+//!
+//! Lookup order for a tool name:
+//!
+//! 1. Override registered from `PipelineConfig::tool_paths` / `[tools]`
+//! 2. Environment variable `SUPERBOOK_<NAME>` (e.g. `SUPERBOOK_PDFTOPPM`,
+//!    `SUPERBOOK_NVIDIA_SMI`)
+//! 3. `PATH` search (honours `PATHEXT` on Windows)
+//!
+//! Tools run by a pipeline are spawned through its [`ToolContext`]:
+//! [`ToolContext::command`] applies the niceness (`--nice`) on Unix, and
+//! [`ToolContext::output`] kills a call still running after the context's
+//! timeout (`--tool-timeout`) and retries it once.
+//! With a thread budget, it also runs at most that many tools at once, each capped to one thread through
+//! [`THREAD_LIMIT_VARS`]. OCR calls go through [`ToolContext::ocr_output`],
+//! which runs them single-threaded and at most `ocr_concurrency` at once
+//! before taking a thread budget slot. Outside a pipeline, [`command`] and
+//! [`output`] use the default context.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use superbook_pdf::tools;
+//! use std::process::Command;
+//!
+//! if tools::is_available("pdftoppm") {
+//!     let status = Command::new(tools::resolve("pdftoppm")).arg("-v").status();
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::{Arc, Condvar, Mutex, OnceLock, RwLock};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Prefix of the per-tool environment override
+pub const TOOL_ENV_PREFIX: &str = "SUPERBOOK_";
+
+/// Launcher used to lower the priority of spawned tools
+pub const NICE_TOOL: &str = "nice";
+
+/// Niceness range accepted by `nice(1)`
+pub const NICENESS_RANGE: std::ops::RangeInclusive<i32> = -20..=19;
+
+/// Runs of a call that keeps timing out (the first run plus one retry)
+pub const TIMEOUT_ATTEMPTS: u32 = 2;
+
+/// Environment variables capping the threads of OpenMP tools (tesseract)
+/// and ImageMagick
+pub const THREAD_LIMIT_VARS: [&str; 3] = ["OMP_NUM_THREADS", "OMP_THREAD_LIMIT", "MAGICK_THREAD_LIMIT"];
+
+/// Longest wait between checks of a running tool
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+fn overrides() -> &'static RwLock<HashMap<String, PathBuf>> {
+    static OVERRIDES: OnceLock<RwLock<HashMap<String, PathBuf>>> = OnceLock::new();
+    OVERRIDES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Register configured tool paths for the rest of the process
+///
+/// Entries are added to (not replacing) earlier registrations.
+pub fn register_overrides(paths: &HashMap<String, PathBuf>) {
+    if paths.is_empty() {
+        return;
+    }
+    if let Ok(mut map) = overrides().write() {
+        map.extend(paths.iter().map(|(name, path)| (name.clone(), path.clone())));
+    }
+}
+
+/// Environment variable consulted for `name` (`nvidia-smi` -> `SUPERBOOK_NVIDIA_SMI`)
+pub fn env_var_name(name: &str) -> String {
+    let suffix: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    format!("{}{}", TOOL_ENV_PREFIX, suffix)
+}
+
+/// Explicitly configured location (registered override, then environment)
+pub fn override_path(name: &str) -> Option<PathBuf> {
+    let configured = overrides().read().ok().and_then(|map| map.get(name).cloned());
+    configured.or_else(|| {
+        std::env::var_os(env_var_name(name))
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    })
+}
+
+/// Locate a tool, returning None if it is neither configured nor on `PATH`
+pub fn find(name: &str) -> Option<PathBuf> {
+    match override_path(name) {
+        Some(path) if path.is_file() => Some(path),
+        Some(path) => which::which(path).ok(),
+        None => which::which(name).ok(),
+    }
+}
+
+/// Whether a tool can be located
+pub fn is_available(name: &str) -> bool {
+    find(name).is_some()
+}
+
+/// Cap the threads `cmd` may start to `threads` through [`THREAD_LIMIT_VARS`]
+pub fn limit_threads(cmd: &mut Command, threads: usize) -> &mut Command {
+    let threads = threads.max(1).to_string();
+    for var in THREAD_LIMIT_VARS {
+        cmd.env(var, &threads);
+    }
+    cmd
+}
+
+/// Counter of running tool calls, bounded by the thread budget
+#[derive(Debug, Default)]
+struct ToolSlots {
+    running: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl ToolSlots {
+    /// Wait until fewer than `budget` calls run, then take a slot
+    fn acquire(&self, budget: usize) -> ToolSlot<'_> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+        while *running >= budget.max(1) {
+            running = self.freed.wait(running).unwrap_or_else(|e| e.into_inner());
+        }
+        *running += 1;
+        ToolSlot { slots: self }
+    }
+}
+
+/// Slot of a running tool call, released on drop
+struct ToolSlot<'a> {
+    slots: &'a ToolSlots,
+}
+
+impl Drop for ToolSlot<'_> {
+    fn drop(&mut self) {
+        let mut running = self.slots.running.lock().unwrap_or_else(|e| e.into_inner());
+        *running = running.saturating_sub(1);
+        self.slots.freed.notify_one();
+    }
+}
+
+/// Settings for the external tools run by one pipeline
+///
+/// Clones share the counters of running calls, so all stages of a pipeline
+/// draw from the same limits while other pipelines (concurrent web jobs)
+/// keep their own. The default context spawns tools unchanged, without a
+/// timeout, thread budget or OCR limit.
+#[derive(Debug, Clone, Default)]
+pub struct ToolContext {
+    niceness: i32,
+    timeout: Option<Duration>,
+    thread_budget: Option<usize>,
+    ocr_concurrency: Option<usize>,
+    tool_slots: Arc<ToolSlots>,
+    ocr_slots: Arc<ToolSlots>,
+}
+
+impl ToolContext {
+    /// Spawn tools at `niceness`, clamped to [`NICENESS_RANGE`] (0 = unchanged)
+    pub fn with_niceness(mut self, niceness: i32) -> Self {
+        self.niceness = niceness.clamp(*NICENESS_RANGE.start(), *NICENESS_RANGE.end());
+        self
+    }
+
+    /// Kill and retry tool calls running longer than `timeout`
+    ///
+    /// `None` (or a zero duration) lets tool calls run indefinitely.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout.filter(|timeout| !timeout.is_zero());
+        self
+    }
+
+    /// Share `threads` between the tools run in this context
+    ///
+    /// `None` (or 0) lifts the limit. The budget is the `external` side of a
+    /// [`crate::ThreadBudget`].
+    pub fn with_thread_budget(mut self, threads: Option<usize>) -> Self {
+        self.thread_budget = threads.filter(|&threads| threads > 0);
+        self
+    }
+
+    /// Run at most `limit` OCR calls at once
+    ///
+    /// `None` (or 0) lifts the limit; the calls are then bounded only by the
+    /// pool running them and the thread budget.
+    pub fn with_ocr_concurrency(mut self, limit: Option<usize>) -> Self {
+        self.ocr_concurrency = limit.filter(|&limit| limit > 0);
+        self
+    }
+
+    /// Niceness applied to spawned tools
+    pub fn niceness(&self) -> i32 {
+        self.niceness
+    }
+
+    /// Timeout applied to tool calls
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Threads shared by the tools run in this context
+    pub fn thread_budget(&self) -> Option<usize> {
+        self.thread_budget
+    }
+
+    /// OCR calls allowed at once
+    pub fn ocr_concurrency(&self) -> Option<usize> {
+        self.ocr_concurrency
+    }
+
+    /// Command running tool `name` at this context's niceness
+    ///
+    /// On Unix a non-zero niceness wraps the tool as `nice -n <n> <tool>`;
+    /// when `nice` is not installed (or on other platforms) the tool runs
+    /// unchanged.
+    pub fn command(&self, name: &str) -> Command {
+        command_with_niceness(name, self.niceness)
+    }
+
+    /// Run `cmd` to completion and capture its output, like [`Command::output`]
+    ///
+    /// With a thread budget, the call
+    /// waits for one of the budget's slots and runs single-threaded, so
+    /// concurrent tools never start more threads than the budget.
+    ///
+    /// With a timeout, a call still running when it expires is killed and run again; if every one of
+    /// [`TIMEOUT_ATTEMPTS`] runs times out the error has kind
+    /// [`io::ErrorKind::TimedOut`].
+    pub fn output(&self, cmd: &mut Command) -> io::Result<Output> {
+        let _slot = self.thread_budget.map(|budget| {
+            limit_threads(cmd, 1);
+            self.tool_slots.acquire(budget)
+        });
+        output_with_timeout(cmd, self.timeout)
+    }
+
+    /// Run an OCR call (tesseract) like [`ToolContext::output`]
+    ///
+    /// OCR runs on many pages in parallel, so the call is single-threaded
+    /// and, with an `ocr_concurrency` limit, waits for one of its slots
+    /// first. The OCR slot is always taken before the thread budget slot,
+    /// so the two never wait on each other.
+    pub fn ocr_output(&self, cmd: &mut Command) -> io::Result<Output> {
+        limit_threads(cmd, 1);
+        let _slot = self.ocr_concurrency.map(|limit| self.ocr_slots.acquire(limit));
+        self.output(cmd)
+    }
+}
+
+/// Run `cmd` with the default [`ToolContext`]
+pub fn output(cmd: &mut Command) -> io::Result<Output> {
+    ToolContext::default().output(cmd)
+}
+
+fn output_with_timeout(cmd: &mut Command, timeout: Option<Duration>) -> io::Result<Output> {
+    let Some(limit) = timeout else {
+        return cmd.output();
+    };
+    for _ in 0..TIMEOUT_ATTEMPTS {
+        if let Some(output) = output_within(cmd, limit)? {
+            return Ok(output);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::TimedOut,
+        format!(
+            "{} timed out after {:.1}s ({} attempts)",
+            program_name(cmd),
+            limit.as_secs_f64(),
+            TIMEOUT_ATTEMPTS
+        ),
+    ))
+}
+
+/// One run of `cmd`, killed after `limit` (None = timed out)
+fn output_within(cmd: &mut Command, limit: Duration) -> io::Result<Option<Output>> {
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + limit;
+    let mut interval = Duration::from_millis(1);
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            child.kill().ok();
+            child.wait().ok();
+            // Readers may stay blocked on pipes inherited by grandchildren,
+            // so they are left to finish on their own
+            return Ok(None);
+        }
+        thread::sleep(interval.min(deadline - now));
+        interval = (interval * 2).min(MAX_POLL_INTERVAL);
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    }))
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buf).ok();
+        }
+        buf
+    })
+}
+
+/// Tool name of `cmd` for messages (the wrapped tool for `nice -n <n> <tool>`)
+fn program_name(cmd: &Command) -> String {
+    let name = |program: &std::ffi::OsStr| {
+        std::path::Path::new(program)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let program = name(cmd.get_program());
+    if program == NICE_TOOL {
+        if let Some(tool) = cmd.get_args().nth(2) {
+            return name(tool);
+        }
+    }
+    program
+}
+
+/// Command running tool `name` with the default [`ToolContext`] (unchanged)
+pub fn command(name: &str) -> Command {
+    command_with_niceness(name, 0)
+}
+
+fn command_with_niceness(name: &str, niceness: i32) -> Command {
+    let program = resolve(name);
+    let launcher = if cfg!(unix) && niceness != 0 { find(NICE_TOOL) } else { None };
+    match launcher {
+        Some(nice) => {
+            let mut cmd = Command::new(nice);
+            cmd.arg("-n").arg(niceness.to_string()).arg(program);
+            cmd
+        }
+        None => Command::new(program),
+    }
+}
+
+/// Program to execute for `name`
+///
+/// Falls back to the configured value or the bare name so that spawning
+/// reports the usual "not found" error.
+pub fn resolve(name: &str) -> PathBuf {
+    find(name)
+        .or_else(|| override_path(name))
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("pdftoppm"), "SUPERBOOK_PDFTOPPM");
+        assert_eq!(env_var_name("nvidia-smi"), "SUPERBOOK_NVIDIA_SMI");
+    }
+
+    #[test]
+    fn test_registered_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("fake-renderer");
+        std::fs::write(&tool, b"").unwrap();
+
+        let name = "superbook-test-registered-tool";
+        assert!(!is_available(name));
+        assert_eq!(resolve(name), PathBuf::from(name));
+
+        register_overrides(&HashMap::from([(name.to_string(), tool.clone())]));
+        assert_eq!(find(name), Some(tool.clone()));
+        assert_eq!(resolve(name), tool);
+    }
+
+    #[test]
+    fn test_env_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let tool = dir.path().join("fake-ocr");
+        std::fs::write(&tool, b"").unwrap();
+
+        let name = "superbook-test-env-tool";
+        std::env::set_var(env_var_name(name), &tool);
+        assert_eq!(find(name), Some(tool));
+
+        // A configured path that does not exist is reported as unavailable
+        std::env::set_var(env_var_name(name), dir.path().join("missing"));
+        assert!(!is_available(name));
+        assert_eq!(resolve(name), dir.path().join("missing"));
+        std::env::remove_var(env_var_name(name));
+    }
+
+    #[test]
+    fn test_command_niceness() {
+        let name = "superbook-test-niced-tool";
+        let plain = command_with_niceness(name, 0);
+        assert_eq!(plain.get_program(), name);
+        assert_eq!(plain.get_args().count(), 0);
+
+        let niced = command_with_niceness(name, 10);
+        if cfg!(unix) && is_available(NICE_TOOL) {
+            assert_eq!(niced.get_program(), resolve(NICE_TOOL));
+            let args: Vec<_> = niced.get_args().collect();
+            assert_eq!(args, ["-n", "10", name]);
+        } else {
+            assert_eq!(niced.get_program(), name);
+        }
+    }
+
+    #[test]
+    fn test_limit_threads() {
+        let mut cmd = Command::new("superbook-test-omp-tool");
+        limit_threads(&mut cmd, 0);
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert_eq!(envs.len(), THREAD_LIMIT_VARS.len());
+        for (var, value) in envs {
+            assert!(THREAD_LIMIT_VARS.iter().any(|name| var == *name));
+            assert_eq!(value, Some(std::ffi::OsStr::new("1")));
+        }
+    }
+
+    #[test]
+    fn test_tool_slots_bound_concurrency() {
+        let slots = ToolSlots::default();
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            for _ in 0..6 {
+                scope.spawn(|| {
+                    let _slot = slots.acquire(2);
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(20));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(*slots.running.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_tool_context() {
+        let context = ToolContext::default().with_niceness(40).with_ocr_concurrency(Some(3));
+        assert_eq!(context.niceness(), 19);
+        assert_eq!(context.ocr_concurrency(), Some(3));
+        assert_eq!(context.clone().with_ocr_concurrency(Some(0)).ocr_concurrency(), None);
+        assert_eq!(context.timeout(), None);
+        assert_eq!(context.clone().with_timeout(Some(Duration::ZERO)).timeout(), None);
+
+        // Clones share the running OCR calls; other contexts do not
+        let clone = context.clone();
+        let _held = [context.ocr_slots.acquire(3), clone.ocr_slots.acquire(3)];
+        assert_eq!(*context.ocr_slots.running.lock().unwrap(), 2);
+        assert_eq!(*ToolContext::default().ocr_slots.running.lock().unwrap(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_output_timeout_kills_and_retries() {
+        let dir = tempfile::tempdir().unwrap();
+        let limit = Some(Duration::from_millis(300));
+
+        let out = output_with_timeout(Command::new("sh").args(["-c", "echo ok"]), limit).unwrap();
+        assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "ok");
+
+        let start = Instant::now();
+        let err = output_with_timeout(Command::new("sleep").arg("10"), limit).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(err.to_string().starts_with("sleep timed out"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        // Hangs on the first run only: the retry succeeds
+        let script = "if [ -e marker ]; then echo retried; else touch marker; sleep 10; fi";
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", script]).current_dir(dir.path());
+        let out = output_with_timeout(&mut cmd, limit).unwrap();
+        assert_eq!(String::from_utf8_lossy(&out.stdout).trim(), "retried");
+    }
+}
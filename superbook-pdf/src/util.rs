@@ -45,7 +45,7 @@
 //! assert!(dur.contains("1") || dur.contains("min") || dur.contains(":"));
 //! ```
 
-use image::DynamicImage;
+use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
 use std::path::Path;
 
 /// Millimeters per inch (exactly 25.4)
@@ -62,6 +62,143 @@ pub fn load_image<P: AsRef<Path>>(path: P) -> Result<DynamicImage, String> {
     image::open(path).map_err(|e| format!("Failed to load image: {}", e))
 }
 
+/// Background transparent pixels are composited over by default (white paper)
+pub const DEFAULT_FLATTEN_BACKGROUND: [u8; 3] = [255, 255, 255];
+
+/// Load an image and composite any transparency over `background`
+pub fn load_image_flattened<P: AsRef<Path>>(path: P, background: [u8; 3]) -> Result<DynamicImage, String> {
+    load_image(path).map(|image| flatten_alpha(image, background))
+}
+
+/// Composite an image with an alpha channel over `background`
+///
+/// `to_rgb8` / `to_luma8` drop the alpha channel and keep whatever color the
+/// transparent pixels happen to carry, which is often black. Gray images stay
+/// gray (over the luma of `background`); images without alpha are returned
+/// unchanged.
+pub fn flatten_alpha(image: DynamicImage, background: [u8; 3]) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return image;
+    }
+    let blend = |value: u8, alpha: u8, bg: u8| {
+        ((u32::from(value) * u32::from(alpha) + u32::from(bg) * (255 - u32::from(alpha)) + 127) / 255) as u8
+    };
+    if image.color().has_color() {
+        let rgba = image.to_rgba8();
+        let flat = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+            let [r, g, b, a] = rgba.get_pixel(x, y).0;
+            Rgb([blend(r, a, background[0]), blend(g, a, background[1]), blend(b, a, background[2])])
+        });
+        DynamicImage::ImageRgb8(flat)
+    } else {
+        let [r, g, b] = background.map(u32::from);
+        let bg_luma = ((r * 299 + g * 587 + b * 114 + 500) / 1000) as u8;
+        let gray = image.to_luma_alpha8();
+        let flat = GrayImage::from_fn(gray.width(), gray.height(), |x, y| {
+            let [value, alpha] = gray.get_pixel(x, y).0;
+            Luma([blend(value, alpha, bg_luma)])
+        });
+        DynamicImage::ImageLuma8(flat)
+    }
+}
+
+/// Parse an RGB color written as `#RRGGBB` / `RRGGBB` or `R,G,B`
+pub fn parse_rgb_color(value: &str) -> Result<[u8; 3], String> {
+    let trimmed = value.trim();
+    if trimmed.contains(',') {
+        let parts: Vec<&str> = trimmed.split(',').map(str::trim).collect();
+        let [r, g, b] = parts[..] else {
+            return Err(format!("expected R,G,B, got '{}'", value));
+        };
+        let channel = |part: &str| part.parse::<u8>().map_err(|_| format!("invalid channel '{}' in '{}'", part, value));
+        return Ok([channel(r)?, channel(g)?, channel(b)?]);
+    }
+    let hex = trimmed.strip_prefix('#').unwrap_or(trimmed);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("expected #RRGGBB or R,G,B, got '{}'", value));
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| e.to_string());
+    Ok([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Page ranges such as `1-12,15,40-` (1-based, inclusive; an open end runs
+/// to the last page)
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PageRanges(Vec<(usize, Option<usize>)>);
+
+impl PageRanges {
+    /// Check if a page (1-based) is in any range
+    pub fn contains(&self, page_number: usize) -> bool {
+        self.0
+            .iter()
+            .any(|&(first, last)| page_number >= first && last.map_or(true, |last| page_number <= last))
+    }
+}
+
+impl std::fmt::Display for PageRanges {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ranges: Vec<String> = self
+            .0
+            .iter()
+            .map(|&(first, last)| match last {
+                Some(last) if last == first => first.to_string(),
+                Some(last) => format!("{}-{}", first, last),
+                None => format!("{}-", first),
+            })
+            .collect();
+        write!(f, "{}", ranges.join(","))
+    }
+}
+
+impl TryFrom<String> for PageRanges {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, String> {
+        parse_page_ranges(&value)
+    }
+}
+
+impl From<PageRanges> for String {
+    fn from(ranges: PageRanges) -> Self {
+        ranges.to_string()
+    }
+}
+
+/// Parse page ranges written as `N`, `N-M` or `N-` separated by commas
+pub fn parse_page_ranges(value: &str) -> Result<PageRanges, String> {
+    let page = |s: &str| match s.trim().parse::<usize>() {
+        Ok(page) if page > 0 => Ok(page),
+        _ => Err(format!("page must be a number from 1, got '{}'", s.trim())),
+    };
+    let ranges = value
+        .split(',')
+        .map(|part| match part.split_once('-') {
+            Some((first, last)) if last.trim().is_empty() => Ok((page(first)?, None)),
+            Some((first, last)) => {
+                let (first, last) = (page(first)?, page(last)?);
+                if last < first {
+                    return Err(format!("range '{}' ends before it starts", part.trim()));
+                }
+                Ok((first, Some(last)))
+            }
+            None => page(part).map(|page| (page, Some(page))),
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(PageRanges(ranges))
+}
+
+/// Whether an image file has an alpha channel (reads only the header)
+pub fn image_has_alpha<P: AsRef<Path>>(path: P) -> bool {
+    use image::ImageDecoder;
+
+    image::ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .ok()
+        .and_then(|reader| reader.into_decoder().ok())
+        .is_some_and(|decoder| decoder.color_type().has_alpha())
+}
+
 /// Check if a path exists and is a file
 pub fn ensure_file_exists<P: AsRef<Path>>(path: P) -> Result<(), String> {
     let path = path.as_ref();
@@ -225,6 +362,66 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_flatten_transparent_page() {
+        let fixture = "tests/fixtures/transparent_page.png";
+        assert!(image_has_alpha(fixture));
+        assert!(!image_has_alpha("tests/fixtures/book_page_1.png"));
+        assert!(!image_has_alpha("/nonexistent/image.png"));
+
+        // Dropping alpha leaves the transparent paper black
+        let page = load_image(fixture).unwrap();
+        assert_eq!(page.to_rgb8().get_pixel(5, 5).0, [0, 0, 0]);
+
+        let flat = load_image_flattened(fixture, DEFAULT_FLATTEN_BACKGROUND).unwrap();
+        assert!(!flat.color().has_alpha());
+        let flat = flat.to_rgb8();
+        assert_eq!(flat.get_pixel(5, 5).0, [255, 255, 255]);
+        // Opaque content is kept
+        assert_eq!(flat.get_pixel(100, 75).0, [50, 50, 50]);
+
+        let cream = flatten_alpha(page, [250, 240, 220]).to_rgb8();
+        assert_eq!(cream.get_pixel(5, 5).0, [250, 240, 220]);
+    }
+
+    #[test]
+    fn test_parse_rgb_color() {
+        assert_eq!(parse_rgb_color("#ffffff"), Ok([255, 255, 255]));
+        assert_eq!(parse_rgb_color("F5EBDC"), Ok([245, 235, 220]));
+        assert_eq!(parse_rgb_color("250, 240, 220"), Ok([250, 240, 220]));
+        for invalid in ["", "#fff", "#gggggg", "256,0,0", "1,2", "1,2,3,4", "white"] {
+            assert!(parse_rgb_color(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_parse_page_ranges() {
+        let ranges = parse_page_ranges("1-3, 7,10-").unwrap();
+        let included: Vec<usize> = (1..=12).filter(|&page| ranges.contains(page)).collect();
+        assert_eq!(included, vec![1, 2, 3, 7, 10, 11, 12]);
+        assert_eq!(ranges.to_string(), "1-3,7,10-");
+
+        let json = serde_json::to_string(&ranges).unwrap();
+        assert_eq!(json, r#""1-3,7,10-""#);
+        assert_eq!(serde_json::from_str::<PageRanges>(&json).unwrap(), ranges);
+
+        for invalid in ["", "0", "3-1", "a-b", "1,,2", "-4", "1-2-3"] {
+            assert!(parse_page_ranges(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_flatten_alpha_gray_and_opaque() {
+        // Half-transparent black over white
+        let gray = DynamicImage::ImageLumaA8(image::ImageBuffer::from_pixel(2, 2, image::LumaA([0, 128])));
+        let flat = flatten_alpha(gray, DEFAULT_FLATTEN_BACKGROUND);
+        assert!(matches!(flat, DynamicImage::ImageLuma8(_)));
+        assert_eq!(flat.to_luma8().get_pixel(0, 0).0, [127]);
+
+        let opaque = DynamicImage::ImageRgb8(RgbImage::from_pixel(2, 2, Rgb([1, 2, 3])));
+        assert_eq!(flatten_alpha(opaque.clone(), [0, 0, 0]), opaque);
+    }
+
     #[test]
     fn test_format_file_size() {
         assert_eq!(format_file_size(0), "0 B");
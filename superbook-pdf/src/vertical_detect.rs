@@ -11,6 +11,9 @@
 //! 2. Rotate the image 90° and apply the same scan for a "vertical score"
 //! 3. Normalize the scores to get vertical writing probability
 //!
+//! Unevenly lit scans can be binarized with a local threshold first
+//! ([`VerticalDetectOptions::adaptive`]), so shadowed paper is not counted as ink.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -21,6 +24,7 @@
 //! println!("Vertical probability: {:.2}", result.vertical_probability);
 //! ```
 
+use crate::binarize::AdaptiveMethod;
 use image::{GrayImage, ImageBuffer};
 
 #[cfg(test)]
@@ -59,6 +63,8 @@ pub struct VerticalDetectOptions {
     pub block_count: u32,
     /// Minimum probability threshold for vertical writing (default: 0.5)
     pub vertical_threshold: f64,
+    /// Binarize with a local threshold before scanning (default: None)
+    pub adaptive: Option<AdaptiveMethod>,
 }
 
 impl Default for VerticalDetectOptions {
@@ -67,6 +73,7 @@ impl Default for VerticalDetectOptions {
             black_threshold: 128,
             block_count: 4,
             vertical_threshold: 0.5,
+            adaptive: None,
         }
     }
 }
@@ -145,6 +152,9 @@ pub fn detect_vertical_probability(
         ));
     }
 
+    let binarized = options.adaptive.map(|method| crate::binarize::adaptive(image, method));
+    let image = binarized.as_ref().unwrap_or(image);
+
     // 1. Compute horizontal score (scanning rows)
     let horizontal_score = compute_linear_score(image, options);
 
@@ -214,7 +224,7 @@ pub fn detect_book_vertical_writing(
 /// 1. Variation coefficient of intersection counts
 /// 2. Zero-line ratio (empty rows)
 /// 3. Separation ratio (gap between lines)
-fn compute_linear_score(image: &GrayImage, options: &VerticalDetectOptions) -> f64 {
+pub fn compute_linear_score(image: &GrayImage, options: &VerticalDetectOptions) -> f64 {
     let width = image.width() as usize;
     let height = image.height() as usize;
 
@@ -820,4 +830,21 @@ mod tests {
         let debug_str = format!("{:?}", err);
         assert!(debug_str.contains("InvalidImage"));
     }
+
+    #[test]
+    fn test_adaptive_on_gradient_lit_page() {
+        // Vertical columns of text on a page lit from the top
+        let (page, _) = crate::binarize::tests::gradient_lit_page();
+        let page = GrayImage::from_fn(page.height(), page.width(), |x, y| *page.get_pixel(y, x));
+
+        let global = detect_vertical_probability(&page, &VerticalDetectOptions::default()).unwrap();
+        let options = VerticalDetectOptions {
+            adaptive: Some(AdaptiveMethod::default()),
+            ..Default::default()
+        };
+        let adaptive = detect_vertical_probability(&page, &options).unwrap();
+        assert!(adaptive.is_vertical);
+        assert!(adaptive.vertical_score > global.vertical_score);
+        assert!(adaptive.vertical_probability > global.vertical_probability);
+    }
 }
@@ -0,0 +1,227 @@
+//! Early warning for pipeline stages that stop making progress (`--stall-warning`)
+//!
+//! A hung GPU call or external tool leaves a conversion silent until the
+//! overall wall time gives it away. [`StallWatchdog`] wraps a
+//! [`ProgressCallback`] and, while the pipeline runs, warns through
+//! [`ProgressCallback::on_warning`] when no step started, progressed or
+//! completed (and no page finished) within the interval.
+//!
+//! Unlike `tool_timeout_secs` nothing is killed: a long but legitimate stage
+//! keeps running, and the warning repeats once per further interval of
+//! silence. Because it goes through the progress callback, the CLI prints
+//! it (and writes it to `--event-log`) and the web worker logs it for the job.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use superbook_pdf::{ProgressCallback, SilentProgress, StallWatchdog};
+//!
+//! let pages = StallWatchdog::watch(&SilentProgress, Duration::from_secs(600), |progress| {
+//!     progress.on_step_start("Upscaling...");
+//!     progress.on_step_progress(1, 1);
+//!     progress.on_step_complete("Upscaling", "1 page");
+//!     1
+//! });
+//! assert_eq!(pages, 1);
+//! ```
+
+use std::path::Path;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::pipeline::{PipelineContext, ProgressCallback};
+
+/// Default silence before warning of a possible hang in the CLI and web
+/// server (seconds)
+pub const DEFAULT_STALL_WARNING_SECS: u64 = 600;
+
+/// Activity seen by the watchdog
+struct Activity {
+    /// Last step started
+    step: Option<String>,
+    /// Last start/progress/complete/page callback
+    last: Instant,
+    /// Warnings since the last activity
+    warned: u32,
+    /// The watched work has returned
+    finished: bool,
+}
+
+/// Progress callback wrapper that warns about stalled steps
+pub struct StallWatchdog<'a, P: ProgressCallback> {
+    inner: &'a P,
+    interval: Duration,
+    activity: Mutex<Activity>,
+    wake: Condvar,
+}
+
+impl<'a, P: ProgressCallback> StallWatchdog<'a, P> {
+    fn new(inner: &'a P, interval: Duration) -> Self {
+        Self {
+            inner,
+            interval,
+            activity: Mutex::new(Activity {
+                step: None,
+                last: Instant::now(),
+                warned: 0,
+                finished: false,
+            }),
+            wake: Condvar::new(),
+        }
+    }
+
+    /// Run `f` with a progress callback that forwards to `inner` and warns
+    /// after each `interval` without progress, until `f` returns
+    pub fn watch<T>(inner: &'a P, interval: Duration, f: impl FnOnce(&Self) -> T) -> T {
+        let watchdog = Self::new(inner, interval);
+        std::thread::scope(|scope| {
+            scope.spawn(|| watchdog.check_loop());
+            let result = f(&watchdog);
+            watchdog.finish();
+            result
+        })
+    }
+
+    /// Warn at every interval of silence until `finish`
+    fn check_loop(&self) {
+        let Ok(mut activity) = self.activity.lock() else {
+            return;
+        };
+        while !activity.finished {
+            let deadline = activity.last + self.interval * (activity.warned + 1);
+            let now = Instant::now();
+            if now < deadline {
+                activity = match self.wake.wait_timeout(activity, deadline - now) {
+                    Ok((activity, _)) => activity,
+                    Err(_) => return,
+                };
+                continue;
+            }
+            activity.warned += 1;
+            let message = match &activity.step {
+                Some(step) => format!(
+                    "No progress in \"{}\" for {}s; the stage may be hung",
+                    step.trim_end_matches("..."),
+                    now.duration_since(activity.last).as_secs()
+                ),
+                None => format!(
+                    "No progress for {}s; the conversion may be hung",
+                    now.duration_since(activity.last).as_secs()
+                ),
+            };
+            // Warn without holding the lock so the callback may report progress
+            drop(activity);
+            self.inner.on_warning(&message);
+            activity = match self.activity.lock() {
+                Ok(activity) => activity,
+                Err(_) => return,
+            };
+        }
+    }
+
+    fn finish(&self) {
+        if let Ok(mut activity) = self.activity.lock() {
+            activity.finished = true;
+        }
+        self.wake.notify_all();
+    }
+
+    fn touch(&self, step: Option<&str>) {
+        if let Ok(mut activity) = self.activity.lock() {
+            activity.last = Instant::now();
+            activity.warned = 0;
+            if let Some(step) = step {
+                activity.step = Some(step.to_string());
+            }
+        }
+    }
+}
+
+impl<P: ProgressCallback> ProgressCallback for StallWatchdog<'_, P> {
+    fn on_file_start(&self, context: &PipelineContext) {
+        self.touch(None);
+        self.inner.on_file_start(context);
+    }
+
+    fn on_step_start(&self, step: &str) {
+        self.touch(Some(step));
+        self.inner.on_step_start(step);
+    }
+
+    fn on_step_progress(&self, current: usize, total: usize) {
+        self.touch(None);
+        self.inner.on_step_progress(current, total);
+    }
+
+    fn on_step_complete(&self, step: &str, message: &str) {
+        self.touch(None);
+        self.inner.on_step_complete(step, message);
+    }
+
+    fn on_debug(&self, message: &str) {
+        self.inner.on_debug(message);
+    }
+
+    fn on_page_complete(&self, page: usize, image: &Path, is_final: bool) {
+        self.touch(None);
+        self.inner.on_page_complete(page, image, is_final);
+    }
+
+    fn on_warning(&self, message: &str) {
+        self.inner.on_warning(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Warnings(Mutex<Vec<String>>);
+
+    impl ProgressCallback for Warnings {
+        fn on_step_start(&self, _step: &str) {}
+        fn on_step_progress(&self, _current: usize, _total: usize) {}
+        fn on_step_complete(&self, _step: &str, _message: &str) {}
+        fn on_debug(&self, _message: &str) {}
+        fn on_warning(&self, message: &str) {
+            self.0.lock().unwrap().push(message.to_string());
+        }
+    }
+
+    // TC-WDG-001: 進捗のない段階で警告し、処理は続行
+    #[test]
+    fn test_stalled_step_warns() {
+        let warnings = Warnings::default();
+        let result = StallWatchdog::watch(&warnings, Duration::from_millis(50), |progress| {
+            progress.on_step_start("Upscaling...");
+            std::thread::sleep(Duration::from_millis(180));
+            progress.on_step_complete("Upscaling", "done");
+            42
+        });
+        assert_eq!(result, 42);
+
+        let warnings = warnings.0.into_inner().unwrap();
+        assert!((2..=4).contains(&warnings.len()), "{:?}", warnings);
+        assert!(warnings[0].starts_with("No progress in \"Upscaling\" for 0s"), "{}", warnings[0]);
+    }
+
+    // TC-WDG-002: 進捗が続く間と処理終了後は警告しない
+    #[test]
+    fn test_steady_progress_does_not_warn() {
+        let warnings = Warnings::default();
+        let start = Instant::now();
+        StallWatchdog::watch(&warnings, Duration::from_millis(100), |progress| {
+            progress.on_step_start("Running OCR (YomiToku)...");
+            for page in 0..20 {
+                std::thread::sleep(Duration::from_millis(10));
+                progress.on_step_progress(page + 1, 20);
+            }
+        });
+        // Returns as soon as the work is done, not after the next interval
+        StallWatchdog::watch(&warnings, Duration::from_secs(3600), |_| ());
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert!(warnings.0.lock().unwrap().is_empty());
+    }
+}
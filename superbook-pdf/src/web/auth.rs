@@ -40,6 +40,9 @@ pub struct ApiKey {
     pub expires_at: Option<DateTime<Utc>>,
     /// Allowed scopes
     pub scopes: Vec<Scope>,
+    /// Upload size limit in bytes for this key (None = server default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upload_limit: Option<usize>,
 }
 
 impl ApiKey {
@@ -50,6 +53,7 @@ impl ApiKey {
             name: name.into(),
             expires_at: None,
             scopes: vec![Scope::Read, Scope::Write],
+            upload_limit: None,
         }
     }
 
@@ -60,6 +64,7 @@ impl ApiKey {
             name: name.into(),
             expires_at: None,
             scopes: vec![Scope::Admin],
+            upload_limit: None,
         }
     }
 
@@ -75,6 +80,12 @@ impl ApiKey {
         self
     }
 
+    /// Set a per-key upload size limit in bytes
+    pub fn with_upload_limit(mut self, bytes: usize) -> Self {
+        self.upload_limit = Some(bytes);
+        self
+    }
+
     /// Check if key is expired
     pub fn is_expired(&self) -> bool {
         if let Some(expires_at) = self.expires_at {
@@ -238,6 +249,21 @@ impl AuthManager {
         false
     }
 
+    /// Per-key upload limit for a valid, unexpired key
+    ///
+    /// Returns None when auth is disabled or the key has no override, in
+    /// which case the server-wide limit applies.
+    pub fn upload_limit(&self, key: &str) -> Option<usize> {
+        if !self.config.enabled {
+            return None;
+        }
+        self.config
+            .api_keys
+            .iter()
+            .find(|api_key| api_key.key == key && !api_key.is_expired())
+            .and_then(|api_key| api_key.upload_limit)
+    }
+
     /// Get the number of configured API keys
     pub fn key_count(&self) -> usize {
         self.config.api_keys.len()
@@ -594,4 +620,27 @@ mod tests {
         assert!(key.has_scope(Scope::Read));
         assert!(!key.has_scope(Scope::Write));
     }
+
+    // AUTH-013: キーごとのアップロード上限
+    #[test]
+    fn test_upload_limit_per_key() {
+        let keys = vec![
+            ApiKey::new("trusted", "Trusted").with_upload_limit(2048),
+            ApiKey::new("plain", "Plain"),
+            ApiKey::new("old", "Old")
+                .with_upload_limit(4096)
+                .with_expires_at(Utc::now() - Duration::hours(1)),
+        ];
+        let manager = AuthManager::new(AuthConfig::enabled_with_keys(keys.clone()));
+        assert_eq!(manager.upload_limit("trusted"), Some(2048));
+        assert_eq!(manager.upload_limit("plain"), None);
+        assert_eq!(manager.upload_limit("old"), None);
+        assert_eq!(manager.upload_limit("unknown"), None);
+
+        let disabled = AuthManager::new(AuthConfig {
+            enabled: false,
+            ..AuthConfig::enabled_with_keys(keys)
+        });
+        assert_eq!(disabled.upload_limit("trusted"), None);
+    }
 }
@@ -5,7 +5,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use uuid::Uuid;
 
@@ -85,6 +85,129 @@ pub struct ConvertOptions {
     /// Enable all advanced features
     #[serde(default)]
     pub advanced: bool,
+    /// Margin trim (percent)
+    #[serde(default = "default_margin_trim")]
+    pub margin_trim: f64,
+    /// Internal resolution normalization (None = follow `advanced`)
+    #[serde(default)]
+    pub internal_resolution: Option<bool>,
+    /// Global color correction (None = follow `advanced`)
+    #[serde(default)]
+    pub color_correction: Option<bool>,
+    /// Page number offset alignment (None = follow `advanced`)
+    #[serde(default)]
+    pub offset_alignment: Option<bool>,
+    /// Output page height in pixels
+    #[serde(default = "default_output_height")]
+    pub output_height: u32,
+    /// Process only the first N pages
+    #[serde(default)]
+    pub max_pages: Option<usize>,
+    /// JPEG quality (1-100)
+    #[serde(default = "default_jpeg_quality")]
+    pub jpeg_quality: u8,
+    /// Automatic contrast stretch
+    #[serde(default)]
+    pub auto_contrast: crate::AutoContrast,
+    /// Stretch each color channel separately
+    #[serde(default)]
+    pub auto_contrast_per_channel: bool,
+    /// Tone curve applied after color correction
+    #[serde(default)]
+    pub tone_curve: Option<crate::ToneCurve>,
+    /// Extra OCR export formats (requires `ocr`)
+    #[serde(default)]
+    pub ocr_formats: Vec<crate::markdown::OcrExportFormat>,
+    /// Reproducible output (fixed timestamps and IDs)
+    #[serde(default)]
+    pub deterministic: bool,
+    /// Document metadata
+    #[serde(default)]
+    pub metadata: crate::PdfMetadata,
+    /// Fill missing title/author from OCR text (requires `ocr`)
+    #[serde(default)]
+    pub metadata_from_ocr: bool,
+    /// Write a plain text file next to the output (requires `ocr`)
+    #[serde(default)]
+    pub write_text_sidecar: bool,
+    /// Continue without a text layer when OCR is unavailable
+    #[serde(default)]
+    pub ocr_optional: bool,
+    /// Keep color only on pages that have it
+    #[serde(default)]
+    pub mixed_color: bool,
+    /// Which parts of the output keep their color
+    #[serde(default)]
+    pub color_policy: crate::ColorPolicy,
+    /// Handling of pages with different sizes
+    #[serde(default)]
+    pub mixed_size: crate::MixedSize,
+    /// Compute per-page quality metrics
+    #[serde(default)]
+    pub quality_metrics: bool,
+    /// Binding direction
+    #[serde(default)]
+    pub binding: crate::Binding,
+    /// Resampling filter for resizing
+    #[serde(default)]
+    pub resize_filter: crate::Resampler,
+    /// Page image encoding
+    #[serde(default)]
+    pub image_encoding: crate::ImageEncoding,
+    /// Reuse an existing text layer instead of running OCR
+    #[serde(default)]
+    pub skip_existing_ocr: bool,
+    /// Remove scanner line artifacts
+    #[serde(default)]
+    pub remove_line_artifacts: bool,
+    /// Remove highlighter marks
+    #[serde(default)]
+    pub remove_markers: bool,
+    /// Marker colors to remove (empty = all)
+    #[serde(default)]
+    pub marker_colors: Vec<String>,
+    /// Page area markers are removed from ("page" or "margins")
+    #[serde(default)]
+    pub marker_region: crate::cleanup::MarkerArea,
+    /// Convert pages below this saturation to grayscale (0.0-1.0)
+    #[serde(default)]
+    pub desaturate_below: Option<f32>,
+    /// Pad pages to this aspect ratio (width, height)
+    #[serde(default)]
+    pub target_aspect: Option<(u32, u32)>,
+    /// PDF/A conformance level
+    #[serde(default)]
+    pub pdfa: Option<crate::PdfaLevel>,
+    /// Write page labels
+    #[serde(default)]
+    pub page_labels: bool,
+    /// Running head handling in OCR text (requires `ocr` unless `keep`)
+    #[serde(default)]
+    pub running_heads: crate::markdown::RunningHeadMode,
+    /// Which pages to process
+    #[serde(default)]
+    pub parity: crate::PageParity,
+    /// Negative (inverted) page handling
+    #[serde(default)]
+    pub invert: crate::Invert,
+    /// What to do when a page fails
+    #[serde(default)]
+    pub on_page_error: crate::PageErrorPolicy,
+    /// Shared crop across facing pages
+    #[serde(default)]
+    pub group_crop: crate::GroupCropParams,
+    /// Align odd and even pages horizontally
+    #[serde(default)]
+    pub register_sides: bool,
+    /// Minimum effective output DPI (0 = no check)
+    #[serde(default = "default_min_output_dpi")]
+    pub min_output_dpi: u32,
+    /// What to do when the output DPI is below `min_output_dpi`
+    #[serde(default)]
+    pub on_low_dpi: crate::LowDpiPolicy,
+    /// Fast preview preset (no upscaling, OCR or metrics)
+    #[serde(default)]
+    pub quick: bool,
 }
 
 fn default_dpi() -> u32 {
@@ -95,6 +218,81 @@ fn default_true() -> bool {
     true
 }
 
+fn default_margin_trim() -> f64 {
+    0.5
+}
+
+fn default_output_height() -> u32 {
+    3508
+}
+
+fn default_jpeg_quality() -> u8 {
+    90
+}
+
+fn default_min_output_dpi() -> u32 {
+    crate::pipeline::DEFAULT_MIN_OUTPUT_DPI
+}
+
+impl ConvertOptions {
+    /// Normalized hash of the effective pipeline configuration
+    ///
+    /// Two option sets with the same hash produce the same output.
+    pub fn options_hash(&self) -> String {
+        let config = super::worker::to_pipeline_config(self, false);
+        crate::cache::hash_options(&config.to_json())
+    }
+
+    /// Check ranges and reject combinations that cannot run
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.ocr {
+            let needs_ocr = [
+                (!self.ocr_formats.is_empty(), "ocr_formats"),
+                (self.write_text_sidecar, "write_text_sidecar"),
+                (self.metadata_from_ocr, "metadata_from_ocr"),
+                (!self.running_heads.is_keep(), "running_heads"),
+                (self.color_policy == crate::ColorPolicy::FiguresOnly, "color_policy figures-only"),
+            ];
+            if let Some((_, name)) = needs_ocr.iter().find(|(set, _)| *set) {
+                return Err(format!("{} requires ocr", name));
+            }
+        }
+        if self.quick {
+            let conflicts = [
+                (self.ocr, "ocr"),
+                (self.advanced, "advanced"),
+                (self.quality_metrics, "quality_metrics"),
+            ];
+            if let Some((_, name)) = conflicts.iter().find(|(set, _)| *set) {
+                return Err(format!("quick cannot be combined with {}", name));
+            }
+        }
+        super::worker::to_pipeline_config(self, false)
+            .validate()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Apply a partial JSON object of option fields on top of these options
+    ///
+    /// Unknown field names and values of the wrong type are errors.
+    pub fn merged(&self, overrides: &serde_json::Value) -> Result<Self, String> {
+        let overrides = overrides
+            .as_object()
+            .ok_or_else(|| "options override must be a JSON object".to_string())?;
+        let mut value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        let fields = value
+            .as_object_mut()
+            .ok_or_else(|| "options are not an object".to_string())?;
+        for (key, field) in overrides {
+            if !fields.contains_key(key) {
+                return Err(format!("unknown option '{}'", key));
+            }
+            fields.insert(key.clone(), field.clone());
+        }
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+}
+
 impl Default for ConvertOptions {
     fn default() -> Self {
         Self {
@@ -103,6 +301,47 @@ impl Default for ConvertOptions {
             upscale: true,
             ocr: false,
             advanced: false,
+            margin_trim: default_margin_trim(),
+            internal_resolution: None,
+            color_correction: None,
+            offset_alignment: None,
+            output_height: default_output_height(),
+            max_pages: None,
+            jpeg_quality: default_jpeg_quality(),
+            auto_contrast: crate::AutoContrast::Off,
+            auto_contrast_per_channel: false,
+            tone_curve: None,
+            ocr_formats: Vec::new(),
+            deterministic: false,
+            metadata: crate::PdfMetadata::default(),
+            metadata_from_ocr: false,
+            write_text_sidecar: false,
+            ocr_optional: false,
+            mixed_color: false,
+            color_policy: crate::ColorPolicy::PerPage,
+            mixed_size: crate::MixedSize::Uniform,
+            quality_metrics: false,
+            binding: crate::Binding::Auto,
+            resize_filter: crate::Resampler::Lanczos3,
+            image_encoding: crate::ImageEncoding::Standard,
+            skip_existing_ocr: false,
+            remove_line_artifacts: false,
+            remove_markers: false,
+            marker_colors: Vec::new(),
+            marker_region: crate::cleanup::MarkerArea::Page,
+            desaturate_below: None,
+            target_aspect: None,
+            pdfa: None,
+            page_labels: false,
+            running_heads: crate::markdown::RunningHeadMode::Keep,
+            parity: crate::PageParity::All,
+            invert: crate::Invert::Off,
+            on_page_error: crate::PageErrorPolicy::Skip,
+            group_crop: crate::GroupCropParams::default(),
+            register_sides: false,
+            min_output_dpi: default_min_output_dpi(),
+            on_low_dpi: crate::LowDpiPolicy::Warn,
+            quick: false,
         }
     }
 }
@@ -116,6 +355,15 @@ pub struct Job {
     pub status: JobStatus,
     /// Conversion options
     pub options: ConvertOptions,
+    /// Hash of the effective pipeline options
+    #[serde(default)]
+    pub options_hash: String,
+    /// Hash of the uploaded source file (when known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_hash: Option<String>,
+    /// Job whose source and options this job re-runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerun_of: Option<Uuid>,
     /// Progress information (when processing)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub progress: Option<Progress>,
@@ -135,6 +383,21 @@ pub struct Job {
     /// Error message (when failed)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Directory of per-page preview JPEGs (set when processing starts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_dir: Option<PathBuf>,
+    /// Pages with a preview available (`GET /api/jobs/{id}/preview/{page}`)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub preview_pages: usize,
+}
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+/// Preview file of a 1-based page inside a job's preview directory
+pub fn preview_file(preview_dir: &Path, page: usize) -> PathBuf {
+    preview_dir.join(format!("page_{:04}.jpg", page))
 }
 
 impl Job {
@@ -143,6 +406,9 @@ impl Job {
         Self {
             id: Uuid::new_v4(),
             status: JobStatus::Queued,
+            options_hash: options.options_hash(),
+            source_hash: None,
+            rerun_of: None,
             options,
             progress: None,
             input_filename: input_filename.into(),
@@ -151,9 +417,23 @@ impl Job {
             started_at: None,
             completed_at: None,
             error: None,
+            preview_dir: None,
+            preview_pages: 0,
         }
     }
 
+    /// Set the source file hash
+    pub fn with_source_hash(mut self, source_hash: impl Into<String>) -> Self {
+        self.source_hash = Some(source_hash.into());
+        self
+    }
+
+    /// Set the job this one re-runs
+    pub fn with_rerun_of(mut self, job_id: Uuid) -> Self {
+        self.rerun_of = Some(job_id);
+        self
+    }
+
     /// Mark job as processing
     pub fn start(&mut self) {
         self.status = JobStatus::Processing;
@@ -186,6 +466,19 @@ impl Job {
         self.completed_at = Some(Utc::now());
     }
 
+    /// Record that `page` (1-based) has a preview
+    pub fn add_preview(&mut self, page: usize) {
+        self.preview_pages = self.preview_pages.max(page);
+    }
+
+    /// Preview file of a 1-based page, if it has been written
+    pub fn preview_path(&self, page: usize) -> Option<PathBuf> {
+        let dir = self.preview_dir.as_ref()?;
+        (1..=self.preview_pages)
+            .contains(&page)
+            .then(|| preview_file(dir, page))
+    }
+
     /// Check if job is in terminal state
     pub fn is_terminal(&self) -> bool {
         matches!(
@@ -243,6 +536,12 @@ impl JobQueue {
         }
     }
 
+    /// Remove a job from the queue
+    pub fn remove(&self, id: Uuid) -> Option<Job> {
+        let mut jobs = self.jobs.write().expect("lock poisoned");
+        jobs.remove(&id)
+    }
+
     /// Cancel a job
     pub fn cancel(&self, id: Uuid) -> Option<Job> {
         self.update(id, |job| job.cancel())
@@ -254,6 +553,18 @@ impl JobQueue {
         jobs.values().cloned().collect()
     }
 
+    /// Find a completed job with the same source and options hash whose output still exists
+    pub fn find_completed(&self, source_hash: &str, options_hash: &str) -> Option<Job> {
+        let jobs = self.jobs.read().expect("lock poisoned");
+        jobs.values()
+            .filter(|j| j.status == JobStatus::Completed)
+            .filter(|j| j.options_hash == options_hash)
+            .filter(|j| j.source_hash.as_deref() == Some(source_hash))
+            .filter(|j| j.output_path.as_ref().is_some_and(|p| p.exists()))
+            .max_by_key(|j| j.completed_at)
+            .cloned()
+    }
+
     /// Get pending jobs (queued or processing)
     pub fn pending(&self) -> Vec<Job> {
         let jobs = self.jobs.read().expect("lock poisoned");
@@ -370,6 +681,23 @@ mod tests {
         assert!(job.is_terminal());
     }
 
+    #[test]
+    fn test_job_preview_pages() {
+        let mut job = Job::new("test.pdf", ConvertOptions::default());
+        assert!(job.preview_path(1).is_none());
+        assert!(!serde_json::to_string(&job).unwrap().contains("preview"));
+
+        job.preview_dir = Some(PathBuf::from("/tmp/previews"));
+        job.add_preview(1);
+        job.add_preview(2);
+        job.add_preview(1);
+        assert_eq!(job.preview_pages, 2);
+        assert_eq!(job.preview_path(2), Some(PathBuf::from("/tmp/previews/page_0002.jpg")));
+        assert!(job.preview_path(0).is_none());
+        assert!(job.preview_path(3).is_none());
+        assert!(serde_json::to_string(&job).unwrap().contains("\"preview_pages\":2"));
+    }
+
     #[test]
     fn test_job_cancel() {
         let mut job = Job::new("test.pdf", ConvertOptions::default());
@@ -486,4 +814,115 @@ mod tests {
         assert!(opts.ocr);
         assert!(!opts.advanced); // default
     }
+
+    #[test]
+    fn test_convert_options_stage_fields() {
+        let opts: ConvertOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(opts.margin_trim, 0.5);
+        assert_eq!(opts.output_height, 3508);
+        assert_eq!(opts.jpeg_quality, 90);
+        assert_eq!(opts.min_output_dpi, crate::pipeline::DEFAULT_MIN_OUTPUT_DPI);
+        assert!(opts.internal_resolution.is_none());
+        assert!(opts.validate().is_ok());
+
+        let json = r#"{"remove_markers":true,"marker_colors":["yellow"],"marker_region":"margins","image_encoding":"flate","target_aspect":[3,4]}"#;
+        let opts: ConvertOptions = serde_json::from_str(json).unwrap();
+        assert!(opts.remove_markers);
+        assert_eq!(opts.marker_region, crate::cleanup::MarkerArea::Margins);
+        assert_eq!(opts.image_encoding, crate::ImageEncoding::Flate);
+        assert_eq!(opts.target_aspect, Some((3, 4)));
+        assert!(opts.validate().is_ok());
+    }
+
+    #[test]
+    fn test_convert_options_validate() {
+        let with = |edit: fn(&mut ConvertOptions)| {
+            let mut opts = ConvertOptions::default();
+            edit(&mut opts);
+            opts.validate()
+        };
+        let rejected = [
+            (with(|o| o.dpi = 0), "dpi"),
+            (with(|o| o.jpeg_quality = 101), "jpeg_quality"),
+            (with(|o| o.write_text_sidecar = true), "requires ocr"),
+            (with(|o| o.ocr_formats = vec![crate::markdown::OcrExportFormat::Hocr]), "requires ocr"),
+            (with(|o| (o.quick, o.ocr) = (true, true)), "quick"),
+            (with(|o| o.desaturate_below = Some(2.0)), "desaturate_below"),
+            (with(|o| (o.remove_markers, o.marker_colors) = (true, vec!["nope".to_string()])), "nope"),
+        ];
+        for (result, expected) in rejected {
+            let err = result.unwrap_err();
+            assert!(err.contains(expected), "{}", err);
+        }
+
+        assert!(with(|o| (o.ocr, o.write_text_sidecar) = (true, true)).is_ok());
+        assert!(with(|o| o.quick = true).is_ok());
+    }
+
+    #[test]
+    fn test_convert_options_merged() {
+        let base = ConvertOptions {
+            dpi: 450,
+            ..Default::default()
+        };
+        let merged = base.merged(&serde_json::json!({"ocr": true, "binding": "right-to-left"})).unwrap();
+        assert_eq!(merged.dpi, 450);
+        assert!(merged.ocr);
+        assert_eq!(merged.binding, crate::Binding::RightToLeft);
+
+        assert_eq!(base.merged(&serde_json::json!({})).unwrap().options_hash(), base.options_hash());
+        assert!(base.merged(&serde_json::json!({"nope": 1})).unwrap_err().contains("nope"));
+        assert!(base.merged(&serde_json::json!({"dpi": "high"})).is_err());
+        assert!(base.merged(&serde_json::json!([1])).is_err());
+    }
+
+    #[test]
+    fn test_options_hash_normalized() {
+        let a = ConvertOptions::default();
+        let b: ConvertOptions = serde_json::from_str("{}").unwrap();
+        let c = ConvertOptions {
+            dpi: 600,
+            ..Default::default()
+        };
+
+        assert!(a.options_hash().starts_with("sha256:"));
+        assert_eq!(a.options_hash(), b.options_hash());
+        assert_ne!(a.options_hash(), c.options_hash());
+        assert_eq!(Job::new("a.pdf", a.clone()).options_hash, a.options_hash());
+    }
+
+    #[test]
+    fn test_job_queue_find_completed() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.pdf");
+        std::fs::write(&output, b"%PDF").unwrap();
+
+        let queue = JobQueue::new();
+        let options = ConvertOptions::default();
+        let mut done = Job::new("book.pdf", options.clone()).with_source_hash("sha256:src");
+        done.complete(output);
+        let done_id = queue.submit(done);
+        queue.submit(Job::new("book.pdf", options.clone()).with_source_hash("sha256:src"));
+
+        let found = queue.find_completed("sha256:src", &options.options_hash());
+        assert_eq!(found.map(|j| j.id), Some(done_id));
+        assert!(queue.find_completed("sha256:other", &options.options_hash()).is_none());
+
+        let ocr = ConvertOptions {
+            ocr: true,
+            ..Default::default()
+        };
+        assert!(queue.find_completed("sha256:src", &ocr.options_hash()).is_none());
+    }
+
+    #[test]
+    fn test_job_deserialize_without_hashes() {
+        let job = Job::new("old.pdf", ConvertOptions::default());
+        let mut value = serde_json::to_value(&job).unwrap();
+        value.as_object_mut().unwrap().remove("options_hash");
+
+        let restored: Job = serde_json::from_value(value).unwrap();
+        assert!(restored.options_hash.is_empty());
+        assert!(restored.source_hash.is_none());
+    }
 }
@@ -93,6 +93,12 @@ pub struct MetricsCollector {
     completed_batches: AtomicU64,
     /// Active batches
     active_batches: AtomicU64,
+    /// Retention cleanup runs
+    cleanup_runs: AtomicU64,
+    /// Jobs purged by retention cleanup
+    cleanup_jobs_removed: AtomicU64,
+    /// Bytes freed by retention cleanup
+    cleanup_bytes_freed: AtomicU64,
 }
 
 impl MetricsCollector {
@@ -110,6 +116,9 @@ impl MetricsCollector {
             total_batches: AtomicU64::new(0),
             completed_batches: AtomicU64::new(0),
             active_batches: AtomicU64::new(0),
+            cleanup_runs: AtomicU64::new(0),
+            cleanup_jobs_removed: AtomicU64::new(0),
+            cleanup_bytes_freed: AtomicU64::new(0),
         }
     }
 
@@ -145,6 +154,13 @@ impl MetricsCollector {
         self.active_batches.fetch_sub(1, Ordering::Relaxed);
     }
 
+    /// Record a retention cleanup run
+    pub fn record_cleanup(&self, jobs_removed: u64, bytes_freed: u64) {
+        self.cleanup_runs.fetch_add(1, Ordering::Relaxed);
+        self.cleanup_jobs_removed.fetch_add(jobs_removed, Ordering::Relaxed);
+        self.cleanup_bytes_freed.fetch_add(bytes_freed, Ordering::Relaxed);
+    }
+
     /// Get server uptime in seconds
     pub fn get_uptime(&self) -> u64 {
         self.started_at.elapsed().as_secs()
@@ -237,6 +253,17 @@ impl MetricsCollector {
         output.push_str("# TYPE superbook_workers gauge\n");
         output.push_str(&format!("superbook_workers {}\n", worker_count));
 
+        // Retention cleanup
+        output.push_str("\n# HELP superbook_cleanup_runs_total Retention cleanup runs\n");
+        output.push_str("# TYPE superbook_cleanup_runs_total counter\n");
+        output.push_str(&format!("superbook_cleanup_runs_total {}\n", self.cleanup_runs.load(Ordering::Relaxed)));
+        output.push_str("\n# HELP superbook_cleanup_jobs_removed_total Jobs purged by retention cleanup\n");
+        output.push_str("# TYPE superbook_cleanup_jobs_removed_total counter\n");
+        output.push_str(&format!("superbook_cleanup_jobs_removed_total {}\n", self.cleanup_jobs_removed.load(Ordering::Relaxed)));
+        output.push_str("\n# HELP superbook_cleanup_bytes_freed_total Bytes freed by retention cleanup\n");
+        output.push_str("# TYPE superbook_cleanup_bytes_freed_total counter\n");
+        output.push_str(&format!("superbook_cleanup_bytes_freed_total {}\n", self.cleanup_bytes_freed.load(Ordering::Relaxed)));
+
         output
     }
 }
@@ -27,6 +27,7 @@ mod job;
 mod metrics;
 mod persistence;
 mod rate_limit;
+mod retention;
 mod routes;
 mod server;
 mod shutdown;
@@ -38,12 +39,14 @@ pub use batch::{BatchJob, BatchProgress, BatchQueue, BatchStatus, Priority};
 pub use cors::CorsConfig;
 pub use job::{ConvertOptions, Job, JobQueue, JobStatus, Progress};
 pub use metrics::{BatchStatistics, JobStatistics, MetricsCollector, ServerInfo, StatsResponse, SystemMetrics};
-pub use persistence::{HistoryQuery, HistoryResponse, JsonJobStore, JobStore, PersistenceConfig, RecoveryManager, RecoveryResult, RetryResponse, StorageBackend, StoreError};
+pub use persistence::{HistoryQuery, HistoryResponse, InMemoryJobStore, JsonJobStore, JobStore, PersistenceConfig, RecoveryManager, RecoveryResult, RetryResponse, StorageBackend, StoreError};
 pub use rate_limit::{RateLimitConfig, RateLimitError, RateLimitResult, RateLimiter, RateLimitStatus};
+pub use retention::{CleanupStats, RetentionTask};
 pub use server::{ServerConfig, WebServer};
 pub use shutdown::{ShutdownConfig, ShutdownCoordinator, ShutdownResult, ShutdownSignal, graceful_shutdown, wait_for_shutdown_signal};
 pub use websocket::{
-    generate_preview_base64, preview_stage, WsBroadcaster, WsMessage, PREVIEW_WIDTH,
+    generate_preview_base64, generate_preview_jpeg, preview_stage, WsBroadcaster, WsMessage,
+    PREVIEW_WIDTH,
 };
 pub use worker::{JobWorker, WorkerPool};
 
@@ -59,6 +62,12 @@ pub const DEFAULT_UPLOAD_LIMIT: usize = 500 * 1024 * 1024;
 /// Default job timeout in seconds (1 hour)
 pub const DEFAULT_JOB_TIMEOUT: u64 = 3600;
 
+/// Default queue depth above which `/readyz` reports not ready
+pub const DEFAULT_MAX_QUEUE_DEPTH: usize = 100;
+
+/// Width of the per-page previews served by `GET /api/jobs/{id}/preview/{page}`
+pub const PAGE_PREVIEW_WIDTH: u32 = 480;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,5 +79,7 @@ mod tests {
         assert_eq!(DEFAULT_BIND, "127.0.0.1");
         assert_eq!(DEFAULT_UPLOAD_LIMIT, 500 * 1024 * 1024);
         assert_eq!(DEFAULT_JOB_TIMEOUT, 3600);
+        assert_eq!(DEFAULT_MAX_QUEUE_DEPTH, 100);
+        assert_eq!(PAGE_PREVIEW_WIDTH, 480);
     }
 }
@@ -5,8 +5,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
 use uuid::Uuid;
 
 use super::job::{Job, JobStatus};
@@ -33,8 +34,12 @@ pub struct PersistenceConfig {
     pub backend: StorageBackend,
     /// Auto-save interval in seconds
     pub auto_save_interval: u64,
-    /// History retention in days
+    /// History retention in days (0 disables cleanup)
     pub retention_days: u32,
+    /// Retention cleanup interval in seconds
+    pub cleanup_interval: u64,
+    /// Write the JSON store without indentation (smaller files for large histories)
+    pub compact: bool,
 }
 
 impl Default for PersistenceConfig {
@@ -45,6 +50,8 @@ impl Default for PersistenceConfig {
             backend: StorageBackend::Json,
             auto_save_interval: 30,
             retention_days: 30,
+            cleanup_interval: 3600,
+            compact: false,
         }
     }
 }
@@ -69,6 +76,24 @@ impl PersistenceConfig {
         self.backend = backend;
         self
     }
+
+    /// Set history retention in days (0 disables cleanup)
+    pub fn with_retention_days(mut self, days: u32) -> Self {
+        self.retention_days = days;
+        self
+    }
+
+    /// Set retention cleanup interval in seconds
+    pub fn with_cleanup_interval(mut self, seconds: u64) -> Self {
+        self.cleanup_interval = seconds;
+        self
+    }
+
+    /// Write the JSON store compact instead of pretty-printed
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
 }
 
 /// Store error type
@@ -119,10 +144,17 @@ impl Default for StoredJobs {
 }
 
 /// JSON file-based job store
+///
+/// `flush` writes a temporary file next to the store, syncs it and renames
+/// it over the old one, so a crash mid-write leaves the previous history
+/// intact.
 pub struct JsonJobStore {
     path: PathBuf,
     cache: RwLock<HashMap<Uuid, Job>>,
     dirty: RwLock<bool>,
+    compact: bool,
+    /// Serializes flushes (they share the temporary file)
+    flush_lock: Mutex<()>,
 }
 
 impl JsonJobStore {
@@ -139,6 +171,8 @@ impl JsonJobStore {
             path,
             cache: RwLock::new(HashMap::new()),
             dirty: RwLock::new(false),
+            compact: false,
+            flush_lock: Mutex::new(()),
         };
 
         // Load existing data if present
@@ -164,11 +198,25 @@ impl JsonJobStore {
         Ok(())
     }
 
+    /// Write compact JSON instead of pretty-printed JSON
+    pub fn with_compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
     /// Check if there are unsaved changes
     pub fn is_dirty(&self) -> bool {
         *self.dirty.read().unwrap_or_else(|e| e.into_inner())
     }
 
+    fn set_dirty(&self, value: bool) -> Result<(), StoreError> {
+        let mut dirty = self.dirty.write().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
+        *dirty = value;
+        Ok(())
+    }
+
     /// Get the storage path
     pub fn path(&self) -> &PathBuf {
         &self.path
@@ -266,6 +314,9 @@ impl JobStore for JsonJobStore {
     }
 
     fn flush(&self) -> Result<(), StoreError> {
+        let _flush = self.flush_lock.lock().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
         let cache = self.cache.read().map_err(|e| {
             StoreError::Storage(format!("Lock error: {}", e))
         })?;
@@ -274,15 +325,137 @@ impl JobStore for JsonJobStore {
             version: 1,
             jobs: cache.clone(),
         };
+        // Clear the flag together with the snapshot: a save after this point
+        // sets it again and is written by the next flush
+        self.set_dirty(false)?;
+        drop(cache);
+
+        let content = if self.compact {
+            serde_json::to_string(&stored)
+        } else {
+            serde_json::to_string_pretty(&stored)
+        };
+        let written = content
+            .map_err(StoreError::from)
+            .and_then(|content| write_atomic(&self.path, content.as_bytes()).map_err(StoreError::from));
+        if written.is_err() {
+            self.set_dirty(true)?;
+        }
+        written
+    }
+}
 
-        let content = serde_json::to_string_pretty(&stored)?;
-        std::fs::write(&self.path, content)?;
+/// Replace `path` with `content` via a synced temporary file and a rename
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp{}", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+
+    let result = std::fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(e) = result.and_then(|()| std::fs::rename(&tmp, path)) {
+        std::fs::remove_file(&tmp).ok();
+        return Err(e);
+    }
 
-        let mut dirty = self.dirty.write().map_err(|e| {
+    // Persist the rename itself (directories cannot be opened on Windows)
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// In-memory job store
+///
+/// Keeps jobs in a `HashMap` without touching disk. `flush` is a no-op,
+/// which makes it suitable for tests and embedders that do not need
+/// persistence across restarts.
+#[derive(Default)]
+pub struct InMemoryJobStore {
+    jobs: RwLock<HashMap<Uuid, Job>>,
+}
+
+impl InMemoryJobStore {
+    /// Create an empty in-memory job store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the number of stored jobs
+    pub fn len(&self) -> usize {
+        self.jobs.read().map(|j| j.len()).unwrap_or(0)
+    }
+
+    /// Check if store is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl JobStore for InMemoryJobStore {
+    fn save(&self, job: &Job) -> Result<(), StoreError> {
+        let mut jobs = self.jobs.write().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
+        jobs.insert(job.id, job.clone());
+        Ok(())
+    }
+
+    fn get(&self, id: Uuid) -> Result<Option<Job>, StoreError> {
+        let jobs = self.jobs.read().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
+        Ok(jobs.get(&id).cloned())
+    }
+
+    fn list(&self) -> Result<Vec<Job>, StoreError> {
+        let jobs = self.jobs.read().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
+        Ok(jobs.values().cloned().collect())
+    }
+
+    fn delete(&self, id: Uuid) -> Result<(), StoreError> {
+        let mut jobs = self.jobs.write().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
+        jobs.remove(&id);
+        Ok(())
+    }
+
+    fn get_pending(&self) -> Result<Vec<Job>, StoreError> {
+        let jobs = self.jobs.read().map_err(|e| {
+            StoreError::Storage(format!("Lock error: {}", e))
+        })?;
+        Ok(jobs
+            .values()
+            .filter(|job| !job.is_terminal())
+            .cloned()
+            .collect())
+    }
+
+    fn cleanup(&self, older_than: DateTime<Utc>) -> Result<usize, StoreError> {
+        let mut jobs = self.jobs.write().map_err(|e| {
             StoreError::Storage(format!("Lock error: {}", e))
         })?;
-        *dirty = false;
 
+        let before = jobs.len();
+        jobs.retain(|_, job| {
+            if let Some(completed_at) = job.completed_at {
+                completed_at > older_than
+            } else {
+                true // Keep non-completed jobs
+            }
+        });
+
+        Ok(before - jobs.len())
+    }
+
+    fn flush(&self) -> Result<(), StoreError> {
+        // Nothing to persist
         Ok(())
     }
 }
@@ -504,6 +677,7 @@ mod tests {
         assert_eq!(config.backend, StorageBackend::Json);
         assert_eq!(config.auto_save_interval, 30);
         assert_eq!(config.retention_days, 30);
+        assert_eq!(config.cleanup_interval, 3600);
     }
 
     #[test]
@@ -522,6 +696,17 @@ mod tests {
         assert_eq!(config.backend, StorageBackend::Sqlite);
     }
 
+    #[test]
+    fn test_persistence_config_retention_builder() {
+        let config = PersistenceConfig::default()
+            .with_retention_days(7)
+            .with_cleanup_interval(600);
+        assert_eq!(config.retention_days, 7);
+        assert_eq!(config.cleanup_interval, 600);
+        assert!(!config.compact);
+        assert!(config.with_compact(true).compact);
+    }
+
     // PERSIST-002: JsonJobStore 作成
     #[test]
     fn test_json_store_new() {
@@ -633,6 +818,53 @@ mod tests {
 
         assert!(path.exists());
         assert!(!store.is_dirty());
+        // Written via a temporary file that is renamed away
+        let names: Vec<_> = std::fs::read_dir(dir.path()).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(names, vec![std::ffi::OsString::from("jobs.json")]);
+        assert!(std::fs::read_to_string(&path).unwrap().contains("\n  "));
+    }
+
+    // PERSIST-013: コンパクト出力と上書き失敗時の保持
+    #[test]
+    fn test_json_store_compact_and_failed_flush() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        let store = JsonJobStore::new(&path).unwrap().with_compact(true);
+        let job = Job::new("test.pdf", ConvertOptions::default());
+        store.save(&job).unwrap();
+        store.flush().unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains('\n'));
+        assert_eq!(JsonJobStore::new(&path).unwrap().len(), 1);
+
+        // A flush that cannot write leaves the previous history in place
+        store.save(&Job::new("second.pdf", ConvertOptions::default())).unwrap();
+        std::fs::create_dir(dir.path().join(format!("jobs.json.tmp{}", std::process::id()))).unwrap();
+        assert!(store.flush().is_err());
+        assert!(store.is_dirty());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+    }
+
+    // PERSIST-014: フラッシュ中の保存は未保存のまま残る
+    #[test]
+    fn test_json_store_save_during_flush() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        let store = JsonJobStore::new(&path).unwrap();
+        for round in 0..50 {
+            let job = Job::new(format!("{}.pdf", round), ConvertOptions::default());
+            std::thread::scope(|scope| {
+                scope.spawn(|| store.flush().unwrap());
+                scope.spawn(|| store.save(&job).unwrap());
+            });
+            // Clean means the job reached the file
+            if !store.is_dirty() {
+                assert!(std::fs::read_to_string(&path).unwrap().contains(&job.id.to_string()));
+            }
+        }
+        store.flush().unwrap();
+        assert_eq!(JsonJobStore::new(&path).unwrap().len(), 50);
     }
 
     // PERSIST-009: ファイル読み込み
@@ -693,6 +925,42 @@ mod tests {
         assert_eq!(store.len(), 2);
     }
 
+    #[test]
+    fn test_memory_store_save_get_delete() {
+        let store = InMemoryJobStore::new();
+        assert!(store.is_empty());
+
+        let job = Job::new("test.pdf", ConvertOptions::default());
+        let job_id = job.id;
+        store.save(&job).unwrap();
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get(job_id).unwrap().unwrap().input_filename, "test.pdf");
+
+        store.delete(job_id).unwrap();
+        assert!(store.get(job_id).unwrap().is_none());
+        assert!(store.flush().is_ok());
+    }
+
+    #[test]
+    fn test_memory_store_pending_and_cleanup() {
+        let store = InMemoryJobStore::new();
+
+        let mut old_job = Job::new("old.pdf", ConvertOptions::default());
+        old_job.complete(PathBuf::from("/out/old.pdf"));
+        old_job.completed_at = Some(Utc::now() - chrono::Duration::days(60));
+        let pending_job = Job::new("pending.pdf", ConvertOptions::default());
+
+        store.save(&old_job).unwrap();
+        store.save(&pending_job).unwrap();
+
+        assert_eq!(store.get_pending().unwrap().len(), 1);
+
+        let cutoff = Utc::now() - chrono::Duration::days(30);
+        assert_eq!(store.cleanup(cutoff).unwrap(), 1);
+        assert_eq!(store.len(), 1);
+    }
+
     #[test]
     fn test_storage_backend_default() {
         assert_eq!(StorageBackend::default(), StorageBackend::Json);
@@ -847,4 +1115,18 @@ mod tests {
 
         assert!(Arc::ptr_eq(manager.store(), &store));
     }
+
+    #[tokio::test]
+    async fn test_recovery_manager_with_memory_store() {
+        let store: Arc<dyn JobStore> = Arc::new(InMemoryJobStore::new());
+        let queue = crate::web::job::JobQueue::new();
+
+        let mut processing = Job::new("processing.pdf", ConvertOptions::default());
+        processing.start();
+        store.save(&processing).unwrap();
+
+        let manager = RecoveryManager::new(store, queue.clone());
+        assert_eq!(manager.requeue_processing().await, 1);
+        assert_eq!(queue.get(processing.id).unwrap().status, JobStatus::Queued);
+    }
 }
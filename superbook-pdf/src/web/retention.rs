@@ -0,0 +1,333 @@
+//! Result retention for the web server
+//!
+//! Periodically purges finished jobs older than the retention period
+//! together with their output PDFs, uploaded sources and page previews.
+//!
+//! Spec Reference: specs/26-persistence.spec.md
+
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::job::{Job, JobQueue};
+use super::metrics::MetricsCollector;
+use super::persistence::JobStore;
+
+/// Statistics of a single cleanup run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanupStats {
+    /// Number of jobs purged
+    pub jobs_removed: usize,
+    /// Number of files deleted (outputs, uploads and previews)
+    pub files_removed: usize,
+    /// Bytes freed by deleted files
+    pub bytes_freed: u64,
+    /// Expired jobs skipped because they are still queued or processing
+    pub skipped_active: usize,
+}
+
+/// Retention cleanup task
+pub struct RetentionTask {
+    queue: JobQueue,
+    store: Option<Arc<dyn JobStore>>,
+    upload_dir: PathBuf,
+    metrics: Arc<MetricsCollector>,
+    retention_days: u32,
+}
+
+impl RetentionTask {
+    /// Create a new retention task
+    pub fn new(
+        queue: JobQueue,
+        store: Option<Arc<dyn JobStore>>,
+        upload_dir: PathBuf,
+        metrics: Arc<MetricsCollector>,
+        retention_days: u32,
+    ) -> Self {
+        Self {
+            queue,
+            store,
+            upload_dir,
+            metrics,
+            retention_days,
+        }
+    }
+
+    /// Cutoff timestamp: jobs completed before this are purged
+    pub fn cutoff(&self) -> DateTime<Utc> {
+        Utc::now() - chrono::Duration::days(i64::from(self.retention_days))
+    }
+
+    /// Run a single cleanup pass
+    pub fn run_once(&self) -> CleanupStats {
+        self.run_with_cutoff(self.cutoff())
+    }
+
+    /// Run a single cleanup pass with an explicit cutoff
+    pub fn run_with_cutoff(&self, cutoff: DateTime<Utc>) -> CleanupStats {
+        let mut stats = CleanupStats::default();
+
+        let queued = self.queue.list();
+        let active_ids: HashSet<_> = queued
+            .iter()
+            .filter(|job| !job.is_terminal())
+            .map(|job| job.id)
+            .collect();
+        // Files still referenced by running jobs are never deleted
+        let protected: HashSet<PathBuf> = queued
+            .iter()
+            .filter(|job| !job.is_terminal())
+            .flat_map(|job| self.job_files(job))
+            .collect();
+
+        let mut expired: Vec<Job> = queued
+            .into_iter()
+            .filter(|job| job.is_terminal() && is_expired(job, cutoff))
+            .collect();
+        if let Some(store) = &self.store {
+            match store.list() {
+                Ok(jobs) => {
+                    let known: HashSet<_> = expired.iter().map(|job| job.id).collect();
+                    for job in jobs {
+                        if known.contains(&job.id) || !is_expired(&job, cutoff) {
+                            continue;
+                        }
+                        if active_ids.contains(&job.id) || !job.is_terminal() {
+                            stats.skipped_active += 1;
+                            continue;
+                        }
+                        expired.push(job);
+                    }
+                }
+                Err(e) => warn!("Retention: failed to list stored jobs: {}", e),
+            }
+        }
+
+        for job in &expired {
+            for path in self.job_files(job) {
+                if protected.contains(&path) {
+                    continue;
+                }
+                if let Some(size) = remove_file(&path) {
+                    stats.files_removed += 1;
+                    stats.bytes_freed += size;
+                }
+            }
+            if let Some(dir) = &job.preview_dir {
+                for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+                    if let Some(size) = remove_file(&entry.path()) {
+                        stats.files_removed += 1;
+                        stats.bytes_freed += size;
+                    }
+                }
+                std::fs::remove_dir(dir).ok();
+            }
+            self.queue.remove(job.id);
+            if let Some(store) = &self.store {
+                if let Err(e) = store.delete(job.id) {
+                    warn!(job_id = %job.id, "Retention: failed to delete stored job: {}", e);
+                }
+            }
+            stats.jobs_removed += 1;
+        }
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.flush() {
+                warn!("Retention: failed to flush job store: {}", e);
+            }
+        }
+
+        self.metrics
+            .record_cleanup(stats.jobs_removed as u64, stats.bytes_freed);
+        if stats.jobs_removed > 0 {
+            info!(
+                "Retention cleanup: removed {} jobs, {} files ({} bytes), skipped {} active",
+                stats.jobs_removed, stats.files_removed, stats.bytes_freed, stats.skipped_active
+            );
+        }
+
+        stats
+    }
+
+    /// Spawn the task, running a cleanup pass every `interval`
+    pub fn spawn(self, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.run_once();
+            }
+        })
+    }
+
+    /// Files owned by a job: its output PDF and uploaded source
+    fn job_files(&self, job: &Job) -> Vec<PathBuf> {
+        let mut files = vec![self
+            .upload_dir
+            .join(format!("{}_{}", job.id, job.input_filename))];
+        if let Some(output) = &job.output_path {
+            files.push(output.clone());
+        }
+        files
+    }
+}
+
+fn is_expired(job: &Job, cutoff: DateTime<Utc>) -> bool {
+    job.completed_at.is_some_and(|completed_at| completed_at <= cutoff)
+}
+
+/// Remove a file, returning its size if it was deleted
+fn remove_file(path: &Path) -> Option<u64> {
+    let size = std::fs::metadata(path).ok()?.len();
+    match std::fs::remove_file(path) {
+        Ok(()) => Some(size),
+        Err(e) => {
+            warn!("Retention: failed to remove {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::job::{ConvertOptions, JobStatus};
+    use crate::web::persistence::InMemoryJobStore;
+    use tempfile::tempdir;
+
+    fn finished_job(dir: &Path, name: &str, days_ago: i64) -> Job {
+        let mut job = Job::new(name, ConvertOptions::default());
+        let output = dir.join(format!("{}_converted.pdf", job.id));
+        std::fs::write(&output, b"%PDF-1.4").unwrap();
+        job.complete(output);
+        job.completed_at = Some(Utc::now() - chrono::Duration::days(days_ago));
+        job
+    }
+
+    fn task(queue: &JobQueue, store: Option<Arc<dyn JobStore>>, upload_dir: &Path) -> RetentionTask {
+        RetentionTask::new(
+            queue.clone(),
+            store,
+            upload_dir.to_path_buf(),
+            Arc::new(MetricsCollector::new()),
+            30,
+        )
+    }
+
+    #[test]
+    fn test_cleanup_removes_expired_job_and_files() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::new();
+
+        let job = finished_job(dir.path(), "old.pdf", 60);
+        let upload = dir.path().join(format!("{}_old.pdf", job.id));
+        std::fs::write(&upload, b"source").unwrap();
+        let output = job.output_path.clone().unwrap();
+        let job_id = queue.submit(job);
+
+        let stats = task(&queue, None, dir.path()).run_once();
+
+        assert_eq!(stats.jobs_removed, 1);
+        assert_eq!(stats.files_removed, 2);
+        assert_eq!(stats.bytes_freed, 14);
+        assert!(!output.exists());
+        assert!(!upload.exists());
+        assert!(queue.get(job_id).is_none());
+    }
+
+    #[test]
+    fn test_cleanup_removes_previews() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::new();
+
+        let mut job = finished_job(dir.path(), "old.pdf", 60);
+        let preview_dir = dir.path().join("previews").join(job.id.to_string());
+        std::fs::create_dir_all(&preview_dir).unwrap();
+        for page in 1..=2 {
+            std::fs::write(crate::web::job::preview_file(&preview_dir, page), b"jpeg").unwrap();
+        }
+        job.preview_dir = Some(preview_dir.clone());
+        queue.submit(job);
+
+        let stats = task(&queue, None, dir.path()).run_once();
+
+        assert_eq!(stats.files_removed, 3);
+        assert_eq!(stats.bytes_freed, 8 + 8);
+        assert!(!preview_dir.exists());
+    }
+
+    #[test]
+    fn test_cleanup_keeps_recent_jobs() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::new();
+        let job = finished_job(dir.path(), "recent.pdf", 1);
+        let output = job.output_path.clone().unwrap();
+        queue.submit(job);
+
+        let stats = task(&queue, None, dir.path()).run_once();
+
+        assert_eq!(stats, CleanupStats::default());
+        assert!(output.exists());
+    }
+
+    #[test]
+    fn test_cleanup_skips_running_jobs() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::new();
+        let store = Arc::new(InMemoryJobStore::new());
+
+        // Stored record looks expired, but the job was requeued and is running again
+        let stored = finished_job(dir.path(), "rerun.pdf", 60);
+        let output = stored.output_path.clone().unwrap();
+        store.save(&stored).unwrap();
+        let mut running = stored.clone();
+        running.status = JobStatus::Processing;
+        running.completed_at = None;
+        queue.submit(running);
+
+        let upload = dir.path().join(format!("{}_rerun.pdf", stored.id));
+        std::fs::write(&upload, b"source").unwrap();
+
+        let stats = task(&queue, Some(store.clone()), dir.path()).run_once();
+
+        assert_eq!(stats.jobs_removed, 0);
+        assert_eq!(stats.skipped_active, 1);
+        assert!(upload.exists());
+        assert!(output.exists());
+        assert!(queue.get(stored.id).is_some());
+        assert!(store.get(stored.id).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_cleanup_purges_stored_jobs() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::new();
+        let store = Arc::new(InMemoryJobStore::new());
+        let job = finished_job(dir.path(), "stored.pdf", 45);
+        store.save(&job).unwrap();
+
+        let stats = task(&queue, Some(store.clone()), dir.path()).run_once();
+
+        assert_eq!(stats.jobs_removed, 1);
+        assert!(store.is_empty());
+        assert!(!job.output_path.unwrap().exists());
+    }
+
+    #[test]
+    fn test_cleanup_records_metrics() {
+        let dir = tempdir().unwrap();
+        let queue = JobQueue::new();
+        queue.submit(finished_job(dir.path(), "old.pdf", 60));
+        let metrics = Arc::new(MetricsCollector::new());
+        let task = RetentionTask::new(queue, None, dir.path().to_path_buf(), metrics.clone(), 30);
+
+        task.run_once();
+
+        let output = metrics.format_prometheus(0, 0, 1);
+        assert!(output.contains("superbook_cleanup_runs_total 1"));
+        assert!(output.contains("superbook_cleanup_jobs_removed_total 1"));
+    }
+}
@@ -3,7 +3,7 @@
 //! Provides endpoints for PDF conversion, job management, and health checks.
 
 use axum::{
-    extract::{Multipart, Path, State},
+    extract::{multipart::Field, DefaultBodyLimit, Multipart, Path, State},
     http::{header, StatusCode},
     response::{IntoResponse, Json},
     routing::{delete, get, post},
@@ -45,9 +45,14 @@ pub struct AppState {
     pub job_store: Option<Arc<dyn JobStore>>,
     #[allow(dead_code)]
     pub persistence_config: PersistenceConfig,
+    /// Pending jobs above which `/readyz` reports not ready
+    pub max_queue_depth: usize,
+    /// Upload size limit in bytes for keys without their own limit
+    pub upload_limit: usize,
 }
 
 impl AppState {
+    #[allow(dead_code)]
     pub fn new(work_dir: PathBuf, worker_count: usize) -> Self {
         Self::new_with_config(work_dir, worker_count, RateLimitConfig::default(), AuthConfig::default())
     }
@@ -96,7 +101,7 @@ impl AppState {
         // Initialize job store if persistence is enabled
         let job_store: Option<Arc<dyn JobStore>> = if persistence_config.enabled {
             let store_path = persistence_config.storage_path.join("jobs.json");
-            match JsonJobStore::new(store_path) {
+            match JsonJobStore::new(store_path).map(|store| store.with_compact(persistence_config.compact)) {
                 Ok(store) => Some(Arc::new(store)),
                 Err(e) => {
                     eprintln!("Warning: Failed to initialize job store: {}", e);
@@ -119,18 +124,47 @@ impl AppState {
             auth_manager,
             job_store,
             persistence_config,
+            max_queue_depth: super::DEFAULT_MAX_QUEUE_DEPTH,
+            upload_limit: super::DEFAULT_UPLOAD_LIMIT,
         }
     }
+
+    /// Set the queue depth above which the server reports not ready
+    pub fn with_max_queue_depth(mut self, depth: usize) -> Self {
+        self.max_queue_depth = depth;
+        self
+    }
+
+    /// Set the default upload size limit in bytes
+    pub fn with_upload_limit(mut self, bytes: usize) -> Self {
+        self.upload_limit = bytes;
+        self
+    }
+
+    /// Serialize GPU stages of jobs with other conversions (`gpu_wait`)
+    pub fn with_gpu_wait(mut self, enabled: bool) -> Self {
+        self.worker_pool = self.worker_pool.with_gpu_wait(enabled);
+        self
+    }
+
+    /// Replace the job store (e.g. with an in-memory store for tests)
+    pub fn with_job_store(mut self, store: Arc<dyn JobStore>) -> Self {
+        self.job_store = Some(store);
+        self
+    }
 }
 
 /// Build the API router
 pub fn api_routes() -> Router<Arc<AppState>> {
     Router::new()
-        .route("/convert", post(upload_and_convert))
+        // Size is enforced per request in the handler (per-key limits may exceed the default)
+        .route("/convert", post(upload_and_convert).layer(DefaultBodyLimit::disable()))
         .route("/jobs/{id}", get(get_job))
         .route("/jobs/{id}", delete(cancel_job))
         .route("/jobs/{id}/download", get(download_result))
+        .route("/jobs/{id}/preview/{page}", get(get_page_preview))
         .route("/jobs/{id}/retry", post(retry_job))
+        .route("/jobs/{id}/rerun", post(rerun_job))
         .route("/jobs/history", get(get_job_history))
         .route("/batch", post(create_batch))
         .route("/batch/{id}", get(get_batch))
@@ -143,6 +177,13 @@ pub fn api_routes() -> Router<Arc<AppState>> {
         .route("/auth/status", get(get_auth_status))
 }
 
+/// Build the liveness/readiness probe router (mounted at the root for load balancers)
+pub fn probe_routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/healthz", get(liveness_check))
+        .route("/readyz", get(readiness_check))
+}
+
 /// Build the web UI router
 pub fn web_routes() -> Router<Arc<AppState>> {
     Router::new().route("/", get(index_page))
@@ -209,8 +250,8 @@ pub struct ToolStatus {
 /// Health check endpoint
 async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
     let tools = ToolStatus {
-        poppler: which::which("pdftoppm").is_ok(),
-        tesseract: which::which("tesseract").is_ok(),
+        poppler: crate::tools::is_available("pdftoppm"),
+        tesseract: crate::tools::is_available("tesseract"),
         realesrgan: check_python_module("realesrgan"),
         yomitoku: check_python_module("yomitoku"),
     };
@@ -222,6 +263,49 @@ async fn health_check(State(state): State<Arc<AppState>>) -> Json<HealthResponse
     })
 }
 
+/// Liveness probe response
+#[derive(Debug, Serialize)]
+pub struct LivenessResponse {
+    pub status: String,
+}
+
+/// Readiness probe response
+#[derive(Debug, Serialize)]
+pub struct ReadinessResponse {
+    pub ready: bool,
+    pub queue_depth: usize,
+    pub max_queue_depth: usize,
+    pub workers: usize,
+}
+
+/// Liveness probe: the process is up and serving requests
+async fn liveness_check() -> Json<LivenessResponse> {
+    Json(LivenessResponse {
+        status: "ok".to_string(),
+    })
+}
+
+/// Readiness probe: 503 while the worker pool is down or the queue exceeds `max_queue_depth`
+async fn readiness_check(State(state): State<Arc<AppState>>) -> (StatusCode, Json<ReadinessResponse>) {
+    let queue_depth = state.queue.pending_count();
+    let ready = state.worker_pool.is_ready() && queue_depth <= state.max_queue_depth;
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (
+        status,
+        Json(ReadinessResponse {
+            ready,
+            queue_depth,
+            max_queue_depth: state.max_queue_depth,
+            workers: state.worker_pool.worker_count(),
+        }),
+    )
+}
+
 /// Check if a Python module is available
 fn check_python_module(module: &str) -> bool {
     // Check if python3 exists
@@ -467,55 +551,227 @@ async fn retry_job(
     Ok(Json(RetryResponse::success(new_job_id)))
 }
 
+/// Re-run a job's uploaded source with modified options
+///
+/// The body is a JSON object of `ConvertOptions` fields merged onto the
+/// original job's options (an empty body re-runs with the same options).
+/// The new job reuses the stored upload, so the source is not sent again.
+async fn rerun_job(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+    body: axum::body::Bytes,
+) -> Result<(StatusCode, Json<UploadResponse>), AppError> {
+    let job = state
+        .queue
+        .get(id)
+        .ok_or_else(|| AppError::NotFound(format!("Job {} not found", id)))?;
+
+    let overrides = if body.iter().all(u8::is_ascii_whitespace) {
+        serde_json::Value::Object(Default::default())
+    } else {
+        serde_json::from_slice(&body)
+            .map_err(|e| AppError::BadRequest(format!("Invalid options JSON: {}", e)))?
+    };
+    let options = job.options.merged(&overrides).map_err(AppError::BadRequest)?;
+    options.validate().map_err(AppError::UnprocessableEntity)?;
+
+    let input_path = state.upload_dir.join(format!("{}_{}", id, job.input_filename));
+    if !input_path.exists() {
+        return Err(AppError::Conflict(format!("Source file of job {} is no longer available", id)));
+    }
+
+    let mut new_job = Job::new(&job.input_filename, options.clone()).with_rerun_of(id);
+    if let Some(source_hash) = &job.source_hash {
+        new_job = new_job.with_source_hash(source_hash.clone());
+    }
+    let new_job_id = new_job.id;
+    let created_at = new_job.created_at.to_rfc3339();
+
+    let new_input_path = state.upload_dir.join(format!("{}_{}", new_job_id, job.input_filename));
+    std::fs::copy(&input_path, &new_input_path)
+        .map_err(|e| AppError::Internal(format!("Failed to copy source file: {}", e)))?;
+
+    state.queue.submit(new_job);
+
+    if let Err(e) = state.worker_pool.submit(new_job_id, new_input_path, options).await {
+        state.queue.update(new_job_id, |job| {
+            job.fail(format!("Failed to start processing: {}", e));
+        });
+    }
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(UploadResponse {
+            job_id: new_job_id,
+            status: "queued".to_string(),
+            created_at,
+            reused: false,
+        }),
+    ))
+}
+
 /// Upload request response
 #[derive(Debug, Serialize)]
 pub struct UploadResponse {
     pub job_id: Uuid,
     pub status: String,
     pub created_at: String,
+    /// True when an existing completed job was returned instead of a new one
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub reused: bool,
+}
+
+impl UploadResponse {
+    /// Response pointing at an existing completed job
+    pub fn reused(job: &Job) -> Self {
+        Self {
+            job_id: job.id,
+            status: job.status.to_string(),
+            created_at: job.created_at.to_rfc3339(),
+            reused: true,
+        }
+    }
+}
+
+/// Content types accepted for the uploaded PDF part
+const PDF_CONTENT_TYPES: &[&str] = &["application/pdf", "application/octet-stream"];
+
+/// Leading bytes of every PDF file
+const PDF_MAGIC: &[u8] = b"%PDF-";
+
+/// Upload size limit for the request: the API key's own limit, else the server default
+fn effective_upload_limit(state: &AppState, headers: &axum::http::HeaderMap) -> usize {
+    let authorization = headers.get(header::AUTHORIZATION).and_then(|v| v.to_str().ok());
+    let x_api_key = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+    extract_api_key(authorization, x_api_key)
+        .and_then(|key| state.auth_manager.upload_limit(&key))
+        .unwrap_or(state.upload_limit)
+}
+
+/// Reject a file part whose declared content type is missing or not a PDF
+fn validate_pdf_content_type(content_type: Option<&str>) -> Result<(), AppError> {
+    let content_type = content_type
+        .ok_or_else(|| AppError::UnsupportedMediaType("Missing content type on 'file' field".to_string()))?;
+    let essence = content_type.split(';').next().unwrap_or("").trim().to_ascii_lowercase();
+    if PDF_CONTENT_TYPES.contains(&essence.as_str()) {
+        Ok(())
+    } else {
+        Err(AppError::UnsupportedMediaType(format!(
+            "Unsupported content type '{}' on 'file' field (expected application/pdf)",
+            content_type
+        )))
+    }
+}
+
+/// Read the file part, enforcing the size limit and the `%PDF-` header as
+/// data arrives; the `%%EOF` marker is checked once the part is complete
+async fn read_pdf_field(mut field: Field<'_>, limit: usize) -> Result<Vec<u8>, AppError> {
+    let mut data = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if data.len() + chunk.len() > limit {
+            return Err(AppError::PayloadTooLarge(format!(
+                "Upload exceeds the {} byte limit",
+                limit
+            )));
+        }
+        data.extend_from_slice(&chunk);
+        if data.len() >= PDF_MAGIC.len() && !data.starts_with(PDF_MAGIC) {
+            return Err(not_a_pdf());
+        }
+    }
+    if !data.is_empty() && !data.starts_with(PDF_MAGIC) {
+        return Err(not_a_pdf());
+    }
+    crate::validate_pdf_bytes(&data).map_err(AppError::InvalidPdf)?;
+    Ok(data)
+}
+
+fn not_a_pdf() -> AppError {
+    AppError::InvalidPdf(crate::InvalidPdf::MissingHeader)
+}
+
+fn multipart_error(e: axum::extract::multipart::MultipartError) -> AppError {
+    AppError::BadRequest(format!("Malformed multipart body: {}", e))
 }
 
 /// Upload and convert a PDF
+///
+/// Accepts exactly the fields `file` (required), `options` (JSON) and
+/// `reuse_existing`; anything else, a repeated field, a non-PDF part or an
+/// oversized upload is rejected with a 4xx error. Options that parse but
+/// fail [`ConvertOptions::validate`] are rejected with 422.
 async fn upload_and_convert(
     State(state): State<Arc<AppState>>,
+    headers: axum::http::HeaderMap,
     mut multipart: Multipart,
 ) -> Result<(StatusCode, Json<UploadResponse>), AppError> {
+    let upload_limit = effective_upload_limit(&state, &headers);
     let mut filename = String::new();
-    let mut options = ConvertOptions::default();
+    let mut options: Option<ConvertOptions> = None;
     let mut file_data: Option<Vec<u8>> = None;
+    let mut reuse_existing: Option<bool> = None;
 
-    while let Ok(Some(field)) = multipart.next_field().await {
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
         let name = field.name().unwrap_or("").to_string();
 
         match name.as_str() {
             "file" => {
+                if file_data.is_some() {
+                    return Err(AppError::BadRequest("Duplicate 'file' field".to_string()));
+                }
+                validate_pdf_content_type(field.content_type())?;
                 filename = field
                     .file_name()
                     .unwrap_or("upload.pdf")
                     .to_string();
-                if let Ok(data) = field.bytes().await {
-                    file_data = Some(data.to_vec());
-                }
+                file_data = Some(read_pdf_field(field, upload_limit).await?);
             }
             "options" => {
-                if let Ok(text) = field.text().await {
-                    if let Ok(parsed) = serde_json::from_str(&text) {
-                        options = parsed;
-                    }
+                if options.is_some() {
+                    return Err(AppError::BadRequest("Duplicate 'options' field".to_string()));
                 }
+                let text = field.text().await.map_err(multipart_error)?;
+                let parsed = serde_json::from_str(&text)
+                    .map_err(|e| AppError::BadRequest(format!("Invalid 'options' JSON: {}", e)))?;
+                options = Some(parsed);
+            }
+            "reuse_existing" => {
+                if reuse_existing.is_some() {
+                    return Err(AppError::BadRequest("Duplicate 'reuse_existing' field".to_string()));
+                }
+                let text = field.text().await.map_err(multipart_error)?;
+                reuse_existing = Some(match text.trim() {
+                    "true" | "1" | "yes" => true,
+                    "false" | "0" | "no" => false,
+                    other => {
+                        return Err(AppError::BadRequest(format!(
+                            "Invalid 'reuse_existing' value '{}' (expected true or false)",
+                            other
+                        )))
+                    }
+                });
+            }
+            other => {
+                return Err(AppError::BadRequest(format!("Unexpected multipart field '{}'", other)));
             }
-            _ => {}
         }
     }
 
-    if filename.is_empty() {
-        return Err(AppError::BadRequest("No file uploaded".to_string()));
-    }
+    let file_data = file_data.ok_or_else(|| AppError::BadRequest("No file uploaded".to_string()))?;
+    let options = options.unwrap_or_default();
+    options.validate().map_err(AppError::UnprocessableEntity)?;
+    let reuse_existing = reuse_existing.unwrap_or(false);
+    let source_hash = crate::cache::hash_bytes(&file_data);
 
-    let file_data = file_data.ok_or_else(|| AppError::BadRequest("No file data".to_string()))?;
+    // Return an existing result for an identical source + options
+    if reuse_existing {
+        if let Some(existing) = state.queue.find_completed(&source_hash, &options.options_hash()) {
+            return Ok((StatusCode::OK, Json(UploadResponse::reused(&existing))));
+        }
+    }
 
     // Create job
-    let job = Job::new(&filename, options.clone());
+    let job = Job::new(&filename, options.clone()).with_source_hash(source_hash);
     let job_id = job.id;
     let created_at = job.created_at.to_rfc3339();
 
@@ -542,6 +798,7 @@ async fn upload_and_convert(
             job_id,
             status: "queued".to_string(),
             created_at,
+            reused: false,
         }),
     ))
 }
@@ -639,6 +896,31 @@ async fn download_result(
     }
 }
 
+/// Low-resolution JPEG preview of a processed page (1-based), available while the job runs
+async fn get_page_preview(
+    State(state): State<Arc<AppState>>,
+    Path((id, page)): Path<(Uuid, usize)>,
+) -> Result<impl IntoResponse, AppError> {
+    let job = state
+        .queue
+        .get(id)
+        .ok_or(AppError::NotFound(format!("Job {} not found", id)))?;
+    let path = job
+        .preview_path(page)
+        .ok_or(AppError::NotFound(format!("Preview of page {} is not available", page)))?;
+    let data = std::fs::read(&path)
+        .map_err(|e| AppError::Internal(format!("Failed to read preview: {}", e)))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/jpeg"),
+            // Pages are previewed again once the final image is ready
+            (header::CACHE_CONTROL, "no-cache"),
+        ],
+        data,
+    ))
+}
+
 // ========== Batch API Handlers ==========
 
 /// Batch creation request
@@ -657,6 +939,16 @@ pub struct BatchResponse {
     pub status: String,
     pub job_count: usize,
     pub created_at: String,
+    /// Uploaded files that are not valid PDFs (no job created)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_files: Vec<InvalidUpload>,
+}
+
+/// A batch upload rejected by the PDF header/trailer check
+#[derive(Debug, Serialize)]
+pub struct InvalidUpload {
+    pub filename: String,
+    pub error: String,
 }
 
 /// Batch status response
@@ -748,6 +1040,26 @@ async fn create_batch(
     if filenames.is_empty() {
         return Err(AppError::BadRequest("No files uploaded".to_string()));
     }
+    options.validate().map_err(AppError::UnprocessableEntity)?;
+
+    // Invalid PDFs are reported instead of becoming failed jobs; a batch
+    // without a single valid PDF is rejected
+    let mut invalid_files = Vec::new();
+    let mut first_invalid = None;
+    file_data_list.retain(|(filename, data)| match crate::validate_pdf_bytes(data) {
+        Ok(()) => true,
+        Err(reason) => {
+            first_invalid.get_or_insert(reason);
+            invalid_files.push(InvalidUpload {
+                filename: filename.clone(),
+                error: crate::PdfReaderError::from(reason).to_string(),
+            });
+            false
+        }
+    });
+    if let (true, Some(reason)) = (file_data_list.is_empty(), first_invalid) {
+        return Err(AppError::InvalidPdf(reason));
+    }
 
     // Create batch job
     let mut batch = BatchJob::new(options.clone(), priority);
@@ -790,6 +1102,7 @@ async fn create_batch(
             status: "processing".to_string(),
             job_count,
             created_at,
+            invalid_files,
         }),
     ))
 }
@@ -892,6 +1205,15 @@ pub enum AppError {
     NotFound(String),
     Conflict(String),
     Internal(String),
+    /// Upload larger than the applicable limit
+    PayloadTooLarge(String),
+    /// Upload that is not a PDF
+    UnsupportedMediaType(String),
+    /// Well-formed options that fail validation
+    UnprocessableEntity(String),
+    /// Upload that is not a valid PDF (415 without a `%PDF-` header, 422
+    /// when empty or truncated)
+    InvalidPdf(crate::InvalidPdf),
     /// Rate limit exceeded (used by rate limiting middleware)
     #[allow(dead_code)]
     TooManyRequests { retry_after: u64 },
@@ -911,6 +1233,16 @@ impl IntoResponse for AppError {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone(), None),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone(), None),
             AppError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone(), None),
+            AppError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg.clone(), None),
+            AppError::UnsupportedMediaType(msg) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, msg.clone(), None),
+            AppError::UnprocessableEntity(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone(), None),
+            AppError::InvalidPdf(reason) => {
+                let status = match reason {
+                    crate::InvalidPdf::MissingHeader => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    _ => StatusCode::UNPROCESSABLE_ENTITY,
+                };
+                (status, crate::PdfReaderError::from(*reason).to_string(), None)
+            }
             AppError::TooManyRequests { retry_after } => (
                 StatusCode::TOO_MANY_REQUESTS,
                 "Rate limit exceeded".to_string(),
@@ -973,6 +1305,228 @@ mod tests {
         assert!(json.contains("\"version\":\"0.4.0\""));
     }
 
+    #[tokio::test]
+    async fn test_liveness_check() {
+        let Json(response) = liveness_check().await;
+        assert_eq!(response.status, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_check_queue_depth() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let state = Arc::new(AppState::new(work_dir.path().to_path_buf(), 1).with_max_queue_depth(1));
+
+        let (status, Json(response)) = readiness_check(State(state.clone())).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(response.ready);
+        assert_eq!(response.queue_depth, 0);
+
+        state.queue.submit(Job::new("a.pdf", ConvertOptions::default()));
+        state.queue.submit(Job::new("b.pdf", ConvertOptions::default()));
+        let (status, Json(response)) = readiness_check(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!response.ready);
+        assert_eq!(response.queue_depth, 2);
+        assert_eq!(response.max_queue_depth, 1);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_check_without_workers() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let state = Arc::new(AppState::new(work_dir.path().to_path_buf(), 0));
+        let (status, Json(response)) = readiness_check(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(response.workers, 0);
+    }
+
+    #[tokio::test]
+    async fn test_page_preview_route() {
+        use tower::ServiceExt;
+
+        let work_dir = tempfile::tempdir().unwrap();
+        let state = Arc::new(AppState::new(work_dir.path().to_path_buf(), 0));
+        let mut job = Job::new("book.pdf", ConvertOptions::default());
+        let preview_dir = work_dir.path().join("previews");
+        std::fs::create_dir_all(&preview_dir).unwrap();
+        std::fs::write(super::super::job::preview_file(&preview_dir, 1), b"\xFF\xD8jpeg").unwrap();
+        job.preview_dir = Some(preview_dir);
+        job.add_preview(1);
+        let id = state.queue.submit(job);
+
+        let get = |uri: String| {
+            let state = state.clone();
+            async move {
+                api_routes()
+                    .with_state(state)
+                    .oneshot(axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+            }
+        };
+
+        let response = get(format!("/jobs/{}/preview/1", id)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/jpeg");
+
+        // Pages without a preview yet, and unknown jobs
+        assert_eq!(get(format!("/jobs/{}/preview/2", id)).await.status(), StatusCode::NOT_FOUND);
+        assert_eq!(get(format!("/jobs/{}/preview/1", Uuid::new_v4())).await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rerun_route() {
+        use tower::ServiceExt;
+
+        let work_dir = tempfile::tempdir().unwrap();
+        let state = Arc::new(AppState::new(work_dir.path().to_path_buf(), 0));
+        let options = ConvertOptions {
+            dpi: 450,
+            ..Default::default()
+        };
+        let mut job = Job::new("book.pdf", options).with_source_hash("sha256:src");
+        job.complete(work_dir.path().join("book_converted.pdf"));
+        let id = state.queue.submit(job);
+        std::fs::write(state.upload_dir.join(format!("{}_book.pdf", id)), b"%PDF-1.4\n").unwrap();
+
+        let post = |id: Uuid, body: &'static str| {
+            let state = state.clone();
+            async move {
+                let request = axum::http::Request::post(format!("/jobs/{}/rerun", id))
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(axum::body::Body::from(body))
+                    .unwrap();
+                api_routes().with_state(state).oneshot(request).await.unwrap()
+            }
+        };
+
+        let response = post(id, r#"{"ocr":true,"deskew":false}"#).await;
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let new_id: Uuid = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["job_id"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let rerun = state.queue.get(new_id).unwrap();
+        assert_eq!(rerun.rerun_of, Some(id));
+        assert_eq!(rerun.source_hash.as_deref(), Some("sha256:src"));
+        assert_eq!(rerun.options.dpi, 450);
+        assert!(rerun.options.ocr);
+        assert!(!rerun.options.deskew);
+        assert!(state.upload_dir.join(format!("{}_book.pdf", new_id)).exists());
+
+        // Same options again with an empty body
+        assert_eq!(post(id, "").await.status(), StatusCode::ACCEPTED);
+
+        assert_eq!(post(id, r#"{"no_such_option":1}"#).await.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(post(id, r#"{"dpi":"high"}"#).await.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(post(id, r#"{"write_text_sidecar":true}"#).await.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(post(Uuid::new_v4(), "{}").await.status(), StatusCode::NOT_FOUND);
+
+        std::fs::remove_file(state.upload_dir.join(format!("{}_book.pdf", id))).unwrap();
+        assert_eq!(post(id, "{}").await.status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn test_validate_pdf_content_type() {
+        assert!(validate_pdf_content_type(Some("application/pdf")).is_ok());
+        assert!(validate_pdf_content_type(Some("Application/PDF; name=book.pdf")).is_ok());
+        assert!(validate_pdf_content_type(Some("application/octet-stream")).is_ok());
+        assert!(matches!(
+            validate_pdf_content_type(None),
+            Err(AppError::UnsupportedMediaType(_))
+        ));
+        assert!(matches!(
+            validate_pdf_content_type(Some("image/png")),
+            Err(AppError::UnsupportedMediaType(_))
+        ));
+    }
+
+    /// Multipart part: field name, content type, data
+    type Part<'a> = (&'a str, Option<&'a str>, &'a [u8]);
+
+    /// POST a hand-built multipart body to /convert and return the status code
+    async fn post_convert(state: Arc<AppState>, parts: &[Part<'_>], api_key: Option<&str>) -> StatusCode {
+        use tower::ServiceExt;
+
+        let boundary = "superbook-test-boundary";
+        let mut body = Vec::new();
+        for (name, content_type, data) in parts {
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            if *name == "file" {
+                body.extend_from_slice(b"Content-Disposition: form-data; name=\"file\"; filename=\"book.pdf\"\r\n");
+            } else {
+                body.extend_from_slice(format!("Content-Disposition: form-data; name=\"{}\"\r\n", name).as_bytes());
+            }
+            if let Some(content_type) = content_type {
+                body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+            }
+            body.extend_from_slice(b"\r\n");
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+        let mut request = axum::http::Request::post("/convert")
+            .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary));
+        if let Some(key) = api_key {
+            request = request.header("x-api-key", key);
+        }
+        let response = api_routes()
+            .with_state(state)
+            .oneshot(request.body(axum::body::Body::from(body)).unwrap())
+            .await
+            .unwrap();
+        response.status()
+    }
+
+    #[tokio::test]
+    async fn test_upload_validation() {
+        let work_dir = tempfile::tempdir().unwrap();
+        let state = Arc::new(AppState::new(work_dir.path().to_path_buf(), 1).with_upload_limit(64));
+        let pdf: &[u8] = b"%PDF-1.4\n%test\n%%EOF\n";
+
+        let cases: Vec<(Vec<Part>, StatusCode)> = vec![
+            (vec![("file", Some("application/pdf"), pdf)], StatusCode::ACCEPTED),
+            (vec![("file", None, pdf)], StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            (vec![("file", Some("application/pdf"), b"GIF89a not a pdf")], StatusCode::UNSUPPORTED_MEDIA_TYPE),
+            (vec![("file", Some("application/pdf"), b"")], StatusCode::UNPROCESSABLE_ENTITY),
+            (vec![("file", Some("application/pdf"), b"%PDF-1.4\n1 0 obj")], StatusCode::UNPROCESSABLE_ENTITY),
+            (vec![("file", Some("application/pdf"), pdf), ("extra", None, b"x")], StatusCode::BAD_REQUEST),
+            (vec![("file", Some("application/pdf"), pdf), ("file", Some("application/pdf"), pdf)], StatusCode::BAD_REQUEST),
+            (vec![("file", Some("application/pdf"), pdf), ("options", None, b"{not json")], StatusCode::BAD_REQUEST),
+            (vec![("file", Some("application/pdf"), pdf), ("reuse_existing", None, b"maybe")], StatusCode::BAD_REQUEST),
+            (vec![("file", Some("application/pdf"), pdf), ("options", None, br#"{"dpi":0}"#)], StatusCode::UNPROCESSABLE_ENTITY),
+            (vec![("file", Some("application/pdf"), pdf), ("options", None, br#"{"write_text_sidecar":true}"#)], StatusCode::UNPROCESSABLE_ENTITY),
+            (vec![("file", Some("application/pdf"), pdf), ("options", None, br#"{"ocr":true,"write_text_sidecar":true}"#)], StatusCode::ACCEPTED),
+            (vec![("options", None, b"{}")], StatusCode::BAD_REQUEST),
+            (vec![("file", Some("application/pdf"), &[b'%'; 100])], StatusCode::PAYLOAD_TOO_LARGE),
+        ];
+        for (parts, expected) in cases {
+            assert_eq!(post_convert(state.clone(), &parts, None).await, expected, "{:?}", parts);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_limit_per_api_key() {
+        use super::super::auth::ApiKey;
+
+        let work_dir = tempfile::tempdir().unwrap();
+        let auth = AuthConfig::enabled_with_keys(vec![ApiKey::new("trusted", "Trusted").with_upload_limit(1024)]);
+        let state = Arc::new(
+            AppState::new_with_config(work_dir.path().to_path_buf(), 1, RateLimitConfig::default(), auth)
+                .with_upload_limit(64),
+        );
+        let mut large = b"%PDF-1.4\n".to_vec();
+        large.resize(194, b' ');
+        large.extend_from_slice(b"%%EOF\n");
+        let parts: &[Part] = &[("file", Some("application/pdf"), &large)];
+
+        assert_eq!(post_convert(state.clone(), parts, None).await, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(post_convert(state.clone(), parts, Some("other")).await, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(post_convert(state, parts, Some("trusted")).await, StatusCode::ACCEPTED);
+    }
+
     #[test]
     fn test_upload_response_serialize() {
         let id = Uuid::new_v4();
@@ -980,10 +1534,24 @@ mod tests {
             job_id: id,
             status: "queued".to_string(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
+            reused: false,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains(&id.to_string()));
         assert!(json.contains("\"status\":\"queued\""));
+        assert!(!json.contains("reused"));
+    }
+
+    #[test]
+    fn test_upload_response_reused() {
+        let mut job = Job::new("book.pdf", ConvertOptions::default());
+        job.complete(PathBuf::from("/tmp/book_converted.pdf"));
+
+        let response = UploadResponse::reused(&job);
+        assert_eq!(response.job_id, job.id);
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains("\"status\":\"completed\""));
+        assert!(json.contains("\"reused\":true"));
     }
 
     #[test]
@@ -1011,11 +1579,13 @@ mod tests {
             status: "processing".to_string(),
             job_count: 5,
             created_at: "2024-01-01T00:00:00Z".to_string(),
+            invalid_files: vec![],
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains(&id.to_string()));
         assert!(json.contains("\"status\":\"processing\""));
         assert!(json.contains("\"job_count\":5"));
+        assert!(!json.contains("invalid_files"));
     }
 
     // TC-BATCH-API-002: Batch status response serialization
@@ -1118,6 +1688,46 @@ mod tests {
         std::fs::remove_dir_all(&work_dir).ok();
     }
 
+    // TC-BATCH-API-009: Invalid PDFs are reported, not queued
+    #[tokio::test]
+    async fn test_batch_invalid_pdfs() {
+        use tower::ServiceExt;
+
+        let work_dir = tempfile::tempdir().unwrap();
+        let state = Arc::new(AppState::new(work_dir.path().to_path_buf(), 1));
+        let post_batch = |files: &[(&str, &[u8])]| {
+            let boundary = "superbook-test-boundary";
+            let mut body = Vec::new();
+            for (filename, data) in files {
+                body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+                body.extend_from_slice(
+                    format!("Content-Disposition: form-data; name=\"files[]\"; filename=\"{}\"\r\n\r\n", filename).as_bytes(),
+                );
+                body.extend_from_slice(data);
+                body.extend_from_slice(b"\r\n");
+            }
+            body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+            let request = axum::http::Request::post("/batch")
+                .header(header::CONTENT_TYPE, format!("multipart/form-data; boundary={}", boundary))
+                .body(axum::body::Body::from(body))
+                .unwrap();
+            api_routes().with_state(state.clone()).oneshot(request)
+        };
+
+        let response = post_batch(&[("good.pdf", b"%PDF-1.4\n%%EOF\n"), ("cut.pdf", b"%PDF-1.4\n1 0 obj")])
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["job_count"], 1);
+        assert_eq!(json["invalid_files"][0]["filename"], "cut.pdf");
+        assert!(json["invalid_files"][0]["error"].as_str().unwrap().contains("truncated"));
+
+        let response = post_batch(&[("empty.pdf", b"")]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     // TC-RATE-001: AppState includes rate limiter
     #[tokio::test]
     async fn test_app_state_has_rate_limiter() {
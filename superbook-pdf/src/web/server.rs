@@ -10,7 +10,11 @@ use std::sync::Arc;
 //use tower_http::limit::RequestBodyLimitLayer;
 
 use super::cors::CorsConfig;
-use super::routes::{api_routes, web_routes, ws_routes, AppState};
+use super::auth::AuthConfig;
+use super::persistence::{JobStore, PersistenceConfig};
+use super::rate_limit::RateLimitConfig;
+use super::retention::RetentionTask;
+use super::routes::{api_routes, probe_routes, web_routes, ws_routes, AppState};
 use super::shutdown::{ShutdownConfig, wait_for_shutdown_signal};
 use super::{DEFAULT_BIND, DEFAULT_PORT, DEFAULT_UPLOAD_LIMIT};
 
@@ -33,6 +37,12 @@ pub struct ServerConfig {
     pub cors: CorsConfig,
     /// Graceful shutdown configuration
     pub shutdown: ShutdownConfig,
+    /// Persistence and retention configuration
+    pub persistence: PersistenceConfig,
+    /// Pending jobs above which `/readyz` returns 503
+    pub max_queue_depth: usize,
+    /// Run GPU stages one at a time across conversions (`gpu_wait`)
+    pub gpu_wait: bool,
 }
 
 impl Default for ServerConfig {
@@ -46,6 +56,9 @@ impl Default for ServerConfig {
             work_dir: std::env::temp_dir().join("superbook-pdf"),
             cors: CorsConfig::default(),
             shutdown: ShutdownConfig::default(),
+            persistence: PersistenceConfig::default(),
+            max_queue_depth: super::DEFAULT_MAX_QUEUE_DEPTH,
+            gpu_wait: false,
         }
     }
 }
@@ -93,6 +106,30 @@ impl ServerConfig {
         self
     }
 
+    /// Set persistence and retention configuration
+    pub fn with_persistence(mut self, persistence: PersistenceConfig) -> Self {
+        self.persistence = persistence;
+        self
+    }
+
+    /// Set history retention in days (0 disables cleanup)
+    pub fn with_retention_days(mut self, days: u32) -> Self {
+        self.persistence.retention_days = days;
+        self
+    }
+
+    /// Set the queue depth above which the server reports not ready
+    pub fn with_max_queue_depth(mut self, depth: usize) -> Self {
+        self.max_queue_depth = depth;
+        self
+    }
+
+    /// Run GPU stages (upscale, OCR) one at a time across jobs and processes
+    pub fn with_gpu_wait(mut self, enabled: bool) -> Self {
+        self.gpu_wait = enabled;
+        self
+    }
+
     /// Get the socket address
     pub fn socket_addr(&self) -> Result<SocketAddr, std::net::AddrParseError> {
         format!("{}:{}", self.bind, self.port).parse()
@@ -108,19 +145,61 @@ pub struct WebServer {
 impl WebServer {
     /// Create a new web server with default configuration
     pub fn new() -> Self {
-        let config = ServerConfig::default();
-        std::fs::create_dir_all(&config.work_dir).ok();
-        let state = Arc::new(AppState::new(config.work_dir.clone(), config.workers));
-        Self { config, state }
+        Self::with_config(ServerConfig::default())
     }
 
     /// Create a new web server with the given configuration
     pub fn with_config(config: ServerConfig) -> Self {
-        std::fs::create_dir_all(&config.work_dir).ok();
-        let state = Arc::new(AppState::new(config.work_dir.clone(), config.workers));
+        let state = Arc::new(Self::build_state(&config));
         Self { config, state }
     }
 
+    /// Create a new web server with default configuration backed by the given job store
+    pub fn with_store(store: Arc<dyn JobStore>) -> Self {
+        Self::with_config_and_store(ServerConfig::default(), store)
+    }
+
+    /// Create a new web server with the given configuration and job store
+    pub fn with_config_and_store(config: ServerConfig, store: Arc<dyn JobStore>) -> Self {
+        let state = Arc::new(Self::build_state(&config).with_job_store(store));
+        Self { config, state }
+    }
+
+    fn build_state(config: &ServerConfig) -> AppState {
+        std::fs::create_dir_all(&config.work_dir).ok();
+        AppState::new_with_persistence(
+            config.work_dir.clone(),
+            config.workers,
+            RateLimitConfig::default(),
+            AuthConfig::default(),
+            config.persistence.clone(),
+        )
+        .with_max_queue_depth(config.max_queue_depth)
+        .with_upload_limit(config.upload_limit)
+        .with_gpu_wait(config.gpu_wait)
+    }
+
+    /// Create the retention cleanup task for this server
+    ///
+    /// Returns `None` when retention is disabled (`retention_days == 0`).
+    pub fn retention_task(&self) -> Option<RetentionTask> {
+        if self.config.persistence.retention_days == 0 {
+            return None;
+        }
+        Some(RetentionTask::new(
+            self.state.queue.clone(),
+            self.state.job_store.clone(),
+            self.state.upload_dir.clone(),
+            self.state.metrics.clone(),
+            self.config.persistence.retention_days,
+        ))
+    }
+
+    /// Get the job store, if one is configured
+    pub fn job_store(&self) -> Option<&Arc<dyn JobStore>> {
+        self.state.job_store.as_ref()
+    }
+
     /// Get the server configuration
     pub fn config(&self) -> &ServerConfig {
         &self.config
@@ -130,6 +209,7 @@ impl WebServer {
     fn build_router(&self) -> Router {
         Router::new()
             .merge(web_routes())
+            .merge(probe_routes())
             .nest("/api", api_routes())
             .nest("/ws", ws_routes())
             .layer(self.config.cors.clone().into_layer())
@@ -149,10 +229,18 @@ impl WebServer {
         println!("  DELETE /api/jobs/:id  - Cancel job");
         println!("  GET  /api/jobs/:id/download - Download result");
         println!("  GET  /api/health      - Health check");
+        println!("  GET  /healthz         - Liveness probe");
+        println!("  GET  /readyz          - Readiness probe (503 when the queue is full)");
         println!("WebSocket endpoints:");
         println!("  WS   /ws/jobs/:id     - Real-time job progress");
         println!("Press Ctrl+C to shutdown gracefully");
 
+        // Periodically purge expired jobs and their files
+        let cleanup_handle = self.retention_task().map(|task| {
+            let interval = std::time::Duration::from_secs(self.config.persistence.cleanup_interval.max(1));
+            task.spawn(interval)
+        });
+
         let listener = tokio::net::TcpListener::bind(addr).await?;
 
         // Run server with graceful shutdown
@@ -160,6 +248,10 @@ impl WebServer {
             .with_graceful_shutdown(wait_for_shutdown_signal())
             .await?;
 
+        if let Some(handle) = cleanup_handle {
+            handle.abort();
+        }
+
         println!("Server shutdown complete");
         Ok(())
     }
@@ -194,6 +286,10 @@ mod tests {
         assert_eq!(config.port, 3000);
         assert_eq!(config.bind, "0.0.0.0");
         assert_eq!(config.upload_limit, 100 * 1024 * 1024);
+        assert_eq!(config.max_queue_depth, crate::web::DEFAULT_MAX_QUEUE_DEPTH);
+        assert!(!config.gpu_wait);
+        assert!(config.clone().with_gpu_wait(true).gpu_wait);
+        assert_eq!(config.with_max_queue_depth(5).max_queue_depth, 5);
     }
 
     #[test]
@@ -217,6 +313,28 @@ mod tests {
         assert_eq!(server.config().port, 9000);
     }
 
+    #[tokio::test]
+    async fn test_web_server_with_store() {
+        let store: Arc<dyn JobStore> = Arc::new(crate::web::InMemoryJobStore::new());
+        let server = WebServer::with_store(store.clone());
+        assert!(Arc::ptr_eq(server.job_store().unwrap(), &store));
+    }
+
+    #[tokio::test]
+    async fn test_web_server_retention_task() {
+        let server = WebServer::with_config(ServerConfig::default().with_retention_days(7));
+        assert!(server.retention_task().is_some());
+
+        let server = WebServer::with_config(ServerConfig::default().with_retention_days(0));
+        assert!(server.retention_task().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_web_server_without_store() {
+        let server = WebServer::new();
+        assert!(server.job_store().is_none());
+    }
+
     // CORS integration tests
     #[test]
     fn test_server_config_default_cors() {
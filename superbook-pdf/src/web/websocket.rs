@@ -402,6 +402,19 @@ pub fn generate_preview_base64(
     max_width: u32,
 ) -> Option<(String, u32, u32)> {
     use base64::{engine::general_purpose::STANDARD, Engine};
+
+    let (jpeg, width, height) = generate_preview_jpeg(image_path, max_width)?;
+    Some((STANDARD.encode(jpeg), width, height))
+}
+
+/// Generate a JPEG thumbnail from an image file
+///
+/// # Returns
+/// Tuple of (jpeg_bytes, width, height) or None if generation fails
+pub fn generate_preview_jpeg(
+    image_path: &std::path::Path,
+    max_width: u32,
+) -> Option<(Vec<u8>, u32, u32)> {
     use image::{imageops::FilterType, GenericImageView};
     use std::io::Cursor;
 
@@ -432,10 +445,7 @@ pub fn generate_preview_base64(
         )
         .ok()?;
 
-    // Convert to base64
-    let base64_str = STANDARD.encode(buffer.into_inner());
-
-    Some((base64_str, thumb_width, thumb_height))
+    Some((buffer.into_inner(), thumb_width, thumb_height))
 }
 
 /// Processing stage names for preview
@@ -2,13 +2,13 @@
 //!
 //! Handles the actual PDF conversion in a background task.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
-use super::job::{ConvertOptions, JobQueue, JobStatus, Progress};
+use super::job::{preview_file, ConvertOptions, JobQueue, JobStatus, Progress};
 use super::websocket::WsBroadcaster;
 use crate::pipeline::{PdfPipeline, PipelineConfig, ProgressCallback};
 use tracing::{info, error, warn};
@@ -20,7 +20,9 @@ pub enum WorkerMessage {
     Process {
         job_id: Uuid,
         input_path: PathBuf,
-        options: ConvertOptions,
+        options: Box<ConvertOptions>,
+        /// Serialize GPU stages with other conversions (`gpu_wait`)
+        gpu_wait: bool,
     },
     /// Shutdown the worker
     Shutdown,
@@ -34,10 +36,11 @@ struct WebProgressCallback {
     total_steps: AtomicU32,
     step_progress: AtomicUsize,
     step_total: AtomicUsize,
+    preview_dir: PathBuf,
 }
 
 impl WebProgressCallback {
-    fn new(job_id: Uuid, queue: JobQueue) -> Self {
+    fn new(job_id: Uuid, queue: JobQueue, preview_dir: PathBuf) -> Self {
         Self {
             job_id,
             queue,
@@ -45,10 +48,22 @@ impl WebProgressCallback {
             total_steps: AtomicU32::new(13),
             step_progress: AtomicUsize::new(0),
             step_total: AtomicUsize::new(0),
+            preview_dir,
         }
     }
 }
 
+/// Write a low-resolution JPEG preview of `image` to `output`
+///
+/// The file is renamed into place so readers never see a partial image.
+fn write_page_preview(image: &Path, output: &Path) -> std::io::Result<()> {
+    let (jpeg, _, _) = super::generate_preview_jpeg(image, super::PAGE_PREVIEW_WIDTH)
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "preview encoding failed"))?;
+    let partial = output.with_extension("jpg.part");
+    std::fs::write(&partial, jpeg)?;
+    std::fs::rename(&partial, output)
+}
+
 impl ProgressCallback for WebProgressCallback {
     fn on_step_start(&self, step: &str) {
         let current = self.current_step.fetch_add(1, Ordering::Relaxed);
@@ -74,29 +89,107 @@ impl ProgressCallback for WebProgressCallback {
     fn on_debug(&self, _message: &str) {
         // Debug messages not shown in web UI
     }
+
+    fn on_warning(&self, message: &str) {
+        warn!(job_id = %self.job_id, "{}", message);
+    }
+
+    fn on_page_complete(&self, page: usize, image: &Path, _is_final: bool) {
+        let page_number = page + 1;
+        match write_page_preview(image, &preview_file(&self.preview_dir, page_number)) {
+            Ok(()) => {
+                self.queue.update(self.job_id, |job| job.add_preview(page_number));
+            }
+            Err(e) => warn!(job_id = %self.job_id, "Failed to write preview of page {}: {}", page_number, e),
+        }
+    }
 }
 
 /// Convert web ConvertOptions to pipeline PipelineConfig
-fn to_pipeline_config(options: &ConvertOptions) -> PipelineConfig {
+///
+/// `gpu_wait` is a server setting rather than a job option.
+pub(crate) fn to_pipeline_config(options: &ConvertOptions, gpu_wait: bool) -> PipelineConfig {
     let advanced = options.advanced;
     PipelineConfig {
         dpi: options.dpi,
         deskew: options.deskew,
-        margin_trim: 0.5,
+        margin_trim: options.margin_trim,
         upscale: options.upscale,
         gpu: true,
-        internal_resolution: advanced,
-        color_correction: advanced,
-        offset_alignment: advanced,
-        output_height: 3508,
+        internal_resolution: options.internal_resolution.unwrap_or(advanced),
+        color_correction: options.color_correction.unwrap_or(advanced),
+        offset_alignment: options.offset_alignment.unwrap_or(advanced),
+        output_height: options.output_height,
         ocr: options.ocr,
-        max_pages: None,
+        max_pages: options.max_pages,
         save_debug: false,
-        jpeg_quality: 90,
+        jpeg_quality: options.jpeg_quality,
         threads: None,
         max_memory_mb: 0,  // Auto-detect
         chunk_size: 0,    // Auto-calculate
+        extract_queue_depth: crate::pipeline::DEFAULT_EXTRACT_QUEUE_DEPTH,
+        extract_batch_pages: crate::image_extract::DEFAULT_BATCH_PAGES,
+        auto_contrast: options.auto_contrast,
+        auto_contrast_per_channel: options.auto_contrast_per_channel,
+        tone_curve: options.tone_curve.clone(),
+        ocr_formats: options.ocr_formats.clone(),
+        deterministic: options.deterministic,
+        metadata: options.metadata.clone(),
+        metadata_from_ocr: options.metadata_from_ocr,
+        write_text_sidecar: options.write_text_sidecar,
+        dehyphenate: false,
+        ocr_pages: None,
+        ocr_skip_pictures: false,
+        ocr_optional: options.ocr_optional,
+        mixed_color: options.mixed_color,
+        color_policy: options.color_policy,
+        mixed_size: options.mixed_size,
+        ocr_min_confidence: crate::pipeline::DEFAULT_OCR_MIN_CONFIDENCE,
+        post_hook: None,
+        page_hook: None,
+        quality_metrics: options.quality_metrics,
+        binding: options.binding,
+        text_direction: crate::markdown::TextDirectionOption::Auto,
+        flatten_background: crate::util::DEFAULT_FLATTEN_BACKGROUND,
+        resize_filter: options.resize_filter,
+        image_encoding: options.image_encoding,
+        skip_existing_ocr: options.skip_existing_ocr,
+        remove_line_artifacts: options.remove_line_artifacts,
+        remove_markers: options.remove_markers,
+        marker_colors: options.marker_colors.clone(),
+        marker_region: options.marker_region,
+        highlighters: vec![],
+        desaturate_below: options.desaturate_below,
+        target_aspect: options.target_aspect,
+        pdfa: options.pdfa,
+        pdfa_validate: false,
+        output_format: crate::BookFormat::Pdf,
+        page_labels: options.page_labels,
+        running_heads: options.running_heads,
+        parity: options.parity,
+        invert: options.invert,
+        on_page_error: options.on_page_error,
+        group_crop: options.group_crop,
+        register_sides: options.register_sides,
+        tool_paths: Default::default(),
+        nice: 0,
+        tool_timeout_secs: None,
+        stall_warning_secs: Some(crate::DEFAULT_STALL_WARNING_SECS),
+        ocr_concurrency: None,
+        gpu_wait,
+        external_thread_fraction: 0.0,
+        min_output_dpi: options.min_output_dpi,
+        on_low_dpi: options.on_low_dpi,
+        max_page_megapixels: crate::pipeline::DEFAULT_MAX_PAGE_MEGAPIXELS,
+        max_page_dimension: crate::pipeline::DEFAULT_MAX_PAGE_DIMENSION,
+        preserve_source_metadata: false,
+        preserve_source_outline: false,
+        work_cache: None,
+        work_cache_max_mb: crate::work_cache::DEFAULT_WORK_CACHE_MAX_MB,
+        resume: false,
+        quick: false,
     }
+    .with_quick(options.quick)
 }
 
 /// Background worker for job processing
@@ -105,6 +198,7 @@ pub struct JobWorker {
     receiver: mpsc::Receiver<WorkerMessage>,
     work_dir: PathBuf,
     broadcaster: Arc<WsBroadcaster>,
+    gpu_wait: bool,
 }
 
 impl JobWorker {
@@ -120,9 +214,16 @@ impl JobWorker {
             receiver,
             work_dir,
             broadcaster,
+            gpu_wait: false,
         }
     }
 
+    /// Serialize GPU stages with other conversions, across processes
+    pub fn with_gpu_wait(mut self, enabled: bool) -> Self {
+        self.gpu_wait = enabled;
+        self
+    }
+
     /// Run the worker loop
     pub async fn run(mut self) {
         while let Some(msg) = self.receiver.recv().await {
@@ -131,8 +232,10 @@ impl JobWorker {
                     job_id,
                     input_path,
                     options,
+                    gpu_wait,
                 } => {
-                    self.process_job(job_id, input_path, options).await;
+                    self.gpu_wait = gpu_wait;
+                    self.process_job(job_id, input_path, *options).await;
                 }
                 WorkerMessage::Shutdown => {
                     break;
@@ -179,12 +282,21 @@ impl JobWorker {
             return;
         }
 
+        // Per-page previews are written while the job runs
+        let preview_dir = self.work_dir.join("previews").join(job_id.to_string());
+        match std::fs::create_dir_all(&preview_dir) {
+            Ok(()) => {
+                self.queue.update(job_id, |job| job.preview_dir = Some(preview_dir.clone()));
+            }
+            Err(e) => warn!(%job_id, "Failed to create preview directory: {}", e),
+        }
+
         // Convert options to pipeline config
-        let config = to_pipeline_config(&options);
+        let config = to_pipeline_config(&options, self.gpu_wait);
         let pipeline = PdfPipeline::new(config);
 
         // Create progress callback
-        let progress = WebProgressCallback::new(job_id, self.queue.clone());
+        let progress = WebProgressCallback::new(job_id, self.queue.clone(), preview_dir);
 
         // Run pipeline in blocking task (pipeline uses rayon internally)
         let queue = self.queue.clone();
@@ -242,6 +354,7 @@ pub struct WorkerPool {
     sender: mpsc::Sender<WorkerMessage>,
     work_dir: PathBuf,
     worker_count: usize,
+    gpu_wait: bool,
 }
 
 impl WorkerPool {
@@ -275,6 +388,7 @@ impl WorkerPool {
                             job_id,
                             input_path,
                             options,
+                            gpu_wait,
                         }) => {
                             // Create a temporary worker for this job
                             let (_, dummy_rx) = mpsc::channel(1);
@@ -283,8 +397,9 @@ impl WorkerPool {
                                 dummy_rx,
                                 work_dir.clone(),
                                 broadcaster.clone(),
-                            );
-                            worker.process_job(job_id, input_path, options).await;
+                            )
+                            .with_gpu_wait(gpu_wait);
+                            worker.process_job(job_id, input_path, *options).await;
                         }
                         Some(WorkerMessage::Shutdown) | None => {
                             break;
@@ -294,7 +409,18 @@ impl WorkerPool {
             });
         }
 
-        Self { sender, work_dir, worker_count }
+        Self {
+            sender,
+            work_dir,
+            worker_count,
+            gpu_wait: false,
+        }
+    }
+
+    /// Serialize GPU stages of submitted jobs with other conversions (`gpu_wait`)
+    pub fn with_gpu_wait(mut self, enabled: bool) -> Self {
+        self.gpu_wait = enabled;
+        self
     }
 
     /// Submit a job for processing
@@ -308,7 +434,8 @@ impl WorkerPool {
             .send(WorkerMessage::Process {
                 job_id,
                 input_path,
-                options,
+                options: Box::new(options),
+                gpu_wait: self.gpu_wait,
             })
             .await
             .map_err(|e| format!("Failed to submit job: {}", e))
@@ -329,6 +456,11 @@ impl WorkerPool {
     pub fn worker_count(&self) -> usize {
         self.worker_count
     }
+
+    /// Whether workers were spawned and are still receiving jobs
+    pub fn is_ready(&self) -> bool {
+        self.worker_count > 0 && !self.sender.is_closed()
+    }
 }
 
 #[cfg(test)]
@@ -341,7 +473,8 @@ mod tests {
         let msg = WorkerMessage::Process {
             job_id: Uuid::new_v4(),
             input_path: PathBuf::from("/test.pdf"),
-            options: ConvertOptions::default(),
+            options: Box::default(),
+            gpu_wait: false,
         };
         let debug = format!("{:?}", msg);
         assert!(debug.contains("Process"));
@@ -359,13 +492,15 @@ mod tests {
     #[tokio::test]
     async fn test_convert_options_to_pipeline_config() {
         let options = ConvertOptions::default();
-        let config = to_pipeline_config(&options);
+        let config = to_pipeline_config(&options, false);
 
         assert_eq!(config.dpi, 300);
         assert!(config.deskew);
         assert!(config.upscale);
         assert!(!config.ocr);
         assert!(!config.internal_resolution);
+        assert!(!config.gpu_wait);
+        assert!(to_pipeline_config(&options, true).gpu_wait);
     }
 
     #[tokio::test]
@@ -376,8 +511,9 @@ mod tests {
             upscale: true,
             ocr: true,
             advanced: true,
+            ..Default::default()
         };
-        let config = to_pipeline_config(&options);
+        let config = to_pipeline_config(&options, false);
 
         assert_eq!(config.dpi, 600);
         assert!(config.internal_resolution);
@@ -386,6 +522,67 @@ mod tests {
         assert!(config.ocr);
     }
 
+    #[test]
+    fn test_convert_options_stage_mapping() {
+        let options = ConvertOptions {
+            advanced: true,
+            color_correction: Some(false),
+            margin_trim: 2.0,
+            remove_markers: true,
+            binding: crate::Binding::RightToLeft,
+            max_pages: Some(4),
+            register_sides: true,
+            ..Default::default()
+        };
+        let config = to_pipeline_config(&options, false);
+
+        assert!(config.internal_resolution);
+        assert!(!config.color_correction);
+        assert_eq!(config.margin_trim, 2.0);
+        assert!(config.remove_markers);
+        assert_eq!(config.binding, crate::Binding::RightToLeft);
+        assert_eq!(config.max_pages, Some(4));
+        assert!(config.register_sides);
+        assert!(config.post_hook.is_none());
+
+        let quick = to_pipeline_config(
+            &ConvertOptions {
+                quick: true,
+                ..Default::default()
+            },
+            false,
+        );
+        assert!(quick.quick);
+        assert!(!quick.upscale);
+    }
+
+    #[test]
+    fn test_page_complete_writes_preview() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = dir.path().join("page_0000.png");
+        image::RgbImage::from_pixel(1000, 1400, image::Rgb([240, 240, 240]))
+            .save(&page)
+            .unwrap();
+
+        let queue = JobQueue::new();
+        let job = Job::new("test.pdf", ConvertOptions::default());
+        let job_id = job.id;
+        queue.submit(job);
+        queue.update(job_id, |job| job.preview_dir = Some(dir.path().to_path_buf()));
+
+        let progress = WebProgressCallback::new(job_id, queue.clone(), dir.path().to_path_buf());
+        progress.on_page_complete(0, &page, false);
+
+        let preview = queue.get(job_id).unwrap().preview_path(1).unwrap();
+        let (width, height) = image::image_dimensions(&preview).unwrap();
+        assert_eq!((width, height), (crate::web::PAGE_PREVIEW_WIDTH, 672));
+        assert!(!preview.with_extension("jpg.part").exists());
+
+        // Unreadable page images leave the count unchanged
+        progress.on_page_complete(1, &dir.path().join("missing.png"), false);
+        assert_eq!(queue.get(job_id).unwrap().preview_pages, 1);
+    }
+
     #[tokio::test]
     async fn test_job_processing_with_invalid_pdf() {
         let queue = JobQueue::new();
@@ -0,0 +1,316 @@
+//! Content-addressed cache of per-page stage results (`--work-cache`)
+//!
+//! The per-page stages (inversion, line artifacts, markers, trim, upscale,
+//! normalization, deskew) look at one page at a time, so their result is a
+//! function of the extracted page image and the options that drive them.
+//! [`WorkCache`] stores the final image of those stages under the SHA-256
+//! of both, letting identical pages (a retried run, reprints, shared front
+//! matter) skip the expensive stages across books and runs.
+//!
+//! Unlike the output cache ([`crate::cache`]), which skips a whole book
+//! whose source and options are unchanged, this works page by page.
+//!
+//! - Any change to the page-stage options or the crate version changes
+//!   every key, so stale entries are never returned (they age out)
+//! - Entries are written to a temporary file and renamed, so concurrent
+//!   runs sharing a directory never read a partial entry
+//! - The directory is kept under a size limit by evicting the least
+//!   recently used entries (a hit refreshes the entry's modification time)
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use std::path::Path;
+//! use superbook_pdf::WorkCache;
+//!
+//! let cache = WorkCache::open(Path::new("/var/cache/superbook"), 4096, "{\"deskew\":true}").unwrap();
+//! let key = cache.key(Path::new("page_00001.png")).unwrap();
+//! if let Some(entry) = cache.get(&key, Path::new("work/cached/page_00001.png")) {
+//!     println!("{} transforms", entry.transforms.len());
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// Default size limit of the work cache directory (MB)
+pub const DEFAULT_WORK_CACHE_MAX_MB: u64 = 4096;
+
+/// Entry format version (part of every key)
+const WORK_CACHE_VERSION: u32 = 1;
+
+/// Extension of the metadata file next to each cached image
+const ENTRY_EXTENSION: &str = "json";
+
+/// Errors from the work cache
+#[derive(Debug, Error)]
+pub enum WorkCacheError {
+    #[error("Invalid cache entry: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, WorkCacheError>;
+
+/// Geometry recorded with a cached page image
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkCacheEntry {
+    /// Transforms the page stages applied, in order
+    pub transforms: Vec<crate::PageTransform>,
+    /// Inverted as a negative scan
+    pub inverted: bool,
+    /// Highlighter marks were removed
+    #[serde(default)]
+    pub marked: bool,
+}
+
+/// Hits and misses of one book (for the verbose report)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorkCacheStats {
+    /// Pages whose stage results were reused
+    pub hits: usize,
+    /// Pages processed (and stored when complete)
+    pub misses: usize,
+    /// Entries evicted to stay under the size limit
+    pub evicted: usize,
+}
+
+impl WorkCacheStats {
+    /// Pages looked up
+    pub fn lookups(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    /// Share of lookups that hit (0.0 without lookups)
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups() == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups() as f64
+        }
+    }
+
+    /// Add the counts of another batch
+    pub fn add(&mut self, other: WorkCacheStats) {
+        self.hits += other.hits;
+        self.misses += other.misses;
+        self.evicted += other.evicted;
+    }
+}
+
+/// Content-addressed store of per-page stage results
+#[derive(Debug, Clone)]
+pub struct WorkCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    options_hash: String,
+}
+
+impl WorkCache {
+    /// Open (creating) the cache directory; `options` describes every
+    /// option the cached stages depend on and is mixed into each key
+    pub fn open(dir: &Path, max_mb: u64, options: &str) -> Result<Self> {
+        fs::create_dir_all(dir)?;
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}:{}:", WORK_CACHE_VERSION, env!("CARGO_PKG_VERSION")));
+        hasher.update(options.as_bytes());
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_bytes: max_mb.saturating_mul(1024 * 1024),
+            options_hash: format!("{:x}", hasher.finalize()),
+        })
+    }
+
+    /// Cache directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Key of a page: SHA-256 of the options and the image file's bytes
+    pub fn key(&self, page: &Path) -> Result<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(self.options_hash.as_bytes());
+        hasher.update(fs::read(page)?);
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
+    /// Copy the cached image for `key` to `dest` and return its geometry
+    /// (`None` on a miss or an unreadable entry)
+    pub fn get(&self, key: &str, dest: &Path) -> Option<WorkCacheEntry> {
+        let (image, meta) = self.entry_paths(key);
+        let entry: WorkCacheEntry = serde_json::from_str(&fs::read_to_string(&meta).ok()?).ok()?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).ok()?;
+        }
+        fs::copy(&image, dest).ok()?;
+        // Refresh for LRU eviction
+        if let Ok(file) = fs::File::options().append(true).open(&image) {
+            file.set_modified(SystemTime::now()).ok();
+        }
+        Some(entry)
+    }
+
+    /// Store the stage result `image` for `key`
+    pub fn put(&self, key: &str, image: &Path, entry: &WorkCacheEntry) -> Result<()> {
+        let (image_path, meta_path) = self.entry_paths(key);
+        if let Some(parent) = image_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Image first: an entry counts once its metadata exists
+        let tmp = image_path.with_extension(format!("tmp{}", std::process::id()));
+        fs::copy(image, &tmp)?;
+        fs::rename(&tmp, &image_path)?;
+        let tmp = meta_path.with_extension(format!("tmp{}", std::process::id()));
+        fs::write(&tmp, serde_json::to_string(entry)?)?;
+        fs::rename(&tmp, &meta_path)?;
+        Ok(())
+    }
+
+    /// Delete the least recently used entries until the directory is under
+    /// the size limit; returns the number evicted
+    pub fn evict(&self) -> Result<usize> {
+        let mut entries = Vec::new();
+        let mut total = 0;
+        for shard in fs::read_dir(&self.dir)?.flatten() {
+            if !shard.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            for file in fs::read_dir(shard.path())?.flatten() {
+                let path = file.path();
+                let Ok(meta) = file.metadata() else { continue };
+                total += meta.len();
+                if path.extension().is_none() {
+                    let size = meta.len() + fs::metadata(path.with_extension(ENTRY_EXTENSION)).map_or(0, |m| m.len());
+                    entries.push((meta.modified().unwrap_or(SystemTime::UNIX_EPOCH), size, path));
+                }
+            }
+        }
+
+        entries.sort_by_key(|(modified, _, _)| *modified);
+        let mut evicted = 0;
+        for (_, size, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            fs::remove_file(path.with_extension(ENTRY_EXTENSION)).ok();
+            fs::remove_file(&path).ok();
+            total = total.saturating_sub(size);
+            evicted += 1;
+        }
+        Ok(evicted)
+    }
+
+    /// Image and metadata paths of an entry (sharded by the key's first byte)
+    fn entry_paths(&self, key: &str) -> (PathBuf, PathBuf) {
+        let image = self.dir.join(&key[..2.min(key.len())]).join(key);
+        let meta = image.with_extension(ENTRY_EXTENSION);
+        (image, meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(dir: &Path, name: &str, shade: u8) -> PathBuf {
+        let path = dir.join(name);
+        image::GrayImage::from_pixel(32, 48, image::Luma([shade])).save(&path).unwrap();
+        path
+    }
+
+    fn entry() -> WorkCacheEntry {
+        WorkCacheEntry {
+            transforms: vec![crate::PageTransform::Crop { x: 1, y: 2, width: 30, height: 44 }],
+            inverted: false,
+            marked: false,
+        }
+    }
+
+    #[test]
+    fn test_key_depends_on_content_and_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = page(dir.path(), "a.png", 10);
+        let b = page(dir.path(), "b.png", 10);
+        let c = page(dir.path(), "c.png", 20);
+
+        let cache = WorkCache::open(&dir.path().join("cache"), 1, "{\"deskew\":true}").unwrap();
+        // Same pixels under another name share the key
+        assert_eq!(cache.key(&a).unwrap(), cache.key(&b).unwrap());
+        assert_ne!(cache.key(&a).unwrap(), cache.key(&c).unwrap());
+
+        let other = WorkCache::open(&dir.path().join("cache"), 1, "{\"deskew\":false}").unwrap();
+        assert_ne!(cache.key(&a).unwrap(), other.key(&a).unwrap());
+        assert!(cache.key(&dir.path().join("missing.png")).is_err());
+    }
+
+    #[test]
+    fn test_put_get_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = page(dir.path(), "page.png", 128);
+        let cache = WorkCache::open(&dir.path().join("cache"), 1, "{}").unwrap();
+        let key = cache.key(&source).unwrap();
+
+        let dest = dir.path().join("work").join("page.png");
+        assert!(cache.get(&key, &dest).is_none());
+
+        cache.put(&key, &source, &entry()).unwrap();
+        assert_eq!(cache.get(&key, &dest), Some(entry()));
+        assert_eq!(fs::read(&dest).unwrap(), fs::read(&source).unwrap());
+        // No temporary files are left behind
+        let shard = cache.dir().join(&key[..2]);
+        assert_eq!(fs::read_dir(shard).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_evict_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        // 1 MB keeps both small entries
+        let roomy = WorkCache::open(&dir.path().join("cache"), 1, "{}").unwrap();
+        let keys: Vec<String> = (0..2)
+            .map(|i| {
+                let source = page(dir.path(), &format!("p{}.png", i), i * 50);
+                let key = roomy.key(&source).unwrap();
+                roomy.put(&key, &source, &entry()).unwrap();
+                key
+            })
+            .collect();
+        assert_eq!(roomy.evict().unwrap(), 0);
+
+        // Make the first entry the most recently used
+        let (old, _) = roomy.entry_paths(&keys[1]);
+        fs::File::options()
+            .append(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(roomy.get(&keys[0], &dir.path().join("out.png")).is_some());
+
+        let (image, _) = roomy.entry_paths(&keys[0]);
+        let one_entry = fs::metadata(&image).unwrap().len()
+            + fs::metadata(image.with_extension(ENTRY_EXTENSION)).unwrap().len();
+        let tight = WorkCache {
+            max_bytes: one_entry,
+            ..roomy.clone()
+        };
+        assert_eq!(tight.evict().unwrap(), 1);
+        assert!(tight.get(&keys[0], &dir.path().join("out.png")).is_some());
+        assert!(tight.get(&keys[1], &dir.path().join("out.png")).is_none());
+    }
+
+    #[test]
+    fn test_stats_hit_rate() {
+        let mut stats = WorkCacheStats::default();
+        assert_eq!(stats.hit_rate(), 0.0);
+        stats.add(WorkCacheStats { hits: 3, misses: 1, evicted: 2 });
+        assert_eq!(stats.lookups(), 4);
+        assert_eq!(stats.hit_rate(), 0.75);
+        assert_eq!(stats.evicted, 2);
+    }
+}
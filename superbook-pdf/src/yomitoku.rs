@@ -263,6 +263,17 @@ pub struct OcrResult {
     pub text_direction: TextDirection,
 }
 
+impl OcrResult {
+    /// Mean text block confidence (0.0-1.0), falling back to the overall
+    /// score when the page has no blocks
+    pub fn mean_confidence(&self) -> f32 {
+        if self.text_blocks.is_empty() {
+            return self.confidence;
+        }
+        self.text_blocks.iter().map(|b| b.confidence).sum::<f32>() / self.text_blocks.len() as f32
+    }
+}
+
 /// A recognized text block
 #[derive(Debug, Clone)]
 pub struct TextBlock {
@@ -10,7 +10,10 @@ use tempfile::TempDir;
 
 fn superbook_cmd() -> Command {
     // Use CARGO_BIN_EXE_<name> environment variable set by cargo test
-    Command::new(env!("CARGO_BIN_EXE_superbook-pdf"))
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_superbook-pdf"));
+    // Pin English output regardless of the developer's locale
+    cmd.env("LANG", "C");
+    cmd
 }
 
 // TC-CLI-001: ヘルプ表示
@@ -82,6 +85,22 @@ fn test_convert_dry_run_single_file() {
         .stdout(predicate::str::contains("Files to process: 1"));
 }
 
+#[test]
+fn test_convert_dry_run_japanese() {
+    superbook_cmd()
+        .args([
+            "convert",
+            "tests/fixtures/sample.pdf",
+            "-o", "/tmp/out",
+            "--dry-run",
+            "--lang", "ja",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("実行計画"))
+        .stdout(predicate::str::contains("処理対象ファイル数: 1"));
+}
+
 // TC-CLI-005: ディレクトリ入力処理
 #[test]
 fn test_convert_dry_run_directory() {
@@ -596,3 +615,113 @@ dpi = 600
         // CLI value (450) should override config (600)
         .stdout(predicate::str::contains("DPI: 450"));
 }
+
+#[test]
+fn test_convert_skips_previous_outputs_in_output_dir() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    for name in ["book.pdf", "book_converted.pdf", "book_converted.pdf.superbook-cache", "book_odd_converted.pdf"] {
+        std::fs::copy("tests/fixtures/sample.pdf", dir.join(name)).unwrap();
+    }
+    let dir = dir.to_str().unwrap();
+
+    superbook_cmd()
+        .args(["convert", dir, "-o", dir, "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files to process: 1"))
+        .stderr(predicate::str::contains("Skipping 2 file(s) written by a previous run"));
+
+    // A previous output given directly is refused
+    let output = temp_dir.path().join("book_converted.pdf");
+    superbook_cmd()
+        .args(["convert", output.to_str().unwrap(), "-o", dir, "--dry-run"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("is an output of a previous run"));
+}
+
+// TC-CLI-005c: rerun は出力のキャッシュから入力とオプションを復元する
+#[test]
+fn test_rerun_requires_recorded_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = temp_dir.path().join("book_converted.pdf");
+    std::fs::copy("tests/fixtures/sample.pdf", &output).unwrap();
+    let output = output.to_str().unwrap();
+
+    // No cache file
+    superbook_cmd().args(["rerun", output]).assert().code(3);
+
+    // Cache written before the source was recorded
+    let cache = format!("{}.superbook-cache", output);
+    let mut recorded = serde_json::json!({
+        "version": 1,
+        "processed_at": 0,
+        "digest": {"source_modified": 1, "source_size": 2, "options_hash": "sha256:x"},
+        "result": {"page_count": 1, "page_number_shift": null, "is_vertical": false, "elapsed_seconds": 0.0, "output_size": 0},
+    });
+    std::fs::write(&cache, recorded.to_string()).unwrap();
+    superbook_cmd()
+        .args(["rerun", output])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("does not record the source"));
+
+    // Unknown --set option
+    let source = temp_dir.path().join("book.pdf");
+    std::fs::copy("tests/fixtures/sample.pdf", &source).unwrap();
+    recorded["source"] = serde_json::json!(source);
+    recorded["options"] = serde_json::json!({"dpi": 200});
+    std::fs::write(&cache, recorded.to_string()).unwrap();
+    superbook_cmd()
+        .args(["rerun", output, "--set", "no_such_option=1"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("unknown option 'no_such_option'"));
+}
+
+// TC-CLI-004b: ドライランで元画像の解像度と低解像度の警告を表示
+#[test]
+fn test_convert_dry_run_source_dpi() {
+    superbook_cmd()
+        .args(["convert", "tests/fixtures/sample.pdf", "-o", "/tmp/out", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(source images: ~72 DPI)"))
+        .stdout(predicate::str::contains("well below the requested 300 DPI"));
+
+    superbook_cmd()
+        .args(["convert", "tests/fixtures/sample.pdf", "-o", "/tmp/out", "--dry-run", "--dpi", "100"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("(source images: ~72 DPI)"))
+        .stdout(predicate::str::contains("well below").not());
+}
+
+// TC-CLI-004c: 空・途中で切れた PDF は処理前に検出する
+#[test]
+fn test_convert_rejects_invalid_pdfs() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+    let fixture = std::fs::read("tests/fixtures/sample.pdf").unwrap();
+    std::fs::write(dir.join("book.pdf"), &fixture).unwrap();
+    std::fs::write(dir.join("cut.pdf"), &fixture[..fixture.len() / 2]).unwrap();
+    std::fs::write(dir.join("empty.pdf"), b"").unwrap();
+
+    // A single bad file fails before anything is set up
+    superbook_cmd()
+        .args(["convert", dir.join("empty.pdf").to_str().unwrap(), "-o", "/tmp/out", "--dry-run"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("Not a valid PDF"))
+        .stderr(predicate::str::contains("file is empty"));
+
+    // In a directory they are reported and left out
+    superbook_cmd()
+        .args(["convert", dir.to_str().unwrap(), "-o", "/tmp/out", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Files to process: 1"))
+        .stderr(predicate::str::contains("cut.pdf: missing %%EOF marker"))
+        .stderr(predicate::str::contains("empty.pdf: file is empty"));
+}
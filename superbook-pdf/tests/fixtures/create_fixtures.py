@@ -195,6 +195,40 @@ def create_skewed_image(filename, width=200, height=300, skew_degrees=2):
         f.write(signature + ihdr + idat + iend)
     print(f"Created: {filename} (skewed image {skew_degrees}°)")
 
+def create_transparent_page(filename, width=200, height=300):
+    """Create an RGBA page whose paper is fully transparent black.
+
+    Dropping the alpha channel instead of compositing turns the page black.
+    """
+    
+    def png_chunk(chunk_type, data):
+        chunk = chunk_type + data
+        crc = zlib.crc32(chunk) & 0xffffffff
+        return struct.pack('>I', len(data)) + chunk + struct.pack('>I', crc)
+    
+    signature = b'\x89PNG\r\n\x1a\n'
+    ihdr_data = struct.pack('>IIBBBBB', width, height, 8, 6, 0, 0, 0)
+    ihdr = png_chunk(b'IHDR', ihdr_data)
+    
+    raw_data = b''
+    for y in range(height):
+        raw_data += b'\x00'
+        for x in range(width):
+            if 50 < y < 100 and 30 < x < width - 30:
+                # Opaque dark content area (simulating text)
+                raw_data += bytes([50, 50, 50, 255])
+            else:
+                # Transparent paper
+                raw_data += bytes([0, 0, 0, 0])
+    
+    compressed = zlib.compress(raw_data)
+    idat = png_chunk(b'IDAT', compressed)
+    iend = png_chunk(b'IEND', b'')
+    
+    with open(filename, 'wb') as f:
+        f.write(signature + ihdr + idat + iend)
+    print(f"Created: {filename} (transparent page {width}x{height})")
+
 def main():
     fixtures_dir = os.path.dirname(os.path.abspath(__file__))
     os.chdir(fixtures_dir)
@@ -224,6 +258,9 @@ def main():
     create_skewed_image("slightly_skewed.png", 200, 300, 1)
     create_skewed_image("heavily_skewed.png", 200, 300, 10)
     
+    # Page with an alpha channel for transparency flattening
+    create_transparent_page("transparent_page.png", 200, 300)
+    
     # Book page images for batch tests
     for i in range(1, 11):
         create_test_image_with_content(f"book_page_{i}.png", 400, 600)
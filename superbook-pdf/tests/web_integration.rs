@@ -5,10 +5,11 @@
 #![cfg(feature = "web")]
 
 use superbook_pdf::{
-    BatchJob, BatchProgress, BatchQueue, BatchStatus, Job, JobQueue, JobStatus, Priority,
-    ServerConfig, WebConvertOptions, WebProgress,
+    BatchJob, BatchProgress, BatchQueue, BatchStatus, InMemoryJobStore, Job, JobQueue, JobStatus,
+    JobStore, Priority, RecoveryManager, ServerConfig, WebConvertOptions, WebProgress, WebServer,
 };
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[cfg(test)]
 mod tests {
@@ -422,4 +423,31 @@ mod tests {
         assert_eq!(retrieved.status, BatchStatus::Processing);
         assert!(retrieved.started_at.is_some());
     }
+
+    // TC-WEB-STORE-001: In-memory store injected into the server
+    #[tokio::test]
+    async fn test_web_server_with_in_memory_store() {
+        let store = Arc::new(InMemoryJobStore::new());
+        let job = Job::new("book.pdf", WebConvertOptions::default());
+        store.save(&job).unwrap();
+
+        let server = WebServer::with_store(store.clone());
+        let injected = server.job_store().unwrap();
+        assert_eq!(injected.list().unwrap().len(), 1);
+    }
+
+    // TC-WEB-STORE-002: Recovery without touching disk
+    #[tokio::test]
+    async fn test_recovery_with_in_memory_store() {
+        let store: Arc<dyn JobStore> = Arc::new(InMemoryJobStore::new());
+        store.save(&Job::new("a.pdf", WebConvertOptions::default())).unwrap();
+        store.save(&Job::new("b.pdf", WebConvertOptions::default())).unwrap();
+
+        let queue = JobQueue::new();
+        let manager = RecoveryManager::new(store, queue.clone());
+        let result = manager.recover_on_startup().await;
+
+        assert_eq!(result.recovered, 2);
+        assert_eq!(queue.list().len(), 2);
+    }
 }